@@ -8,13 +8,14 @@ use aptos_consensus::{
     consensus_observer::{
         network_message::ConsensusObserverMessage, publisher::ConsensusPublisher,
     },
-    consensus_provider::start_consensus_observer,
+    consensus_provider::{start_consensus_observer, start_publisher_only_runtime},
     network_interface::ConsensusMsg,
 };
 use aptos_consensus_notifications::ConsensusNotifier;
 use aptos_dkg_runtime::{start_dkg_runtime, DKGMessage};
 use aptos_event_notifications::{
-    DbBackedOnChainConfig, EventNotificationListener, ReconfigNotificationListener,
+    DbBackedOnChainConfig, EventNotificationListener, ObservedCommitNotifier,
+    ReconfigNotificationListener, SyncedCommitNotificationListener,
 };
 use aptos_jwk_consensus::{start_jwk_consensus_runtime, types::JWKConsensusMsg};
 use aptos_logger::debug;
@@ -27,7 +28,8 @@ use std::sync::Arc;
 use tokio::runtime::Runtime;
 
 /// Creates and returns the consensus observer runtime (if either the
-/// observer or publisher is enabled).
+/// observer or publisher is enabled). If only the publisher is enabled,
+/// a lightweight publisher only runtime is returned instead.
 pub fn create_consensus_observer_runtime(
     node_config: &NodeConfig,
     consensus_observer_network_interfaces: Option<
@@ -40,17 +42,17 @@ pub fn create_consensus_observer_runtime(
     consensus_observer_reconfig_subscription: Option<
         ReconfigNotificationListener<DbBackedOnChainConfig>,
     >,
+    consensus_observer_synced_commit_subscription: Option<SyncedCommitNotificationListener>,
+    observed_commit_notifier: ObservedCommitNotifier,
+    admin_service: &mut AdminService,
 ) -> Option<Runtime> {
-    if node_config
-        .consensus_observer
-        .is_observer_or_publisher_enabled()
-    {
+    if node_config.consensus_observer.observer_enabled {
         // Fetch the network interfaces and reconfig subscription
         let consensus_observer_network_interfaces = consensus_observer_network_interfaces
             .expect("Consensus observer is enabled, but network interfaces are missing!");
 
         // Start the consensus observer runtime
-        let consensus_observer_runtime = start_consensus_observer(
+        let (consensus_observer_runtime, consensus_observer_handles) = start_consensus_observer(
             node_config,
             consensus_observer_network_interfaces.network_client,
             consensus_observer_network_interfaces.network_service_events,
@@ -59,8 +61,37 @@ pub fn create_consensus_observer_runtime(
             consensus_to_mempool_sender,
             db_rw,
             consensus_observer_reconfig_subscription,
+            consensus_observer_synced_commit_subscription,
+            observed_commit_notifier,
         );
+        admin_service
+            .set_consensus_observer_control_handle(consensus_observer_handles.control_handle);
+        admin_service
+            .set_consensus_observer_event_journal(consensus_observer_handles.event_journal);
+        admin_service
+            .set_consensus_observer_feature_flags(consensus_observer_handles.feature_flags);
+        admin_service
+            .set_consensus_observer_peer_overrides(consensus_observer_handles.peer_overrides);
+        admin_service
+            .set_consensus_observer_status_handle(consensus_observer_handles.status_handle);
+        admin_service.set_consensus_observer_supervisor(consensus_observer_handles.supervisor);
+
         Some(consensus_observer_runtime)
+    } else if node_config.consensus_observer.publisher_enabled {
+        // Fetch the network interfaces and consensus publisher
+        let consensus_observer_network_interfaces = consensus_observer_network_interfaces
+            .expect("Consensus publisher is enabled, but network interfaces are missing!");
+        let consensus_publisher = consensus_publisher
+            .expect("Consensus publisher is enabled, but the publisher is missing!");
+
+        // Start the publisher only runtime (to forward requests to the publisher)
+        let publisher_only_runtime = start_publisher_only_runtime(
+            node_config,
+            consensus_observer_network_interfaces.network_service_events,
+            consensus_publisher,
+        );
+
+        Some(publisher_only_runtime)
     } else {
         None
     }
@@ -80,12 +111,15 @@ pub fn create_consensus_publisher(
             .expect("Consensus publisher is enabled, but network interfaces are missing!");
 
         // Create the publisher runtime
-        let runtime = aptos_runtimes::spawn_named_runtime("publisher".into(), None);
+        let runtime = aptos_runtimes::spawn_named_runtime(
+            "publisher".into(),
+            node_config.consensus_observer.publisher_runtime_threads,
+        );
 
         // Create the consensus publisher
         let (consensus_publisher, outbound_message_receiver) = ConsensusPublisher::new(
             consensus_observer_network_interfaces.network_client.clone(),
-            node_config.consensus_observer,
+            node_config.consensus_observer.clone(),
         );
 
         // Start the consensus publisher
@@ -636,11 +636,15 @@ pub fn setup_environment_and_start_node(
         mut event_subscription_service,
         mempool_reconfig_subscription,
         consensus_observer_reconfig_subscription,
+        consensus_observer_synced_commit_subscription,
         consensus_reconfig_subscription,
         dkg_subscriptions,
         jwk_consensus_subscriptions,
     ) = state_sync::create_event_subscription_service(&node_config, &db_rw);
 
+    // Create the notifier for commits applied by the consensus observer
+    let observed_commit_notifier = event_subscription_service.get_observed_commit_notifier();
+
     // Set up the networks and gather the application network handles
     let peers_and_metadata = network::create_peers_and_metadata(&node_config);
     let (
@@ -736,6 +740,11 @@ pub fn setup_environment_and_start_node(
     state_sync_runtimes.block_until_initialized();
     debug!("State sync initialization complete.");
 
+    // Cap the cardinality of the peer-labeled consensus observer and publisher metrics
+    aptos_consensus::consensus_observer::metrics::initialize_peer_label_guard(
+        node_config.consensus_observer.max_num_peer_metric_labels,
+    );
+
     // Create the consensus observer publisher (if enabled)
     let (consensus_publisher_runtime, consensus_publisher) =
         consensus::create_consensus_publisher(&node_config, &consensus_observer_network_interfaces);
@@ -762,6 +771,9 @@ pub fn setup_environment_and_start_node(
         consensus_to_mempool_sender,
         db_rw,
         consensus_observer_reconfig_subscription,
+        consensus_observer_synced_commit_subscription,
+        observed_commit_notifier,
+        &mut admin_service,
     );
 
     Ok(AptosHandle {
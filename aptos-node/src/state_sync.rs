@@ -11,7 +11,7 @@ use aptos_data_streaming_service::{
 };
 use aptos_event_notifications::{
     DbBackedOnChainConfig, EventNotificationListener, EventSubscriptionService,
-    ReconfigNotificationListener,
+    ReconfigNotificationListener, SyncedCommitNotificationListener,
 };
 use aptos_executor::chunk_executor::ChunkExecutor;
 use aptos_infallible::RwLock;
@@ -38,8 +38,9 @@ use aptos_vm::AptosVM;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
 
-/// Creates the event subscription service and two reconfiguration
-/// notification listeners (for mempool and consensus, respectively).
+/// Creates the event subscription service, the reconfiguration notification
+/// listeners (e.g., for mempool and consensus) and the synced commit
+/// notification listener for consensus observer.
 pub fn create_event_subscription_service(
     node_config: &NodeConfig,
     db_rw: &DbReaderWriter,
@@ -47,6 +48,7 @@ pub fn create_event_subscription_service(
     EventSubscriptionService,
     ReconfigNotificationListener<DbBackedOnChainConfig>,
     Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
+    Option<SyncedCommitNotificationListener>,
     Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
     Option<(
         ReconfigNotificationListener<DbBackedOnChainConfig>,
@@ -78,6 +80,18 @@ pub fn create_event_subscription_service(
             None
         };
 
+    // Create a synced commit subscription for consensus observer (if enabled)
+    let consensus_observer_synced_commit_subscription =
+        if node_config.consensus_observer.observer_enabled {
+            Some(
+                event_subscription_service
+                    .subscribe_to_synced_commits()
+                    .expect("Consensus observer must subscribe to synced commits"),
+            )
+        } else {
+            None
+        };
+
     // Create a reconfiguration subscription for consensus
     let consensus_reconfig_subscription = if node_config.base.role.is_validator() {
         Some(
@@ -119,6 +133,7 @@ pub fn create_event_subscription_service(
         event_subscription_service,
         mempool_reconfig_subscription,
         consensus_observer_reconfig_subscription,
+        consensus_observer_synced_commit_subscription,
         consensus_reconfig_subscription,
         dkg_subscriptions,
         jwk_consensus_subscriptions,
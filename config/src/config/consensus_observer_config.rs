@@ -1,19 +1,24 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::config::{
-    config_optimizer::ConfigOptimizer, node_config_loader::NodeType, Error, NodeConfig,
+use crate::{
+    config::{
+        config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
+        node_config_loader::NodeType, Error, NodeConfig,
+    },
+    network_id::PeerNetworkId,
 };
 use aptos_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
+use std::net::SocketAddr;
 
 // Useful constants for enabling consensus observer on different node types
 const ENABLE_ON_VALIDATORS: bool = false;
 const ENABLE_ON_VALIDATOR_FULLNODES: bool = false;
 const ENABLE_ON_PUBLIC_FULLNODES: bool = false;
 
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct ConsensusObserverConfig {
     /// Whether the consensus observer is enabled
@@ -27,11 +32,28 @@ pub struct ConsensusObserverConfig {
     pub max_parallel_serialization_tasks: usize,
     /// Timeout (in milliseconds) for network RPC requests
     pub network_request_timeout_ms: u64,
+    /// Maximum number of distinct peer labels for peer-labeled metrics (the
+    /// labels of disconnected peers are released and reused for new peers)
+    pub max_num_peer_metric_labels: u64,
+
+    /// Number of worker threads for the observer runtime (if None, the number of CPUs is used)
+    pub observer_runtime_threads: Option<usize>,
+    /// Number of worker threads for the publisher runtime (if None, the number of CPUs is used)
+    pub publisher_runtime_threads: Option<usize>,
 
     /// Interval (in milliseconds) to garbage collect peer state
     pub garbage_collection_interval_ms: u64,
+    /// Maximum number of recent events to keep in the observer event journal
+    pub max_num_journal_events: u64,
+    /// Maximum number of recent epoch states to cache (used to verify
+    /// messages from previous epochs during epoch transitions).
+    pub max_num_cached_epoch_states: u64,
     /// Maximum number of pending blocks to keep in memory
     pub max_num_pending_blocks: u64,
+    /// Maximum number of rounds ahead of the root for which to buffer ordered
+    /// blocks and payloads. Data beyond this horizon is dropped (and later
+    /// recovered via state sync), bounding memory when execution is slow.
+    pub max_rounds_ahead_of_root: u64,
     /// Maximum timeout (in milliseconds) for active subscriptions
     pub max_subscription_timeout_ms: u64,
     /// Maximum timeout (in milliseconds) we'll wait for the synced version to
@@ -41,6 +63,279 @@ pub struct ConsensusObserverConfig {
     pub peer_optimality_check_interval_ms: u64,
     /// Interval (in milliseconds) to check progress of the consensus observer
     pub progress_check_interval_ms: u64,
+
+    /// Maximum number of consecutive failures to finalize ordered blocks or forward
+    /// commit decisions to the execution pipeline before the failures are escalated.
+    pub max_consecutive_pipeline_failures: u64,
+    /// The escalation to perform when the pipeline failures exceed the maximum
+    pub pipeline_failure_escalation: PipelineFailureEscalation,
+    /// The deadline (in milliseconds) for the execution pipeline to commit ordered
+    /// blocks and commit decisions (after they are handed off by the observer).
+    /// Handoffs that aren't committed by the deadline are tracked as missed.
+    pub pipeline_processing_deadline_ms: u64,
+
+    /// The policy to enforce when messages from the subscription peer fail verification
+    pub verification_failure_policy: VerificationFailurePolicy,
+    /// Maximum number of verification failures (for a single subscription)
+    /// before the verification failure policy is enforced.
+    pub max_verification_failures: u64,
+    /// Duration (in milliseconds) to blocklist peers (if required by the policy)
+    pub peer_blocklist_duration_ms: u64,
+
+    /// Whether to disconnect from peers that exceed the maximum misbehavior score
+    pub disconnect_misbehaving_peers: bool,
+    /// Maximum misbehavior score (i.e., accumulated penalties) before disconnecting a peer
+    pub max_peer_misbehavior_score: u64,
+
+    /// The address to serve the gRPC export stream of observed data (if None, the export
+    /// is disabled). Note: this requires the `consensus-observer-grpc` feature.
+    pub grpc_export_address: Option<SocketAddr>,
+    /// The address to serve the server-sent events (SSE) stream of observed
+    /// commits (if None, the stream is disabled). This is a lightweight
+    /// alternative to the gRPC export for external consumers (e.g., dashboards).
+    /// The same server also offers a WebSocket feed of the observed commits.
+    pub sse_export_address: Option<SocketAddr>,
+    /// Maximum number of observed items to buffer for each export stream (gRPC, SSE or WebSocket)
+    pub max_export_buffer_size: u64,
+
+    /// Whether to append every applied commit to a (rotating) local commit journal
+    pub enable_commit_journal: bool,
+    /// Maximum size (in bytes) of a single commit journal file (before it is rotated)
+    pub max_commit_journal_file_size_bytes: u64,
+    /// Maximum number of commit journal files to keep (including the active file)
+    pub max_num_commit_journal_files: u64,
+
+    /// Whether to periodically persist snapshots of the observer state (to warm restarts)
+    pub enable_state_snapshots: bool,
+    /// Interval (in milliseconds) at which to persist observer state snapshots
+    pub state_snapshot_interval_ms: u64,
+    /// Whether to prune the pending blocks (and payloads) that were already committed
+    /// to local storage on startup (e.g., by state sync), instead of re-fetching them.
+    pub enable_committed_block_pruning: bool,
+    /// Whether to verify the transaction signatures of received block payloads
+    /// (in parallel) before the payloads are stored and sent for execution.
+    pub enable_payload_signature_verification: bool,
+
+    /// Duration (in milliseconds) of the sliding window over which the block rate
+    /// and average payload size are observed (to auto-tune the payload store size).
+    pub payload_store_sizing_window_ms: u64,
+    /// Duration (in milliseconds) of observed payloads that the payload store should
+    /// be able to buffer. The target capacity of the store is the observed block rate,
+    /// multiplied by the average payload size, multiplied by this duration.
+    pub payload_store_target_buffer_ms: u64,
+    /// Minimum (i.e., floor) target capacity (in bytes) of the payload store
+    pub min_payload_store_capacity_bytes: u64,
+    /// Maximum (i.e., ceiling) target capacity (in bytes) of the payload store
+    pub max_payload_store_capacity_bytes: u64,
+
+    /// Whether to use shared-dictionary (zstd) compression for block payloads. For
+    /// publishers, this trains and refreshes dictionaries (and ships them to the
+    /// subscribers that support them). For observers, this requests dictionary
+    /// compressed payloads when subscribing (the publisher must also enable it).
+    pub enable_payload_dictionary_compression: bool,
+    /// Interval (in milliseconds) at which publishers retrain the payload dictionary
+    pub payload_dictionary_refresh_interval_ms: u64,
+    /// Maximum size (in bytes) of the trained payload dictionaries
+    pub max_payload_dictionary_size_bytes: u64,
+    /// Maximum number of recent payloads to sample when training the payload dictionary
+    pub max_num_payload_dictionary_samples: u64,
+
+    /// Whether to use delta encoding for block payloads. For publishers, this sends
+    /// digests-only for the transactions that subscribers have acknowledged holding.
+    /// For observers, this requests delta encoded payloads when subscribing (and
+    /// acknowledges received payloads, so the publisher knows what is held locally).
+    pub enable_payload_delta_encoding: bool,
+    /// Maximum number of recent transactions cached by observers (to reconstruct delta
+    /// encoded payloads). Publishers only elide up to half this many transactions.
+    pub max_num_delta_cached_transactions: u64,
+    /// Whether publishers prefetch the batches of proposed blocks and publish the
+    /// block payloads before the blocks are ordered. This allows observers to start
+    /// executing the blocks as soon as the ordered blocks arrive. Observers prune
+    /// the payloads of blocks that are never ordered (e.g., forked proposals).
+    pub enable_payload_prefetch: bool,
+    /// Whether observers speculatively forward structurally valid ordered blocks to
+    /// the execution pipeline before their ordered proofs are verified. Blocks are
+    /// never committed until a verified commit decision arrives, and the pipeline
+    /// is reset if a proof fails verification. This lowers the commit latency, but
+    /// should only be enabled on trusted topologies (e.g., operator-run publishers).
+    pub enable_speculative_forwarding: bool,
+    /// Whether observers stream the blocks of ordered batches into the execution
+    /// pipeline as their payloads arrive, instead of handing off each batch as a
+    /// unit. Blocks are sent in order, and each block is only sent once its payload
+    /// is in the payload store (so the pipeline never stalls on a missing payload).
+    pub enable_incremental_block_finalization: bool,
+    /// Whether observers group candidate subscription peers into latency buckets
+    /// (and load balance within the nearest bucket), instead of strictly ranking
+    /// peers by latency. This improves locality for globally distributed observers.
+    pub enable_latency_bucketed_peer_selection: bool,
+    /// The width (in milliseconds) of each latency bucket used for peer selection
+    pub peer_latency_bucket_width_ms: u64,
+    /// Whether observers periodically rotate their (healthy) subscription to another
+    /// candidate peer, to spread the observer load across the upstream peers. Each
+    /// rotation subscribes to the new peer before unsubscribing from the old one.
+    pub enable_subscription_rotation: bool,
+    /// Interval (in milliseconds) between subscription rotations
+    pub subscription_rotation_interval_ms: u64,
+    /// The number of top ranked peers that form the candidate set for rotations (if
+    /// rotation is enabled, subscriptions to any of these peers are considered optimal)
+    pub subscription_rotation_candidates: u64,
+    /// Minimum time (in milliseconds) to hold a new subscription before switching
+    /// peers for optimality reasons (or rotations). Hard failures (e.g., timeouts
+    /// or disconnects) still terminate the subscription. 0 disables the hold time.
+    pub min_subscription_hold_time_ms: u64,
+    /// Maximum amount (in milliseconds) that an observed block timestamp may be
+    /// ahead of the local time. Block timestamps must also be non-decreasing.
+    pub max_block_timestamp_skew_ms: u64,
+    /// Whether observed blocks with invalid timestamps (i.e., non-monotonic or too
+    /// far in the future) are rejected. Otherwise, the violations are only flagged.
+    pub reject_invalid_block_timestamps: bool,
+    /// Maximum time (in milliseconds) that the synced version may stall, while other
+    /// connected peers report higher synced versions (via the peer monitoring service),
+    /// before the subscription is considered stale. This detects publishers that stay
+    /// connected and responsive, but silently stop pushing new data.
+    pub max_subscription_staleness_ms: u64,
+    /// Minimum number of versions that another connected peer must be ahead of
+    /// the local synced version for a stalled subscription to be considered stale.
+    pub min_subscription_staleness_version_lag: u64,
+    /// Whether to probe the subscription peer for its latest commit before timing out
+    /// the subscription. The subscription is only terminated if the probe fails or
+    /// shows that the peer is stale (avoiding failovers when the chain is quiet).
+    pub enable_subscription_timeout_probes: bool,
+    /// Whether to rank peers without peer monitoring metadata (e.g., on small or
+    /// private networks) using locally observed activity: recent messages, request
+    /// round-trip times and connection age. Otherwise, these peers are ranked last.
+    pub enable_fallback_peer_ranking: bool,
+    /// The window (in milliseconds) within which a message received from a peer
+    /// counts as recent activity (when ranking peers without monitoring metadata).
+    pub peer_activity_window_ms: u64,
+    /// Maximum time (in milliseconds) to buffer a commit decision for a future epoch
+    /// that can't be verified yet (i.e., until the epoch proof arrives). Unverified
+    /// decisions never trigger state sync: if no proof arrives in time, the decision
+    /// is dropped and the subscription to the peer that sent it is terminated.
+    pub max_future_commit_buffer_ms: u64,
+    /// Maximum number of epochs ahead of the current epoch for which unverified
+    /// commit decisions are buffered (decisions further ahead are dropped).
+    pub max_future_commit_epochs: u64,
+    /// Maximum number of concurrent subscriptions (i.e., the active subscription and
+    /// any backup subscriptions). Messages are deduplicated across the subscriptions,
+    /// and if the active subscription fails, a backup subscription takes over (without
+    /// resetting the execution pipeline). A value of 1 disables backup subscriptions.
+    pub max_concurrent_subscriptions: u64,
+    /// Whether observers fetch the ordered blocks missing before a received ordered
+    /// block (i.e., if its parent is missing) from the sending peer, instead of
+    /// dropping the block. Note: this requires publishers that serve the blocks.
+    pub enable_ordered_block_backfill: bool,
+    /// Maximum number of missing rounds that observers attempt to backfill. Larger
+    /// gaps are left to state sync (as the observer has likely fallen too far behind).
+    pub max_ordered_block_backfill_rounds: u64,
+    /// Whether observers request the payloads missing from verified pending blocks
+    /// from the subscription peer (on each progress check), instead of waiting for
+    /// the payloads to arrive. Note: this requires publishers that serve payloads.
+    pub enable_block_payload_requests: bool,
+    /// Maximum number of missing block payloads requested on each progress check
+    pub max_block_payload_requests: u64,
+    /// Whether observers send their latest block when subscribing, so that the
+    /// publisher replays the retained blocks (and payloads) the observer missed.
+    /// Note: this requires publishers that support the subscription replay.
+    pub enable_subscription_replay: bool,
+    /// Maximum number of recently published messages (i.e., ordered blocks, payloads
+    /// and commit decisions) buffered by publishers to catch up new subscribers
+    pub max_replay_buffer_messages: u64,
+    /// Whether observers persist the buffered block payloads (in the node's storage
+    /// directory), so that the payloads can be restored after a node restart.
+    pub enable_persistent_payload_store: bool,
+    /// Maximum number of block payloads in the payload store. If the limit is
+    /// exceeded, the payloads with the highest rounds are evicted first.
+    pub max_num_payload_store_entries: u64,
+    /// Maximum total size (in bytes) of the block payloads in the payload store.
+    /// If the limit is exceeded, the payloads with the highest rounds are evicted first.
+    pub max_payload_store_memory_bytes: u64,
+    /// Whether observers abandon all pending blocks and fall back to state sync
+    /// (to the latest known commit decision) once the maximum number of pending
+    /// blocks is reached (e.g., because execution is slow, or payloads are
+    /// missing). Otherwise, new ordered blocks are dropped until blocks commit.
+    pub enable_pending_blocks_overflow_sync: bool,
+    /// Whether to rank subscription candidates by their recent reputation (i.e.,
+    /// the invalid payloads, failed proofs, timeouts and stalls they served), so
+    /// that peers with bad reputations are only selected as a last resort.
+    pub enable_peer_reputation_ranking: bool,
+    /// The window (in milliseconds) within which a reputation event (e.g., a
+    /// subscription timeout) counts towards the reputation score of a peer.
+    pub peer_reputation_window_ms: u64,
+    /// Whether to rate limit the messages received from each subscription peer
+    /// (using a token bucket per peer). Messages that exceed the budget are dropped.
+    /// The publisher also rate limits the block and payload requests of each subscriber.
+    pub enable_inbound_rate_limiting: bool,
+    /// Maximum number of messages per second that can be received from each peer
+    /// (i.e., the refill rate of each token bucket).
+    pub max_inbound_messages_per_sec: u64,
+    /// Maximum number of messages that can be received from each peer in a single
+    /// burst (i.e., the capacity of each token bucket).
+    pub max_inbound_message_burst: u64,
+    /// Maximum number of messages from a peer that can be dropped by the rate
+    /// limiter (within a single window) before the peer is unsubscribed from.
+    pub max_rate_limited_messages: u64,
+    /// The window (in milliseconds) within which the dropped messages are counted
+    pub rate_limit_window_ms: u64,
+
+    /// Whether to verify the proofs of ordered blocks and commit decisions in
+    /// parallel (off the observer loop), before the messages are processed.
+    pub enable_parallel_proof_verification: bool,
+    /// Maximum number of messages that can be verified in parallel (before the
+    /// observer stops reading new messages from the network).
+    pub max_parallel_proof_verifications: u64,
+
+    /// Whether to append every verified ordered block and commit decision to
+    /// a (replayable) on-disk block archive (e.g., for offline analysis and audits)
+    pub enable_block_archive: bool,
+    /// Maximum size (in bytes) of a single block archive segment (before it is rotated)
+    pub max_block_archive_segment_size_bytes: u64,
+    /// Maximum number of block archive segments to keep (including the active
+    /// segment). If this is zero, all segments are kept (i.e., the archive is complete).
+    pub max_num_block_archive_segments: u64,
+    /// Whether to replay the block archive (in the storage directory) through the
+    /// observer, instead of subscribing to peers. This is useful for reproducing
+    /// issues deterministically (e.g., a stall at a specific round) on a local node.
+    pub enable_block_archive_replay: bool,
+    /// The peers to always try first when selecting a subscription peer (in
+    /// the given order, and only if they are connected), before falling back
+    /// to the distance and latency based sorting. This allows operators that
+    /// run their own upstream nodes to enforce a deterministic topology.
+    pub preferred_subscription_peers: Vec<PeerNetworkId>,
+}
+
+/// The escalations that can be performed when the consensus observer
+/// repeatedly fails to send blocks and commits to the execution pipeline.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineFailureEscalation {
+    /// Reset the execution pipeline and state sync to the latest known commit
+    StateSync,
+    /// Reset the execution pipeline (as above) and resubscribe to a different peer
+    Resubscribe,
+}
+
+impl PipelineFailureEscalation {
+    /// Returns a summary label for the escalation
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            PipelineFailureEscalation::StateSync => "state_sync",
+            PipelineFailureEscalation::Resubscribe => "resubscribe",
+        }
+    }
+}
+
+/// The policies that can be enforced when messages from the
+/// subscription peer (e.g., ordered blocks or commits) fail verification.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationFailurePolicy {
+    /// Drop the invalid messages (but keep the subscription)
+    DropOnly,
+    /// Terminate the subscription after too many verification failures
+    TerminateSubscription,
+    /// Terminate the subscription (as above) and blocklist the peer
+    TerminateAndBlocklist,
 }
 
 impl Default for ConsensusObserverConfig {
@@ -51,12 +346,89 @@ impl Default for ConsensusObserverConfig {
             max_network_channel_size: 1000,
             max_parallel_serialization_tasks: num_cpus::get(), // Default to the number of CPUs
             network_request_timeout_ms: 10_000,                // 10 seconds
+            max_num_peer_metric_labels: 100,                   // 100 peers
+            observer_runtime_threads: None,                    // Default to the number of CPUs
+            publisher_runtime_threads: None,                   // Default to the number of CPUs
             garbage_collection_interval_ms: 60_000,            // 60 seconds
+            max_num_journal_events: 1000,                      // 1000 events
+            max_num_cached_epoch_states: 3,                    // 3 epochs
             max_num_pending_blocks: 100,                       // 100 blocks
+            max_rounds_ahead_of_root: 1000,                    // 1000 rounds
             max_subscription_timeout_ms: 30_000,               // 30 seconds
             max_synced_version_timeout_ms: 60_000,             // 60 seconds
             peer_optimality_check_interval_ms: 60_000,         // 60 seconds
             progress_check_interval_ms: 5_000,                 // 5 seconds
+            max_consecutive_pipeline_failures: 10,             // 10 failures
+            pipeline_failure_escalation: PipelineFailureEscalation::StateSync,
+            pipeline_processing_deadline_ms: 10_000, // 10 seconds
+            verification_failure_policy: VerificationFailurePolicy::DropOnly,
+            max_verification_failures: 3,        // 3 failures
+            peer_blocklist_duration_ms: 600_000, // 10 minutes
+            disconnect_misbehaving_peers: false,
+            max_peer_misbehavior_score: 100,
+            grpc_export_address: None,
+            sse_export_address: None,
+            max_export_buffer_size: 1000, // 1000 items
+            enable_commit_journal: false,
+            max_commit_journal_file_size_bytes: 100 * 1024 * 1024, // 100 MB
+            max_num_commit_journal_files: 5,                       // 5 files
+            enable_state_snapshots: false,
+            state_snapshot_interval_ms: 10_000, // 10 seconds
+            enable_committed_block_pruning: false,
+            enable_payload_signature_verification: false,
+            payload_store_sizing_window_ms: 60_000, // 60 seconds
+            payload_store_target_buffer_ms: 30_000, // 30 seconds
+            min_payload_store_capacity_bytes: 64 * 1024 * 1024, // 64 MB
+            max_payload_store_capacity_bytes: 2 * 1024 * 1024 * 1024, // 2 GB
+            enable_payload_dictionary_compression: false,
+            payload_dictionary_refresh_interval_ms: 600_000, // 10 minutes
+            max_payload_dictionary_size_bytes: 110 * 1024,   // 110 KB
+            max_num_payload_dictionary_samples: 1000,        // 1000 payloads
+            enable_payload_delta_encoding: false,
+            max_num_delta_cached_transactions: 100_000,
+            enable_payload_prefetch: false,
+            enable_speculative_forwarding: false,
+            enable_incremental_block_finalization: false,
+            enable_latency_bucketed_peer_selection: false,
+            peer_latency_bucket_width_ms: 50, // 50 ms
+            enable_subscription_rotation: false,
+            subscription_rotation_interval_ms: 14_400_000, // 4 hours
+            subscription_rotation_candidates: 3,           // 3 peers
+            min_subscription_hold_time_ms: 0,
+            max_block_timestamp_skew_ms: 300_000, // 5 minutes
+            reject_invalid_block_timestamps: false,
+            max_subscription_staleness_ms: 15_000, // 15 seconds
+            min_subscription_staleness_version_lag: 1000, // 1000 versions
+            enable_subscription_timeout_probes: true,
+            enable_fallback_peer_ranking: true,
+            peer_activity_window_ms: 60_000,     // 60 seconds
+            max_future_commit_buffer_ms: 60_000, // 60 seconds
+            max_future_commit_epochs: 2,
+            max_concurrent_subscriptions: 1,
+            enable_ordered_block_backfill: false,
+            max_ordered_block_backfill_rounds: 20,
+            enable_block_payload_requests: false,
+            max_block_payload_requests: 10,
+            enable_subscription_replay: false,
+            max_replay_buffer_messages: 300,
+            enable_persistent_payload_store: false,
+            max_num_payload_store_entries: 5_000,
+            max_payload_store_memory_bytes: 4 * 1024 * 1024 * 1024, // 4 GB
+            enable_pending_blocks_overflow_sync: true,
+            enable_peer_reputation_ranking: true,
+            peer_reputation_window_ms: 600_000, // 10 minutes
+            enable_inbound_rate_limiting: false,
+            max_inbound_messages_per_sec: 1_000,
+            max_inbound_message_burst: 2_000,
+            max_rate_limited_messages: 1_000,
+            rate_limit_window_ms: 10_000, // 10 seconds
+            enable_parallel_proof_verification: true,
+            max_parallel_proof_verifications: 16,
+            enable_block_archive: false,
+            max_block_archive_segment_size_bytes: 256 * 1024 * 1024, // 256 MB
+            max_num_block_archive_segments: 0,                       // Keep all segments
+            enable_block_archive_replay: false,
+            preferred_subscription_peers: vec![],
         }
     }
 }
@@ -68,6 +440,280 @@ impl ConsensusObserverConfig {
     }
 }
 
+impl ConfigSanitizer for ConsensusObserverConfig {
+    fn sanitize(
+        node_config: &NodeConfig,
+        _node_type: NodeType,
+        _chain_id: Option<ChainId>,
+    ) -> Result<(), Error> {
+        let sanitizer_name = Self::get_sanitizer_name();
+        let consensus_observer_config = &node_config.consensus_observer;
+
+        // If the observer and publisher are both disabled, there's nothing to do
+        if !consensus_observer_config.is_observer_or_publisher_enabled() {
+            return Ok(());
+        }
+
+        // Verify that none of the sizes, timeouts or intervals are zero
+        let non_zero_values = [
+            (
+                "max_network_channel_size",
+                consensus_observer_config.max_network_channel_size,
+            ),
+            (
+                "max_parallel_serialization_tasks",
+                consensus_observer_config.max_parallel_serialization_tasks as u64,
+            ),
+            (
+                "network_request_timeout_ms",
+                consensus_observer_config.network_request_timeout_ms,
+            ),
+            (
+                "garbage_collection_interval_ms",
+                consensus_observer_config.garbage_collection_interval_ms,
+            ),
+            (
+                "max_num_cached_epoch_states",
+                consensus_observer_config.max_num_cached_epoch_states,
+            ),
+            (
+                "max_rounds_ahead_of_root",
+                consensus_observer_config.max_rounds_ahead_of_root,
+            ),
+            (
+                "max_subscription_timeout_ms",
+                consensus_observer_config.max_subscription_timeout_ms,
+            ),
+            (
+                "max_synced_version_timeout_ms",
+                consensus_observer_config.max_synced_version_timeout_ms,
+            ),
+            (
+                "peer_optimality_check_interval_ms",
+                consensus_observer_config.peer_optimality_check_interval_ms,
+            ),
+            (
+                "progress_check_interval_ms",
+                consensus_observer_config.progress_check_interval_ms,
+            ),
+            (
+                "max_consecutive_pipeline_failures",
+                consensus_observer_config.max_consecutive_pipeline_failures,
+            ),
+            (
+                "pipeline_processing_deadline_ms",
+                consensus_observer_config.pipeline_processing_deadline_ms,
+            ),
+            (
+                "max_verification_failures",
+                consensus_observer_config.max_verification_failures,
+            ),
+            (
+                "peer_blocklist_duration_ms",
+                consensus_observer_config.peer_blocklist_duration_ms,
+            ),
+            (
+                "max_peer_misbehavior_score",
+                consensus_observer_config.max_peer_misbehavior_score,
+            ),
+            (
+                "max_export_buffer_size",
+                consensus_observer_config.max_export_buffer_size,
+            ),
+            (
+                "max_commit_journal_file_size_bytes",
+                consensus_observer_config.max_commit_journal_file_size_bytes,
+            ),
+            (
+                "max_num_commit_journal_files",
+                consensus_observer_config.max_num_commit_journal_files,
+            ),
+            (
+                "state_snapshot_interval_ms",
+                consensus_observer_config.state_snapshot_interval_ms,
+            ),
+            (
+                "payload_store_sizing_window_ms",
+                consensus_observer_config.payload_store_sizing_window_ms,
+            ),
+            (
+                "payload_store_target_buffer_ms",
+                consensus_observer_config.payload_store_target_buffer_ms,
+            ),
+            (
+                "max_payload_store_capacity_bytes",
+                consensus_observer_config.max_payload_store_capacity_bytes,
+            ),
+            (
+                "payload_dictionary_refresh_interval_ms",
+                consensus_observer_config.payload_dictionary_refresh_interval_ms,
+            ),
+            (
+                "max_payload_dictionary_size_bytes",
+                consensus_observer_config.max_payload_dictionary_size_bytes,
+            ),
+            (
+                "max_num_payload_dictionary_samples",
+                consensus_observer_config.max_num_payload_dictionary_samples,
+            ),
+            (
+                "max_num_delta_cached_transactions",
+                consensus_observer_config.max_num_delta_cached_transactions,
+            ),
+            (
+                "peer_latency_bucket_width_ms",
+                consensus_observer_config.peer_latency_bucket_width_ms,
+            ),
+            (
+                "subscription_rotation_interval_ms",
+                consensus_observer_config.subscription_rotation_interval_ms,
+            ),
+            (
+                "subscription_rotation_candidates",
+                consensus_observer_config.subscription_rotation_candidates,
+            ),
+            (
+                "max_block_timestamp_skew_ms",
+                consensus_observer_config.max_block_timestamp_skew_ms,
+            ),
+            (
+                "max_subscription_staleness_ms",
+                consensus_observer_config.max_subscription_staleness_ms,
+            ),
+            (
+                "min_subscription_staleness_version_lag",
+                consensus_observer_config.min_subscription_staleness_version_lag,
+            ),
+            (
+                "peer_activity_window_ms",
+                consensus_observer_config.peer_activity_window_ms,
+            ),
+            (
+                "max_future_commit_buffer_ms",
+                consensus_observer_config.max_future_commit_buffer_ms,
+            ),
+            (
+                "max_future_commit_epochs",
+                consensus_observer_config.max_future_commit_epochs,
+            ),
+            (
+                "max_concurrent_subscriptions",
+                consensus_observer_config.max_concurrent_subscriptions,
+            ),
+            (
+                "max_ordered_block_backfill_rounds",
+                consensus_observer_config.max_ordered_block_backfill_rounds,
+            ),
+            (
+                "max_block_payload_requests",
+                consensus_observer_config.max_block_payload_requests,
+            ),
+            (
+                "max_replay_buffer_messages",
+                consensus_observer_config.max_replay_buffer_messages,
+            ),
+            (
+                "max_num_payload_store_entries",
+                consensus_observer_config.max_num_payload_store_entries,
+            ),
+            (
+                "max_payload_store_memory_bytes",
+                consensus_observer_config.max_payload_store_memory_bytes,
+            ),
+            (
+                "peer_reputation_window_ms",
+                consensus_observer_config.peer_reputation_window_ms,
+            ),
+            (
+                "max_inbound_messages_per_sec",
+                consensus_observer_config.max_inbound_messages_per_sec,
+            ),
+            (
+                "max_inbound_message_burst",
+                consensus_observer_config.max_inbound_message_burst,
+            ),
+            (
+                "max_rate_limited_messages",
+                consensus_observer_config.max_rate_limited_messages,
+            ),
+            (
+                "rate_limit_window_ms",
+                consensus_observer_config.rate_limit_window_ms,
+            ),
+            (
+                "max_parallel_proof_verifications",
+                consensus_observer_config.max_parallel_proof_verifications,
+            ),
+            (
+                "max_block_archive_segment_size_bytes",
+                consensus_observer_config.max_block_archive_segment_size_bytes,
+            ),
+        ];
+        for (config_name, config_value) in non_zero_values {
+            if config_value == 0 {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!("The {} must be greater than zero!", config_name),
+                ));
+            }
+        }
+
+        // Verify that the runtime thread counts are not zero (if specified)
+        let runtime_threads = [
+            (
+                "observer_runtime_threads",
+                consensus_observer_config.observer_runtime_threads,
+            ),
+            (
+                "publisher_runtime_threads",
+                consensus_observer_config.publisher_runtime_threads,
+            ),
+        ];
+        for (config_name, num_threads) in runtime_threads {
+            if num_threads == Some(0) {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    format!(
+                        "The {} must be greater than zero (if specified)!",
+                        config_name
+                    ),
+                ));
+            }
+        }
+
+        // Verify that the progress check interval is not longer than the subscription
+        // timeout (otherwise, subscription timeouts will not be detected promptly).
+        let progress_check_interval_ms = consensus_observer_config.progress_check_interval_ms;
+        let max_subscription_timeout_ms = consensus_observer_config.max_subscription_timeout_ms;
+        if progress_check_interval_ms > max_subscription_timeout_ms {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The progress check interval ({} ms) must not be longer than the subscription timeout ({} ms)!",
+                    progress_check_interval_ms, max_subscription_timeout_ms
+                ),
+            ));
+        }
+
+        // Verify that the payload store capacity bounds are valid (i.e., the floor is not above the ceiling)
+        let min_payload_store_capacity_bytes =
+            consensus_observer_config.min_payload_store_capacity_bytes;
+        let max_payload_store_capacity_bytes =
+            consensus_observer_config.max_payload_store_capacity_bytes;
+        if min_payload_store_capacity_bytes > max_payload_store_capacity_bytes {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                format!(
+                    "The minimum payload store capacity ({} bytes) must not be greater than the maximum ({} bytes)!",
+                    min_payload_store_capacity_bytes, max_payload_store_capacity_bytes
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 impl ConfigOptimizer for ConsensusObserverConfig {
     fn optimize(
         node_config: &mut NodeConfig,
@@ -116,3 +762,132 @@ impl ConfigOptimizer for ConsensusObserverConfig {
         Ok(modified_config)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_disabled_config() {
+        // Create a node config with an invalid (but disabled) observer config
+        let node_config = NodeConfig {
+            consensus_observer: ConsensusObserverConfig {
+                network_request_timeout_ms: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config passes sanitization (the observer and publisher are disabled)
+        ConsensusObserverConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_zero_values() {
+        // Create a node config with a zero subscription timeout
+        let node_config = NodeConfig {
+            consensus_observer: ConsensusObserverConfig {
+                observer_enabled: true,
+                max_subscription_timeout_ms: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config fails sanitization
+        let error = ConsensusObserverConfig::sanitize(&node_config, NodeType::PublicFullnode, None)
+            .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Create a node config with a zero network channel size
+        let node_config = NodeConfig {
+            consensus_observer: ConsensusObserverConfig {
+                publisher_enabled: true,
+                max_network_channel_size: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config fails sanitization
+        let error =
+            ConsensusObserverConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Create a node config with zero publisher runtime threads
+        let node_config = NodeConfig {
+            consensus_observer: ConsensusObserverConfig {
+                publisher_enabled: true,
+                publisher_runtime_threads: Some(0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config fails sanitization
+        let error =
+            ConsensusObserverConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_progress_check_interval() {
+        // Create a node config with a progress check interval longer than the subscription timeout
+        let node_config = NodeConfig {
+            consensus_observer: ConsensusObserverConfig {
+                observer_enabled: true,
+                max_subscription_timeout_ms: 1_000,
+                progress_check_interval_ms: 2_000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config fails sanitization
+        let error = ConsensusObserverConfig::sanitize(&node_config, NodeType::PublicFullnode, None)
+            .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Verify that the default config passes sanitization
+        let node_config = NodeConfig {
+            consensus_observer: ConsensusObserverConfig {
+                observer_enabled: true,
+                publisher_enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        ConsensusObserverConfig::sanitize(&node_config, NodeType::ValidatorFullnode, None).unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_payload_store_capacity_bounds() {
+        // Create a node config with a payload store capacity floor above the ceiling
+        let node_config = NodeConfig {
+            consensus_observer: ConsensusObserverConfig {
+                observer_enabled: true,
+                min_payload_store_capacity_bytes: 2_000,
+                max_payload_store_capacity_bytes: 1_000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config fails sanitization
+        let error = ConsensusObserverConfig::sanitize(&node_config, NodeType::PublicFullnode, None)
+            .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Verify that equal bounds pass sanitization
+        let node_config = NodeConfig {
+            consensus_observer: ConsensusObserverConfig {
+                observer_enabled: true,
+                min_payload_store_capacity_bytes: 1_000,
+                max_payload_store_capacity_bytes: 1_000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        ConsensusObserverConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap();
+    }
+}
@@ -0,0 +1,377 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::config::ConsensusObserverConfig;
+use aptos_consensus::consensus_observer::{
+    network_message::{ConsensusObserverDirectSend, ConsensusObserverMessage, OrderedBlock},
+    payload_store::BlockPayloadStore,
+    pending_blocks::PendingOrderedBlocks,
+};
+use aptos_consensus_types::{
+    block::Block,
+    block_data::{BlockData, BlockType},
+    pipelined_block::PipelinedBlock,
+    quorum_cert::QuorumCert,
+    vote_data::VoteData,
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519Signature},
+    HashValue, PrivateKey, Uniform,
+};
+use aptos_network::protocols::wire::handshake::v1::ProtocolId;
+use aptos_time_service::TimeService;
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    chain_id::ChainId,
+    epoch_state::EpochState,
+    ledger_info::{generate_ledger_info_with_sig, LedgerInfo, LedgerInfoWithSignatures},
+    transaction::{RawTransaction, Script, SignedTransaction, TransactionPayload},
+    validator_signer::ValidatorSigner,
+    validator_verifier::generate_validator_verifier,
+};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use move_core_types::account_address::AccountAddress;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+// Useful constants for the benchmarks
+const EPOCH: u64 = 10;
+const NUM_BLOCKS_PER_ORDERED_BLOCK: [usize; 3] = [1, 5, 10];
+const NUM_CONCURRENT_WRITERS: [usize; 3] = [0, 1, 4];
+const NUM_PAYLOADS_IN_STORE: usize = 100;
+const NUM_TRANSACTIONS_PER_BLOCK: [usize; 3] = [10, 100, 1000];
+const NUM_VALIDATORS: usize = 10;
+
+/// Benchmarks the insertion of block payloads into the payload store
+fn payload_insertion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("observer_payload_insertion");
+    for num_transactions in NUM_TRANSACTIONS_PER_BLOCK {
+        let transactions = create_signed_transactions(num_transactions);
+        group.throughput(Throughput::Elements(num_transactions as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_transactions),
+            &transactions,
+            |b, transactions| {
+                b.iter_batched(
+                    || {
+                        let block_info = BlockInfo::random_with_epoch(EPOCH, 1);
+                        (
+                            BlockPayloadStore::new(
+                                ConsensusObserverConfig::default(),
+                                TimeService::real(),
+                            ),
+                            block_info,
+                            transactions.clone(),
+                        )
+                    },
+                    |(mut block_payload_store, block_info, transactions)| {
+                        block_payload_store.insert_block_payload(block_info, transactions, None);
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Benchmarks payload reads (as performed by the payload manager during block
+/// execution), while other threads concurrently insert and remove payloads.
+fn payload_reads_under_contention(c: &mut Criterion) {
+    let mut group = c.benchmark_group("observer_payload_reads_under_contention");
+    for num_writers in NUM_CONCURRENT_WRITERS {
+        // Create a payload store with several existing payloads
+        let mut block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::real());
+        let block_ids: Vec<_> = (0..NUM_PAYLOADS_IN_STORE)
+            .map(|round| {
+                let block_info = BlockInfo::random_with_epoch(EPOCH, round as u64);
+                let block_id = block_info.id();
+                block_payload_store.insert_block_payload(
+                    block_info,
+                    create_signed_transactions(10),
+                    None,
+                );
+                block_id
+            })
+            .collect();
+
+        // Start the writers (each inserts and removes payloads for other blocks)
+        let stop_writers = Arc::new(AtomicBool::new(false));
+        let writer_handles: Vec<_> = (0..num_writers)
+            .map(|_| {
+                let mut block_payload_store = block_payload_store.clone();
+                let stop_writers = stop_writers.clone();
+                let transactions = create_signed_transactions(10);
+                thread::spawn(move || {
+                    while !stop_writers.load(Ordering::Relaxed) {
+                        let block_info = BlockInfo::random_with_epoch(EPOCH, 0);
+                        let block_id = block_info.id();
+                        block_payload_store.insert_block_payload(
+                            block_info,
+                            transactions.clone(),
+                            None,
+                        );
+                        block_payload_store
+                            .get_payload_store_backend()
+                            .remove_payloads(&[block_id]);
+                    }
+                })
+            })
+            .collect();
+
+        // Benchmark the payload reads
+        let payload_store_backend = block_payload_store.get_payload_store_backend();
+        group.throughput(Throughput::Elements(block_ids.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_writers),
+            &block_ids,
+            |b, block_ids| {
+                b.iter(|| {
+                    for block_id in block_ids {
+                        assert!(payload_store_backend
+                            .get_or_request_payload(*block_id)
+                            .is_left());
+                    }
+                })
+            },
+        );
+
+        // Stop the writers
+        stop_writers.store(true, Ordering::Relaxed);
+        for writer_handle in writer_handles {
+            writer_handle.join().unwrap();
+        }
+    }
+    group.finish();
+}
+
+/// Benchmarks the verification of ordered blocks (including the ordered proof)
+fn ordered_block_verification(c: &mut Criterion) {
+    let validator_signers = create_validator_signers();
+    let epoch_state = create_epoch_state(&validator_signers);
+
+    let mut group = c.benchmark_group("observer_ordered_block_verification");
+    for num_blocks in NUM_BLOCKS_PER_ORDERED_BLOCK {
+        let ordered_block = create_ordered_block(&validator_signers, num_blocks);
+        group.throughput(Throughput::Elements(num_blocks as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_blocks),
+            &ordered_block,
+            |b, ordered_block| {
+                b.iter(|| {
+                    ordered_block.verify_ordered_blocks().unwrap();
+                    ordered_block.verify_ordered_proof(&epoch_state).unwrap();
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Benchmarks the end-to-end processing of the messages for a single
+/// block (i.e., deserialization, verification, storage and commit).
+fn message_processing(c: &mut Criterion) {
+    let validator_signers = create_validator_signers();
+    let epoch_state = create_epoch_state(&validator_signers);
+    let protocol_id = ProtocolId::ConsensusObserver;
+
+    let mut group = c.benchmark_group("observer_message_processing");
+    for num_transactions in NUM_TRANSACTIONS_PER_BLOCK {
+        // Create and serialize the messages for a single block
+        let ordered_block = create_ordered_block(&validator_signers, 1);
+        let block_info = ordered_block.proof_block_info().clone();
+        let messages = [
+            ConsensusObserverMessage::new_block_payload_message(
+                block_info.clone(),
+                create_signed_transactions(num_transactions),
+                None,
+            ),
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+            ConsensusObserverMessage::new_commit_decision_message(
+                ordered_block.ordered_proof().clone(),
+            ),
+        ];
+        let serialized_messages: Vec<_> = messages
+            .into_iter()
+            .map(|message| {
+                protocol_id
+                    .to_bytes(&ConsensusObserverMessage::DirectSend(message))
+                    .unwrap()
+            })
+            .collect();
+
+        // Process the messages in the same order as the observer
+        group.throughput(Throughput::Elements(num_transactions as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(num_transactions),
+            &serialized_messages,
+            |b, serialized_messages| {
+                b.iter_batched(
+                    || {
+                        let pending_ordered_blocks =
+                            PendingOrderedBlocks::new(ConsensusObserverConfig::default());
+                        (
+                            pending_ordered_blocks,
+                            BlockPayloadStore::new(
+                                ConsensusObserverConfig::default(),
+                                TimeService::real(),
+                            ),
+                        )
+                    },
+                    |(pending_ordered_blocks, mut block_payload_store)| {
+                        for serialized_message in serialized_messages {
+                            let message = protocol_id
+                                .from_bytes::<ConsensusObserverMessage>(serialized_message)
+                                .unwrap();
+                            process_direct_send_message(
+                                message,
+                                &epoch_state,
+                                &pending_ordered_blocks,
+                                &mut block_payload_store,
+                            );
+                        }
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Processes the given direct send message (mirroring the observer's message handling)
+fn process_direct_send_message(
+    message: ConsensusObserverMessage,
+    epoch_state: &EpochState,
+    pending_ordered_blocks: &PendingOrderedBlocks,
+    block_payload_store: &mut BlockPayloadStore,
+) {
+    match message {
+        ConsensusObserverMessage::DirectSend(ConsensusObserverDirectSend::BlockPayload(
+            block_payload,
+        )) => {
+            block_payload_store.insert_block_payload(
+                block_payload.block,
+                block_payload.transactions,
+                block_payload.limit,
+            );
+        },
+        ConsensusObserverMessage::DirectSend(ConsensusObserverDirectSend::OrderedBlock(
+            ordered_block,
+        )) => {
+            ordered_block.verify_ordered_blocks().unwrap();
+            ordered_block.verify_ordered_proof(epoch_state).unwrap();
+            pending_ordered_blocks.insert_ordered_block(Arc::new(ordered_block), true);
+        },
+        ConsensusObserverMessage::DirectSend(ConsensusObserverDirectSend::CommitDecision(
+            commit_decision,
+        )) => {
+            commit_decision.verify_commit_proof(epoch_state).unwrap();
+            let pending_block = pending_ordered_blocks
+                .get_verified_pending_block(commit_decision.epoch(), commit_decision.round())
+                .unwrap();
+            assert!(block_payload_store.all_payloads_exist(pending_block.blocks()));
+            pending_ordered_blocks.update_commit_decision(&commit_decision);
+
+            // Commit the block (as the commit callback would)
+            block_payload_store.remove_blocks(pending_block.blocks());
+            pending_ordered_blocks.remove_blocks_for_commit(commit_decision.commit_proof());
+        },
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+/// Creates and returns an epoch state for the given validators
+fn create_epoch_state(validator_signers: &[ValidatorSigner]) -> EpochState {
+    EpochState::new(EPOCH, generate_validator_verifier(validator_signers))
+}
+
+/// Creates an ordered block with the given number of (correctly chained)
+/// blocks, and an ordered proof signed by all the given validators.
+fn create_ordered_block(validator_signers: &[ValidatorSigner], num_blocks: usize) -> OrderedBlock {
+    let mut parent_block_info = BlockInfo::random_with_epoch(EPOCH, 0);
+    let mut pipelined_blocks = vec![];
+    for round in 1..=num_blocks as u64 {
+        // Create the block info
+        let block_info = BlockInfo::new(
+            EPOCH,
+            round,
+            HashValue::random(),
+            HashValue::random(),
+            round,
+            round,
+            None,
+        );
+
+        // Create the pipelined block (certifying the parent block)
+        let quorum_cert = QuorumCert::new(
+            VoteData::new(parent_block_info.clone(), parent_block_info.clone()),
+            LedgerInfoWithSignatures::new(
+                LedgerInfo::new(parent_block_info.clone(), HashValue::zero()),
+                AggregateSignature::empty(),
+            ),
+        );
+        let block_data = BlockData::new_for_testing(
+            EPOCH,
+            round,
+            block_info.timestamp_usecs(),
+            quorum_cert,
+            BlockType::Genesis,
+        );
+        let block = Block::new_for_testing(block_info.id(), block_data, None);
+        pipelined_blocks.push(Arc::new(PipelinedBlock::new_ordered(block)));
+
+        parent_block_info = block_info;
+    }
+
+    // Create the ordered proof for the last block
+    let ordered_proof = generate_ledger_info_with_sig(
+        validator_signers,
+        LedgerInfo::new(parent_block_info, HashValue::zero()),
+    );
+    OrderedBlock::new(pipelined_blocks, ordered_proof)
+}
+
+/// Creates and returns the given number of signed transactions
+fn create_signed_transactions(num_transactions: usize) -> Vec<SignedTransaction> {
+    let public_key = Ed25519PrivateKey::generate_for_testing().public_key();
+    (0..num_transactions)
+        .map(|_| {
+            let raw_transaction = RawTransaction::new(
+                AccountAddress::random(),
+                0,
+                TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+                0,
+                1,
+                0,
+                ChainId::new(10),
+            );
+            SignedTransaction::new(
+                raw_transaction,
+                public_key.clone(),
+                Ed25519Signature::dummy_signature(),
+            )
+        })
+        .collect()
+}
+
+/// Creates and returns the validator signers
+fn create_validator_signers() -> Vec<ValidatorSigner> {
+    (0..NUM_VALIDATORS)
+        .map(|index| ValidatorSigner::from_int(index as u8))
+        .collect()
+}
+
+criterion_group!(
+    name = observer_benches;
+    config = Criterion::default().sample_size(20);
+    targets = payload_insertion, payload_reads_under_contention, ordered_block_verification, message_processing
+);
+criterion_main!(observer_benches);
@@ -365,6 +365,8 @@ impl BlockStore {
             self.payload_manager
                 .prefetch_payload_data(payload, pipelined_block.block().timestamp_usecs());
         }
+        self.payload_manager
+            .prefetch_and_publish_payload(pipelined_block.block());
         self.storage
             .save_tree(vec![pipelined_block.block().clone()], vec![])
             .context("Insert block failed when saving block")?;
@@ -0,0 +1,185 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    block_archive::BlockArchiveReader,
+    block_source::{BlockSourceMessage, ObserverBlockSource},
+    logging::{LogEntry, LogSchema},
+};
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_logger::{error, info};
+use aptos_types::PeerId;
+use futures::Stream;
+use std::{
+    io,
+    path::Path,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+// The label of the archive replay block source
+pub const ARCHIVE_REPLAY_LABEL: &str = "archive_replay";
+
+/// A block source that replays the messages recorded in a block archive
+/// (in the order they were recorded). Replayed messages are verified and
+/// processed in the same way as messages received from the subscription
+/// peer, so that issues observed in production (e.g., a stall at a specific
+/// round) can be reproduced deterministically against a local node.
+pub struct ArchiveReplayBlockSource {
+    // The reader for the archived records
+    block_archive_reader: BlockArchiveReader,
+
+    // The (synthetic) peer that the replayed messages are attributed to
+    replay_peer: PeerNetworkId,
+
+    // The number of messages replayed so far
+    num_replayed_messages: u64,
+
+    // Whether the replay has finished (i.e., the archive was fully read, or is corrupt)
+    replay_finished: bool,
+}
+
+impl ArchiveReplayBlockSource {
+    /// Creates a new replay block source for the archive in the given directory
+    pub fn new(archive_directory: &Path) -> io::Result<Self> {
+        let block_archive_reader = BlockArchiveReader::new(archive_directory)?;
+        Ok(Self {
+            block_archive_reader,
+            replay_peer: PeerNetworkId::new(NetworkId::Public, PeerId::ZERO),
+            num_replayed_messages: 0,
+            replay_finished: false,
+        })
+    }
+
+    /// Logs the end of the replay (with the number of replayed messages)
+    fn finish_replay(&mut self, error: Option<io::Error>) {
+        self.replay_finished = true;
+        match error {
+            Some(error) => {
+                error!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to read the block archive! Stopping the replay after {} messages. Error: {:?}",
+                        self.num_replayed_messages, error
+                    ))
+                );
+            },
+            None => {
+                info!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Finished replaying the block archive! Replayed {} messages.",
+                        self.num_replayed_messages
+                    ))
+                );
+            },
+        }
+    }
+}
+
+impl ObserverBlockSource for ArchiveReplayBlockSource {
+    fn get_label(&self) -> &'static str {
+        ARCHIVE_REPLAY_LABEL
+    }
+}
+
+impl Stream for ArchiveReplayBlockSource {
+    type Item = BlockSourceMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // If the replay has finished, terminate the stream
+        if self.replay_finished {
+            return Poll::Ready(None);
+        }
+
+        // Read the next archived record (and transform it into a message).
+        // Note: the archive is read synchronously, as replays are only
+        // used for local debugging (and this keeps the replay deterministic).
+        match self.block_archive_reader.next() {
+            Some(Ok(archived_record)) => {
+                self.num_replayed_messages += 1;
+                let message = archived_record.into_direct_send_message();
+                Poll::Ready(Some(BlockSourceMessage::new(self.replay_peer, message)))
+            },
+            Some(Err(error)) => {
+                self.finish_replay(Some(error));
+                Poll::Ready(None)
+            },
+            None => {
+                self.finish_replay(None);
+                Poll::Ready(None)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::{
+        block_archive::BlockArchiveWriter,
+        event_listener::ObserverEventListener,
+        network_message::{BlockPayload, CommitDecision, ConsensusObserverDirectSend},
+    };
+    use aptos_crypto::HashValue;
+    use aptos_temppath::TempPath;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        block_info::BlockInfo,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_archive_replay() {
+        // Archive several block payloads and commit decisions
+        let archive_directory = TempPath::new();
+        let block_archive_writer =
+            BlockArchiveWriter::new(archive_directory.path().to_path_buf(), 1_000_000, 0).unwrap();
+        let num_rounds = 5;
+        for round in 0..num_rounds {
+            let block_info = BlockInfo::random_with_epoch(0, round);
+            block_archive_writer.on_block_payload(&BlockPayload {
+                block: block_info.clone(),
+                transactions: vec![],
+                limit: None,
+            });
+            block_archive_writer.on_commit_decision(&CommitDecision::new(
+                LedgerInfoWithSignatures::new(
+                    LedgerInfo::new(block_info, HashValue::random()),
+                    AggregateSignature::empty(),
+                ),
+            ));
+        }
+
+        // Create a replay block source for the archive
+        let mut archive_replay_block_source =
+            ArchiveReplayBlockSource::new(archive_directory.path()).unwrap();
+        assert_eq!(
+            archive_replay_block_source.get_label(),
+            ARCHIVE_REPLAY_LABEL
+        );
+
+        // Verify that the block source replays all the messages (in order)
+        for round in 0..num_rounds {
+            let block_source_message = archive_replay_block_source.next().await.unwrap();
+            match block_source_message.message {
+                ConsensusObserverDirectSend::BlockPayload(block_payload) => {
+                    assert_eq!(block_payload.block.round(), round);
+                },
+                message => panic!("Unexpected replayed message: {:?}", message),
+            }
+
+            let block_source_message = archive_replay_block_source.next().await.unwrap();
+            match block_source_message.message {
+                ConsensusObserverDirectSend::CommitDecision(commit_decision) => {
+                    assert_eq!(commit_decision.round(), round);
+                },
+                message => panic!("Unexpected replayed message: {:?}", message),
+            }
+        }
+
+        // Verify that the block source terminates (and stays terminated)
+        assert!(archive_replay_block_source.next().await.is_none());
+        assert!(archive_replay_block_source.next().await.is_none());
+        assert_eq!(archive_replay_block_source.num_replayed_messages, 10);
+    }
+}
@@ -0,0 +1,575 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the block archive of the consensus observer: an
+//! append-only, replayable log of every ordered block, block payload and
+//! commit decision accepted by the observer (e.g., for offline analysis,
+//! auditing and replays).
+//!
+//! Records are appended to numbered segment files, which are rotated once
+//! they exceed the maximum size. Each record is a BCS-encoded frame:
+//! ```text
+//! |<--4 bytes-->|<--32 bytes-->|<--length bytes-->|
+//! |   length    |   checksum   |      record      |
+//! ```
+//! The length is encoded in little endian, and the checksum is the SHA3-256
+//! hash of the record bytes (used to detect corrupted or torn records).
+
+use crate::consensus_observer::{
+    event_listener::ObserverEventListener,
+    logging::{LogEntry, LogSchema},
+    network_message::{BlockPayload, CommitDecision, ConsensusObserverDirectSend, OrderedBlock},
+};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::warn;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Write},
+    mem::size_of,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+// The prefix and extension of the archive segment files
+const SEGMENT_FILE_PREFIX: &str = "block_archive_";
+const SEGMENT_FILE_EXTENSION: &str = "seg";
+
+// The size of the header of each record frame (i.e., the length and the checksum)
+const FRAME_HEADER_SIZE: usize = size_of::<u32>() + HashValue::LENGTH;
+
+// The maximum size of a single record (this matches the maximum network message
+// size, so every accepted message fits). Larger lengths are treated as corruption.
+const MAX_RECORD_SIZE_BYTES: usize = 64 * 1024 * 1024; // 64 MiB
+
+/// A single record in the block archive. Note: new record
+/// types must be appended (to keep existing archives readable).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ArchivedRecord {
+    OrderedBlock(OrderedBlock),
+    CommitDecision(CommitDecision),
+    BlockPayload(BlockPayload),
+}
+
+impl ArchivedRecord {
+    /// Transforms the record into the direct send message it was accepted from
+    pub fn into_direct_send_message(self) -> ConsensusObserverDirectSend {
+        match self {
+            ArchivedRecord::OrderedBlock(ordered_block) => {
+                ConsensusObserverDirectSend::OrderedBlock(ordered_block)
+            },
+            ArchivedRecord::CommitDecision(commit_decision) => {
+                ConsensusObserverDirectSend::CommitDecision(commit_decision)
+            },
+            ArchivedRecord::BlockPayload(block_payload) => {
+                ConsensusObserverDirectSend::BlockPayload(block_payload)
+            },
+        }
+    }
+}
+
+/// The writer for the block archive. The writer is registered as an
+/// observer event listener, so that every verified ordered block, block
+/// payload and commit decision is appended to the archive. The writer is cheaply
+/// cloneable, and all clones append to the same archive.
+#[derive(Clone)]
+pub struct BlockArchiveWriter {
+    inner: Arc<Mutex<BlockArchiveSegments>>,
+}
+
+impl BlockArchiveWriter {
+    /// Creates a new block archive writer in the given directory. If the
+    /// directory already contains segments, a new segment is started
+    /// (after the existing ones) so that the existing records are kept.
+    pub fn new(
+        archive_directory: PathBuf,
+        max_segment_size_bytes: u64,
+        max_num_segments: u64,
+    ) -> io::Result<Self> {
+        let block_archive_segments =
+            BlockArchiveSegments::new(archive_directory, max_segment_size_bytes, max_num_segments)?;
+        Ok(Self {
+            inner: Arc::new(Mutex::new(block_archive_segments)),
+        })
+    }
+
+    /// Appends the given record to the archive. Failures are logged
+    /// (but otherwise ignored) to avoid impacting the observer.
+    fn archive_record(&self, archived_record: ArchivedRecord) {
+        if let Err(error) = self.inner.lock().append_record(&archived_record) {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to append the record to the block archive! Error: {:?}",
+                    error
+                ))
+            );
+        }
+    }
+}
+
+impl ObserverEventListener for BlockArchiveWriter {
+    fn name(&self) -> &'static str {
+        "block_archive"
+    }
+
+    fn on_ordered_block(&self, ordered_block: &OrderedBlock) {
+        self.archive_record(ArchivedRecord::OrderedBlock(ordered_block.clone()));
+    }
+
+    fn on_block_payload(&self, block_payload: &BlockPayload) {
+        self.archive_record(ArchivedRecord::BlockPayload(block_payload.clone()));
+    }
+
+    fn on_commit_decision(&self, commit_decision: &CommitDecision) {
+        self.archive_record(ArchivedRecord::CommitDecision(commit_decision.clone()));
+    }
+}
+
+/// The archive segment files (i.e., the active segment and the older segments)
+struct BlockArchiveSegments {
+    // The directory containing the segment files
+    archive_directory: PathBuf,
+
+    // The maximum size of the active segment (before it is rotated)
+    max_segment_size_bytes: u64,
+
+    // The maximum number of segments to keep (including the active segment).
+    // If this is zero, all segments are kept (i.e., the archive is complete).
+    max_num_segments: u64,
+
+    // The index of the active segment (and its writer and current size)
+    active_segment_index: u64,
+    active_segment_writer: BufWriter<File>,
+    active_segment_size_bytes: u64,
+}
+
+impl BlockArchiveSegments {
+    fn new(
+        archive_directory: PathBuf,
+        max_segment_size_bytes: u64,
+        max_num_segments: u64,
+    ) -> io::Result<Self> {
+        // Create the archive directory and identify the next segment index
+        fs::create_dir_all(&archive_directory)?;
+        let active_segment_index = get_segment_indices(&archive_directory)?
+            .last()
+            .map(|segment_index| segment_index + 1)
+            .unwrap_or(0);
+
+        // Open the active segment file
+        let (active_segment_writer, active_segment_size_bytes) = open_segment_file(
+            &get_segment_file_path(&archive_directory, active_segment_index),
+        )?;
+
+        Ok(Self {
+            archive_directory,
+            max_segment_size_bytes,
+            max_num_segments,
+            active_segment_index,
+            active_segment_writer,
+            active_segment_size_bytes,
+        })
+    }
+
+    /// Appends the given record to the active segment (rotating if required)
+    fn append_record(&mut self, archived_record: &ArchivedRecord) -> io::Result<()> {
+        // Rotate the segments if the active segment is full
+        if self.active_segment_size_bytes >= self.max_segment_size_bytes {
+            self.rotate_segments()?;
+        }
+
+        // Frame and append the record (flushing to ensure the record is persisted)
+        let record_frame = create_record_frame(archived_record)?;
+        self.active_segment_writer.write_all(&record_frame)?;
+        self.active_segment_writer.flush()?;
+        self.active_segment_size_bytes += record_frame.len() as u64;
+
+        Ok(())
+    }
+
+    /// Rotates the segments (i.e., opens a new active segment, and
+    /// removes the oldest segments if there are too many).
+    fn rotate_segments(&mut self) -> io::Result<()> {
+        // Flush the active segment before rotating it
+        self.active_segment_writer.flush()?;
+
+        // Open a new active segment
+        self.active_segment_index += 1;
+        let (active_segment_writer, active_segment_size_bytes) = open_segment_file(
+            &get_segment_file_path(&self.archive_directory, self.active_segment_index),
+        )?;
+        self.active_segment_writer = active_segment_writer;
+        self.active_segment_size_bytes = active_segment_size_bytes;
+
+        // Remove the oldest segments (if there are too many)
+        if self.max_num_segments > 0 {
+            let segment_indices = get_segment_indices(&self.archive_directory)?;
+            let num_segments_to_remove =
+                (segment_indices.len() as u64).saturating_sub(self.max_num_segments);
+            for segment_index in segment_indices.iter().take(num_segments_to_remove as usize) {
+                fs::remove_file(get_segment_file_path(
+                    &self.archive_directory,
+                    *segment_index,
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A reader for the block archive. The reader iterates over all records in
+/// the archive (in the order they were appended), verifying the checksum of
+/// each record. A torn record at the end of a segment (e.g., caused by a
+/// crash while appending) ends the segment, but corrupted records are errors.
+pub struct BlockArchiveReader {
+    // The paths of the segment files that have not been read yet
+    remaining_segment_paths: Vec<PathBuf>,
+
+    // The reader for the current segment (if any)
+    segment_reader: Option<BufReader<File>>,
+}
+
+impl BlockArchiveReader {
+    /// Opens the block archive in the given directory
+    pub fn new(archive_directory: &Path) -> io::Result<Self> {
+        let remaining_segment_paths = get_segment_indices(archive_directory)?
+            .into_iter()
+            .rev() // Segments are popped from the end
+            .map(|segment_index| get_segment_file_path(archive_directory, segment_index))
+            .collect();
+        Ok(Self {
+            remaining_segment_paths,
+            segment_reader: None,
+        })
+    }
+
+    /// Reads the next record from the current segment. Returns None
+    /// if the segment has ended (or it ends with a torn record).
+    fn read_next_record(
+        segment_reader: &mut BufReader<File>,
+    ) -> io::Result<Option<ArchivedRecord>> {
+        // Read the frame header
+        let mut frame_header = [0u8; FRAME_HEADER_SIZE];
+        if !read_exact_or_eof(segment_reader, &mut frame_header)? {
+            return Ok(None);
+        }
+        let (length_bytes, checksum_bytes) = frame_header.split_at(size_of::<u32>());
+        let record_length = u32::from_le_bytes(length_bytes.try_into().unwrap()) as usize;
+        let checksum = HashValue::from_slice(checksum_bytes)
+            .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+
+        // Verify the record length (before allocating the record buffer)
+        if record_length > MAX_RECORD_SIZE_BYTES {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "The length of the archived record is too large! Length: {}, max: {}",
+                    record_length, MAX_RECORD_SIZE_BYTES
+                ),
+            ));
+        }
+
+        // Read the record bytes
+        let mut record_bytes = vec![0u8; record_length];
+        if !read_exact_or_eof(segment_reader, &mut record_bytes)? {
+            return Ok(None);
+        }
+
+        // Verify the checksum and deserialize the record
+        if HashValue::sha3_256_of(&record_bytes) != checksum {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "The checksum of the archived record does not match!",
+            ));
+        }
+        let archived_record = bcs::from_bytes(&record_bytes)
+            .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+
+        Ok(Some(archived_record))
+    }
+}
+
+impl Iterator for BlockArchiveReader {
+    type Item = io::Result<ArchivedRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // Open the next segment (if required)
+            if self.segment_reader.is_none() {
+                let segment_path = self.remaining_segment_paths.pop()?;
+                match File::open(segment_path) {
+                    Ok(file) => self.segment_reader = Some(BufReader::new(file)),
+                    Err(error) => return Some(Err(error)),
+                }
+            }
+
+            // Read the next record (moving to the next segment if this one has ended)
+            let segment_reader = self.segment_reader.as_mut()?;
+            match Self::read_next_record(segment_reader) {
+                Ok(Some(archived_record)) => return Some(Ok(archived_record)),
+                Ok(None) => self.segment_reader = None,
+                Err(error) => {
+                    self.segment_reader = None;
+                    return Some(Err(error));
+                },
+            }
+        }
+    }
+}
+
+/// Serializes the given record and frames it (i.e., prepends the length and checksum)
+fn create_record_frame(archived_record: &ArchivedRecord) -> io::Result<Vec<u8>> {
+    let record_bytes = bcs::to_bytes(archived_record)
+        .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+    if record_bytes.len() > MAX_RECORD_SIZE_BYTES {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "The archived record is too large! Size: {}, max: {}",
+                record_bytes.len(),
+                MAX_RECORD_SIZE_BYTES
+            ),
+        ));
+    }
+    let record_length = u32::try_from(record_bytes.len())
+        .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+
+    let mut record_frame = Vec::with_capacity(FRAME_HEADER_SIZE + record_bytes.len());
+    record_frame.extend_from_slice(&record_length.to_le_bytes());
+    record_frame.extend_from_slice(HashValue::sha3_256_of(&record_bytes).as_ref());
+    record_frame.extend_from_slice(&record_bytes);
+    Ok(record_frame)
+}
+
+/// Returns the path of the segment file with the given index
+fn get_segment_file_path(archive_directory: &Path, segment_index: u64) -> PathBuf {
+    archive_directory.join(format!(
+        "{}{:010}.{}",
+        SEGMENT_FILE_PREFIX, segment_index, SEGMENT_FILE_EXTENSION
+    ))
+}
+
+/// Returns the (sorted) indices of all segment files in the given directory
+fn get_segment_indices(archive_directory: &Path) -> io::Result<Vec<u64>> {
+    let mut segment_indices = vec![];
+    for directory_entry in fs::read_dir(archive_directory)? {
+        let file_name = directory_entry?.file_name();
+        let segment_index = file_name
+            .to_str()
+            .and_then(|file_name| file_name.strip_prefix(SEGMENT_FILE_PREFIX))
+            .and_then(|file_name| file_name.strip_suffix(&format!(".{}", SEGMENT_FILE_EXTENSION)))
+            .and_then(|segment_index| segment_index.parse::<u64>().ok());
+        if let Some(segment_index) = segment_index {
+            segment_indices.push(segment_index);
+        }
+    }
+    segment_indices.sort_unstable();
+    Ok(segment_indices)
+}
+
+/// Opens (or creates) the segment file at the given path in append
+/// mode. Returns the file writer and the current size of the file.
+fn open_segment_file(file_path: &Path) -> io::Result<(BufWriter<File>, u64)> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)?;
+    let file_size_bytes = file.metadata()?.len();
+    Ok((BufWriter::new(file), file_size_bytes))
+}
+
+/// Fills the given buffer from the reader. Returns false if the reader
+/// ended before the buffer was filled (i.e., the data was torn).
+fn read_exact_or_eof(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<bool> {
+    match reader.read_exact(buffer) {
+        Ok(()) => Ok(true),
+        Err(error) if error.kind() == ErrorKind::UnexpectedEof => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_consensus_types::{
+        block::Block,
+        block_data::{BlockData, BlockType},
+        pipelined_block::PipelinedBlock,
+        quorum_cert::QuorumCert,
+    };
+    use aptos_crypto::hash::CryptoHash;
+    use aptos_temppath::TempPath;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        block_info::BlockInfo,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+
+    #[test]
+    fn test_archive_and_read_records() {
+        // Create a block archive writer
+        let archive_directory = TempPath::new();
+        let block_archive_writer =
+            BlockArchiveWriter::new(archive_directory.path().to_path_buf(), 1_000_000, 0).unwrap();
+
+        // Archive several ordered blocks, payloads and commit decisions (via the listener callbacks)
+        let mut archived_records = vec![];
+        for round in 0..5 {
+            let ordered_block = create_ordered_block(round);
+            block_archive_writer.on_ordered_block(&ordered_block);
+            archived_records.push(ArchivedRecord::OrderedBlock(ordered_block.clone()));
+
+            let block_payload = BlockPayload {
+                block: ordered_block.proof_block_info().clone(),
+                transactions: vec![],
+                limit: None,
+            };
+            block_archive_writer.on_block_payload(&block_payload);
+            archived_records.push(ArchivedRecord::BlockPayload(block_payload));
+
+            let commit_decision = create_commit_decision(round);
+            block_archive_writer.on_commit_decision(&commit_decision);
+            archived_records.push(ArchivedRecord::CommitDecision(commit_decision));
+        }
+
+        // Verify that the archive contains all the records (in order)
+        let read_records: Vec<_> = BlockArchiveReader::new(archive_directory.path())
+            .unwrap()
+            .map(|record| record.unwrap())
+            .collect();
+        assert_eq!(read_records, archived_records);
+
+        // Recreate the writer and archive another record (in a new segment)
+        drop(block_archive_writer);
+        let block_archive_writer =
+            BlockArchiveWriter::new(archive_directory.path().to_path_buf(), 1_000_000, 0).unwrap();
+        let commit_decision = create_commit_decision(10);
+        block_archive_writer.on_commit_decision(&commit_decision);
+        archived_records.push(ArchivedRecord::CommitDecision(commit_decision));
+
+        // Verify that all records are read (across both segments)
+        assert_eq!(
+            get_segment_indices(archive_directory.path()).unwrap(),
+            vec![0, 1]
+        );
+        let read_records: Vec<_> = BlockArchiveReader::new(archive_directory.path())
+            .unwrap()
+            .map(|record| record.unwrap())
+            .collect();
+        assert_eq!(read_records, archived_records);
+    }
+
+    #[test]
+    fn test_segment_rotation() {
+        // Create a block archive writer with tiny segments
+        let archive_directory = TempPath::new();
+        let max_num_segments = 3;
+        let block_archive_writer = BlockArchiveWriter::new(
+            archive_directory.path().to_path_buf(),
+            1, // Rotate after every record
+            max_num_segments,
+        )
+        .unwrap();
+
+        // Archive many commit decisions (forcing several rotations)
+        let num_commits = 10;
+        for round in 0..num_commits {
+            block_archive_writer.on_commit_decision(&create_commit_decision(round));
+        }
+
+        // Verify that only the newest segments are kept
+        assert_eq!(
+            get_segment_indices(archive_directory.path()).unwrap(),
+            vec![7, 8, 9]
+        );
+
+        // Verify that the newest records are read (in order)
+        let read_rounds: Vec<_> = BlockArchiveReader::new(archive_directory.path())
+            .unwrap()
+            .map(|record| match record.unwrap() {
+                ArchivedRecord::CommitDecision(commit_decision) => commit_decision.round(),
+                record => panic!("Unexpected archived record: {:?}", record),
+            })
+            .collect();
+        assert_eq!(read_rounds, vec![7, 8, 9]);
+    }
+
+    #[test]
+    fn test_torn_and_corrupted_records() {
+        // Archive two commit decisions
+        let archive_directory = TempPath::new();
+        let block_archive_writer =
+            BlockArchiveWriter::new(archive_directory.path().to_path_buf(), 1_000_000, 0).unwrap();
+        for round in 0..2 {
+            block_archive_writer.on_commit_decision(&create_commit_decision(round));
+        }
+
+        // Truncate the segment (tearing the second record)
+        let segment_path = get_segment_file_path(archive_directory.path(), 0);
+        let segment_bytes = fs::read(&segment_path).unwrap();
+        fs::write(&segment_path, &segment_bytes[..segment_bytes.len() - 1]).unwrap();
+
+        // Verify that only the first record is read
+        let read_records: Vec<_> = BlockArchiveReader::new(archive_directory.path())
+            .unwrap()
+            .collect();
+        assert_eq!(read_records.len(), 1);
+        assert!(read_records[0].is_ok());
+
+        // Corrupt the first record and verify that the corruption is detected
+        let mut segment_bytes = fs::read(&segment_path).unwrap();
+        segment_bytes[FRAME_HEADER_SIZE] ^= 0xFF;
+        fs::write(&segment_path, &segment_bytes).unwrap();
+        let mut block_archive_reader = BlockArchiveReader::new(archive_directory.path()).unwrap();
+        let error = block_archive_reader.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_oversized_record_length() {
+        // Write a segment with a frame header that claims an oversized record
+        let archive_directory = TempPath::new();
+        fs::create_dir_all(archive_directory.path()).unwrap();
+        let oversized_length = (MAX_RECORD_SIZE_BYTES + 1) as u32;
+        let mut segment_bytes = oversized_length.to_le_bytes().to_vec();
+        segment_bytes.extend_from_slice(HashValue::random().as_ref());
+        segment_bytes.extend_from_slice(&[0u8; 16]);
+        let segment_path = get_segment_file_path(archive_directory.path(), 0);
+        fs::write(&segment_path, &segment_bytes).unwrap();
+
+        // Verify that the oversized length is rejected (without reading the record)
+        let mut block_archive_reader = BlockArchiveReader::new(archive_directory.path()).unwrap();
+        let error = block_archive_reader.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    /// Creates a commit decision (with an empty signature) for the given round
+    fn create_commit_decision(round: u64) -> CommitDecision {
+        CommitDecision::new(LedgerInfoWithSignatures::new(
+            LedgerInfo::new(BlockInfo::random_with_epoch(0, round), HashValue::random()),
+            AggregateSignature::empty(),
+        ))
+    }
+
+    /// Creates an ordered block (with a single block) for the given round
+    fn create_ordered_block(round: u64) -> OrderedBlock {
+        // Create a pipelined block (the block ID is the hash of the block
+        // data, so that the block is identical after deserialization).
+        let block_data =
+            BlockData::new_for_testing(0, round, round, QuorumCert::dummy(), BlockType::Genesis);
+        let block = Block::new_for_testing(block_data.hash(), block_data, None);
+        let block_info = block.gen_block_info(HashValue::zero(), 0, None);
+        let pipelined_block = Arc::new(PipelinedBlock::new_ordered(block));
+
+        // Create the ordered block
+        OrderedBlock::new(
+            vec![pipelined_block],
+            LedgerInfoWithSignatures::new(
+                LedgerInfo::new(block_info, HashValue::random()),
+                AggregateSignature::empty(),
+            ),
+        )
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::network_message::ConsensusObserverDirectSend;
+use aptos_config::network_id::PeerNetworkId;
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    Stream,
+};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A single consensus data message (i.e., an ordered block, commit
+/// decision or block payload) emitted by a block source, along with
+/// the peer that originally produced the message.
+pub struct BlockSourceMessage {
+    pub source_peer: PeerNetworkId,
+    pub message: ConsensusObserverDirectSend,
+}
+
+impl BlockSourceMessage {
+    pub fn new(source_peer: PeerNetworkId, message: ConsensusObserverDirectSend) -> Self {
+        Self {
+            source_peer,
+            message,
+        }
+    }
+}
+
+/// A source of consensus data for the observer, other than the network
+/// subscription (e.g., a recorded file, the pipeline of a colocated
+/// validator, or an RPC poller). Messages from block sources bypass the
+/// subscription checks, but are otherwise verified and processed in
+/// the same way as messages received from the subscription peer.
+pub trait ObserverBlockSource: Stream<Item = BlockSourceMessage> + Send + Unpin {
+    /// Returns a label for the block source (used for logging and metrics)
+    fn get_label(&self) -> &'static str;
+}
+
+/// A simple block source that emits the messages pushed into a channel.
+/// This is useful for driving the observer from in-process producers.
+pub struct ChannelBlockSource {
+    // The label of the block source
+    label: &'static str,
+
+    // The receiver for the messages pushed into the block source
+    message_receiver: UnboundedReceiver<BlockSourceMessage>,
+}
+
+impl ChannelBlockSource {
+    /// Creates a new channel block source with the given label.
+    /// Returns the block source and the sender used to push messages.
+    pub fn new(label: &'static str) -> (Self, UnboundedSender<BlockSourceMessage>) {
+        let (message_sender, message_receiver) = unbounded();
+        let channel_block_source = Self {
+            label,
+            message_receiver,
+        };
+        (channel_block_source, message_sender)
+    }
+}
+
+impl ObserverBlockSource for ChannelBlockSource {
+    fn get_label(&self) -> &'static str {
+        self.label
+    }
+}
+
+impl Stream for ChannelBlockSource {
+    type Item = BlockSourceMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.message_receiver).poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::network_message::ConsensusObserverMessage;
+    use aptos_config::network_id::NetworkId;
+    use aptos_types::{block_info::BlockInfo, PeerId};
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_channel_block_source() {
+        // Create a channel block source
+        let block_source_label = "test_block_source";
+        let (mut channel_block_source, message_sender) =
+            ChannelBlockSource::new(block_source_label);
+        assert_eq!(channel_block_source.get_label(), block_source_label);
+
+        // Push several block payload messages into the block source
+        let source_peer = PeerNetworkId::new(NetworkId::Validator, PeerId::random());
+        let num_messages = 10;
+        for _ in 0..num_messages {
+            let message = ConsensusObserverMessage::new_block_payload_message(
+                BlockInfo::empty(),
+                vec![],
+                None,
+            );
+            message_sender
+                .unbounded_send(BlockSourceMessage::new(source_peer, message))
+                .unwrap();
+        }
+
+        // Verify that the block source emits all the messages (in order)
+        for _ in 0..num_messages {
+            let block_source_message = channel_block_source.next().await.unwrap();
+            assert_eq!(block_source_message.source_peer, source_peer);
+            assert!(matches!(
+                block_source_message.message,
+                ConsensusObserverDirectSend::BlockPayload(_)
+            ));
+        }
+
+        // Drop the sender and verify that the block source terminates
+        drop(message_sender);
+        assert!(channel_block_source.next().await.is_none());
+    }
+}
@@ -0,0 +1,516 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::{
+        block_archive::BlockArchiveWriter,
+        block_source::ObserverBlockSource,
+        commit_journal::CommitJournalWriter,
+        data_exporter::ObserverDataExporter,
+        error::Error,
+        event_journal::ObserverEventJournal,
+        feature_flags::ObserverFeatureFlags,
+        network_client::ConsensusObserverClient,
+        network_message::ConsensusObserverMessage,
+        observer::ConsensusObserver,
+        payload_store_db::ObserverPayloadDb,
+        peer_selector::{
+            DistanceAndLatencyPeerSelector, LatencyBucketedPeerSelector, SubscriptionPeerSelector,
+        },
+        publisher::ConsensusPublisher,
+        state_snapshot::ObserverStateSnapshotter,
+        storage::ObserverStorageInterface,
+        sync_notifications::SyncNotificationSender,
+    },
+    pipeline::execution_client::TExecutionClient,
+};
+use aptos_config::config::ConsensusObserverConfig;
+use aptos_event_notifications::{
+    DbBackedOnChainConfig, ObservedCommitNotifier, ReconfigNotificationListener,
+    SyncedCommitNotificationListener,
+};
+use aptos_network::application::interface::NetworkClient;
+use aptos_time_service::TimeService;
+use std::sync::Arc;
+
+/// A builder for the consensus observer. The network client, storage,
+/// execution client and sync notification sender are required. All
+/// other components are optional, and default values are used if they
+/// are not provided. The combination of components is validated (against
+/// the observer config) when the observer is built.
+pub struct ObserverBuilder {
+    // The configuration of the consensus observer
+    consensus_observer_config: ConsensusObserverConfig,
+
+    // The required observer components
+    consensus_observer_client:
+        Option<Arc<ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>>>,
+    observer_storage: Option<Arc<dyn ObserverStorageInterface>>,
+    execution_client: Option<Arc<dyn TExecutionClient>>,
+    sync_notification_sender: Option<SyncNotificationSender>,
+
+    // The optional observer components
+    reconfig_events: Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    time_service: Option<TimeService>,
+    event_journal: Option<ObserverEventJournal>,
+    feature_flags: Option<ObserverFeatureFlags>,
+    peer_selector: Option<Arc<dyn SubscriptionPeerSelector>>,
+    block_sources: Vec<Box<dyn ObserverBlockSource>>,
+    data_exporter: Option<ObserverDataExporter>,
+    commit_journal: Option<CommitJournalWriter>,
+    block_archive: Option<BlockArchiveWriter>,
+    observed_commit_notifier: Option<ObservedCommitNotifier>,
+    synced_commit_listener: Option<SyncedCommitNotificationListener>,
+    state_snapshotter: Option<ObserverStateSnapshotter>,
+    payload_store_db: Option<Arc<ObserverPayloadDb>>,
+}
+
+impl ObserverBuilder {
+    pub fn new(consensus_observer_config: ConsensusObserverConfig) -> Self {
+        Self {
+            consensus_observer_config,
+            consensus_observer_client: None,
+            observer_storage: None,
+            execution_client: None,
+            sync_notification_sender: None,
+            reconfig_events: None,
+            consensus_publisher: None,
+            time_service: None,
+            event_journal: None,
+            feature_flags: None,
+            peer_selector: None,
+            block_sources: vec![],
+            data_exporter: None,
+            commit_journal: None,
+            block_archive: None,
+            observed_commit_notifier: None,
+            synced_commit_listener: None,
+            state_snapshotter: None,
+            payload_store_db: None,
+        }
+    }
+
+    /// Sets the block archive (optional). If provided, every ordered block
+    /// and commit decision verified by the observer is appended to the archive.
+    pub fn with_block_archive(mut self, block_archive: Option<BlockArchiveWriter>) -> Self {
+        self.block_archive = block_archive;
+        self
+    }
+
+    /// Adds a (non-network) block source to the observer (optional). Multiple
+    /// block sources can be added, in addition to the network subscription.
+    pub fn with_block_source(mut self, block_source: Box<dyn ObserverBlockSource>) -> Self {
+        self.block_sources.push(block_source);
+        self
+    }
+
+    /// Sets the commit journal (optional). If provided, every
+    /// commit applied by the observer is appended to the journal.
+    pub fn with_commit_journal(mut self, commit_journal: Option<CommitJournalWriter>) -> Self {
+        self.commit_journal = commit_journal;
+        self
+    }
+
+    /// Sets the consensus observer client (required)
+    pub fn with_consensus_observer_client(
+        mut self,
+        consensus_observer_client: Arc<
+            ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>,
+        >,
+    ) -> Self {
+        self.consensus_observer_client = Some(consensus_observer_client);
+        self
+    }
+
+    /// Sets the consensus publisher (optional). The publisher must
+    /// be provided iff the publisher is enabled in the config.
+    pub fn with_consensus_publisher(
+        mut self,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    ) -> Self {
+        self.consensus_publisher = consensus_publisher;
+        self
+    }
+
+    /// Sets the data exporter (optional). If provided, all observed
+    /// (and verified) data is exported to the exporter's listeners.
+    pub fn with_data_exporter(mut self, data_exporter: Option<ObserverDataExporter>) -> Self {
+        self.data_exporter = data_exporter;
+        self
+    }
+
+    /// Sets the event journal (optional). If not provided, a new
+    /// journal is created using the config and time service.
+    pub fn with_event_journal(mut self, event_journal: ObserverEventJournal) -> Self {
+        self.event_journal = Some(event_journal);
+        self
+    }
+
+    /// Sets the execution client (required)
+    pub fn with_execution_client(mut self, execution_client: Arc<dyn TExecutionClient>) -> Self {
+        self.execution_client = Some(execution_client);
+        self
+    }
+
+    /// Sets the runtime feature flags (optional). If not provided,
+    /// the feature flags are initialized from the observer config.
+    pub fn with_feature_flags(mut self, feature_flags: ObserverFeatureFlags) -> Self {
+        self.feature_flags = Some(feature_flags);
+        self
+    }
+
+    /// Sets the observed commit notifier (optional). If provided, every commit
+    /// applied by the observer is published on the event notification service.
+    pub fn with_observed_commit_notifier(
+        mut self,
+        observed_commit_notifier: ObservedCommitNotifier,
+    ) -> Self {
+        self.observed_commit_notifier = Some(observed_commit_notifier);
+        self
+    }
+
+    /// Sets the observer storage (required)
+    pub fn with_observer_storage(
+        mut self,
+        observer_storage: Arc<dyn ObserverStorageInterface>,
+    ) -> Self {
+        self.observer_storage = Some(observer_storage);
+        self
+    }
+
+    /// Sets the persistent storage for the block payloads (optional). If provided,
+    /// the buffered payloads are persisted, and restored after a node restart.
+    pub fn with_payload_store_db(
+        mut self,
+        payload_store_db: Option<Arc<ObserverPayloadDb>>,
+    ) -> Self {
+        self.payload_store_db = payload_store_db;
+        self
+    }
+
+    /// Sets the subscription peer selector (optional). If not provided,
+    /// peers are prioritized by validator distance and latency.
+    pub fn with_peer_selector(mut self, peer_selector: Arc<dyn SubscriptionPeerSelector>) -> Self {
+        self.peer_selector = Some(peer_selector);
+        self
+    }
+
+    /// Sets the reconfiguration event listener (optional). The
+    /// listener must be provided if the observer is enabled.
+    pub fn with_reconfig_events(
+        mut self,
+        reconfig_events: Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
+    ) -> Self {
+        self.reconfig_events = reconfig_events;
+        self
+    }
+
+    /// Sets the state snapshotter (optional). If provided, the observer
+    /// periodically persists snapshots of its state, and uses the last
+    /// snapshot to resume observation with warm state after a restart.
+    pub fn with_state_snapshotter(
+        mut self,
+        state_snapshotter: Option<ObserverStateSnapshotter>,
+    ) -> Self {
+        self.state_snapshotter = state_snapshotter;
+        self
+    }
+
+    /// Sets the synced commit listener (optional). If provided, the observer
+    /// prunes stale pending blocks and payloads as soon as storage is synced
+    /// past them (e.g., by state sync), instead of on the next commit.
+    pub fn with_synced_commit_listener(
+        mut self,
+        synced_commit_listener: Option<SyncedCommitNotificationListener>,
+    ) -> Self {
+        self.synced_commit_listener = synced_commit_listener;
+        self
+    }
+
+    /// Sets the sync notification sender (required)
+    pub fn with_sync_notification_sender(
+        mut self,
+        sync_notification_sender: SyncNotificationSender,
+    ) -> Self {
+        self.sync_notification_sender = Some(sync_notification_sender);
+        self
+    }
+
+    /// Sets the time service (optional). If not provided, the real time service is used.
+    pub fn with_time_service(mut self, time_service: TimeService) -> Self {
+        self.time_service = Some(time_service);
+        self
+    }
+
+    /// Validates the combination of components and builds the consensus observer
+    pub fn build(self) -> Result<ConsensusObserver, Error> {
+        // Verify that all required components were provided
+        let consensus_observer_client = self
+            .consensus_observer_client
+            .ok_or_else(|| missing_component_error("consensus observer client"))?;
+        let observer_storage = self
+            .observer_storage
+            .ok_or_else(|| missing_component_error("observer storage"))?;
+        let execution_client = self
+            .execution_client
+            .ok_or_else(|| missing_component_error("execution client"))?;
+        let sync_notification_sender = self
+            .sync_notification_sender
+            .ok_or_else(|| missing_component_error("sync notification sender"))?;
+
+        // Verify that the publisher is provided iff the publisher is enabled
+        let publisher_enabled = self.consensus_observer_config.publisher_enabled;
+        if publisher_enabled && self.consensus_publisher.is_none() {
+            return Err(Error::ObserverBuildError(
+                "The publisher is enabled, but no consensus publisher was provided!".into(),
+            ));
+        }
+        if !publisher_enabled && self.consensus_publisher.is_some() {
+            return Err(Error::ObserverBuildError(
+                "A consensus publisher was provided, but the publisher is disabled!".into(),
+            ));
+        }
+
+        // Verify that the observer is enabled (publisher only
+        // nodes should use the `PublisherOnlyRuntime` instead).
+        if !self.consensus_observer_config.observer_enabled && publisher_enabled {
+            return Err(Error::ObserverBuildError(
+                "The observer is disabled! Use the publisher only runtime instead!".into(),
+            ));
+        }
+
+        // Verify that the reconfig listener is provided if the observer is enabled
+        if self.consensus_observer_config.observer_enabled && self.reconfig_events.is_none() {
+            return Err(Error::ObserverBuildError(
+                "The observer is enabled, but no reconfig event listener was provided!".into(),
+            ));
+        }
+
+        // Use the default values for any missing optional components
+        let time_service = self.time_service.unwrap_or_else(TimeService::real);
+        let event_journal = self.event_journal.unwrap_or_else(|| {
+            ObserverEventJournal::new(
+                self.consensus_observer_config.max_num_journal_events,
+                time_service.clone(),
+            )
+        });
+        let peer_selector = self.peer_selector.unwrap_or_else(|| {
+            if self
+                .consensus_observer_config
+                .enable_latency_bucketed_peer_selection
+            {
+                Arc::new(LatencyBucketedPeerSelector::new(
+                    self.consensus_observer_config.peer_latency_bucket_width_ms,
+                ))
+            } else {
+                Arc::new(DistanceAndLatencyPeerSelector)
+            }
+        });
+        let feature_flags = self
+            .feature_flags
+            .unwrap_or_else(|| ObserverFeatureFlags::new(&self.consensus_observer_config));
+
+        // Build the consensus observer
+        let mut consensus_observer = ConsensusObserver::new(
+            self.consensus_observer_config,
+            consensus_observer_client,
+            observer_storage,
+            execution_client,
+            sync_notification_sender,
+            self.reconfig_events,
+            self.consensus_publisher,
+            time_service,
+            event_journal,
+            peer_selector,
+            feature_flags,
+        );
+
+        // Add the remaining optional components to the observer
+        for block_source in self.block_sources {
+            consensus_observer.add_block_source(block_source);
+        }
+        if let Some(data_exporter) = self.data_exporter {
+            consensus_observer.set_data_exporter(data_exporter);
+        }
+        if let Some(commit_journal) = self.commit_journal {
+            consensus_observer.set_commit_journal(commit_journal);
+        }
+        if let Some(block_archive) = self.block_archive {
+            consensus_observer.register_listener(Box::new(block_archive));
+        }
+        if let Some(observed_commit_notifier) = self.observed_commit_notifier {
+            consensus_observer.set_observed_commit_notifier(observed_commit_notifier);
+        }
+        if let Some(synced_commit_listener) = self.synced_commit_listener {
+            consensus_observer.set_synced_commit_listener(synced_commit_listener);
+        }
+        if let Some(state_snapshotter) = self.state_snapshotter {
+            consensus_observer.set_state_snapshotter(state_snapshotter);
+        }
+        if let Some(payload_store_db) = self.payload_store_db {
+            consensus_observer.set_payload_store_db(payload_store_db);
+        }
+
+        Ok(consensus_observer)
+    }
+}
+
+/// Returns an error for the given missing (required) component
+fn missing_component_error(component_name: &str) -> Error {
+    Error::ObserverBuildError(format!(
+        "The {} is required, but was not provided!",
+        component_name
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        consensus_observer::{
+            storage::InMemoryObserverStorage, sync_notifications::new_sync_notification_channel,
+        },
+        pipeline::execution_client::DummyExecutionClient,
+    };
+    use aptos_channels::{aptos_channel, message_queues::QueueStyle};
+    use aptos_config::network_id::NetworkId;
+    use aptos_crypto::HashValue;
+    use aptos_network::application::storage::PeersAndMetadata;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        block_info::BlockInfo,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+    use maplit::hashmap;
+
+    #[test]
+    fn test_build_missing_required_components() {
+        // Verify that building without any components fails
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let result = ObserverBuilder::new(consensus_observer_config.clone()).build();
+        assert!(matches!(result, Err(Error::ObserverBuildError(_))));
+
+        // Verify that building without the sync notification sender fails
+        let result = create_observer_builder(consensus_observer_config.clone()).build();
+        assert!(matches!(result, Err(Error::ObserverBuildError(_))));
+
+        // Verify that building with all required components succeeds
+        let (sync_notification_sender, _) = new_sync_notification_channel();
+        let result = create_observer_builder(consensus_observer_config)
+            .with_sync_notification_sender(sync_notification_sender)
+            .build();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_build_publisher_mismatch() {
+        // Create a consensus publisher
+        let network_client = NetworkClient::new(
+            vec![],
+            vec![],
+            hashmap![],
+            PeersAndMetadata::new(&[NetworkId::Public]),
+        );
+        let (consensus_publisher, _) =
+            ConsensusPublisher::new(network_client, ConsensusObserverConfig::default());
+        let consensus_publisher = Arc::new(consensus_publisher);
+
+        // Verify that building with a publisher (when the publisher is disabled) fails
+        let (sync_notification_sender, _) = new_sync_notification_channel();
+        let result = create_observer_builder(ConsensusObserverConfig::default())
+            .with_sync_notification_sender(sync_notification_sender.clone())
+            .with_consensus_publisher(Some(consensus_publisher.clone()))
+            .build();
+        assert!(matches!(result, Err(Error::ObserverBuildError(_))));
+
+        // Verify that building without a publisher (when the publisher is enabled) fails
+        let consensus_observer_config = ConsensusObserverConfig {
+            observer_enabled: true,
+            publisher_enabled: true,
+            ..ConsensusObserverConfig::default()
+        };
+        let result = create_observer_builder(consensus_observer_config.clone())
+            .with_sync_notification_sender(sync_notification_sender.clone())
+            .with_reconfig_events(Some(create_reconfig_events()))
+            .build();
+        assert!(matches!(result, Err(Error::ObserverBuildError(_))));
+
+        // Verify that building a publisher only observer fails
+        let publisher_only_config = ConsensusObserverConfig {
+            observer_enabled: false,
+            ..consensus_observer_config.clone()
+        };
+        let result = create_observer_builder(publisher_only_config)
+            .with_sync_notification_sender(sync_notification_sender.clone())
+            .with_consensus_publisher(Some(consensus_publisher.clone()))
+            .build();
+        assert!(matches!(result, Err(Error::ObserverBuildError(_))));
+
+        // Verify that building with a publisher (when the publisher is enabled) succeeds
+        let result = create_observer_builder(consensus_observer_config)
+            .with_sync_notification_sender(sync_notification_sender)
+            .with_reconfig_events(Some(create_reconfig_events()))
+            .with_consensus_publisher(Some(consensus_publisher))
+            .build();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_build_missing_reconfig_events() {
+        // Verify that building without reconfig events (when the observer is enabled) fails
+        let consensus_observer_config = ConsensusObserverConfig {
+            observer_enabled: true,
+            ..ConsensusObserverConfig::default()
+        };
+        let (sync_notification_sender, _) = new_sync_notification_channel();
+        let result = create_observer_builder(consensus_observer_config.clone())
+            .with_sync_notification_sender(sync_notification_sender.clone())
+            .build();
+        assert!(matches!(result, Err(Error::ObserverBuildError(_))));
+
+        // Verify that building with reconfig events (when the observer is enabled) succeeds
+        let result = create_observer_builder(consensus_observer_config)
+            .with_sync_notification_sender(sync_notification_sender)
+            .with_reconfig_events(Some(create_reconfig_events()))
+            .with_time_service(TimeService::mock())
+            .build();
+        assert!(result.is_ok());
+    }
+
+    /// Creates an observer builder with all required
+    /// components (except the sync notification sender).
+    fn create_observer_builder(
+        consensus_observer_config: ConsensusObserverConfig,
+    ) -> ObserverBuilder {
+        // Create the consensus observer client
+        let network_client = NetworkClient::new(
+            vec![],
+            vec![],
+            hashmap![],
+            PeersAndMetadata::new(&[NetworkId::Public]),
+        );
+        let consensus_observer_client = Arc::new(ConsensusObserverClient::new(network_client));
+
+        // Create the observer storage
+        let observer_storage =
+            Arc::new(InMemoryObserverStorage::new(LedgerInfoWithSignatures::new(
+                LedgerInfo::new(BlockInfo::empty(), HashValue::zero()),
+                AggregateSignature::empty(),
+            )));
+
+        // Create the observer builder
+        ObserverBuilder::new(consensus_observer_config)
+            .with_consensus_observer_client(consensus_observer_client)
+            .with_observer_storage(observer_storage)
+            .with_execution_client(Arc::new(DummyExecutionClient))
+    }
+
+    /// Creates a reconfig event listener (the sender is dropped immediately)
+    fn create_reconfig_events() -> ReconfigNotificationListener<DbBackedOnChainConfig> {
+        let (_, reconfig_receiver) = aptos_channel::new(QueueStyle::KLAST, 1, None);
+        ReconfigNotificationListener {
+            notification_receiver: reconfig_receiver,
+        }
+    }
+}
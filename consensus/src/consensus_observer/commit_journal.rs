@@ -0,0 +1,266 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::logging::{LogEntry, LogSchema};
+use aptos_infallible::{duration_since_epoch, Mutex};
+use aptos_logger::warn;
+use aptos_types::block_info::BlockInfo;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+// The name of the active commit journal file
+const COMMIT_JOURNAL_FILE_NAME: &str = "commit_journal.log";
+
+/// A single entry in the commit journal (one per applied commit)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CommitJournalEntry {
+    pub epoch: u64,
+    pub round: u64,
+    pub block_id: String,
+    pub version: u64,
+    pub timestamp_usecs: u64,
+    pub applied_at_usecs: u64,
+}
+
+impl CommitJournalEntry {
+    pub fn new(commit_info: &BlockInfo, applied_at_usecs: u64) -> Self {
+        Self {
+            epoch: commit_info.epoch(),
+            round: commit_info.round(),
+            block_id: commit_info.id().to_hex(),
+            version: commit_info.version(),
+            timestamp_usecs: commit_info.timestamp_usecs(),
+            applied_at_usecs,
+        }
+    }
+}
+
+/// The writer for the commit journal: an audit trail of every commit applied
+/// by the consensus observer. Entries are appended (as JSON lines) to the
+/// active journal file, which is rotated once it exceeds the maximum size.
+/// Only the most recent journal files are kept. The writer is cheaply
+/// cloneable, and all clones append to the same journal.
+#[derive(Clone)]
+pub struct CommitJournalWriter {
+    inner: Arc<Mutex<CommitJournalFiles>>,
+}
+
+impl CommitJournalWriter {
+    /// Creates a new commit journal writer in the given directory
+    pub fn new(
+        journal_directory: PathBuf,
+        max_journal_file_size_bytes: u64,
+        max_num_journal_files: u64,
+    ) -> io::Result<Self> {
+        let commit_journal_files = CommitJournalFiles::new(
+            journal_directory,
+            max_journal_file_size_bytes,
+            max_num_journal_files,
+        )?;
+        Ok(Self {
+            inner: Arc::new(Mutex::new(commit_journal_files)),
+        })
+    }
+
+    /// Appends an entry for the given commit to the journal. Failures are
+    /// logged (but otherwise ignored) to avoid impacting the observer.
+    pub fn record_commit(&self, commit_info: &BlockInfo) {
+        let applied_at_usecs = duration_since_epoch().as_micros() as u64;
+        let commit_journal_entry = CommitJournalEntry::new(commit_info, applied_at_usecs);
+        if let Err(error) = self.inner.lock().append_entry(&commit_journal_entry) {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to append the commit to the commit journal: {:?}! Error: {:?}",
+                    commit_journal_entry, error
+                ))
+            );
+        }
+    }
+}
+
+/// The commit journal files (i.e., the active file and the rotated files)
+struct CommitJournalFiles {
+    // The directory containing the journal files
+    journal_directory: PathBuf,
+
+    // The maximum size of the active journal file (before it is rotated)
+    max_journal_file_size_bytes: u64,
+
+    // The maximum number of journal files to keep (including the active file)
+    max_num_journal_files: u64,
+
+    // The writer for the active journal file (and its current size)
+    active_file_writer: BufWriter<File>,
+    active_file_size_bytes: u64,
+}
+
+impl CommitJournalFiles {
+    fn new(
+        journal_directory: PathBuf,
+        max_journal_file_size_bytes: u64,
+        max_num_journal_files: u64,
+    ) -> io::Result<Self> {
+        // Create the journal directory and open the active journal file
+        fs::create_dir_all(&journal_directory)?;
+        let (active_file_writer, active_file_size_bytes) =
+            open_journal_file(&journal_directory.join(COMMIT_JOURNAL_FILE_NAME))?;
+
+        Ok(Self {
+            journal_directory,
+            max_journal_file_size_bytes,
+            max_num_journal_files,
+            active_file_writer,
+            active_file_size_bytes,
+        })
+    }
+
+    /// Appends the given entry to the active journal file (rotating if required)
+    fn append_entry(&mut self, commit_journal_entry: &CommitJournalEntry) -> io::Result<()> {
+        // Rotate the journal files if the active file is full
+        if self.active_file_size_bytes >= self.max_journal_file_size_bytes {
+            self.rotate_journal_files()?;
+        }
+
+        // Serialize and append the entry (flushing to ensure the entry is persisted)
+        let mut serialized_entry = serde_json::to_vec(commit_journal_entry)?;
+        serialized_entry.push(b'\n');
+        self.active_file_writer.write_all(&serialized_entry)?;
+        self.active_file_writer.flush()?;
+        self.active_file_size_bytes += serialized_entry.len() as u64;
+
+        Ok(())
+    }
+
+    /// Returns the path of the journal file with the given index
+    /// (index 0 is the active file, and higher indices are older files).
+    fn get_journal_file_path(&self, file_index: u64) -> PathBuf {
+        if file_index == 0 {
+            self.journal_directory.join(COMMIT_JOURNAL_FILE_NAME)
+        } else {
+            self.journal_directory
+                .join(format!("{}.{}", COMMIT_JOURNAL_FILE_NAME, file_index))
+        }
+    }
+
+    /// Rotates the journal files (i.e., shifts each file to the next index,
+    /// removes the oldest file and opens a new active journal file).
+    fn rotate_journal_files(&mut self) -> io::Result<()> {
+        // Flush the active file before rotating it
+        self.active_file_writer.flush()?;
+
+        // Shift all existing files (the oldest file is overwritten)
+        for file_index in (0..self.max_num_journal_files.saturating_sub(1)).rev() {
+            let file_path = self.get_journal_file_path(file_index);
+            if file_path.exists() {
+                fs::rename(file_path, self.get_journal_file_path(file_index + 1))?;
+            }
+        }
+
+        // If only a single file is kept, remove the active file
+        let active_file_path = self.get_journal_file_path(0);
+        if active_file_path.exists() {
+            fs::remove_file(&active_file_path)?;
+        }
+
+        // Open a new active journal file
+        let (active_file_writer, active_file_size_bytes) = open_journal_file(&active_file_path)?;
+        self.active_file_writer = active_file_writer;
+        self.active_file_size_bytes = active_file_size_bytes;
+
+        Ok(())
+    }
+}
+
+/// Opens (or creates) the journal file at the given path in append
+/// mode. Returns the file writer and the current size of the file.
+fn open_journal_file(file_path: &Path) -> io::Result<(BufWriter<File>, u64)> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)?;
+    let file_size_bytes = file.metadata()?.len();
+    Ok((BufWriter::new(file), file_size_bytes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_temppath::TempPath;
+
+    #[test]
+    fn test_record_commits() {
+        // Create a commit journal writer
+        let journal_directory = TempPath::new();
+        let commit_journal_writer =
+            CommitJournalWriter::new(journal_directory.path().to_path_buf(), 1_000_000, 3).unwrap();
+
+        // Record several commits
+        let num_commits = 10;
+        let commit_infos: Vec<_> = (0..num_commits)
+            .map(|round| BlockInfo::random_with_epoch(5, round))
+            .collect();
+        for commit_info in &commit_infos {
+            commit_journal_writer.record_commit(commit_info);
+        }
+
+        // Verify that the journal contains all the commits (in order)
+        let journal_entries =
+            read_journal_entries(&journal_directory.path().join(COMMIT_JOURNAL_FILE_NAME));
+        assert_eq!(journal_entries.len(), num_commits as usize);
+        for (journal_entry, commit_info) in journal_entries.iter().zip(commit_infos.iter()) {
+            assert_eq!(journal_entry.epoch, commit_info.epoch());
+            assert_eq!(journal_entry.round, commit_info.round());
+            assert_eq!(journal_entry.block_id, commit_info.id().to_hex());
+            assert_eq!(journal_entry.version, commit_info.version());
+        }
+    }
+
+    #[test]
+    fn test_journal_rotation() {
+        // Create a commit journal writer with tiny journal files
+        let journal_directory = TempPath::new();
+        let max_num_journal_files = 3;
+        let commit_journal_writer = CommitJournalWriter::new(
+            journal_directory.path().to_path_buf(),
+            1, // Rotate after every entry
+            max_num_journal_files,
+        )
+        .unwrap();
+
+        // Record many commits (forcing several rotations)
+        let num_commits = 10;
+        for round in 0..num_commits {
+            commit_journal_writer.record_commit(&BlockInfo::random_with_epoch(0, round));
+        }
+
+        // Verify that only the maximum number of journal files exist
+        let num_journal_files = fs::read_dir(journal_directory.path()).unwrap().count();
+        assert_eq!(num_journal_files, max_num_journal_files as usize);
+
+        // Verify that each file contains a single entry (with the newest entries kept)
+        for file_index in 0..max_num_journal_files {
+            let file_name = if file_index == 0 {
+                COMMIT_JOURNAL_FILE_NAME.to_string()
+            } else {
+                format!("{}.{}", COMMIT_JOURNAL_FILE_NAME, file_index)
+            };
+            let journal_entries = read_journal_entries(&journal_directory.path().join(file_name));
+            assert_eq!(journal_entries.len(), 1);
+            assert_eq!(journal_entries[0].round, num_commits - 1 - file_index);
+        }
+    }
+
+    /// Reads and deserializes all entries in the given journal file
+    fn read_journal_entries(file_path: &Path) -> Vec<CommitJournalEntry> {
+        fs::read_to_string(file_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+}
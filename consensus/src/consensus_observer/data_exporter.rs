@@ -0,0 +1,173 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::network_message::{BlockPayload, CommitDecision, OrderedBlock};
+use aptos_types::{
+    block_info::BlockInfo, ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction,
+};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// A single item of data observed (and verified) by the consensus observer.
+/// The proofs and transactions are shared, so that the data can be cheaply
+/// cloned for each listener (even if the listener doesn't require them).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ObservedData {
+    OrderedBlock {
+        blocks: Vec<BlockInfo>,
+        proof_block_info: BlockInfo,
+        ordered_proof: Arc<LedgerInfoWithSignatures>,
+    },
+    BlockPayload {
+        block: BlockInfo,
+        num_transactions: u64,
+        transaction_limit: Option<u64>,
+        transactions: Arc<Vec<SignedTransaction>>,
+    },
+    CommitDecision {
+        proof_block_info: BlockInfo,
+        commit_proof: Arc<LedgerInfoWithSignatures>,
+    },
+}
+
+/// Exports the data observed by the consensus observer to any number of
+/// listeners (e.g., the gRPC export service). The exporter is cheaply
+/// cloneable, and all clones share the same listeners. If a listener
+/// falls too far behind, the oldest data is dropped for that listener.
+#[derive(Clone)]
+pub struct ObserverDataExporter {
+    observed_data_sender: broadcast::Sender<ObservedData>,
+}
+
+impl ObserverDataExporter {
+    pub fn new(max_buffer_size: usize) -> Self {
+        let (observed_data_sender, _) = broadcast::channel(max_buffer_size);
+        Self {
+            observed_data_sender,
+        }
+    }
+
+    /// Exports the given block payload (if there are any listeners)
+    pub fn export_block_payload(&self, block_payload: &BlockPayload) {
+        if self.has_listeners() {
+            self.export_data(ObservedData::BlockPayload {
+                block: block_payload.block.clone(),
+                num_transactions: block_payload.transactions.len() as u64,
+                transaction_limit: block_payload.limit,
+                transactions: Arc::new(block_payload.transactions.clone()),
+            });
+        }
+    }
+
+    /// Exports the given commit decision (if there are any listeners)
+    pub fn export_commit_decision(&self, commit_decision: &CommitDecision) {
+        if self.has_listeners() {
+            self.export_data(ObservedData::CommitDecision {
+                proof_block_info: commit_decision.proof_block_info().clone(),
+                commit_proof: commit_decision.shared_commit_proof(),
+            });
+        }
+    }
+
+    /// Exports the given ordered block (if there are any listeners)
+    pub fn export_ordered_block(&self, ordered_block: &OrderedBlock) {
+        if self.has_listeners() {
+            self.export_data(ObservedData::OrderedBlock {
+                blocks: ordered_block
+                    .blocks()
+                    .iter()
+                    .map(|block| block.block_info())
+                    .collect(),
+                proof_block_info: ordered_block.proof_block_info().clone(),
+                ordered_proof: Arc::new(ordered_block.ordered_proof().clone()),
+            });
+        }
+    }
+
+    /// Returns a new receiver for all data exported from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<ObservedData> {
+        self.observed_data_sender.subscribe()
+    }
+
+    /// Sends the observed data to all listeners
+    fn export_data(&self, observed_data: ObservedData) {
+        // Note: sending only fails if there are no listeners (which is fine)
+        let _ = self.observed_data_sender.send(observed_data);
+    }
+
+    /// Returns true iff there are any active listeners
+    fn has_listeners(&self) -> bool {
+        self.observed_data_sender.receiver_count() > 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    #[test]
+    fn test_export_observed_data() {
+        // Create a data exporter and subscribe to it
+        let data_exporter = ObserverDataExporter::new(10);
+        let mut observed_data_receiver = data_exporter.subscribe();
+
+        // Export a block payload and verify that it is received
+        let block_info = BlockInfo::random_with_epoch(0, 10);
+        let block_payload = BlockPayload {
+            block: block_info.clone(),
+            transactions: vec![],
+            limit: Some(5),
+        };
+        data_exporter.export_block_payload(&block_payload);
+        assert_eq!(
+            observed_data_receiver.try_recv().unwrap(),
+            ObservedData::BlockPayload {
+                block: block_info.clone(),
+                num_transactions: 0,
+                transaction_limit: Some(5),
+                transactions: Arc::new(vec![]),
+            }
+        );
+
+        // Export a commit decision and verify that it is received
+        let ledger_info = LedgerInfo::new(block_info.clone(), HashValue::random());
+        let commit_proof = LedgerInfoWithSignatures::new(ledger_info, AggregateSignature::empty());
+        let commit_decision = CommitDecision::new(commit_proof.clone());
+        data_exporter.export_commit_decision(&commit_decision);
+        assert_eq!(
+            observed_data_receiver.try_recv().unwrap(),
+            ObservedData::CommitDecision {
+                proof_block_info: block_info,
+                commit_proof: Arc::new(commit_proof),
+            }
+        );
+
+        // Verify that no other data was received
+        assert_eq!(observed_data_receiver.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    #[test]
+    fn test_export_without_listeners() {
+        // Create a data exporter (without any listeners)
+        let data_exporter = ObserverDataExporter::new(10);
+
+        // Export a commit decision (this should be a no-op)
+        let block_info = BlockInfo::random_with_epoch(0, 10);
+        let ledger_info = LedgerInfo::new(block_info, HashValue::random());
+        let commit_decision = CommitDecision::new(LedgerInfoWithSignatures::new(
+            ledger_info,
+            AggregateSignature::empty(),
+        ));
+        data_exporter.export_commit_decision(&commit_decision);
+
+        // Subscribe to the exporter and verify that no data is received
+        let mut observed_data_receiver = data_exporter.subscribe();
+        assert_eq!(observed_data_receiver.try_recv(), Err(TryRecvError::Empty));
+    }
+}
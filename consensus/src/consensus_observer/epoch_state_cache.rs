@@ -0,0 +1,229 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    logging::{LogEntry, LogSchema},
+    storage::ObserverStorageInterface,
+};
+use aptos_logger::warn;
+use aptos_types::{epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// A small cache of the most recent epoch states (i.e., validator verifiers).
+/// The cache is populated from reconfiguration events (as each epoch starts)
+/// and from epoch-ending ledger infos read from storage. This allows the
+/// observer to verify messages from previous epochs during epoch transitions
+/// (instead of treating them as unverifiable).
+pub struct EpochStateCache {
+    // The maximum number of epoch states to cache
+    max_num_epoch_states: usize,
+
+    // The cached epoch states (indexed by epoch)
+    epoch_states: BTreeMap<u64, Arc<EpochState>>,
+
+    // A handle to storage (used to derive the epoch states missing from the cache)
+    observer_storage: Arc<dyn ObserverStorageInterface>,
+}
+
+impl EpochStateCache {
+    pub fn new(
+        max_num_epoch_states: u64,
+        observer_storage: Arc<dyn ObserverStorageInterface>,
+    ) -> Self {
+        Self {
+            max_num_epoch_states: max_num_epoch_states as usize,
+            epoch_states: BTreeMap::new(),
+            observer_storage,
+        }
+    }
+
+    /// Returns the epoch state for the given epoch. If the epoch state is not
+    /// cached, it is derived from the ending ledger info of the previous epoch
+    /// (read from storage) and cached. If neither exist, None is returned.
+    pub fn get_epoch_state(&mut self, epoch: u64) -> Option<Arc<EpochState>> {
+        // Check if the epoch state is already cached
+        if let Some(epoch_state) = self.epoch_states.get(&epoch) {
+            return Some(epoch_state.clone());
+        }
+
+        // Otherwise, derive the epoch state from storage (the genesis epoch has no previous epoch)
+        let previous_epoch = epoch.checked_sub(1)?;
+        let epoch_state = match self
+            .observer_storage
+            .get_epoch_ending_ledger_info(previous_epoch)
+        {
+            Ok(ledger_info) => ledger_info.ledger_info().next_epoch_state().cloned(),
+            Err(error) => {
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to read the epoch state for epoch {} from storage! Error: {:?}",
+                        epoch, error
+                    ))
+                );
+                return None;
+            },
+        };
+
+        // Verify the derived epoch state is for the expected epoch and cache it
+        let epoch_state = Arc::new(epoch_state.filter(|epoch_state| epoch_state.epoch == epoch)?);
+        self.insert_epoch_state(epoch_state.clone());
+        Some(epoch_state)
+    }
+
+    /// Inserts the given epoch state into the cache. If the cache
+    /// is full, the epoch states of the oldest epochs are evicted.
+    pub fn insert_epoch_state(&mut self, epoch_state: Arc<EpochState>) {
+        // Insert the epoch state
+        self.epoch_states.insert(epoch_state.epoch, epoch_state);
+
+        // Evict the oldest epoch states (if the cache is full)
+        while self.epoch_states.len() > self.max_num_epoch_states {
+            self.epoch_states.pop_first();
+        }
+    }
+
+    /// Inserts the next epoch state of the given ledger info into the
+    /// cache. If the ledger info doesn't end an epoch, nothing is done.
+    pub fn insert_epoch_ending_ledger_info(&mut self, ledger_info: &LedgerInfoWithSignatures) {
+        if let Some(next_epoch_state) = ledger_info.ledger_info().next_epoch_state() {
+            self.insert_epoch_state(Arc::new(next_epoch_state.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::storage::InMemoryObserverStorage;
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature, block_info::BlockInfo, ledger_info::LedgerInfo,
+        validator_verifier::ValidatorVerifier,
+    };
+
+    #[test]
+    fn test_insert_and_evict_epoch_states() {
+        // Create an epoch state cache
+        let max_num_epoch_states = 3;
+        let mut epoch_state_cache =
+            EpochStateCache::new(max_num_epoch_states, create_observer_storage());
+
+        // Verify that the cache is empty
+        assert!(epoch_state_cache.get_epoch_state(0).is_none());
+
+        // Insert several epoch states
+        let num_epochs = 10;
+        for epoch in 0..num_epochs {
+            epoch_state_cache.insert_epoch_state(create_epoch_state(epoch));
+        }
+
+        // Verify that only the most recent epoch states are cached
+        for epoch in 0..num_epochs {
+            let epoch_state = epoch_state_cache.get_epoch_state(epoch);
+            if epoch >= num_epochs - max_num_epoch_states {
+                assert_eq!(epoch_state.unwrap().epoch, epoch);
+            } else {
+                assert!(epoch_state.is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_epoch_ending_ledger_info() {
+        // Create an epoch state cache
+        let mut epoch_state_cache = EpochStateCache::new(3, create_observer_storage());
+
+        // Insert a ledger info that doesn't end the epoch and verify nothing is cached
+        let epoch = 10;
+        let block_info = BlockInfo::random_with_epoch(epoch, 100);
+        epoch_state_cache.insert_epoch_ending_ledger_info(&create_ledger_info(block_info));
+        assert!(epoch_state_cache.get_epoch_state(epoch).is_none());
+        assert!(epoch_state_cache.get_epoch_state(epoch + 1).is_none());
+
+        // Insert an epoch-ending ledger info and verify the next epoch state is cached
+        let next_epoch_state = EpochState::new(epoch + 1, ValidatorVerifier::new(vec![]));
+        epoch_state_cache.insert_epoch_ending_ledger_info(&create_epoch_ending_ledger_info(
+            epoch,
+            next_epoch_state.clone(),
+        ));
+        assert_eq!(
+            epoch_state_cache
+                .get_epoch_state(epoch + 1)
+                .unwrap()
+                .as_ref(),
+            &next_epoch_state
+        );
+    }
+
+    #[test]
+    fn test_get_epoch_state_from_storage() {
+        // Create an epoch state cache
+        let observer_storage = create_observer_storage();
+        let mut epoch_state_cache = EpochStateCache::new(3, observer_storage.clone());
+
+        // Verify that no epoch states can be found (they're not cached or in storage)
+        let epoch = 10;
+        assert!(epoch_state_cache.get_epoch_state(0).is_none());
+        assert!(epoch_state_cache.get_epoch_state(epoch).is_none());
+
+        // Insert the ending ledger info of the previous epoch into storage
+        let epoch_state = EpochState::new(epoch, ValidatorVerifier::new(vec![]));
+        observer_storage.insert_epoch_ending_ledger_info(create_epoch_ending_ledger_info(
+            epoch - 1,
+            epoch_state.clone(),
+        ));
+
+        // Verify that the epoch state is derived from storage (and cached)
+        assert_eq!(
+            epoch_state_cache.get_epoch_state(epoch).unwrap().as_ref(),
+            &epoch_state
+        );
+        assert!(epoch_state_cache.epoch_states.contains_key(&epoch));
+
+        // Insert an ending ledger info with an unexpected next epoch into storage
+        observer_storage.insert_epoch_ending_ledger_info(create_epoch_ending_ledger_info(
+            epoch,
+            EpochState::new(epoch + 5, ValidatorVerifier::new(vec![])),
+        ));
+
+        // Verify that the invalid epoch state is not returned
+        assert!(epoch_state_cache.get_epoch_state(epoch + 1).is_none());
+    }
+
+    /// Creates an (empty) epoch state for the given epoch
+    fn create_epoch_state(epoch: u64) -> Arc<EpochState> {
+        Arc::new(EpochState::new(epoch, ValidatorVerifier::new(vec![])))
+    }
+
+    /// Creates a ledger info that ends the given epoch (with the given next epoch state)
+    fn create_epoch_ending_ledger_info(
+        epoch: u64,
+        next_epoch_state: EpochState,
+    ) -> LedgerInfoWithSignatures {
+        let block_info = BlockInfo::new(
+            epoch,
+            100,
+            HashValue::random(),
+            HashValue::random(),
+            0,
+            0,
+            Some(next_epoch_state),
+        );
+        create_ledger_info(block_info)
+    }
+
+    /// Creates an in-memory observer storage (with an empty ledger info)
+    fn create_observer_storage() -> Arc<InMemoryObserverStorage> {
+        Arc::new(InMemoryObserverStorage::new(create_ledger_info(
+            BlockInfo::empty(),
+        )))
+    }
+
+    /// Creates a ledger info with the given block info
+    fn create_ledger_info(block_info: BlockInfo) -> LedgerInfoWithSignatures {
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(block_info, HashValue::random()),
+            AggregateSignature::empty(),
+        )
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    logging::{LogEntry, LogSchema},
+    metrics,
+};
+use aptos_infallible::Mutex;
+use aptos_logger::info;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Summary statistics for the consensus observer over a single epoch
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct EpochSummary {
+    pub epoch: u64,
+    pub num_blocks_observed: u64,
+    pub num_commits_applied: u64,
+    pub num_subscription_switches: u64,
+    pub num_sync_fallbacks: u64,
+    pub total_commit_lag_usecs: u64,
+}
+
+impl EpochSummary {
+    pub fn new(epoch: u64) -> Self {
+        Self {
+            epoch,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the average commit lag (in milliseconds) over the epoch.
+    /// The lag is the difference between the local time at commit and
+    /// the timestamp of the committed block.
+    pub fn average_commit_lag_ms(&self) -> u64 {
+        if self.num_commits_applied == 0 {
+            return 0;
+        }
+        (self.total_commit_lag_usecs / self.num_commits_applied) / 1000
+    }
+}
+
+/// A simple tracker that accumulates the summary statistics of the current
+/// epoch and emits them (as logs and metrics) at each epoch boundary.
+#[derive(Clone)]
+pub struct EpochSummaryTracker {
+    // The summary of the current epoch (if an epoch has started)
+    current_summary: Arc<Mutex<Option<EpochSummary>>>,
+
+    // The time service (used to calculate the commit lag)
+    time_service: TimeService,
+}
+
+impl EpochSummaryTracker {
+    pub fn new(time_service: TimeService) -> Self {
+        Self {
+            current_summary: Arc::new(Mutex::new(None)),
+            time_service,
+        }
+    }
+
+    /// Returns a copy of the summary for the current epoch (if any)
+    pub fn get_current_summary(&self) -> Option<EpochSummary> {
+        self.current_summary.lock().clone()
+    }
+
+    /// Records that the given number of blocks were observed
+    pub fn record_blocks_observed(&self, num_blocks: u64) {
+        self.update_current_summary(|summary| summary.num_blocks_observed += num_blocks);
+    }
+
+    /// Records that a commit was applied for a block with the given timestamp
+    pub fn record_commit_applied(&self, block_timestamp_usecs: u64) {
+        let time_now_usecs = self.time_service.now_unix_time().as_micros() as u64;
+        let commit_lag_usecs = time_now_usecs.saturating_sub(block_timestamp_usecs);
+        self.update_current_summary(|summary| {
+            summary.num_commits_applied += 1;
+            summary.total_commit_lag_usecs = summary
+                .total_commit_lag_usecs
+                .saturating_add(commit_lag_usecs);
+        });
+    }
+
+    /// Records that the active subscription was switched to a new peer
+    pub fn record_subscription_switch(&self) {
+        self.update_current_summary(|summary| summary.num_subscription_switches += 1);
+    }
+
+    /// Records that the observer fell back to state sync
+    pub fn record_sync_fallback(&self) {
+        self.update_current_summary(|summary| summary.num_sync_fallbacks += 1);
+    }
+
+    /// Starts tracking a new epoch. If a previous epoch was being tracked,
+    /// its summary is emitted (logged and exported) and returned.
+    pub fn start_new_epoch(&self, epoch: u64) -> Option<EpochSummary> {
+        // Replace the current summary with a new one
+        let previous_summary = self
+            .current_summary
+            .lock()
+            .replace(EpochSummary::new(epoch));
+
+        // Emit the previous summary (if any)
+        if let Some(previous_summary) = &previous_summary {
+            emit_epoch_summary(previous_summary);
+        }
+
+        previous_summary
+    }
+
+    /// Applies the given update to the current summary (if an epoch has started)
+    fn update_current_summary<F: FnOnce(&mut EpochSummary)>(&self, update: F) {
+        if let Some(summary) = self.current_summary.lock().as_mut() {
+            update(summary);
+        }
+    }
+}
+
+/// Logs the given epoch summary and exports it via the summary metrics
+fn emit_epoch_summary(epoch_summary: &EpochSummary) {
+    // Log the epoch summary
+    info!(
+        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+            "Epoch summary: {:?}, average commit lag (ms): {}",
+            epoch_summary,
+            epoch_summary.average_commit_lag_ms()
+        ))
+    );
+
+    // Export the epoch summary
+    for (summary_label, value) in [
+        (metrics::EPOCH_SUMMARY_EPOCH_LABEL, epoch_summary.epoch),
+        (
+            metrics::EPOCH_SUMMARY_BLOCKS_OBSERVED_LABEL,
+            epoch_summary.num_blocks_observed,
+        ),
+        (
+            metrics::EPOCH_SUMMARY_COMMITS_APPLIED_LABEL,
+            epoch_summary.num_commits_applied,
+        ),
+        (
+            metrics::EPOCH_SUMMARY_SUBSCRIPTION_SWITCHES_LABEL,
+            epoch_summary.num_subscription_switches,
+        ),
+        (
+            metrics::EPOCH_SUMMARY_SYNC_FALLBACKS_LABEL,
+            epoch_summary.num_sync_fallbacks,
+        ),
+        (
+            metrics::EPOCH_SUMMARY_AVERAGE_COMMIT_LAG_MS_LABEL,
+            epoch_summary.average_commit_lag_ms(),
+        ),
+    ] {
+        metrics::set_gauge_with_label(
+            &metrics::OBSERVER_LAST_EPOCH_SUMMARY,
+            summary_label,
+            value as i64,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_epoch_summary_tracking() {
+        // Create a new epoch summary tracker
+        let time_service = TimeService::mock();
+        let epoch_summary_tracker = EpochSummaryTracker::new(time_service.clone());
+
+        // Record several statistics and verify they're ignored (no epoch has started)
+        epoch_summary_tracker.record_blocks_observed(10);
+        epoch_summary_tracker.record_sync_fallback();
+        assert!(epoch_summary_tracker.get_current_summary().is_none());
+
+        // Start the first epoch and verify no previous summary is returned
+        assert!(epoch_summary_tracker.start_new_epoch(1).is_none());
+        assert_eq!(
+            epoch_summary_tracker.get_current_summary(),
+            Some(EpochSummary::new(1))
+        );
+
+        // Record several statistics for the epoch
+        epoch_summary_tracker.record_blocks_observed(5);
+        epoch_summary_tracker.record_blocks_observed(3);
+        epoch_summary_tracker.record_subscription_switch();
+        epoch_summary_tracker.record_sync_fallback();
+        epoch_summary_tracker.record_sync_fallback();
+
+        // Record several commits (each with a lag of 2 seconds)
+        let mock_time_service = time_service.into_mock();
+        for _ in 0..4 {
+            mock_time_service.advance(Duration::from_secs(10));
+            let time_now_usecs = mock_time_service.now_unix_time().as_micros() as u64;
+            epoch_summary_tracker.record_commit_applied(time_now_usecs - 2_000_000);
+        }
+
+        // Start the next epoch and verify the previous summary
+        let epoch_summary = epoch_summary_tracker.start_new_epoch(2).unwrap();
+        assert_eq!(epoch_summary.epoch, 1);
+        assert_eq!(epoch_summary.num_blocks_observed, 8);
+        assert_eq!(epoch_summary.num_commits_applied, 4);
+        assert_eq!(epoch_summary.num_subscription_switches, 1);
+        assert_eq!(epoch_summary.num_sync_fallbacks, 2);
+        assert_eq!(epoch_summary.average_commit_lag_ms(), 2_000);
+
+        // Verify the current summary was reset for the new epoch
+        assert_eq!(
+            epoch_summary_tracker.get_current_summary(),
+            Some(EpochSummary::new(2))
+        );
+    }
+
+    #[test]
+    fn test_average_commit_lag() {
+        // Verify the average commit lag is zero if no commits were applied
+        let epoch_summary = EpochSummary::new(0);
+        assert_eq!(epoch_summary.average_commit_lag_ms(), 0);
+
+        // Verify the average commit lag is calculated correctly
+        let epoch_summary = EpochSummary {
+            num_commits_applied: 3,
+            total_commit_lag_usecs: 9_000_000,
+            ..EpochSummary::new(0)
+        };
+        assert_eq!(epoch_summary.average_commit_lag_ms(), 3_000);
+    }
+}
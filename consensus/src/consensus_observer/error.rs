@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_network::protocols::network::RpcError;
+use aptos_network::{application::error::Error as ApplicationError, protocols::network::RpcError};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -10,23 +10,56 @@ pub enum Error {
     InvalidMessageError(String),
 
     #[error("Network error: {0}")]
-    NetworkError(String),
+    NetworkError(NetworkClientError),
+
+    #[error("Failed to build the consensus observer: {0}")]
+    ObserverBuildError(String),
+
+    #[error("Payload compression error: {0}")]
+    PayloadCompressionError(String),
+
+    #[error("Payload delta encoding error: {0}")]
+    PayloadDeltaError(String),
+
+    #[error("Execution pipeline failure: {0}")]
+    PipelineFailure(String),
 
     #[error("Aptos network rpc error: {0}")]
     RpcError(#[from] RpcError),
 
+    #[error("State snapshot error: {0}")]
+    StateSnapshotError(String),
+
     #[error("Subscription disconnected: {0}")]
     SubscriptionDisconnected(String),
 
+    #[error("Subscription peer equivocated: {0}")]
+    SubscriptionEquivocation(String),
+
+    #[error("Subscription overridden by the operator: {0}")]
+    SubscriptionOverridden(String),
+
     #[error("Subscription progress stopped: {0}")]
     SubscriptionProgressStopped(String),
 
+    #[error("Subscription rate limited: {0}")]
+    SubscriptionRateLimited(String),
+
+    #[error("Subscription rotated: {0}")]
+    SubscriptionRotated(String),
+
+    #[error("Subscription stale: {0}")]
+    SubscriptionStale(String),
+
     #[error("Subscription suboptimal: {0}")]
     SubscriptionSuboptimal(String),
 
     #[error("Subscription timeout: {0}")]
     SubscriptionTimeout(String),
 
+    #[error("Subscription verification failed: {0}")]
+    SubscriptionVerificationFailed(String),
+
     #[error("Unexpected error encountered: {0}")]
     UnexpectedError(String),
 }
@@ -36,19 +69,148 @@ impl Error {
     pub fn get_label(&self) -> &'static str {
         match self {
             Self::InvalidMessageError(_) => "invalid_message_error",
-            Self::NetworkError(_) => "network_error",
+            Self::NetworkError(error) => error.get_label(),
+            Self::ObserverBuildError(_) => "observer_build_error",
+            Self::PayloadCompressionError(_) => "payload_compression_error",
+            Self::PayloadDeltaError(_) => "payload_delta_error",
+            Self::PipelineFailure(_) => "pipeline_failure",
             Self::RpcError(_) => "rpc_error",
+            Self::StateSnapshotError(_) => "state_snapshot_error",
             Self::SubscriptionDisconnected(_) => "subscription_disconnected",
+            Self::SubscriptionEquivocation(_) => "subscription_equivocation",
+            Self::SubscriptionOverridden(_) => "subscription_overridden",
             Self::SubscriptionProgressStopped(_) => "subscription_progress_stopped",
+            Self::SubscriptionRateLimited(_) => "subscription_rate_limited",
+            Self::SubscriptionRotated(_) => "subscription_rotated",
+            Self::SubscriptionStale(_) => "subscription_stale",
             Self::SubscriptionSuboptimal(_) => "subscription_suboptimal",
             Self::SubscriptionTimeout(_) => "subscription_timeout",
+            Self::SubscriptionVerificationFailed(_) => "subscription_verification_failed",
             Self::UnexpectedError(_) => "unexpected_error",
         }
     }
+
+    /// Returns true iff the error was caused by a network request timeout
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Self::RpcError(RpcError::TimedOut) => true,
+            Self::NetworkError(error) => matches!(error, NetworkClientError::Timeout(_)),
+            _ => false,
+        }
+    }
 }
 
-impl From<aptos_network::application::error::Error> for Error {
-    fn from(error: aptos_network::application::error::Error) -> Self {
-        Error::NetworkError(error.to_string())
+impl From<ApplicationError> for Error {
+    fn from(error: ApplicationError) -> Self {
+        Error::NetworkError(error.into())
+    }
+}
+
+impl From<NetworkClientError> for Error {
+    fn from(error: NetworkClientError) -> Self {
+        Error::NetworkError(error)
+    }
+}
+
+/// The typed errors surfaced by the consensus observer network client. These
+/// allow callers to branch on the failure type (e.g., to retry on timeouts).
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum NetworkClientError {
+    #[error("Peer unavailable: {0}")]
+    PeerUnavailable(String),
+
+    #[error("Protocol mismatch: {0}")]
+    ProtocolMismatch(String),
+
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+
+    #[error("Unexpected network error: {0}")]
+    UnexpectedError(String),
+}
+
+impl NetworkClientError {
+    /// Returns a summary label for the error
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::PeerUnavailable(_) => "network_peer_unavailable",
+            Self::ProtocolMismatch(_) => "network_protocol_mismatch",
+            Self::SerializationError(_) => "network_serialization_error",
+            Self::Timeout(_) => "network_timeout",
+            Self::UnexpectedError(_) => "network_unexpected_error",
+        }
+    }
+}
+
+impl From<ApplicationError> for NetworkClientError {
+    fn from(error: ApplicationError) -> Self {
+        // Note: the network framework only surfaces stringified errors,
+        // so we classify them based on the underlying error messages.
+        let error_message = error.to_string();
+        let error_contains = |message: &str| error_message.contains(message);
+        if error_contains(&RpcError::TimedOut.to_string()) {
+            Self::Timeout(error_message)
+        } else if error_contains("Not connected with peer")
+            || error_contains("No metadata was found for the given peer")
+        {
+            Self::PeerUnavailable(error_message)
+        } else if error_contains("None of the preferred protocols are supported") {
+            Self::ProtocolMismatch(error_message)
+        } else if error_contains("Bcs error") {
+            Self::SerializationError(error_message)
+        } else {
+            Self::UnexpectedError(error_message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_types::PeerId;
+
+    #[test]
+    fn test_network_client_error_classification() {
+        // Verify that timeouts are classified correctly
+        let error = ApplicationError::from(RpcError::TimedOut);
+        let network_client_error = NetworkClientError::from(error.clone());
+        assert!(matches!(
+            network_client_error,
+            NetworkClientError::Timeout(_)
+        ));
+        assert!(Error::from(error).is_timeout());
+
+        // Verify that disconnected peers are classified correctly
+        let error = ApplicationError::from(RpcError::NotConnected(PeerId::random()));
+        let network_client_error = NetworkClientError::from(error);
+        assert!(matches!(
+            network_client_error,
+            NetworkClientError::PeerUnavailable(_)
+        ));
+
+        // Verify that protocol mismatches are classified correctly
+        let error = ApplicationError::NetworkError(
+            "None of the preferred protocols are supported by this peer!".into(),
+        );
+        let network_client_error = NetworkClientError::from(error);
+        assert!(matches!(
+            network_client_error,
+            NetworkClientError::ProtocolMismatch(_)
+        ));
+
+        // Verify that unknown errors are classified as unexpected
+        let error = ApplicationError::UnexpectedError("Something went wrong!".into());
+        let network_client_error = NetworkClientError::from(error);
+        assert!(matches!(
+            network_client_error,
+            NetworkClientError::UnexpectedError(_)
+        ));
+        assert_eq!(
+            Error::from(network_client_error).get_label(),
+            "network_unexpected_error"
+        );
     }
 }
@@ -0,0 +1,258 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::network_id::PeerNetworkId;
+use aptos_infallible::Mutex;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::block_info::Round;
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Formatter},
+    sync::Arc,
+};
+
+/// The significant consensus observer events that are recorded in the journal
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum ObserverEvent {
+    BackupSubscriptionCreated {
+        peer_network_id: PeerNetworkId,
+    },
+    EpochStarted {
+        epoch: u64,
+    },
+    EquivocationDetected {
+        peer_network_id: PeerNetworkId,
+        epoch: u64,
+        round: Round,
+    },
+    PipelineFailuresEscalated {
+        num_failures: u64,
+        escalation: String,
+    },
+    PipelineReset {
+        epoch: u64,
+        round: Round,
+        num_dropped_blocks: usize,
+    },
+    SubscriptionCreated {
+        peer_network_id: PeerNetworkId,
+    },
+    SubscriptionTerminated {
+        peer_network_id: PeerNetworkId,
+        reason: String,
+    },
+    SyncCompleted {
+        epoch: u64,
+        round: Round,
+    },
+    SyncStarted {
+        epoch: u64,
+        round: Round,
+    },
+    VerificationFailed {
+        message_type: String,
+        error: String,
+    },
+}
+
+impl Display for ObserverEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObserverEvent::BackupSubscriptionCreated { peer_network_id } => {
+                write!(f, "BackupSubscriptionCreated: peer {}", peer_network_id)
+            },
+            ObserverEvent::EpochStarted { epoch } => {
+                write!(f, "EpochStarted: epoch {}", epoch)
+            },
+            ObserverEvent::EquivocationDetected {
+                peer_network_id,
+                epoch,
+                round,
+            } => {
+                write!(
+                    f,
+                    "EquivocationDetected: peer {}, epoch {}, round {}",
+                    peer_network_id, epoch, round
+                )
+            },
+            ObserverEvent::PipelineFailuresEscalated {
+                num_failures,
+                escalation,
+            } => {
+                write!(
+                    f,
+                    "PipelineFailuresEscalated: num failures {}, escalation: {}",
+                    num_failures, escalation
+                )
+            },
+            ObserverEvent::PipelineReset {
+                epoch,
+                round,
+                num_dropped_blocks,
+            } => {
+                write!(
+                    f,
+                    "PipelineReset: epoch {}, round {}, num dropped blocks: {}",
+                    epoch, round, num_dropped_blocks
+                )
+            },
+            ObserverEvent::SubscriptionCreated { peer_network_id } => {
+                write!(f, "SubscriptionCreated: peer {}", peer_network_id)
+            },
+            ObserverEvent::SubscriptionTerminated {
+                peer_network_id,
+                reason,
+            } => {
+                write!(
+                    f,
+                    "SubscriptionTerminated: peer {}, reason: {}",
+                    peer_network_id, reason
+                )
+            },
+            ObserverEvent::SyncCompleted { epoch, round } => {
+                write!(f, "SyncCompleted: epoch {}, round {}", epoch, round)
+            },
+            ObserverEvent::SyncStarted { epoch, round } => {
+                write!(f, "SyncStarted: epoch {}, round {}", epoch, round)
+            },
+            ObserverEvent::VerificationFailed {
+                message_type,
+                error,
+            } => {
+                write!(
+                    f,
+                    "VerificationFailed: message type {}, error: {}",
+                    message_type, error
+                )
+            },
+        }
+    }
+}
+
+/// A single journal entry (i.e., an event and the time it was recorded)
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ObserverJournalEntry {
+    pub timestamp_usecs: u64,
+    pub event: ObserverEvent,
+}
+
+impl Display for ObserverJournalEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.timestamp_usecs, self.event)
+    }
+}
+
+/// A bounded, in-memory journal of recent significant observer events.
+/// This is useful for post-incident review (e.g., via the admin service).
+#[derive(Clone)]
+pub struct ObserverEventJournal {
+    // The maximum number of entries to hold in the journal
+    max_num_entries: usize,
+
+    // The recorded journal entries (ordered from oldest to newest)
+    journal_entries: Arc<Mutex<VecDeque<ObserverJournalEntry>>>,
+
+    // The time service (used to timestamp the entries)
+    time_service: TimeService,
+}
+
+impl ObserverEventJournal {
+    pub fn new(max_num_entries: u64, time_service: TimeService) -> Self {
+        Self {
+            max_num_entries: max_num_entries as usize,
+            journal_entries: Arc::new(Mutex::new(VecDeque::new())),
+            time_service,
+        }
+    }
+
+    /// Returns a copy of all entries in the journal (ordered from oldest to newest)
+    pub fn get_journal_entries(&self) -> Vec<ObserverJournalEntry> {
+        self.journal_entries.lock().iter().cloned().collect()
+    }
+
+    /// Records the given event in the journal. If the journal is
+    /// full, the oldest entry is evicted to make room for the event.
+    pub fn record_event(&self, event: ObserverEvent) {
+        // If the journal is disabled, there's nothing to do
+        if self.max_num_entries == 0 {
+            return;
+        }
+
+        // Create the journal entry
+        let timestamp_usecs = self.time_service.now_unix_time().as_micros() as u64;
+        let journal_entry = ObserverJournalEntry {
+            timestamp_usecs,
+            event,
+        };
+
+        // Insert the entry and evict the oldest entries (if required)
+        let mut journal_entries = self.journal_entries.lock();
+        journal_entries.push_back(journal_entry);
+        while journal_entries.len() > self.max_num_entries {
+            journal_entries.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_record_event() {
+        // Create a new event journal
+        let max_num_entries = 10;
+        let time_service = TimeService::mock();
+        let event_journal = ObserverEventJournal::new(max_num_entries, time_service.clone());
+
+        // Verify the journal is empty
+        assert!(event_journal.get_journal_entries().is_empty());
+
+        // Record several events (with time elapsing between each)
+        let mock_time_service = time_service.into_mock();
+        for epoch in 0..max_num_entries {
+            event_journal.record_event(ObserverEvent::EpochStarted { epoch });
+            mock_time_service.advance(Duration::from_secs(1));
+        }
+
+        // Verify the events were recorded in order (with increasing timestamps)
+        let journal_entries = event_journal.get_journal_entries();
+        assert_eq!(journal_entries.len(), max_num_entries as usize);
+        for (index, journal_entry) in journal_entries.iter().enumerate() {
+            assert_eq!(journal_entry.event, ObserverEvent::EpochStarted {
+                epoch: index as u64
+            });
+            if index > 0 {
+                assert!(journal_entry.timestamp_usecs > journal_entries[index - 1].timestamp_usecs);
+            }
+        }
+
+        // Record another event and verify the oldest event was evicted
+        let peer_network_id = PeerNetworkId::random();
+        event_journal.record_event(ObserverEvent::SubscriptionCreated { peer_network_id });
+        let journal_entries = event_journal.get_journal_entries();
+        assert_eq!(journal_entries.len(), max_num_entries as usize);
+        assert_eq!(
+            journal_entries.first().unwrap().event,
+            ObserverEvent::EpochStarted { epoch: 1 }
+        );
+        assert_eq!(
+            journal_entries.last().unwrap().event,
+            ObserverEvent::SubscriptionCreated { peer_network_id }
+        );
+    }
+
+    #[test]
+    fn test_record_event_disabled() {
+        // Create a new event journal with no capacity
+        let event_journal = ObserverEventJournal::new(0, TimeService::mock());
+
+        // Record several events and verify that none are stored
+        for epoch in 0..10 {
+            event_journal.record_event(ObserverEvent::EpochStarted { epoch });
+        }
+        assert!(event_journal.get_journal_entries().is_empty());
+    }
+}
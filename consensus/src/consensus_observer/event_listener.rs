@@ -0,0 +1,160 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::network_message::{BlockPayload, CommitDecision, OrderedBlock};
+use aptos_types::block_info::{BlockInfo, Round};
+
+/// A listener for the progress of the consensus observer. This allows other
+/// in-process components to react to observer progress (e.g., verified ordered
+/// blocks, payloads, commit decisions and state sync transitions), without
+/// scraping logs or metrics. Listeners are invoked synchronously on the observer
+/// loop, so implementations must be cheap (and must not block). By default, all
+/// callbacks are no-ops, so listeners only implement what they require.
+pub trait ObserverEventListener: Send + Sync {
+    /// Returns the name of the listener (used for logging)
+    fn name(&self) -> &'static str;
+
+    /// Invoked when an ordered block has been verified (and inserted into the pending blocks)
+    fn on_ordered_block(&self, _ordered_block: &OrderedBlock) {}
+
+    /// Invoked when a block payload has been accepted (and inserted into the payload store)
+    fn on_block_payload(&self, _block_payload: &BlockPayload) {}
+
+    /// Invoked when a commit decision (for the current epoch) has been verified
+    fn on_commit_decision(&self, _commit_decision: &CommitDecision) {}
+
+    /// Invoked when the observer starts syncing to the given target (i.e., enters state sync mode)
+    fn on_state_sync_started(&self, _sync_target: &BlockInfo) {}
+
+    /// Invoked when the observer finishes syncing to the given epoch and round
+    fn on_state_sync_completed(&self, _epoch: u64, _round: Round) {}
+}
+
+/// The set of event listeners registered with the observer
+#[derive(Default)]
+pub struct ObserverEventListeners {
+    listeners: Vec<Box<dyn ObserverEventListener>>,
+}
+
+impl ObserverEventListeners {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true iff no listeners are registered
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+
+    /// Notifies all listeners of the accepted block payload
+    pub fn notify_block_payload(&self, block_payload: &BlockPayload) {
+        for listener in &self.listeners {
+            listener.on_block_payload(block_payload);
+        }
+    }
+
+    /// Notifies all listeners of the verified commit decision
+    pub fn notify_commit_decision(&self, commit_decision: &CommitDecision) {
+        for listener in &self.listeners {
+            listener.on_commit_decision(commit_decision);
+        }
+    }
+
+    /// Notifies all listeners of the verified ordered block
+    pub fn notify_ordered_block(&self, ordered_block: &OrderedBlock) {
+        for listener in &self.listeners {
+            listener.on_ordered_block(ordered_block);
+        }
+    }
+
+    /// Notifies all listeners that the state sync to the given epoch and round completed
+    pub fn notify_state_sync_completed(&self, epoch: u64, round: Round) {
+        for listener in &self.listeners {
+            listener.on_state_sync_completed(epoch, round);
+        }
+    }
+
+    /// Notifies all listeners that a state sync to the given target started
+    pub fn notify_state_sync_started(&self, sync_target: &BlockInfo) {
+        for listener in &self.listeners {
+            listener.on_state_sync_started(sync_target);
+        }
+    }
+
+    /// Registers the given listener (listeners are notified in registration order)
+    pub fn register_listener(&mut self, listener: Box<dyn ObserverEventListener>) {
+        self.listeners.push(listener);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_infallible::Mutex;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+    use std::sync::Arc;
+
+    /// A listener that records the callbacks it receives
+    struct RecordingListener {
+        name: &'static str,
+        notifications: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl ObserverEventListener for RecordingListener {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn on_commit_decision(&self, commit_decision: &CommitDecision) {
+            self.notifications.lock().push(format!(
+                "{}: commit {}",
+                self.name,
+                commit_decision.round()
+            ));
+        }
+
+        fn on_state_sync_completed(&self, epoch: u64, round: Round) {
+            self.notifications
+                .lock()
+                .push(format!("{}: synced {} {}", self.name, epoch, round));
+        }
+    }
+
+    #[test]
+    fn test_notify_listeners() {
+        // Register two recording listeners
+        let notifications = Arc::new(Mutex::new(vec![]));
+        let mut observer_event_listeners = ObserverEventListeners::new();
+        assert!(observer_event_listeners.is_empty());
+        for name in ["first", "second"] {
+            observer_event_listeners.register_listener(Box::new(RecordingListener {
+                name,
+                notifications: notifications.clone(),
+            }));
+        }
+        assert!(!observer_event_listeners.is_empty());
+
+        // Notify the listeners of a commit decision and a sync completion
+        let commit_decision = CommitDecision::new(LedgerInfoWithSignatures::new(
+            LedgerInfo::new(BlockInfo::random_with_epoch(1, 5), HashValue::random()),
+            AggregateSignature::empty(),
+        ));
+        observer_event_listeners.notify_commit_decision(&commit_decision);
+        observer_event_listeners.notify_state_sync_completed(1, 10);
+
+        // Notify the listeners of a sync start (which isn't implemented by the listeners)
+        observer_event_listeners.notify_state_sync_started(&BlockInfo::empty());
+
+        // Verify that all listeners were notified (in registration order)
+        assert_eq!(notifications.lock().clone(), vec![
+            "first: commit 5".to_string(),
+            "second: commit 5".to_string(),
+            "first: synced 1 10".to_string(),
+            "second: synced 1 10".to_string(),
+        ]);
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::logging::{LogEntry, LogSchema};
+use aptos_config::config::ConsensusObserverConfig;
+use aptos_infallible::RwLock;
+use aptos_logger::info;
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    sync::Arc,
+};
+
+/// The optional observer subfeatures that can be toggled at runtime
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ObserverFeature {
+    CommittedBlockPruning, // Prune pending blocks already committed to storage on startup
+    ExportStreams,         // Export observed data to the gRPC and SSE streams
+    PayloadSignatureVerification, // Verify transaction signatures in received payloads
+    StateSnapshots,        // Periodically persist snapshots of the observer state
+}
+
+impl ObserverFeature {
+    /// Returns all observer features
+    pub fn all() -> [ObserverFeature; 4] {
+        [
+            ObserverFeature::CommittedBlockPruning,
+            ObserverFeature::ExportStreams,
+            ObserverFeature::PayloadSignatureVerification,
+            ObserverFeature::StateSnapshots,
+        ]
+    }
+
+    /// Returns the feature for the given label (if any)
+    pub fn from_label(label: &str) -> Option<ObserverFeature> {
+        Self::all()
+            .into_iter()
+            .find(|feature| feature.get_label() == label)
+    }
+
+    /// Returns a summary label for the feature
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ObserverFeature::CommittedBlockPruning => "committed_block_pruning",
+            ObserverFeature::ExportStreams => "export_streams",
+            ObserverFeature::PayloadSignatureVerification => "payload_signature_verification",
+            ObserverFeature::StateSnapshots => "state_snapshots",
+        }
+    }
+}
+
+impl Display for ObserverFeature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_label())
+    }
+}
+
+/// The runtime feature flags for the optional observer subfeatures. The flags
+/// are initialized from the observer config, and can be toggled at runtime
+/// (e.g., via the admin service) to enable features incrementally or roll them
+/// back without redeploying. Note: disabling a feature at runtime only pauses
+/// it, and features whose components were not created at startup (e.g.,
+/// because they were disabled in the config) can't be enabled at runtime.
+/// The flags are cheaply cloneable, and all clones share the same state.
+#[derive(Clone)]
+pub struct ObserverFeatureFlags {
+    enabled_features: Arc<RwLock<BTreeMap<ObserverFeature, bool>>>,
+}
+
+impl ObserverFeatureFlags {
+    pub fn new(consensus_observer_config: &ConsensusObserverConfig) -> Self {
+        let enabled_features = ObserverFeature::all()
+            .into_iter()
+            .map(|feature| {
+                let enabled = match feature {
+                    ObserverFeature::CommittedBlockPruning => {
+                        consensus_observer_config.enable_committed_block_pruning
+                    },
+                    ObserverFeature::ExportStreams => true, // Streams only run if configured
+                    ObserverFeature::PayloadSignatureVerification => {
+                        consensus_observer_config.enable_payload_signature_verification
+                    },
+                    ObserverFeature::StateSnapshots => {
+                        consensus_observer_config.enable_state_snapshots
+                    },
+                };
+                (feature, enabled)
+            })
+            .collect();
+        Self {
+            enabled_features: Arc::new(RwLock::new(enabled_features)),
+        }
+    }
+
+    /// Returns all features and whether they are currently enabled
+    pub fn get_all_features(&self) -> Vec<(ObserverFeature, bool)> {
+        self.enabled_features
+            .read()
+            .iter()
+            .map(|(feature, enabled)| (*feature, *enabled))
+            .collect()
+    }
+
+    /// Returns true iff the given feature is currently enabled
+    pub fn is_enabled(&self, feature: ObserverFeature) -> bool {
+        self.enabled_features
+            .read()
+            .get(&feature)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Enables or disables the given feature at runtime
+    pub fn set_enabled(&self, feature: ObserverFeature, enabled: bool) {
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Updating observer feature flag: {}, enabled: {}",
+                feature, enabled
+            ))
+        );
+        self.enabled_features.write().insert(feature, enabled);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_feature_flags_from_config() {
+        // Create the feature flags with the default config
+        let feature_flags = ObserverFeatureFlags::new(&ConsensusObserverConfig::default());
+        assert!(feature_flags.is_enabled(ObserverFeature::ExportStreams));
+        assert!(!feature_flags.is_enabled(ObserverFeature::CommittedBlockPruning));
+        assert!(!feature_flags.is_enabled(ObserverFeature::PayloadSignatureVerification));
+        assert!(!feature_flags.is_enabled(ObserverFeature::StateSnapshots));
+
+        // Create the feature flags with the optional features enabled
+        let consensus_observer_config = ConsensusObserverConfig {
+            enable_committed_block_pruning: true,
+            enable_payload_signature_verification: true,
+            enable_state_snapshots: true,
+            ..ConsensusObserverConfig::default()
+        };
+        let feature_flags = ObserverFeatureFlags::new(&consensus_observer_config);
+        for (_, enabled) in feature_flags.get_all_features() {
+            assert!(enabled);
+        }
+    }
+
+    #[test]
+    fn test_runtime_toggles() {
+        // Create the feature flags and a clone
+        let feature_flags = ObserverFeatureFlags::new(&ConsensusObserverConfig::default());
+        let feature_flags_clone = feature_flags.clone();
+
+        // Toggle the features and verify the clone observes the updates
+        feature_flags.set_enabled(ObserverFeature::ExportStreams, false);
+        feature_flags.set_enabled(ObserverFeature::CommittedBlockPruning, true);
+        assert!(!feature_flags_clone.is_enabled(ObserverFeature::ExportStreams));
+        assert!(feature_flags_clone.is_enabled(ObserverFeature::CommittedBlockPruning));
+        assert!(!feature_flags_clone.is_enabled(ObserverFeature::StateSnapshots));
+    }
+
+    #[test]
+    fn test_feature_labels() {
+        for feature in ObserverFeature::all() {
+            assert_eq!(
+                ObserverFeature::from_label(feature.get_label()),
+                Some(feature)
+            );
+        }
+        assert_eq!(ObserverFeature::from_label("invalid_feature"), None);
+    }
+}
@@ -0,0 +1,353 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::network_message::CommitDecision;
+use aptos_config::network_id::PeerNetworkId;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+/// A buffered commit decision for a future epoch (sent by a single peer)
+struct BufferedCommitDecision {
+    // The (unverified) commit decision
+    commit_decision: CommitDecision,
+
+    // The time at which a future commit decision was first buffered (for the peer)
+    buffer_start_time: Instant,
+}
+
+/// A buffer for commit decisions from future epochs that can't be verified
+/// yet (i.e., the epoch state of the commit epoch is unknown). Decisions are
+/// held until the epoch proof arrives (e.g., the epoch-ending commit of the
+/// current epoch), and are never used to start state sync unverified.
+///
+/// Decisions are buffered per epoch and per peer (only the highest round sent
+/// by each peer is kept). This ensures that a peer sending forged decisions
+/// (e.g., for an arbitrarily high epoch) can't displace the decisions of other
+/// peers. Decisions too far ahead of the current epoch are rejected, and
+/// decisions that can't be verified within the maximum buffer duration expire.
+pub struct FutureCommitDecisionBuffer {
+    // The buffered commit decisions (indexed by epoch and then by peer)
+    buffered_commit_decisions: BTreeMap<u64, HashMap<PeerNetworkId, BufferedCommitDecision>>,
+
+    // The maximum duration for which commit decisions are buffered
+    max_buffer_duration: Duration,
+
+    // The maximum number of epochs (ahead of the current epoch) to buffer
+    max_epochs_ahead: u64,
+
+    // The time service (used to expire the buffered commit decisions)
+    time_service: TimeService,
+}
+
+impl FutureCommitDecisionBuffer {
+    pub fn new(
+        max_buffer_duration_ms: u64,
+        max_epochs_ahead: u64,
+        time_service: TimeService,
+    ) -> Self {
+        Self {
+            buffered_commit_decisions: BTreeMap::new(),
+            max_buffer_duration: Duration::from_millis(max_buffer_duration_ms),
+            max_epochs_ahead,
+            time_service,
+        }
+    }
+
+    /// Returns true iff the given commit epoch is too far ahead of the
+    /// current epoch to be buffered.
+    pub fn is_too_far_ahead(&self, current_epoch: u64, commit_epoch: u64) -> bool {
+        commit_epoch > current_epoch.saturating_add(self.max_epochs_ahead)
+    }
+
+    /// Buffers the given commit decision (sent by the given peer) if it is
+    /// not too far ahead of the current epoch, and is higher than the decision
+    /// already buffered for the peer and epoch (if any). Returns true iff the
+    /// decision was buffered. Note: the buffer start time is retained when a
+    /// decision is replaced (otherwise, a stream of new decisions from the
+    /// peer would prevent the expiry).
+    pub fn buffer_commit_decision(
+        &mut self,
+        current_epoch: u64,
+        peer_network_id: PeerNetworkId,
+        commit_decision: CommitDecision,
+    ) -> bool {
+        // Reject the decision if it is too far ahead
+        let commit_epoch = commit_decision.epoch();
+        if self.is_too_far_ahead(current_epoch, commit_epoch) {
+            return false;
+        }
+
+        // Get the buffer start time (retaining any existing start time for the peer)
+        let peer_commit_decisions = self
+            .buffered_commit_decisions
+            .entry(commit_epoch)
+            .or_default();
+        let buffer_start_time = match peer_commit_decisions.get(&peer_network_id) {
+            Some(buffered_commit_decision) => {
+                if commit_decision.round() <= buffered_commit_decision.commit_decision.round() {
+                    return false; // The buffered decision is already higher
+                }
+                buffered_commit_decision.buffer_start_time
+            },
+            None => self.time_service.now(),
+        };
+
+        // Buffer the commit decision
+        peer_commit_decisions.insert(peer_network_id, BufferedCommitDecision {
+            commit_decision,
+            buffer_start_time,
+        });
+        true
+    }
+
+    /// Returns the epochs of the buffered commit decisions (in ascending order)
+    pub fn get_buffered_epochs(&self) -> Vec<u64> {
+        self.buffered_commit_decisions.keys().copied().collect()
+    }
+
+    /// Returns the total number of buffered commit decisions
+    pub fn num_buffered_commit_decisions(&self) -> usize {
+        self.buffered_commit_decisions
+            .values()
+            .map(|peer_commit_decisions| peer_commit_decisions.len())
+            .sum()
+    }
+
+    /// Removes and returns all buffered commit decisions for the given epoch
+    /// (ordered by round, highest first).
+    pub fn take_commit_decisions(&mut self, epoch: u64) -> Vec<(PeerNetworkId, CommitDecision)> {
+        let mut commit_decisions: Vec<_> = self
+            .buffered_commit_decisions
+            .remove(&epoch)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(peer_network_id, buffered_commit_decision)| {
+                (peer_network_id, buffered_commit_decision.commit_decision)
+            })
+            .collect();
+        commit_decisions
+            .sort_by_key(|(_, commit_decision)| std::cmp::Reverse(commit_decision.round()));
+        commit_decisions
+    }
+
+    /// Removes and returns all buffered commit decisions that have been
+    /// buffered for longer than the maximum buffer duration.
+    pub fn take_expired_commit_decisions(&mut self) -> Vec<(PeerNetworkId, CommitDecision)> {
+        let time_now = self.time_service.now();
+        let max_buffer_duration = self.max_buffer_duration;
+
+        // Remove the expired commit decisions from each epoch
+        let mut expired_commit_decisions = vec![];
+        for peer_commit_decisions in self.buffered_commit_decisions.values_mut() {
+            let expired_peers: Vec<_> = peer_commit_decisions
+                .iter()
+                .filter(|(_, buffered_commit_decision)| {
+                    time_now.saturating_duration_since(buffered_commit_decision.buffer_start_time)
+                        > max_buffer_duration
+                })
+                .map(|(peer_network_id, _)| *peer_network_id)
+                .collect();
+            for peer_network_id in expired_peers {
+                if let Some(buffered_commit_decision) =
+                    peer_commit_decisions.remove(&peer_network_id)
+                {
+                    expired_commit_decisions
+                        .push((peer_network_id, buffered_commit_decision.commit_decision));
+                }
+            }
+        }
+
+        // Remove any epochs that no longer have buffered commit decisions
+        self.buffered_commit_decisions
+            .retain(|_, peer_commit_decisions| !peer_commit_decisions.is_empty());
+
+        expired_commit_decisions
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        block_info::{BlockInfo, Round},
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+
+    #[test]
+    fn test_buffer_commit_decisions() {
+        // Create a future commit decision buffer
+        let mut future_commit_decisions =
+            FutureCommitDecisionBuffer::new(1000, 2, TimeService::mock());
+        assert!(future_commit_decisions.get_buffered_epochs().is_empty());
+
+        // Buffer a commit decision and verify it is buffered
+        let peer_network_id = PeerNetworkId::random();
+        assert!(future_commit_decisions.buffer_commit_decision(
+            4,
+            peer_network_id,
+            create_commit_decision(5, 10)
+        ));
+        assert_eq!(future_commit_decisions.get_buffered_epochs(), vec![5]);
+
+        // Verify that lower (or equal) commit decisions from the peer are not buffered
+        assert!(!future_commit_decisions.buffer_commit_decision(
+            4,
+            peer_network_id,
+            create_commit_decision(5, 10)
+        ));
+        assert!(!future_commit_decisions.buffer_commit_decision(
+            4,
+            peer_network_id,
+            create_commit_decision(5, 9)
+        ));
+
+        // Verify that higher commit decisions from the peer replace the buffered decision
+        assert!(future_commit_decisions.buffer_commit_decision(
+            4,
+            peer_network_id,
+            create_commit_decision(5, 11)
+        ));
+        assert_eq!(future_commit_decisions.num_buffered_commit_decisions(), 1);
+
+        // Verify that decisions from other peers (and epochs) are buffered separately
+        let other_peer_network_id = PeerNetworkId::random();
+        assert!(future_commit_decisions.buffer_commit_decision(
+            4,
+            other_peer_network_id,
+            create_commit_decision(5, 20)
+        ));
+        assert!(future_commit_decisions.buffer_commit_decision(
+            4,
+            other_peer_network_id,
+            create_commit_decision(6, 1)
+        ));
+        assert_eq!(future_commit_decisions.get_buffered_epochs(), vec![5, 6]);
+        assert_eq!(future_commit_decisions.num_buffered_commit_decisions(), 3);
+
+        // Take the decisions for epoch 5 and verify they are ordered by round
+        let commit_decisions = future_commit_decisions.take_commit_decisions(5);
+        assert_eq!(commit_decisions.len(), 2);
+        assert_eq!(commit_decisions[0].0, other_peer_network_id);
+        assert_eq!(commit_decisions[0].1.round(), 20);
+        assert_eq!(commit_decisions[1].0, peer_network_id);
+        assert_eq!(commit_decisions[1].1.round(), 11);
+
+        // Verify that only the decisions for epoch 6 remain
+        assert!(future_commit_decisions.take_commit_decisions(5).is_empty());
+        assert_eq!(future_commit_decisions.get_buffered_epochs(), vec![6]);
+        assert_eq!(future_commit_decisions.num_buffered_commit_decisions(), 1);
+    }
+
+    #[test]
+    fn test_buffer_commit_decisions_too_far_ahead() {
+        // Create a future commit decision buffer
+        let mut future_commit_decisions =
+            FutureCommitDecisionBuffer::new(1000, 2, TimeService::mock());
+
+        // Verify that decisions too far ahead of the current epoch are rejected
+        let peer_network_id = PeerNetworkId::random();
+        assert!(future_commit_decisions.is_too_far_ahead(4, 7));
+        assert!(!future_commit_decisions.buffer_commit_decision(
+            4,
+            peer_network_id,
+            create_commit_decision(7, 1)
+        ));
+        assert!(!future_commit_decisions.buffer_commit_decision(
+            4,
+            peer_network_id,
+            create_commit_decision(u64::MAX, u64::MAX)
+        ));
+        assert!(future_commit_decisions.get_buffered_epochs().is_empty());
+
+        // Verify that decisions within the limit are buffered
+        assert!(!future_commit_decisions.is_too_far_ahead(4, 6));
+        assert!(future_commit_decisions.buffer_commit_decision(
+            4,
+            peer_network_id,
+            create_commit_decision(6, 1)
+        ));
+
+        // Verify that a forged decision from another peer doesn't displace the buffered decision
+        let forging_peer_network_id = PeerNetworkId::random();
+        assert!(future_commit_decisions.buffer_commit_decision(
+            4,
+            forging_peer_network_id,
+            create_commit_decision(6, u64::MAX)
+        ));
+        let commit_decisions = future_commit_decisions.take_commit_decisions(6);
+        assert_eq!(commit_decisions.len(), 2);
+        assert_eq!(commit_decisions[1].0, peer_network_id);
+        assert_eq!(commit_decisions[1].1.round(), 1);
+    }
+
+    #[test]
+    fn test_expire_commit_decisions() {
+        // Create a future commit decision buffer
+        let time_service = TimeService::mock();
+        let mut future_commit_decisions =
+            FutureCommitDecisionBuffer::new(1000, 2, time_service.clone());
+        let mock_time_service = time_service.into_mock();
+
+        // Buffer a commit decision and verify it doesn't expire immediately
+        let peer_network_id = PeerNetworkId::random();
+        future_commit_decisions.buffer_commit_decision(
+            4,
+            peer_network_id,
+            create_commit_decision(5, 10),
+        );
+        assert!(future_commit_decisions
+            .take_expired_commit_decisions()
+            .is_empty());
+
+        // Replace the decision (after some time) and buffer a decision from another peer
+        mock_time_service.advance(Duration::from_millis(600));
+        future_commit_decisions.buffer_commit_decision(
+            4,
+            peer_network_id,
+            create_commit_decision(5, 11),
+        );
+        let other_peer_network_id = PeerNetworkId::random();
+        future_commit_decisions.buffer_commit_decision(
+            4,
+            other_peer_network_id,
+            create_commit_decision(5, 12),
+        );
+
+        // Verify that only the first peer's decision expires (the buffer start time is retained)
+        mock_time_service.advance(Duration::from_millis(600));
+        let expired_commit_decisions = future_commit_decisions.take_expired_commit_decisions();
+        assert_eq!(expired_commit_decisions.len(), 1);
+        let (expired_peer, commit_decision) = &expired_commit_decisions[0];
+        assert_eq!(*expired_peer, peer_network_id);
+        assert_eq!((commit_decision.epoch(), commit_decision.round()), (5, 11));
+
+        // Verify that the other peer's decision expires later
+        assert_eq!(future_commit_decisions.num_buffered_commit_decisions(), 1);
+        mock_time_service.advance(Duration::from_millis(600));
+        let expired_commit_decisions = future_commit_decisions.take_expired_commit_decisions();
+        assert_eq!(expired_commit_decisions.len(), 1);
+        assert_eq!(expired_commit_decisions[0].0, other_peer_network_id);
+
+        // Verify that the buffer is now empty
+        assert!(future_commit_decisions
+            .take_expired_commit_decisions()
+            .is_empty());
+        assert!(future_commit_decisions.get_buffered_epochs().is_empty());
+    }
+
+    /// Creates a (unsigned) commit decision for the given epoch and round
+    fn create_commit_decision(epoch: u64, round: Round) -> CommitDecision {
+        CommitDecision::new(LedgerInfoWithSignatures::new(
+            LedgerInfo::new(
+                BlockInfo::random_with_epoch(epoch, round),
+                HashValue::zero(),
+            ),
+            AggregateSignature::empty(),
+        ))
+    }
+}
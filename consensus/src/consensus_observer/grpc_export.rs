@@ -0,0 +1,284 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    data_exporter::{ObservedData, ObserverDataExporter},
+    logging::{LogEntry, LogSchema},
+};
+use aptos_logger::{error, info, warn};
+use aptos_protos::internal::consensus_observer::v1::{
+    consensus_observer_export_server::{ConsensusObserverExport, ConsensusObserverExportServer},
+    stream_observed_data_response::Data,
+    BlockSummary, ObservedBlockPayload, ObservedCommitDecision, ObservedOrderedBlock,
+    StreamObservedDataRequest, StreamObservedDataResponse,
+};
+use aptos_types::block_info::BlockInfo;
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use serde::Serialize;
+use std::net::SocketAddr;
+use tokio::{runtime::Handle, sync::broadcast::error::RecvError};
+use tonic::{transport::Server, Request, Response, Status};
+
+/// A gRPC service that streams the data observed by the consensus observer
+/// (i.e., ordered blocks, payload summaries and commit decisions) to clients.
+pub struct ConsensusObserverExportService {
+    data_exporter: ObserverDataExporter,
+}
+
+impl ConsensusObserverExportService {
+    pub fn new(data_exporter: ObserverDataExporter) -> Self {
+        Self { data_exporter }
+    }
+}
+
+#[tonic::async_trait]
+impl ConsensusObserverExport for ConsensusObserverExportService {
+    type StreamObservedDataStream = BoxStream<'static, Result<StreamObservedDataResponse, Status>>;
+
+    async fn stream_observed_data(
+        &self,
+        request: Request<StreamObservedDataRequest>,
+    ) -> Result<Response<Self::StreamObservedDataStream>, Status> {
+        // Subscribe to the observed data and transform it into a response stream
+        // (including the proofs and transactions only if requested by the client).
+        let stream_request = request.into_inner();
+        let observed_data_receiver = self.data_exporter.subscribe();
+        let response_stream = stream::unfold(
+            (observed_data_receiver, stream_request),
+            |(mut observed_data_receiver, stream_request)| async move {
+                loop {
+                    match observed_data_receiver.recv().await {
+                        Ok(observed_data) => {
+                            let response = create_stream_response(observed_data, &stream_request);
+                            return Some((Ok(response), (observed_data_receiver, stream_request)));
+                        },
+                        Err(RecvError::Lagged(num_skipped_items)) => {
+                            warn!(
+                                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                    "The gRPC export stream is lagging! Skipped {} items!",
+                                    num_skipped_items
+                                ))
+                            );
+                        },
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            },
+        )
+        .boxed();
+
+        Ok(Response::new(response_stream))
+    }
+}
+
+/// Starts the gRPC export server (on the given runtime) at the specified address
+pub fn start_grpc_export_server(
+    grpc_export_address: SocketAddr,
+    data_exporter: ObserverDataExporter,
+    runtime: &Handle,
+) {
+    info!(
+        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+            "Starting the consensus observer gRPC export server at: {}",
+            grpc_export_address
+        ))
+    );
+
+    // Spawn the gRPC server
+    let export_service = ConsensusObserverExportService::new(data_exporter);
+    runtime.spawn(async move {
+        if let Err(error) = Server::builder()
+            .add_service(ConsensusObserverExportServer::new(export_service))
+            .serve(grpc_export_address)
+            .await
+        {
+            error!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "The consensus observer gRPC export server failed! Error: {:?}",
+                    error
+                ))
+            );
+        }
+    });
+}
+
+/// Transforms the given block info into a block summary
+fn create_block_summary(block_info: &BlockInfo) -> BlockSummary {
+    BlockSummary {
+        epoch: block_info.epoch(),
+        round: block_info.round(),
+        id: block_info.id().to_vec(),
+        version: block_info.version(),
+        timestamp_usecs: block_info.timestamp_usecs(),
+    }
+}
+
+/// Transforms the given observed data into a stream response. The proofs and
+/// transactions are only included (BCS-encoded) if requested by the client.
+fn create_stream_response(
+    observed_data: ObservedData,
+    stream_request: &StreamObservedDataRequest,
+) -> StreamObservedDataResponse {
+    let data = match observed_data {
+        ObservedData::OrderedBlock {
+            blocks,
+            proof_block_info,
+            ordered_proof,
+        } => Data::OrderedBlock(ObservedOrderedBlock {
+            blocks: blocks.iter().map(create_block_summary).collect(),
+            proof_block: Some(create_block_summary(&proof_block_info)),
+            ordered_proof: if stream_request.include_proofs {
+                serialize_to_bytes(ordered_proof.as_ref())
+            } else {
+                vec![]
+            },
+        }),
+        ObservedData::BlockPayload {
+            block,
+            num_transactions,
+            transaction_limit,
+            transactions,
+        } => Data::BlockPayload(ObservedBlockPayload {
+            block: Some(create_block_summary(&block)),
+            num_transactions,
+            transaction_limit,
+            transactions: if stream_request.include_transactions {
+                transactions.iter().map(serialize_to_bytes).collect()
+            } else {
+                vec![]
+            },
+        }),
+        ObservedData::CommitDecision {
+            proof_block_info,
+            commit_proof,
+        } => Data::CommitDecision(ObservedCommitDecision {
+            proof_block: Some(create_block_summary(&proof_block_info)),
+            commit_proof: if stream_request.include_proofs {
+                serialize_to_bytes(commit_proof.as_ref())
+            } else {
+                vec![]
+            },
+        }),
+    };
+
+    StreamObservedDataResponse { data: Some(data) }
+}
+
+/// Serializes the given value to BCS bytes (returning empty bytes on failure)
+fn serialize_to_bytes<T: Serialize>(value: &T) -> Vec<u8> {
+    bcs::to_bytes(value).unwrap_or_else(|error| {
+        error!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Failed to serialize the data for the gRPC export stream! Error: {:?}",
+                error
+            ))
+        );
+        vec![]
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform};
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+        test_helpers::transaction_test_helpers::get_test_signed_txn,
+        transaction::SignedTransaction,
+        PeerId,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn test_create_stream_response() {
+        // Create a commit decision and verify the stream response
+        let block_info = BlockInfo::random_with_epoch(10, 20);
+        let commit_proof = Arc::new(LedgerInfoWithSignatures::new(
+            LedgerInfo::new(block_info.clone(), HashValue::random()),
+            AggregateSignature::empty(),
+        ));
+        let commit_decision = ObservedData::CommitDecision {
+            proof_block_info: block_info.clone(),
+            commit_proof: commit_proof.clone(),
+        };
+        let stream_response = create_stream_response(
+            commit_decision.clone(),
+            &StreamObservedDataRequest::default(),
+        );
+        match stream_response.data {
+            Some(Data::CommitDecision(observed_commit_decision)) => {
+                let proof_block = observed_commit_decision.proof_block.unwrap();
+                assert_eq!(proof_block.epoch, 10);
+                assert_eq!(proof_block.round, 20);
+                assert_eq!(proof_block.id, block_info.id().to_vec());
+                assert!(observed_commit_decision.commit_proof.is_empty());
+            },
+            data => panic!("Unexpected stream response data: {:?}", data),
+        }
+
+        // Verify that the commit proof is included (if requested)
+        let stream_request = StreamObservedDataRequest {
+            include_proofs: true,
+            include_transactions: false,
+        };
+        match create_stream_response(commit_decision, &stream_request).data {
+            Some(Data::CommitDecision(observed_commit_decision)) => {
+                let decoded_proof: LedgerInfoWithSignatures =
+                    bcs::from_bytes(&observed_commit_decision.commit_proof).unwrap();
+                assert_eq!(decoded_proof, *commit_proof);
+            },
+            data => panic!("Unexpected stream response data: {:?}", data),
+        }
+    }
+
+    #[test]
+    fn test_create_stream_response_transactions() {
+        // Create a block payload (with several transactions)
+        let block_info = BlockInfo::random_with_epoch(10, 20);
+        let transactions: Vec<_> = (0..3)
+            .map(|sequence_number| {
+                get_test_signed_txn(
+                    PeerId::random(),
+                    sequence_number,
+                    &Ed25519PrivateKey::generate_for_testing(),
+                    Ed25519PrivateKey::generate_for_testing().public_key(),
+                    None,
+                )
+            })
+            .collect();
+        let block_payload = ObservedData::BlockPayload {
+            block: block_info,
+            num_transactions: transactions.len() as u64,
+            transaction_limit: None,
+            transactions: Arc::new(transactions.clone()),
+        };
+
+        // Verify that the transactions are only included if requested
+        for include_transactions in [false, true] {
+            let stream_request = StreamObservedDataRequest {
+                include_proofs: false,
+                include_transactions,
+            };
+            match create_stream_response(block_payload.clone(), &stream_request).data {
+                Some(Data::BlockPayload(observed_block_payload)) => {
+                    assert_eq!(observed_block_payload.num_transactions, 3);
+                    if include_transactions {
+                        let decoded_transactions: Vec<SignedTransaction> = observed_block_payload
+                            .transactions
+                            .iter()
+                            .map(|transaction| bcs::from_bytes(transaction).unwrap())
+                            .collect();
+                        assert_eq!(decoded_transactions, transactions);
+                    } else {
+                        assert!(observed_block_payload.transactions.is_empty());
+                    }
+                },
+                data => panic!("Unexpected stream response data: {:?}", data),
+            }
+        }
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::network_message::OrderedBlock;
+use aptos_consensus_types::pipelined_block::PipelinedBlock;
+use std::{collections::VecDeque, sync::Arc};
+
+/// The next blocks of an ordered batch that can be sent to the execution
+/// pipeline (i.e., all of their payloads are available in the payload store).
+pub struct FinalizableBlocks {
+    pub ordered_block: Arc<OrderedBlock>, // The ordered batch containing the blocks
+    pub blocks: Vec<Arc<PipelinedBlock>>, // The blocks to send to the pipeline
+    pub completes_ordered_block: bool,    // Whether these are the last blocks of the batch
+}
+
+/// A queue of the ordered batches that are being streamed into the execution
+/// pipeline. The blocks of each batch are released (in order) as soon as their
+/// payloads arrive, instead of waiting for all payloads of the batch. Note: a
+/// block is never released before its parent, so the pipeline always receives
+/// a contiguous chain of blocks.
+#[derive(Default)]
+pub struct IncrementalFinalizationQueue {
+    // The ordered batches being streamed (in order), and the
+    // number of blocks of each batch already sent to the pipeline.
+    ordered_blocks: VecDeque<(Arc<OrderedBlock>, usize)>,
+}
+
+impl IncrementalFinalizationQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clears all ordered batches from the queue (e.g., when the pipeline is reset)
+    pub fn clear(&mut self) {
+        self.ordered_blocks.clear();
+    }
+
+    /// Returns true iff there are no ordered batches in the queue
+    pub fn is_empty(&self) -> bool {
+        self.ordered_blocks.is_empty()
+    }
+
+    /// Returns the number of ordered batches in the queue
+    pub fn num_ordered_blocks(&self) -> usize {
+        self.ordered_blocks.len()
+    }
+
+    /// Adds the ordered batch to the end of the queue
+    pub fn push_ordered_block(&mut self, ordered_block: Arc<OrderedBlock>) {
+        self.ordered_blocks.push_back((ordered_block, 0));
+    }
+
+    /// Takes the next blocks of the first ordered batch that can be sent to
+    /// the pipeline (i.e., the longest run of unsent blocks whose payloads
+    /// exist). If the run completes the batch, the batch is removed from the
+    /// queue. Returns None if the next unsent block is still missing its payload.
+    pub fn take_next_finalizable_blocks(
+        &mut self,
+        payload_exists: impl Fn(&Arc<PipelinedBlock>) -> bool,
+    ) -> Option<FinalizableBlocks> {
+        // Identify the unsent blocks of the first batch with available payloads
+        let (ordered_block, num_sent_blocks) = self.ordered_blocks.front_mut()?;
+        let blocks: Vec<_> = ordered_block.blocks()[*num_sent_blocks..]
+            .iter()
+            .take_while(|block| payload_exists(block))
+            .cloned()
+            .collect();
+        if blocks.is_empty() {
+            return None;
+        }
+
+        // Mark the blocks as sent (and remove the batch if it is complete)
+        *num_sent_blocks += blocks.len();
+        let ordered_block = ordered_block.clone();
+        let completes_ordered_block = *num_sent_blocks == ordered_block.blocks().len();
+        if completes_ordered_block {
+            self.ordered_blocks.pop_front();
+        }
+
+        Some(FinalizableBlocks {
+            ordered_block,
+            blocks,
+            completes_ordered_block,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_consensus_types::{
+        block::Block,
+        block_data::{BlockData, BlockType},
+        common::Round,
+        quorum_cert::QuorumCert,
+    };
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        block_info::BlockInfo,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_take_next_finalizable_blocks() {
+        // Create a queue with two ordered batches
+        let mut finalization_queue = IncrementalFinalizationQueue::new();
+        let first_ordered_block = create_ordered_block(0, 3);
+        let second_ordered_block = create_ordered_block(3, 2);
+        finalization_queue.push_ordered_block(first_ordered_block.clone());
+        finalization_queue.push_ordered_block(second_ordered_block.clone());
+        assert_eq!(finalization_queue.num_ordered_blocks(), 2);
+
+        // Verify that no blocks are released without payloads
+        let mut payloads = HashSet::new();
+        assert!(take_next_blocks(&mut finalization_queue, &payloads).is_none());
+
+        // Add the payload of the second block in the first batch, and verify
+        // that no blocks are released (the first block is still missing).
+        payloads.insert(first_ordered_block.blocks()[1].id());
+        assert!(take_next_blocks(&mut finalization_queue, &payloads).is_none());
+
+        // Add the payload of the first block, and verify the first two blocks are released
+        payloads.insert(first_ordered_block.blocks()[0].id());
+        let finalizable_blocks = take_next_blocks(&mut finalization_queue, &payloads).unwrap();
+        assert_eq!(
+            finalizable_blocks.blocks,
+            first_ordered_block.blocks()[0..2].to_vec()
+        );
+        assert!(!finalizable_blocks.completes_ordered_block);
+        assert!(take_next_blocks(&mut finalization_queue, &payloads).is_none());
+
+        // Add the payloads of the second batch, and verify that nothing is
+        // released (the last block of the first batch is still missing).
+        for block in second_ordered_block.blocks() {
+            payloads.insert(block.id());
+        }
+        assert!(take_next_blocks(&mut finalization_queue, &payloads).is_none());
+
+        // Add the last payload of the first batch, and verify the batch is completed
+        payloads.insert(first_ordered_block.blocks()[2].id());
+        let finalizable_blocks = take_next_blocks(&mut finalization_queue, &payloads).unwrap();
+        assert_eq!(
+            finalizable_blocks.blocks,
+            first_ordered_block.blocks()[2..].to_vec()
+        );
+        assert_eq!(
+            finalizable_blocks.ordered_block.proof_block_info(),
+            first_ordered_block.proof_block_info()
+        );
+        assert!(finalizable_blocks.completes_ordered_block);
+        assert_eq!(finalization_queue.num_ordered_blocks(), 1);
+
+        // Verify the entire second batch is released at once
+        let finalizable_blocks = take_next_blocks(&mut finalization_queue, &payloads).unwrap();
+        assert_eq!(
+            finalizable_blocks.blocks,
+            second_ordered_block.blocks().clone()
+        );
+        assert!(finalizable_blocks.completes_ordered_block);
+        assert!(finalization_queue.is_empty());
+    }
+
+    #[test]
+    fn test_clear() {
+        // Create a queue with several ordered batches
+        let mut finalization_queue = IncrementalFinalizationQueue::new();
+        for i in 0..5 {
+            finalization_queue.push_ordered_block(create_ordered_block(i * 2, 2));
+        }
+        assert_eq!(finalization_queue.num_ordered_blocks(), 5);
+
+        // Clear the queue and verify it is empty
+        finalization_queue.clear();
+        assert!(finalization_queue.is_empty());
+    }
+
+    /// Creates an ordered block with the given number of blocks (starting at the given round)
+    fn create_ordered_block(first_round: Round, num_blocks: u64) -> Arc<OrderedBlock> {
+        // Create the pipelined blocks
+        let mut blocks = vec![];
+        let mut last_block_info = None;
+        for round in first_round..first_round + num_blocks {
+            let block_info = BlockInfo::random_with_epoch(0, round);
+            let block_data = BlockData::new_for_testing(
+                block_info.epoch(),
+                block_info.round(),
+                block_info.timestamp_usecs(),
+                QuorumCert::dummy(),
+                BlockType::Genesis,
+            );
+            let block = Block::new_for_testing(block_info.id(), block_data, None);
+            blocks.push(Arc::new(PipelinedBlock::new_ordered(block)));
+            last_block_info = Some(block_info);
+        }
+
+        // Create the ordered block
+        let ordered_proof = LedgerInfoWithSignatures::new(
+            LedgerInfo::new(last_block_info.unwrap(), HashValue::random()),
+            AggregateSignature::empty(),
+        );
+        Arc::new(OrderedBlock::new(blocks, ordered_proof))
+    }
+
+    /// Takes the next finalizable blocks (using the given set of available payloads)
+    fn take_next_blocks(
+        finalization_queue: &mut IncrementalFinalizationQueue,
+        payloads: &HashSet<HashValue>,
+    ) -> Option<FinalizableBlocks> {
+        finalization_queue.take_next_finalizable_blocks(|block| payloads.contains(&block.id()))
+    }
+}
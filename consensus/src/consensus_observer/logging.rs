@@ -41,6 +41,7 @@ pub enum LogEntry {
     ConsensusObserver,
     ConsensusPublisher,
     GetDownstreamPeers,
+    PeerMisbehavior,
     SendDirectSendMessage,
     SendRpcRequest,
 }
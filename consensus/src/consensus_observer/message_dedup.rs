@@ -0,0 +1,109 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    logging::{LogEntry, LogSchema},
+    network_message::ConsensusObserverDirectSend,
+};
+use aptos_crypto::HashValue;
+use aptos_logger::error;
+use std::collections::{HashSet, VecDeque};
+
+/// A deduplicator for the messages received across concurrent subscriptions.
+/// Each message is identified by the digest of its serialized bytes, so only
+/// identical messages are deduplicated (i.e., a different, invalid message
+/// from one subscription can never shadow a valid message from another).
+/// The digests of the most recent messages are retained, up to the capacity.
+pub struct MessageDeduplicator {
+    // The maximum number of message digests to retain
+    max_num_digests: usize,
+
+    // The digests of the most recently received messages (in receive order)
+    received_digests: VecDeque<HashValue>,
+
+    // The set of received digests (for fast lookups)
+    received_digest_set: HashSet<HashValue>,
+}
+
+impl MessageDeduplicator {
+    pub fn new(max_num_digests: usize) -> Self {
+        Self {
+            max_num_digests,
+            received_digests: VecDeque::new(),
+            received_digest_set: HashSet::new(),
+        }
+    }
+
+    /// Returns true iff an identical message was already received. Otherwise,
+    /// the message is recorded (evicting the oldest digest, if required).
+    pub fn is_duplicate(&mut self, message: &ConsensusObserverDirectSend) -> bool {
+        // Calculate the digest of the message
+        let message_digest = match bcs::to_bytes(message) {
+            Ok(message_bytes) => HashValue::sha3_256_of(&message_bytes),
+            Err(error) => {
+                error!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to serialize the message for deduplication! Error: {:?}",
+                        error
+                    ))
+                );
+                return false; // Process the message (to be safe)
+            },
+        };
+
+        // Check if the message was already received
+        if !self.received_digest_set.insert(message_digest) {
+            return true;
+        }
+
+        // Record the digest (and evict the oldest digest, if required)
+        self.received_digests.push_back(message_digest);
+        if self.received_digests.len() > self.max_num_digests {
+            if let Some(oldest_digest) = self.received_digests.pop_front() {
+                self.received_digest_set.remove(&oldest_digest);
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::network_message::CommitDecision;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        block_info::BlockInfo,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+
+    #[test]
+    fn test_message_deduplication() {
+        // Create a message deduplicator (with a small capacity)
+        let mut message_deduplicator = MessageDeduplicator::new(2);
+
+        // Verify that new messages are not duplicates (but repeated messages are)
+        let messages: Vec<_> = (0..3).map(create_commit_decision_message).collect();
+        assert!(!message_deduplicator.is_duplicate(&messages[0]));
+        assert!(message_deduplicator.is_duplicate(&messages[0]));
+        assert!(!message_deduplicator.is_duplicate(&messages[1]));
+        assert!(message_deduplicator.is_duplicate(&messages[0]));
+        assert!(message_deduplicator.is_duplicate(&messages[1]));
+
+        // Receive another message and verify the oldest digest is evicted
+        assert!(!message_deduplicator.is_duplicate(&messages[2]));
+        assert!(message_deduplicator.is_duplicate(&messages[1]));
+        assert!(message_deduplicator.is_duplicate(&messages[2]));
+        assert!(!message_deduplicator.is_duplicate(&messages[0]));
+    }
+
+    /// Creates a commit decision message for the given round
+    fn create_commit_decision_message(round: u64) -> ConsensusObserverDirectSend {
+        let commit_decision = CommitDecision::new(LedgerInfoWithSignatures::new(
+            LedgerInfo::new(BlockInfo::random_with_epoch(1, round), HashValue::zero()),
+            AggregateSignature::empty(),
+        ));
+        ConsensusObserverDirectSend::CommitDecision(commit_decision)
+    }
+}
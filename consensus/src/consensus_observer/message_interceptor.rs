@@ -0,0 +1,331 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    logging::{LogEntry, LogSchema},
+    metrics,
+    network_message::{ConsensusObserverDirectSend, ConsensusObserverMessage},
+};
+use aptos_config::network_id::PeerNetworkId;
+use aptos_infallible::RwLock;
+use aptos_logger::debug;
+use std::sync::Arc;
+
+// Useful constants for the message directions
+pub const INBOUND_DIRECTION_LABEL: &str = "inbound";
+pub const OUTBOUND_DIRECTION_LABEL: &str = "outbound";
+
+/// The action to take after a message has been intercepted
+pub enum InterceptorAction<T> {
+    Continue(T), // Continue processing the (possibly modified) message
+    Drop,        // Drop the message (no further processing will occur)
+}
+
+/// An interceptor that is invoked on every inbound observer message and
+/// every outbound publisher message. Interceptors can observe, modify or
+/// drop messages (e.g., for custom filtering, auditing or experimentation).
+/// By default, all messages are passed through unmodified.
+pub trait ConsensusObserverMessageInterceptor: Send + Sync {
+    /// Returns the name of the interceptor (used for logging and metrics)
+    fn name(&self) -> &'static str;
+
+    /// Intercepts an inbound message received from the given peer
+    fn intercept_inbound_message(
+        &self,
+        _peer_network_id: &PeerNetworkId,
+        message: ConsensusObserverMessage,
+    ) -> InterceptorAction<ConsensusObserverMessage> {
+        InterceptorAction::Continue(message)
+    }
+
+    /// Intercepts an outbound message about to be published to the given peer
+    fn intercept_outbound_message(
+        &self,
+        _peer_network_id: &PeerNetworkId,
+        message: ConsensusObserverDirectSend,
+    ) -> InterceptorAction<ConsensusObserverDirectSend> {
+        InterceptorAction::Continue(message)
+    }
+}
+
+/// An ordered chain of message interceptors. The chain is cheaply
+/// cloneable, and all clones share the same set of interceptors.
+#[derive(Clone, Default)]
+pub struct MessageInterceptorChain {
+    interceptors: Arc<RwLock<Vec<Arc<dyn ConsensusObserverMessageInterceptor>>>>,
+}
+
+impl MessageInterceptorChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the given interceptor to the end of the chain
+    pub fn add_interceptor(&self, interceptor: Arc<dyn ConsensusObserverMessageInterceptor>) {
+        self.interceptors.write().push(interceptor);
+    }
+
+    /// Returns true iff the chain contains no interceptors
+    pub fn is_empty(&self) -> bool {
+        self.interceptors.read().is_empty()
+    }
+
+    /// Runs the inbound message through the chain of interceptors. Returns
+    /// the (possibly modified) message, or None if the message was dropped.
+    pub fn intercept_inbound_message(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        message: ConsensusObserverMessage,
+    ) -> Option<ConsensusObserverMessage> {
+        let mut message = message;
+        for interceptor in self.interceptors.read().iter() {
+            let message_label = message.get_label();
+            match interceptor.intercept_inbound_message(peer_network_id, message) {
+                InterceptorAction::Continue(intercepted_message) => message = intercepted_message,
+                InterceptorAction::Drop => {
+                    log_and_count_dropped_message(
+                        interceptor.name(),
+                        INBOUND_DIRECTION_LABEL,
+                        message_label,
+                        peer_network_id,
+                    );
+                    return None;
+                },
+            }
+        }
+        Some(message)
+    }
+
+    /// Runs the outbound message through the chain of interceptors. Returns
+    /// the (possibly modified) message, or None if the message was dropped.
+    pub fn intercept_outbound_message(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        message: ConsensusObserverDirectSend,
+    ) -> Option<ConsensusObserverDirectSend> {
+        let mut message = message;
+        for interceptor in self.interceptors.read().iter() {
+            let message_label = message.get_label();
+            match interceptor.intercept_outbound_message(peer_network_id, message) {
+                InterceptorAction::Continue(intercepted_message) => message = intercepted_message,
+                InterceptorAction::Drop => {
+                    log_and_count_dropped_message(
+                        interceptor.name(),
+                        OUTBOUND_DIRECTION_LABEL,
+                        message_label,
+                        peer_network_id,
+                    );
+                    return None;
+                },
+            }
+        }
+        Some(message)
+    }
+}
+
+/// Logs and counts a message dropped by the given interceptor
+fn log_and_count_dropped_message(
+    interceptor_name: &str,
+    direction_label: &str,
+    message_label: &str,
+    peer_network_id: &PeerNetworkId,
+) {
+    debug!(
+        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+            "Interceptor {} dropped {} message: {}, for peer: {}",
+            interceptor_name, direction_label, message_label, peer_network_id
+        ))
+    );
+    metrics::increment_interceptor_dropped_messages(
+        interceptor_name,
+        direction_label,
+        message_label,
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::network_message::ConsensusObserverRequest;
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        block_info::BlockInfo,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// A simple interceptor that counts all messages and drops unsubscribe requests
+    #[derive(Default)]
+    struct CountingInterceptor {
+        num_inbound_messages: AtomicU64,
+        num_outbound_messages: AtomicU64,
+    }
+
+    impl ConsensusObserverMessageInterceptor for CountingInterceptor {
+        fn name(&self) -> &'static str {
+            "counting_interceptor"
+        }
+
+        fn intercept_inbound_message(
+            &self,
+            _peer_network_id: &PeerNetworkId,
+            message: ConsensusObserverMessage,
+        ) -> InterceptorAction<ConsensusObserverMessage> {
+            self.num_inbound_messages.fetch_add(1, Ordering::Relaxed);
+            match message {
+                ConsensusObserverMessage::Request(ConsensusObserverRequest::Unsubscribe) => {
+                    InterceptorAction::Drop
+                },
+                message => InterceptorAction::Continue(message),
+            }
+        }
+
+        fn intercept_outbound_message(
+            &self,
+            _peer_network_id: &PeerNetworkId,
+            message: ConsensusObserverDirectSend,
+        ) -> InterceptorAction<ConsensusObserverDirectSend> {
+            self.num_outbound_messages.fetch_add(1, Ordering::Relaxed);
+            InterceptorAction::Continue(message)
+        }
+    }
+
+    /// A simple interceptor that drops all outbound messages
+    struct DropOutboundInterceptor;
+
+    impl ConsensusObserverMessageInterceptor for DropOutboundInterceptor {
+        fn name(&self) -> &'static str {
+            "drop_outbound_interceptor"
+        }
+
+        fn intercept_outbound_message(
+            &self,
+            _peer_network_id: &PeerNetworkId,
+            _message: ConsensusObserverDirectSend,
+        ) -> InterceptorAction<ConsensusObserverDirectSend> {
+            InterceptorAction::Drop
+        }
+    }
+
+    /// A simple interceptor that rewrites subscribe requests to unsubscribe requests
+    struct RewriteInterceptor;
+
+    impl ConsensusObserverMessageInterceptor for RewriteInterceptor {
+        fn name(&self) -> &'static str {
+            "rewrite_interceptor"
+        }
+
+        fn intercept_inbound_message(
+            &self,
+            _peer_network_id: &PeerNetworkId,
+            message: ConsensusObserverMessage,
+        ) -> InterceptorAction<ConsensusObserverMessage> {
+            match message {
+                ConsensusObserverMessage::Request(ConsensusObserverRequest::Subscribe) => {
+                    InterceptorAction::Continue(ConsensusObserverMessage::Request(
+                        ConsensusObserverRequest::Unsubscribe,
+                    ))
+                },
+                message => InterceptorAction::Continue(message),
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_chain() {
+        // Create an empty interceptor chain
+        let interceptor_chain = MessageInterceptorChain::new();
+        assert!(interceptor_chain.is_empty());
+
+        // Verify that inbound and outbound messages pass through unmodified
+        let peer_network_id = PeerNetworkId::random();
+        let inbound_message = interceptor_chain.intercept_inbound_message(
+            &peer_network_id,
+            ConsensusObserverMessage::Request(ConsensusObserverRequest::Subscribe),
+        );
+        assert!(matches!(
+            inbound_message,
+            Some(ConsensusObserverMessage::Request(
+                ConsensusObserverRequest::Subscribe
+            ))
+        ));
+        let outbound_message = interceptor_chain
+            .intercept_outbound_message(&peer_network_id, create_commit_decision_message());
+        assert!(matches!(
+            outbound_message,
+            Some(ConsensusObserverDirectSend::CommitDecision(_))
+        ));
+    }
+
+    #[test]
+    fn test_interceptor_chain() {
+        // Create an interceptor chain with a rewrite and a counting interceptor
+        let interceptor_chain = MessageInterceptorChain::new();
+        let counting_interceptor = Arc::new(CountingInterceptor::default());
+        interceptor_chain.add_interceptor(Arc::new(RewriteInterceptor));
+        interceptor_chain.add_interceptor(counting_interceptor.clone());
+        assert!(!interceptor_chain.is_empty());
+
+        // Verify that subscribe requests are rewritten and then dropped (by the second interceptor)
+        let peer_network_id = PeerNetworkId::random();
+        let inbound_message = interceptor_chain.intercept_inbound_message(
+            &peer_network_id,
+            ConsensusObserverMessage::Request(ConsensusObserverRequest::Subscribe),
+        );
+        assert!(inbound_message.is_none());
+
+        // Verify that other messages pass through the chain
+        let inbound_message = interceptor_chain.intercept_inbound_message(
+            &peer_network_id,
+            ConsensusObserverMessage::DirectSend(create_commit_decision_message()),
+        );
+        assert!(matches!(
+            inbound_message,
+            Some(ConsensusObserverMessage::DirectSend(
+                ConsensusObserverDirectSend::CommitDecision(_)
+            ))
+        ));
+        assert_eq!(
+            counting_interceptor
+                .num_inbound_messages
+                .load(Ordering::Relaxed),
+            2
+        );
+
+        // Verify that outbound messages pass through the chain
+        let outbound_message = interceptor_chain
+            .intercept_outbound_message(&peer_network_id, create_commit_decision_message());
+        assert!(outbound_message.is_some());
+        assert_eq!(
+            counting_interceptor
+                .num_outbound_messages
+                .load(Ordering::Relaxed),
+            1
+        );
+
+        // Add an interceptor that drops all outbound messages (via a clone of the chain)
+        interceptor_chain
+            .clone()
+            .add_interceptor(Arc::new(DropOutboundInterceptor));
+
+        // Verify that outbound messages are now dropped
+        let outbound_message = interceptor_chain
+            .intercept_outbound_message(&peer_network_id, create_commit_decision_message());
+        assert!(outbound_message.is_none());
+        assert_eq!(
+            counting_interceptor
+                .num_outbound_messages
+                .load(Ordering::Relaxed),
+            2
+        );
+    }
+
+    /// Creates and returns a simple commit decision message
+    fn create_commit_decision_message() -> ConsensusObserverDirectSend {
+        ConsensusObserverMessage::new_commit_decision_message(LedgerInfoWithSignatures::new(
+            LedgerInfo::new(BlockInfo::empty(), HashValue::zero()),
+            AggregateSignature::empty(),
+        ))
+    }
+}
@@ -0,0 +1,314 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// The decision of the rate limiter for an inbound message
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RateLimitDecision {
+    Allowed,         // The message is within the peer's budget (and should be processed)
+    Dropped,         // The message exceeds the peer's budget (and should be dropped)
+    BudgetExhausted, // The peer persistently exceeds its budget (and should be unsubscribed)
+}
+
+/// The token bucket (and rate limit violations) for a single peer
+#[derive(Clone, Copy, Debug)]
+struct TokenBucket {
+    // The number of tokens currently available (i.e., messages that can be processed)
+    available_tokens: f64,
+
+    // The time the tokens were last refilled
+    last_refill_time: Instant,
+
+    // The number of messages dropped within the current violation window
+    num_dropped_messages: u64,
+
+    // The start time of the current violation window
+    violation_window_start: Instant,
+}
+
+impl TokenBucket {
+    fn new(available_tokens: f64, time_now: Instant) -> Self {
+        Self {
+            available_tokens,
+            last_refill_time: time_now,
+            num_dropped_messages: 0,
+            violation_window_start: time_now,
+        }
+    }
+}
+
+/// A rate limiter for the messages received from each peer. Each peer has
+/// a token bucket that refills at a fixed rate (up to a maximum burst), and
+/// each message consumes a single token. Messages received without available
+/// tokens are dropped. If too many messages are dropped within a single window,
+/// the peer is deemed to persistently exceed its budget (e.g., because it is
+/// flooding the observer), and the caller should unsubscribe from the peer.
+pub struct InboundRateLimiter {
+    // The maximum number of tokens in each bucket (i.e., the maximum burst)
+    max_burst_tokens: f64,
+
+    // The number of tokens added to each bucket per second
+    refill_tokens_per_sec: f64,
+
+    // The maximum number of dropped messages within a window (before the budget is exhausted)
+    max_dropped_messages: u64,
+
+    // The window within which dropped messages are counted
+    violation_window: Duration,
+
+    // The token bucket of each peer
+    token_buckets: HashMap<PeerNetworkId, TokenBucket>,
+
+    // The time service (used to refill the buckets)
+    time_service: TimeService,
+}
+
+impl InboundRateLimiter {
+    pub fn new(
+        consensus_observer_config: ConsensusObserverConfig,
+        time_service: TimeService,
+    ) -> Self {
+        Self {
+            max_burst_tokens: consensus_observer_config.max_inbound_message_burst as f64,
+            refill_tokens_per_sec: consensus_observer_config.max_inbound_messages_per_sec as f64,
+            max_dropped_messages: consensus_observer_config.max_rate_limited_messages,
+            violation_window: Duration::from_millis(consensus_observer_config.rate_limit_window_ms),
+            token_buckets: HashMap::new(),
+            time_service,
+        }
+    }
+
+    /// Checks if a message from the given peer is within the peer's budget
+    /// (consuming a token if so). If the peer's budget is exhausted, the
+    /// peer's bucket is reset (so that a later subscription starts afresh).
+    pub fn check_message(&mut self, peer_network_id: &PeerNetworkId) -> RateLimitDecision {
+        // Get the token bucket for the peer
+        let time_now = self.time_service.now();
+        let max_burst_tokens = self.max_burst_tokens;
+        let token_bucket = self
+            .token_buckets
+            .entry(*peer_network_id)
+            .or_insert_with(|| TokenBucket::new(max_burst_tokens, time_now));
+
+        // Refill the tokens (based on the time elapsed since the last refill)
+        let elapsed_secs = time_now
+            .saturating_duration_since(token_bucket.last_refill_time)
+            .as_secs_f64();
+        token_bucket.available_tokens = (token_bucket.available_tokens
+            + elapsed_secs * self.refill_tokens_per_sec)
+            .min(max_burst_tokens);
+        token_bucket.last_refill_time = time_now;
+
+        // If a token is available, consume it and allow the message
+        if token_bucket.available_tokens >= 1.0 {
+            token_bucket.available_tokens -= 1.0;
+            return RateLimitDecision::Allowed;
+        }
+
+        // Otherwise, record the dropped message (starting a new window, if required)
+        if time_now.saturating_duration_since(token_bucket.violation_window_start)
+            > self.violation_window
+        {
+            token_bucket.violation_window_start = time_now;
+            token_bucket.num_dropped_messages = 0;
+        }
+        token_bucket.num_dropped_messages += 1;
+
+        // Check if the peer has exhausted its budget
+        if token_bucket.num_dropped_messages >= self.max_dropped_messages {
+            self.token_buckets.remove(peer_network_id);
+            RateLimitDecision::BudgetExhausted
+        } else {
+            RateLimitDecision::Dropped
+        }
+    }
+
+    /// Returns true iff the given peer has at least the given number of tokens
+    /// available (without consuming them). This allows callers to check the
+    /// budget before doing work on behalf of the peer (e.g., verifying proofs).
+    pub fn has_available_tokens(&self, peer_network_id: &PeerNetworkId, num_tokens: usize) -> bool {
+        let available_tokens = match self.token_buckets.get(peer_network_id) {
+            Some(token_bucket) => {
+                let elapsed_secs = self
+                    .time_service
+                    .now()
+                    .saturating_duration_since(token_bucket.last_refill_time)
+                    .as_secs_f64();
+                (token_bucket.available_tokens + elapsed_secs * self.refill_tokens_per_sec)
+                    .min(self.max_burst_tokens)
+            },
+            None => self.max_burst_tokens, // The peer has a full bucket
+        };
+        available_tokens >= num_tokens as f64
+    }
+
+    /// Removes the token buckets of all peers that are not in the given peers
+    pub fn garbage_collect(&mut self, subscription_peers: &[PeerNetworkId]) {
+        self.token_buckets
+            .retain(|peer_network_id, _| subscription_peers.contains(peer_network_id));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_messages() {
+        // Create a rate limiter (10 messages per second, with a burst of 5)
+        let consensus_observer_config = ConsensusObserverConfig {
+            max_inbound_messages_per_sec: 10,
+            max_inbound_message_burst: 5,
+            max_rate_limited_messages: 100,
+            ..ConsensusObserverConfig::default()
+        };
+        let time_service = TimeService::mock();
+        let mut rate_limiter =
+            InboundRateLimiter::new(consensus_observer_config, time_service.clone());
+
+        // Verify that the burst is allowed, and that subsequent messages are dropped
+        let peer_network_id = PeerNetworkId::random();
+        for _ in 0..5 {
+            assert_eq!(
+                rate_limiter.check_message(&peer_network_id),
+                RateLimitDecision::Allowed
+            );
+        }
+        assert_eq!(
+            rate_limiter.check_message(&peer_network_id),
+            RateLimitDecision::Dropped
+        );
+
+        // Verify that other peers have their own budgets
+        let other_peer_network_id = PeerNetworkId::random();
+        assert_eq!(
+            rate_limiter.check_message(&other_peer_network_id),
+            RateLimitDecision::Allowed
+        );
+
+        // Elapse some time and verify that the tokens are refilled (at the configured rate)
+        let time_service = time_service.into_mock();
+        time_service.advance(Duration::from_millis(200));
+        for _ in 0..2 {
+            assert_eq!(
+                rate_limiter.check_message(&peer_network_id),
+                RateLimitDecision::Allowed
+            );
+        }
+        assert_eq!(
+            rate_limiter.check_message(&peer_network_id),
+            RateLimitDecision::Dropped
+        );
+
+        // Elapse a long time and verify that the tokens are capped at the burst
+        time_service.advance(Duration::from_secs(100));
+        for _ in 0..5 {
+            assert_eq!(
+                rate_limiter.check_message(&peer_network_id),
+                RateLimitDecision::Allowed
+            );
+        }
+        assert_eq!(
+            rate_limiter.check_message(&peer_network_id),
+            RateLimitDecision::Dropped
+        );
+
+        // Garbage collect the other peer and verify only the first peer remains
+        rate_limiter.garbage_collect(&[peer_network_id]);
+        assert_eq!(rate_limiter.token_buckets.len(), 1);
+    }
+
+    #[test]
+    fn test_rate_limit_budget_exhausted() {
+        // Create a rate limiter (that allows 3 dropped messages within 10 seconds)
+        let consensus_observer_config = ConsensusObserverConfig {
+            max_inbound_messages_per_sec: 1,
+            max_inbound_message_burst: 1,
+            max_rate_limited_messages: 3,
+            rate_limit_window_ms: 10_000,
+            ..ConsensusObserverConfig::default()
+        };
+        let time_service = TimeService::mock();
+        let mut rate_limiter =
+            InboundRateLimiter::new(consensus_observer_config, time_service.clone());
+
+        // Exceed the budget (dropping two messages)
+        let peer_network_id = PeerNetworkId::random();
+        assert_eq!(
+            rate_limiter.check_message(&peer_network_id),
+            RateLimitDecision::Allowed
+        );
+        for _ in 0..2 {
+            assert_eq!(
+                rate_limiter.check_message(&peer_network_id),
+                RateLimitDecision::Dropped
+            );
+        }
+
+        // Elapse the window and verify that the dropped messages are reset
+        let time_service = time_service.into_mock();
+        time_service.advance(Duration::from_millis(10_500));
+        assert_eq!(
+            rate_limiter.check_message(&peer_network_id),
+            RateLimitDecision::Allowed
+        );
+        for _ in 0..2 {
+            assert_eq!(
+                rate_limiter.check_message(&peer_network_id),
+                RateLimitDecision::Dropped
+            );
+        }
+
+        // Persistently exceed the budget and verify that the budget is exhausted
+        assert_eq!(
+            rate_limiter.check_message(&peer_network_id),
+            RateLimitDecision::BudgetExhausted
+        );
+
+        // Verify that the peer's bucket is reset (i.e., the burst is allowed again)
+        assert_eq!(
+            rate_limiter.check_message(&peer_network_id),
+            RateLimitDecision::Allowed
+        );
+    }
+
+    #[test]
+    fn test_has_available_tokens() {
+        // Create a rate limiter (10 messages per second, with a burst of 5)
+        let consensus_observer_config = ConsensusObserverConfig {
+            max_inbound_messages_per_sec: 10,
+            max_inbound_message_burst: 5,
+            ..ConsensusObserverConfig::default()
+        };
+        let time_service = TimeService::mock();
+        let mut rate_limiter =
+            InboundRateLimiter::new(consensus_observer_config, time_service.clone());
+
+        // Verify that a new peer has the full burst available
+        let peer_network_id = PeerNetworkId::random();
+        assert!(rate_limiter.has_available_tokens(&peer_network_id, 5));
+        assert!(!rate_limiter.has_available_tokens(&peer_network_id, 6));
+
+        // Consume the burst and verify that checking the tokens doesn't consume them
+        for _ in 0..4 {
+            rate_limiter.check_message(&peer_network_id);
+        }
+        assert!(rate_limiter.has_available_tokens(&peer_network_id, 1));
+        assert!(rate_limiter.has_available_tokens(&peer_network_id, 1));
+        assert!(!rate_limiter.has_available_tokens(&peer_network_id, 2));
+        rate_limiter.check_message(&peer_network_id);
+        assert!(!rate_limiter.has_available_tokens(&peer_network_id, 1));
+
+        // Elapse some time and verify that the refilled tokens are available
+        let time_service = time_service.into_mock();
+        time_service.advance(Duration::from_millis(200));
+        assert!(rate_limiter.has_available_tokens(&peer_network_id, 2));
+        assert!(!rate_limiter.has_available_tokens(&peer_network_id, 3));
+    }
+}
@@ -1,15 +1,276 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_config::{
+    config::ConsensusObserverConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_infallible::Mutex;
 use aptos_metrics_core::{
-    register_histogram_vec, register_int_counter_vec, register_int_gauge_vec, HistogramVec,
-    IntCounterVec, IntGaugeVec,
+    register_histogram, register_histogram_vec, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, Histogram, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
+};
+use once_cell::sync::{Lazy, OnceCell};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
 };
-use once_cell::sync::Lazy;
 
 // Useful metric labels
+pub const BACKFILL_FAILED_LABEL: &str = "failed";
+pub const BACKFILL_SKIPPED_LABEL: &str = "skipped";
+pub const BACKFILL_SUCCEEDED_LABEL: &str = "succeeded";
+pub const BLOCK_SOURCE_BRANCH_LABEL: &str = "block_source";
+pub const CONTROL_COMMAND_BRANCH_LABEL: &str = "control_command";
+pub const CREATED_BACKUP_SUBSCRIPTION_LABEL: &str = "created_backup_subscription";
 pub const CREATED_SUBSCRIPTION_LABEL: &str = "created_subscription";
+pub const EPOCH_SUMMARY_AVERAGE_COMMIT_LAG_MS_LABEL: &str = "average_commit_lag_ms";
+pub const EPOCH_SUMMARY_BLOCKS_OBSERVED_LABEL: &str = "blocks_observed";
+pub const EPOCH_SUMMARY_COMMITS_APPLIED_LABEL: &str = "commits_applied";
+pub const EPOCH_SUMMARY_EPOCH_LABEL: &str = "epoch";
+pub const EPOCH_SUMMARY_SUBSCRIPTION_SWITCHES_LABEL: &str = "subscription_switches";
+pub const EPOCH_SUMMARY_SYNC_FALLBACKS_LABEL: &str = "sync_fallbacks";
+pub const FINALIZE_ORDERED_BLOCK_LABEL: &str = "finalize_ordered_block";
+pub const FORWARD_COMMIT_DECISION_LABEL: &str = "forward_commit_decision";
+pub const FUTURE_COMMIT_BUFFERED_LABEL: &str = "buffered";
+pub const FUTURE_COMMIT_EXPIRED_LABEL: &str = "expired";
+pub const FUTURE_COMMIT_REJECTED_LABEL: &str = "rejected";
+pub const FUTURE_COMMIT_VERIFIED_LABEL: &str = "verified";
+pub const OTHER_PEER_LABEL: &str = "other";
+pub const PAYLOAD_BLOCK_MISMATCH_LABEL: &str = "block_mismatch";
+pub const PAYLOAD_DIGEST_MISMATCH_LABEL: &str = "digest_mismatch";
+pub const PAYLOAD_INVALID_PROOFS_LABEL: &str = "invalid_proofs";
+pub const PAYLOAD_REQUEST_FAILED_LABEL: &str = "failed";
+pub const PAYLOAD_REQUEST_NOT_FOUND_LABEL: &str = "not_found";
+pub const PAYLOAD_REQUEST_RECEIVED_LABEL: &str = "received";
+pub const PAYLOAD_STORE_BYTES_LABEL: &str = "bytes";
+pub const PAYLOAD_STORE_ENTRIES_LABEL: &str = "entries";
+pub const PREFETCH_DEDUPLICATED_LABEL: &str = "deduplicated";
+pub const PREFETCH_MISSING_BATCHES_LABEL: &str = "missing_batches";
+pub const PREFETCH_PUBLISHED_LABEL: &str = "published";
+pub const PROBE_FAILED_LABEL: &str = "failed";
+pub const PROBE_HEALTHY_LABEL: &str = "healthy";
+pub const PROBE_STALE_LABEL: &str = "stale";
+pub const PROGRESS_CHECK_BRANCH_LABEL: &str = "progress_check";
+pub const PROOF_VERIFICATION_INVALID_LABEL: &str = "invalid";
+pub const PROOF_VERIFICATION_SKIPPED_LABEL: &str = "skipped";
+pub const PROOF_VERIFICATION_VALID_LABEL: &str = "valid";
+pub const PUBLISHER_OUTBOUND_CHANNEL_LABEL: &str = "publisher_outbound_messages";
+pub const SPECULATIVE_FORWARDED_LABEL: &str = "forwarded";
+pub const SPECULATIVE_REJECTED_LABEL: &str = "rejected";
+pub const SYNCED_COMMIT_BRANCH_LABEL: &str = "synced_commit";
+pub const SYNC_NOTIFICATION_BRANCH_LABEL: &str = "sync_notification";
+pub const SYNC_NOTIFICATION_REPLACED_LABEL: &str = "replaced";
+pub const SYNC_NOTIFICATION_STALE_LABEL: &str = "stale";
+pub const SYNC_NOTIFICATIONS_CHANNEL_LABEL: &str = "sync_notifications";
+pub const TEARDOWN_RESET_LABEL: &str = "reset";
+pub const TEARDOWN_SYNCED_ROOT_LABEL: &str = "synced_root";
+pub const TIMESTAMP_FLAGGED_LABEL: &str = "flagged";
+pub const TIMESTAMP_REJECTED_LABEL: &str = "rejected";
+pub const UNSUBSCRIBE_FAILED_LABEL: &str = "failed";
+pub const UNSUBSCRIBE_SUCCESS_LABEL: &str = "success";
+pub const UNSUBSCRIBE_TIMEOUT_LABEL: &str = "timeout";
+
+/// The guard used to cap the number of distinct peer labels across
+/// all peer-labeled observer and publisher metrics. This is initialized
+/// once (at node startup) using the consensus observer config.
+static PEER_LABEL_GUARD: OnceCell<PeerLabelGuard> = OnceCell::new();
+
+/// A simple guard that limits the cardinality of peer labels. Once the maximum
+/// number of distinct peers has been labeled, all new peers share a single
+/// "other" label (to avoid overwhelming the metric backends). The labels of
+/// disconnected peers are released, so that new peers can be labeled.
+pub struct PeerLabelGuard {
+    // The maximum number of distinct peer labels
+    max_num_peer_labels: u64,
+
+    // The peers that have been assigned a distinct label (and the
+    // message labels that have been recorded for each peer).
+    labeled_peers: Mutex<HashMap<PeerNetworkId, HashSet<&'static str>>>,
+}
+
+impl PeerLabelGuard {
+    pub fn new(max_num_peer_labels: u64) -> Self {
+        Self {
+            max_num_peer_labels,
+            labeled_peers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the metric label for the given peer. If the peer has not
+    /// been labeled and the maximum number of labels has been reached,
+    /// the "other" label is returned instead.
+    pub fn get_peer_label(&self, peer_network_id: &PeerNetworkId) -> String {
+        self.get_peer_label_for_message(peer_network_id, None)
+    }
+
+    /// Returns the metric label for the given peer, and records the message
+    /// label (if any) against the peer (so that the series can be removed
+    /// when the peer label is released).
+    fn get_peer_label_for_message(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        message_label: Option<&'static str>,
+    ) -> String {
+        let mut labeled_peers = self.labeled_peers.lock();
+
+        // Check if we can label the peer (if it hasn't already been labeled)
+        if !labeled_peers.contains_key(peer_network_id) {
+            if (labeled_peers.len() as u64) >= self.max_num_peer_labels {
+                return OTHER_PEER_LABEL.into();
+            }
+            labeled_peers.insert(*peer_network_id, HashSet::new());
+        }
+
+        // Record the message label for the peer
+        if let Some(message_label) = message_label {
+            if let Some(message_labels) = labeled_peers.get_mut(peer_network_id) {
+                message_labels.insert(message_label);
+            }
+        }
+
+        peer_network_id.peer_id().to_string()
+    }
+
+    /// Releases the labels of all peers that are no longer connected, and
+    /// returns the released peers (along with their recorded message labels).
+    pub fn release_disconnected_peers(
+        &self,
+        connected_peers: &HashSet<PeerNetworkId>,
+    ) -> Vec<(PeerNetworkId, HashSet<&'static str>)> {
+        let mut labeled_peers = self.labeled_peers.lock();
+        let disconnected_peers: Vec<PeerNetworkId> = labeled_peers
+            .keys()
+            .filter(|peer_network_id| !connected_peers.contains(peer_network_id))
+            .cloned()
+            .collect();
+        disconnected_peers
+            .into_iter()
+            .filter_map(|peer_network_id| {
+                labeled_peers
+                    .remove(&peer_network_id)
+                    .map(|message_labels| (peer_network_id, message_labels))
+            })
+            .collect()
+    }
+}
+
+impl Default for PeerLabelGuard {
+    fn default() -> Self {
+        Self::new(ConsensusObserverConfig::default().max_num_peer_metric_labels)
+    }
+}
+
+/// Returns the peer label guard (using the default config if it
+/// has not been initialized, e.g., in tests).
+fn get_peer_label_guard() -> &'static PeerLabelGuard {
+    PEER_LABEL_GUARD.get_or_init(PeerLabelGuard::default)
+}
+
+/// Initializes the maximum number of distinct peer labels used by the
+/// metrics. This should be called once (at node startup), before any
+/// peer-labeled metrics are updated. Subsequent calls have no effect.
+pub fn initialize_peer_label_guard(max_num_peer_labels: u64) {
+    let _ = PEER_LABEL_GUARD.set(PeerLabelGuard::new(max_num_peer_labels));
+}
+
+/// Returns the (cardinality guarded) metric label for the given peer
+pub fn get_peer_label(peer_network_id: &PeerNetworkId) -> String {
+    get_peer_label_guard().get_peer_label(peer_network_id)
+}
+
+/// Releases the labels of all disconnected peers, and removes the
+/// peer-labeled metric series for those peers. This frees up the
+/// labels for new peers (and keeps the metric cardinality bounded).
+pub fn garbage_collect_peer_labels(connected_peers: &HashSet<PeerNetworkId>) {
+    let released_peers = get_peer_label_guard().release_disconnected_peers(connected_peers);
+    for (peer_network_id, message_labels) in released_peers {
+        let peer_label = peer_network_id.peer_id().to_string();
+        let network_id = peer_network_id.network_id();
+
+        // Remove the per-peer message series
+        for message_label in message_labels {
+            let label_values = [message_label, peer_label.as_str(), network_id.as_str()];
+            let _ = OBSERVER_RECEIVED_MESSAGES_PER_PEER.remove_label_values(&label_values);
+            let _ = OBSERVER_RECEIVED_MESSAGE_BYTES_PER_PEER.remove_label_values(&label_values);
+            let _ = PUBLISHER_SENT_MESSAGE_BYTES_PER_PEER.remove_label_values(&label_values);
+        }
+
+        // Remove the active subscription series
+        let _ = OBSERVER_ACTIVE_SUBSCRIPTION_PEER
+            .remove_label_values(&[peer_label.as_str(), network_id.as_str()]);
+    }
+}
+
+/// Gauge for tracking the peer of the active subscription for the consensus observer
+pub static OBSERVER_ACTIVE_SUBSCRIPTION_PEER: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "consensus_observer_active_subscription_peer",
+        "Gauge identifying the peer of the active subscription for the consensus observer",
+        &["peer_id", "network_id"]
+    )
+    .unwrap()
+});
+
+/// Gauge for tracking the number of active background tasks spawned by the consensus observer
+pub static OBSERVER_ACTIVE_TASKS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "consensus_observer_active_tasks",
+        "Gauge related to the active background tasks spawned by the consensus observer",
+        &["task_name"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the requests for missing block payloads (by outcome)
+pub static OBSERVER_BLOCK_PAYLOAD_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_block_payload_requests",
+        "Counters for the requests for missing block payloads (by outcome)",
+        &["outcome"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the messages received by the consensus observer from block sources
+pub static OBSERVER_BLOCK_SOURCE_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_block_source_messages",
+        "Counters related to the messages received by the consensus observer from block sources",
+        &["source_label", "message_type"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the observed blocks with invalid timestamps
+pub static OBSERVER_BLOCK_TIMESTAMP_VIOLATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_block_timestamp_violations",
+        "Counters for observed blocks with invalid timestamps (by the action taken)",
+        &["action"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the messages dropped for being beyond the buffering horizon
+pub static OBSERVER_BUFFERING_HORIZON_DROPS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_buffering_horizon_drops",
+        "Counters for the messages dropped for being too many rounds ahead of the root",
+        &["message_type"]
+    )
+    .unwrap()
+});
+
+/// Gauge for tracking the queue depths of the internal observer and publisher channels
+pub static OBSERVER_CHANNEL_QUEUE_DEPTHS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "consensus_observer_channel_queue_depths",
+        "Gauge related to the queue depths of the internal observer and publisher channels",
+        &["channel"]
+    )
+    .unwrap()
+});
 
 /// Counter for tracking created subscriptions for the consensus observer
 pub static OBSERVER_CREATED_SUBSCRIPTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
@@ -21,7 +282,97 @@ pub static OBSERVER_CREATED_SUBSCRIPTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
-/// Counter for tracking the number of active subscriptions for the consensus observer
+/// Counter for tracking the sync notifications dropped by the consensus observer
+pub static OBSERVER_DROPPED_SYNC_NOTIFICATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_dropped_sync_notifications",
+        "Counters for the sync notifications dropped by the consensus observer",
+        &["drop_reason"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the identical messages received across concurrent subscriptions
+pub static OBSERVER_DUPLICATE_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_duplicate_messages",
+        "Counters for the identical messages dropped across concurrent subscriptions",
+        &["message_type"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the equivocations detected for subscription peers
+pub static OBSERVER_EQUIVOCATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_equivocations",
+        "Counters for the equivocations (i.e., conflicting ordered blocks) detected by consensus observer",
+        &["network_id"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the commit decisions received for future epochs (by outcome)
+pub static OBSERVER_FUTURE_COMMIT_DECISIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_future_commit_decisions",
+        "Counters for the commit decisions received for future epochs (by outcome)",
+        &["outcome"]
+    )
+    .unwrap()
+});
+
+/// Gauge for tracking the observer instances managed by the supervisor
+pub static OBSERVER_INSTANCES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "consensus_observer_instances",
+        "Gauge indicating the running consensus observer instances (by instance name)",
+        &["instance_name"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking messages dropped by the message interceptors
+pub static OBSERVER_INTERCEPTOR_DROPPED_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_interceptor_dropped_messages",
+        "Counters for messages dropped by the consensus observer message interceptors",
+        &["interceptor_name", "direction", "message_type"]
+    )
+    .unwrap()
+});
+
+/// Gauge for tracking the summary statistics of the last completed epoch
+pub static OBSERVER_LAST_EPOCH_SUMMARY: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "consensus_observer_last_epoch_summary",
+        "Gauge related to the summary statistics of the last completed epoch",
+        &["summary_label"]
+    )
+    .unwrap()
+});
+
+/// Histogram for tracking the processing time (in seconds) of each observer loop branch
+pub static OBSERVER_LOOP_BRANCH_PROCESSING_TIMES: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "consensus_observer_loop_branch_processing_times",
+        "Histogram related to the processing time (in seconds) of each observer loop branch",
+        &["branch"]
+    )
+    .unwrap()
+});
+
+/// Histogram for tracking the latency (in seconds) of each observer loop iteration
+/// (i.e., the time spent waiting for an event, and then processing the event).
+pub static OBSERVER_LOOP_ITERATION_LATENCIES: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "consensus_observer_loop_iteration_latencies",
+        "Histogram related to the latency (in seconds) of each observer loop iteration"
+    )
+    .unwrap()
+});
+
+/// Gauge for tracking the number of active subscriptions for the consensus observer
 pub static OBSERVER_NUM_ACTIVE_SUBSCRIPTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "consensus_observer_num_active_subscriptions",
@@ -31,6 +382,155 @@ pub static OBSERVER_NUM_ACTIVE_SUBSCRIPTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking the attempts to backfill missing ordered blocks (by outcome)
+pub static OBSERVER_ORDERED_BLOCK_BACKFILLS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_ordered_block_backfills",
+        "Counters for the attempts to backfill missing ordered blocks (by outcome)",
+        &["outcome"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the proofs verified off the observer loop (by result)
+pub static OBSERVER_PARALLEL_PROOF_VERIFICATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_parallel_proof_verifications",
+        "Counters for the proofs verified off the observer loop (by verification result)",
+        &["verification_result"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the block payloads rejected due to batch digest mismatches
+pub static OBSERVER_PAYLOAD_DIGEST_MISMATCHES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_payload_digest_mismatches",
+        "Counters for the block payloads rejected due to quorum store batch digest mismatches",
+        &["message_type"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the payloads evicted from the payload store (by exceeded limit)
+pub static OBSERVER_PAYLOAD_STORE_EVICTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_payload_store_evictions",
+        "Counters for the payloads evicted from the payload store (by exceeded limit)",
+        &["limit_type"]
+    )
+    .unwrap()
+});
+
+/// Gauge for tracking the occupancy (i.e., number of payloads and bytes) of the payload store
+pub static OBSERVER_PAYLOAD_STORE_OCCUPANCY: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "consensus_observer_payload_store_occupancy",
+        "Gauges for the occupancy (i.e., number of payloads and bytes) of the payload store",
+        &["occupancy_type"]
+    )
+    .unwrap()
+});
+
+/// Gauge for tracking the auto-tuned target capacity (in bytes) of the payload store
+pub static OBSERVER_PAYLOAD_STORE_TARGET_CAPACITY_BYTES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "consensus_observer_payload_store_target_capacity_bytes",
+        "Gauge for the auto-tuned target capacity (in bytes) of the payload store"
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the misbehavior reported for peers (by the observer and publisher)
+pub static OBSERVER_PEER_MISBEHAVIORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_peer_misbehaviors",
+        "Counters for the misbehavior reported for peers by consensus observer and publisher",
+        &["misbehavior_label", "network_id"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the peers disconnected due to misbehavior
+pub static OBSERVER_PEER_MISBEHAVIOR_DISCONNECTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_peer_misbehavior_disconnects",
+        "Counters for the peers disconnected due to misbehavior by consensus observer and publisher",
+        &["network_id"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the reputation events recorded for subscription peers
+pub static OBSERVER_PEER_REPUTATION_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_peer_reputation_events",
+        "Counters for the reputation events recorded for subscription peers by consensus observer",
+        &["event_label", "network_id"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the pipeline handoffs that missed their processing deadlines
+pub static OBSERVER_PIPELINE_DEADLINE_MISSES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_pipeline_deadline_misses",
+        "Counters for the pipeline handoffs that missed their processing deadlines",
+        &["handoff_label"]
+    )
+    .unwrap()
+});
+
+/// Gauge for tracking the outstanding pipeline handoffs that are past their deadlines
+pub static OBSERVER_PIPELINE_OVERDUE_HANDOFFS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "consensus_observer_pipeline_overdue_handoffs",
+        "Gauges for the outstanding pipeline handoffs that are past their deadlines",
+        &["handoff_label"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking failures to send blocks and commits to the execution pipeline
+pub static OBSERVER_PIPELINE_FAILURES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_pipeline_failures",
+        "Counters for failures to send blocks and commits to the execution pipeline",
+        &["failure_label"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the escalations performed due to repeated pipeline failures
+pub static OBSERVER_PIPELINE_FAILURE_ESCALATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_pipeline_failure_escalations",
+        "Counters for the escalations performed due to repeated pipeline failures",
+        &["escalation_label"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the failed steps of the pipeline teardown (around state syncs)
+pub static OBSERVER_PIPELINE_TEARDOWN_FAILURES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_pipeline_teardown_failures",
+        "Counters for the failed steps of the pipeline teardown (around state syncs)",
+        &["teardown_step"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the messages dropped by the inbound rate limiter
+pub static OBSERVER_RATE_LIMITED_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_rate_limited_messages",
+        "Counters related to the messages dropped by the consensus observer rate limiter",
+        &["message_type", "network_id"]
+    )
+    .unwrap()
+});
+
 /// Counter for tracking successful RPC responses received by the consensus observer
 pub static OBSERVER_RECEIVED_MESSAGE_RESPONSES: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -51,6 +551,36 @@ pub static OBSERVER_RECEIVED_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking the bytes of messages received by the consensus observer (per peer)
+pub static OBSERVER_RECEIVED_MESSAGE_BYTES_PER_PEER: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_received_message_bytes_per_peer",
+        "Counters related to the bytes of messages received by the consensus observer (per peer)",
+        &["message_type", "peer_id", "network_id"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the messages received by the consensus observer (per peer)
+pub static OBSERVER_RECEIVED_MESSAGES_PER_PEER: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_received_messages_per_peer",
+        "Counters related to the messages received by the consensus observer (per peer)",
+        &["message_type", "peer_id", "network_id"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the block payloads rejected by verification (by reason)
+pub static OBSERVER_REJECTED_BLOCK_PAYLOADS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_rejected_block_payloads",
+        "Counters for the block payloads rejected by verification against the ordered blocks",
+        &["rejection_reason"]
+    )
+    .unwrap()
+});
+
 /// Counter for tracking RPC request latencies sent by the consensus observer
 pub static OBSERVER_REQUEST_LATENCIES: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -81,6 +611,46 @@ pub static OBSERVER_SENT_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking the ordered blocks speculatively forwarded by the consensus observer
+pub static OBSERVER_SPECULATIVE_FORWARDS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_speculative_forwards",
+        "Counters related to the ordered blocks speculatively forwarded by the consensus observer",
+        &["forward_result"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking subscription state transitions for the consensus observer
+pub static OBSERVER_SUBSCRIPTION_STATE_TRANSITIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_subscription_state_transitions",
+        "Counters for subscription state transitions for the consensus observer",
+        &["from_state", "to_state"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the results of the probes sent before subscription timeouts
+pub static OBSERVER_SUBSCRIPTION_PROBES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_subscription_probes",
+        "Counters for the results of the probes sent before subscription timeouts",
+        &["probe_result"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the failovers to backup subscriptions for the consensus observer
+pub static OBSERVER_SUBSCRIPTION_FAILOVERS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_subscription_failovers",
+        "Counters for the failovers from the active subscription to a backup subscription",
+        &["network_id"]
+    )
+    .unwrap()
+});
+
 /// Counter for tracking terminated subscriptions for the consensus observer
 pub static OBSERVER_TERMINATED_SUBSCRIPTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -91,6 +661,26 @@ pub static OBSERVER_TERMINATED_SUBSCRIPTIONS: Lazy<IntCounterVec> = Lazy::new(||
     .unwrap()
 });
 
+/// Counter for tracking the cumulative time (in milliseconds) the observer spent in each state
+pub static OBSERVER_TIME_IN_STATE_MS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_time_in_state_ms",
+        "Counters for the cumulative time (in milliseconds) the observer spent in each state",
+        &["observer_state"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the outcomes of unsubscribe requests sent by the consensus observer
+pub static OBSERVER_UNSUBSCRIBE_OUTCOMES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_observer_unsubscribe_outcomes",
+        "Counters for the outcomes of unsubscribe requests sent by consensus observer",
+        &["outcome_label", "network_id"]
+    )
+    .unwrap()
+});
+
 /// Counter for pending network events for consensus observer and publisher
 pub static PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -111,6 +701,16 @@ pub static PUBLISHER_NUM_ACTIVE_SUBSCRIBERS: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking the payloads prefetched and published by the consensus publisher
+pub static PUBLISHER_PREFETCHED_PAYLOADS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_publisher_prefetched_payloads",
+        "Counters related to the payloads prefetched and published by the consensus publisher",
+        &["prefetch_result"]
+    )
+    .unwrap()
+});
+
 /// Counter for tracking received RPC requests by the consensus publisher
 pub static PUBLISHER_RECEIVED_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -121,6 +721,16 @@ pub static PUBLISHER_RECEIVED_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking the messages replayed to new subscribers by the consensus publisher
+pub static PUBLISHER_REPLAYED_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_publisher_replayed_messages",
+        "Counters related to the messages replayed to new subscribers by the consensus publisher",
+        &["message_type"]
+    )
+    .unwrap()
+});
+
 /// Counter for tracking sent (direct send) message errors for the consensus publisher
 pub static PUBLISHER_SENT_MESSAGE_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -131,6 +741,16 @@ pub static PUBLISHER_SENT_MESSAGE_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking the bytes of messages sent by the consensus publisher (per peer)
+pub static PUBLISHER_SENT_MESSAGE_BYTES_PER_PEER: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "consensus_publisher_sent_message_bytes_per_peer",
+        "Counters related to the bytes of messages sent by the consensus publisher (per peer)",
+        &["message_type", "peer_id", "network_id"]
+    )
+    .unwrap()
+});
+
 /// Counter for tracking sent (direct send) messages by the consensus publisher
 pub static PUBLISHER_SENT_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -166,7 +786,379 @@ pub fn observe_value_with_label(
         .observe(value)
 }
 
+/// Updates the per-peer message and byte counters for a message received from the given peer
+pub fn increment_received_message_counters_for_peer(
+    peer_network_id: &PeerNetworkId,
+    message_label: &'static str,
+    num_message_bytes: u64,
+) {
+    let peer_label =
+        get_peer_label_guard().get_peer_label_for_message(peer_network_id, Some(message_label));
+    let network_id = peer_network_id.network_id();
+    let label_values = [message_label, peer_label.as_str(), network_id.as_str()];
+    OBSERVER_RECEIVED_MESSAGES_PER_PEER
+        .with_label_values(&label_values)
+        .inc();
+    OBSERVER_RECEIVED_MESSAGE_BYTES_PER_PEER
+        .with_label_values(&label_values)
+        .inc_by(num_message_bytes);
+}
+
+/// Updates the per-peer byte counter for a message sent by the publisher to the given peer
+pub fn increment_sent_message_bytes_for_peer(
+    peer_network_id: &PeerNetworkId,
+    message_label: &'static str,
+    num_message_bytes: u64,
+) {
+    let peer_label =
+        get_peer_label_guard().get_peer_label_for_message(peer_network_id, Some(message_label));
+    let network_id = peer_network_id.network_id();
+    PUBLISHER_SENT_MESSAGE_BYTES_PER_PEER
+        .with_label_values(&[message_label, peer_label.as_str(), network_id.as_str()])
+        .inc_by(num_message_bytes);
+}
+
+/// Increments the dropped message counter for the given interceptor
+pub fn increment_interceptor_dropped_messages(
+    interceptor_name: &str,
+    direction_label: &str,
+    message_label: &str,
+) {
+    OBSERVER_INTERCEPTOR_DROPPED_MESSAGES
+        .with_label_values(&[interceptor_name, direction_label, message_label])
+        .inc();
+}
+
+/// Decrements the active task gauge for the given task name
+pub fn decrement_active_tasks(task_name: &str) {
+    OBSERVER_ACTIVE_TASKS.with_label_values(&[task_name]).dec();
+}
+
+/// Decrements the queue depth gauge for the given channel (i.e., an item was dequeued)
+pub fn decrement_channel_queue_depth(channel_label: &str) {
+    OBSERVER_CHANNEL_QUEUE_DEPTHS
+        .with_label_values(&[channel_label])
+        .dec();
+}
+
+/// Increments the active task gauge for the given task name
+pub fn increment_active_tasks(task_name: &str) {
+    OBSERVER_ACTIVE_TASKS.with_label_values(&[task_name]).inc();
+}
+
+/// Increments the block source message counter for the given source and message type
+pub fn increment_block_source_messages(source_label: &str, message_type: &str) {
+    OBSERVER_BLOCK_SOURCE_MESSAGES
+        .with_label_values(&[source_label, message_type])
+        .inc();
+}
+
+/// Increments the block payload request counter for the given outcome
+pub fn increment_block_payload_requests(outcome: &str) {
+    OBSERVER_BLOCK_PAYLOAD_REQUESTS
+        .with_label_values(&[outcome])
+        .inc();
+}
+
+/// Increments the block timestamp violation counter for the given action
+pub fn increment_block_timestamp_violations(action_label: &str) {
+    OBSERVER_BLOCK_TIMESTAMP_VIOLATIONS
+        .with_label_values(&[action_label])
+        .inc();
+}
+
+/// Increments the buffering horizon drop counter for the given message type
+pub fn increment_buffering_horizon_drops(message_type: &str) {
+    OBSERVER_BUFFERING_HORIZON_DROPS
+        .with_label_values(&[message_type])
+        .inc();
+}
+
+/// Increments the queue depth gauge for the given channel (i.e., an item was enqueued)
+pub fn increment_channel_queue_depth(channel_label: &str) {
+    OBSERVER_CHANNEL_QUEUE_DEPTHS
+        .with_label_values(&[channel_label])
+        .inc();
+}
+
+/// Increments the dropped sync notification counter for the given reason
+pub fn increment_dropped_sync_notifications(drop_reason: &str) {
+    OBSERVER_DROPPED_SYNC_NOTIFICATIONS
+        .with_label_values(&[drop_reason])
+        .inc();
+}
+
+/// Increments the duplicate message counter for the given message type
+pub fn increment_duplicate_messages(message_type: &str) {
+    OBSERVER_DUPLICATE_MESSAGES
+        .with_label_values(&[message_type])
+        .inc();
+}
+
+/// Increments the equivocation counter for the given network
+pub fn increment_equivocations(network_id: &NetworkId) {
+    OBSERVER_EQUIVOCATIONS
+        .with_label_values(&[network_id.as_str()])
+        .inc();
+}
+
+/// Increments the future commit decision counter for the given outcome
+pub fn increment_future_commit_decisions(outcome: &str) {
+    OBSERVER_FUTURE_COMMIT_DECISIONS
+        .with_label_values(&[outcome])
+        .inc();
+}
+
+/// Increments the ordered block backfill counter for the given outcome
+pub fn increment_ordered_block_backfills(outcome: &str) {
+    OBSERVER_ORDERED_BLOCK_BACKFILLS
+        .with_label_values(&[outcome])
+        .inc();
+}
+
+/// Increments the parallel proof verification counter for the given result
+pub fn increment_parallel_proof_verifications(verification_result: &str) {
+    OBSERVER_PARALLEL_PROOF_VERIFICATIONS
+        .with_label_values(&[verification_result])
+        .inc();
+}
+
+/// Increments the payload digest mismatch counter for the given message type
+pub fn increment_payload_digest_mismatch(message_type: &str) {
+    OBSERVER_PAYLOAD_DIGEST_MISMATCHES
+        .with_label_values(&[message_type])
+        .inc();
+}
+
+/// Increments the eviction counter of the payload store for the given limit
+pub fn increment_payload_store_evictions(limit_label: &str, num_evictions: u64) {
+    OBSERVER_PAYLOAD_STORE_EVICTIONS
+        .with_label_values(&[limit_label])
+        .inc_by(num_evictions);
+}
+
+/// Increments the misbehavior disconnect counter for the given network
+pub fn increment_peer_misbehavior_disconnects(network_id: &NetworkId) {
+    OBSERVER_PEER_MISBEHAVIOR_DISCONNECTS
+        .with_label_values(&[network_id.as_str()])
+        .inc();
+}
+
+/// Increments the reputation event counter for the given event and network
+pub fn increment_peer_reputation_events(event_label: &str, network_id: &NetworkId) {
+    OBSERVER_PEER_REPUTATION_EVENTS
+        .with_label_values(&[event_label, network_id.as_str()])
+        .inc();
+}
+
+/// Increments the pipeline deadline miss counter for the given handoff
+pub fn increment_pipeline_deadline_miss(handoff_label: &str) {
+    OBSERVER_PIPELINE_DEADLINE_MISSES
+        .with_label_values(&[handoff_label])
+        .inc();
+}
+
+/// Increments the pipeline failure counter for the given failure
+pub fn increment_pipeline_failure(failure_label: &str) {
+    OBSERVER_PIPELINE_FAILURES
+        .with_label_values(&[failure_label])
+        .inc();
+}
+
+/// Increments the pipeline failure escalation counter for the given escalation
+pub fn increment_pipeline_failure_escalation(escalation_label: &str) {
+    OBSERVER_PIPELINE_FAILURE_ESCALATIONS
+        .with_label_values(&[escalation_label])
+        .inc();
+}
+
+/// Increments the pipeline teardown failure counter for the given teardown step
+pub fn increment_pipeline_teardown_failure(teardown_step: &str) {
+    OBSERVER_PIPELINE_TEARDOWN_FAILURES
+        .with_label_values(&[teardown_step])
+        .inc();
+}
+
+/// Increments the prefetched payload counter for the given prefetch result
+pub fn increment_prefetched_payloads(prefetch_result: &str) {
+    PUBLISHER_PREFETCHED_PAYLOADS
+        .with_label_values(&[prefetch_result])
+        .inc();
+}
+
+/// Increments the rejected block payload counter for the given reason
+pub fn increment_rejected_block_payloads(rejection_reason: &str) {
+    OBSERVER_REJECTED_BLOCK_PAYLOADS
+        .with_label_values(&[rejection_reason])
+        .inc();
+}
+
+/// Increments the replayed message counter for the given message type
+pub fn increment_replayed_messages(message_type: &str) {
+    PUBLISHER_REPLAYED_MESSAGES
+        .with_label_values(&[message_type])
+        .inc();
+}
+
+/// Increments the speculative forward counter for the given forward result
+pub fn increment_speculative_forwards(forward_result: &str) {
+    OBSERVER_SPECULATIVE_FORWARDS
+        .with_label_values(&[forward_result])
+        .inc();
+}
+
+/// Increments the subscription failover counter for the given network
+pub fn increment_subscription_failovers(network_id: &NetworkId) {
+    OBSERVER_SUBSCRIPTION_FAILOVERS
+        .with_label_values(&[network_id.as_str()])
+        .inc();
+}
+
+/// Increments the subscription state transition counter for the given states
+pub fn increment_subscription_state_transition(from_state_label: &str, to_state_label: &str) {
+    OBSERVER_SUBSCRIPTION_STATE_TRANSITIONS
+        .with_label_values(&[from_state_label, to_state_label])
+        .inc();
+}
+
+/// Increments the subscription probe counter for the given probe result
+pub fn increment_subscription_probes(probe_result: &str) {
+    OBSERVER_SUBSCRIPTION_PROBES
+        .with_label_values(&[probe_result])
+        .inc();
+}
+
+/// Increments the time in state counter for the given state
+pub fn increment_time_in_state(state_label: &str, time_in_state: Duration) {
+    OBSERVER_TIME_IN_STATE_MS
+        .with_label_values(&[state_label])
+        .inc_by(time_in_state.as_millis() as u64);
+}
+
+/// Increments the unsubscribe outcome counter for the given outcome and network
+pub fn increment_unsubscribe_outcome(outcome_label: &str, network_id: &NetworkId) {
+    OBSERVER_UNSUBSCRIBE_OUTCOMES
+        .with_label_values(&[outcome_label, network_id.as_str()])
+        .inc();
+}
+
+/// Observes the processing time for the given observer loop branch
+pub fn observe_loop_branch_processing_time(branch_label: &str, processing_time: Duration) {
+    OBSERVER_LOOP_BRANCH_PROCESSING_TIMES
+        .with_label_values(&[branch_label])
+        .observe(processing_time.as_secs_f64());
+}
+
+/// Observes the latency of a single observer loop iteration
+pub fn observe_loop_iteration_latency(iteration_latency: Duration) {
+    OBSERVER_LOOP_ITERATION_LATENCIES.observe(iteration_latency.as_secs_f64());
+}
+
+/// Removes the active subscription peer gauge for the given peer
+pub fn remove_active_subscription_peer(peer_network_id: &PeerNetworkId) {
+    let peer_label = get_peer_label(peer_network_id);
+    let network_id = peer_network_id.network_id();
+    let _ = OBSERVER_ACTIVE_SUBSCRIPTION_PEER
+        .remove_label_values(&[peer_label.as_str(), network_id.as_str()]);
+}
+
+/// Sets the active subscription peer gauge for the given peer
+pub fn set_active_subscription_peer(peer_network_id: &PeerNetworkId) {
+    let peer_label = get_peer_label(peer_network_id);
+    let network_id = peer_network_id.network_id();
+    OBSERVER_ACTIVE_SUBSCRIPTION_PEER
+        .with_label_values(&[peer_label.as_str(), network_id.as_str()])
+        .set(1);
+}
+
+/// Sets the gauge with the provided label and value
+pub fn set_gauge_with_label(counter: &Lazy<IntGaugeVec>, label: &str, value: i64) {
+    counter.with_label_values(&[label]).set(value);
+}
+
 /// Sets the gauge with the specific label and value
 pub fn set_gauge(counter: &Lazy<IntGaugeVec>, network_id: &NetworkId, value: i64) {
     counter.with_label_values(&[network_id.as_str()]).set(value);
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_peer_label_guard() {
+        // Create a peer label guard with a maximum of 10 labels
+        let max_num_peer_labels = 10;
+        let peer_label_guard = PeerLabelGuard::new(max_num_peer_labels);
+
+        // Label the maximum number of peers and verify each peer gets a distinct label
+        let mut labeled_peers = vec![];
+        for _ in 0..max_num_peer_labels {
+            let peer_network_id = PeerNetworkId::random();
+            let peer_label = peer_label_guard.get_peer_label(&peer_network_id);
+            assert_eq!(peer_label, peer_network_id.peer_id().to_string());
+            labeled_peers.push(peer_network_id);
+        }
+
+        // Verify that new peers are assigned the "other" label
+        for _ in 0..5 {
+            let peer_label = peer_label_guard.get_peer_label(&PeerNetworkId::random());
+            assert_eq!(peer_label, OTHER_PEER_LABEL);
+        }
+
+        // Verify that the existing peers still receive their distinct labels
+        for peer_network_id in &labeled_peers {
+            let peer_label = peer_label_guard.get_peer_label(peer_network_id);
+            assert_eq!(peer_label, peer_network_id.peer_id().to_string());
+        }
+
+        // Disconnect the first peer and release the labels of all disconnected peers
+        let disconnected_peer = labeled_peers.remove(0);
+        let connected_peers: HashSet<_> = labeled_peers.iter().cloned().collect();
+        let released_peers = peer_label_guard.release_disconnected_peers(&connected_peers);
+        assert_eq!(released_peers.len(), 1);
+        assert_eq!(released_peers[0].0, disconnected_peer);
+
+        // Verify that a new peer now gets a distinct label
+        let peer_network_id = PeerNetworkId::random();
+        let peer_label = peer_label_guard.get_peer_label(&peer_network_id);
+        assert_eq!(peer_label, peer_network_id.peer_id().to_string());
+
+        // Verify that the next new peer is assigned the "other" label
+        let peer_label = peer_label_guard.get_peer_label(&PeerNetworkId::random());
+        assert_eq!(peer_label, OTHER_PEER_LABEL);
+    }
+
+    #[test]
+    fn test_peer_label_guard_message_labels() {
+        // Create a peer label guard with a maximum of 1 label
+        let peer_label_guard = PeerLabelGuard::new(1);
+
+        // Label a peer with several message labels
+        let peer_network_id = PeerNetworkId::random();
+        for message_label in ["subscribe", "ordered_block", "subscribe"] {
+            let peer_label =
+                peer_label_guard.get_peer_label_for_message(&peer_network_id, Some(message_label));
+            assert_eq!(peer_label, peer_network_id.peer_id().to_string());
+        }
+
+        // Verify that messages from other peers are not recorded
+        let other_peer = PeerNetworkId::random();
+        let peer_label = peer_label_guard.get_peer_label_for_message(&other_peer, Some("commit"));
+        assert_eq!(peer_label, OTHER_PEER_LABEL);
+
+        // Release the peer and verify the recorded message labels are returned
+        let released_peers = peer_label_guard.release_disconnected_peers(&HashSet::new());
+        assert_eq!(released_peers.len(), 1);
+        let (released_peer, message_labels) = &released_peers[0];
+        assert_eq!(released_peer, &peer_network_id);
+        assert_eq!(
+            message_labels,
+            &HashSet::from(["subscribe", "ordered_block"])
+        );
+
+        // Verify that the other peer can now be labeled
+        let peer_label = peer_label_guard.get_peer_label(&other_peer);
+        assert_eq!(peer_label, other_peer.peer_id().to_string());
+    }
+}
@@ -1,14 +1,67 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod archive_replay;
+pub mod block_archive;
+pub mod block_source;
+pub mod builder;
+pub mod commit_journal;
+pub mod data_exporter;
+pub mod epoch_state_cache;
+pub mod epoch_summary;
 pub mod error;
+pub mod event_journal;
+pub mod event_listener;
+pub mod feature_flags;
+pub mod future_commits;
+#[cfg(feature = "consensus-observer-grpc")]
+pub mod grpc_export;
+pub mod incremental_finalization;
 pub mod logging;
+pub mod message_dedup;
+pub mod message_interceptor;
+pub mod message_rate_limiter;
 pub mod metrics;
 pub mod network_client;
 pub mod network_events;
 pub mod network_message;
 pub mod observer;
+pub mod observer_control;
+pub mod observer_status;
+pub mod payload_compression;
+pub mod payload_delta;
 pub mod payload_store;
+pub mod payload_store_db;
+pub mod payload_store_sizing;
+pub mod peer_activity;
+pub mod peer_misbehavior;
+pub mod peer_overrides;
+pub mod peer_reputation;
+pub mod peer_selector;
 pub mod pending_blocks;
+pub mod pipeline_deadlines;
+pub mod pipeline_failures;
+pub mod proof_verification;
 pub mod publisher;
+pub mod publisher_runtime;
+pub mod replay_buffer;
+#[cfg(test)]
+mod scenario_tests;
+pub mod sse_export;
+pub mod state_snapshot;
+pub mod state_tracker;
+pub mod storage;
 mod subscription;
+pub mod subscription_manager;
+pub mod subscription_state;
+pub mod supervisor;
+pub mod sync_notifications;
+pub mod task_registry;
+#[cfg(test)]
+mod tests;
+pub mod websocket_export;
+#[cfg(test)]
+pub mod test_harness;
+#[cfg(test)]
+pub mod test_utils;
+pub mod time_in_state;
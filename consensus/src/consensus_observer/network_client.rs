@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::consensus_observer::{
-    error::Error,
+    error::{Error, NetworkClientError},
     logging::{LogEntry, LogEvent, LogSchema},
     metrics,
     network_message::{
@@ -41,7 +41,7 @@ impl<NetworkClient: NetworkClientInterface<ConsensusObserverMessage>>
         &self,
         peer_network_id: &PeerNetworkId,
         message: Bytes,
-        message_label: &str,
+        message_label: &'static str,
     ) -> Result<(), Error> {
         // Increment the message counter
         metrics::increment_request_counter(
@@ -56,11 +56,18 @@ impl<NetworkClient: NetworkClientInterface<ConsensusObserverMessage>>
             .message_type(message_label)
             .peer(peer_network_id));
 
+        // Update the per-peer byte counter
+        metrics::increment_sent_message_bytes_for_peer(
+            peer_network_id,
+            message_label,
+            message.len() as u64,
+        );
+
         // Send the message
         let result = self
             .network_client
             .send_to_peer_raw(message, *peer_network_id)
-            .map_err(|error| Error::NetworkError(error.to_string()));
+            .map_err(Error::from);
 
         // Process any error results
         if let Err(error) = result {
@@ -78,7 +85,7 @@ impl<NetworkClient: NetworkClientInterface<ConsensusObserverMessage>>
                 peer_network_id,
             );
 
-            Err(Error::NetworkError(error.to_string()))
+            Err(error)
         } else {
             Ok(())
         }
@@ -96,7 +103,9 @@ impl<NetworkClient: NetworkClientInterface<ConsensusObserverMessage>>
         let result = self
             .network_client
             .to_bytes_by_protocol(vec![*peer_network_id], message)
-            .map_err(|error| Error::NetworkError(error.to_string()));
+            .map_err(|error| {
+                Error::from(NetworkClientError::SerializationError(error.to_string()))
+            });
 
         // Process the serialization result
         match result {
@@ -106,7 +115,7 @@ impl<NetworkClient: NetworkClientInterface<ConsensusObserverMessage>>
                     peer_to_serialized_bytes
                         .get(peer_network_id)
                         .ok_or_else(|| {
-                            Error::NetworkError(format!(
+                            NetworkClientError::SerializationError(format!(
                                 "Failed to get serialized bytes for peer: {:?}!",
                                 peer_network_id
                             ))
@@ -129,7 +138,7 @@ impl<NetworkClient: NetworkClientInterface<ConsensusObserverMessage>>
                     peer_network_id,
                 );
 
-                Err(Error::NetworkError(error.to_string()))
+                Err(error)
             },
         }
     }
@@ -221,7 +230,7 @@ impl<NetworkClient: NetworkClientInterface<ConsensusObserverMessage>>
                 peer_network_id,
             )
             .await
-            .map_err(|error| Error::NetworkError(error.to_string()))?;
+            .map_err(Error::from)?;
 
         // Stop the timer and calculate the duration
         let request_duration_secs = start_time.elapsed().as_secs_f64();
@@ -237,17 +246,29 @@ impl<NetworkClient: NetworkClientInterface<ConsensusObserverMessage>>
         // Process the response
         match response {
             ConsensusObserverMessage::Response(response) => Ok(response),
-            ConsensusObserverMessage::Request(request) => Err(Error::NetworkError(format!(
-                "Got consensus observer request instead of response! Request: {:?}",
-                request
-            ))),
-            ConsensusObserverMessage::DirectSend(message) => Err(Error::NetworkError(format!(
-                "Got consensus observer direct send message instead of response! Message: {:?}",
-                message
-            ))),
+            ConsensusObserverMessage::Request(request) => {
+                Err(Error::from(NetworkClientError::ProtocolMismatch(format!(
+                    "Got consensus observer request instead of response! Request: {:?}",
+                    request
+                ))))
+            },
+            ConsensusObserverMessage::DirectSend(message) => {
+                Err(Error::from(NetworkClientError::ProtocolMismatch(format!(
+                    "Got consensus observer direct send message instead of response! Message: {:?}",
+                    message
+                ))))
+            },
         }
     }
 
+    /// Disconnects from the specified peer (e.g., due to misbehavior)
+    pub async fn disconnect_from_peer(&self, peer_network_id: &PeerNetworkId) -> Result<(), Error> {
+        self.network_client
+            .disconnect_from_peer(*peer_network_id)
+            .await
+            .map_err(Error::from)
+    }
+
     /// Returns the peers and metadata struct
     pub fn get_peers_and_metadata(&self) -> Arc<PeersAndMetadata> {
         self.network_client.get_peers_and_metadata()
@@ -30,6 +30,7 @@ pub struct NetworkMessage {
     pub protocol_id: Option<ProtocolId>,
     pub consensus_observer_message: ConsensusObserverMessage,
     pub response_sender: Option<ResponseSender>,
+    pub num_message_bytes: usize, // The size of the message received over the wire
 }
 
 /// A stream of messages from the network. Each message also comes with
@@ -44,14 +45,18 @@ impl ConsensusObserverNetworkEvents {
         let network_events: Vec<_> = network_service_events
             .into_network_and_events()
             .into_iter()
-            .map(|(network_id, events)| events.map(move |event| (network_id, event)))
+            .map(|(network_id, events)| {
+                events
+                    .into_sized_event_stream()
+                    .map(move |(event, num_message_bytes)| (network_id, event, num_message_bytes))
+            })
             .collect();
         let network_events = select_all(network_events).fuse();
 
         // Transform each event to a network message
         let network_message_stream = network_events
-            .filter_map(|(network_id, event)| {
-                future::ready(Self::event_to_request(network_id, event))
+            .filter_map(|(network_id, event, num_message_bytes)| {
+                future::ready(Self::event_to_request(network_id, event, num_message_bytes))
             })
             .boxed();
 
@@ -60,10 +65,22 @@ impl ConsensusObserverNetworkEvents {
         }
     }
 
+    #[cfg(test)]
+    /// Creates a new network events stream from the given stream of
+    /// network messages (e.g., a fake network for testing purposes).
+    pub fn new_for_test(
+        network_message_stream: impl Stream<Item = NetworkMessage> + Send + 'static,
+    ) -> Self {
+        Self {
+            network_message_stream: network_message_stream.boxed(),
+        }
+    }
+
     /// Transforms each network event into a network message
     fn event_to_request(
         network_id: NetworkId,
         network_event: Event<ConsensusObserverMessage>,
+        num_message_bytes: usize,
     ) -> Option<NetworkMessage> {
         match network_event {
             Event::Message(peer_id, consensus_observer_message) => {
@@ -74,6 +91,7 @@ impl ConsensusObserverNetworkEvents {
                     protocol_id: None,
                     consensus_observer_message,
                     response_sender: None,
+                    num_message_bytes,
                 };
                 Some(network_message)
             },
@@ -86,6 +104,7 @@ impl ConsensusObserverNetworkEvents {
                     protocol_id: Some(protocol_id),
                     consensus_observer_message,
                     response_sender: Some(response_sender),
+                    num_message_bytes,
                 };
                 Some(network_message)
             },
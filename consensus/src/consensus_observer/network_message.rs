@@ -1,8 +1,14 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::consensus_observer::error::Error;
-use aptos_consensus_types::pipelined_block::PipelinedBlock;
+use crate::{consensus_observer::error::Error, execution_pipeline::SIG_VERIFY_POOL};
+use aptos_consensus_types::{
+    common::{BatchPayload, Payload, ProofWithData},
+    pipelined_block::PipelinedBlock,
+    proof_of_store::{BatchInfo, ProofCache},
+};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_experimental_runtimes::thread_manager::optimal_min_len;
 use aptos_types::{
     block_info::{BlockInfo, Round},
     epoch_change::Verifier,
@@ -10,6 +16,8 @@ use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
     transaction::SignedTransaction,
 };
+use fail::fail_point;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 use std::{
     fmt::{Display, Formatter},
@@ -40,7 +48,7 @@ impl ConsensusObserverMessage {
     pub fn new_commit_decision_message(
         commit_proof: LedgerInfoWithSignatures,
     ) -> ConsensusObserverDirectSend {
-        ConsensusObserverDirectSend::CommitDecision(CommitDecision { commit_proof })
+        ConsensusObserverDirectSend::CommitDecision(CommitDecision::new(commit_proof))
     }
 
     /// Creates and returns a new block payload message using the given block, transactions and limit
@@ -57,6 +65,17 @@ impl ConsensusObserverMessage {
     }
 }
 
+impl ConsensusObserverMessage {
+    /// Returns a summary label for the message
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ConsensusObserverMessage::Request(request) => request.get_label(),
+            ConsensusObserverMessage::Response(response) => response.get_label(),
+            ConsensusObserverMessage::DirectSend(direct_send) => direct_send.get_label(),
+        }
+    }
+}
+
 impl Display for ConsensusObserverMessage {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -82,6 +101,14 @@ impl Display for ConsensusObserverMessage {
 pub enum ConsensusObserverRequest {
     Subscribe,
     Unsubscribe,
+    SubscribeWithOptions(SubscriptionOptions),
+    AcknowledgePayloads(PayloadAcknowledgement),
+    GetLatestCommit,
+    GetOrderedBlocks {
+        start_round: Round,
+        end_round: Round,
+    },
+    GetBlockPayload(HashValue),
 }
 
 impl ConsensusObserverRequest {
@@ -90,20 +117,71 @@ impl ConsensusObserverRequest {
         match self {
             ConsensusObserverRequest::Subscribe => "subscribe",
             ConsensusObserverRequest::Unsubscribe => "unsubscribe",
+            ConsensusObserverRequest::SubscribeWithOptions(_) => "subscribe_with_options",
+            ConsensusObserverRequest::AcknowledgePayloads(_) => "acknowledge_payloads",
+            ConsensusObserverRequest::GetLatestCommit => "get_latest_commit",
+            ConsensusObserverRequest::GetOrderedBlocks { .. } => "get_ordered_blocks",
+            ConsensusObserverRequest::GetBlockPayload(_) => "get_block_payload",
         }
     }
 
     /// Returns the message content for the request. This is useful for debugging.
     pub fn get_content(&self) -> String {
-        self.get_label().into()
+        match self {
+            ConsensusObserverRequest::SubscribeWithOptions(subscription_options) => {
+                format!("SubscribeWithOptions: {:?}", subscription_options)
+            },
+            ConsensusObserverRequest::AcknowledgePayloads(payload_acknowledgement) => {
+                format!(
+                    "AcknowledgePayloads: {}",
+                    payload_acknowledgement.payload_rounds.len()
+                )
+            },
+            ConsensusObserverRequest::GetOrderedBlocks {
+                start_round,
+                end_round,
+            } => {
+                format!("GetOrderedBlocks: [{}, {}]", start_round, end_round)
+            },
+            ConsensusObserverRequest::GetBlockPayload(block_id) => {
+                format!("GetBlockPayload: {}", block_id)
+            },
+            _ => self.get_label().into(),
+        }
     }
 }
 
+/// The options negotiated by observers when subscribing to a publisher. Note:
+/// the options are only requests, and the publisher may ignore any options it
+/// doesn't support (e.g., if dictionary compression is disabled locally).
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SubscriptionOptions {
+    // Whether the observer supports dictionary compressed block payloads
+    pub dictionary_compression: bool,
+    // Whether the observer supports delta encoded block payloads
+    pub delta_encoding: bool,
+    // The latest block (i.e., epoch and round) held by the observer. If set, the
+    // publisher replays the retained blocks (and payloads) after the block.
+    pub starting_block: Option<(u64, Round)>,
+}
+
+/// An acknowledgement (sent by observers that support delta encoded payloads)
+/// of the block payloads (i.e., epoch and round) that the observer now holds.
+/// The publisher may then send digests-only for the acknowledged transactions.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PayloadAcknowledgement {
+    pub payload_rounds: Vec<(u64, Round)>,
+}
+
 /// Types of responses that can be sent between the consensus publisher and observer
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum ConsensusObserverResponse {
     SubscribeAck,
     UnsubscribeAck,
+    AcknowledgePayloadsAck,
+    LatestCommit(Option<BlockInfo>),
+    OrderedBlocks(Vec<OrderedBlock>),
+    BlockPayload(Option<BlockPayload>),
 }
 
 impl ConsensusObserverResponse {
@@ -112,12 +190,30 @@ impl ConsensusObserverResponse {
         match self {
             ConsensusObserverResponse::SubscribeAck => "subscribe_ack",
             ConsensusObserverResponse::UnsubscribeAck => "unsubscribe_ack",
+            ConsensusObserverResponse::AcknowledgePayloadsAck => "acknowledge_payloads_ack",
+            ConsensusObserverResponse::LatestCommit(_) => "latest_commit",
+            ConsensusObserverResponse::OrderedBlocks(_) => "ordered_blocks",
+            ConsensusObserverResponse::BlockPayload(_) => "block_payload",
         }
     }
 
     /// Returns the message content for the response. This is useful for debugging.
     pub fn get_content(&self) -> String {
-        self.get_label().into()
+        match self {
+            ConsensusObserverResponse::LatestCommit(latest_commit_info) => {
+                format!("LatestCommit: {:?}", latest_commit_info)
+            },
+            ConsensusObserverResponse::OrderedBlocks(ordered_blocks) => {
+                format!("OrderedBlocks: {}", ordered_blocks.len())
+            },
+            ConsensusObserverResponse::BlockPayload(block_payload) => {
+                let block_info = block_payload
+                    .as_ref()
+                    .map(|block_payload| block_payload.block.clone());
+                format!("BlockPayload: {:?}", block_info)
+            },
+            _ => self.get_label().into(),
+        }
     }
 }
 
@@ -127,6 +223,9 @@ pub enum ConsensusObserverDirectSend {
     OrderedBlock(OrderedBlock),
     CommitDecision(CommitDecision),
     BlockPayload(BlockPayload),
+    CompressionDictionary(CompressionDictionary),
+    CompressedBlockPayload(CompressedBlockPayload),
+    DeltaBlockPayload(DeltaBlockPayload),
 }
 
 impl ConsensusObserverDirectSend {
@@ -136,6 +235,9 @@ impl ConsensusObserverDirectSend {
             ConsensusObserverDirectSend::OrderedBlock(_) => "ordered_block",
             ConsensusObserverDirectSend::CommitDecision(_) => "commit_decision",
             ConsensusObserverDirectSend::BlockPayload(_) => "block_payload",
+            ConsensusObserverDirectSend::CompressionDictionary(_) => "compression_dictionary",
+            ConsensusObserverDirectSend::CompressedBlockPayload(_) => "compressed_block_payload",
+            ConsensusObserverDirectSend::DeltaBlockPayload(_) => "delta_block_payload",
         }
     }
 
@@ -156,6 +258,29 @@ impl ConsensusObserverDirectSend {
                     block_payload.limit
                 )
             },
+            ConsensusObserverDirectSend::CompressionDictionary(compression_dictionary) => {
+                format!(
+                    "CompressionDictionary: {} {}",
+                    compression_dictionary.dictionary_id,
+                    compression_dictionary.dictionary_bytes.len()
+                )
+            },
+            ConsensusObserverDirectSend::CompressedBlockPayload(compressed_block_payload) => {
+                format!(
+                    "CompressedBlockPayload: {} {} {}",
+                    compressed_block_payload.block.id(),
+                    compressed_block_payload.dictionary_id,
+                    compressed_block_payload.compressed_payload.len()
+                )
+            },
+            ConsensusObserverDirectSend::DeltaBlockPayload(delta_block_payload) => {
+                format!(
+                    "DeltaBlockPayload: {} {} {:?}",
+                    delta_block_payload.block.id(),
+                    delta_block_payload.transactions.len(),
+                    delta_block_payload.limit
+                )
+            },
         }
     }
 }
@@ -249,8 +374,55 @@ impl OrderedBlock {
         Ok(())
     }
 
+    /// Verifies that the block timestamps are non-decreasing (starting from
+    /// the given parent timestamp, if known), and that no block timestamp is
+    /// beyond the given maximum timestamp (e.g., the local time plus skew).
+    pub fn verify_block_timestamps(
+        &self,
+        parent_timestamp_usecs: Option<u64>,
+        max_timestamp_usecs: u64,
+    ) -> Result<(), Error> {
+        let mut previous_timestamp_usecs = parent_timestamp_usecs;
+        for block in self.blocks.iter() {
+            // Verify the block timestamp is not before the previous timestamp
+            let block_timestamp_usecs = block.timestamp_usecs();
+            if let Some(previous_timestamp_usecs) = previous_timestamp_usecs {
+                if block_timestamp_usecs < previous_timestamp_usecs {
+                    return Err(Error::InvalidMessageError(format!(
+                        "Block timestamp is before the parent timestamp! Block ID: {:?}, \
+                        Block timestamp: {:?}, Parent timestamp: {:?}",
+                        block.id(),
+                        block_timestamp_usecs,
+                        previous_timestamp_usecs
+                    )));
+                }
+            }
+
+            // Verify the block timestamp is not too far in the future
+            if block_timestamp_usecs > max_timestamp_usecs {
+                return Err(Error::InvalidMessageError(format!(
+                    "Block timestamp is too far in the future! Block ID: {:?}, \
+                    Block timestamp: {:?}, Maximum timestamp: {:?}",
+                    block.id(),
+                    block_timestamp_usecs,
+                    max_timestamp_usecs
+                )));
+            }
+
+            previous_timestamp_usecs = Some(block_timestamp_usecs);
+        }
+
+        Ok(())
+    }
+
     /// Verifies the ordered proof and returns an error if the proof is invalid
     pub fn verify_ordered_proof(&self, epoch_state: &EpochState) -> Result<(), Error> {
+        fail_point!("consensus_observer::verify_ordered_proof", |_| {
+            Err(Error::InvalidMessageError(
+                "Injected error in verify_ordered_proof".into(),
+            ))
+        });
+
         epoch_state.verify(&self.ordered_proof).map_err(|error| {
             Error::InvalidMessageError(format!(
                 "Failed to verify ordered proof ledger info: {:?}, Error: {:?}",
@@ -261,14 +433,21 @@ impl OrderedBlock {
     }
 }
 
-/// CommitDecision message contains the commit decision proof
+/// CommitDecision message contains the commit decision proof. The proof
+/// is shared (e.g., with the pending blocks and the root), to avoid copying
+/// the multi-signature each time the commit decision is handled.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct CommitDecision {
-    commit_proof: LedgerInfoWithSignatures,
+    commit_proof: Arc<LedgerInfoWithSignatures>,
 }
 
 impl CommitDecision {
     pub fn new(commit_proof: LedgerInfoWithSignatures) -> Self {
+        Self::new_with_shared_proof(Arc::new(commit_proof))
+    }
+
+    /// Creates a commit decision from the given shared commit proof
+    pub fn new_with_shared_proof(commit_proof: Arc<LedgerInfoWithSignatures>) -> Self {
         Self { commit_proof }
     }
 
@@ -277,6 +456,11 @@ impl CommitDecision {
         &self.commit_proof
     }
 
+    /// Returns a shared reference to the commit proof
+    pub fn shared_commit_proof(&self) -> Arc<LedgerInfoWithSignatures> {
+        self.commit_proof.clone()
+    }
+
     /// Returns the epoch of the commit proof
     pub fn epoch(&self) -> u64 {
         self.commit_proof.ledger_info().epoch()
@@ -294,6 +478,12 @@ impl CommitDecision {
 
     /// Verifies the commit proof and returns an error if the proof is invalid
     pub fn verify_commit_proof(&self, epoch_state: &EpochState) -> Result<(), Error> {
+        fail_point!("consensus_observer::verify_commit_proof", |_| {
+            Err(Error::InvalidMessageError(
+                "Injected error in verify_commit_proof".into(),
+            ))
+        });
+
         epoch_state.verify(&self.commit_proof).map_err(|error| {
             Error::InvalidMessageError(format!(
                 "Failed to verify commit proof ledger info: {:?}, Error: {:?}",
@@ -311,3 +501,432 @@ pub struct BlockPayload {
     pub transactions: Vec<SignedTransaction>,
     pub limit: Option<u64>,
 }
+
+/// A compression dictionary (trained by the publisher) that is shipped to
+/// subscribers, so that they can decompress dictionary compressed payloads.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CompressionDictionary {
+    pub dictionary_id: u64,
+    pub dictionary_bytes: Vec<u8>,
+}
+
+/// A block payload whose transactions and limit have been compressed
+/// using the compression dictionary with the specified ID.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CompressedBlockPayload {
+    pub block: BlockInfo,
+    pub dictionary_id: u64,
+    pub compressed_payload: Vec<u8>,
+}
+
+/// A block payload where the transactions that the subscriber has already
+/// acknowledged holding are replaced by their digests (i.e., committed hashes).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DeltaBlockPayload {
+    pub block: BlockInfo,
+    pub transactions: Vec<DeltaTransaction>,
+    pub limit: Option<u64>,
+}
+
+/// A transaction in a delta encoded block payload
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DeltaTransaction {
+    Digest(HashValue),              // The subscriber already holds the transaction
+    Transaction(SignedTransaction), // The full transaction
+}
+
+impl BlockPayload {
+    /// Returns the batch infos of the unexpired proofs (the transactions of
+    /// proofs that expired before the block timestamp are not executed).
+    fn get_unexpired_batch_infos<'a>(
+        &self,
+        proof_with_data: &'a ProofWithData,
+    ) -> Vec<&'a BatchInfo> {
+        let block_timestamp = self.block.timestamp_usecs();
+        proof_with_data
+            .proofs
+            .iter()
+            .filter(|proof| block_timestamp <= proof.expiration())
+            .map(|proof| proof.info())
+            .collect()
+    }
+
+    /// Verifies the payload transactions against the given (ordered) block payload.
+    /// For quorum store payloads, the batch digests are recomputed over the payload
+    /// transactions and checked against the digests in the proofs (and inline
+    /// batches). This ensures that a publisher can't substitute transactions
+    /// within a signed batch. The transaction limit is also verified.
+    pub fn verify_payload_digests(&self, payload: Option<&Payload>) -> Result<(), Error> {
+        fail_point!("consensus_observer::verify_payload_digests", |_| {
+            Err(Error::InvalidMessageError(
+                "Injected error in verify_payload_digests".into(),
+            ))
+        });
+
+        // Identify the batches and transaction limit of the ordered block payload
+        let (batch_infos, expected_limit): (Vec<&BatchInfo>, _) = match payload {
+            None => (vec![], None), // The block has no transactions
+            Some(Payload::DirectMempool(transactions)) => {
+                // Direct mempool payloads contain the transactions themselves
+                if *transactions != self.transactions || self.limit.is_some() {
+                    return Err(Error::InvalidMessageError(format!(
+                        "The block payload doesn't match the direct mempool transactions: {}!",
+                        self.block
+                    )));
+                }
+                return Ok(());
+            },
+            Some(Payload::InQuorumStore(proof_with_data)) => {
+                (self.get_unexpired_batch_infos(proof_with_data), None)
+            },
+            Some(Payload::InQuorumStoreWithLimit(proof_with_data_with_limit)) => (
+                self.get_unexpired_batch_infos(&proof_with_data_with_limit.proof_with_data),
+                proof_with_data_with_limit.max_txns_to_execute,
+            ),
+            Some(Payload::QuorumStoreInlineHybrid(
+                inline_batches,
+                proof_with_data,
+                max_txns_to_execute,
+            )) => {
+                let mut batch_infos = self.get_unexpired_batch_infos(proof_with_data);
+                batch_infos.extend(inline_batches.iter().map(|(batch_info, _)| batch_info));
+                (batch_infos, *max_txns_to_execute)
+            },
+        };
+
+        // Recompute the digest of each batch and verify it against the expected digest
+        let mut remaining_transactions = self.transactions.as_slice();
+        for batch_info in batch_infos {
+            let num_transactions = batch_info.num_txns() as usize;
+            if remaining_transactions.len() < num_transactions {
+                return Err(Error::InvalidMessageError(format!(
+                    "The block payload is missing transactions for batch: {}, in block: {}!",
+                    batch_info.digest(),
+                    self.block
+                )));
+            }
+            let (batch_transactions, next_transactions) =
+                remaining_transactions.split_at(num_transactions);
+            let batch_digest =
+                BatchPayload::new(batch_info.author(), batch_transactions.to_vec()).hash();
+            if batch_digest != *batch_info.digest() {
+                return Err(Error::InvalidMessageError(format!(
+                    "The batch digest doesn't match! Expected: {}, found: {}, in block: {}!",
+                    batch_info.digest(),
+                    batch_digest,
+                    self.block
+                )));
+            }
+            remaining_transactions = next_transactions;
+        }
+
+        // Verify there are no unexpected transactions and that the limit matches
+        if !remaining_transactions.is_empty() {
+            return Err(Error::InvalidMessageError(format!(
+                "The block payload contains {} unexpected transactions, in block: {}!",
+                remaining_transactions.len(),
+                self.block
+            )));
+        }
+        if self.limit != expected_limit {
+            return Err(Error::InvalidMessageError(format!(
+                "The block payload limit doesn't match! Expected: {:?}, found: {:?}, in block: {}!",
+                expected_limit, self.limit, self.block
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the quorum store proofs (and inline batches) of the given (ordered)
+    /// block payload using the validator verifier of the given epoch state. This
+    /// ensures that the batches the payload transactions are checked against were
+    /// certified by the validators. Direct mempool payloads have no proofs.
+    pub fn verify_payload_proofs(
+        &self,
+        payload: Option<&Payload>,
+        epoch_state: &EpochState,
+        proof_cache: &ProofCache,
+    ) -> Result<(), Error> {
+        match payload {
+            None | Some(Payload::DirectMempool(_)) => Ok(()),
+            Some(payload) => payload
+                .verify(&epoch_state.verifier, proof_cache, true)
+                .map_err(|error| {
+                    Error::InvalidMessageError(format!(
+                        "Failed to verify the payload proofs of block: {}! Error: {:?}",
+                        self.block, error
+                    ))
+                }),
+        }
+    }
+
+    /// Verifies the signatures of all transactions in the payload. The
+    /// signatures are verified in parallel (on the signature verification
+    /// pool), and the first invalid signature (if any) is returned as an error.
+    pub fn verify_transaction_signatures(&self) -> Result<(), Error> {
+        let num_transactions = self.transactions.len();
+        SIG_VERIFY_POOL.install(|| {
+            self.transactions
+                .par_iter()
+                .with_min_len(optimal_min_len(num_transactions, 32))
+                .try_for_each(|transaction| {
+                    transaction.verify_signature().map_err(|error| {
+                        Error::InvalidMessageError(format!(
+                            "Failed to verify the signature of transaction: {:?}, in block payload: {}! Error: {:?}",
+                            transaction.committed_hash(),
+                            self.block,
+                            error
+                        ))
+                    })
+                })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_consensus_types::{
+        common::ProofWithDataWithTxnLimit,
+        proof_of_store::{BatchId, ProofOfStore},
+    };
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+    use aptos_types::{
+        account_address::AccountAddress,
+        aggregate_signature::{AggregateSignature, PartialSignatures},
+        test_helpers::transaction_test_helpers::get_test_signed_txn,
+        validator_signer::ValidatorSigner,
+        validator_verifier::ValidatorVerifier,
+        PeerId,
+    };
+    use mini_moka::sync::Cache;
+
+    #[test]
+    fn test_verify_payload_digests() {
+        // Create two batches of transactions (with their batch infos)
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let mut transactions = vec![];
+        let mut batch_infos = vec![];
+        for batch_id in 0..2 {
+            let author = PeerId::random();
+            let batch_transactions: Vec<_> = (0..10)
+                .map(|sequence_number| {
+                    get_test_signed_txn(
+                        AccountAddress::random(),
+                        sequence_number,
+                        &private_key,
+                        private_key.public_key(),
+                        None,
+                    )
+                })
+                .collect();
+            let digest = BatchPayload::new(author, batch_transactions.clone()).hash();
+            batch_infos.push(BatchInfo::new(
+                author,
+                BatchId::new_for_test(batch_id),
+                0,
+                u64::MAX,
+                digest,
+                batch_transactions.len() as u64,
+                0,
+                0,
+            ));
+            transactions.extend(batch_transactions);
+        }
+
+        // Create a quorum store payload (with a transaction limit) for the batches
+        let proofs = batch_infos
+            .iter()
+            .map(|batch_info| ProofOfStore::new(batch_info.clone(), AggregateSignature::empty()))
+            .collect();
+        let payload = Payload::InQuorumStoreWithLimit(ProofWithDataWithTxnLimit::new(
+            ProofWithData::new(proofs),
+            Some(15),
+        ));
+
+        // Verify that a matching block payload passes verification
+        let mut block_payload = BlockPayload {
+            block: BlockInfo::random_with_epoch(0, 10),
+            transactions: transactions.clone(),
+            limit: Some(15),
+        };
+        assert!(block_payload.verify_payload_digests(Some(&payload)).is_ok());
+
+        // Verify that a mismatched transaction limit fails verification
+        block_payload.limit = None;
+        assert!(block_payload
+            .verify_payload_digests(Some(&payload))
+            .is_err());
+        block_payload.limit = Some(15);
+
+        // Verify that a substituted transaction fails verification
+        block_payload.transactions[5] = get_test_signed_txn(
+            AccountAddress::random(),
+            0,
+            &private_key,
+            private_key.public_key(),
+            None,
+        );
+        assert!(block_payload
+            .verify_payload_digests(Some(&payload))
+            .is_err());
+
+        // Verify that missing and unexpected transactions fail verification
+        block_payload.transactions = transactions[1..].to_vec();
+        assert!(block_payload
+            .verify_payload_digests(Some(&payload))
+            .is_err());
+        block_payload.transactions = transactions.clone();
+        block_payload.transactions.push(transactions[0].clone());
+        assert!(block_payload
+            .verify_payload_digests(Some(&payload))
+            .is_err());
+
+        // Verify that the transactions of expired proofs must be excluded
+        let expired_batch_info = BatchInfo::new(
+            PeerId::random(),
+            BatchId::new_for_test(2),
+            0,
+            0,
+            HashValue::random(),
+            10,
+            0,
+            0,
+        );
+        let expired_block_payload = BlockPayload {
+            block: BlockInfo::new(0, 10, HashValue::random(), HashValue::random(), 0, 1, None),
+            transactions: transactions.clone(),
+            limit: None,
+        };
+        let mut proofs: Vec<_> = batch_infos
+            .iter()
+            .map(|batch_info| ProofOfStore::new(batch_info.clone(), AggregateSignature::empty()))
+            .collect();
+        proofs.insert(
+            1,
+            ProofOfStore::new(expired_batch_info, AggregateSignature::empty()),
+        );
+        let payload = Payload::InQuorumStore(ProofWithData::new(proofs));
+        assert!(expired_block_payload
+            .verify_payload_digests(Some(&payload))
+            .is_ok());
+
+        // Verify that direct mempool payloads must match the transactions exactly
+        block_payload.transactions = transactions.clone();
+        block_payload.limit = None;
+        let payload = Payload::DirectMempool(transactions.clone());
+        assert!(block_payload.verify_payload_digests(Some(&payload)).is_ok());
+        let payload = Payload::DirectMempool(transactions[1..].to_vec());
+        assert!(block_payload
+            .verify_payload_digests(Some(&payload))
+            .is_err());
+
+        // Verify that blocks without payloads must not contain transactions
+        assert!(block_payload.verify_payload_digests(None).is_err());
+        block_payload.transactions = vec![];
+        assert!(block_payload.verify_payload_digests(None).is_ok());
+    }
+
+    #[test]
+    fn test_verify_payload_proofs() {
+        // Create an epoch state with a single validator
+        let validator_signer = ValidatorSigner::random(None);
+        let epoch_state = EpochState::new(
+            10,
+            ValidatorVerifier::new_single(validator_signer.author(), validator_signer.public_key()),
+        );
+
+        // Create a batch info and a proof signed by the validator
+        let batch_info = BatchInfo::new(
+            validator_signer.author(),
+            BatchId::new_for_test(0),
+            10,
+            u64::MAX,
+            HashValue::random(),
+            0,
+            0,
+            0,
+        );
+        let mut partial_signatures = PartialSignatures::empty();
+        partial_signatures.add_signature(
+            validator_signer.author(),
+            validator_signer.sign(&batch_info).unwrap(),
+        );
+        let multi_signature = epoch_state
+            .verifier
+            .aggregate_signatures(&partial_signatures)
+            .unwrap();
+        let valid_proof = ProofOfStore::new(batch_info.clone(), multi_signature);
+
+        // Verify that a payload with a valid proof passes verification
+        let block_payload = BlockPayload {
+            block: BlockInfo::random_with_epoch(10, 5),
+            transactions: vec![],
+            limit: None,
+        };
+        let proof_cache = Cache::new(10);
+        let payload = Payload::InQuorumStore(ProofWithData::new(vec![valid_proof]));
+        assert!(block_payload
+            .verify_payload_proofs(Some(&payload), &epoch_state, &proof_cache)
+            .is_ok());
+
+        // Verify that a payload with an unsigned proof fails verification
+        let invalid_proof = ProofOfStore::new(batch_info, AggregateSignature::empty());
+        let payload = Payload::InQuorumStore(ProofWithData::new(vec![invalid_proof]));
+        assert!(matches!(
+            block_payload.verify_payload_proofs(Some(&payload), &epoch_state, &proof_cache),
+            Err(Error::InvalidMessageError(_))
+        ));
+
+        // Verify that payloads without proofs pass verification
+        assert!(block_payload
+            .verify_payload_proofs(None, &epoch_state, &proof_cache)
+            .is_ok());
+        assert!(block_payload
+            .verify_payload_proofs(
+                Some(&Payload::DirectMempool(vec![])),
+                &epoch_state,
+                &proof_cache
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_verify_transaction_signatures() {
+        // Create a block payload with valid transaction signatures
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let transactions: Vec<_> = (0..100)
+            .map(|sequence_number| {
+                get_test_signed_txn(
+                    AccountAddress::random(),
+                    sequence_number,
+                    &private_key,
+                    private_key.public_key(),
+                    None,
+                )
+            })
+            .collect();
+        let mut block_payload = BlockPayload {
+            block: BlockInfo::random_with_epoch(0, 10),
+            transactions,
+            limit: None,
+        };
+
+        // Verify that the signatures are valid
+        assert!(block_payload.verify_transaction_signatures().is_ok());
+
+        // Add a transaction with an invalid signature (i.e., signed by a different key)
+        let invalid_transaction = get_test_signed_txn(
+            AccountAddress::random(),
+            0,
+            &Ed25519PrivateKey::generate_for_testing(),
+            private_key.public_key(),
+            None,
+        );
+        block_payload.transactions.insert(50, invalid_transaction);
+
+        // Verify that the signature verification fails
+        assert!(block_payload.verify_transaction_signatures().is_err());
+    }
+}
@@ -3,20 +3,52 @@
 
 use crate::{
     consensus_observer::{
+        block_source::{BlockSourceMessage, ObserverBlockSource},
+        commit_journal::CommitJournalWriter,
+        data_exporter::ObserverDataExporter,
+        epoch_state_cache::EpochStateCache,
+        epoch_summary::EpochSummaryTracker,
         error::Error,
+        event_journal::{ObserverEvent, ObserverEventJournal},
+        event_listener::{ObserverEventListener, ObserverEventListeners},
+        feature_flags::{ObserverFeature, ObserverFeatureFlags},
+        future_commits::FutureCommitDecisionBuffer,
+        incremental_finalization::IncrementalFinalizationQueue,
         logging::{LogEntry, LogSchema},
+        message_dedup::MessageDeduplicator,
+        message_interceptor::{ConsensusObserverMessageInterceptor, MessageInterceptorChain},
+        message_rate_limiter::{InboundRateLimiter, RateLimitDecision},
         metrics,
         network_client::ConsensusObserverClient,
         network_events::{ConsensusObserverNetworkEvents, NetworkMessage, ResponseSender},
         network_message::{
-            BlockPayload, CommitDecision, ConsensusObserverDirectSend, ConsensusObserverMessage,
-            ConsensusObserverRequest, ConsensusObserverResponse, OrderedBlock,
+            BlockPayload, CommitDecision, CompressedBlockPayload, ConsensusObserverDirectSend,
+            ConsensusObserverMessage, ConsensusObserverRequest, DeltaBlockPayload, OrderedBlock,
         },
+        observer_control::{ObserverControlCommand, ObserverControlHandle},
+        observer_status::{ObserverStatusHandle, ObserverSyncStatus},
+        payload_compression::PayloadDictionaryDecompressor,
+        payload_delta::DeltaPayloadDecoder,
         payload_store::BlockPayloadStore,
+        payload_store_db::ObserverPayloadDb,
+        peer_misbehavior::{PeerMisbehavior, PeerMisbehaviorReporter},
+        peer_overrides::SubscriptionPeerOverrides,
+        peer_reputation::PeerReputationEvent,
+        peer_selector::SubscriptionPeerSelector,
         pending_blocks::PendingOrderedBlocks,
+        pipeline_deadlines::{PipelineDeadlineTracker, PipelineHandoff},
+        pipeline_failures::PipelineFailureTracker,
+        proof_verification::{
+            ProofVerificationFilter, ProofVerificationStream, VerifiedProofCache,
+        },
         publisher::ConsensusPublisher,
-        subscription,
-        subscription::ConsensusObserverSubscription,
+        state_snapshot::{ObserverStateSnapshot, ObserverStateSnapshotter},
+        state_tracker::ObserverStateTracker,
+        storage::ObserverStorageInterface,
+        subscription_manager::SubscriptionManager,
+        sync_notifications::{SyncNotificationListener, SyncNotificationSender},
+        task_registry::TaskRegistry,
+        time_in_state::{ObserverState, TimeInStateTracker},
     },
     dag::DagCommitSigner,
     network::{IncomingCommitRequest, IncomingRandGenRequest},
@@ -26,19 +58,26 @@ use crate::{
     state_replication::StateComputerCommitCallBackType,
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
-use aptos_consensus_types::pipeline;
-use aptos_crypto::{bls12381, Genesis};
-use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
+use aptos_config::{
+    config::{ConsensusObserverConfig, PipelineFailureEscalation},
+    network_id::PeerNetworkId,
+};
+use aptos_consensus_types::{
+    pipeline, pipelined_block::PipelinedBlock, proof_of_store::ProofCache,
+};
+use aptos_crypto::{bls12381, Genesis, HashValue};
+use aptos_event_notifications::{
+    DbBackedOnChainConfig, ObservedCommitNotifier, ReconfigNotificationListener,
+    SyncedCommitNotification, SyncedCommitNotificationListener,
+};
+use aptos_executor_types::ExecutorResult;
 use aptos_infallible::Mutex;
 use aptos_logger::{debug, error, info, warn};
 use aptos_network::{
-    application::{interface::NetworkClient, metadata::PeerMetadata},
-    protocols::wire::handshake::v1::ProtocolId,
+    application::interface::NetworkClient, protocols::wire::handshake::v1::ProtocolId,
 };
 use aptos_reliable_broadcast::DropGuard;
-use aptos_storage_interface::DbReader;
-use aptos_time_service::TimeService;
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
     block_info::{BlockInfo, Round},
     epoch_state::EpochState,
@@ -49,318 +88,783 @@ use aptos_types::{
     },
     validator_signer::ValidatorSigner,
 };
-use futures::{
-    future::{AbortHandle, Abortable},
-    StreamExt,
-};
-use futures_channel::oneshot;
+use fail::fail_point;
+use futures::{future::AbortHandle, stream::select_all, StreamExt};
+use futures_channel::{mpsc, oneshot};
+use mini_moka::sync::Cache;
 use move_core_types::account_address::AccountAddress;
-use std::{collections::HashMap, sync::Arc, time::Duration};
-use tokio::{sync::mpsc::UnboundedSender, time::interval};
+use std::{
+    mem,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::time::interval;
 use tokio_stream::wrappers::IntervalStream;
 
+// The capacity and time-to-live of the cache of verified quorum store proofs
+const PROOF_CACHE_CAPACITY: u64 = 10_000;
+const PROOF_CACHE_TTL_SECS: u64 = 20;
+
+// The number of recent message digests retained to deduplicate concurrent subscriptions
+const MAX_DEDUPLICATED_MESSAGES: usize = 10_000;
+
 /// The consensus observer receives consensus updates and propagates them to the execution pipeline
 pub struct ConsensusObserver {
     // The configuration of the consensus observer
     consensus_observer_config: ConsensusObserverConfig,
-    // The consensus observer client to send network messages
-    consensus_observer_client:
-        Arc<ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>>,
 
-    // The current epoch state
-    epoch_state: Option<Arc<EpochState>>,
-    // The latest ledger info (updated via a callback)
-    root: Arc<Mutex<LedgerInfoWithSignatures>>,
+    // The tracker for the root ledger info and epoch state
+    observer_state_tracker: ObserverStateTracker,
+    // The cache of recent epoch states (used to verify messages from previous epochs)
+    epoch_state_cache: EpochStateCache,
+    // A handle to storage (used to read the latest committed state)
+    observer_storage: Arc<dyn ObserverStorageInterface>,
+    // The time service (used to verify the timestamps of observed blocks)
+    time_service: TimeService,
 
     // The payload store holds block transaction payloads
     block_payload_store: BlockPayloadStore,
+    // The cache of verified quorum store proofs (used to verify block payloads)
+    proof_cache: ProofCache,
+    // The cache of ordered and commit proofs verified off the observer loop (if enabled)
+    verified_proof_cache: Option<VerifiedProofCache>,
+    // The subscription and rate limit checks applied before verifying proofs off the loop
+    proof_verification_filter: ProofVerificationFilter,
+    // The decompressor for dictionary compressed block payloads
+    payload_decompressor: PayloadDictionaryDecompressor,
+    // The decoder for delta encoded block payloads (if delta encoding is enabled)
+    payload_delta_decoder: Option<DeltaPayloadDecoder>,
     // The pending ordered blocks (these are also buffered when in state sync mode)
     pending_ordered_blocks: PendingOrderedBlocks,
+    // The buffered commit decision for a future epoch (that can't be verified yet)
+    future_commit_decisions: FutureCommitDecisionBuffer,
     // The execution client to the buffer manager
     execution_client: Arc<dyn TExecutionClient>,
+    // The tracker for consecutive failures to send blocks and commits to the execution pipeline
+    pipeline_failure_tracker: PipelineFailureTracker,
+    // The tracker for the processing deadlines of blocks and commits sent to the execution pipeline
+    pipeline_deadline_tracker: PipelineDeadlineTracker,
+    // The queue of ordered batches being streamed into the execution pipeline (if enabled)
+    incremental_finalization_queue: Option<IncrementalFinalizationQueue>,
 
     // If the sync handle is set it indicates that we're in state sync mode
     sync_handle: Option<DropGuard>,
     // The sender to notify the consensus observer that state sync to the (epoch, round) is done
-    sync_notification_sender: UnboundedSender<(u64, Round)>,
+    sync_notification_sender: SyncNotificationSender,
     // The reconfiguration event listener to refresh on-chain configs
     reconfig_events: Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
 
     // The consensus publisher to forward payload messages
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
-    // The currently active consensus observer subscription
-    active_observer_subscription: Option<ConsensusObserverSubscription>,
-    // A handle to storage (used to read the latest state and check progress)
-    db_reader: Arc<dyn DbReader>,
-    // The time service (used to check progress)
-    time_service: TimeService,
+    // The subscription manager (responsible for the subscription lifecycle)
+    subscription_manager: SubscriptionManager,
+    // The reporter for peer misbehavior (shared with the publisher, if any)
+    peer_misbehavior_reporter: PeerMisbehaviorReporter,
+    // The deduplicator for messages received across concurrent subscriptions (if enabled)
+    message_deduplicator: Option<MessageDeduplicator>,
+    // The rate limiter for the messages received from each peer (if enabled). This
+    // is shared with the proof verification filter (which only reads the budgets).
+    inbound_rate_limiter: Option<Arc<Mutex<InboundRateLimiter>>>,
+
+    // The journal of recent significant observer events
+    event_journal: ObserverEventJournal,
+    // The tracker for the summary statistics of the current epoch
+    epoch_summary_tracker: EpochSummaryTracker,
+    // The tracker for the time spent in each observer state
+    time_in_state_tracker: TimeInStateTracker,
+    // The runtime feature flags for the optional observer subfeatures
+    feature_flags: ObserverFeatureFlags,
+    // The handle used by other components to query the observer status
+    observer_status_handle: ObserverStatusHandle,
+    // The handle used by other components to send control commands to the observer
+    observer_control_handle: ObserverControlHandle,
+    // The receiver for control commands (consumed when the observer starts)
+    control_command_receiver: Option<mpsc::UnboundedReceiver<ObserverControlCommand>>,
+    // Whether observation is paused until the active (forced) state sync completes
+    observation_paused_for_sync: bool,
+    // The epoch and round of the last block sent to the execution pipeline
+    // before finalization was paused by an operator (if finalization is paused)
+    finalization_paused_after: Option<(u64, Round)>,
+
+    // The chain of interceptors invoked on every inbound message
+    message_interceptors: MessageInterceptorChain,
+    // The (non-network) sources of consensus data (consumed when the observer starts)
+    block_sources: Vec<Box<dyn ObserverBlockSource>>,
+    // The exporter for observed data (e.g., used by the gRPC export service)
+    data_exporter: Option<ObserverDataExporter>,
+    // The in-process listeners for observer progress (e.g., verified blocks and commits)
+    event_listeners: ObserverEventListeners,
+    // The journal of all applied commits (used for auditing)
+    commit_journal: Option<CommitJournalWriter>,
+    // The notifier for applied commits (published on the event notification service)
+    observed_commit_notifier: Option<ObservedCommitNotifier>,
+    // The listener for commits synced to storage (consumed when the observer starts)
+    synced_commit_listener: Option<SyncedCommitNotificationListener>,
+    // The snapshotter that periodically persists the observer state (for fast restarts)
+    state_snapshotter: Option<ObserverStateSnapshotter>,
+
+    // The registry of background tasks spawned by the observer
+    task_registry: TaskRegistry,
 }
 
 impl ConsensusObserver {
-    pub fn new(
+    /// Creates a new consensus observer. Note: callers should use
+    /// the `ObserverBuilder` (which validates the observer components).
+    pub(crate) fn new(
         consensus_observer_config: ConsensusObserverConfig,
         consensus_observer_client: Arc<
             ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>,
         >,
-        db_reader: Arc<dyn DbReader>,
+        observer_storage: Arc<dyn ObserverStorageInterface>,
         execution_client: Arc<dyn TExecutionClient>,
-        sync_notification_sender: UnboundedSender<(u64, Round)>,
+        sync_notification_sender: SyncNotificationSender,
         reconfig_events: Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
         consensus_publisher: Option<Arc<ConsensusPublisher>>,
         time_service: TimeService,
+        event_journal: ObserverEventJournal,
+        peer_selector: Arc<dyn SubscriptionPeerSelector>,
+        feature_flags: ObserverFeatureFlags,
     ) -> Self {
         // Read the latest ledger info from storage
-        let root = db_reader
+        let root = observer_storage
             .get_latest_ledger_info()
             .expect("Failed to read latest ledger info!");
 
-        Self {
-            consensus_observer_config,
+        // Create the epoch state cache (and cache the next epoch state of the root, if any)
+        let mut epoch_state_cache = EpochStateCache::new(
+            consensus_observer_config.max_num_cached_epoch_states,
+            observer_storage.clone(),
+        );
+        epoch_state_cache.insert_epoch_ending_ledger_info(&root);
+
+        // Create the peer misbehavior reporter (sharing the publisher's reporter, if any)
+        let peer_misbehavior_reporter = match &consensus_publisher {
+            Some(consensus_publisher) => consensus_publisher.get_peer_misbehavior_reporter(),
+            None => PeerMisbehaviorReporter::new(
+                consensus_observer_config.clone(),
+                consensus_observer_client.clone(),
+            ),
+        };
+
+        // Create the subscription manager
+        let epoch_summary_tracker = EpochSummaryTracker::new(time_service.clone());
+        let task_registry = TaskRegistry::new();
+        let subscription_manager = SubscriptionManager::new(
+            consensus_observer_config.clone(),
             consensus_observer_client,
-            epoch_state: None,
-            root: Arc::new(Mutex::new(root)),
-            pending_ordered_blocks: PendingOrderedBlocks::new(consensus_observer_config),
+            consensus_publisher.clone(),
+            peer_selector,
+            observer_storage.clone(),
+            time_service.clone(),
+            event_journal.clone(),
+            epoch_summary_tracker.clone(),
+            task_registry.clone(),
+        );
+
+        // Create the observer state (and the status handle that reads it)
+        let observer_state_tracker = ObserverStateTracker::new(root);
+        let pending_ordered_blocks = PendingOrderedBlocks::new(consensus_observer_config.clone());
+        let block_payload_store =
+            BlockPayloadStore::new(consensus_observer_config.clone(), time_service.clone());
+        let observer_status_handle = ObserverStatusHandle::new(
+            observer_state_tracker.clone(),
+            pending_ordered_blocks.clone(),
+            block_payload_store.clone(),
+        );
+
+        // Create the control handle (and the receiver for the control commands)
+        let (observer_control_handle, control_command_receiver) = ObserverControlHandle::new();
+
+        // Create the inbound rate limiter (and the proof verification filter that reads it)
+        let inbound_rate_limiter =
+            consensus_observer_config
+                .enable_inbound_rate_limiting
+                .then(|| {
+                    Arc::new(Mutex::new(InboundRateLimiter::new(
+                        consensus_observer_config.clone(),
+                        time_service.clone(),
+                    )))
+                });
+        let proof_verification_filter = ProofVerificationFilter::new(inbound_rate_limiter.clone());
+
+        Self {
+            consensus_observer_config: consensus_observer_config.clone(),
+            observer_state_tracker,
+            epoch_state_cache,
+            observer_storage,
+            time_service: time_service.clone(),
+            pending_ordered_blocks,
+            future_commit_decisions: FutureCommitDecisionBuffer::new(
+                consensus_observer_config.max_future_commit_buffer_ms,
+                consensus_observer_config.max_future_commit_epochs,
+                time_service.clone(),
+            ),
             execution_client,
-            block_payload_store: BlockPayloadStore::new(),
+            pipeline_failure_tracker: PipelineFailureTracker::new(
+                consensus_observer_config.max_consecutive_pipeline_failures,
+            ),
+            pipeline_deadline_tracker: PipelineDeadlineTracker::new(
+                consensus_observer_config.pipeline_processing_deadline_ms,
+                time_service.clone(),
+            ),
+            incremental_finalization_queue: consensus_observer_config
+                .enable_incremental_block_finalization
+                .then(IncrementalFinalizationQueue::new),
+            block_payload_store,
+            proof_cache: Cache::builder()
+                .max_capacity(PROOF_CACHE_CAPACITY)
+                .time_to_live(Duration::from_secs(PROOF_CACHE_TTL_SECS))
+                .build(),
+            verified_proof_cache: consensus_observer_config
+                .enable_parallel_proof_verification
+                .then(VerifiedProofCache::new),
+            proof_verification_filter,
+            payload_decompressor: PayloadDictionaryDecompressor::new(),
+            payload_delta_decoder: consensus_observer_config
+                .enable_payload_delta_encoding
+                .then(|| DeltaPayloadDecoder::new(&consensus_observer_config)),
             sync_handle: None,
             sync_notification_sender,
             reconfig_events,
             consensus_publisher,
-            active_observer_subscription: None,
-            db_reader,
-            time_service,
+            subscription_manager,
+            peer_misbehavior_reporter,
+            message_deduplicator: (consensus_observer_config.max_concurrent_subscriptions > 1)
+                .then(|| MessageDeduplicator::new(MAX_DEDUPLICATED_MESSAGES)),
+            inbound_rate_limiter,
+            event_journal,
+            epoch_summary_tracker,
+            time_in_state_tracker: TimeInStateTracker::new(
+                ObserverState::EpochTransition,
+                time_service,
+            ),
+            feature_flags,
+            observer_status_handle,
+            observer_control_handle,
+            control_command_receiver: Some(control_command_receiver),
+            observation_paused_for_sync: false,
+            finalization_paused_after: None,
+            message_interceptors: MessageInterceptorChain::new(),
+            block_sources: vec![],
+            data_exporter: None,
+            event_listeners: ObserverEventListeners::new(),
+            commit_journal: None,
+            observed_commit_notifier: None,
+            synced_commit_listener: None,
+            state_snapshotter: None,
+            task_registry,
         }
     }
 
-    /// Checks the progress of the consensus observer
-    async fn check_progress(&mut self) {
-        debug!(LogSchema::new(LogEntry::ConsensusObserver)
-            .message("Checking consensus observer progress!"));
+    /// Adds the given block source to the observer. Note: block sources
+    /// must be added before the observer is started.
+    pub fn add_block_source(&mut self, block_source: Box<dyn ObserverBlockSource>) {
+        self.block_sources.push(block_source);
+    }
 
-        // Get the peer ID of the currently active subscription (if any)
-        let active_subscription_peer = self
-            .active_observer_subscription
-            .as_ref()
-            .map(|subscription| subscription.get_peer_network_id());
+    /// Aborts observation and forces a state sync to the given commit (or the
+    /// latest known commit, if none is given). Observation is paused until the
+    /// sync completes. Returns the block info of the sync target.
+    pub(crate) fn force_state_sync(
+        &mut self,
+        ledger_info: Option<LedgerInfoWithSignatures>,
+    ) -> Result<BlockInfo, Error> {
+        // Identify (and verify) the commit to sync to
+        let commit_decision = match ledger_info {
+            Some(ledger_info) => {
+                // Verify the commit is for the current epoch
+                let commit_decision = CommitDecision::new(ledger_info);
+                let epoch_state = self.observer_state_tracker.epoch_state();
+                if commit_decision.epoch() != epoch_state.epoch {
+                    return Err(Error::InvalidMessageError(format!(
+                        "The sync target must be in the current epoch: {}! Sync target: {}",
+                        epoch_state.epoch,
+                        commit_decision.proof_block_info()
+                    )));
+                }
+
+                // Verify the commit proof and that the commit is beyond the root
+                commit_decision.verify_commit_proof(&epoch_state)?;
+                let root_block = self.observer_state_tracker.root_block();
+                if commit_decision.round() <= root_block.round() {
+                    return Err(Error::InvalidMessageError(format!(
+                        "The sync target must be beyond the root: {}! Sync target: {}",
+                        root_block,
+                        commit_decision.proof_block_info()
+                    )));
+                }
+
+                commit_decision
+            },
+            None => self.get_latest_commit_decision(),
+        };
+
+        // Abort observation (i.e., terminate the subscription and pause resubscriptions)
+        self.subscription_manager
+            .terminate_active_subscription(Error::SubscriptionOverridden(
+                "Observation was aborted for a forced state sync!".into(),
+            ));
+        self.observation_paused_for_sync = true;
+
+        // Sync to the commit (this also aborts any active sync)
+        let sync_target = commit_decision.proof_block_info().clone();
+        self.start_state_sync(commit_decision);
+
+        Ok(sync_target)
+    }
+
+    /// Returns the data exporter (if the exporter exists and the export streams are enabled)
+    fn get_data_exporter(&self) -> Option<&ObserverDataExporter> {
+        if self
+            .feature_flags
+            .is_enabled(ObserverFeature::ExportStreams)
+        {
+            self.data_exporter.as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Returns a handle to send control commands to the observer
+    pub fn get_control_handle(&self) -> ObserverControlHandle {
+        self.observer_control_handle.clone()
+    }
+
+    /// Returns the runtime feature flags of the observer
+    pub fn get_feature_flags(&self) -> ObserverFeatureFlags {
+        self.feature_flags.clone()
+    }
+
+    /// Returns the latest known (verified) commit decision, i.e., the latest
+    /// commit decision of the pending blocks, or the root (if there is none).
+    fn get_latest_commit_decision(&self) -> CommitDecision {
+        self.pending_ordered_blocks
+            .get_all_verified_pending_blocks()
+            .into_values()
+            .rev()
+            .find_map(|(_, commit_decision)| commit_decision)
+            .unwrap_or_else(|| {
+                CommitDecision::new_with_shared_proof(self.observer_state_tracker.root())
+            })
+    }
+
+    /// Returns the operator overrides for subscription peer selection
+    pub fn get_peer_overrides(&self) -> SubscriptionPeerOverrides {
+        self.subscription_manager.get_peer_overrides()
+    }
+
+    /// Returns a handle to query the status of the observer
+    pub fn get_status_handle(&self) -> ObserverStatusHandle {
+        self.observer_status_handle.clone()
+    }
+
+    /// Registers the given listener for observer progress (i.e., verified ordered
+    /// blocks, commit decisions and state sync transitions). Note: listeners
+    /// must be registered before the observer is started.
+    pub fn register_listener(&mut self, listener: Box<dyn ObserverEventListener>) {
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Registered the observer event listener: {}",
+                listener.name()
+            ))
+        );
+        self.event_listeners.register_listener(listener);
+    }
+
+    /// Sets the journal to which all applied commits are appended
+    pub fn set_commit_journal(&mut self, commit_journal: CommitJournalWriter) {
+        self.commit_journal = Some(commit_journal);
+    }
+
+    /// Sets the exporter for all observed (and verified) data
+    pub fn set_data_exporter(&mut self, data_exporter: ObserverDataExporter) {
+        self.data_exporter = Some(data_exporter);
+    }
+
+    /// Sets the notifier used to publish applied commits to other node subsystems
+    pub fn set_observed_commit_notifier(
+        &mut self,
+        observed_commit_notifier: ObservedCommitNotifier,
+    ) {
+        self.observed_commit_notifier = Some(observed_commit_notifier);
+    }
+
+    /// Sets the persistent storage for the block payloads, and restores the
+    /// payloads persisted before the node restarted (any payloads at or
+    /// below the current root are stale, and are pruned immediately).
+    pub fn set_payload_store_db(&mut self, payload_store_db: Arc<ObserverPayloadDb>) {
+        self.block_payload_store
+            .set_payload_store_db(payload_store_db);
+        self.block_payload_store
+            .remove_payloads_for_commit(&self.observer_state_tracker.root_block());
+    }
+
+    /// Sets the listener for commits synced to storage (e.g., by state sync).
+    /// Note: the listener must be set before the observer is started.
+    pub fn set_synced_commit_listener(
+        &mut self,
+        synced_commit_listener: SyncedCommitNotificationListener,
+    ) {
+        self.synced_commit_listener = Some(synced_commit_listener);
+    }
+
+    /// Sets the snapshotter used to periodically persist the observer state
+    pub fn set_state_snapshotter(&mut self, state_snapshotter: ObserverStateSnapshotter) {
+        self.state_snapshotter = Some(state_snapshotter);
+    }
+
+    /// Adds the given interceptor to the end of the inbound message interceptor chain
+    pub fn add_message_interceptor(
+        &self,
+        message_interceptor: Arc<dyn ConsensusObserverMessageInterceptor>,
+    ) {
+        self.message_interceptors
+            .add_interceptor(message_interceptor);
+    }
 
-        // If we have an active subscription, verify that the subscription
-        // is still healthy. If not, the subscription should be terminated.
-        if let Some(active_subscription_peer) = active_subscription_peer {
-            if let Err(error) = self.check_active_subscription() {
-                // Log the subscription termination
+    /// Prunes the pending blocks (and their payloads) that were already committed
+    /// to storage. For example, after a restart, storage may have been synced
+    /// beyond the observer root (e.g., by state sync while the node was
+    /// bootstrapping). Such blocks will never be sent to the execution pipeline,
+    /// so pruning them avoids re-fetching their payloads from the publisher.
+    fn prune_committed_pending_blocks(&mut self) {
+        // Get the latest committed ledger info in storage
+        let latest_ledger_info = match self.observer_storage.get_latest_ledger_info() {
+            Ok(latest_ledger_info) => latest_ledger_info,
+            Err(error) => {
                 warn!(
                     LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                        "Terminating subscription to peer: {:?}! Error: {:?}",
-                        active_subscription_peer, error
+                        "Failed to read the latest ledger info to prune committed blocks! Error: {:?}",
+                        error
                     ))
                 );
+                return;
+            },
+        };
 
-                // Unsubscribe from the peer
-                self.unsubscribe_from_peer(active_subscription_peer);
+        // Prune the pending blocks (and payloads) at or below the latest commit
+        let num_pruned_blocks = self.prune_pending_blocks_for_commit(&latest_ledger_info);
+        if num_pruned_blocks > 0 {
+            info!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Pruned {} pending blocks that were already committed to storage! \
+                    Latest committed block: {}",
+                    num_pruned_blocks,
+                    latest_ledger_info.commit_info()
+                ))
+            );
+        }
+    }
 
-                // Update the subscription termination metrics
-                self.update_subscription_termination_metrics(active_subscription_peer, error);
-            }
+    /// Removes all pending blocks (and their payloads) at or below the given
+    /// commit ledger info, in the same way as the commit path. This also
+    /// prunes the payloads of any older blocks that were never ordered.
+    /// Returns the number of pending blocks that were removed.
+    fn prune_pending_blocks_for_commit(
+        &mut self,
+        commit_ledger_info: &LedgerInfoWithSignatures,
+    ) -> usize {
+        // Prune the payloads of any blocks that were never ordered
+        self.block_payload_store
+            .remove_payloads_for_commit(commit_ledger_info.commit_info());
+
+        // Remove the stale pending blocks
+        let removed_blocks = self
+            .pending_ordered_blocks
+            .remove_blocks_for_commit(commit_ledger_info);
+
+        // Remove the payloads of the stale pending blocks (as a single batch)
+        if !removed_blocks.is_empty() {
+            self.block_payload_store
+                .remove_ordered_blocks(&removed_blocks);
         }
 
-        // If we don't have a subscription, we should select a new peer to
-        // subscribe to. If we had a previous subscription, it should be
-        // excluded from the selection process.
-        if self.active_observer_subscription.is_none() {
-            // Create a new observer subscription
-            self.create_new_observer_subscription(active_subscription_peer)
-                .await;
+        removed_blocks.len()
+    }
+
+    /// Backfills the ordered blocks missing between our last block and the
+    /// given (verified) ordered block, by requesting them from the peer that
+    /// sent the block. If the missing blocks are processed successfully, the
+    /// given block is re-processed (instead of dropping it and waiting for
+    /// state sync). Note: the gap is bounded by the maximum number of backfill
+    /// rounds, as the request is awaited by the observer.
+    async fn backfill_missing_ordered_blocks(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        ordered_block: Arc<OrderedBlock>,
+    ) {
+        // Identify the missing rounds (only gaps within the same epoch can be backfilled)
+        let last_block = self
+            .observer_state_tracker
+            .last_block(&self.pending_ordered_blocks);
+        if last_block.epoch() != ordered_block.first_block().epoch() {
+            return;
+        }
+        let start_round = last_block.round().saturating_add(1);
+        let end_round = ordered_block.first_block().round().saturating_sub(1);
+        if start_round > end_round {
+            return; // There are no missing rounds (e.g., the parent was forked)
+        }
+
+        // Verify the gap is small enough to backfill
+        let num_missing_rounds = end_round - start_round + 1;
+        let max_backfill_rounds = self
+            .consensus_observer_config
+            .max_ordered_block_backfill_rounds;
+        if num_missing_rounds > max_backfill_rounds {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Too many missing rounds to backfill: {} (max: {})! Ignoring: {:?}",
+                    num_missing_rounds,
+                    max_backfill_rounds,
+                    ordered_block.proof_block_info()
+                ))
+            );
+            metrics::increment_ordered_block_backfills(metrics::BACKFILL_SKIPPED_LABEL);
+            return;
+        }
 
-            // If we successfully created a new subscription, update the subscription creation metrics
-            if let Some(active_subscription) = &self.active_observer_subscription {
-                self.update_subscription_creation_metrics(
-                    active_subscription.get_peer_network_id(),
+        // Request the missing ordered blocks from the peer
+        let missing_blocks = match self
+            .subscription_manager
+            .request_ordered_blocks(&peer_network_id, start_round, end_round)
+            .await
+        {
+            Ok(missing_blocks) => missing_blocks,
+            Err(error) => {
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to request the missing ordered blocks in rounds [{}, {}] \
+                        from peer: {}! Error: {:?}",
+                        start_round, end_round, peer_network_id, error
+                    ))
                 );
+                metrics::increment_ordered_block_backfills(metrics::BACKFILL_FAILED_LABEL);
+                return;
+            },
+        };
+
+        // Process the missing blocks (in round order), ignoring any unrequested blocks
+        let mut missing_blocks: Vec<_> = missing_blocks
+            .into_iter()
+            .filter(|missing_block| {
+                let round = missing_block.proof_block_info().round();
+                (start_round..=end_round).contains(&round)
+            })
+            .collect();
+        missing_blocks.sort_by_key(|missing_block| missing_block.proof_block_info().round());
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Backfilling {} missing ordered blocks in rounds [{}, {}] from peer: {}",
+                missing_blocks.len(),
+                start_round,
+                end_round,
+                peer_network_id
+            ))
+        );
+        for missing_block in missing_blocks {
+            if self
+                .process_ordered_block(peer_network_id, missing_block)
+                .await
+                .is_some()
+            {
+                metrics::increment_ordered_block_backfills(metrics::BACKFILL_FAILED_LABEL);
+                return; // The missing block couldn't be inserted
             }
         }
+
+        // Re-process the original ordered block (now that its parent should exist)
+        let backfill_outcome = if self
+            .process_ordered_block(peer_network_id, (*ordered_block).clone())
+            .await
+            .is_none()
+        {
+            metrics::BACKFILL_SUCCEEDED_LABEL
+        } else {
+            metrics::BACKFILL_FAILED_LABEL
+        };
+        metrics::increment_ordered_block_backfills(backfill_outcome);
     }
 
-    /// Checks if the active subscription is still healthy. If not, an error is returned.
-    fn check_active_subscription(&mut self) -> Result<(), Error> {
-        let active_observer_subscription = self.active_observer_subscription.take();
-        if let Some(mut active_subscription) = active_observer_subscription {
-            // Check if the peer for the subscription is still connected
-            let peer_network_id = active_subscription.get_peer_network_id();
-            let peer_still_connected = self
-                .get_connected_peers_and_metadata()
-                .map_or(false, |peers_and_metadata| {
-                    peers_and_metadata.contains_key(&peer_network_id)
-                });
+    /// Checks the progress of the consensus observer
+    pub(crate) async fn check_progress(&mut self) {
+        debug!(LogSchema::new(LogEntry::ConsensusObserver)
+            .message("Checking consensus observer progress!"));
 
-            // Verify the peer is still connected
-            if !peer_still_connected {
-                return Err(Error::SubscriptionDisconnected(
-                    "The peer is no longer connected!".to_string(),
-                ));
-            }
+        // Check the health of the active subscription (and create a new one if
+        // required). Note: this is skipped if observation is paused for a sync,
+        // or if the observer is replaying an archive (only archived messages are
+        // processed, so that the replay is deterministic).
+        if !self.observation_paused_for_sync
+            && !self.consensus_observer_config.enable_block_archive_replay
+        {
+            self.subscription_manager
+                .check_and_manage_subscriptions()
+                .await;
+        }
 
-            // Verify the subscription has not timed out
-            active_subscription.check_subscription_timeout()?;
+        // Reset the execution pipeline if the subscription was replaced
+        self.reset_pipeline_if_subscription_replaced().await;
 
-            // Verify that the DB is continuing to sync and commit new data.
-            // Note: we should only do this if we're not waiting for state sync.
-            active_subscription.check_syncing_progress()?;
+        // Garbage collect the misbehavior scores of disconnected peers
+        self.peer_misbehavior_reporter.garbage_collect_scores();
 
-            // Verify that the subscription peer is optimal
-            if let Some(peers_and_metadata) = self.get_connected_peers_and_metadata() {
-                active_subscription.check_subscription_peer_optimality(peers_and_metadata)?;
-            }
+        // Garbage collect the rate limits of peers that are no longer subscribed to
+        if let Some(inbound_rate_limiter) = &self.inbound_rate_limiter {
+            inbound_rate_limiter
+                .lock()
+                .garbage_collect(&self.subscription_manager.get_subscription_peers());
+        }
 
-            // The subscription seems healthy, we can keep it
-            self.active_observer_subscription = Some(active_subscription);
+        // Drop the future commit decisions that couldn't be verified in time
+        self.process_expired_future_commit_decisions();
+
+        // Request the missing payloads of pending blocks from the subscription peer (if enabled)
+        if self.consensus_observer_config.enable_block_payload_requests {
+            self.request_missing_block_payloads().await;
         }
 
-        Ok(())
+        // Escalate any repeated failures to send data to the execution pipeline
+        if self.pipeline_failure_tracker.should_escalate() {
+            self.escalate_pipeline_failures().await;
+        }
+
+        // Acknowledge the received payloads to the publisher (if delta encoding is enabled)
+        if let Some(payload_acknowledgement) = self
+            .payload_delta_decoder
+            .as_mut()
+            .and_then(|payload_delta_decoder| payload_delta_decoder.take_payload_acknowledgement())
+        {
+            self.subscription_manager
+                .send_payload_acknowledgement(payload_acknowledgement);
+        }
+
+        // Update the target capacity of the payload store (from the observed block rate)
+        self.block_payload_store.update_target_capacity();
+        self.block_payload_store.update_occupancy_metrics();
+
+        // Check for blocks and commits that missed their processing deadlines
+        let num_overdue_handoffs = self.pipeline_deadline_tracker.check_deadlines();
+        if num_overdue_handoffs > 0 {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "The execution pipeline has missed the processing deadlines of {} handoffs!",
+                    num_overdue_handoffs
+                ))
+            );
+        }
+
+        // Update the time spent in the current observer state
+        self.update_observer_state();
+
+        // Persist a snapshot of the observer state (if required)
+        self.take_state_snapshot_if_required();
     }
 
     /// Creates and returns a commit callback (to be called after the execution pipeline)
     fn create_commit_callback(&self) -> StateComputerCommitCallBackType {
-        // Clone the root, pending blocks and payload store
-        let root = self.root.clone();
+        // Clone the state tracker, pending blocks, payload store, epoch summary tracker,
+        // pipeline deadline tracker, commit journal and commit notifier.
+        let observer_state_tracker = self.observer_state_tracker.clone();
         let pending_ordered_blocks = self.pending_ordered_blocks.clone();
         let block_payload_store = self.block_payload_store.clone();
+        let epoch_summary_tracker = self.epoch_summary_tracker.clone();
+        let pipeline_deadline_tracker = self.pipeline_deadline_tracker.clone();
+        let commit_journal = self.commit_journal.clone();
+        let observed_commit_notifier = self.observed_commit_notifier.clone();
 
         // Create the commit callback
         Box::new(move |blocks, ledger_info: LedgerInfoWithSignatures| {
-            // Remove the committed blocks from the payload store
+            fail_point!("consensus_observer::commit_callback", |_| {});
+
+            // Share the ledger info (to avoid copying it for the root and subscribers)
+            let ledger_info = Arc::new(ledger_info);
+
+            // Remove the committed blocks from the payload store (and prune
+            // the payloads of any older blocks that were never ordered).
             block_payload_store.remove_blocks(blocks);
+            block_payload_store.remove_payloads_for_commit(ledger_info.commit_info());
 
             // Remove the committed blocks from the pending blocks
             pending_ordered_blocks.remove_blocks_for_commit(&ledger_info);
 
-            // Verify the ledger info is for the same epoch
-            let mut root = root.lock();
-            if ledger_info.commit_info().epoch() != root.commit_info().epoch() {
+            // Update the root ledger info. Note: this will be ignored if the
+            // ledger info is for a different epoch, or if the round is not
+            // greater than the current root round (e.g., due to state sync).
+            let commit_info = ledger_info.commit_info().clone();
+            pipeline_deadline_tracker.complete_handoffs(&commit_info);
+            let observed_commit = observed_commit_notifier
+                .as_ref()
+                .map(|notifier| (notifier, ledger_info.clone()));
+            if let Err(error) = observer_state_tracker.advance_root(ledger_info) {
                 warn!(
                     LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                        "Received commit callback for a different epoch! Ledger info: {:?}, Root: {:?}",
-                        ledger_info.commit_info(),
-                        root.commit_info()
+                        "Failed to advance the root in the commit callback! Error: {:?}",
+                        error
                     ))
                 );
                 return;
             }
 
-            // Update the root ledger info. Note: we only want to do this if
-            // the new ledger info round is greater than the current root
-            // round. Otherwise, this can race with the state sync process.
-            if ledger_info.commit_info().round() > root.commit_info().round() {
-                *root = ledger_info;
+            // Update the epoch summary with the applied commit
+            epoch_summary_tracker.record_commit_applied(commit_info.timestamp_usecs());
+
+            // Append the applied commit to the commit journal
+            if let Some(commit_journal) = &commit_journal {
+                commit_journal.record_commit(&commit_info);
+            }
+
+            // Publish the applied commit to the other node subsystems
+            if let Some((observed_commit_notifier, ledger_info)) = observed_commit {
+                if let Err(error) = observed_commit_notifier.notify_observed_commit(ledger_info) {
+                    warn!(
+                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                            "Failed to notify subscribers of the observed commit! Error: {:?}",
+                            error
+                        ))
+                    );
+                }
             }
         })
     }
 
-    /// Creates a new observer subscription by sending subscription requests to
-    /// appropriate peers and waiting for a successful response. If `previous_subscription_peer`
-    /// is provided, it will be excluded from the selection process.
-    async fn create_new_observer_subscription(
-        &mut self,
-        previous_subscription_peer: Option<PeerNetworkId>,
-    ) {
-        // Get a set of sorted peers to service our subscription request
-        let sorted_peers = match self.sort_peers_for_subscription(previous_subscription_peer) {
-            Some(sorted_peers) => sorted_peers,
-            None => {
-                error!(LogSchema::new(LogEntry::ConsensusObserver)
-                    .message("Failed to sort peers for subscription requests!"));
-                return;
-            },
-        };
+    /// Finalizes the ordered block by sending it to the execution pipeline. If
+    /// incremental finalization is enabled, the blocks of the batch are streamed
+    /// into the pipeline as their payloads arrive (see `finalize_incremental_blocks()`).
+    async fn finalize_ordered_block(&mut self, ordered_block: Arc<OrderedBlock>) {
+        fail_point!("consensus_observer::finalize_ordered_block", |_| {});
 
-        // Verify that we have potential peers
-        if sorted_peers.is_empty() {
-            warn!(LogSchema::new(LogEntry::ConsensusObserver)
-                .message("There are no peers to subscribe to!"));
+        // The pipeline is torn down while syncing, so no blocks are accepted
+        if self.sync_handle.is_some() {
+            debug!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Not finalizing the ordered block while syncing: {}",
+                    ordered_block.proof_block_info()
+                ))
+            );
             return;
         }
 
-        // Go through the sorted peers and attempt to subscribe to a single peer.
-        // The first peer that responds successfully will be the selected peer.
-        for selected_peer in &sorted_peers {
-            info!(
+        // No blocks are sent to the pipeline while finalization is paused
+        if self.finalization_paused_after.is_some() {
+            debug!(
                 LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                    "Attempting to subscribe to peer: {}!",
-                    selected_peer
+                    "Not finalizing the ordered block while finalization is paused: {}",
+                    ordered_block.proof_block_info()
                 ))
             );
-
-            // Send a subscription request to the peer and wait for the response.
-            // Note: it is fine to block here because we assume only a single active subscription.
-            let subscription_request = ConsensusObserverRequest::Subscribe;
-            let response = self
-                .consensus_observer_client
-                .send_rpc_request_to_peer(
-                    selected_peer,
-                    subscription_request,
-                    self.consensus_observer_config.network_request_timeout_ms,
-                )
-                .await;
-
-            // Process the response and update the active subscription
-            match response {
-                Ok(ConsensusObserverResponse::SubscribeAck) => {
-                    info!(
-                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                            "Successfully subscribed to peer: {}!",
-                            selected_peer
-                        ))
-                    );
-
-                    // Update the active subscription
-                    let subscription = ConsensusObserverSubscription::new(
-                        self.consensus_observer_config,
-                        self.db_reader.clone(),
-                        *selected_peer,
-                        self.time_service.clone(),
-                    );
-                    self.active_observer_subscription = Some(subscription);
-
-                    return; // Return after successfully subscribing
-                },
-                Ok(response) => {
-                    // We received an invalid response
-                    warn!(
-                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                            "Got unexpected response type: {:?}",
-                            response.get_label()
-                        ))
-                    );
-                },
-                Err(error) => {
-                    // We encountered an error while sending the request
-                    error!(
-                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                            "Failed to send subscription request to peer: {}! Error: {:?}",
-                            selected_peer, error
-                        ))
-                    );
-                },
-            }
+            return;
         }
 
-        // We failed to connect to any peers
-        warn!(
-            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                "Failed to subscribe to any peers! Num peers attempted: {:?}",
-                sorted_peers.len()
-            ))
-        );
-    }
+        // If incremental finalization is enabled, stream the blocks into the pipeline
+        if let Some(incremental_finalization_queue) = &mut self.incremental_finalization_queue {
+            incremental_finalization_queue.push_ordered_block(ordered_block);
+            self.finalize_incremental_blocks().await;
+            return;
+        }
 
-    /// Finalizes the ordered block by sending it to the execution pipeline
-    async fn finalize_ordered_block(&mut self, ordered_block: OrderedBlock) {
         if let Err(error) = self
-            .execution_client
-            .finalize_order(
-                ordered_block.blocks(),
-                ordered_block.ordered_proof().clone(),
-                self.create_commit_callback(),
-            )
+            .send_ordered_blocks_to_pipeline(ordered_block.blocks(), ordered_block.ordered_proof())
             .await
         {
             error!(
@@ -369,95 +873,482 @@ impl ConsensusObserver {
                     error
                 ))
             );
+            self.pipeline_failure_tracker
+                .record_failure(metrics::FINALIZE_ORDERED_BLOCK_LABEL);
+        } else {
+            self.pipeline_failure_tracker.record_success();
+            self.pipeline_deadline_tracker.add_handoff(
+                PipelineHandoff::OrderedBlock,
+                ordered_block.proof_block_info(),
+            );
         }
     }
 
-    /// Forwards the commit decision to the execution pipeline
-    fn forward_commit_decision(&self, commit_decision: CommitDecision) {
-        // Create a dummy RPC message
-        let (response_sender, _response_receiver) = oneshot::channel();
-        let commit_request = IncomingCommitRequest {
-            req: CommitMessage::Decision(pipeline::commit_decision::CommitDecision::new(
-                commit_decision.commit_proof().clone(),
-            )),
-            protocol: ProtocolId::ConsensusDirectSendCompressed,
-            response_sender,
-        };
-
-        // Send the message to the execution client
-        if let Err(error) = self
-            .execution_client
-            .send_commit_msg(AccountAddress::ONE, commit_request)
-        {
-            error!(
-                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                    "Failed to send commit decision to the execution pipeline! Error: {:?}",
-                    error
-                ))
-            )
-        };
-    }
-
-    /// Returns the current epoch state, and panics if it is not set
-    fn get_epoch_state(&self) -> Arc<EpochState> {
-        self.epoch_state
-            .clone()
-            .expect("The epoch state is not set! This should never happen!")
-    }
-
-    /// Returns the last known block
-    fn get_last_block(&self) -> BlockInfo {
-        if let Some(last_pending_block) = self.pending_ordered_blocks.get_last_pending_block() {
-            last_pending_block
-        } else {
-            // Return the root ledger info
-            self.root.lock().commit_info().clone()
+    /// Streams the queued ordered batches into the execution pipeline. The blocks
+    /// of each batch are sent (in order) as soon as their payloads are available,
+    /// instead of waiting for the payloads of the entire batch. All sub-batches
+    /// carry the ordered proof of the batch: the pipeline only commits them once
+    /// the commit decision for the last block of the batch arrives.
+    async fn finalize_incremental_blocks(&mut self) {
+        // No blocks are sent to the pipeline while syncing or paused
+        if self.sync_handle.is_some() || self.finalization_paused_after.is_some() {
+            return;
         }
-    }
 
-    /// Gets the connected peers and metadata. If an error occurred,
-    /// it is logged and None is returned.
-    fn get_connected_peers_and_metadata(&self) -> Option<HashMap<PeerNetworkId, PeerMetadata>> {
-        match self
-            .consensus_observer_client
-            .get_peers_and_metadata()
-            .get_connected_peers_and_metadata()
-        {
-            Ok(connected_peers_and_metadata) => Some(connected_peers_and_metadata),
-            Err(error) => {
+        loop {
+            // Take the next blocks that can be sent to the pipeline
+            let block_payload_store = &self.block_payload_store;
+            let finalizable_blocks = match &mut self.incremental_finalization_queue {
+                Some(incremental_finalization_queue) => incremental_finalization_queue
+                    .take_next_finalizable_blocks(|block| {
+                        block_payload_store.all_payloads_exist(&[block.clone()])
+                    }),
+                None => return, // Incremental finalization is disabled
+            };
+            let Some(finalizable_blocks) = finalizable_blocks else {
+                return; // The next block is still waiting for its payload
+            };
+
+            // Send the blocks to the execution pipeline
+            let ordered_block = finalizable_blocks.ordered_block;
+            if let Err(error) = self
+                .send_ordered_blocks_to_pipeline(
+                    &finalizable_blocks.blocks,
+                    ordered_block.ordered_proof(),
+                )
+                .await
+            {
                 error!(
                     LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                        "Failed to get connected peers and metadata! Error: {:?}",
+                        "Failed to finalize blocks of the ordered block: {}! Error: {:?}",
+                        ordered_block.proof_block_info(),
                         error
                     ))
                 );
-                None
-            },
+                self.pipeline_failure_tracker
+                    .record_failure(metrics::FINALIZE_ORDERED_BLOCK_LABEL);
+
+                // The remaining blocks no longer extend the pipeline, so they
+                // are dropped (the failure handling recovers them via sync).
+                self.clear_incremental_finalization_queue();
+                return;
+            }
+            self.pipeline_failure_tracker.record_success();
+
+            // If the batch is complete, track the handoff and forward any commit
+            // decision that arrived before the last block reached the pipeline
+            // (the pipeline drops commit decisions for blocks it doesn't hold).
+            if finalizable_blocks.completes_ordered_block {
+                let proof_block_info = ordered_block.proof_block_info();
+                self.pipeline_deadline_tracker
+                    .add_handoff(PipelineHandoff::OrderedBlock, proof_block_info);
+                if let Some(commit_decision) = self
+                    .pending_ordered_blocks
+                    .get_commit_decision(proof_block_info.epoch(), proof_block_info.round())
+                {
+                    self.forward_commit_decision(&commit_decision);
+                }
+            }
+        }
+    }
+
+    /// Clears the ordered batches being streamed into the execution pipeline
+    /// (e.g., when the pipeline is torn down or reset).
+    fn clear_incremental_finalization_queue(&mut self) {
+        if let Some(incremental_finalization_queue) = &mut self.incremental_finalization_queue {
+            incremental_finalization_queue.clear();
+        }
+    }
+
+    /// Sends the ordered blocks to the execution pipeline (to be executed and
+    /// committed). Errors can be injected here to exercise the pipeline
+    /// failure handling (e.g., the escalation of repeated failures).
+    async fn send_ordered_blocks_to_pipeline(
+        &self,
+        blocks: &[Arc<PipelinedBlock>],
+        ordered_proof: &LedgerInfoWithSignatures,
+    ) -> ExecutorResult<()> {
+        fail_point!("consensus_observer::finalize_order", |_| {
+            Err(aptos_executor_types::ExecutorError::InternalError {
+                error: "Injected error in finalize_order".into(),
+            })
+        });
+
+        self.execution_client
+            .finalize_order(blocks, ordered_proof.clone(), self.create_commit_callback())
+            .await
+    }
+
+    /// Forwards the commit decision to the execution pipeline
+    fn forward_commit_decision(&self, commit_decision: &CommitDecision) {
+        // The pipeline is torn down while syncing, so no commits are accepted
+        if self.sync_handle.is_some() {
+            debug!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Not forwarding the commit decision while syncing: {}",
+                    commit_decision.proof_block_info()
+                ))
+            );
+            return;
+        }
+
+        // No commits are sent to the pipeline while finalization is paused
+        if self.finalization_paused_after.is_some() {
+            debug!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Not forwarding the commit decision while finalization is paused: {}",
+                    commit_decision.proof_block_info()
+                ))
+            );
+            return;
+        }
+
+        // Create a dummy RPC message
+        let (response_sender, _response_receiver) = oneshot::channel();
+        let commit_request = IncomingCommitRequest {
+            req: CommitMessage::Decision(pipeline::commit_decision::CommitDecision::new(
+                commit_decision.commit_proof().clone(),
+            )),
+            protocol: ProtocolId::ConsensusDirectSendCompressed,
+            response_sender,
+        };
+
+        // Send the message to the execution client
+        if let Err(error) = self
+            .execution_client
+            .send_commit_msg(AccountAddress::ONE, commit_request)
+        {
+            error!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to send commit decision to the execution pipeline! Error: {:?}",
+                    error
+                ))
+            );
+            self.pipeline_failure_tracker
+                .record_failure(metrics::FORWARD_COMMIT_DECISION_LABEL);
+        } else {
+            self.pipeline_failure_tracker.record_success();
+            self.pipeline_deadline_tracker.add_handoff(
+                PipelineHandoff::CommitDecision,
+                commit_decision.proof_block_info(),
+            );
+        }
+    }
+
+    /// Escalates the repeated failures to send data to the execution pipeline
+    /// (as specified by the config). This resets the pipeline (by syncing to
+    /// the latest known commit) and optionally resubscribes to a new peer.
+    async fn escalate_pipeline_failures(&mut self) {
+        // Reset the failure tracker (to avoid escalating again immediately)
+        let num_failures = self.pipeline_failure_tracker.get_num_consecutive_failures();
+        self.pipeline_failure_tracker.reset();
+
+        // Log the escalation and update the metrics and event journal
+        let escalation = self.consensus_observer_config.pipeline_failure_escalation;
+        warn!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Escalating {} consecutive execution pipeline failures! Escalation: {:?}",
+                num_failures, escalation
+            ))
+        );
+        metrics::increment_pipeline_failure_escalation(escalation.get_label());
+        self.observer_status_handle.record_error(format!(
+            "{} consecutive execution pipeline failures (escalation: {})",
+            num_failures,
+            escalation.get_label()
+        ));
+        self.event_journal
+            .record_event(ObserverEvent::PipelineFailuresEscalated {
+                num_failures,
+                escalation: escalation.get_label().into(),
+            });
+
+        // Reset the execution pipeline
+        self.reset_execution_pipeline();
+
+        // Resubscribe to a new peer (if required)
+        if escalation == PipelineFailureEscalation::Resubscribe {
+            self.subscription_manager
+                .terminate_and_resubscribe(Error::PipelineFailure(format!(
+                    "{} consecutive failures to send data to the execution pipeline!",
+                    num_failures
+                )))
+                .await;
+            self.reset_pipeline_if_subscription_replaced().await;
+            self.update_observer_state();
+        }
+    }
+
+    /// Returns true iff the maximum number of pending blocks has been reached,
+    /// and the observer should fall back to state sync (as specified by the config).
+    /// Note: if we're already syncing, new blocks are dropped until the sync completes.
+    fn should_sync_on_pending_blocks_overflow(&self) -> bool {
+        self.consensus_observer_config
+            .enable_pending_blocks_overflow_sync
+            && self.sync_handle.is_none()
+            && self.pending_ordered_blocks.get_num_pending_blocks()
+                >= self.consensus_observer_config.max_num_pending_blocks as usize
+    }
+
+    /// Abandons all pending blocks (i.e., the buffered window of unexecuted blocks)
+    /// and falls back to state sync to the latest known commit decision, instead
+    /// of buffering more blocks (e.g., because execution is slow, or payloads are
+    /// missing). New blocks are processed once the sync completes.
+    fn sync_on_pending_blocks_overflow(&mut self) {
+        // Identify the latest commit decision (before the pending blocks are dropped)
+        let latest_commit_decision = self.get_latest_commit_decision();
+
+        // Drop the pending blocks
+        let num_dropped_blocks = self.pending_ordered_blocks.clear_all_pending_blocks();
+        warn!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Reached the maximum number of pending blocks: {}! Dropped all pending blocks \
+                and falling back to state sync: {}",
+                num_dropped_blocks,
+                latest_commit_decision.proof_block_info()
+            ))
+        );
+
+        // Start syncing to the latest commit decision
+        self.start_state_sync(latest_commit_decision);
+    }
+
+    /// Handles the payloads evicted from the payload store (because the store
+    /// exceeded its limits). If any of the evicted payloads are still needed by
+    /// the pending blocks, the blocks can't be executed, so we fall back to state
+    /// sync (to the latest known commit) instead of waiting on the payloads.
+    fn handle_evicted_payloads(&mut self, evicted_payloads: &[(u64, Round, HashValue)]) {
+        if evicted_payloads.is_empty() {
+            return;
+        }
+
+        // Identify the evicted payloads that are still needed
+        let num_needed_payloads = evicted_payloads
+            .iter()
+            .filter(|(epoch, round, block_id)| {
+                self.pending_ordered_blocks
+                    .contains_block(*epoch, *round, block_id)
+            })
+            .count();
+        if num_needed_payloads == 0 {
+            debug!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Evicted {} unneeded payloads from the payload store!",
+                    evicted_payloads.len()
+                ))
+            );
+            return;
+        }
+
+        // Fall back to state sync
+        warn!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Evicted {} payloads still needed by the pending blocks from the payload store! \
+                Falling back to state sync.",
+                num_needed_payloads
+            ))
+        );
+        self.reset_execution_pipeline();
+    }
+
+    /// Returns true iff the given block is too many rounds ahead of the root to
+    /// be buffered (as specified by the config). If so, the drop is recorded.
+    fn is_beyond_rounds_ahead_horizon(&self, block_info: &BlockInfo, message_type: &str) -> bool {
+        let max_rounds_ahead_of_root = self.consensus_observer_config.max_rounds_ahead_of_root;
+        if !self
+            .observer_state_tracker
+            .is_beyond_rounds_ahead_horizon(block_info, max_rounds_ahead_of_root)
+        {
+            return false;
+        }
+
+        // Log the drop and update the metrics
+        warn!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Dropping {} beyond the buffering horizon ({} rounds ahead of the root): {}",
+                message_type, max_rounds_ahead_of_root, block_info
+            ))
+        );
+        metrics::increment_buffering_horizon_drops(message_type);
+
+        true
+    }
+
+    /// Notifies the verified commit subscribers (e.g., state sync) of the
+    /// given commit decision. This allows other subsystems to learn about
+    /// the latest commits before the observer has executed and applied them.
+    /// Note: this function assumes the commit decision has been verified.
+    fn notify_verified_commit(&self, commit_decision: &CommitDecision) {
+        if let Some(observed_commit_notifier) = &self.observed_commit_notifier {
+            if let Err(error) = observed_commit_notifier
+                .notify_verified_commit(commit_decision.shared_commit_proof())
+            {
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to notify subscribers of the verified commit! Error: {:?}",
+                        error
+                    ))
+                );
+            }
         }
     }
 
     /// Processes the block payload
-    fn process_block_payload(&mut self, block_payload: BlockPayload) {
+    async fn process_block_payload(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        block_payload: BlockPayload,
+    ) {
+        // Note: the payload is verified against the ordered block (i.e., the block
+        // info, quorum store proofs and batch digests) before it is stored. If the
+        // ordered block hasn't been received yet, the stored payload is verified
+        // once the ordered block arrives (see `verify_stored_block_payloads()`).
+
+        // Verify the transaction signatures of the payload (if enabled). This
+        // catches invalid transactions (e.g., from a bad publisher) before
+        // they are stored and sent to the execution pipeline.
+        let block_payload = if self
+            .feature_flags
+            .is_enabled(ObserverFeature::PayloadSignatureVerification)
+        {
+            match verify_transaction_signatures(block_payload).await {
+                Ok(block_payload) => block_payload,
+                Err(error) => {
+                    error!(
+                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                            "Failed to verify block payload signatures! Ignoring payload from peer: {:?}, Error: {:?}",
+                            peer_network_id, error
+                        ))
+                    );
+                    if matches!(error, Error::InvalidMessageError(_)) {
+                        self.record_verification_failure(peer_network_id, "block_payload", &error);
+                    }
+                    return;
+                },
+            }
+        } else {
+            block_payload
+        };
+
+        // Cache the payload transactions for delta decoding (if enabled)
+        if let Some(payload_delta_decoder) = &mut self.payload_delta_decoder {
+            payload_delta_decoder.record_payload(&block_payload);
+        }
+
+        // Drop the payload if it is too far ahead of the root (it will be recovered via sync)
+        if self.is_beyond_rounds_ahead_horizon(&block_payload.block, "block_payload") {
+            return;
+        }
+
+        // If the ordered block has already been received, verify the payload against it
+        if let Some(pipelined_block) = self
+            .pending_ordered_blocks
+            .get_verified_pipelined_block(&block_payload.block)
+        {
+            if let Err(error) =
+                self.verify_block_payload(&block_payload, &pipelined_block, "block_payload")
+            {
+                error!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to verify block payload! Ignoring payload from peer: {:?}, Error: {:?}",
+                        peer_network_id, error
+                    ))
+                );
+                if matches!(error, Error::InvalidMessageError(_)) {
+                    self.record_verification_failure(peer_network_id, "block_payload", &error);
+                }
+                return;
+            }
+        }
+
+        // Export the block payload (and notify the listeners)
+        if let Some(data_exporter) = self.get_data_exporter() {
+            data_exporter.export_block_payload(&block_payload);
+        }
+        self.event_listeners.notify_block_payload(&block_payload);
+
         // Unpack the block payload
         let block = block_payload.block;
         let transactions = block_payload.transactions;
         let limit = block_payload.limit;
 
-        // TODO: verify the block payload!
-
         // Update the payload store with the payload
-        self.block_payload_store
-            .insert_block_payload(block, transactions, limit);
+        let evicted_payloads =
+            self.block_payload_store
+                .insert_block_payload(block, transactions, limit);
+        self.handle_evicted_payloads(&evicted_payloads);
+
+        // Stream any blocks that were waiting for the payload into the pipeline
+        if self
+            .incremental_finalization_queue
+            .as_ref()
+            .is_some_and(|queue| !queue.is_empty())
+        {
+            self.finalize_incremental_blocks().await;
+        }
     }
 
     /// Processes the commit decision
-    fn process_commit_decision(&mut self, commit_decision: CommitDecision) {
-        // If the commit decision is for the current epoch, verify it
-        let epoch_state = self.get_epoch_state();
+    fn process_commit_decision(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        commit_decision: CommitDecision,
+    ) {
+        // If the commit decision is for a previous epoch, verify it using the
+        // epoch state of that epoch (cached, or derived from storage). Verified
+        // commit decisions are ignored, as the root has already moved past the
+        // previous epoch. Commit decisions that can't be verified are dropped.
+        let epoch_state = self.observer_state_tracker.epoch_state();
         let commit_decision_epoch = commit_decision.epoch();
+        if commit_decision_epoch < epoch_state.epoch {
+            match self
+                .epoch_state_cache
+                .get_epoch_state(commit_decision_epoch)
+            {
+                Some(previous_epoch_state) => {
+                    match commit_decision.verify_commit_proof(&previous_epoch_state) {
+                        Ok(()) => {
+                            debug!(
+                                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                    "Ignoring verified commit decision for a previous epoch: {}",
+                                    commit_decision.proof_block_info()
+                                ))
+                            );
+                        },
+                        Err(error) => {
+                            error!(
+                                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                    "Failed to verify commit decision for a previous epoch! \
+                                    Ignoring: {:?}, Error: {:?}",
+                                    commit_decision.proof_block_info(),
+                                    error
+                                ))
+                            );
+                            self.record_verification_failure(
+                                peer_network_id,
+                                "commit_decision",
+                                &error,
+                            );
+                        },
+                    }
+                },
+                None => {
+                    warn!(
+                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                            "No epoch state was found to verify the commit decision for a \
+                            previous epoch! Ignoring: {:?}",
+                            commit_decision.proof_block_info()
+                        ))
+                    );
+                },
+            }
+            return;
+        }
+
+        // If the commit decision is for the current epoch, verify it
         if commit_decision_epoch == epoch_state.epoch {
             // Verify the commit decision
-            if let Err(error) = commit_decision.verify_commit_proof(&epoch_state) {
+            if let Err(error) = self.verify_commit_proof(&commit_decision, &epoch_state) {
                 error!(
                     LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
                         "Failed to verify commit decision! Ignoring: {:?}, Error: {:?}",
@@ -465,45 +1356,50 @@ impl ConsensusObserver {
                         error
                     ))
                 );
+                self.record_verification_failure(peer_network_id, "commit_decision", &error);
                 return;
             }
 
+            // Export the verified commit decision (and notify the listeners)
+            if let Some(data_exporter) = self.get_data_exporter() {
+                data_exporter.export_commit_decision(&commit_decision);
+            }
+            self.event_listeners
+                .notify_commit_decision(&commit_decision);
+
+            // If the commit decision ends the epoch, cache the next epoch state
+            // (this allows the buffered future commit decisions to be verified).
+            if commit_decision.commit_proof().ledger_info().ends_epoch() {
+                self.epoch_state_cache
+                    .insert_epoch_ending_ledger_info(commit_decision.commit_proof());
+                self.process_buffered_future_commit_decision();
+            }
+
+            // Hand the verified commit to state sync (as a sync target hint)
+            self.notify_verified_commit(&commit_decision);
+
             // Update the pending blocks with the commit decision
             if self.process_commit_decision_for_pending_block(&commit_decision) {
                 return; // The commit decision was successfully processed
             }
         }
 
-        // TODO: identify the best way to handle an invalid commit decision
-        // for a future epoch. In such cases, we currently rely on state sync.
+        // If the commit decision is for a future epoch, it must be verified
+        // before we state sync (or buffered until it can be verified).
+        if commit_decision_epoch > epoch_state.epoch {
+            self.process_future_commit_decision(peer_network_id, commit_decision);
+            return;
+        }
 
         // Otherwise, we failed to process the commit decision. If the commit
-        // is for a future epoch or round, we need to state sync.
+        // is for a future round, we need to state sync.
         let commit_decision_round = commit_decision.round();
-        let last_block = self.get_last_block();
+        let last_block = self
+            .observer_state_tracker
+            .last_block(&self.pending_ordered_blocks);
         if commit_decision_epoch > last_block.epoch() || commit_decision_round > last_block.round()
         {
-            info!(
-                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                    "Started syncing to {}!",
-                    commit_decision.proof_block_info()
-                ))
-            );
-
-            // Update the root and clear the pending blocks (up to the commit)
-            *self.root.lock() = commit_decision.commit_proof().clone();
-            self.pending_ordered_blocks
-                .remove_blocks_for_commit(commit_decision.commit_proof());
-
-            // Start the state sync process
-            let abort_handle = sync_to_commit_decision(
-                commit_decision,
-                commit_decision_epoch,
-                commit_decision_round,
-                self.execution_client.clone(),
-                self.sync_notification_sender.clone(),
-            );
-            self.sync_handle = Some(DropGuard::new(abort_handle));
+            self.start_state_sync(commit_decision);
         }
     }
 
@@ -540,7 +1436,7 @@ impl ConsensusObserver {
                             commit_decision.proof_block_info()
                         ))
                     );
-                    self.forward_commit_decision(commit_decision.clone());
+                    self.forward_commit_decision(commit_decision);
                 }
 
                 return true; // The commit decision was successfully processed
@@ -550,59 +1446,396 @@ impl ConsensusObserver {
         false // The commit decision was not processed
     }
 
-    /// Processes a direct send message
-    async fn process_direct_send_message(
+    /// Processes a commit decision for a future epoch. If the epoch state of the
+    /// commit epoch is known (e.g., the epoch-ending commit of the current epoch
+    /// was verified), the decision is verified and we state sync to it. Otherwise,
+    /// the decision is buffered until it can be verified (or the buffer expires).
+    /// This prevents unverifiable decisions from triggering spurious state syncs.
+    fn process_future_commit_decision(
         &mut self,
         peer_network_id: PeerNetworkId,
-        message: ConsensusObserverDirectSend,
+        commit_decision: CommitDecision,
     ) {
-        // Verify the message is from the peer we've subscribed to
-        if let Some(active_subscription) = &mut self.active_observer_subscription {
-            if let Err(error) = active_subscription.verify_message_sender(&peer_network_id) {
-                warn!(
-                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                        "Message failed subscription sender verification! Error: {:?}",
-                        error,
-                    ))
-                );
+        // Get the epoch state of the commit epoch (if it is known)
+        let epoch_state = match self
+            .epoch_state_cache
+            .get_epoch_state(commit_decision.epoch())
+        {
+            Some(epoch_state) => epoch_state,
+            None => {
+                // Drop the decision if it is too far ahead of the current epoch
+                let current_epoch = self.observer_state_tracker.epoch_state().epoch;
+                if self
+                    .future_commit_decisions
+                    .is_too_far_ahead(current_epoch, commit_decision.epoch())
+                {
+                    warn!(
+                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                            "Dropping commit decision too far ahead of the current epoch ({}): {}",
+                            current_epoch,
+                            commit_decision.proof_block_info()
+                        ))
+                    );
+                    metrics::increment_future_commit_decisions(
+                        metrics::FUTURE_COMMIT_REJECTED_LABEL,
+                    );
+                    return;
+                }
 
-                // Send another unsubscription request to the peer
-                self.unsubscribe_from_peer(peer_network_id);
+                // Otherwise, the decision can't be verified yet, so buffer it
+                if self.future_commit_decisions.buffer_commit_decision(
+                    current_epoch,
+                    peer_network_id,
+                    commit_decision.clone(),
+                ) {
+                    debug!(
+                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                            "Buffering commit decision for a future epoch (until it can be \
+                            verified): {}",
+                            commit_decision.proof_block_info()
+                        ))
+                    );
+                    metrics::increment_future_commit_decisions(
+                        metrics::FUTURE_COMMIT_BUFFERED_LABEL,
+                    );
+                }
                 return;
-            }
-        } else {
-            warn!(
+            },
+        };
+
+        // Verify the commit decision
+        if let Err(error) = commit_decision.verify_commit_proof(&epoch_state) {
+            error!(
                 LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                    "Received message from unexpected peer: {}! No active subscription found!",
-                    peer_network_id
+                    "Failed to verify commit decision for a future epoch! Ignoring: {:?}, Error: {:?}",
+                    commit_decision.proof_block_info(),
+                    error
                 ))
             );
-
-            // Send an unsubscription request to the peer
-            self.unsubscribe_from_peer(peer_network_id);
+            metrics::increment_future_commit_decisions(metrics::FUTURE_COMMIT_REJECTED_LABEL);
+            self.record_verification_failure(peer_network_id, "commit_decision", &error);
             return;
-        };
+        }
 
-        // Increment the received message counter
-        metrics::increment_request_counter(
-            &metrics::OBSERVER_RECEIVED_MESSAGES,
-            message.get_label(),
-            &peer_network_id,
+        // The commit decision is verified, so state sync to it
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Verified commit decision for a future epoch! Starting state sync to: {}",
+                commit_decision.proof_block_info()
+            ))
         );
+        metrics::increment_future_commit_decisions(metrics::FUTURE_COMMIT_VERIFIED_LABEL);
+        self.notify_verified_commit(&commit_decision);
+        self.start_state_sync(commit_decision);
+    }
 
-        // Process the message based on the type
-        match message {
-            ConsensusObserverDirectSend::OrderedBlock(ordered_block) => {
-                debug!(
-                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                        "Received ordered block: {}, from peer: {}!",
-                        ordered_block.proof_block_info(),
-                        peer_network_id
-                    ))
-                );
-                self.process_ordered_block(ordered_block).await;
-            },
-            ConsensusObserverDirectSend::CommitDecision(commit_decision) => {
+    /// Processes the buffered future commit decisions of every epoch that can
+    /// now be processed, i.e., the epoch state of the commit epoch has become
+    /// known, or the observer has reached the commit epoch. Each decision is
+    /// verified before it is acted upon (highest round first).
+    fn process_buffered_future_commit_decision(&mut self) {
+        for buffered_epoch in self.future_commit_decisions.get_buffered_epochs() {
+            // Check if the buffered commit decisions can now be processed
+            let current_epoch = self.observer_state_tracker.epoch_state().epoch;
+            if buffered_epoch > current_epoch
+                && self
+                    .epoch_state_cache
+                    .get_epoch_state(buffered_epoch)
+                    .is_none()
+            {
+                continue; // The buffered commit decisions still can't be verified
+            }
+
+            // Process the buffered commit decisions
+            for (peer_network_id, commit_decision) in self
+                .future_commit_decisions
+                .take_commit_decisions(buffered_epoch)
+            {
+                self.process_commit_decision(peer_network_id, commit_decision);
+            }
+        }
+    }
+
+    /// Drops the buffered future commit decisions that could not be verified
+    /// within the maximum buffer duration. Unverified decisions never trigger
+    /// state sync (otherwise, a peer could forge a decision to force a sync).
+    /// Instead, the subscriptions to the peers that sent the decisions are
+    /// terminated, so that the observer resubscribes (and receives the epoch
+    /// proofs from another peer, or falls back to the progress checks).
+    fn process_expired_future_commit_decisions(&mut self) {
+        let current_epoch = self.observer_state_tracker.epoch_state().epoch;
+        for (peer_network_id, commit_decision) in
+            self.future_commit_decisions.take_expired_commit_decisions()
+        {
+            // If the observer has since reached the commit epoch, there's nothing to do
+            if commit_decision.epoch() <= current_epoch {
+                continue;
+            }
+
+            // Otherwise, drop the decision and terminate the subscription to the peer
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to verify the buffered commit decision for a future epoch in time! \
+                    Dropping the decision: {} and terminating the subscription to: {}",
+                    commit_decision.proof_block_info(),
+                    peer_network_id
+                ))
+            );
+            metrics::increment_future_commit_decisions(metrics::FUTURE_COMMIT_EXPIRED_LABEL);
+            self.subscription_manager.terminate_subscription_to_peer(
+                peer_network_id,
+                Error::SubscriptionVerificationFailed(format!(
+                    "The commit decision for epoch: {}, round: {} could not be verified in time",
+                    commit_decision.epoch(),
+                    commit_decision.round()
+                )),
+            );
+        }
+    }
+
+    /// Forces a resubscription to the given peer (or reruns the peer selection,
+    /// if no peer is given), e.g., when an operator knows the upstream is bad
+    /// before the health checks do. Returns the peer of the new subscription.
+    pub(crate) async fn force_resubscription(
+        &mut self,
+        peer_network_id: Option<PeerNetworkId>,
+    ) -> Result<PeerNetworkId, Error> {
+        // Subscriptions are not managed while observation is paused for a sync
+        if self.observation_paused_for_sync {
+            return Err(Error::SubscriptionOverridden(
+                "Observation is paused until the forced state sync completes!".into(),
+            ));
+        }
+
+        // Force the resubscription (and reset the pipeline, if required)
+        let result = self
+            .subscription_manager
+            .force_resubscription(peer_network_id)
+            .await;
+        self.reset_pipeline_if_subscription_replaced().await;
+        self.update_observer_state();
+
+        result
+    }
+
+    /// Pauses the finalization of ordered blocks and commit decisions (e.g., to
+    /// hold execution during a maintenance window). Messages continue to be
+    /// verified and buffered, and the subscription is maintained (i.e., the
+    /// syncing progress checks are paused). Note: if the pending block limit
+    /// is reached while paused, the observer falls back to state sync.
+    pub(crate) fn pause_finalization(&mut self) {
+        // Check if finalization is already paused
+        if self.finalization_paused_after.is_some() {
+            return;
+        }
+
+        // Identify the last block sent to the pipeline (i.e., the last verified
+        // pending block, or the root if there are no verified pending blocks).
+        let root_block = self.observer_state_tracker.root_block();
+        let last_finalized_block = self
+            .pending_ordered_blocks
+            .get_all_verified_pending_blocks()
+            .into_keys()
+            .last()
+            .unwrap_or((root_block.epoch(), root_block.round()));
+
+        // Pause finalization (and the syncing progress checks)
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Pausing finalization after block (epoch, round): {:?}",
+                last_finalized_block
+            ))
+        );
+        self.finalization_paused_after = Some(last_finalized_block);
+        self.subscription_manager
+            .set_syncing_progress_checks_paused(true);
+        self.observer_status_handle.update_finalization_paused(true);
+    }
+
+    /// Resumes the finalization of ordered blocks and commit decisions. All
+    /// blocks buffered while finalization was paused (and the commits of the
+    /// pending blocks) are sent to the execution pipeline (in order).
+    pub(crate) async fn resume_finalization(&mut self) {
+        // Check if finalization is paused
+        let last_finalized_block = match self.finalization_paused_after.take() {
+            Some(last_finalized_block) => last_finalized_block,
+            None => return, // Finalization is not paused
+        };
+
+        // Resume the syncing progress checks
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Resuming finalization after block (epoch, round): {:?}",
+                last_finalized_block
+            ))
+        );
+        self.subscription_manager
+            .set_syncing_progress_checks_paused(false);
+        self.observer_status_handle
+            .update_finalization_paused(false);
+
+        // Stream any blocks that were held back while paused into the pipeline
+        self.finalize_incremental_blocks().await;
+
+        // Send the buffered blocks (and the pending commits) to the execution pipeline
+        for (block_epoch_and_round, (ordered_block, commit_decision)) in self
+            .pending_ordered_blocks
+            .get_all_verified_pending_blocks()
+        {
+            // Finalize the ordered block (if it wasn't already sent to the pipeline)
+            if block_epoch_and_round > last_finalized_block {
+                self.finalize_ordered_block(ordered_block).await;
+            }
+
+            // If a commit decision is available, forward it to the execution pipeline
+            if let Some(commit_decision) = commit_decision {
+                self.forward_commit_decision(&commit_decision);
+            }
+        }
+    }
+
+    /// Processes a control command sent by an operator (e.g., via the admin service)
+    async fn process_control_command(&mut self, control_command: ObserverControlCommand) {
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Processing observer control command: {}",
+                control_command.get_label()
+            ))
+        );
+
+        match control_command {
+            ObserverControlCommand::ForceSync(ledger_info, response_sender) => {
+                let result = self.force_state_sync(ledger_info);
+                if let Err(error) = &result {
+                    warn!(LogSchema::new(LogEntry::ConsensusObserver)
+                        .message(&format!("Failed to force a state sync! Error: {:?}", error)));
+                }
+                let _ = response_sender.send(result); // The operator may have hung up
+            },
+            ObserverControlCommand::ForceResubscribe(peer_network_id, response_sender) => {
+                let result = self.force_resubscription(peer_network_id).await;
+                if let Err(error) = &result {
+                    warn!(
+                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                            "Failed to force a resubscription! Error: {:?}",
+                            error
+                        ))
+                    );
+                }
+                let _ = response_sender.send(result); // The operator may have hung up
+            },
+            ObserverControlCommand::PauseFinalization(response_sender) => {
+                self.pause_finalization();
+                let _ = response_sender.send(()); // The operator may have hung up
+            },
+            ObserverControlCommand::ResumeFinalization(response_sender) => {
+                self.resume_finalization().await;
+                let _ = response_sender.send(()); // The operator may have hung up
+            },
+        }
+    }
+
+    /// Processes a direct send message
+    async fn process_direct_send_message(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        message: ConsensusObserverDirectSend,
+    ) {
+        // Verify the message is from the peer we've subscribed to
+        if let Err(error) = self
+            .subscription_manager
+            .verify_message_sender(&peer_network_id)
+        {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Message failed subscription sender verification! Error: {:?}",
+                    error,
+                ))
+            );
+            self.peer_misbehavior_reporter.report_misbehavior(
+                &peer_network_id,
+                PeerMisbehavior::UnsolicitedMessage,
+                &error.to_string(),
+            );
+
+            // Send an unsubscription request to the peer
+            self.subscription_manager
+                .unsubscribe_from_peer(peer_network_id);
+            return;
+        }
+
+        // Drop the message if the peer has exceeded its rate limit
+        if self.is_rate_limited(peer_network_id, &message) {
+            return;
+        }
+
+        // Increment the received message counter
+        metrics::increment_request_counter(
+            &metrics::OBSERVER_RECEIVED_MESSAGES,
+            message.get_label(),
+            &peer_network_id,
+        );
+
+        // Drop the message if it was already received via another subscription
+        if let Some(message_deduplicator) = &mut self.message_deduplicator {
+            if message_deduplicator.is_duplicate(&message) {
+                metrics::increment_duplicate_messages(message.get_label());
+                return;
+            }
+        }
+
+        // Process the consensus data in the message
+        self.process_consensus_data_message(peer_network_id, message)
+            .await;
+    }
+
+    /// Processes a message received from a (non-network) block source.
+    /// Note: the subscription checks are skipped for these messages.
+    async fn process_block_source_message(
+        &mut self,
+        source_label: &'static str,
+        block_source_message: BlockSourceMessage,
+    ) {
+        // Unpack the block source message
+        let BlockSourceMessage {
+            source_peer,
+            message,
+        } = block_source_message;
+
+        // Update the block source metrics
+        metrics::increment_block_source_messages(source_label, message.get_label());
+
+        // Process the consensus data in the message
+        self.process_consensus_data_message(source_peer, message)
+            .await;
+    }
+
+    /// Processes the consensus data (i.e., ordered blocks, commit
+    /// decisions and block payloads) in the given message.
+    async fn process_consensus_data_message(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        message: ConsensusObserverDirectSend,
+    ) {
+        // Process the message based on the type
+        match message {
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block) => {
+                debug!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Received ordered block: {}, from peer: {}!",
+                        ordered_block.proof_block_info(),
+                        peer_network_id
+                    ))
+                );
+                if let Some(ordered_block) = self
+                    .process_ordered_block(peer_network_id, ordered_block)
+                    .await
+                {
+                    self.backfill_missing_ordered_blocks(peer_network_id, ordered_block)
+                        .await;
+                }
+            },
+            ConsensusObserverDirectSend::CommitDecision(commit_decision) => {
                 debug!(
                     LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
                         "Received commit decision: {}, from peer: {}!",
@@ -610,7 +1843,7 @@ impl ConsensusObserver {
                         peer_network_id
                     ))
                 );
-                self.process_commit_decision(commit_decision);
+                self.process_commit_decision(peer_network_id, commit_decision);
             },
             ConsensusObserverDirectSend::BlockPayload(block_payload) => {
                 debug!(
@@ -619,13 +1852,176 @@ impl ConsensusObserver {
                         block_payload.block, peer_network_id
                     ))
                 );
-                self.process_block_payload(block_payload);
+                self.process_block_payload(peer_network_id, block_payload)
+                    .await;
+            },
+            ConsensusObserverDirectSend::CompressionDictionary(compression_dictionary) => {
+                debug!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Received compression dictionary: {}, from peer: {}!",
+                        compression_dictionary.dictionary_id, peer_network_id
+                    ))
+                );
+                self.payload_decompressor
+                    .insert_dictionary(compression_dictionary);
+            },
+            ConsensusObserverDirectSend::CompressedBlockPayload(compressed_block_payload) => {
+                debug!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Received compressed block payload: {}, from peer: {}!",
+                        compressed_block_payload.block, peer_network_id
+                    ))
+                );
+                self.process_compressed_block_payload(peer_network_id, compressed_block_payload)
+                    .await;
+            },
+            ConsensusObserverDirectSend::DeltaBlockPayload(delta_block_payload) => {
+                debug!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Received delta block payload: {}, from peer: {}!",
+                        delta_block_payload.block, peer_network_id
+                    ))
+                );
+                self.process_delta_block_payload(peer_network_id, delta_block_payload)
+                    .await;
+            },
+        }
+    }
+
+    /// Reconstructs and processes a delta encoded block payload
+    async fn process_delta_block_payload(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        delta_block_payload: DeltaBlockPayload,
+    ) {
+        // Reconstruct the full block payload from the cached transactions
+        let decoded_block_payload = match &self.payload_delta_decoder {
+            Some(payload_delta_decoder) => {
+                payload_delta_decoder.decode_payload(delta_block_payload)
+            },
+            None => Err(Error::PayloadDeltaError(format!(
+                "Delta encoding is disabled! Ignoring delta block payload: {}",
+                delta_block_payload.block
+            ))),
+        };
+
+        // Process the block payload
+        match decoded_block_payload {
+            Ok(block_payload) => {
+                self.process_block_payload(peer_network_id, block_payload)
+                    .await;
+            },
+            Err(error) => {
+                // The referenced transactions are missing (e.g., they were evicted from the cache)
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to decode the delta block payload from peer: {}! Error: {:?}",
+                        peer_network_id, error
+                    ))
+                );
             },
         }
     }
 
-    /// Processes the ordered block
-    async fn process_ordered_block(&mut self, ordered_block: OrderedBlock) {
+    /// Decompresses and processes a dictionary compressed block payload
+    async fn process_compressed_block_payload(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        compressed_block_payload: CompressedBlockPayload,
+    ) {
+        match self
+            .payload_decompressor
+            .decompress_payload(compressed_block_payload)
+        {
+            Ok(block_payload) => {
+                self.process_block_payload(peer_network_id, block_payload)
+                    .await;
+            },
+            Err(error @ Error::InvalidMessageError(_)) => {
+                // The payload is corrupt (or was compressed using a different dictionary)
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to decompress the block payload from peer: {}! Error: {:?}",
+                        peer_network_id, error
+                    ))
+                );
+                self.record_verification_failure(
+                    peer_network_id,
+                    "compressed_block_payload",
+                    &error,
+                );
+            },
+            Err(error) => {
+                // The dictionary is missing (e.g., the payload was sent before a resubscription)
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Unable to decompress the block payload from peer: {}! Error: {:?}",
+                        peer_network_id, error
+                    ))
+                );
+            },
+        }
+    }
+
+    /// Processes a network message received from the network events stream
+    pub(crate) async fn process_network_message(&mut self, network_message: NetworkMessage) {
+        // Unpack the network message
+        let NetworkMessage {
+            peer_network_id,
+            protocol_id: _,
+            consensus_observer_message,
+            response_sender,
+            num_message_bytes,
+        } = network_message;
+
+        // Update the inbound message metrics for the peer
+        update_inbound_message_metrics(
+            &peer_network_id,
+            &consensus_observer_message,
+            num_message_bytes,
+        );
+
+        // Run the message through the interceptors (the message may be dropped)
+        let consensus_observer_message = match self
+            .message_interceptors
+            .intercept_inbound_message(&peer_network_id, consensus_observer_message)
+        {
+            Some(consensus_observer_message) => consensus_observer_message,
+            None => return, // The message was dropped by an interceptor
+        };
+
+        // Process the consensus observer message
+        match consensus_observer_message {
+            ConsensusObserverMessage::DirectSend(message) => {
+                self.process_direct_send_message(peer_network_id, message)
+                    .await;
+            },
+            ConsensusObserverMessage::Request(request) => {
+                self.process_request_message(peer_network_id, request, response_sender);
+            },
+            _ => {
+                error!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Received unexpected message from peer: {}",
+                        peer_network_id
+                    ))
+                );
+                self.peer_misbehavior_reporter.report_misbehavior(
+                    &peer_network_id,
+                    PeerMisbehavior::UnexpectedMessage,
+                    "The observer doesn't expect response messages!",
+                );
+            },
+        }
+    }
+
+    /// Processes the ordered block. Returns the ordered block iff its parent
+    /// is missing and the missing blocks should be backfilled from the peer.
+    async fn process_ordered_block(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        ordered_block: OrderedBlock,
+    ) -> Option<Arc<OrderedBlock>> {
         // Verify the ordered blocks before processing
         if let Err(error) = ordered_block.verify_ordered_blocks() {
             error!(
@@ -635,38 +2031,154 @@ impl ConsensusObserver {
                     error
                 ))
             );
-            return;
+            self.record_verification_failure(peer_network_id, "ordered_block", &error);
+            return None;
         };
 
+        // If the ordered block is for a previous epoch, verify the proof using the
+        // cached epoch state (if any). Verified ordered blocks are ignored, as
+        // the root has already moved past the previous epoch.
+        let epoch_state = self.observer_state_tracker.epoch_state();
+        let ordered_block_epoch = ordered_block.proof_block_info().epoch();
+        if ordered_block_epoch < epoch_state.epoch {
+            if let Some(previous_epoch_state) =
+                self.epoch_state_cache.get_epoch_state(ordered_block_epoch)
+            {
+                match ordered_block.verify_ordered_proof(&previous_epoch_state) {
+                    Ok(()) => {
+                        debug!(
+                            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                "Ignoring verified ordered block for a previous epoch: {}",
+                                ordered_block.proof_block_info()
+                            ))
+                        );
+                    },
+                    Err(error) => {
+                        warn!(
+                            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                "Failed to verify ordered proof for a previous epoch! \
+                                Ignoring: {:?}, Error: {:?}",
+                                ordered_block.proof_block_info(),
+                                error
+                            ))
+                        );
+                        self.record_verification_failure(peer_network_id, "ordered_proof", &error);
+                    },
+                }
+                return None;
+            }
+        }
+
+        // If the ordered block is for the current epoch, speculatively forward
+        // it to the execution pipeline before verifying the proof (if enabled).
+        let ordered_block = Arc::new(ordered_block);
+        let speculatively_forwarded = ordered_block_epoch == epoch_state.epoch
+            && self
+                .speculatively_forward_ordered_block(&ordered_block)
+                .await;
+
         // If the ordered block is for the current epoch, verify the proof
-        let epoch_state = self.get_epoch_state();
-        let verified_ordered_proof =
-            if ordered_block.proof_block_info().epoch() == epoch_state.epoch {
-                // Verify the ordered proof
-                if let Err(error) = ordered_block.verify_ordered_proof(&epoch_state) {
-                    warn!(
-                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                            "Failed to verify ordered proof! Ignoring: {:?}, Error: {:?}",
-                            ordered_block.proof_block_info(),
-                            error
-                        ))
-                    );
-                    return;
+        let verified_ordered_proof = if ordered_block_epoch == epoch_state.epoch {
+            // Verify the ordered proof
+            if let Err(error) = self.verify_ordered_proof(&ordered_block, &epoch_state) {
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to verify ordered proof! Ignoring: {:?}, Error: {:?}",
+                        ordered_block.proof_block_info(),
+                        error
+                    ))
+                );
+                self.record_verification_failure(peer_network_id, "ordered_proof", &error);
+
+                // Reset the execution pipeline (if the block was forwarded speculatively)
+                if speculatively_forwarded {
+                    metrics::increment_speculative_forwards(metrics::SPECULATIVE_REJECTED_LABEL);
+                    self.reset_execution_pipeline();
                 }
+                return None;
+            }
 
-                true // We have successfully verified the proof
-            } else {
-                false // We can't verify the proof yet
-            };
+            // Verify the block timestamps (only once the proof is verified, so that
+            // blocks forged by unverified senders are never flagged or rejected).
+            if !self.check_block_timestamps(peer_network_id, &ordered_block) {
+                // Reset the execution pipeline (if the block was forwarded speculatively)
+                if speculatively_forwarded {
+                    metrics::increment_speculative_forwards(metrics::SPECULATIVE_REJECTED_LABEL);
+                    self.reset_execution_pipeline();
+                }
+                return None;
+            }
+
+            true // We have successfully verified the proof
+        } else {
+            false // We can't verify the proof yet
+        };
+
+        // Drop the ordered block if it is too far ahead of the root (it will be recovered via sync)
+        if self.is_beyond_rounds_ahead_horizon(ordered_block.proof_block_info(), "ordered_block") {
+            return None;
+        }
+
+        // If the (verified) ordered block conflicts with a verified pending block (i.e.,
+        // there is a different block for the same epoch and round), the peer is equivocating.
+        // Unverified blocks are never compared, as they may have been forged by anyone.
+        if verified_ordered_proof {
+            if let Some(conflicting_block) = self
+                .pending_ordered_blocks
+                .get_conflicting_block(&ordered_block)
+            {
+                self.handle_equivocation(peer_network_id, &ordered_block, conflicting_block);
+                return None;
+            }
+        }
 
         // If the block is a child of our last block, we can insert it
-        if self.get_last_block().id() == ordered_block.first_block().parent_id() {
-            // Insert the ordered block into the pending blocks
-            self.pending_ordered_blocks
-                .insert_ordered_block(ordered_block.clone(), verified_ordered_proof);
+        if self
+            .observer_state_tracker
+            .last_block(&self.pending_ordered_blocks)
+            .id()
+            == ordered_block.first_block().parent_id()
+        {
+            // If there are too many pending blocks, abandon them and fall back to
+            // state sync (the ordered block will be recovered via the sync).
+            if self.should_sync_on_pending_blocks_overflow() {
+                self.sync_on_pending_blocks_overflow();
+                return None;
+            }
+
+            // Insert the ordered block into the pending blocks (the block
+            // is shared with the pending blocks, to avoid copying it).
+            self.pending_ordered_blocks.insert_ordered_block(
+                peer_network_id,
+                ordered_block.clone(),
+                verified_ordered_proof,
+            );
+            self.epoch_summary_tracker
+                .record_blocks_observed(ordered_block.blocks().len() as u64);
+
+            // Export the ordered block
+            if let Some(data_exporter) = self.get_data_exporter() {
+                data_exporter.export_ordered_block(&ordered_block);
+            }
 
-            // If we verified the proof, and we're not in sync mode, finalize the ordered blocks
-            if verified_ordered_proof && self.sync_handle.is_none() {
+            // Verify any payloads received before the ordered block (and notify the listeners)
+            if verified_ordered_proof {
+                let stored_payloads_valid =
+                    self.verify_stored_block_payloads(peer_network_id, &ordered_block);
+                self.event_listeners.notify_ordered_block(&ordered_block);
+
+                // Reset the execution pipeline if the block was forwarded speculatively
+                // with an invalid payload (the pipeline may have already read the payload).
+                if speculatively_forwarded && !stored_payloads_valid {
+                    metrics::increment_speculative_forwards(metrics::SPECULATIVE_REJECTED_LABEL);
+                    self.reset_execution_pipeline();
+                    return None;
+                }
+            }
+
+            // If we verified the proof, and we're not in sync mode, finalize the ordered
+            // blocks (unless they were already forwarded speculatively).
+            if verified_ordered_proof && self.sync_handle.is_none() && !speculatively_forwarded {
                 debug!(
                     LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
                         "Forwarding blocks to the execution pipeline: {}",
@@ -677,219 +2189,909 @@ impl ConsensusObserver {
                 // Finalize the ordered block
                 self.finalize_ordered_block(ordered_block).await;
             }
+
+            None
+        } else {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Parent block is missing! Ignoring: {:?}",
+                    ordered_block.proof_block_info()
+                ))
+            );
+
+            // Only backfill verified blocks (and only if we're not in sync mode)
+            let backfill_missing_blocks =
+                self.consensus_observer_config.enable_ordered_block_backfill
+                    && verified_ordered_proof
+                    && self.sync_handle.is_none();
+            backfill_missing_blocks.then_some(ordered_block)
+        }
+    }
+
+    /// Processes a request message
+    fn process_request_message(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        request: ConsensusObserverRequest,
+        response_sender: Option<ResponseSender>,
+    ) {
+        // Ensure that the response sender is present
+        let response_sender = match response_sender {
+            Some(response_sender) => response_sender,
+            None => {
+                error!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Missing response sender for RCP request: {:?}",
+                        request
+                    ))
+                );
+                return; // Something has gone wrong!
+            },
+        };
+
+        // Forward the request to the consensus publisher
+        if let Some(consensus_publisher) = &self.consensus_publisher {
+            consensus_publisher.handle_subscription_request(
+                &peer_network_id,
+                request,
+                response_sender,
+            );
+        }
+    }
+
+    /// Processes a synced commit notification (i.e., storage was synced to
+    /// the given ledger info, possibly outside of the observer's own sync
+    /// handle). All pending blocks (and their payloads) that are at or
+    /// below the synced commit are stale, so they are pruned immediately.
+    fn process_synced_commit_notification(
+        &mut self,
+        synced_commit_notification: SyncedCommitNotification,
+    ) {
+        // Cache the next epoch state (if the synced ledger info ends the epoch)
+        let synced_ledger_info = synced_commit_notification.ledger_info;
+        self.epoch_state_cache
+            .insert_epoch_ending_ledger_info(&synced_ledger_info);
+        self.process_buffered_future_commit_decision();
+
+        // Remove the stale pending blocks (and their payloads)
+        let num_pruned_blocks = self.prune_pending_blocks_for_commit(&synced_ledger_info);
+        if num_pruned_blocks == 0 {
+            return; // Nothing was pruned
+        }
+
+        // Log the pruned blocks
+        debug!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Pruned {} stale pending blocks after storage was synced to version: {}, \
+                epoch: {}, round: {}",
+                num_pruned_blocks,
+                synced_commit_notification.version,
+                synced_ledger_info.ledger_info().epoch(),
+                synced_ledger_info.commit_info().round(),
+            ))
+        );
+    }
+
+    /// Processes the sync complete notification for the given epoch and round
+    pub(crate) async fn process_sync_notification(&mut self, epoch: u64, round: Round) {
+        // Log the sync notification
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Received sync complete notification for epoch {}, round: {}",
+                epoch, round
+            ))
+        );
+
+        // Verify that a sync is in progress (otherwise, the notification is
+        // stale, e.g., a duplicate of a notification that was already processed).
+        if self.sync_handle.is_none() {
+            info!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Ignoring stale sync notification for epoch: {}, round: {}! No sync is in progress.",
+                    epoch, round
+                ))
+            );
+            metrics::increment_dropped_sync_notifications(metrics::SYNC_NOTIFICATION_STALE_LABEL);
+            return;
+        }
+
+        // Verify that the sync notification is for the current epoch and round
+        if !self
+            .observer_state_tracker
+            .root_matches_epoch_and_round(epoch, round)
+        {
+            info!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Received invalid sync notification for epoch: {}, round: {}! Current root: {:?}",
+                epoch, round, self.observer_state_tracker.root_block()
+                ))
+            );
+            metrics::increment_dropped_sync_notifications(metrics::SYNC_NOTIFICATION_STALE_LABEL);
+            return;
+        }
+
+        // Verify that storage was synced to the root (otherwise, the pipeline
+        // can't restart from the root, so we retry the sync to the root).
+        if let Err(error) = self.verify_synced_root() {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to verify the synced root! Retrying the sync. Error: {:?}",
+                    error
+                ))
+            );
+            metrics::increment_pipeline_teardown_failure(metrics::TEARDOWN_SYNCED_ROOT_LABEL);
+            let root_commit_decision =
+                CommitDecision::new_with_shared_proof(self.observer_state_tracker.root());
+            self.start_state_sync(root_commit_decision);
+            return;
+        }
+
+        // If the epoch has changed, end the current epoch and start the new one
+        let current_epoch_state = self.observer_state_tracker.epoch_state();
+        if epoch > current_epoch_state.epoch {
+            // Wait for the next epoch to start
+            self.execution_client.end_epoch().await;
+            self.wait_for_epoch_start().await;
+
+            // Verify the pending blocks for the new epoch (the epoch
+            // state must be read again, now that the new epoch has started).
+            // If a block fails verification, the peer that sent it is blamed.
+            let new_epoch_state = self.observer_state_tracker.epoch_state();
+            if let Some((peer_network_id, error)) = self
+                .pending_ordered_blocks
+                .verify_pending_blocks(&new_epoch_state)
+            {
+                self.record_verification_failure(peer_network_id, "ordered_proof", &error);
+            }
+        }
+
+        // Reset and drop the sync handle (and resume observation, if it was paused)
+        self.sync_handle = None;
+        self.observation_paused_for_sync = false;
+        self.observer_status_handle
+            .update_sync_status(ObserverSyncStatus::default());
+        self.update_observer_state();
+
+        // Record the sync completion in the event journal
+        self.event_journal
+            .record_event(ObserverEvent::SyncCompleted { epoch, round });
+        self.event_listeners
+            .notify_state_sync_completed(epoch, round);
+
+        // If finalization is paused, the pipeline restarts from the synced root
+        // (once finalization resumes), as the sync tears down the pipeline.
+        if self.finalization_paused_after.is_some() {
+            self.finalization_paused_after = Some((epoch, round));
+        }
+
+        // Process all the pending blocks. These were all buffered during the state sync process.
+        for (_, (ordered_block, commit_decision)) in self
+            .pending_ordered_blocks
+            .get_all_verified_pending_blocks()
+        {
+            // Finalize the ordered block
+            self.finalize_ordered_block(ordered_block).await;
+
+            // If a commit decision is available, forward it to the execution pipeline
+            if let Some(commit_decision) = commit_decision {
+                self.forward_commit_decision(&commit_decision);
+            }
+        }
+    }
+
+    /// Handles an equivocation by the given peer (i.e., the verified ordered block
+    /// conflicts with the given verified pending block). The equivocation is recorded,
+    /// the subscription to the peer is terminated, and the peer is blocklisted for the epoch.
+    fn handle_equivocation(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        ordered_block: &OrderedBlock,
+        conflicting_block: BlockInfo,
+    ) {
+        // Log the equivocation and update the metrics
+        error!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Detected equivocation by peer: {}! Ordered block: {:?} conflicts with pending block: {:?}",
+                peer_network_id,
+                ordered_block.proof_block_info(),
+                conflicting_block
+            ))
+        );
+        metrics::increment_equivocations(&peer_network_id.network_id());
+
+        // Record the equivocation in the event journal
+        let epoch = conflicting_block.epoch();
+        self.event_journal
+            .record_event(ObserverEvent::EquivocationDetected {
+                peer_network_id,
+                epoch,
+                round: conflicting_block.round(),
+            });
+
+        // Terminate the subscription and blocklist the peer for the epoch
+        self.subscription_manager.terminate_subscription_to_peer(
+            peer_network_id,
+            Error::SubscriptionEquivocation(format!(
+                "Conflicting ordered block for epoch: {}, round: {}",
+                epoch,
+                conflicting_block.round()
+            )),
+        );
+        self.subscription_manager
+            .blocklist_peer_for_epoch(peer_network_id, epoch);
+    }
+
+    /// Returns true iff the given message exceeds the rate limit of the sending
+    /// peer (and should be dropped). If the peer persistently exceeds its rate
+    /// limit, the subscription to the peer is also terminated.
+    fn is_rate_limited(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        message: &ConsensusObserverDirectSend,
+    ) -> bool {
+        // Check the message against the peer's rate limit (if enabled)
+        let rate_limit_decision = match &self.inbound_rate_limiter {
+            Some(inbound_rate_limiter) => {
+                inbound_rate_limiter.lock().check_message(&peer_network_id)
+            },
+            None => return false,
+        };
+        if rate_limit_decision == RateLimitDecision::Allowed {
+            return false;
+        }
+
+        // Update the rate limited message metrics
+        metrics::increment_request_counter(
+            &metrics::OBSERVER_RATE_LIMITED_MESSAGES,
+            message.get_label(),
+            &peer_network_id,
+        );
+
+        // Terminate the subscription if the peer persistently exceeds its rate limit
+        if rate_limit_decision == RateLimitDecision::BudgetExhausted {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Peer {} persistently exceeded its rate limit! Terminating the subscription!",
+                    peer_network_id
+                ))
+            );
+            self.subscription_manager.terminate_subscription_to_peer(
+                peer_network_id,
+                Error::SubscriptionRateLimited(format!(
+                    "Too many messages dropped for peer: {}",
+                    peer_network_id
+                )),
+            );
+        }
+
+        true
+    }
+
+    /// Records the verification failure for the given message type in the event
+    /// journal, and enforces the verification failure policy for the sending peer.
+    fn record_verification_failure(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        message_type: &str,
+        error: &Error,
+    ) {
+        // Record the verification failure in the event journal
+        self.event_journal
+            .record_event(ObserverEvent::VerificationFailed {
+                message_type: message_type.into(),
+                error: error.to_string(),
+            });
+        self.observer_status_handle
+            .record_error(format!("Invalid {} message: {}", message_type, error));
+
+        // Report the misbehavior, update the reputation and enforce the
+        // verification failure policy for the peer.
+        self.peer_misbehavior_reporter.report_misbehavior(
+            &peer_network_id,
+            PeerMisbehavior::InvalidProof,
+            &format!("Invalid {} message: {}", message_type, error),
+        );
+        let reputation_event = if message_type == "block_payload" {
+            PeerReputationEvent::InvalidPayload
         } else {
+            PeerReputationEvent::FailedProof
+        };
+        self.subscription_manager
+            .record_peer_reputation_event(&peer_network_id, reputation_event);
+        self.subscription_manager
+            .handle_verification_failure(peer_network_id, error);
+        self.update_observer_state();
+    }
+
+    /// Requests the missing payloads of the verified pending blocks from the
+    /// active subscription peer (instead of waiting passively for the payloads
+    /// to arrive). The received payloads are processed like any other payload
+    /// (i.e., they are verified against the ordered blocks before being stored).
+    /// Note: the number of requests is bounded, as the requests are awaited.
+    async fn request_missing_block_payloads(&mut self) {
+        // Get the active subscription peer (payloads are only requested from it)
+        let peer_network_id = match self.subscription_manager.get_active_subscription_peer() {
+            Some(peer_network_id) => peer_network_id,
+            None => return, // There is no active subscription
+        };
+
+        // Identify the pending blocks with missing payloads (up to the maximum)
+        let max_block_payload_requests =
+            self.consensus_observer_config.max_block_payload_requests as usize;
+        let missing_payload_blocks: Vec<_> = self
+            .pending_ordered_blocks
+            .get_all_verified_pending_blocks()
+            .into_values()
+            .flat_map(|(ordered_block, _)| ordered_block.blocks().clone())
+            .filter(|block| {
+                !self
+                    .block_payload_store
+                    .all_payloads_exist(&[block.clone()])
+            })
+            .take(max_block_payload_requests)
+            .collect();
+
+        // Request and process each missing payload
+        for block in missing_payload_blocks {
+            let block_info = block.block_info();
+            let block_payload = match self
+                .subscription_manager
+                .request_block_payload(&peer_network_id, block.id())
+                .await
+            {
+                Ok(Some(block_payload)) => block_payload,
+                Ok(None) => {
+                    debug!(
+                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                            "The peer: {} doesn't hold the missing payload for block: {}",
+                            peer_network_id, block_info
+                        ))
+                    );
+                    metrics::increment_block_payload_requests(
+                        metrics::PAYLOAD_REQUEST_NOT_FOUND_LABEL,
+                    );
+                    continue;
+                },
+                Err(error) => {
+                    warn!(
+                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                            "Failed to request the missing payload for block: {} from peer: {}! \
+                            Error: {:?}",
+                            block_info, peer_network_id, error
+                        ))
+                    );
+                    metrics::increment_block_payload_requests(
+                        metrics::PAYLOAD_REQUEST_FAILED_LABEL,
+                    );
+                    return; // Avoid waiting on an unresponsive peer for every block
+                },
+            };
+
+            // Verify the payload is for the requested block (the payload
+            // is verified against the ordered block when it is processed).
+            if block_payload.block.id() != block.id() {
+                let error = Error::InvalidMessageError(format!(
+                    "Received a payload for an unrequested block! Requested: {}, received: {}",
+                    block_info, block_payload.block
+                ));
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to verify the requested block payload! Error: {:?}",
+                        error
+                    ))
+                );
+                metrics::increment_block_payload_requests(metrics::PAYLOAD_REQUEST_FAILED_LABEL);
+                self.record_verification_failure(peer_network_id, "block_payload", &error);
+                continue;
+            }
+
+            // Process the block payload
+            metrics::increment_block_payload_requests(metrics::PAYLOAD_REQUEST_RECEIVED_LABEL);
+            self.process_block_payload(peer_network_id, block_payload)
+                .await;
+        }
+    }
+
+    /// Resets the execution pipeline by syncing to the latest known commit
+    /// (unless we're already syncing, which will reset the pipeline anyway).
+    fn reset_execution_pipeline(&mut self) {
+        if self.sync_handle.is_none() {
+            let latest_commit_decision = self.get_latest_commit_decision();
+            self.start_state_sync(latest_commit_decision);
+        }
+    }
+
+    /// Resets the execution pipeline if the subscription was replaced (e.g.,
+    /// after a failover). The blocks of the old subscription are dropped (from
+    /// the pending blocks and the pipeline), so that the new subscription starts
+    /// from a consistent buffer state (i.e., the root). If the reset fails, we
+    /// fall back to resetting the pipeline via state sync.
+    async fn reset_pipeline_if_subscription_replaced(&mut self) {
+        // Check if the subscription was replaced
+        if !self.subscription_manager.take_subscription_replaced() {
+            return;
+        }
+
+        // If we're syncing, or there are no pending blocks, there's nothing to reset
+        if self.sync_handle.is_some()
+            || self
+                .pending_ordered_blocks
+                .get_last_pending_block()
+                .is_none()
+        {
+            return;
+        }
+
+        // Drop the pending blocks (and the streamed blocks) and clear the pipeline deadlines
+        let num_dropped_blocks = self.pending_ordered_blocks.clear_all_pending_blocks();
+        self.clear_incremental_finalization_queue();
+        self.pipeline_deadline_tracker.clear();
+
+        // Reset the execution pipeline to the root
+        let root = self.observer_state_tracker.root();
+        let root_block = root.commit_info().clone();
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "The subscription was replaced! Resetting the execution pipeline to the root: {}. \
+                Num dropped blocks: {}",
+                root_block, num_dropped_blocks
+            ))
+        );
+        if let Err(error) = self.execution_client.reset(&root).await {
             warn!(
                 LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                    "Parent block is missing! Ignoring: {:?}",
-                    ordered_block.proof_block_info()
+                    "Failed to reset the execution pipeline! Falling back to state sync. Error: {:?}",
+                    error
+                ))
+            );
+            self.reset_execution_pipeline();
+            return;
+        }
+
+        // Record the reset in the event journal
+        self.event_journal
+            .record_event(ObserverEvent::PipelineReset {
+                epoch: root_block.epoch(),
+                round: root_block.round(),
+                num_dropped_blocks,
+            });
+        self.update_observer_state();
+    }
+
+    /// Resumes observation using the last persisted state snapshot (if any).
+    /// This allows the observer to immediately resubscribe to its previous
+    /// peer after a restart (instead of starting blind). Stale snapshots
+    /// (e.g., from a previous epoch) are ignored.
+    fn resume_from_state_snapshot(&mut self) {
+        // Load the last state snapshot
+        let state_snapshot = match &self.state_snapshotter {
+            Some(state_snapshotter) => match state_snapshotter.load_snapshot() {
+                Ok(Some(state_snapshot)) => state_snapshot,
+                Ok(None) => return, // No snapshot has been persisted yet
+                Err(error) => {
+                    warn!(
+                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                            "Failed to load the observer state snapshot! Error: {:?}",
+                            error
+                        ))
+                    );
+                    return;
+                },
+            },
+            None => return, // State snapshots are disabled
+        };
+
+        // Verify that the snapshot is not stale (i.e., the snapshot is for the current epoch)
+        let root_block = self.observer_state_tracker.root_block();
+        if state_snapshot.root.epoch() != root_block.epoch() {
+            info!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Ignoring the stale observer state snapshot! Snapshot root: {}, current root: {}",
+                    state_snapshot.root, root_block
                 ))
             );
+            return;
+        }
+
+        // Log the resumed state and prioritize the previous subscription peer (if any)
+        let num_pending_blocks = state_snapshot
+            .pending_block_digests
+            .iter()
+            .filter(|digest| digest.round > root_block.round())
+            .count();
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Resuming from the observer state snapshot! Snapshot root: {}, subscription peer: {:?}, \
+                num uncommitted pending blocks: {}, num payloads: {}",
+                state_snapshot.root,
+                state_snapshot.subscription_peer,
+                num_pending_blocks,
+                state_snapshot.payload_digests.len()
+            ))
+        );
+        if let Some(subscription_peer) = state_snapshot.subscription_peer {
+            self.subscription_manager
+                .set_resume_subscription_peer(subscription_peer);
         }
     }
 
-    /// Processes a request message
-    fn process_request_message(
+    /// Speculatively forwards the given (structurally valid) ordered block to the
+    /// execution pipeline before its ordered proof is verified (if enabled). The
+    /// block is only forwarded if it extends the last pending block, and we're not
+    /// syncing. Speculative blocks are never committed until a verified commit
+    /// decision arrives. Returns true iff the block was forwarded.
+    async fn speculatively_forward_ordered_block(
         &mut self,
-        peer_network_id: PeerNetworkId,
-        request: ConsensusObserverRequest,
-        response_sender: Option<ResponseSender>,
-    ) {
-        // Ensure that the response sender is present
-        let response_sender = match response_sender {
-            Some(response_sender) => response_sender,
-            None => {
-                error!(
-                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                        "Missing response sender for RCP request: {:?}",
-                        request
-                    ))
-                );
-                return; // Something has gone wrong!
-            },
-        };
+        ordered_block: &Arc<OrderedBlock>,
+    ) -> bool {
+        // Ensure that speculative forwarding is enabled, and that we're not syncing
+        if !self.consensus_observer_config.enable_speculative_forwarding
+            || self.sync_handle.is_some()
+        {
+            return false;
+        }
 
-        // Forward the request to the consensus publisher
-        if let Some(consensus_publisher) = &self.consensus_publisher {
-            consensus_publisher.handle_subscription_request(
-                &peer_network_id,
-                request,
-                response_sender,
-            );
+        // Ensure the block is within the buffering horizon and extends the last pending block
+        if self.observer_state_tracker.is_beyond_rounds_ahead_horizon(
+            ordered_block.proof_block_info(),
+            self.consensus_observer_config.max_rounds_ahead_of_root,
+        ) {
+            return false;
         }
+        if self
+            .observer_state_tracker
+            .last_block(&self.pending_ordered_blocks)
+            .id()
+            != ordered_block.first_block().parent_id()
+        {
+            return false;
+        }
+
+        // Forward the block to the execution pipeline
+        debug!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Speculatively forwarding blocks to the execution pipeline: {}",
+                ordered_block.proof_block_info()
+            ))
+        );
+        metrics::increment_speculative_forwards(metrics::SPECULATIVE_FORWARDED_LABEL);
+        self.finalize_ordered_block(ordered_block.clone()).await;
+
+        true
     }
 
-    /// Processes the sync complete notification for the given epoch and round
-    async fn process_sync_notification(&mut self, epoch: u64, round: Round) {
-        // Log the sync notification
+    /// Starts the state sync process to the given commit decision. This updates
+    /// the root, clears the pending blocks (up to the commit) and tears down the
+    /// pipeline: no blocks or commits are accepted until the sync completes, and
+    /// the in-flight blocks are dropped (by resetting the pipeline) before syncing.
+    fn start_state_sync(&mut self, commit_decision: CommitDecision) {
+        let commit_decision_epoch = commit_decision.epoch();
+        let commit_decision_round = commit_decision.round();
+
+        // Log the start of the sync
         info!(
             LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                "Received sync complete notification for epoch {}, round: {}",
-                epoch, round
+                "Started syncing to {}!",
+                commit_decision.proof_block_info()
             ))
         );
 
-        // Verify that the sync notification is for the current epoch and round
-        if !check_root_epoch_and_round(self.root.clone(), epoch, round) {
-            info!(
-                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                "Received invalid sync notification for epoch: {}, round: {}! Current root: {:?}",
-                epoch, round, self.root
-                ))
-            );
+        // Record the start of the sync in the event journal
+        self.event_journal.record_event(ObserverEvent::SyncStarted {
+            epoch: commit_decision_epoch,
+            round: commit_decision_round,
+        });
+        self.epoch_summary_tracker.record_sync_fallback();
+        self.event_listeners
+            .notify_state_sync_started(commit_decision.proof_block_info());
+
+        // Update the root and clear the pending blocks (up to the commit)
+        self.observer_state_tracker
+            .set_root(commit_decision.shared_commit_proof());
+        self.pending_ordered_blocks
+            .remove_blocks_for_commit(commit_decision.commit_proof());
+
+        // Clear the streamed blocks and the pipeline deadlines (the pipeline is torn down by the sync)
+        self.clear_incremental_finalization_queue();
+        self.pipeline_deadline_tracker.clear();
+
+        // Start the state sync process
+        self.observer_status_handle
+            .update_sync_status(ObserverSyncStatus {
+                sync_target: Some(commit_decision.proof_block_info().clone()),
+                observation_paused_for_sync: self.observation_paused_for_sync,
+            });
+        let abort_handle = sync_to_commit_decision(
+            &self.task_registry,
+            commit_decision,
+            commit_decision_epoch,
+            commit_decision_round,
+            self.execution_client.clone(),
+            self.sync_notification_sender.clone(),
+        );
+        self.sync_handle = Some(DropGuard::new(abort_handle));
+        self.update_observer_state();
+    }
+
+    /// Persists a snapshot of the observer state (if the snapshot interval has elapsed)
+    fn take_state_snapshot_if_required(&mut self) {
+        // Check if a snapshot should be taken (and snapshots are enabled)
+        if !self
+            .feature_flags
+            .is_enabled(ObserverFeature::StateSnapshots)
+        {
+            return;
+        }
+        let should_take_snapshot = self
+            .state_snapshotter
+            .as_ref()
+            .map_or(false, |state_snapshotter| {
+                state_snapshotter.should_take_snapshot()
+            });
+        if !should_take_snapshot {
             return;
         }
 
-        // If the epoch has changed, end the current epoch and start the new one
-        let current_epoch_state = self.get_epoch_state();
-        if epoch > current_epoch_state.epoch {
-            // Wait for the next epoch to start
-            self.execution_client.end_epoch().await;
-            self.wait_for_epoch_start().await;
+        // Create the state snapshot
+        let state_snapshot = ObserverStateSnapshot {
+            root: self.observer_state_tracker.root_block(),
+            subscription_peer: self.subscription_manager.get_active_subscription_peer(),
+            pending_block_digests: self.pending_ordered_blocks.get_pending_block_digests(),
+            payload_digests: self
+                .block_payload_store
+                .get_payload_store_backend()
+                .get_available_payload_ids(),
+        };
 
-            // Verify the pending blocks for the new epoch
-            self.pending_ordered_blocks
-                .verify_pending_blocks(&current_epoch_state);
+        // Persist the snapshot
+        if let Some(state_snapshotter) = &mut self.state_snapshotter {
+            state_snapshotter.write_snapshot(&state_snapshot);
         }
+    }
 
-        // Reset and drop the sync handle
-        self.sync_handle = None;
-
-        // Process all the pending blocks. These were all buffered during the state sync process.
-        for (_, (ordered_block, commit_decision)) in self
-            .pending_ordered_blocks
-            .get_all_verified_pending_blocks()
+    /// Updates the current observer state (based on the subscription
+    /// and sync status) and accounts for the time spent in the previous state.
+    fn update_observer_state(&mut self) {
+        let observer_state = if self.sync_handle.is_some() {
+            ObserverState::Syncing
+        } else if self
+            .subscription_manager
+            .get_active_subscription_peer()
+            .is_some()
         {
-            // Finalize the ordered block
-            self.finalize_ordered_block(ordered_block).await;
-
-            // If a commit decision is available, forward it to the execution pipeline
-            if let Some(commit_decision) = commit_decision {
-                self.forward_commit_decision(commit_decision.clone());
-            }
-        }
+            ObserverState::Subscribed
+        } else {
+            ObserverState::Searching
+        };
+        self.time_in_state_tracker.update_state(observer_state);
+        self.observer_status_handle.update_state(
+            observer_state,
+            self.subscription_manager.get_active_subscription_peer(),
+        );
+        self.observer_status_handle
+            .update_subscription_health(self.subscription_manager.get_active_subscription_health());
     }
 
-    /// Produces a list of sorted peers to service our subscription request. Peers
-    /// are prioritized by validator distance and latency.
-    /// Note: if `previous_subscription_peer` is provided, it will be excluded
-    /// from the selection process. Likewise, all peers currently subscribed to us
-    /// will be excluded from the selection process.
-    fn sort_peers_for_subscription(
+    /// Checks the timestamps of the given (verified) ordered block. Violations
+    /// are either rejected or flagged (depending on the config). Returns false
+    /// iff the ordered block should be rejected.
+    fn check_block_timestamps(
         &mut self,
-        previous_subscription_peer: Option<PeerNetworkId>,
-    ) -> Option<Vec<PeerNetworkId>> {
-        if let Some(mut peers_and_metadata) = self.get_connected_peers_and_metadata() {
-            // Remove the previous subscription peer (if provided)
-            if let Some(previous_subscription_peer) = previous_subscription_peer {
-                let _ = peers_and_metadata.remove(&previous_subscription_peer);
-            }
+        peer_network_id: PeerNetworkId,
+        ordered_block: &OrderedBlock,
+    ) -> bool {
+        let error = match self.verify_block_timestamps(ordered_block) {
+            Ok(()) => return true,
+            Err(error) => error,
+        };
 
-            // Remove any peers that are currently subscribed to us
-            if let Some(consensus_publisher) = &self.consensus_publisher {
-                for peer_network_id in consensus_publisher.get_active_subscribers() {
-                    let _ = peers_and_metadata.remove(&peer_network_id);
-                }
-            }
+        if self
+            .consensus_observer_config
+            .reject_invalid_block_timestamps
+        {
+            error!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to verify block timestamps! Ignoring: {:?}, Error: {:?}",
+                    ordered_block.proof_block_info(),
+                    error
+                ))
+            );
+            metrics::increment_block_timestamp_violations(metrics::TIMESTAMP_REJECTED_LABEL);
+            self.record_verification_failure(peer_network_id, "block_timestamps", &error);
+            return false;
+        }
 
-            // Sort the peers by validator distance and latency
-            let sorted_peers = subscription::sort_peers_by_distance_and_latency(peers_and_metadata);
+        warn!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Observed blocks with invalid timestamps: {:?}, Error: {:?}",
+                ordered_block.proof_block_info(),
+                error
+            ))
+        );
+        metrics::increment_block_timestamp_violations(metrics::TIMESTAMP_FLAGGED_LABEL);
+        true
+    }
 
-            // Return the sorted peers
-            Some(sorted_peers)
-        } else {
-            None // No connected peers were found
+    /// Verifies the timestamps of the given ordered block, i.e., that they
+    /// are non-decreasing (relative to the parent, if the parent is our last
+    /// block) and within the maximum skew of the local time.
+    fn verify_block_timestamps(&self, ordered_block: &OrderedBlock) -> Result<(), Error> {
+        // Get the parent timestamp (if the parent is our last block)
+        let last_block = self
+            .observer_state_tracker
+            .last_block(&self.pending_ordered_blocks);
+        let parent_timestamp_usecs = (last_block.id() == ordered_block.first_block().parent_id())
+            .then(|| last_block.timestamp_usecs());
+
+        // Calculate the maximum timestamp (i.e., the local time plus the skew)
+        let time_now_usecs = self.time_service.now_unix_time().as_micros() as u64;
+        let max_timestamp_skew_usecs = self
+            .consensus_observer_config
+            .max_block_timestamp_skew_ms
+            .saturating_mul(1000);
+        let max_timestamp_usecs = time_now_usecs.saturating_add(max_timestamp_skew_usecs);
+
+        ordered_block.verify_block_timestamps(parent_timestamp_usecs, max_timestamp_usecs)
+    }
+
+    /// Verifies the commit proof of the given commit decision. If the
+    /// proof was already verified off the observer loop, it is not re-verified.
+    fn verify_commit_proof(
+        &self,
+        commit_decision: &CommitDecision,
+        epoch_state: &EpochState,
+    ) -> Result<(), Error> {
+        if self.is_proof_verified(commit_decision.commit_proof()) {
+            return Ok(());
         }
+        commit_decision.verify_commit_proof(epoch_state)
     }
 
-    /// Unsubscribes from the given peer by sending an unsubscribe request
-    fn unsubscribe_from_peer(&self, peer_network_id: PeerNetworkId) {
-        // Send an unsubscribe request to the peer and process the response.
-        // Note: we execute this asynchronously, as we don't need to wait for the response.
-        let consensus_observer_client = self.consensus_observer_client.clone();
-        let consensus_observer_config = self.consensus_observer_config;
-        tokio::spawn(async move {
-            // Send the unsubscribe request to the peer
-            let unsubscribe_request = ConsensusObserverRequest::Unsubscribe;
-            let response = consensus_observer_client
-                .send_rpc_request_to_peer(
-                    &peer_network_id,
-                    unsubscribe_request,
-                    consensus_observer_config.network_request_timeout_ms,
-                )
-                .await;
+    /// Verifies the ordered proof of the given ordered block. If the
+    /// proof was already verified off the observer loop, it is not re-verified.
+    fn verify_ordered_proof(
+        &self,
+        ordered_block: &OrderedBlock,
+        epoch_state: &EpochState,
+    ) -> Result<(), Error> {
+        if self.is_proof_verified(ordered_block.ordered_proof()) {
+            return Ok(());
+        }
+        ordered_block.verify_ordered_proof(epoch_state)
+    }
 
-            // Process the response
-            match response {
-                Ok(ConsensusObserverResponse::UnsubscribeAck) => {
-                    info!(
-                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                            "Successfully unsubscribed from peer: {}!",
-                            peer_network_id
-                        ))
-                    );
-                },
-                Ok(response) => {
-                    // We received an invalid response
-                    warn!(
-                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                            "Got unexpected response type: {:?}",
-                            response.get_label()
-                        ))
-                    );
-                },
-                Err(error) => {
-                    // We encountered an error while sending the request
-                    error!(
-                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                            "Failed to send unsubscribe request to peer: {}! Error: {:?}",
-                            peer_network_id, error
-                        ))
-                    );
-                },
-            }
-        });
+    /// Returns true iff the given proof was already verified off the observer loop
+    fn is_proof_verified(&self, proof: &LedgerInfoWithSignatures) -> bool {
+        self.verified_proof_cache
+            .as_ref()
+            .map_or(false, |verified_proof_cache| {
+                verified_proof_cache.contains(proof)
+            })
     }
 
-    /// Updates the subscription creation metrics for the given peer
-    fn update_subscription_creation_metrics(&self, peer_network_id: PeerNetworkId) {
-        // Set the number of active subscriptions
-        metrics::set_gauge(
-            &metrics::OBSERVER_NUM_ACTIVE_SUBSCRIPTIONS,
-            &peer_network_id.network_id(),
-            1,
-        );
+    /// Verifies that storage was synced to (or beyond) the root. This
+    /// ensures that the pipeline restarts from the synced root after a sync.
+    fn verify_synced_root(&self) -> Result<(), Error> {
+        let root_block = self.observer_state_tracker.root_block();
+        let latest_ledger_info = self.observer_storage.get_latest_ledger_info()?;
+        let synced_block = latest_ledger_info.commit_info();
+        if (synced_block.epoch(), synced_block.round()) < (root_block.epoch(), root_block.round()) {
+            return Err(Error::UnexpectedError(format!(
+                "Storage was not synced to the root! Root: {}, latest synced block: {}",
+                root_block, synced_block
+            )));
+        }
 
-        // Update the number of created subscriptions
-        metrics::increment_request_counter(
-            &metrics::OBSERVER_CREATED_SUBSCRIPTIONS,
-            metrics::CREATED_SUBSCRIPTION_LABEL,
-            &peer_network_id,
-        );
+        Ok(())
     }
 
-    /// Updates the subscription termination metrics for the given peer
-    fn update_subscription_termination_metrics(
-        &self,
+    /// Verifies the given block payload against the given (verified) ordered
+    /// block. The payload must be for the same block (including the timestamp,
+    /// which determines the expired proofs), the quorum store proofs of the block
+    /// must be certified by the validators of the block epoch, and the payload
+    /// transactions must match the batch digests. The rejection metrics are
+    /// updated if verification fails.
+    fn verify_block_payload(
+        &mut self,
+        block_payload: &BlockPayload,
+        block: &PipelinedBlock,
+        message_type: &str,
+    ) -> Result<(), Error> {
+        // Verify the payload is for the ordered block
+        let payload_block = &block_payload.block;
+        let block_info = block.block_info();
+        if (
+            payload_block.epoch(),
+            payload_block.round(),
+            payload_block.id(),
+            payload_block.timestamp_usecs(),
+        ) != (
+            block_info.epoch(),
+            block_info.round(),
+            block_info.id(),
+            block_info.timestamp_usecs(),
+        ) {
+            metrics::increment_rejected_block_payloads(metrics::PAYLOAD_BLOCK_MISMATCH_LABEL);
+            return Err(Error::InvalidMessageError(format!(
+                "The block payload doesn't match the ordered block! Payload block: {}, ordered block: {}",
+                payload_block, block_info
+            )));
+        }
+
+        // Verify the quorum store proofs of the block (using the epoch state of the block)
+        let epoch_state = self
+            .epoch_state_cache
+            .get_epoch_state(block_info.epoch())
+            .ok_or_else(|| {
+                Error::UnexpectedError(format!(
+                    "Failed to find the epoch state to verify the payload proofs of block: {}",
+                    block_info
+                ))
+            })?;
+        if let Err(error) =
+            block_payload.verify_payload_proofs(block.payload(), &epoch_state, &self.proof_cache)
+        {
+            metrics::increment_rejected_block_payloads(metrics::PAYLOAD_INVALID_PROOFS_LABEL);
+            return Err(error);
+        }
+
+        // Verify the payload transactions against the batch digests
+        if let Err(error) = block_payload.verify_payload_digests(block.payload()) {
+            metrics::increment_payload_digest_mismatch(message_type);
+            metrics::increment_rejected_block_payloads(metrics::PAYLOAD_DIGEST_MISMATCH_LABEL);
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the stored payloads for the given (verified) ordered block.
+    /// Payloads that fail verification are removed from the payload
+    /// store (so that a valid payload can be received instead). Returns
+    /// true iff none of the stored payloads failed verification.
+    fn verify_stored_block_payloads(
+        &mut self,
         peer_network_id: PeerNetworkId,
-        error: Error,
-    ) {
-        // Reset the number of active subscriptions
-        metrics::set_gauge(
-            &metrics::OBSERVER_NUM_ACTIVE_SUBSCRIPTIONS,
-            &peer_network_id.network_id(),
-            0,
-        );
+        ordered_block: &OrderedBlock,
+    ) -> bool {
+        let mut stored_payloads_valid = true;
+        for block in ordered_block.blocks() {
+            // Get the stored payload for the block (if any)
+            let block_payload = match self.block_payload_store.get_block_payload(block) {
+                Some(block_payload) => block_payload,
+                None => continue, // The payload hasn't been received yet
+            };
 
-        // Update the number of terminated subscriptions
-        metrics::increment_request_counter(
-            &metrics::OBSERVER_TERMINATED_SUBSCRIPTIONS,
-            error.get_label(),
-            &peer_network_id,
-        );
+            // Verify the payload against the block
+            if let Err(error) = self.verify_block_payload(&block_payload, block, "ordered_block") {
+                error!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to verify stored block payload! Removing payload: {}, Error: {:?}",
+                        block.block_info(),
+                        error
+                    ))
+                );
+                self.block_payload_store.remove_blocks(&[block.clone()]);
+                if matches!(error, Error::InvalidMessageError(_)) {
+                    self.record_verification_failure(peer_network_id, "block_payload", &error);
+                }
+                stored_payloads_valid = false;
+            }
+        }
+
+        stored_payloads_valid
     }
 
     /// Waits for a new epoch to start
-    async fn wait_for_epoch_start(&mut self) {
+    pub(crate) async fn wait_for_epoch_start(&mut self) {
+        // Update the observer state to reflect the epoch transition
+        self.time_in_state_tracker
+            .update_state(ObserverState::EpochTransition);
+        self.observer_status_handle.update_state(
+            ObserverState::EpochTransition,
+            self.subscription_manager.get_active_subscription_peer(),
+        );
+
         // Extract the epoch state and on-chain configs
         let (epoch_state, consensus_config, execution_config, randomness_config) = if let Some(
             reconfig_events,
@@ -901,8 +3103,23 @@ impl ConsensusObserver {
             panic!("Reconfig events are required to wait for a new epoch to start! Something has gone wrong!")
         };
 
-        // Update the local epoch state
-        self.epoch_state = Some(epoch_state.clone());
+        // Emit the summary of the previous epoch and start tracking the new one
+        self.epoch_summary_tracker
+            .start_new_epoch(epoch_state.epoch);
+
+        // Lift the blocklists of the previous epochs (e.g., for equivocating peers)
+        self.subscription_manager
+            .remove_expired_epoch_blocklists(epoch_state.epoch);
+
+        // Update the local epoch state (and cache it for future verification)
+        self.observer_state_tracker
+            .set_epoch_state(epoch_state.clone());
+        self.epoch_state_cache
+            .insert_epoch_state(epoch_state.clone());
+        self.event_journal
+            .record_event(ObserverEvent::EpochStarted {
+                epoch: epoch_state.epoch,
+            });
         info!(
             LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
                 "New epoch started: {}. Updated the epoch state!",
@@ -913,7 +3130,7 @@ impl ConsensusObserver {
         // Create the payload manager
         let payload_manager = if consensus_config.quorum_store_enabled() {
             PayloadManager::ConsensusObserver(
-                self.block_payload_store.get_block_payloads(),
+                self.block_payload_store.get_payload_store_backend(),
                 self.consensus_publisher.clone(),
             )
         } else {
@@ -942,24 +3159,45 @@ impl ConsensusObserver {
                 0,
             )
             .await;
+
+        // The epoch transition is complete, so update the observer state
+        self.update_observer_state();
+
+        // Process the buffered future commit decisions (if they are for the new epoch)
+        self.process_buffered_future_commit_decision();
+    }
+
+    /// Updates the proof verification filter with the current subscription peers.
+    /// Subscriptions only change on the observer loop, so updating the filter after
+    /// each loop iteration ensures that proofs are only verified off the loop for
+    /// the messages of subscription peers.
+    fn update_proof_verification_filter(&self) {
+        if self.verified_proof_cache.is_some() {
+            self.proof_verification_filter
+                .update_subscription_peers(&self.subscription_manager.get_subscription_peers());
+        }
     }
 
     /// Starts the consensus observer loop that processes incoming
     /// network messages and ensures the observer is making progress.
     pub async fn start(
         mut self,
-        mut network_service_events: ConsensusObserverNetworkEvents,
-        mut sync_notification_listener: tokio::sync::mpsc::UnboundedReceiver<(u64, Round)>,
+        network_service_events: ConsensusObserverNetworkEvents,
+        mut sync_notification_listener: SyncNotificationListener,
     ) {
-        // If the consensus publisher is enabled but the observer is disabled,
-        // we should only forward incoming requests to the consensus publisher.
-        if self.consensus_observer_config.publisher_enabled
-            && !self.consensus_observer_config.observer_enabled
-        {
-            self.start_publisher_forwarding(&mut network_service_events)
-                .await;
-            return; // We should never return from this function
-        }
+        // Verify the message proofs off the observer loop (if enabled)
+        let mut network_messages = match self.verified_proof_cache.clone() {
+            Some(verified_proof_cache) => ProofVerificationStream::new(
+                network_service_events,
+                self.consensus_observer_config
+                    .max_parallel_proof_verifications as usize,
+                self.observer_state_tracker.clone(),
+                verified_proof_cache,
+                self.proof_verification_filter.clone(),
+            )
+            .boxed(),
+            None => network_service_events.boxed(),
+        };
 
         // Create a progress check ticker
         let mut progress_check_interval = IntervalStream::new(interval(Duration::from_millis(
@@ -967,104 +3205,124 @@ impl ConsensusObserver {
         )))
         .fuse();
 
+        // Merge all the block sources into a single stream (tagged by source label)
+        let mut block_source_messages = select_all(
+            mem::take(&mut self.block_sources)
+                .into_iter()
+                .map(|block_source| {
+                    let source_label = block_source.get_label();
+                    block_source.map(move |message| (source_label, message))
+                }),
+        );
+
+        // Create the synced commit stream (this is empty if there is no listener)
+        let mut synced_commit_notifications = select_all(self.synced_commit_listener.take());
+
+        // Create the control command stream
+        let mut control_commands = select_all(self.control_command_receiver.take());
+
         // Wait for the epoch to start
         self.wait_for_epoch_start().await;
 
+        // Resume from the last state snapshot (if any)
+        self.resume_from_state_snapshot();
+
+        // Prune the blocks that were already committed to storage (if enabled)
+        if self
+            .feature_flags
+            .is_enabled(ObserverFeature::CommittedBlockPruning)
+        {
+            self.prune_committed_pending_blocks();
+        }
+
         // Start the consensus observer loop
         info!(LogSchema::new(LogEntry::ConsensusObserver)
             .message("Starting the consensus observer loop!"));
+        self.update_proof_verification_filter();
         loop {
+            let iteration_start_time = Instant::now();
             tokio::select! {
-                Some(network_message) = network_service_events.next() => {
-                    // Unpack the network message
-                    let NetworkMessage {
-                        peer_network_id,
-                        protocol_id: _,
-                        consensus_observer_message,
-                        response_sender,
-                    } = network_message;
-
-                    // Process the consensus observer message
-                    match consensus_observer_message {
-                        ConsensusObserverMessage::DirectSend(message) => {
-                            self.process_direct_send_message(peer_network_id, message).await;
-                        },
-                        ConsensusObserverMessage::Request(request) => {
-                            self.process_request_message(peer_network_id, request, response_sender);
-                        },
-                        _ => {
-                            error!(LogSchema::new(LogEntry::ConsensusObserver)
-                                .message(&format!("Received unexpected message from peer: {}", peer_network_id)));
-                        },
-                    }
+                Some(network_message) = network_messages.next() => {
+                    let message_label = network_message.consensus_observer_message.get_label();
+                    let processing_start_time = Instant::now();
+                    self.process_network_message(network_message).await;
+                    metrics::observe_loop_branch_processing_time(
+                        message_label,
+                        processing_start_time.elapsed(),
+                    );
+                }
+                Some((source_label, block_source_message)) = block_source_messages.next() => {
+                    let processing_start_time = Instant::now();
+                    self.process_block_source_message(source_label, block_source_message).await;
+                    metrics::observe_loop_branch_processing_time(
+                        metrics::BLOCK_SOURCE_BRANCH_LABEL,
+                        processing_start_time.elapsed(),
+                    );
                 }
                 Some((epoch, round)) = sync_notification_listener.recv() => {
+                    let processing_start_time = Instant::now();
                     self.process_sync_notification(epoch, round).await;
+                    metrics::observe_loop_branch_processing_time(
+                        metrics::SYNC_NOTIFICATION_BRANCH_LABEL,
+                        processing_start_time.elapsed(),
+                    );
                 },
+                Some(synced_commit_notification) = synced_commit_notifications.next() => {
+                    let processing_start_time = Instant::now();
+                    self.process_synced_commit_notification(synced_commit_notification);
+                    metrics::observe_loop_branch_processing_time(
+                        metrics::SYNCED_COMMIT_BRANCH_LABEL,
+                        processing_start_time.elapsed(),
+                    );
+                }
+                Some(control_command) = control_commands.next() => {
+                    let processing_start_time = Instant::now();
+                    self.process_control_command(control_command).await;
+                    metrics::observe_loop_branch_processing_time(
+                        metrics::CONTROL_COMMAND_BRANCH_LABEL,
+                        processing_start_time.elapsed(),
+                    );
+                }
                 _ = progress_check_interval.select_next_some() => {
+                    let processing_start_time = Instant::now();
                     self.check_progress().await;
+                    metrics::observe_loop_branch_processing_time(
+                        metrics::PROGRESS_CHECK_BRANCH_LABEL,
+                        processing_start_time.elapsed(),
+                    );
                 }
             else => break,
             }
+
+            // Update the subscription peers (checked before verifying proofs off the loop)
+            self.update_proof_verification_filter();
+
+            // Update the loop iteration latency (including the time spent waiting for an event)
+            metrics::observe_loop_iteration_latency(iteration_start_time.elapsed());
         }
 
         // Log the exit of the consensus observer loop
         error!(LogSchema::new(LogEntry::ConsensusObserver)
             .message("The consensus observer loop exited unexpectedly!"));
-    }
-
-    /// Starts the publisher forwarding loop that forwards incoming
-    /// requests to the consensus publisher. The rest of the consensus
-    /// observer functionality is disabled.
-    async fn start_publisher_forwarding(
-        &mut self,
-        network_service_events: &mut ConsensusObserverNetworkEvents,
-    ) {
-        // TODO: identify if there's a cleaner way to handle this!
 
-        // Start the consensus publisher forwarding loop
-        info!(LogSchema::new(LogEntry::ConsensusObserver)
-            .message("Starting the consensus publisher forwarding loop!"));
-        loop {
-            tokio::select! {
-                Some(network_message) = network_service_events.next() => {
-                    // Unpack the network message
-                    let NetworkMessage {
-                        peer_network_id,
-                        protocol_id: _,
-                        consensus_observer_message,
-                        response_sender,
-                    } = network_message;
-
-                    // Process the consensus observer message
-                    match consensus_observer_message {
-                        ConsensusObserverMessage::Request(request) => {
-                            self.process_request_message(peer_network_id, request, response_sender);
-                        },
-                        _ => {
-                            error!(LogSchema::new(LogEntry::ConsensusObserver)
-                                .message(&format!("Received unexpected message from peer: {}", peer_network_id)));
-                        },
-                    }
-                }
-            }
-        }
+        // Cancel and join all background tasks (to avoid orphaned tasks)
+        self.sync_handle = None;
+        self.task_registry.shutdown().await;
     }
 }
 
-/// Checks that the epoch and round match the current root
-fn check_root_epoch_and_round(
-    root: Arc<Mutex<LedgerInfoWithSignatures>>,
-    epoch: u64,
-    round: Round,
-) -> bool {
-    // Get the expected epoch and round
-    let root = root.lock();
-    let expected_epoch = root.commit_info().epoch();
-    let expected_round = root.commit_info().round();
-
-    // Check if the expected epoch and round match
-    expected_epoch == epoch && expected_round == round
+/// Updates the per-peer inbound message metrics for the given message
+pub(crate) fn update_inbound_message_metrics(
+    peer_network_id: &PeerNetworkId,
+    consensus_observer_message: &ConsensusObserverMessage,
+    num_message_bytes: usize,
+) {
+    // Update the message and byte counters for the peer
+    metrics::increment_received_message_counters_for_peer(
+        peer_network_id,
+        consensus_observer_message.get_label(),
+        num_message_bytes as u64,
+    );
 }
 
 /// A simple helper function that extracts the on-chain configs from the reconfig events
@@ -1141,43 +3399,66 @@ async fn extract_on_chain_configs(
     )
 }
 
-/// Spawns a task to sync to the given commit decision and notifies
-/// the consensus observer. Also, returns an abort handle to cancel the task.
+/// Verifies the transaction signatures of the given block payload on a blocking
+/// thread (to avoid stalling the observer runtime) and returns the payload.
+async fn verify_transaction_signatures(block_payload: BlockPayload) -> Result<BlockPayload, Error> {
+    tokio::task::spawn_blocking(move || {
+        block_payload
+            .verify_transaction_signatures()
+            .map(|_| block_payload)
+    })
+    .await
+    .map_err(|error| {
+        Error::UnexpectedError(format!(
+            "Failed to join the signature verification task! Error: {:?}",
+            error
+        ))
+    })?
+}
+
+/// Spawns a task (via the task registry) to sync to the given commit decision and
+/// notifies the consensus observer. Also, returns an abort handle to cancel the task.
 fn sync_to_commit_decision(
+    task_registry: &TaskRegistry,
     commit_decision: CommitDecision,
     decision_epoch: u64,
     decision_round: Round,
     execution_client: Arc<dyn TExecutionClient>,
-    sync_notification_sender: UnboundedSender<(u64, Round)>,
+    sync_notification_sender: SyncNotificationSender,
 ) -> AbortHandle {
-    let (abort_handle, abort_registration) = AbortHandle::new_pair();
-    tokio::spawn(Abortable::new(
-        async move {
-            // Sync to the commit decision
-            if let Err(error) = execution_client
-                .clone()
-                .sync_to(commit_decision.commit_proof().clone())
-                .await
-            {
-                warn!(
-                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                        "Failed to sync to commit decision: {:?}! Error: {:?}",
-                        commit_decision, error
-                    ))
-                );
-            }
+    task_registry.spawn_task("sync_to_commit_decision", async move {
+        fail_point!("consensus_observer::sync_to_commit_decision", |_| {});
 
-            // Notify the consensus observer that the sync is complete
-            if let Err(error) = sync_notification_sender.send((decision_epoch, decision_round)) {
-                error!(
-                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                        "Failed to send sync notification for decision epoch: {:?}, round: {:?}! Error: {:?}",
-                        decision_epoch, decision_round, error
-                    ))
-                );
-            }
-        },
-        abort_registration,
-    ));
-    abort_handle
+        // Tear down the execution pipeline before syncing (i.e., drop all in-flight
+        // blocks and reset the buffer manager). Note: the sync also resets the
+        // pipeline, so a failure here is not fatal, but it should be investigated.
+        if let Err(error) = execution_client.reset(commit_decision.commit_proof()).await {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to tear down the execution pipeline before syncing to: {}! Error: {:?}",
+                    commit_decision.proof_block_info(),
+                    error
+                ))
+            );
+            metrics::increment_pipeline_teardown_failure(metrics::TEARDOWN_RESET_LABEL);
+        }
+
+        // Sync to the commit decision
+        if let Err(error) = execution_client
+            .clone()
+            .sync_to(commit_decision.commit_proof().clone())
+            .await
+        {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to sync to commit decision: {:?}! Error: {:?}",
+                    commit_decision, error
+                ))
+            );
+        }
+
+        // Notify the consensus observer that the sync is complete (this
+        // replaces any pending notification that is yet to be processed).
+        sync_notification_sender.notify_sync_completed(decision_epoch, decision_round);
+    })
 }
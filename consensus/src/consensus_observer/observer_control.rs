@@ -0,0 +1,235 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::error::Error;
+use aptos_config::network_id::PeerNetworkId;
+use aptos_types::{block_info::BlockInfo, ledger_info::LedgerInfoWithSignatures};
+use futures_channel::{mpsc, oneshot};
+
+/// The commands that operators can send to the running observer
+pub enum ObserverControlCommand {
+    /// Aborts observation and forces a state sync to the given commit (or the
+    /// latest known commit, if none is given). Observation resumes once the
+    /// sync completes. The response contains the block info of the sync target.
+    ForceSync(
+        Option<LedgerInfoWithSignatures>,
+        oneshot::Sender<Result<BlockInfo, Error>>,
+    ),
+
+    /// Terminates the active subscription and immediately subscribes to the
+    /// given peer (or reruns the peer selection, if no peer is given). The
+    /// response contains the peer of the new subscription.
+    ForceResubscribe(
+        Option<PeerNetworkId>,
+        oneshot::Sender<Result<PeerNetworkId, Error>>,
+    ),
+
+    /// Pauses the finalization of ordered blocks and commit decisions (i.e.,
+    /// nothing is sent to the execution pipeline). Messages continue to be
+    /// verified and buffered, and the subscription is maintained.
+    PauseFinalization(oneshot::Sender<()>),
+
+    /// Resumes the finalization of ordered blocks and commit decisions. All
+    /// blocks and commits buffered while finalization was paused are sent to
+    /// the execution pipeline (in order).
+    ResumeFinalization(oneshot::Sender<()>),
+}
+
+impl ObserverControlCommand {
+    /// Returns a summary label for the command
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ObserverControlCommand::ForceSync(..) => "force_sync",
+            ObserverControlCommand::ForceResubscribe(..) => "force_resubscribe",
+            ObserverControlCommand::PauseFinalization(..) => "pause_finalization",
+            ObserverControlCommand::ResumeFinalization(..) => "resume_finalization",
+        }
+    }
+}
+
+/// A cheaply cloneable handle used by other components (e.g., the admin
+/// service) to send control commands to the running observer. Commands are
+/// processed by the observer loop (in the order they are received).
+#[derive(Clone)]
+pub struct ObserverControlHandle {
+    command_sender: mpsc::UnboundedSender<ObserverControlCommand>,
+}
+
+impl ObserverControlHandle {
+    /// Creates a new control handle and the receiver for the control commands
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<ObserverControlCommand>) {
+        let (command_sender, command_receiver) = mpsc::unbounded();
+        (Self { command_sender }, command_receiver)
+    }
+
+    /// Forces the observer to state sync to the given commit (or the latest
+    /// known commit, if none is given), and returns the sync target.
+    pub async fn force_sync(
+        &self,
+        ledger_info: Option<LedgerInfoWithSignatures>,
+    ) -> Result<BlockInfo, Error> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.send_command(ObserverControlCommand::ForceSync(
+            ledger_info,
+            response_sender,
+        ))?;
+        response_receiver.await.map_err(|error| {
+            Error::UnexpectedError(format!(
+                "The observer dropped the force sync command! Error: {:?}",
+                error
+            ))
+        })?
+    }
+
+    /// Forces the observer to resubscribe to the given peer (or to rerun the
+    /// peer selection, if no peer is given), and returns the new subscription peer.
+    pub async fn force_resubscribe(
+        &self,
+        peer_network_id: Option<PeerNetworkId>,
+    ) -> Result<PeerNetworkId, Error> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.send_command(ObserverControlCommand::ForceResubscribe(
+            peer_network_id,
+            response_sender,
+        ))?;
+        response_receiver.await.map_err(|error| {
+            Error::UnexpectedError(format!(
+                "The observer dropped the force resubscribe command! Error: {:?}",
+                error
+            ))
+        })?
+    }
+
+    /// Pauses the finalization of ordered blocks and commit decisions
+    pub async fn pause_finalization(&self) -> Result<(), Error> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.send_command(ObserverControlCommand::PauseFinalization(response_sender))?;
+        response_receiver.await.map_err(|error| {
+            Error::UnexpectedError(format!(
+                "The observer dropped the pause finalization command! Error: {:?}",
+                error
+            ))
+        })
+    }
+
+    /// Resumes the finalization of ordered blocks and commit decisions
+    pub async fn resume_finalization(&self) -> Result<(), Error> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.send_command(ObserverControlCommand::ResumeFinalization(response_sender))?;
+        response_receiver.await.map_err(|error| {
+            Error::UnexpectedError(format!(
+                "The observer dropped the resume finalization command! Error: {:?}",
+                error
+            ))
+        })
+    }
+
+    /// Sends the given command to the observer
+    fn send_command(&self, command: ObserverControlCommand) -> Result<(), Error> {
+        let command_label = command.get_label();
+        self.command_sender
+            .unbounded_send(command)
+            .map_err(|error| {
+                Error::UnexpectedError(format!(
+                    "Failed to send the {} command to the observer! Error: {:?}",
+                    command_label, error
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_config::network_id::NetworkId;
+    use aptos_types::PeerId;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_force_sync_command() {
+        // Create a control handle
+        let (control_handle, mut command_receiver) = ObserverControlHandle::new();
+
+        // Respond to the force sync command (in the background)
+        let sync_target = BlockInfo::random_with_epoch(10, 100);
+        let expected_sync_target = sync_target.clone();
+        tokio::spawn(async move {
+            match command_receiver.next().await {
+                Some(ObserverControlCommand::ForceSync(ledger_info, response_sender)) => {
+                    assert!(ledger_info.is_none());
+                    response_sender.send(Ok(sync_target)).unwrap();
+                },
+                _ => panic!("Expected a force sync command!"),
+            }
+        });
+
+        // Send the force sync command and verify the response
+        let result = control_handle.force_sync(None).await;
+        assert_eq!(result.unwrap(), expected_sync_target);
+
+        // Verify that commands fail once the receiver is dropped
+        let result = control_handle.force_sync(None).await;
+        assert!(matches!(result, Err(Error::UnexpectedError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_force_resubscribe_command() {
+        // Create a control handle
+        let (control_handle, mut command_receiver) = ObserverControlHandle::new();
+
+        // Respond to the force resubscribe command (in the background)
+        let peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        tokio::spawn(async move {
+            match command_receiver.next().await {
+                Some(ObserverControlCommand::ForceResubscribe(
+                    requested_peer_network_id,
+                    response_sender,
+                )) => {
+                    assert_eq!(requested_peer_network_id, Some(peer_network_id));
+                    response_sender.send(Ok(peer_network_id)).unwrap();
+                },
+                _ => panic!("Expected a force resubscribe command!"),
+            }
+        });
+
+        // Send the force resubscribe command and verify the response
+        let result = control_handle
+            .force_resubscribe(Some(peer_network_id))
+            .await;
+        assert_eq!(result.unwrap(), peer_network_id);
+
+        // Verify that commands fail once the receiver is dropped
+        let result = control_handle.force_resubscribe(None).await;
+        assert!(matches!(result, Err(Error::UnexpectedError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_finalization_commands() {
+        // Create a control handle
+        let (control_handle, mut command_receiver) = ObserverControlHandle::new();
+
+        // Respond to the pause and resume commands (in the background)
+        tokio::spawn(async move {
+            match command_receiver.next().await {
+                Some(ObserverControlCommand::PauseFinalization(response_sender)) => {
+                    response_sender.send(()).unwrap();
+                },
+                _ => panic!("Expected a pause finalization command!"),
+            }
+            match command_receiver.next().await {
+                Some(ObserverControlCommand::ResumeFinalization(response_sender)) => {
+                    response_sender.send(()).unwrap();
+                },
+                _ => panic!("Expected a resume finalization command!"),
+            }
+        });
+
+        // Send the pause and resume commands and verify the responses
+        control_handle.pause_finalization().await.unwrap();
+        control_handle.resume_finalization().await.unwrap();
+
+        // Verify that commands fail once the receiver is dropped
+        let result = control_handle.pause_finalization().await;
+        assert!(matches!(result, Err(Error::UnexpectedError(_))));
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    payload_store::{BlockPayloadStore, PayloadStoreOccupancy},
+    pending_blocks::PendingOrderedBlocks,
+    state_snapshot::PendingBlockDigest,
+    state_tracker::ObserverStateTracker,
+    subscription::SubscriptionHealth,
+    time_in_state::ObserverState,
+};
+use aptos_config::network_id::PeerNetworkId;
+use aptos_infallible::{duration_since_epoch, Mutex};
+use aptos_types::{block_info::BlockInfo, ledger_info::LedgerInfoWithSignatures};
+use serde::Serialize;
+use std::{
+    fmt::{Display, Formatter},
+    sync::Arc,
+};
+
+/// A structured summary of the current consensus observer status
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ObserverStatus {
+    pub observer_state: String,
+    pub subscription_peer: Option<PeerNetworkId>,
+    pub root: BlockInfo,
+    pub root_lag_ms: u64,
+    pub num_pending_blocks: u64,
+    pub num_pending_payloads: u64,
+    pub last_error: Option<String>,
+}
+
+impl Display for ObserverStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Observer state: {}", self.observer_state)?;
+        match &self.subscription_peer {
+            Some(subscription_peer) => writeln!(f, "Subscription peer: {}", subscription_peer)?,
+            None => writeln!(f, "Subscription peer: none")?,
+        }
+        writeln!(f, "Root: {}", self.root)?;
+        writeln!(f, "Root lag (ms): {}", self.root_lag_ms)?;
+        writeln!(f, "Num pending blocks: {}", self.num_pending_blocks)?;
+        writeln!(f, "Num pending payloads: {}", self.num_pending_payloads)?;
+        match &self.last_error {
+            Some(last_error) => write!(f, "Last error: {}", last_error),
+            None => write!(f, "Last error: none"),
+        }
+    }
+}
+
+/// The state sync status of the observer
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct ObserverSyncStatus {
+    pub sync_target: Option<BlockInfo>, // The target of the active sync (if any)
+    pub observation_paused_for_sync: bool, // True iff observation was paused for a forced sync
+}
+
+/// A detailed snapshot of the live consensus observer state. This is
+/// dumped by the admin service to help operators debug observer stalls.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ObserverStateDump {
+    pub status: ObserverStatus,
+    pub subscription_health: Option<SubscriptionHealth>,
+    pub root_ledger_info: LedgerInfoWithSignatures,
+    pub pending_blocks: Vec<PendingBlockDigest>,
+    pub payload_store_occupancy: PayloadStoreOccupancy,
+    pub sync_status: ObserverSyncStatus,
+    pub finalization_paused: bool,
+}
+
+/// The status fields that are updated directly by the observer
+struct ObserverStatusFields {
+    observer_state: ObserverState,
+    subscription_peer: Option<PeerNetworkId>,
+    subscription_health: Option<SubscriptionHealth>,
+    sync_status: ObserverSyncStatus,
+    finalization_paused: bool,
+    last_error: Option<String>,
+}
+
+/// A cheaply cloneable handle to query the current status of the consensus
+/// observer. This allows other components (e.g., the admin service and health
+/// checks) to inspect the observer without scraping metrics or logs. The root
+/// and pending counts are read directly from the (shared) observer state.
+#[derive(Clone)]
+pub struct ObserverStatusHandle {
+    // The status fields updated by the observer
+    status_fields: Arc<Mutex<ObserverStatusFields>>,
+
+    // The shared observer state (used to read the root and pending counts)
+    observer_state_tracker: ObserverStateTracker,
+    pending_ordered_blocks: PendingOrderedBlocks,
+    block_payload_store: BlockPayloadStore,
+}
+
+impl ObserverStatusHandle {
+    pub fn new(
+        observer_state_tracker: ObserverStateTracker,
+        pending_ordered_blocks: PendingOrderedBlocks,
+        block_payload_store: BlockPayloadStore,
+    ) -> Self {
+        let status_fields = ObserverStatusFields {
+            observer_state: ObserverState::EpochTransition,
+            subscription_peer: None,
+            subscription_health: None,
+            sync_status: ObserverSyncStatus::default(),
+            finalization_paused: false,
+            last_error: None,
+        };
+        Self {
+            status_fields: Arc::new(Mutex::new(status_fields)),
+            observer_state_tracker,
+            pending_ordered_blocks,
+            block_payload_store,
+        }
+    }
+
+    /// Returns the current status of the observer
+    pub fn get_status(&self) -> ObserverStatus {
+        // Calculate the lag between the root timestamp and the local time
+        let root = self.observer_state_tracker.root_block();
+        let time_now_usecs = duration_since_epoch().as_micros() as u64;
+        let root_lag_ms = time_now_usecs.saturating_sub(root.timestamp_usecs()) / 1000;
+
+        // Create the observer status
+        let status_fields = self.status_fields.lock();
+        ObserverStatus {
+            observer_state: status_fields.observer_state.get_label().into(),
+            subscription_peer: status_fields.subscription_peer,
+            root,
+            root_lag_ms,
+            num_pending_blocks: self.pending_ordered_blocks.get_num_pending_blocks() as u64,
+            num_pending_payloads: self
+                .block_payload_store
+                .get_payload_store_backend()
+                .get_num_payloads() as u64,
+            last_error: status_fields.last_error.clone(),
+        }
+    }
+
+    /// Returns a detailed snapshot of the current observer state
+    pub fn get_state_dump(&self) -> ObserverStateDump {
+        let status = self.get_status();
+        let status_fields = self.status_fields.lock();
+        ObserverStateDump {
+            status,
+            subscription_health: status_fields.subscription_health.clone(),
+            root_ledger_info: self.observer_state_tracker.root().as_ref().clone(),
+            pending_blocks: self.pending_ordered_blocks.get_pending_block_digests(),
+            payload_store_occupancy: self.block_payload_store.get_occupancy(),
+            sync_status: status_fields.sync_status.clone(),
+            finalization_paused: status_fields.finalization_paused,
+        }
+    }
+
+    /// Records the given error as the last observer error
+    pub fn record_error(&self, error: String) {
+        self.status_fields.lock().last_error = Some(error);
+    }
+
+    /// Updates the current observer state and subscription peer
+    pub fn update_state(
+        &self,
+        observer_state: ObserverState,
+        subscription_peer: Option<PeerNetworkId>,
+    ) {
+        let mut status_fields = self.status_fields.lock();
+        status_fields.observer_state = observer_state;
+        status_fields.subscription_peer = subscription_peer;
+    }
+
+    /// Updates whether the finalization of ordered blocks is paused
+    pub fn update_finalization_paused(&self, finalization_paused: bool) {
+        self.status_fields.lock().finalization_paused = finalization_paused;
+    }
+
+    /// Updates the health of the active subscription (if any)
+    pub fn update_subscription_health(&self, subscription_health: Option<SubscriptionHealth>) {
+        self.status_fields.lock().subscription_health = subscription_health;
+    }
+
+    /// Updates the state sync status of the observer
+    pub fn update_sync_status(&self, sync_status: ObserverSyncStatus) {
+        self.status_fields.lock().sync_status = sync_status;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_config::{config::ConsensusObserverConfig, network_id::NetworkId};
+    use aptos_crypto::HashValue;
+    use aptos_time_service::TimeService;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+        PeerId,
+    };
+
+    #[test]
+    fn test_get_status() {
+        // Create a status handle
+        let root = BlockInfo::random_with_epoch(10, 100);
+        let observer_status_handle = ObserverStatusHandle::new(
+            ObserverStateTracker::new(LedgerInfoWithSignatures::new(
+                LedgerInfo::new(root.clone(), HashValue::random()),
+                AggregateSignature::empty(),
+            )),
+            PendingOrderedBlocks::new(ConsensusObserverConfig::default()),
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock()),
+        );
+
+        // Verify the initial status
+        let observer_status = observer_status_handle.get_status();
+        assert_eq!(observer_status.observer_state, "epoch_transition");
+        assert_eq!(observer_status.subscription_peer, None);
+        assert_eq!(observer_status.root, root);
+        assert_eq!(observer_status.num_pending_blocks, 0);
+        assert_eq!(observer_status.num_pending_payloads, 0);
+        assert_eq!(observer_status.last_error, None);
+
+        // Update the observer state and record an error
+        let subscription_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        observer_status_handle.update_state(ObserverState::Subscribed, Some(subscription_peer));
+        observer_status_handle.record_error("test error".into());
+
+        // Verify that a cloned handle returns the updated status
+        let observer_status = observer_status_handle.clone().get_status();
+        assert_eq!(observer_status.observer_state, "subscribed");
+        assert_eq!(observer_status.subscription_peer, Some(subscription_peer));
+        assert_eq!(observer_status.last_error, Some("test error".into()));
+    }
+
+    #[test]
+    fn test_get_state_dump() {
+        // Create a status handle
+        let root = BlockInfo::random_with_epoch(10, 100);
+        let root_ledger_info = LedgerInfoWithSignatures::new(
+            LedgerInfo::new(root.clone(), HashValue::random()),
+            AggregateSignature::empty(),
+        );
+        let observer_status_handle = ObserverStatusHandle::new(
+            ObserverStateTracker::new(root_ledger_info.clone()),
+            PendingOrderedBlocks::new(ConsensusObserverConfig::default()),
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock()),
+        );
+
+        // Verify the initial state dump
+        let state_dump = observer_status_handle.get_state_dump();
+        assert_eq!(state_dump.status, observer_status_handle.get_status());
+        assert_eq!(state_dump.subscription_health, None);
+        assert_eq!(state_dump.root_ledger_info, root_ledger_info);
+        assert!(state_dump.pending_blocks.is_empty());
+        assert_eq!(state_dump.payload_store_occupancy.num_payloads, 0);
+        assert_eq!(state_dump.payload_store_occupancy.total_size_bytes, 0);
+        assert_eq!(state_dump.sync_status, ObserverSyncStatus::default());
+        assert!(!state_dump.finalization_paused);
+
+        // Update the subscription health and sync status
+        let subscription_health = SubscriptionHealth {
+            peer_network_id: PeerNetworkId::new(NetworkId::Public, PeerId::random()),
+            subscription_age_ms: 1000,
+            time_since_last_message_ms: 10,
+            highest_synced_version: 500,
+            time_since_last_synced_version_increase_ms: 20,
+        };
+        observer_status_handle.update_subscription_health(Some(subscription_health.clone()));
+        let sync_status = ObserverSyncStatus {
+            sync_target: Some(BlockInfo::random_with_epoch(10, 200)),
+            observation_paused_for_sync: true,
+        };
+        observer_status_handle.update_sync_status(sync_status.clone());
+        observer_status_handle.update_finalization_paused(true);
+
+        // Verify the updated state dump
+        let state_dump = observer_status_handle.get_state_dump();
+        assert_eq!(state_dump.subscription_health, Some(subscription_health));
+        assert_eq!(state_dump.sync_status, sync_status);
+        assert!(state_dump.finalization_paused);
+    }
+}
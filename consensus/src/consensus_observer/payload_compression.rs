@@ -0,0 +1,377 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    error::Error,
+    network_message::{BlockPayload, CompressedBlockPayload, CompressionDictionary},
+};
+use aptos_config::config::{ConsensusObserverConfig, MAX_APPLICATION_MESSAGE_SIZE};
+use aptos_infallible::{Mutex, RwLock};
+use aptos_types::transaction::SignedTransaction;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+};
+
+// The zstd compression level used for dictionary compressed payloads
+const COMPRESSION_LEVEL: i32 = 3;
+
+// The minimum number of payload samples required to train a dictionary
+const MIN_NUM_TRAINING_SAMPLES: usize = 10;
+
+// The maximum number of dictionaries held by observers (to decompress
+// payloads that were compressed before the most recent dictionary refresh).
+const MAX_NUM_HELD_DICTIONARIES: usize = 3;
+
+/// The publisher-side compressor for block payloads. The compressor collects
+/// samples of the published payloads, and periodically (re)trains a shared
+/// zstd dictionary from them. Transactions within a stream share significant
+/// structural redundancy (e.g., module IDs and script prologues), so payloads
+/// compressed using the dictionary are much smaller than those compressed alone.
+pub struct PayloadDictionaryCompressor {
+    // The maximum size (in bytes) of the trained dictionaries
+    max_dictionary_size_bytes: usize,
+
+    // The maximum number of payload samples to hold for training
+    max_num_samples: usize,
+
+    // The most recent (serialized) payload samples
+    payload_samples: Mutex<VecDeque<Vec<u8>>>,
+
+    // The dictionary currently used to compress payloads (if one has been trained)
+    current_dictionary: RwLock<Option<Arc<CompressionDictionary>>>,
+}
+
+impl PayloadDictionaryCompressor {
+    pub fn new(consensus_observer_config: &ConsensusObserverConfig) -> Self {
+        Self {
+            max_dictionary_size_bytes: consensus_observer_config.max_payload_dictionary_size_bytes
+                as usize,
+            max_num_samples: consensus_observer_config.max_num_payload_dictionary_samples as usize,
+            payload_samples: Mutex::new(VecDeque::new()),
+            current_dictionary: RwLock::new(None),
+        }
+    }
+
+    /// Compresses the given block payload using the given dictionary. Note:
+    /// callers should fetch the dictionary once (via `get_current_dictionary`)
+    /// so that the dictionary shipped to subscribers matches the one used here.
+    pub fn compress_payload(
+        &self,
+        dictionary: &CompressionDictionary,
+        block_payload: &BlockPayload,
+    ) -> Result<CompressedBlockPayload, Error> {
+        // Compress the serialized payload using the dictionary
+        let serialized_payload = serialize_payload(block_payload)?;
+        let compressed_payload = zstd::bulk::Compressor::with_dictionary(
+            COMPRESSION_LEVEL,
+            &dictionary.dictionary_bytes,
+        )
+        .and_then(|mut compressor| compressor.compress(&serialized_payload))
+        .map_err(|error| {
+            Error::PayloadCompressionError(format!(
+                "Failed to compress the block payload: {}! Error: {:?}",
+                block_payload.block, error
+            ))
+        })?;
+
+        Ok(CompressedBlockPayload {
+            block: block_payload.block.clone(),
+            dictionary_id: dictionary.dictionary_id,
+            compressed_payload,
+        })
+    }
+
+    /// Returns the dictionary currently used to compress payloads (if any)
+    pub fn get_current_dictionary(&self) -> Option<Arc<CompressionDictionary>> {
+        self.current_dictionary.read().clone()
+    }
+
+    /// Installs the given dictionary as the current dictionary (i.e., all
+    /// subsequent payloads will be compressed using the new dictionary).
+    pub fn install_dictionary(&self, dictionary: Arc<CompressionDictionary>) {
+        *self.current_dictionary.write() = Some(dictionary);
+    }
+
+    /// Records the given block payload as a sample for dictionary training
+    pub fn record_sample(&self, block_payload: &BlockPayload) {
+        if let Ok(serialized_payload) = serialize_payload(block_payload) {
+            let mut payload_samples = self.payload_samples.lock();
+            payload_samples.push_back(serialized_payload);
+            while payload_samples.len() > self.max_num_samples {
+                payload_samples.pop_front();
+            }
+        }
+    }
+
+    /// Trains a new dictionary from the recorded payload samples. The new
+    /// dictionary is returned (but not installed). If there are not enough
+    /// samples to train a dictionary, None is returned.
+    pub fn train_dictionary(&self) -> Result<Option<Arc<CompressionDictionary>>, Error> {
+        // Ensure there are enough samples to train a dictionary
+        let payload_samples: Vec<_> = self.payload_samples.lock().iter().cloned().collect();
+        if payload_samples.len() < MIN_NUM_TRAINING_SAMPLES {
+            return Ok(None);
+        }
+
+        // Train the dictionary
+        let dictionary_bytes =
+            zstd::dict::from_samples(&payload_samples, self.max_dictionary_size_bytes).map_err(
+                |error| {
+                    Error::PayloadCompressionError(format!(
+                        "Failed to train the payload dictionary! Error: {:?}",
+                        error
+                    ))
+                },
+            )?;
+
+        // Create the dictionary (with the next dictionary ID)
+        let dictionary_id = self
+            .get_current_dictionary()
+            .map(|dictionary| dictionary.dictionary_id + 1)
+            .unwrap_or(0);
+        Ok(Some(Arc::new(CompressionDictionary {
+            dictionary_id,
+            dictionary_bytes,
+        })))
+    }
+}
+
+/// The observer-side decompressor for block payloads. The decompressor holds
+/// the most recent dictionaries shipped by the publisher (so that payloads
+/// compressed before a dictionary refresh can still be decompressed).
+pub struct PayloadDictionaryDecompressor {
+    // The most recent dictionaries (indexed by dictionary ID)
+    dictionaries: BTreeMap<u64, CompressionDictionary>,
+}
+
+impl PayloadDictionaryDecompressor {
+    pub fn new() -> Self {
+        Self {
+            dictionaries: BTreeMap::new(),
+        }
+    }
+
+    /// Decompresses the given compressed block payload
+    pub fn decompress_payload(
+        &self,
+        compressed_block_payload: CompressedBlockPayload,
+    ) -> Result<BlockPayload, Error> {
+        // Get the dictionary used to compress the payload
+        let CompressedBlockPayload {
+            block,
+            dictionary_id,
+            compressed_payload,
+        } = compressed_block_payload;
+        let dictionary = self.dictionaries.get(&dictionary_id).ok_or_else(|| {
+            Error::PayloadCompressionError(format!(
+                "Missing the compression dictionary: {}, for block payload: {}!",
+                dictionary_id, block
+            ))
+        })?;
+
+        // Decompress and deserialize the payload
+        let serialized_payload =
+            zstd::bulk::Decompressor::with_dictionary(&dictionary.dictionary_bytes)
+                .and_then(|mut decompressor| {
+                    decompressor.decompress(&compressed_payload, MAX_APPLICATION_MESSAGE_SIZE)
+                })
+                .map_err(|error| {
+                    Error::InvalidMessageError(format!(
+                        "Failed to decompress the block payload: {}! Error: {:?}",
+                        block, error
+                    ))
+                })?;
+        let (transactions, limit) = bcs::from_bytes::<(Vec<SignedTransaction>, Option<u64>)>(
+            &serialized_payload,
+        )
+        .map_err(|error| {
+            Error::InvalidMessageError(format!(
+                "Failed to deserialize the decompressed block payload: {}! Error: {:?}",
+                block, error
+            ))
+        })?;
+
+        Ok(BlockPayload {
+            block,
+            transactions,
+            limit,
+        })
+    }
+
+    /// Returns the number of held dictionaries
+    pub fn get_num_dictionaries(&self) -> usize {
+        self.dictionaries.len()
+    }
+
+    /// Inserts the given dictionary. If the dictionary ID is lower than those
+    /// already held (e.g., the publisher restarted, or the subscription moved
+    /// to a new publisher), the held dictionaries are stale and are cleared.
+    /// If too many dictionaries are held, those with the lowest IDs are evicted.
+    pub fn insert_dictionary(&mut self, dictionary: CompressionDictionary) {
+        // Clear any stale dictionaries
+        if let Some(highest_dictionary_id) = self.dictionaries.keys().next_back() {
+            if dictionary.dictionary_id < *highest_dictionary_id {
+                self.dictionaries.clear();
+            }
+        }
+
+        // Insert the dictionary and evict the oldest dictionaries (if required)
+        self.dictionaries
+            .insert(dictionary.dictionary_id, dictionary);
+        while self.dictionaries.len() > MAX_NUM_HELD_DICTIONARIES {
+            self.dictionaries.pop_first();
+        }
+    }
+}
+
+impl Default for PayloadDictionaryDecompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Serializes the transactions and limit of the given block payload
+fn serialize_payload(block_payload: &BlockPayload) -> Result<Vec<u8>, Error> {
+    bcs::to_bytes(&(&block_payload.transactions, &block_payload.limit)).map_err(|error| {
+        Error::PayloadCompressionError(format!(
+            "Failed to serialize the block payload: {}! Error: {:?}",
+            block_payload.block, error
+        ))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+    use aptos_types::{
+        account_address::AccountAddress, block_info::BlockInfo,
+        test_helpers::transaction_test_helpers::get_test_signed_txn,
+    };
+
+    #[test]
+    fn test_compress_and_decompress_payload() {
+        // Create a payload compressor and decompressor
+        let payload_compressor = PayloadDictionaryCompressor::new(&create_test_config());
+        let mut payload_decompressor = PayloadDictionaryDecompressor::new();
+
+        // Verify that no dictionary has been trained yet
+        let block_payload = create_block_payload(0);
+        assert!(payload_compressor.get_current_dictionary().is_none());
+
+        // Record several payload samples and train a dictionary
+        for round in 0..100 {
+            payload_compressor.record_sample(&create_block_payload(round));
+        }
+        let dictionary = payload_compressor.train_dictionary().unwrap().unwrap();
+        assert_eq!(dictionary.dictionary_id, 0);
+        payload_compressor.install_dictionary(dictionary.clone());
+
+        // Compress a payload and verify that it can't be decompressed (the dictionary is missing)
+        let current_dictionary = payload_compressor.get_current_dictionary().unwrap();
+        let compressed_block_payload = payload_compressor
+            .compress_payload(&current_dictionary, &block_payload)
+            .unwrap();
+        assert!(payload_decompressor
+            .decompress_payload(compressed_block_payload.clone())
+            .is_err());
+
+        // Insert the dictionary and verify that the payload is decompressed
+        payload_decompressor.insert_dictionary(dictionary.as_ref().clone());
+        let decompressed_block_payload = payload_decompressor
+            .decompress_payload(compressed_block_payload)
+            .unwrap();
+        assert_eq!(decompressed_block_payload, block_payload);
+    }
+
+    #[test]
+    fn test_train_dictionary() {
+        // Create a payload compressor
+        let payload_compressor = PayloadDictionaryCompressor::new(&create_test_config());
+
+        // Verify that no dictionary is trained without enough samples
+        for round in 0..MIN_NUM_TRAINING_SAMPLES - 1 {
+            payload_compressor.record_sample(&create_block_payload(round as u64));
+        }
+        assert!(payload_compressor.train_dictionary().unwrap().is_none());
+
+        // Record more samples and train several dictionaries
+        for round in 0..100 {
+            payload_compressor.record_sample(&create_block_payload(round));
+        }
+        for expected_dictionary_id in 0..5 {
+            let dictionary = payload_compressor.train_dictionary().unwrap().unwrap();
+            assert_eq!(dictionary.dictionary_id, expected_dictionary_id);
+            payload_compressor.install_dictionary(dictionary);
+        }
+    }
+
+    #[test]
+    fn test_insert_dictionary_eviction() {
+        // Create a payload decompressor
+        let mut payload_decompressor = PayloadDictionaryDecompressor::new();
+
+        // Insert several dictionaries
+        let num_dictionaries = 10;
+        for dictionary_id in 0..num_dictionaries {
+            payload_decompressor.insert_dictionary(CompressionDictionary {
+                dictionary_id,
+                dictionary_bytes: vec![],
+            });
+        }
+
+        // Verify that only the most recent dictionaries are held
+        assert_eq!(
+            payload_decompressor.get_num_dictionaries(),
+            MAX_NUM_HELD_DICTIONARIES
+        );
+        for dictionary_id in 0..num_dictionaries {
+            let expected_held =
+                dictionary_id >= num_dictionaries - MAX_NUM_HELD_DICTIONARIES as u64;
+            assert_eq!(
+                payload_decompressor
+                    .dictionaries
+                    .contains_key(&dictionary_id),
+                expected_held
+            );
+        }
+
+        // Insert a dictionary with a lower ID and verify the stale dictionaries are cleared
+        payload_decompressor.insert_dictionary(CompressionDictionary {
+            dictionary_id: 0,
+            dictionary_bytes: vec![],
+        });
+        assert_eq!(payload_decompressor.get_num_dictionaries(), 1);
+        assert!(payload_decompressor.dictionaries.contains_key(&0));
+    }
+
+    /// Creates an observer config with a small dictionary size (for the tests)
+    fn create_test_config() -> ConsensusObserverConfig {
+        ConsensusObserverConfig {
+            max_payload_dictionary_size_bytes: 4 * 1024, // 4 KB
+            ..ConsensusObserverConfig::default()
+        }
+    }
+
+    /// Creates a block payload (with several transactions) for the given round
+    fn create_block_payload(round: u64) -> BlockPayload {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let transactions = (0..10)
+            .map(|sequence_number| {
+                get_test_signed_txn(
+                    AccountAddress::random(),
+                    sequence_number,
+                    &private_key,
+                    private_key.public_key(),
+                    None,
+                )
+            })
+            .collect();
+        BlockPayload {
+            block: BlockInfo::random_with_epoch(0, round),
+            transactions,
+            limit: Some(round),
+        }
+    }
+}
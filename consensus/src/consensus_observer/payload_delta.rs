@@ -0,0 +1,456 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    error::Error,
+    network_message::{BlockPayload, DeltaBlockPayload, DeltaTransaction, PayloadAcknowledgement},
+};
+use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_types::{block_info::Round, transaction::SignedTransaction};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+// The maximum number of sent (but unacknowledged) payloads tracked per subscriber
+const MAX_NUM_UNACKNOWLEDGED_PAYLOADS: usize = 1000;
+
+/// The publisher-side delta encoder for block payloads. The encoder tracks
+/// the transactions that each (delta encoding) subscriber has acknowledged
+/// holding, and replaces those transactions with their digests when payloads
+/// are retransmitted (or overlap previously sent payloads).
+pub struct DeltaPayloadEncoder {
+    // The maximum number of acknowledged transaction digests tracked per subscriber
+    max_num_acknowledged_digests: usize,
+
+    // The delta encoding state of each subscriber
+    subscriber_states: Mutex<HashMap<PeerNetworkId, SubscriberDeltaState>>,
+}
+
+impl DeltaPayloadEncoder {
+    pub fn new(consensus_observer_config: &ConsensusObserverConfig) -> Self {
+        // Only track half the observer cache size (to tolerate the
+        // unacknowledged transactions that are also cached by the observer).
+        let max_num_acknowledged_digests =
+            (consensus_observer_config.max_num_delta_cached_transactions / 2) as usize;
+        Self {
+            max_num_acknowledged_digests,
+            subscriber_states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Processes the payload acknowledgement from the given subscriber. The
+    /// transactions in the acknowledged payloads may then be sent as digests.
+    pub fn acknowledge_payloads(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        payload_acknowledgement: &PayloadAcknowledgement,
+    ) {
+        if let Some(subscriber_state) = self.subscriber_states.lock().get_mut(peer_network_id) {
+            for payload_round in &payload_acknowledgement.payload_rounds {
+                subscriber_state.acknowledge_payload(payload_round);
+            }
+        }
+    }
+
+    /// Adds the given peer as a delta encoding subscriber (resetting any previous state)
+    pub fn add_subscriber(&self, peer_network_id: &PeerNetworkId) {
+        self.subscriber_states.lock().insert(
+            *peer_network_id,
+            SubscriberDeltaState::new(self.max_num_acknowledged_digests),
+        );
+    }
+
+    /// Delta encodes the given block payload for the subscriber. If the peer
+    /// isn't a delta encoding subscriber, or it hasn't acknowledged holding any
+    /// of the transactions, None is returned (and the full payload should be
+    /// sent). In all cases, the payload transactions are tracked until the
+    /// subscriber acknowledges the payload.
+    pub fn encode_payload(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        block_payload: &BlockPayload,
+    ) -> Option<DeltaBlockPayload> {
+        // Get the state of the subscriber
+        let mut subscriber_states = self.subscriber_states.lock();
+        let subscriber_state = subscriber_states.get_mut(peer_network_id)?;
+
+        // Calculate the transaction digests and track the payload as unacknowledged
+        let transaction_digests: Vec<_> = block_payload
+            .transactions
+            .iter()
+            .map(|transaction| transaction.committed_hash())
+            .collect();
+        let payload_round = (block_payload.block.epoch(), block_payload.block.round());
+        subscriber_state.add_unacknowledged_payload(payload_round, transaction_digests.clone());
+
+        // If the subscriber doesn't hold any of the transactions, send the full payload
+        if !transaction_digests
+            .iter()
+            .any(|digest| subscriber_state.acknowledged_digests.contains(digest))
+        {
+            return None;
+        }
+
+        // Otherwise, replace the acknowledged transactions with their digests
+        let transactions = block_payload
+            .transactions
+            .iter()
+            .zip(transaction_digests)
+            .map(|(transaction, digest)| {
+                if subscriber_state.acknowledged_digests.contains(&digest) {
+                    DeltaTransaction::Digest(digest)
+                } else {
+                    DeltaTransaction::Transaction(transaction.clone())
+                }
+            })
+            .collect();
+        Some(DeltaBlockPayload {
+            block: block_payload.block.clone(),
+            transactions,
+            limit: block_payload.limit,
+        })
+    }
+
+    /// Returns true iff the given peer is a delta encoding subscriber
+    pub fn is_delta_subscriber(&self, peer_network_id: &PeerNetworkId) -> bool {
+        self.subscriber_states.lock().contains_key(peer_network_id)
+    }
+
+    /// Removes the given peer as a delta encoding subscriber
+    pub fn remove_subscriber(&self, peer_network_id: &PeerNetworkId) {
+        self.subscriber_states.lock().remove(peer_network_id);
+    }
+}
+
+/// The delta encoding state of a single subscriber
+struct SubscriberDeltaState {
+    // The transaction digests of the payloads sent to the subscriber (but not yet acknowledged)
+    unacknowledged_payloads: BTreeMap<(u64, Round), Vec<HashValue>>,
+
+    // The transaction digests that the subscriber has acknowledged holding
+    acknowledged_digests: BoundedDigestSet,
+}
+
+impl SubscriberDeltaState {
+    fn new(max_num_acknowledged_digests: usize) -> Self {
+        Self {
+            unacknowledged_payloads: BTreeMap::new(),
+            acknowledged_digests: BoundedDigestSet::new(max_num_acknowledged_digests),
+        }
+    }
+
+    /// Marks the payload for the given round as acknowledged (if it was sent)
+    fn acknowledge_payload(&mut self, payload_round: &(u64, Round)) {
+        if let Some(transaction_digests) = self.unacknowledged_payloads.remove(payload_round) {
+            for digest in transaction_digests {
+                self.acknowledged_digests.insert(digest);
+            }
+        }
+    }
+
+    /// Tracks the payload for the given round as unacknowledged. If too many
+    /// payloads are unacknowledged, the payloads for the oldest rounds are dropped.
+    fn add_unacknowledged_payload(
+        &mut self,
+        payload_round: (u64, Round),
+        transaction_digests: Vec<HashValue>,
+    ) {
+        self.unacknowledged_payloads
+            .insert(payload_round, transaction_digests);
+        while self.unacknowledged_payloads.len() > MAX_NUM_UNACKNOWLEDGED_PAYLOADS {
+            self.unacknowledged_payloads.pop_first();
+        }
+    }
+}
+
+/// A set of transaction digests with a maximum size. If the
+/// set is full, the oldest inserted digests are evicted first.
+struct BoundedDigestSet {
+    max_num_digests: usize,
+    digests: HashSet<HashValue>,
+    insertion_order: VecDeque<HashValue>,
+}
+
+impl BoundedDigestSet {
+    fn new(max_num_digests: usize) -> Self {
+        Self {
+            max_num_digests,
+            digests: HashSet::new(),
+            insertion_order: VecDeque::new(),
+        }
+    }
+
+    /// Returns true iff the set contains the given digest
+    fn contains(&self, digest: &HashValue) -> bool {
+        self.digests.contains(digest)
+    }
+
+    /// Inserts the given digest (evicting the oldest digests if the set is full)
+    fn insert(&mut self, digest: HashValue) {
+        if !self.digests.insert(digest) {
+            return; // The digest is already in the set
+        }
+        self.insertion_order.push_back(digest);
+        while self.insertion_order.len() > self.max_num_digests {
+            if let Some(evicted_digest) = self.insertion_order.pop_front() {
+                self.digests.remove(&evicted_digest);
+            }
+        }
+    }
+}
+
+/// The observer-side decoder for delta encoded block payloads. The decoder
+/// caches the most recently received transactions (to reconstruct the full
+/// payloads), and tracks the payloads that should be acknowledged to the
+/// publisher (so that it knows which transactions are held locally).
+pub struct DeltaPayloadDecoder {
+    // The maximum number of cached transactions
+    max_num_cached_transactions: usize,
+
+    // The most recently received transactions (indexed by digest)
+    cached_transactions: HashMap<HashValue, SignedTransaction>,
+
+    // The digests of the cached transactions (in insertion order)
+    insertion_order: VecDeque<HashValue>,
+
+    // The payload rounds that are yet to be acknowledged to the publisher
+    pending_acknowledgements: Vec<(u64, Round)>,
+}
+
+impl DeltaPayloadDecoder {
+    pub fn new(consensus_observer_config: &ConsensusObserverConfig) -> Self {
+        Self {
+            max_num_cached_transactions: consensus_observer_config.max_num_delta_cached_transactions
+                as usize,
+            cached_transactions: HashMap::new(),
+            insertion_order: VecDeque::new(),
+            pending_acknowledgements: vec![],
+        }
+    }
+
+    /// Reconstructs the full block payload from the given delta encoded payload.
+    /// If any of the referenced transactions are not cached, an error is returned.
+    pub fn decode_payload(
+        &self,
+        delta_block_payload: DeltaBlockPayload,
+    ) -> Result<BlockPayload, Error> {
+        let DeltaBlockPayload {
+            block,
+            transactions,
+            limit,
+        } = delta_block_payload;
+
+        // Reconstruct the transactions from the cache
+        let transactions = transactions
+            .into_iter()
+            .map(|delta_transaction| match delta_transaction {
+                DeltaTransaction::Digest(digest) => self
+                    .cached_transactions
+                    .get(&digest)
+                    .cloned()
+                    .ok_or_else(|| {
+                        Error::PayloadDeltaError(format!(
+                            "Missing the cached transaction: {}, for block payload: {}!",
+                            digest, block
+                        ))
+                    }),
+                DeltaTransaction::Transaction(transaction) => Ok(transaction),
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(BlockPayload {
+            block,
+            transactions,
+            limit,
+        })
+    }
+
+    /// Returns the number of cached transactions
+    pub fn get_num_cached_transactions(&self) -> usize {
+        self.cached_transactions.len()
+    }
+
+    /// Records the given (full) block payload. The transactions are cached
+    /// and the payload will be acknowledged to the publisher.
+    pub fn record_payload(&mut self, block_payload: &BlockPayload) {
+        // Cache the payload transactions
+        for transaction in &block_payload.transactions {
+            let digest = transaction.committed_hash();
+            if self
+                .cached_transactions
+                .insert(digest, transaction.clone())
+                .is_none()
+            {
+                self.insertion_order.push_back(digest);
+            }
+        }
+
+        // Evict the oldest transactions (if the cache is full)
+        while self.insertion_order.len() > self.max_num_cached_transactions {
+            if let Some(evicted_digest) = self.insertion_order.pop_front() {
+                self.cached_transactions.remove(&evicted_digest);
+            }
+        }
+
+        // Queue the payload acknowledgement
+        self.pending_acknowledgements
+            .push((block_payload.block.epoch(), block_payload.block.round()));
+    }
+
+    /// Takes the pending payload acknowledgement (if there are any acknowledged payloads)
+    pub fn take_payload_acknowledgement(&mut self) -> Option<PayloadAcknowledgement> {
+        if self.pending_acknowledgements.is_empty() {
+            return None;
+        }
+        Some(PayloadAcknowledgement {
+            payload_rounds: std::mem::take(&mut self.pending_acknowledgements),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_config::network_id::NetworkId;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+    use aptos_types::{
+        account_address::AccountAddress, block_info::BlockInfo,
+        test_helpers::transaction_test_helpers::get_test_signed_txn, PeerId,
+    };
+
+    #[test]
+    fn test_encode_and_decode_payload() {
+        // Create a delta encoder and decoder
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let delta_payload_encoder = DeltaPayloadEncoder::new(&consensus_observer_config);
+        let mut delta_payload_decoder = DeltaPayloadDecoder::new(&consensus_observer_config);
+
+        // Verify that payloads aren't encoded for peers that aren't delta subscribers
+        let peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        let transactions = create_transactions(10);
+        let block_payload = create_block_payload(0, transactions[0..5].to_vec());
+        assert!(delta_payload_encoder
+            .encode_payload(&peer_network_id, &block_payload)
+            .is_none());
+
+        // Add the delta subscriber and verify the first payload is sent in full
+        delta_payload_encoder.add_subscriber(&peer_network_id);
+        assert!(delta_payload_encoder.is_delta_subscriber(&peer_network_id));
+        assert!(delta_payload_encoder
+            .encode_payload(&peer_network_id, &block_payload)
+            .is_none());
+
+        // Verify that an overlapping payload is sent in full (the first payload is unacknowledged)
+        let overlapping_block_payload = create_block_payload(1, transactions[3..10].to_vec());
+        assert!(delta_payload_encoder
+            .encode_payload(&peer_network_id, &overlapping_block_payload)
+            .is_none());
+
+        // Record and acknowledge the first payload
+        delta_payload_decoder.record_payload(&block_payload);
+        let payload_acknowledgement = delta_payload_decoder
+            .take_payload_acknowledgement()
+            .unwrap();
+        assert_eq!(payload_acknowledgement.payload_rounds, vec![(0, 0)]);
+        assert!(delta_payload_decoder
+            .take_payload_acknowledgement()
+            .is_none());
+        delta_payload_encoder.acknowledge_payloads(&peer_network_id, &payload_acknowledgement);
+
+        // Verify that the overlapping payload is now delta encoded
+        let delta_block_payload = delta_payload_encoder
+            .encode_payload(&peer_network_id, &overlapping_block_payload)
+            .unwrap();
+        let num_digests = delta_block_payload
+            .transactions
+            .iter()
+            .filter(|transaction| matches!(transaction, DeltaTransaction::Digest(_)))
+            .count();
+        assert_eq!(num_digests, 2);
+
+        // Verify that the observer reconstructs the full payload
+        let decoded_block_payload = delta_payload_decoder
+            .decode_payload(delta_block_payload)
+            .unwrap();
+        assert_eq!(decoded_block_payload, overlapping_block_payload);
+
+        // Remove the subscriber and verify payloads are no longer encoded
+        delta_payload_encoder.remove_subscriber(&peer_network_id);
+        assert!(delta_payload_encoder
+            .encode_payload(&peer_network_id, &overlapping_block_payload)
+            .is_none());
+    }
+
+    #[test]
+    fn test_decode_missing_transactions() {
+        // Create a delta decoder with a small cache
+        let consensus_observer_config = ConsensusObserverConfig {
+            max_num_delta_cached_transactions: 5,
+            ..ConsensusObserverConfig::default()
+        };
+        let mut delta_payload_decoder = DeltaPayloadDecoder::new(&consensus_observer_config);
+
+        // Record several payloads and verify that only the most recent transactions are cached
+        let transactions = create_transactions(10);
+        delta_payload_decoder.record_payload(&create_block_payload(0, transactions[0..5].to_vec()));
+        delta_payload_decoder
+            .record_payload(&create_block_payload(1, transactions[5..10].to_vec()));
+        assert_eq!(delta_payload_decoder.get_num_cached_transactions(), 5);
+
+        // Verify that a payload referencing an evicted transaction can't be decoded
+        let delta_block_payload = DeltaBlockPayload {
+            block: BlockInfo::random_with_epoch(0, 2),
+            transactions: vec![
+                DeltaTransaction::Digest(transactions[0].committed_hash()),
+                DeltaTransaction::Transaction(transactions[1].clone()),
+            ],
+            limit: None,
+        };
+        assert!(matches!(
+            delta_payload_decoder.decode_payload(delta_block_payload),
+            Err(Error::PayloadDeltaError(_))
+        ));
+
+        // Verify that a payload referencing a cached transaction is decoded
+        let delta_block_payload = DeltaBlockPayload {
+            block: BlockInfo::random_with_epoch(0, 3),
+            transactions: vec![
+                DeltaTransaction::Digest(transactions[9].committed_hash()),
+                DeltaTransaction::Transaction(transactions[1].clone()),
+            ],
+            limit: None,
+        };
+        let block_payload = delta_payload_decoder
+            .decode_payload(delta_block_payload)
+            .unwrap();
+        assert_eq!(block_payload.transactions, vec![
+            transactions[9].clone(),
+            transactions[1].clone()
+        ]);
+    }
+
+    /// Creates a block payload with the given round and transactions
+    fn create_block_payload(round: Round, transactions: Vec<SignedTransaction>) -> BlockPayload {
+        BlockPayload {
+            block: BlockInfo::random_with_epoch(0, round),
+            transactions,
+            limit: None,
+        }
+    }
+
+    /// Creates the specified number of (unique) signed transactions
+    fn create_transactions(num_transactions: u64) -> Vec<SignedTransaction> {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        (0..num_transactions)
+            .map(|sequence_number| {
+                get_test_signed_txn(
+                    AccountAddress::random(),
+                    sequence_number,
+                    &private_key,
+                    private_key.public_key(),
+                    None,
+                )
+            })
+            .collect()
+    }
+}
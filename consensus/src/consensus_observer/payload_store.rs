@@ -1,14 +1,26 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::consensus_observer::logging::{LogEntry, LogSchema};
-use aptos_consensus_types::pipelined_block::PipelinedBlock;
+use crate::consensus_observer::{
+    logging::{LogEntry, LogSchema},
+    metrics,
+    network_message::{BlockPayload, OrderedBlock},
+    payload_store_db::ObserverPayloadDb,
+    payload_store_sizing::PayloadStoreSizer,
+};
+use aptos_config::config::ConsensusObserverConfig;
+use aptos_consensus_types::{common::Round, pipelined_block::PipelinedBlock};
 use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
-use aptos_logger::error;
+use aptos_logger::{debug, error};
+use aptos_time_service::TimeService;
 use aptos_types::{block_info::BlockInfo, transaction::SignedTransaction};
+use dashmap::{mapref::entry::Entry, DashMap};
+use fail::fail_point;
+use itertools::Either;
+use serde::Serialize;
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{BTreeMap, HashMap},
     mem,
     sync::Arc,
 };
@@ -36,48 +48,129 @@ pub enum BlockPayloadStatus {
     Available(BlockTransactionPayload),
 }
 
-/// A simple struct to store the block payloads of ordered and committed blocks
-#[derive(Clone)]
-pub struct BlockPayloadStore {
+/// A pluggable storage backend for block payloads. This allows different
+/// storage implementations (e.g., in-memory, disk-backed or sharded) to
+/// be used by the payload store and payload manager, without modifying
+/// any of the call sites.
+pub trait PayloadStoreBackend: Send + Sync {
+    /// Returns true iff the payloads for all the given block IDs are available
+    fn all_payloads_exist(&self, block_ids: &[HashValue]) -> bool;
+
+    /// Returns the payload for the given block ID (if it is available).
+    /// Otherwise, the payload is marked as requested, and a receiver is
+    /// returned that will be notified once the payload is inserted.
+    fn get_or_request_payload(
+        &self,
+        block_id: HashValue,
+    ) -> Either<BlockTransactionPayload, oneshot::Receiver<BlockTransactionPayload>>;
+
+    /// Returns the number of payloads (available or requested) in the backend
+    fn get_num_payloads(&self) -> usize;
+
+    /// Returns the block IDs of all available payloads in the backend
+    fn get_available_payload_ids(&self) -> Vec<HashValue>;
+
+    /// Inserts the payload for the given block ID. If the payload
+    /// was previously requested, the listener is notified.
+    fn insert_payload(
+        &self,
+        block_id: HashValue,
+        block_transaction_payload: BlockTransactionPayload,
+    );
+
+    /// Inserts the payloads for the given block IDs (as a single batch).
+    /// If any payloads were previously requested, the listeners are notified.
+    fn insert_payloads(
+        &self,
+        block_transaction_payloads: Vec<(HashValue, BlockTransactionPayload)>,
+    );
+
+    /// Removes the payloads for the given block IDs (as a single batch)
+    fn remove_payloads(&self, block_ids: &[HashValue]);
+
+    /// Garbage collects the backend by removing all payloads
+    /// whose block IDs don't satisfy the given predicate.
+    fn retain_payloads(&self, should_retain: &dyn Fn(&HashValue) -> bool);
+}
+
+/// The default (in-memory) payload store backend. The payloads are held
+/// in a sharded concurrent map, so that payload manager reads (during block
+/// execution) don't contend with observer-side insertions and removals of
+/// payloads for other blocks. Note: operations that span multiple block IDs
+/// (e.g., batch insertions) are not atomic across the entire map.
+pub struct InMemoryPayloadStore {
     // Block transaction payloads map the block ID to the transaction payloads
     // (the same payloads that the payload manager returns).
-    block_transaction_payloads: Arc<Mutex<HashMap<HashValue, BlockPayloadStatus>>>,
+    block_transaction_payloads: DashMap<HashValue, BlockPayloadStatus>,
 }
 
-impl BlockPayloadStore {
+impl InMemoryPayloadStore {
     pub fn new() -> Self {
         Self {
-            block_transaction_payloads: Arc::new(Mutex::new(HashMap::new())),
+            block_transaction_payloads: DashMap::new(),
         }
     }
+}
 
-    /// Returns true iff all the payloads for the given blocks are available
-    pub fn all_payloads_exist(&self, blocks: &[Arc<PipelinedBlock>]) -> bool {
-        let block_transaction_payloads = self.block_transaction_payloads.lock();
-        blocks.iter().all(|block| {
-            matches!(
-                block_transaction_payloads.get(&block.id()),
-                Some(BlockPayloadStatus::Available(_))
-            )
+impl Default for InMemoryPayloadStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PayloadStoreBackend for InMemoryPayloadStore {
+    fn all_payloads_exist(&self, block_ids: &[HashValue]) -> bool {
+        block_ids.iter().all(|block_id| {
+            self.block_transaction_payloads
+                .get(block_id)
+                .is_some_and(|status| matches!(status.value(), BlockPayloadStatus::Available(_)))
         })
     }
 
-    /// Returns a reference to the block transaction payloads
-    pub fn get_block_payloads(&self) -> Arc<Mutex<HashMap<HashValue, BlockPayloadStatus>>> {
-        self.block_transaction_payloads.clone()
+    fn get_or_request_payload(
+        &self,
+        block_id: HashValue,
+    ) -> Either<BlockTransactionPayload, oneshot::Receiver<BlockTransactionPayload>> {
+        match self.block_transaction_payloads.entry(block_id) {
+            Entry::Occupied(mut entry) => match entry.get_mut() {
+                BlockPayloadStatus::Available(block_transaction_payload) => {
+                    Either::Left(block_transaction_payload.clone())
+                },
+                BlockPayloadStatus::Requested(payload_sender) => {
+                    // Replace the existing listener with the new one
+                    let (new_payload_sender, payload_receiver) = oneshot::channel();
+                    *payload_sender = new_payload_sender;
+                    Either::Right(payload_receiver)
+                },
+            },
+            Entry::Vacant(entry) => {
+                let (payload_sender, payload_receiver) = oneshot::channel();
+                entry.insert(BlockPayloadStatus::Requested(payload_sender));
+                Either::Right(payload_receiver)
+            },
+        }
     }
 
-    /// Inserts the given block payload data into the payload store
-    pub fn insert_block_payload(
-        &mut self,
-        block: BlockInfo,
-        transactions: Vec<SignedTransaction>,
-        limit: Option<u64>,
-    ) {
-        let mut block_transaction_payloads = self.block_transaction_payloads.lock();
-        let block_transaction_payload = BlockTransactionPayload::new(transactions, limit);
+    fn get_num_payloads(&self) -> usize {
+        self.block_transaction_payloads.len()
+    }
 
-        match block_transaction_payloads.entry(block.id()) {
+    fn get_available_payload_ids(&self) -> Vec<HashValue> {
+        self.block_transaction_payloads
+            .iter()
+            .filter_map(|entry| match entry.value() {
+                BlockPayloadStatus::Available(_) => Some(*entry.key()),
+                BlockPayloadStatus::Requested(_) => None,
+            })
+            .collect()
+    }
+
+    fn insert_payload(
+        &self,
+        block_id: HashValue,
+        block_transaction_payload: BlockTransactionPayload,
+    ) {
+        match self.block_transaction_payloads.entry(block_id) {
             Entry::Occupied(mut entry) => {
                 // Replace the data status with the new block payload
                 let mut status = BlockPayloadStatus::Available(block_transaction_payload.clone());
@@ -98,21 +191,543 @@ impl BlockPayloadStore {
         }
     }
 
+    fn insert_payloads(
+        &self,
+        block_transaction_payloads: Vec<(HashValue, BlockTransactionPayload)>,
+    ) {
+        // Each insertion only locks the shard of the block ID
+        for (block_id, block_transaction_payload) in block_transaction_payloads {
+            self.insert_payload(block_id, block_transaction_payload);
+        }
+    }
+
+    fn remove_payloads(&self, block_ids: &[HashValue]) {
+        for block_id in block_ids {
+            self.block_transaction_payloads.remove(block_id);
+        }
+    }
+
+    fn retain_payloads(&self, should_retain: &dyn Fn(&HashValue) -> bool) {
+        self.block_transaction_payloads
+            .retain(|block_id, _| should_retain(block_id));
+    }
+}
+
+/// The index of the payloads inserted into the payload store (by epoch
+/// and round), along with the number and total size of the payloads.
+#[derive(Default)]
+struct PayloadIndex {
+    // The block IDs and payload sizes (in bytes), indexed by epoch and round
+    payload_sizes: BTreeMap<(u64, Round), HashMap<HashValue, u64>>,
+
+    // The number of indexed payloads
+    num_payloads: usize,
+
+    // The total size (in bytes) of the indexed payloads
+    total_size_bytes: u64,
+
+    // The epoch and round of the highest commit (used to reject stale payloads)
+    highest_committed_block: Option<(u64, Round)>,
+}
+
+impl PayloadIndex {
+    /// Evicts the payloads with the highest epoch and round (i.e., the payloads
+    /// furthest ahead of the root), and returns the evicted blocks.
+    fn evict_highest_round(&mut self) -> Vec<(u64, Round, HashValue)> {
+        let ((epoch, round), payload_sizes) = match self.payload_sizes.pop_last() {
+            Some(highest_round_payloads) => highest_round_payloads,
+            None => return vec![],
+        };
+        payload_sizes
+            .into_iter()
+            .map(|(block_id, payload_size_bytes)| {
+                self.num_payloads -= 1;
+                self.total_size_bytes = self.total_size_bytes.saturating_sub(payload_size_bytes);
+                (epoch, round, block_id)
+            })
+            .collect()
+    }
+
+    /// Returns the label of the limit exceeded by the index (if any)
+    fn get_exceeded_limit(
+        &self,
+        max_num_payloads: usize,
+        max_total_size_bytes: u64,
+    ) -> Option<&'static str> {
+        if self.num_payloads > max_num_payloads {
+            Some(metrics::PAYLOAD_STORE_ENTRIES_LABEL)
+        } else if self.total_size_bytes > max_total_size_bytes {
+            Some(metrics::PAYLOAD_STORE_BYTES_LABEL)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true iff the given block is at (or below) the highest commit
+    fn is_stale(&self, block: &BlockInfo) -> bool {
+        self.highest_committed_block
+            .map_or(false, |highest_commit| {
+                (block.epoch(), block.round()) <= highest_commit
+            })
+    }
+
+    /// Indexes the payload of the given block (replacing any existing entry)
+    fn insert_payload(&mut self, block: &BlockInfo, payload_size_bytes: u64) {
+        let round_payload_sizes = self
+            .payload_sizes
+            .entry((block.epoch(), block.round()))
+            .or_default();
+        match round_payload_sizes.insert(block.id(), payload_size_bytes) {
+            Some(previous_size_bytes) => {
+                self.total_size_bytes = self.total_size_bytes.saturating_sub(previous_size_bytes);
+            },
+            None => self.num_payloads += 1,
+        }
+        self.total_size_bytes = self.total_size_bytes.saturating_add(payload_size_bytes);
+    }
+
+    /// Removes the payload of the given block from the index (if it exists)
+    fn remove_payload(&mut self, epoch: u64, round: Round, block_id: &HashValue) {
+        let round_payload_sizes = match self.payload_sizes.get_mut(&(epoch, round)) {
+            Some(round_payload_sizes) => round_payload_sizes,
+            None => return,
+        };
+        if let Some(payload_size_bytes) = round_payload_sizes.remove(block_id) {
+            self.num_payloads -= 1;
+            self.total_size_bytes = self.total_size_bytes.saturating_sub(payload_size_bytes);
+        }
+        if round_payload_sizes.is_empty() {
+            self.payload_sizes.remove(&(epoch, round));
+        }
+    }
+
+    /// Removes the payloads up to (and including) the given epoch and
+    /// round from the index, and returns the removed block IDs.
+    fn remove_payloads_up_to(&mut self, epoch: u64, round: Round) -> Vec<HashValue> {
+        if self
+            .highest_committed_block
+            .map_or(true, |highest_commit| highest_commit < (epoch, round))
+        {
+            self.highest_committed_block = Some((epoch, round));
+        }
+
+        let retained_payload_sizes = self
+            .payload_sizes
+            .split_off(&(epoch, round.saturating_add(1)));
+        let removed_payload_sizes = mem::replace(&mut self.payload_sizes, retained_payload_sizes);
+
+        let mut removed_block_ids = vec![];
+        for (block_id, payload_size_bytes) in removed_payload_sizes.into_values().flatten() {
+            self.num_payloads -= 1;
+            self.total_size_bytes = self.total_size_bytes.saturating_sub(payload_size_bytes);
+            removed_block_ids.push(block_id);
+        }
+        removed_block_ids
+    }
+}
+
+/// A summary of the payload store occupancy (relative to its limits)
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PayloadStoreOccupancy {
+    pub num_payloads: u64,
+    pub total_size_bytes: u64,
+    pub max_num_payloads: u64,
+    pub max_total_size_bytes: u64,
+    pub target_capacity_bytes: u64,
+}
+
+/// A simple struct to store the block payloads of ordered and committed blocks.
+/// The store is bounded by a maximum number of payloads and total payload size.
+#[derive(Clone)]
+pub struct BlockPayloadStore {
+    // The storage backend for the block transaction payloads
+    // (shared with the payload manager).
+    payload_store_backend: Arc<dyn PayloadStoreBackend>,
+
+    // The sizer that auto-tunes the target capacity of the store
+    payload_store_sizer: Arc<Mutex<PayloadStoreSizer>>,
+
+    // The index of the inserted payloads (by epoch and round). This is used to
+    // prune the payloads of blocks that are never ordered (e.g., the prefetched
+    // payloads of forked proposals), and to enforce the limits of the store.
+    payload_index: Arc<Mutex<PayloadIndex>>,
+
+    // The maximum number of payloads (and total payload size) of the store
+    max_num_payloads: usize,
+    max_total_size_bytes: u64,
+
+    // The persistent storage for the inserted payloads (if enabled). This
+    // allows the buffered payloads to be restored after a node restart.
+    payload_store_db: Option<Arc<ObserverPayloadDb>>,
+}
+
+impl BlockPayloadStore {
+    pub fn new(
+        consensus_observer_config: ConsensusObserverConfig,
+        time_service: TimeService,
+    ) -> Self {
+        Self::new_with_backend(
+            consensus_observer_config,
+            time_service,
+            Arc::new(InMemoryPayloadStore::new()),
+        )
+    }
+
+    /// Creates a new payload store using the given storage backend
+    pub fn new_with_backend(
+        consensus_observer_config: ConsensusObserverConfig,
+        time_service: TimeService,
+        payload_store_backend: Arc<dyn PayloadStoreBackend>,
+    ) -> Self {
+        let payload_store_sizer = PayloadStoreSizer::new(&consensus_observer_config, time_service);
+        Self {
+            payload_store_backend,
+            payload_store_sizer: Arc::new(Mutex::new(payload_store_sizer)),
+            payload_index: Arc::new(Mutex::new(PayloadIndex::default())),
+            max_num_payloads: consensus_observer_config.max_num_payload_store_entries as usize,
+            max_total_size_bytes: consensus_observer_config.max_payload_store_memory_bytes,
+            payload_store_db: None,
+        }
+    }
+
+    /// Returns true iff all the payloads for the given blocks are available
+    pub fn all_payloads_exist(&self, blocks: &[Arc<PipelinedBlock>]) -> bool {
+        self.payload_store_backend
+            .all_payloads_exist(&get_block_ids(blocks))
+    }
+
+    /// Returns the payload for the given block (if it is available)
+    pub fn get_block_payload(&self, block: &PipelinedBlock) -> Option<BlockPayload> {
+        // Ensure the payload is available (to avoid marking it as requested)
+        let block_id = block.id();
+        if !self.payload_store_backend.all_payloads_exist(&[block_id]) {
+            return None;
+        }
+
+        // Get the block payload
+        match self.payload_store_backend.get_or_request_payload(block_id) {
+            Either::Left(block_transaction_payload) => Some(BlockPayload {
+                block: block.block_info(),
+                transactions: block_transaction_payload.transactions,
+                limit: block_transaction_payload.limit,
+            }),
+            Either::Right(_) => None, // The payload was removed concurrently
+        }
+    }
+
+    /// Returns the current occupancy of the store (and its limits)
+    pub fn get_occupancy(&self) -> PayloadStoreOccupancy {
+        let payload_index = self.payload_index.lock();
+        PayloadStoreOccupancy {
+            num_payloads: payload_index.num_payloads as u64,
+            total_size_bytes: payload_index.total_size_bytes,
+            max_num_payloads: self.max_num_payloads as u64,
+            max_total_size_bytes: self.max_total_size_bytes,
+            target_capacity_bytes: self.get_target_capacity_bytes(),
+        }
+    }
+
+    /// Returns a reference to the payload store backend
+    pub fn get_payload_store_backend(&self) -> Arc<dyn PayloadStoreBackend> {
+        self.payload_store_backend.clone()
+    }
+
+    /// Returns the most recently computed target capacity (in bytes) of the store
+    pub fn get_target_capacity_bytes(&self) -> u64 {
+        self.payload_store_sizer.lock().get_target_capacity_bytes()
+    }
+
+    /// Inserts the given block payload data into the payload store.
+    /// The size of the payload is also recorded (to auto-tune the store).
+    /// Stale and duplicate payloads are rejected (see `should_reject_payload()`).
+    /// If the store exceeds its limits, payloads are evicted, and the
+    /// evicted blocks (i.e., epoch, round and block ID) are returned.
+    pub fn insert_block_payload(
+        &mut self,
+        block: BlockInfo,
+        transactions: Vec<SignedTransaction>,
+        limit: Option<u64>,
+    ) -> Vec<(u64, Round, HashValue)> {
+        fail_point!("consensus_observer::insert_block_payload", |_| vec![]);
+
+        // Reject the payload if it is stale or a duplicate
+        if self.should_reject_payload(&block) {
+            return vec![];
+        }
+
+        let payload_size_bytes = get_payload_size_bytes(&transactions);
+        self.payload_store_sizer
+            .lock()
+            .record_payload(payload_size_bytes);
+        self.payload_index
+            .lock()
+            .insert_payload(&block, payload_size_bytes);
+
+        // Persist the block payload (if the persistent storage is enabled)
+        if let Some(payload_store_db) = &self.payload_store_db {
+            let block_payload = BlockPayload {
+                block: block.clone(),
+                transactions: transactions.clone(),
+                limit,
+            };
+            save_payloads_to_db(payload_store_db, &[block_payload]);
+        }
+
+        let block_transaction_payload = BlockTransactionPayload::new(transactions, limit);
+        self.payload_store_backend
+            .insert_payload(block.id(), block_transaction_payload);
+
+        // Evict payloads if the store exceeds its limits
+        self.evict_payloads_over_limits()
+    }
+
+    /// Inserts the given block payloads into the payload store (as a single
+    /// batch). If the store exceeds its limits, payloads are evicted, and the
+    /// evicted blocks (i.e., epoch, round and block ID) are returned.
+    pub fn insert_block_payloads(
+        &mut self,
+        mut block_payloads: Vec<BlockPayload>,
+    ) -> Vec<(u64, Round, HashValue)> {
+        // Reject any stale or duplicate payloads
+        block_payloads.retain(|block_payload| !self.should_reject_payload(&block_payload.block));
+        if block_payloads.is_empty() {
+            return vec![];
+        }
+
+        self.index_block_payloads(&block_payloads);
+
+        // Persist the block payloads (if the persistent storage is enabled)
+        if let Some(payload_store_db) = &self.payload_store_db {
+            save_payloads_to_db(payload_store_db, &block_payloads);
+        }
+
+        let block_transaction_payloads = block_payloads
+            .into_iter()
+            .map(|block_payload| {
+                let block_transaction_payload =
+                    BlockTransactionPayload::new(block_payload.transactions, block_payload.limit);
+                (block_payload.block.id(), block_transaction_payload)
+            })
+            .collect();
+        self.payload_store_backend
+            .insert_payloads(block_transaction_payloads);
+
+        // Evict payloads if the store exceeds its limits
+        self.evict_payloads_over_limits()
+    }
+
+    /// Returns true iff the payload of the given block should be rejected, i.e.,
+    /// the block is stale (at or below the highest commit), or the payload is
+    /// already available in the store. Note: payloads that have only been
+    /// requested (but are not yet available) are never rejected.
+    fn should_reject_payload(&self, block: &BlockInfo) -> bool {
+        if self.payload_index.lock().is_stale(block) {
+            debug!(LogSchema::new(LogEntry::ConsensusObserver)
+                .message(&format!("Ignoring stale block payload: {}", block)));
+            return true;
+        }
+        self.payload_store_backend.all_payloads_exist(&[block.id()])
+    }
+
+    /// Evicts the payloads with the highest rounds (i.e., the payloads furthest
+    /// ahead of the root, which are executed last) until the store is within
+    /// its limits. Returns the evicted blocks (i.e., epoch, round and block ID).
+    fn evict_payloads_over_limits(&self) -> Vec<(u64, Round, HashValue)> {
+        // Evict the payloads from the index
+        let mut evicted_payloads = vec![];
+        {
+            let mut payload_index = self.payload_index.lock();
+            while let Some(limit_label) =
+                payload_index.get_exceeded_limit(self.max_num_payloads, self.max_total_size_bytes)
+            {
+                let evicted_round_payloads = payload_index.evict_highest_round();
+                if evicted_round_payloads.is_empty() {
+                    break; // The index is empty
+                }
+                metrics::increment_payload_store_evictions(
+                    limit_label,
+                    evicted_round_payloads.len() as u64,
+                );
+                evicted_payloads.extend(evicted_round_payloads);
+            }
+        }
+        if evicted_payloads.is_empty() {
+            return evicted_payloads;
+        }
+
+        // Remove the evicted payloads from the store (and the persistent storage)
+        let evicted_block_ids: Vec<_> = evicted_payloads
+            .iter()
+            .map(|(_, _, block_id)| *block_id)
+            .collect();
+        self.payload_store_backend
+            .remove_payloads(&evicted_block_ids);
+        if let Some(payload_store_db) = &self.payload_store_db {
+            if let Err(error) = payload_store_db.delete_payloads(&evicted_payloads) {
+                error!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to delete the evicted block payloads! Error: {:?}",
+                        error
+                    ))
+                );
+            }
+        }
+
+        evicted_payloads
+    }
+
+    /// Indexes the given block payloads (by epoch and round)
+    fn index_block_payloads(&self, block_payloads: &[BlockPayload]) {
+        let mut payload_index = self.payload_index.lock();
+        for block_payload in block_payloads {
+            let payload_size_bytes = get_payload_size_bytes(&block_payload.transactions);
+            payload_index.insert_payload(&block_payload.block, payload_size_bytes);
+        }
+    }
+
+    /// Removes the given pipelined blocks from the payload index
+    fn unindex_blocks(&self, blocks: &[Arc<PipelinedBlock>]) {
+        let mut payload_index = self.payload_index.lock();
+        for block in blocks {
+            payload_index.remove_payload(block.epoch(), block.round(), &block.id());
+        }
+    }
+
     /// Removes the given pipelined blocks from the payload store
     pub fn remove_blocks(&self, blocks: &[Arc<PipelinedBlock>]) {
-        let mut block_transaction_payloads = self.block_transaction_payloads.lock();
-        for block in blocks.iter() {
-            block_transaction_payloads.remove(&block.id());
+        self.unindex_blocks(blocks);
+        self.payload_store_backend
+            .remove_payloads(&get_block_ids(blocks));
+    }
+
+    /// Removes the blocks of all the given ordered blocks from the payload
+    /// store (as a single batch). This is typically used to remove a range
+    /// of pending blocks (e.g., all blocks up to a commit).
+    pub fn remove_ordered_blocks(&self, ordered_blocks: &[Arc<OrderedBlock>]) {
+        for ordered_block in ordered_blocks {
+            self.unindex_blocks(ordered_block.blocks());
         }
+        let block_ids: Vec<_> = ordered_blocks
+            .iter()
+            .flat_map(|ordered_block| get_block_ids(ordered_block.blocks()))
+            .collect();
+        self.payload_store_backend.remove_payloads(&block_ids);
+    }
+
+    /// Removes the payloads of all blocks up to (and including) the given
+    /// committed block. This also prunes the payloads of blocks that were
+    /// never ordered (e.g., the prefetched payloads of forked proposals).
+    pub fn remove_payloads_for_commit(&self, commit_info: &BlockInfo) {
+        // Remove the block IDs up to (and including) the committed round
+        let removed_block_ids = self
+            .payload_index
+            .lock()
+            .remove_payloads_up_to(commit_info.epoch(), commit_info.round());
+
+        // Remove the payloads from the store (as a single batch)
+        self.payload_store_backend
+            .remove_payloads(&removed_block_ids);
+
+        // Prune the persisted payloads (if the persistent storage is enabled)
+        if let Some(payload_store_db) = &self.payload_store_db {
+            if let Err(error) = payload_store_db.prune_payloads(commit_info) {
+                error!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to prune the persisted block payloads! Error: {:?}",
+                        error
+                    ))
+                );
+            }
+        }
+    }
+
+    /// Sets the persistent storage for the block payloads, and restores all
+    /// payloads that were persisted before the node restarted. The payloads
+    /// are restored as-is, so callers should prune any stale payloads (e.g.,
+    /// by calling `remove_payloads_for_commit()` with the latest commit).
+    pub fn set_payload_store_db(&mut self, payload_store_db: Arc<ObserverPayloadDb>) {
+        // Restore the persisted payloads
+        match payload_store_db.get_all_payloads() {
+            Ok(block_payloads) => {
+                self.index_block_payloads(&block_payloads);
+
+                let block_transaction_payloads = block_payloads
+                    .into_iter()
+                    .map(|block_payload| {
+                        let block_transaction_payload = BlockTransactionPayload::new(
+                            block_payload.transactions,
+                            block_payload.limit,
+                        );
+                        (block_payload.block.id(), block_transaction_payload)
+                    })
+                    .collect();
+                self.payload_store_backend
+                    .insert_payloads(block_transaction_payloads);
+            },
+            Err(error) => {
+                error!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to restore the persisted block payloads! Error: {:?}",
+                        error
+                    ))
+                );
+            },
+        }
+
+        // Persist all future payloads (and enforce the limits of the store)
+        self.payload_store_db = Some(payload_store_db);
+        self.evict_payloads_over_limits();
+    }
+
+    /// Updates the occupancy metrics of the store (i.e., the number
+    /// of indexed payloads and their total size in bytes).
+    pub fn update_occupancy_metrics(&self) {
+        let payload_index = self.payload_index.lock();
+        metrics::set_gauge_with_label(
+            &metrics::OBSERVER_PAYLOAD_STORE_OCCUPANCY,
+            metrics::PAYLOAD_STORE_ENTRIES_LABEL,
+            payload_index.num_payloads as i64,
+        );
+        metrics::set_gauge_with_label(
+            &metrics::OBSERVER_PAYLOAD_STORE_OCCUPANCY,
+            metrics::PAYLOAD_STORE_BYTES_LABEL,
+            payload_index.total_size_bytes as i64,
+        );
+    }
+
+    /// Recomputes the target capacity of the store (from the block rate and
+    /// average payload size observed over the sizing window), and returns it.
+    pub fn update_target_capacity(&self) -> u64 {
+        self.payload_store_sizer.lock().update_target_capacity()
     }
 }
 
-impl Default for BlockPayloadStore {
-    fn default() -> Self {
-        Self::new()
+/// Persists the given block payloads (any failures are logged, as the
+/// payloads remain available in memory).
+fn save_payloads_to_db(payload_store_db: &ObserverPayloadDb, block_payloads: &[BlockPayload]) {
+    if let Err(error) = payload_store_db.save_payloads(block_payloads) {
+        error!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Failed to persist the block payloads! Error: {:?}",
+                error
+            ))
+        );
     }
 }
 
+/// Returns the size (in bytes) of the given payload transactions
+fn get_payload_size_bytes(transactions: &[SignedTransaction]) -> u64 {
+    transactions
+        .iter()
+        .map(|transaction| transaction.txn_bytes_len() as u64)
+        .sum()
+}
+
+/// Returns the block IDs of the given pipelined blocks
+fn get_block_ids(blocks: &[Arc<PipelinedBlock>]) -> Vec<HashValue> {
+    blocks.iter().map(|block| block.id()).collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -121,12 +736,22 @@ mod test {
         block_data::{BlockData, BlockType},
         quorum_cert::QuorumCert,
     };
-    use aptos_types::{block_info::Round, transaction::Version};
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+    use aptos_temppath::TempPath;
+    use aptos_types::{
+        account_address::AccountAddress,
+        aggregate_signature::AggregateSignature,
+        block_info::Round,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+        test_helpers::transaction_test_helpers::get_test_signed_txn,
+        transaction::Version,
+    };
 
     #[test]
     fn test_all_payloads_exist() {
         // Create a new block payload store
-        let block_payload_store = BlockPayloadStore::new();
+        let block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock());
 
         // Add some blocks to the payload store
         let num_blocks_in_store = 100;
@@ -160,7 +785,8 @@ mod test {
     #[test]
     fn test_all_payloads_exist_requested() {
         // Create a new block payload store
-        let block_payload_store = BlockPayloadStore::new();
+        let block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock());
 
         // Add several blocks to the payload store
         let num_blocks_in_store = 10;
@@ -183,7 +809,8 @@ mod test {
     #[test]
     fn test_insert_block_payload() {
         // Create a new block payload store
-        let mut block_payload_store = BlockPayloadStore::new();
+        let mut block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock());
 
         // Add some blocks to the payload store
         let num_blocks_in_store = 10;
@@ -212,10 +839,148 @@ mod test {
         assert_eq!(block_transaction_payload.limit, Some(0));
     }
 
+    #[test]
+    fn test_insert_block_payloads() {
+        // Create a new block payload store
+        let mut block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock());
+
+        // Add some blocks to the payload store
+        let num_blocks_in_store = 10;
+        let pipelined_blocks =
+            create_and_add_blocks_to_store(block_payload_store.clone(), num_blocks_in_store);
+
+        // Mark the payloads of the first two blocks as requested
+        let payload_receivers: Vec<_> = pipelined_blocks[0..2]
+            .iter()
+            .map(|block| mark_payload_as_requested(block_payload_store.clone(), block.id()))
+            .collect();
+        assert!(!block_payload_store.all_payloads_exist(&pipelined_blocks));
+
+        // Insert the missing block payloads (as a single batch)
+        let block_payloads = pipelined_blocks[0..2]
+            .iter()
+            .map(|block| BlockPayload {
+                block: block.block_info(),
+                transactions: vec![],
+                limit: Some(100),
+            })
+            .collect();
+        block_payload_store.insert_block_payloads(block_payloads);
+
+        // Check that the block payload store now contains all the block payloads
+        assert!(block_payload_store.all_payloads_exist(&pipelined_blocks));
+        assert_eq!(
+            block_payload_store
+                .get_payload_store_backend()
+                .get_num_payloads(),
+            num_blocks_in_store
+        );
+
+        // Check that the payload receivers receive the requested block payloads
+        for payload_receiver in payload_receivers {
+            let block_transaction_payload = payload_receiver.blocking_recv().unwrap();
+            assert_eq!(block_transaction_payload.limit, Some(100));
+        }
+    }
+
+    #[test]
+    fn test_insert_stale_and_duplicate_payloads() {
+        // Create a new block payload store
+        let mut block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock());
+
+        // Add some blocks to the payload store
+        let num_blocks_in_store = 10;
+        let pipelined_blocks =
+            create_and_add_blocks_to_store(block_payload_store.clone(), num_blocks_in_store);
+
+        // Insert a duplicate payload and verify it is rejected (the existing payload is retained)
+        block_payload_store.insert_block_payload(
+            pipelined_blocks[5].block_info(),
+            vec![],
+            Some(1000),
+        );
+        let block_payload = block_payload_store
+            .get_block_payload(&pipelined_blocks[5])
+            .unwrap();
+        assert_eq!(block_payload.limit, Some(5));
+        assert_eq!(
+            block_payload_store.get_occupancy().num_payloads,
+            num_blocks_in_store as u64
+        );
+
+        // Remove the payloads for a commit
+        block_payload_store.remove_payloads_for_commit(&pipelined_blocks[3].block_info());
+        assert_eq!(block_payload_store.get_occupancy().num_payloads, 6);
+
+        // Insert the stale payloads (individually and as a batch) and verify they are rejected
+        for pipelined_block in &pipelined_blocks[0..4] {
+            block_payload_store.insert_block_payload(pipelined_block.block_info(), vec![], None);
+        }
+        let block_payloads = pipelined_blocks[0..4]
+            .iter()
+            .map(|block| BlockPayload {
+                block: block.block_info(),
+                transactions: vec![],
+                limit: None,
+            })
+            .collect();
+        block_payload_store.insert_block_payloads(block_payloads);
+        for pipelined_block in &pipelined_blocks[0..4] {
+            assert!(!block_payload_store.all_payloads_exist(&[pipelined_block.clone()]));
+        }
+        assert_eq!(block_payload_store.get_occupancy().num_payloads, 6);
+        assert_eq!(
+            block_payload_store
+                .get_payload_store_backend()
+                .get_num_payloads(),
+            6
+        );
+    }
+
+    #[test]
+    fn test_remove_ordered_blocks() {
+        // Create a new block payload store
+        let block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock());
+
+        // Add some blocks to the payload store
+        let num_blocks_in_store = 10;
+        let pipelined_blocks =
+            create_and_add_blocks_to_store(block_payload_store.clone(), num_blocks_in_store);
+
+        // Create several ordered blocks for the first 6 blocks
+        let ordered_blocks: Vec<_> = pipelined_blocks[0..6]
+            .chunks(2)
+            .map(|blocks| {
+                Arc::new(OrderedBlock::new(
+                    blocks.to_vec(),
+                    LedgerInfoWithSignatures::new(
+                        LedgerInfo::new(blocks[1].block_info(), HashValue::random()),
+                        AggregateSignature::empty(),
+                    ),
+                ))
+            })
+            .collect();
+
+        // Remove the ordered blocks from the block payload store
+        block_payload_store.remove_ordered_blocks(&ordered_blocks);
+
+        // Check that only the payloads of the remaining blocks exist
+        let payload_store_backend = block_payload_store.get_payload_store_backend();
+        assert_eq!(payload_store_backend.get_num_payloads(), 4);
+        for pipelined_block in &pipelined_blocks[0..6] {
+            assert!(!block_payload_store.all_payloads_exist(&[pipelined_block.clone()]));
+        }
+        assert!(block_payload_store.all_payloads_exist(&pipelined_blocks[6..10]));
+    }
+
     #[test]
     fn test_remove_blocks() {
         // Create a new block payload store
-        let block_payload_store = BlockPayloadStore::new();
+        let block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock());
 
         // Add some blocks to the payload store
         let num_blocks_in_store = 10;
@@ -226,28 +991,315 @@ mod test {
         block_payload_store.remove_blocks(&pipelined_blocks[0..1]);
 
         // Check that the block payload store no longer contains the removed block
-        let block_transaction_payloads = block_payload_store.get_block_payloads();
-        assert!(!block_transaction_payloads
-            .lock()
-            .contains_key(&pipelined_blocks[0].id()));
+        let payload_store_backend = block_payload_store.get_payload_store_backend();
+        assert!(!block_payload_store.all_payloads_exist(&pipelined_blocks[0..1]));
+        assert_eq!(payload_store_backend.get_num_payloads(), 9);
 
         // Remove the last 5 blocks from the block payload store
         block_payload_store.remove_blocks(&pipelined_blocks[5..10]);
 
         // Check that the block payload store no longer contains the removed blocks
-        let block_transaction_payloads = block_payload_store.get_block_payloads();
         for pipelined_block in pipelined_blocks.iter().take(10).skip(5) {
-            assert!(!block_transaction_payloads
-                .lock()
-                .contains_key(&pipelined_block.id()));
+            assert!(!block_payload_store.all_payloads_exist(&[pipelined_block.clone()]));
         }
+        assert_eq!(payload_store_backend.get_num_payloads(), 4);
 
         // Remove all the blocks from the block payload store (including some that don't exist)
         block_payload_store.remove_blocks(&pipelined_blocks[0..10]);
 
         // Check that the block payload store no longer contains any blocks
-        let block_transaction_payloads = block_payload_store.get_block_payloads();
-        assert!(block_transaction_payloads.lock().is_empty());
+        assert_eq!(payload_store_backend.get_num_payloads(), 0);
+    }
+
+    #[test]
+    fn test_remove_requested_payloads() {
+        // Create a new block payload store
+        let block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock());
+        let payload_store_backend = block_payload_store.get_payload_store_backend();
+
+        // Request several missing payloads
+        let missing_block_ids: Vec<_> = (0..5).map(|_| HashValue::random()).collect();
+        let payload_receivers: Vec<_> = missing_block_ids
+            .iter()
+            .map(
+                |block_id| match payload_store_backend.get_or_request_payload(*block_id) {
+                    Either::Left(_) => panic!("The block payload should not be available!"),
+                    Either::Right(payload_receiver) => payload_receiver,
+                },
+            )
+            .collect();
+
+        // Remove the requested payloads and verify the store is empty
+        payload_store_backend.remove_payloads(&missing_block_ids);
+        assert_eq!(payload_store_backend.get_num_payloads(), 0);
+
+        // Verify that the listeners are notified once the payloads are released
+        for payload_receiver in payload_receivers {
+            assert!(payload_receiver.blocking_recv().is_err());
+        }
+    }
+
+    #[test]
+    fn test_remove_payloads_for_commit() {
+        // Create a new block payload store
+        let mut block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock());
+
+        // Add some blocks to the payload store
+        let num_blocks_in_store = 10;
+        let pipelined_blocks =
+            create_and_add_blocks_to_store(block_payload_store.clone(), num_blocks_in_store);
+
+        // Insert a payload for a forked block (that will never be ordered)
+        let forked_block = pipelined_blocks[3].block_info();
+        let forked_block = BlockInfo::new(
+            forked_block.epoch(),
+            forked_block.round(),
+            HashValue::random(),
+            HashValue::random(),
+            forked_block.version(),
+            forked_block.timestamp_usecs(),
+            None,
+        );
+        block_payload_store.insert_block_payload(forked_block, vec![], None);
+        assert_eq!(
+            block_payload_store
+                .get_payload_store_backend()
+                .get_num_payloads(),
+            num_blocks_in_store + 1
+        );
+
+        // Remove the payloads for a commit and verify the older payloads are pruned
+        block_payload_store.remove_payloads_for_commit(&pipelined_blocks[5].block_info());
+        assert_eq!(
+            block_payload_store
+                .get_payload_store_backend()
+                .get_num_payloads(),
+            4
+        );
+        assert!(!block_payload_store.all_payloads_exist(&pipelined_blocks[0..6]));
+        assert!(block_payload_store.all_payloads_exist(&pipelined_blocks[6..10]));
+
+        // Remove the payloads for the last commit and verify the store is empty
+        block_payload_store.remove_payloads_for_commit(&pipelined_blocks[9].block_info());
+        assert_eq!(
+            block_payload_store
+                .get_payload_store_backend()
+                .get_num_payloads(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_update_target_capacity() {
+        // Create a new block payload store (with no capacity floor)
+        let consensus_observer_config = ConsensusObserverConfig {
+            min_payload_store_capacity_bytes: 0,
+            ..ConsensusObserverConfig::default()
+        };
+        let mut block_payload_store =
+            BlockPayloadStore::new(consensus_observer_config, TimeService::mock());
+
+        // Verify that the target capacity is zero (no payloads have been observed)
+        assert_eq!(block_payload_store.update_target_capacity(), 0);
+
+        // Insert a block payload with several transactions
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let transactions: Vec<_> = (0..10)
+            .map(|sequence_number| {
+                get_test_signed_txn(
+                    AccountAddress::random(),
+                    sequence_number,
+                    &private_key,
+                    private_key.public_key(),
+                    None,
+                )
+            })
+            .collect();
+        block_payload_store.insert_block_payload(
+            BlockInfo::random_with_epoch(0, 1),
+            transactions,
+            None,
+        );
+
+        // Verify that the target capacity is updated (and shared across clones)
+        let target_capacity_bytes = block_payload_store.clone().update_target_capacity();
+        assert!(target_capacity_bytes > 0);
+        assert_eq!(
+            block_payload_store.get_target_capacity_bytes(),
+            target_capacity_bytes
+        );
+    }
+
+    #[test]
+    fn test_payload_store_backend() {
+        // Create a new block payload store
+        let block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock());
+        let payload_store_backend = block_payload_store.get_payload_store_backend();
+
+        // Add some blocks to the payload store
+        let num_blocks_in_store = 10;
+        let pipelined_blocks =
+            create_and_add_blocks_to_store(block_payload_store.clone(), num_blocks_in_store);
+
+        // Verify that the payloads of the blocks are returned directly
+        for (index, pipelined_block) in pipelined_blocks.iter().enumerate() {
+            match payload_store_backend.get_or_request_payload(pipelined_block.id()) {
+                Either::Left(block_transaction_payload) => {
+                    assert_eq!(block_transaction_payload.limit, Some(index as u64));
+                },
+                Either::Right(_) => panic!("The block payload should be available!"),
+            }
+        }
+
+        // Request a missing payload and verify that the store now contains the request
+        let missing_block_id = HashValue::random();
+        let payload_receiver = match payload_store_backend.get_or_request_payload(missing_block_id)
+        {
+            Either::Left(_) => panic!("The block payload should not be available!"),
+            Either::Right(payload_receiver) => payload_receiver,
+        };
+        assert_eq!(
+            payload_store_backend.get_num_payloads(),
+            num_blocks_in_store + 1
+        );
+
+        // Verify that only the available payload IDs are returned
+        let available_payload_ids = payload_store_backend.get_available_payload_ids();
+        assert_eq!(available_payload_ids.len(), num_blocks_in_store);
+        assert!(!available_payload_ids.contains(&missing_block_id));
+
+        // Insert the missing payload and verify that the listener is notified
+        payload_store_backend.insert_payload(
+            missing_block_id,
+            BlockTransactionPayload::new(vec![], Some(100)),
+        );
+        let block_transaction_payload = payload_receiver.blocking_recv().unwrap();
+        assert_eq!(block_transaction_payload.limit, Some(100));
+
+        // Garbage collect all payloads except the first block and verify the store
+        let first_block_id = pipelined_blocks[0].id();
+        payload_store_backend.retain_payloads(&|block_id| *block_id == first_block_id);
+        assert_eq!(payload_store_backend.get_num_payloads(), 1);
+        assert!(block_payload_store.all_payloads_exist(&pipelined_blocks[0..1]));
+    }
+
+    #[test]
+    fn test_payload_store_limits() {
+        // Create a new block payload store (with an entry limit)
+        let max_num_payload_store_entries = 5;
+        let consensus_observer_config = ConsensusObserverConfig {
+            max_num_payload_store_entries,
+            ..ConsensusObserverConfig::default()
+        };
+        let mut block_payload_store =
+            BlockPayloadStore::new(consensus_observer_config, TimeService::mock());
+
+        // Add more blocks than the limit to the payload store
+        let num_blocks_in_store = 10;
+        let pipelined_blocks =
+            create_and_add_blocks_to_store(block_payload_store.clone(), num_blocks_in_store);
+
+        // Verify that the payloads with the highest rounds were evicted
+        let payload_store_backend = block_payload_store.get_payload_store_backend();
+        assert_eq!(
+            payload_store_backend.get_num_payloads(),
+            max_num_payload_store_entries as usize
+        );
+        assert!(block_payload_store.all_payloads_exist(&pipelined_blocks[0..5]));
+        for pipelined_block in &pipelined_blocks[5..10] {
+            assert!(!block_payload_store.all_payloads_exist(&[pipelined_block.clone()]));
+        }
+
+        // Insert a payload for a lower round and verify the highest round is evicted
+        let block_info = BlockInfo::random_with_epoch(0, 100);
+        let evicted_payloads = block_payload_store.insert_block_payload(block_info, vec![], None);
+        let highest_block = &pipelined_blocks[4];
+        assert_eq!(evicted_payloads, vec![(
+            highest_block.epoch(),
+            highest_block.round(),
+            highest_block.id()
+        )]);
+
+        // Remove the payloads for a commit and verify the store is within the limit
+        block_payload_store.remove_payloads_for_commit(&pipelined_blocks[3].block_info());
+        assert_eq!(payload_store_backend.get_num_payloads(), 0);
+
+        // Create a new block payload store (with a memory limit)
+        let consensus_observer_config = ConsensusObserverConfig {
+            max_payload_store_memory_bytes: 1,
+            ..ConsensusObserverConfig::default()
+        };
+        let mut block_payload_store =
+            BlockPayloadStore::new(consensus_observer_config, TimeService::mock());
+
+        // Insert a payload that exceeds the memory limit and verify it is evicted
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let transaction = get_test_signed_txn(
+            AccountAddress::random(),
+            0,
+            &private_key,
+            private_key.public_key(),
+            None,
+        );
+        let block_info = BlockInfo::random_with_epoch(0, 1);
+        let evicted_payloads =
+            block_payload_store.insert_block_payload(block_info.clone(), vec![transaction], None);
+        assert_eq!(evicted_payloads, vec![(
+            block_info.epoch(),
+            block_info.round(),
+            block_info.id()
+        )]);
+        assert_eq!(
+            block_payload_store
+                .get_payload_store_backend()
+                .get_num_payloads(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_persistent_payload_store() {
+        // Create a new block payload store (with persistent storage)
+        let tmp_dir = TempPath::new();
+        let mut block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock());
+        block_payload_store.set_payload_store_db(Arc::new(ObserverPayloadDb::new(&tmp_dir)));
+
+        // Add some blocks to the payload store
+        let num_blocks_in_store = 10;
+        let pipelined_blocks =
+            create_and_add_blocks_to_store(block_payload_store.clone(), num_blocks_in_store);
+
+        // Remove the payloads for a commit (to prune the persisted payloads)
+        block_payload_store.remove_payloads_for_commit(&pipelined_blocks[3].block_info());
+        drop(block_payload_store);
+
+        // Create a new block payload store (to emulate a restart) and restore the payloads
+        let mut block_payload_store =
+            BlockPayloadStore::new(ConsensusObserverConfig::default(), TimeService::mock());
+        block_payload_store.set_payload_store_db(Arc::new(ObserverPayloadDb::new(&tmp_dir)));
+
+        // Verify that only the unpruned payloads were restored
+        assert_eq!(
+            block_payload_store
+                .get_payload_store_backend()
+                .get_num_payloads(),
+            6
+        );
+        assert!(!block_payload_store.all_payloads_exist(&pipelined_blocks[0..4]));
+        assert!(block_payload_store.all_payloads_exist(&pipelined_blocks[4..10]));
+
+        // Verify that the restored payloads are pruned by later commits
+        block_payload_store.remove_payloads_for_commit(&pipelined_blocks[9].block_info());
+        assert_eq!(
+            block_payload_store
+                .get_payload_store_backend()
+                .get_num_payloads(),
+            0
+        );
     }
 
     /// Creates and adds the given number of blocks to the block payload store
@@ -294,16 +1346,14 @@ mod test {
         block_payload_store: BlockPayloadStore,
         block_id: HashValue,
     ) -> oneshot::Receiver<BlockTransactionPayload> {
-        // Get the block payload entry for the given block ID
-        let block_payloads = block_payload_store.get_block_payloads();
-        let mut block_payloads = block_payloads.lock();
-        let block_payload = block_payloads.get_mut(&block_id).unwrap();
+        // Remove the existing block payload for the given block ID
+        let payload_store_backend = block_payload_store.get_payload_store_backend();
+        payload_store_backend.remove_payloads(&[block_id]);
 
-        // Mark the block payload as requested
-        let (payload_sender, payload_receiver) = oneshot::channel();
-        *block_payload = BlockPayloadStatus::Requested(payload_sender);
-
-        // Return the payload receiver
-        payload_receiver
+        // Request the block payload and return the payload receiver
+        match payload_store_backend.get_or_request_payload(block_id) {
+            Either::Left(_) => panic!("The block payload should not be available!"),
+            Either::Right(payload_receiver) => payload_receiver,
+        }
     }
 }
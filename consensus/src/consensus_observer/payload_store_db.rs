@@ -0,0 +1,224 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the persistent storage for the block payloads
+//! buffered by the consensus observer. This allows observers to retain
+//! the payloads (e.g., for large payload windows) across node restarts.
+//!
+//! Serialized block payloads identified by the epoch, round and block ID.
+//! ```text
+//! |<---------key--------->|<---value--->|
+//! | epoch | round | block |   payload   |
+//! ```
+
+use crate::{consensus_observer::network_message::BlockPayload, error::DbError};
+use anyhow::{ensure, Result};
+use aptos_crypto::HashValue;
+use aptos_logger::info;
+use aptos_schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+    ColumnFamilyName, Options, SchemaBatch, DB,
+};
+use aptos_types::block_info::{BlockInfo, Round};
+use std::{mem::size_of, path::Path, time::Instant};
+
+/// The name of the observer payload db file
+pub const OBSERVER_PAYLOAD_DB_NAME: &str = "consensus_observer_payload_db";
+
+/// The column family of the block payloads
+const BLOCK_PAYLOAD_CF_NAME: ColumnFamilyName = "block_payload";
+
+/// The key of each block payload (i.e., the epoch, round and block ID)
+type BlockPayloadKey = (u64, Round, HashValue);
+
+define_schema!(
+    BlockPayloadSchema,
+    BlockPayloadKey,
+    BlockPayload,
+    BLOCK_PAYLOAD_CF_NAME
+);
+
+impl KeyCodec<BlockPayloadSchema> for BlockPayloadKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        // The key is encoded in big endian, so that the payloads are ordered by epoch and round
+        let (epoch, round, block_id) = self;
+        let mut encoded_key = Vec::with_capacity(size_of::<u64>() * 2 + HashValue::LENGTH);
+        encoded_key.extend_from_slice(&epoch.to_be_bytes());
+        encoded_key.extend_from_slice(&round.to_be_bytes());
+        encoded_key.extend_from_slice(block_id.as_ref());
+        Ok(encoded_key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() == size_of::<u64>() * 2 + HashValue::LENGTH,
+            "Unexpected block payload key length: {}",
+            data.len()
+        );
+        let (epoch_bytes, remaining_bytes) = data.split_at(size_of::<u64>());
+        let (round_bytes, block_id_bytes) = remaining_bytes.split_at(size_of::<u64>());
+        Ok((
+            u64::from_be_bytes(epoch_bytes.try_into()?),
+            Round::from_be_bytes(round_bytes.try_into()?),
+            HashValue::from_slice(block_id_bytes)?,
+        ))
+    }
+}
+
+impl ValueCodec<BlockPayloadSchema> for BlockPayload {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(&self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+/// The persistent storage for the block payloads of the consensus observer
+pub struct ObserverPayloadDb {
+    db: DB,
+}
+
+impl ObserverPayloadDb {
+    pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
+        let column_families = vec![BLOCK_PAYLOAD_CF_NAME];
+
+        let path = db_root_path.as_ref().join(OBSERVER_PAYLOAD_DB_NAME);
+        let instant = Instant::now();
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open(
+            path.clone(),
+            OBSERVER_PAYLOAD_DB_NAME,
+            column_families,
+            &opts,
+        )
+        .expect("ObserverPayloadDb open failed; unable to continue");
+
+        info!(
+            "Opened ObserverPayloadDb at {:?} in {} ms",
+            path,
+            instant.elapsed().as_millis()
+        );
+
+        Self { db }
+    }
+
+    /// Deletes the persisted payloads of the given blocks (i.e., epoch, round and block ID)
+    pub fn delete_payloads(&self, blocks: &[(u64, Round, HashValue)]) -> Result<(), DbError> {
+        let batch = SchemaBatch::new();
+        blocks
+            .iter()
+            .try_for_each(|key| batch.delete::<BlockPayloadSchema>(key))?;
+        self.commit(batch)
+    }
+
+    /// Returns all the persisted block payloads (ordered by epoch and round)
+    pub fn get_all_payloads(&self) -> Result<Vec<BlockPayload>, DbError> {
+        let mut iter = self.db.iter::<BlockPayloadSchema>()?;
+        iter.seek_to_first();
+        Ok(iter
+            .filter_map(|entry| entry.ok())
+            .map(|(_, block_payload)| block_payload)
+            .collect())
+    }
+
+    /// Removes all persisted payloads up to (and including) the given committed block
+    pub fn prune_payloads(&self, commit_info: &BlockInfo) -> Result<(), DbError> {
+        // Identify the keys up to (and including) the committed round
+        let mut iter = self.db.iter::<BlockPayloadSchema>()?;
+        iter.seek_to_first();
+        let pruned_keys: Vec<_> = iter
+            .filter_map(|entry| entry.ok())
+            .map(|(key, _)| key)
+            .take_while(|(epoch, round, _)| {
+                (*epoch, *round) <= (commit_info.epoch(), commit_info.round())
+            })
+            .collect();
+        if pruned_keys.is_empty() {
+            return Ok(());
+        }
+
+        // Delete the payloads (as a single batch)
+        let batch = SchemaBatch::new();
+        pruned_keys
+            .iter()
+            .try_for_each(|key| batch.delete::<BlockPayloadSchema>(key))?;
+        self.commit(batch)
+    }
+
+    /// Persists the given block payloads (as a single batch)
+    pub fn save_payloads(&self, block_payloads: &[BlockPayload]) -> Result<(), DbError> {
+        let batch = SchemaBatch::new();
+        block_payloads.iter().try_for_each(|block_payload| {
+            let block = &block_payload.block;
+            batch.put::<BlockPayloadSchema>(
+                &(block.epoch(), block.round(), block.id()),
+                block_payload,
+            )
+        })?;
+        self.commit(batch)
+    }
+
+    fn commit(&self, batch: SchemaBatch) -> Result<(), DbError> {
+        self.db.write_schemas(batch)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_temppath::TempPath;
+
+    #[test]
+    fn test_save_and_prune_payloads() {
+        // Create a new payload db
+        let tmp_dir = TempPath::new();
+        let payload_db = ObserverPayloadDb::new(&tmp_dir);
+        assert!(payload_db.get_all_payloads().unwrap().is_empty());
+
+        // Save several block payloads (out of order)
+        let block_payloads: Vec<_> = [(1, 300), (2, 5), (1, 10), (2, 1)]
+            .into_iter()
+            .map(|(epoch, round)| create_block_payload(epoch, round))
+            .collect();
+        payload_db.save_payloads(&block_payloads).unwrap();
+
+        // Reopen the payload db and verify the payloads are ordered by epoch and round
+        drop(payload_db);
+        let payload_db = ObserverPayloadDb::new(&tmp_dir);
+        let sorted_payloads = vec![
+            block_payloads[2].clone(),
+            block_payloads[0].clone(),
+            block_payloads[3].clone(),
+            block_payloads[1].clone(),
+        ];
+        assert_eq!(payload_db.get_all_payloads().unwrap(), sorted_payloads);
+
+        // Prune the payloads up to a commit and verify the remaining payloads
+        payload_db
+            .prune_payloads(&BlockInfo::random_with_epoch(2, 1))
+            .unwrap();
+        let remaining_payloads = payload_db.get_all_payloads().unwrap();
+        assert_eq!(remaining_payloads, vec![block_payloads[1].clone()]);
+
+        // Prune the payloads up to a commit in the next epoch and verify none remain
+        payload_db
+            .prune_payloads(&BlockInfo::random_with_epoch(3, 0))
+            .unwrap();
+        assert!(payload_db.get_all_payloads().unwrap().is_empty());
+    }
+
+    /// Creates an empty block payload for the given epoch and round
+    fn create_block_payload(epoch: u64, round: Round) -> BlockPayload {
+        BlockPayload {
+            block: BlockInfo::random_with_epoch(epoch, round),
+            transactions: vec![],
+            limit: Some(round),
+        }
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::metrics;
+use aptos_config::config::ConsensusObserverConfig;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// A simple sizer that auto-tunes the target capacity of the payload store.
+/// Instead of a static size budget, the target capacity is computed from the
+/// block rate and average payload size observed over a sliding window (i.e.,
+/// the store should be able to buffer the payloads received over the target
+/// buffer duration). The target is always kept within the configured bounds.
+pub struct PayloadStoreSizer {
+    // The duration of the sliding window over which payloads are observed
+    sizing_window: Duration,
+
+    // The duration of observed payloads that the store should be able to buffer
+    target_buffer_duration: Duration,
+
+    // The bounds (i.e., floor and ceiling) of the target capacity
+    min_capacity_bytes: u64,
+    max_capacity_bytes: u64,
+
+    // The receive times and sizes of the payloads observed in the sliding window
+    observed_payloads: VecDeque<(Instant, u64)>,
+
+    // The total size of the payloads observed in the sliding window
+    observed_payload_bytes: u64,
+
+    // The most recently computed target capacity (in bytes)
+    target_capacity_bytes: u64,
+
+    // The time service (used to maintain the sliding window)
+    time_service: TimeService,
+}
+
+impl PayloadStoreSizer {
+    pub fn new(
+        consensus_observer_config: &ConsensusObserverConfig,
+        time_service: TimeService,
+    ) -> Self {
+        let min_capacity_bytes = consensus_observer_config.min_payload_store_capacity_bytes;
+        Self {
+            sizing_window: Duration::from_millis(
+                consensus_observer_config.payload_store_sizing_window_ms,
+            ),
+            target_buffer_duration: Duration::from_millis(
+                consensus_observer_config.payload_store_target_buffer_ms,
+            ),
+            min_capacity_bytes,
+            max_capacity_bytes: consensus_observer_config.max_payload_store_capacity_bytes,
+            observed_payloads: VecDeque::new(),
+            observed_payload_bytes: 0,
+            target_capacity_bytes: min_capacity_bytes,
+            time_service,
+        }
+    }
+
+    /// Returns the most recently computed target capacity (in bytes)
+    pub fn get_target_capacity_bytes(&self) -> u64 {
+        self.target_capacity_bytes
+    }
+
+    /// Records a newly received payload of the given size (in bytes)
+    pub fn record_payload(&mut self, payload_size_bytes: u64) {
+        let time_now = self.time_service.now();
+        self.observed_payloads
+            .push_back((time_now, payload_size_bytes));
+        self.observed_payload_bytes = self
+            .observed_payload_bytes
+            .saturating_add(payload_size_bytes);
+        self.prune_observed_payloads(time_now);
+    }
+
+    /// Recomputes the target capacity (from the payloads observed in the
+    /// sliding window), updates the metrics and returns the new target.
+    pub fn update_target_capacity(&mut self) -> u64 {
+        // Prune the payloads that have fallen out of the sliding window
+        self.prune_observed_payloads(self.time_service.now());
+
+        // Calculate the observed block rate and average payload size
+        let num_observed_payloads = self.observed_payloads.len() as f64;
+        let block_rate_per_sec = num_observed_payloads / self.sizing_window.as_secs_f64();
+        let average_payload_bytes = if num_observed_payloads > 0.0 {
+            self.observed_payload_bytes as f64 / num_observed_payloads
+        } else {
+            0.0
+        };
+
+        // Calculate the target capacity (within the configured bounds)
+        let target_capacity_bytes = (block_rate_per_sec
+            * average_payload_bytes
+            * self.target_buffer_duration.as_secs_f64()) as u64;
+        self.target_capacity_bytes =
+            target_capacity_bytes.clamp(self.min_capacity_bytes, self.max_capacity_bytes);
+
+        // Update the target capacity metric
+        metrics::OBSERVER_PAYLOAD_STORE_TARGET_CAPACITY_BYTES
+            .set(self.target_capacity_bytes as i64);
+
+        self.target_capacity_bytes
+    }
+
+    /// Removes all observed payloads that are older than the sliding window
+    fn prune_observed_payloads(&mut self, time_now: Instant) {
+        while let Some((receive_time, payload_size_bytes)) = self.observed_payloads.front() {
+            if time_now.duration_since(*receive_time) <= self.sizing_window {
+                break; // The remaining payloads are within the window
+            }
+            self.observed_payload_bytes = self
+                .observed_payload_bytes
+                .saturating_sub(*payload_size_bytes);
+            self.observed_payloads.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_target_capacity_bounds() {
+        // Create a payload store sizer
+        let consensus_observer_config = ConsensusObserverConfig {
+            payload_store_sizing_window_ms: 10_000,
+            payload_store_target_buffer_ms: 10_000,
+            min_payload_store_capacity_bytes: 1_000,
+            max_payload_store_capacity_bytes: 100_000,
+            ..ConsensusObserverConfig::default()
+        };
+        let mut payload_store_sizer =
+            PayloadStoreSizer::new(&consensus_observer_config, TimeService::mock());
+
+        // Verify that the target is the floor (no payloads have been observed)
+        assert_eq!(payload_store_sizer.get_target_capacity_bytes(), 1_000);
+        assert_eq!(payload_store_sizer.update_target_capacity(), 1_000);
+
+        // Record a few small payloads and verify that the target is still the floor
+        for _ in 0..5 {
+            payload_store_sizer.record_payload(10);
+        }
+        assert_eq!(payload_store_sizer.update_target_capacity(), 1_000);
+
+        // Record many large payloads and verify that the target is the ceiling
+        for _ in 0..100 {
+            payload_store_sizer.record_payload(10_000);
+        }
+        assert_eq!(payload_store_sizer.update_target_capacity(), 100_000);
+        assert_eq!(payload_store_sizer.get_target_capacity_bytes(), 100_000);
+    }
+
+    #[test]
+    fn test_target_capacity_sliding_window() {
+        // Create a payload store sizer (where the buffer duration is twice the window)
+        let sizing_window_ms = 10_000;
+        let consensus_observer_config = ConsensusObserverConfig {
+            payload_store_sizing_window_ms: sizing_window_ms,
+            payload_store_target_buffer_ms: sizing_window_ms * 2,
+            min_payload_store_capacity_bytes: 0,
+            max_payload_store_capacity_bytes: u64::MAX,
+            ..ConsensusObserverConfig::default()
+        };
+        let time_service = TimeService::mock();
+        let mut payload_store_sizer =
+            PayloadStoreSizer::new(&consensus_observer_config, time_service.clone());
+
+        // Record 10 payloads of 1000 bytes (i.e., 1 block per second)
+        for _ in 0..10 {
+            payload_store_sizer.record_payload(1_000);
+        }
+
+        // Verify the target capacity (1 block/s * 1000 bytes * 20 seconds)
+        assert_eq!(payload_store_sizer.update_target_capacity(), 20_000);
+
+        // Elapse half the window and record 10 payloads of 3000 bytes
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance(Duration::from_millis(sizing_window_ms / 2));
+        for _ in 0..10 {
+            payload_store_sizer.record_payload(3_000);
+        }
+
+        // Verify the target capacity (2 blocks/s * 2000 bytes * 20 seconds)
+        assert_eq!(payload_store_sizer.update_target_capacity(), 80_000);
+
+        // Elapse the remaining window and verify the first payloads are no longer observed
+        mock_time_service.advance(Duration::from_millis(sizing_window_ms / 2 + 1));
+        assert_eq!(payload_store_sizer.update_target_capacity(), 60_000);
+
+        // Elapse the window again and verify that no payloads are observed
+        mock_time_service.advance(Duration::from_millis(sizing_window_ms));
+        assert_eq!(payload_store_sizer.update_target_capacity(), 0);
+    }
+}
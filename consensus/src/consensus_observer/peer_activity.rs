@@ -0,0 +1,211 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::network_id::PeerNetworkId;
+use aptos_infallible::Mutex;
+use aptos_network::application::metadata::PeerMetadata;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// The activity observed locally for a single peer
+#[derive(Clone, Copy, Debug, Default)]
+struct PeerActivity {
+    // The time the last message was received from the peer (if any)
+    last_message_time: Option<Instant>,
+
+    // The round-trip time of the last successful request to the peer (if any)
+    last_request_rtt: Option<Duration>,
+}
+
+/// The fallback sort key for a peer (lower keys are preferred), i.e.,
+/// (no recent message activity, request RTT, connection ID, peer).
+pub type FallbackSortKey = (bool, Duration, u32, PeerNetworkId);
+
+/// A tracker for the activity observed locally for each peer (i.e., received
+/// messages and request round-trip times). This is used to rank peers when
+/// the peer monitoring metadata is unavailable (e.g., on small or private
+/// networks). The tracker is cheaply cloneable, and all clones share the same state.
+#[derive(Clone)]
+pub struct PeerActivityTracker {
+    // The activity observed for each peer
+    peer_activities: Arc<Mutex<HashMap<PeerNetworkId, PeerActivity>>>,
+
+    // Whether a peer has new ranking inputs (i.e., its first message or RTT)
+    ranking_inputs_changed: Arc<AtomicBool>,
+
+    // The window within which a received message counts as recent activity
+    recent_activity_window: Duration,
+
+    // The time service (used to timestamp the received messages)
+    time_service: TimeService,
+}
+
+impl PeerActivityTracker {
+    pub fn new(recent_activity_window_ms: u64, time_service: TimeService) -> Self {
+        Self {
+            peer_activities: Arc::new(Mutex::new(HashMap::new())),
+            ranking_inputs_changed: Arc::new(AtomicBool::new(false)),
+            recent_activity_window: Duration::from_millis(recent_activity_window_ms),
+            time_service,
+        }
+    }
+
+    /// Returns the fallback sort key for the given peer (lower keys are
+    /// preferred). Peers with recent message activity are preferred, then
+    /// peers with lower request round-trip times (peers without a known RTT
+    /// are ranked last), and then peers with older connections (i.e., lower
+    /// connection IDs). Ties are broken by the peer (for a stable ordering).
+    pub fn get_fallback_sort_key(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        peer_metadata: &PeerMetadata,
+    ) -> FallbackSortKey {
+        // Get the activity for the peer
+        let peer_activity = self
+            .peer_activities
+            .lock()
+            .get(peer_network_id)
+            .copied()
+            .unwrap_or_default();
+
+        // Determine if the peer has recent message activity
+        let time_now = self.time_service.now();
+        let has_recent_activity =
+            peer_activity
+                .last_message_time
+                .map_or(false, |last_message_time| {
+                    time_now.duration_since(last_message_time) <= self.recent_activity_window
+                });
+
+        // Calculate the sort key
+        let request_rtt = peer_activity.last_request_rtt.unwrap_or(Duration::MAX);
+        let connection_id = peer_metadata
+            .get_connection_metadata()
+            .connection_id
+            .get_inner();
+        (
+            !has_recent_activity,
+            request_rtt,
+            connection_id,
+            *peer_network_id,
+        )
+    }
+
+    /// Removes the activity of all peers that are not in the given peers
+    pub fn garbage_collect(&self, connected_peers: &[PeerNetworkId]) {
+        self.peer_activities
+            .lock()
+            .retain(|peer_network_id, _| connected_peers.contains(peer_network_id));
+    }
+
+    /// Records that a message was received from the given peer
+    pub fn record_message_received(&self, peer_network_id: &PeerNetworkId) {
+        let time_now = self.time_service.now();
+        let mut peer_activities = self.peer_activities.lock();
+        let peer_activity = peer_activities.entry(*peer_network_id).or_default();
+        if peer_activity.last_message_time.is_none() {
+            self.ranking_inputs_changed.store(true, Ordering::Relaxed);
+        }
+        peer_activity.last_message_time = Some(time_now);
+    }
+
+    /// Records the round-trip time of a successful request to the given peer
+    pub fn record_request_rtt(&self, peer_network_id: &PeerNetworkId, request_rtt: Duration) {
+        let mut peer_activities = self.peer_activities.lock();
+        let peer_activity = peer_activities.entry(*peer_network_id).or_default();
+        if peer_activity.last_request_rtt.is_none() {
+            self.ranking_inputs_changed.store(true, Ordering::Relaxed);
+        }
+        peer_activity.last_request_rtt = Some(request_rtt);
+    }
+
+    /// Returns true iff a peer has new ranking inputs since the last call
+    /// (i.e., its first message or RTT was recorded), and resets the flag.
+    pub fn take_ranking_inputs_changed(&self) -> bool {
+        self.ranking_inputs_changed.swap(false, Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_config::network_id::NetworkId;
+    use aptos_network::transport::{ConnectionId, ConnectionMetadata};
+    use aptos_types::PeerId;
+
+    #[test]
+    fn test_fallback_sort_keys() {
+        // Create a peer activity tracker (with a 10 second activity window)
+        let time_service = TimeService::mock();
+        let peer_activity_tracker = PeerActivityTracker::new(10_000, time_service.clone());
+
+        // Create several peers (with increasing connection IDs)
+        let peers_and_metadata: Vec<_> = (0..4).map(create_peer_and_metadata).collect();
+        let get_sorted_peers = || {
+            let mut sorted_peers: Vec<_> = peers_and_metadata
+                .iter()
+                .map(|(peer_network_id, peer_metadata)| {
+                    peer_activity_tracker.get_fallback_sort_key(peer_network_id, peer_metadata)
+                })
+                .collect();
+            sorted_peers.sort();
+            sorted_peers
+                .into_iter()
+                .map(|(_, _, _, peer_network_id)| peer_network_id)
+                .collect::<Vec<_>>()
+        };
+
+        // Verify that peers without activity are sorted by connection age
+        let peers: Vec<_> = peers_and_metadata
+            .iter()
+            .map(|(peer_network_id, _)| *peer_network_id)
+            .collect();
+        assert_eq!(get_sorted_peers(), peers);
+        assert!(!peer_activity_tracker.take_ranking_inputs_changed());
+
+        // Record request RTTs and verify that peers with lower RTTs are preferred
+        peer_activity_tracker.record_request_rtt(&peers[2], Duration::from_millis(50));
+        peer_activity_tracker.record_request_rtt(&peers[3], Duration::from_millis(20));
+        assert_eq!(get_sorted_peers(), vec![
+            peers[3], peers[2], peers[0], peers[1]
+        ]);
+        assert!(peer_activity_tracker.take_ranking_inputs_changed());
+        assert!(!peer_activity_tracker.take_ranking_inputs_changed());
+
+        // Record a message and verify that peers with recent activity are preferred
+        peer_activity_tracker.record_message_received(&peers[1]);
+        assert_eq!(get_sorted_peers(), vec![
+            peers[1], peers[3], peers[2], peers[0]
+        ]);
+        assert!(peer_activity_tracker.take_ranking_inputs_changed());
+
+        // Elapse the activity window and verify the activity is no longer recent
+        let time_service = time_service.into_mock();
+        time_service.advance(Duration::from_secs(11));
+        assert_eq!(get_sorted_peers(), vec![
+            peers[3], peers[2], peers[0], peers[1]
+        ]);
+
+        // Garbage collect the disconnected peers and verify their activity is removed
+        peer_activity_tracker.garbage_collect(&[peers[0], peers[1], peers[2]]);
+        assert_eq!(get_sorted_peers(), vec![
+            peers[2], peers[0], peers[1], peers[3]
+        ]);
+    }
+
+    /// Creates a new peer and metadata (with the given connection ID)
+    fn create_peer_and_metadata(connection_id: u32) -> (PeerNetworkId, PeerMetadata) {
+        let peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        let mut connection_metadata = ConnectionMetadata::mock(peer_network_id.peer_id());
+        connection_metadata.connection_id = ConnectionId::from(connection_id);
+        let peer_metadata = PeerMetadata::new(connection_metadata);
+        (peer_network_id, peer_metadata)
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    logging::{LogEntry, LogSchema},
+    metrics,
+    network_client::ConsensusObserverClient,
+    network_message::ConsensusObserverMessage,
+    task_registry::TaskRegistry,
+};
+use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
+use aptos_infallible::Mutex;
+use aptos_logger::{info, warn};
+use aptos_network::application::interface::NetworkClient;
+use std::{collections::HashMap, sync::Arc};
+
+/// The types of peer misbehavior detected by the consensus observer and publisher
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PeerMisbehavior {
+    InvalidProof,       // The peer sent a message that failed verification
+    SpammyRequest,      // The peer sent a redundant (or excessive) request
+    UnexpectedMessage,  // The peer sent a message type that we don't expect
+    UnsolicitedMessage, // The peer sent a message without an active subscription
+}
+
+impl PeerMisbehavior {
+    /// Returns a summary label for the misbehavior
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            PeerMisbehavior::InvalidProof => "invalid_proof",
+            PeerMisbehavior::SpammyRequest => "spammy_request",
+            PeerMisbehavior::UnexpectedMessage => "unexpected_message",
+            PeerMisbehavior::UnsolicitedMessage => "unsolicited_message",
+        }
+    }
+
+    /// Returns the penalty (i.e., the misbehavior score increase) for the misbehavior
+    pub fn get_penalty(&self) -> u64 {
+        match self {
+            PeerMisbehavior::InvalidProof => 20,
+            PeerMisbehavior::SpammyRequest => 5,
+            PeerMisbehavior::UnexpectedMessage => 10,
+            PeerMisbehavior::UnsolicitedMessage => 5,
+        }
+    }
+}
+
+/// A shared component (used by both the consensus observer and publisher) that
+/// handles peer misbehavior reports. Each report applies a penalty to the peer's
+/// misbehavior score and updates the metrics. If the peer's score exceeds the
+/// maximum (and disconnects are enabled), the peer is disconnected.
+#[derive(Clone)]
+pub struct PeerMisbehaviorReporter {
+    // The configuration of the consensus observer
+    consensus_observer_config: ConsensusObserverConfig,
+
+    // The consensus observer client (used to disconnect misbehaving peers)
+    consensus_observer_client:
+        Arc<ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>>,
+
+    // The current misbehavior scores of the peers
+    peer_misbehavior_scores: Arc<Mutex<HashMap<PeerNetworkId, u64>>>,
+
+    // The registry of background tasks (e.g., peer disconnects)
+    task_registry: TaskRegistry,
+}
+
+impl PeerMisbehaviorReporter {
+    pub fn new(
+        consensus_observer_config: ConsensusObserverConfig,
+        consensus_observer_client: Arc<
+            ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>,
+        >,
+    ) -> Self {
+        Self {
+            consensus_observer_config,
+            consensus_observer_client,
+            peer_misbehavior_scores: Arc::new(Mutex::new(HashMap::new())),
+            task_registry: TaskRegistry::new(),
+        }
+    }
+
+    /// Disconnects from the given peer (asynchronously)
+    fn disconnect_from_peer(&self, peer_network_id: PeerNetworkId) {
+        info!(LogSchema::new(LogEntry::PeerMisbehavior).message(&format!(
+            "Disconnecting from misbehaving peer: {}!",
+            peer_network_id
+        )));
+        metrics::increment_peer_misbehavior_disconnects(&peer_network_id.network_id());
+
+        // Disconnect from the peer
+        let consensus_observer_client = self.consensus_observer_client.clone();
+        self.task_registry
+            .spawn_task("disconnect_from_peer", async move {
+                if let Err(error) = consensus_observer_client
+                    .disconnect_from_peer(&peer_network_id)
+                    .await
+                {
+                    warn!(LogSchema::new(LogEntry::PeerMisbehavior).message(&format!(
+                        "Failed to disconnect from peer: {}! Error: {:?}",
+                        peer_network_id, error
+                    )));
+                }
+            });
+    }
+
+    /// Removes the misbehavior scores of all peers that are no longer connected
+    pub fn garbage_collect_scores(&self) {
+        let peers_and_metadata = self.consensus_observer_client.get_peers_and_metadata();
+        if let Ok(connected_peers_and_metadata) =
+            peers_and_metadata.get_connected_peers_and_metadata()
+        {
+            self.peer_misbehavior_scores
+                .lock()
+                .retain(|peer_network_id, _| {
+                    connected_peers_and_metadata.contains_key(peer_network_id)
+                });
+        }
+    }
+
+    /// Returns the current misbehavior score of the given peer
+    pub fn get_misbehavior_score(&self, peer_network_id: &PeerNetworkId) -> u64 {
+        self.peer_misbehavior_scores
+            .lock()
+            .get(peer_network_id)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Reports the given misbehavior for the specified peer. This applies
+    /// the misbehavior penalty, updates the metrics and disconnects
+    /// the peer (if the peer's score exceeds the maximum).
+    pub fn report_misbehavior(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        misbehavior: PeerMisbehavior,
+        details: &str,
+    ) {
+        // Log the misbehavior and update the metrics
+        warn!(LogSchema::new(LogEntry::PeerMisbehavior)
+            .peer(peer_network_id)
+            .message(&format!(
+                "Peer misbehavior reported: {:?}! Details: {}",
+                misbehavior, details
+            )));
+        metrics::increment_request_counter(
+            &metrics::OBSERVER_PEER_MISBEHAVIORS,
+            misbehavior.get_label(),
+            peer_network_id,
+        );
+
+        // Apply the misbehavior penalty
+        let misbehavior_score = {
+            let mut peer_misbehavior_scores = self.peer_misbehavior_scores.lock();
+            let misbehavior_score = peer_misbehavior_scores.entry(*peer_network_id).or_insert(0);
+            *misbehavior_score = misbehavior_score.saturating_add(misbehavior.get_penalty());
+            *misbehavior_score
+        };
+
+        // Disconnect from the peer if the score exceeds the maximum (and disconnects are enabled)
+        if self.consensus_observer_config.disconnect_misbehaving_peers
+            && misbehavior_score >= self.consensus_observer_config.max_peer_misbehavior_score
+        {
+            self.peer_misbehavior_scores.lock().remove(peer_network_id);
+            self.disconnect_from_peer(*peer_network_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_config::network_id::NetworkId;
+    use aptos_network::application::storage::PeersAndMetadata;
+    use aptos_types::PeerId;
+    use maplit::hashmap;
+
+    #[tokio::test]
+    async fn test_report_misbehavior() {
+        // Create a misbehavior reporter that disconnects misbehaving peers
+        let consensus_observer_config = ConsensusObserverConfig {
+            disconnect_misbehaving_peers: true,
+            max_peer_misbehavior_score: 50,
+            ..Default::default()
+        };
+        let peer_misbehavior_reporter = create_peer_misbehavior_reporter(consensus_observer_config);
+
+        // Report several misbehaviors and verify the misbehavior score
+        let peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        peer_misbehavior_reporter.report_misbehavior(
+            &peer_network_id,
+            PeerMisbehavior::UnsolicitedMessage,
+            "Test misbehavior",
+        );
+        peer_misbehavior_reporter.report_misbehavior(
+            &peer_network_id,
+            PeerMisbehavior::InvalidProof,
+            "Test misbehavior",
+        );
+        let expected_score = PeerMisbehavior::UnsolicitedMessage.get_penalty()
+            + PeerMisbehavior::InvalidProof.get_penalty();
+        assert_eq!(
+            peer_misbehavior_reporter.get_misbehavior_score(&peer_network_id),
+            expected_score
+        );
+
+        // Report enough misbehavior to exceed the maximum score
+        peer_misbehavior_reporter.report_misbehavior(
+            &peer_network_id,
+            PeerMisbehavior::InvalidProof,
+            "Test misbehavior",
+        );
+
+        // Verify the peer was disconnected (and its score was reset)
+        assert_eq!(
+            peer_misbehavior_reporter.get_misbehavior_score(&peer_network_id),
+            0
+        );
+    }
+
+    #[test]
+    fn test_report_misbehavior_no_disconnect() {
+        // Create a misbehavior reporter that doesn't disconnect misbehaving peers
+        let consensus_observer_config = ConsensusObserverConfig {
+            disconnect_misbehaving_peers: false,
+            max_peer_misbehavior_score: 10,
+            ..Default::default()
+        };
+        let peer_misbehavior_reporter = create_peer_misbehavior_reporter(consensus_observer_config);
+
+        // Report misbehavior that exceeds the maximum score
+        let peer_network_id = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+        for _ in 0..5 {
+            peer_misbehavior_reporter.report_misbehavior(
+                &peer_network_id,
+                PeerMisbehavior::UnexpectedMessage,
+                "Test misbehavior",
+            );
+        }
+
+        // Verify that the score continues to accumulate (the peer wasn't disconnected)
+        assert_eq!(
+            peer_misbehavior_reporter.get_misbehavior_score(&peer_network_id),
+            5 * PeerMisbehavior::UnexpectedMessage.get_penalty()
+        );
+
+        // Garbage collect the scores and verify the score was removed (the peer isn't connected)
+        peer_misbehavior_reporter.garbage_collect_scores();
+        assert_eq!(
+            peer_misbehavior_reporter.get_misbehavior_score(&peer_network_id),
+            0
+        );
+    }
+
+    /// Creates a peer misbehavior reporter with the given config
+    fn create_peer_misbehavior_reporter(
+        consensus_observer_config: ConsensusObserverConfig,
+    ) -> PeerMisbehaviorReporter {
+        let network_client = NetworkClient::new(
+            vec![],
+            vec![],
+            hashmap![],
+            PeersAndMetadata::new(&[NetworkId::Public]),
+        );
+        let consensus_observer_client = Arc::new(ConsensusObserverClient::new(network_client));
+        PeerMisbehaviorReporter::new(consensus_observer_config, consensus_observer_client)
+    }
+}
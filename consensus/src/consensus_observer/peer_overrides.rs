@@ -0,0 +1,242 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    error::Error,
+    logging::{LogEntry, LogSchema},
+};
+use aptos_config::network_id::PeerNetworkId;
+use aptos_infallible::RwLock;
+use aptos_logger::info;
+use aptos_types::PeerId;
+use std::{
+    collections::BTreeSet,
+    fmt::{Display, Formatter},
+    sync::Arc,
+};
+
+/// The operator overrides for subscription peer selection
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct PeerOverrides {
+    // The peer that the observer must subscribe to (if any)
+    pinned_peer: Option<PeerId>,
+
+    // The peers that the observer must never subscribe to
+    forbidden_peers: BTreeSet<PeerId>,
+}
+
+/// The runtime operator overrides for subscription peer selection. These
+/// allow operators (e.g., via the admin service) to force the observer to
+/// subscribe to a specific peer, or to forbid specific peers, without a
+/// restart (e.g., during incident response). Peers are identified by peer
+/// ID, so the overrides apply across all networks. Overrides take effect at
+/// the next subscription check. The overrides are cheaply cloneable, and all
+/// clones share the same state.
+#[derive(Clone, Default)]
+pub struct SubscriptionPeerOverrides {
+    peer_overrides: Arc<RwLock<PeerOverrides>>,
+}
+
+impl SubscriptionPeerOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows the given peer to be selected for subscriptions again
+    pub fn allow_peer(&self, peer_id: PeerId) {
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Removing the subscription override forbidding peer: {}",
+                peer_id
+            ))
+        );
+        self.peer_overrides.write().forbidden_peers.remove(&peer_id);
+    }
+
+    /// Applies the overrides to the given sorted peers. Forbidden peers are
+    /// removed, and if a peer is pinned, only the pinned peer is retained.
+    pub fn apply_to_sorted_peers(
+        &self,
+        sorted_peers: Arc<[PeerNetworkId]>,
+    ) -> Arc<[PeerNetworkId]> {
+        let peer_overrides = self.peer_overrides.read();
+        if *peer_overrides == PeerOverrides::default() {
+            return sorted_peers; // There are no overrides
+        }
+
+        sorted_peers
+            .iter()
+            .filter(|peer_network_id| is_peer_allowed(&peer_overrides, peer_network_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Verifies that the given subscription peer is permitted by the overrides
+    pub fn check_subscription_peer(&self, peer_network_id: &PeerNetworkId) -> Result<(), Error> {
+        let peer_overrides = self.peer_overrides.read();
+        if !is_peer_allowed(&peer_overrides, peer_network_id) {
+            return Err(Error::SubscriptionOverridden(format!(
+                "Subscription to peer: {} is not permitted by the operator overrides! \
+                Pinned peer: {:?}",
+                peer_network_id, peer_overrides.pinned_peer
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Forbids the given peer from being selected for subscriptions. If
+    /// the peer is currently pinned, the pinned peer is also removed.
+    pub fn forbid_peer(&self, peer_id: PeerId) {
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Adding a subscription override forbidding peer: {}",
+                peer_id
+            ))
+        );
+        let mut peer_overrides = self.peer_overrides.write();
+        if peer_overrides.pinned_peer == Some(peer_id) {
+            peer_overrides.pinned_peer = None;
+        }
+        peer_overrides.forbidden_peers.insert(peer_id);
+    }
+
+    /// Returns the currently forbidden peers
+    pub fn get_forbidden_peers(&self) -> Vec<PeerId> {
+        self.peer_overrides
+            .read()
+            .forbidden_peers
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the currently pinned peer (if any)
+    pub fn get_pinned_peer(&self) -> Option<PeerId> {
+        self.peer_overrides.read().pinned_peer
+    }
+
+    /// Pins the given peer (i.e., the observer will only subscribe to the
+    /// pinned peer). If the peer is currently forbidden, it is also allowed.
+    pub fn pin_peer(&self, peer_id: PeerId) {
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Adding a subscription override pinning peer: {}",
+                peer_id
+            ))
+        );
+        let mut peer_overrides = self.peer_overrides.write();
+        peer_overrides.forbidden_peers.remove(&peer_id);
+        peer_overrides.pinned_peer = Some(peer_id);
+    }
+
+    /// Removes the pinned peer (if any)
+    pub fn unpin_peer(&self) {
+        info!(LogSchema::new(LogEntry::ConsensusObserver)
+            .message("Removing the subscription override pinning a peer"));
+        self.peer_overrides.write().pinned_peer = None;
+    }
+}
+
+impl Display for SubscriptionPeerOverrides {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let peer_overrides = self.peer_overrides.read();
+        match &peer_overrides.pinned_peer {
+            Some(pinned_peer) => writeln!(f, "Pinned peer: {}", pinned_peer)?,
+            None => writeln!(f, "Pinned peer: none")?,
+        }
+        write!(f, "Forbidden peers: {:?}", peer_overrides.forbidden_peers)
+    }
+}
+
+/// Returns true iff the given peer is permitted by the overrides
+fn is_peer_allowed(peer_overrides: &PeerOverrides, peer_network_id: &PeerNetworkId) -> bool {
+    let peer_id = peer_network_id.peer_id();
+    if peer_overrides.forbidden_peers.contains(&peer_id) {
+        return false;
+    }
+    peer_overrides
+        .pinned_peer
+        .map_or(true, |pinned_peer| pinned_peer == peer_id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_config::network_id::NetworkId;
+
+    #[test]
+    fn test_peer_overrides() {
+        // Create the overrides and several peers
+        let peer_overrides = SubscriptionPeerOverrides::new();
+        let sorted_peers: Arc<[PeerNetworkId]> = (0..5)
+            .map(|_| PeerNetworkId::new(NetworkId::Public, PeerId::random()))
+            .collect();
+
+        // Verify that all peers are permitted (there are no overrides)
+        assert_eq!(
+            peer_overrides.apply_to_sorted_peers(sorted_peers.clone()),
+            sorted_peers
+        );
+        for peer_network_id in sorted_peers.iter() {
+            assert!(peer_overrides
+                .check_subscription_peer(peer_network_id)
+                .is_ok());
+        }
+
+        // Forbid a peer and verify it is removed (without changing the order)
+        let forbidden_peer = sorted_peers[1];
+        peer_overrides.forbid_peer(forbidden_peer.peer_id());
+        let filtered_peers = peer_overrides.apply_to_sorted_peers(sorted_peers.clone());
+        assert_eq!(filtered_peers.to_vec(), vec![
+            sorted_peers[0],
+            sorted_peers[2],
+            sorted_peers[3],
+            sorted_peers[4]
+        ]);
+        assert!(matches!(
+            peer_overrides.check_subscription_peer(&forbidden_peer),
+            Err(Error::SubscriptionOverridden(_))
+        ));
+
+        // Verify the forbidden peer applies across all networks
+        let forbidden_vfn_peer = PeerNetworkId::new(NetworkId::Vfn, forbidden_peer.peer_id());
+        assert!(peer_overrides
+            .check_subscription_peer(&forbidden_vfn_peer)
+            .is_err());
+
+        // Pin a peer and verify that only the pinned peer is retained
+        let pinned_peer = sorted_peers[3];
+        peer_overrides.pin_peer(pinned_peer.peer_id());
+        let filtered_peers = peer_overrides.apply_to_sorted_peers(sorted_peers.clone());
+        assert_eq!(filtered_peers.to_vec(), vec![pinned_peer]);
+        assert!(peer_overrides.check_subscription_peer(&pinned_peer).is_ok());
+        assert!(peer_overrides
+            .check_subscription_peer(&sorted_peers[0])
+            .is_err());
+
+        // Pin the forbidden peer and verify it is no longer forbidden
+        peer_overrides.pin_peer(forbidden_peer.peer_id());
+        assert_eq!(
+            peer_overrides.get_pinned_peer(),
+            Some(forbidden_peer.peer_id())
+        );
+        assert!(peer_overrides.get_forbidden_peers().is_empty());
+
+        // Forbid the pinned peer and verify it is no longer pinned
+        peer_overrides.forbid_peer(forbidden_peer.peer_id());
+        assert_eq!(peer_overrides.get_pinned_peer(), None);
+        assert_eq!(peer_overrides.get_forbidden_peers(), vec![
+            forbidden_peer.peer_id()
+        ]);
+
+        // Remove all overrides (using a clone) and verify all peers are permitted
+        let peer_overrides_clone = peer_overrides.clone();
+        peer_overrides_clone.allow_peer(forbidden_peer.peer_id());
+        peer_overrides_clone.unpin_peer();
+        assert_eq!(
+            peer_overrides.apply_to_sorted_peers(sorted_peers.clone()),
+            sorted_peers
+        );
+    }
+}
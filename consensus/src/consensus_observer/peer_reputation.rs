@@ -0,0 +1,215 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::metrics;
+use aptos_config::network_id::PeerNetworkId;
+use aptos_infallible::Mutex;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// The events that affect the reputation of a subscription peer
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PeerReputationEvent {
+    FailedProof,    // The peer sent a message with a proof that failed verification
+    InvalidPayload, // The peer sent a block payload that failed verification
+    RateLimited,    // The peer persistently exceeded its inbound message rate limit
+    Stall,          // The peer's subscription stopped making progress (or became stale)
+    Timeout,        // The peer's subscription timed out (i.e., no messages were received)
+}
+
+impl PeerReputationEvent {
+    /// Returns a summary label for the event
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            PeerReputationEvent::FailedProof => "failed_proof",
+            PeerReputationEvent::InvalidPayload => "invalid_payload",
+            PeerReputationEvent::RateLimited => "rate_limited",
+            PeerReputationEvent::Stall => "stall",
+            PeerReputationEvent::Timeout => "timeout",
+        }
+    }
+
+    /// Returns the penalty (i.e., the reputation score increase) for the event
+    pub fn get_penalty(&self) -> u64 {
+        match self {
+            PeerReputationEvent::FailedProof => 20,
+            PeerReputationEvent::InvalidPayload => 20,
+            PeerReputationEvent::RateLimited => 10,
+            PeerReputationEvent::Stall => 10,
+            PeerReputationEvent::Timeout => 5,
+        }
+    }
+}
+
+/// A tracker for the reputation of each subscription peer, i.e., the recent
+/// events where the peer served bad or slow data (invalid payloads, failed
+/// proofs, rate limit violations, timeouts and stalls). Each event only counts
+/// towards the peer's reputation score for the configured window, so peers can
+/// recover over time. Reputations are retained across disconnects (to avoid repeatedly
+/// re-subscribing to the same bad peers when they reconnect). The tracker
+/// is cheaply cloneable, and all clones share the same state.
+#[derive(Clone)]
+pub struct PeerReputationTracker {
+    // The recent reputation events of each peer (and the times they were recorded)
+    peer_events: Arc<Mutex<HashMap<PeerNetworkId, VecDeque<(Instant, PeerReputationEvent)>>>>,
+
+    // Whether a peer's reputation score has changed (i.e., an event was recorded or expired)
+    ranking_inputs_changed: Arc<AtomicBool>,
+
+    // The window within which an event counts towards the reputation score
+    reputation_window: Duration,
+
+    // The time service (used to timestamp the events)
+    time_service: TimeService,
+}
+
+impl PeerReputationTracker {
+    pub fn new(reputation_window_ms: u64, time_service: TimeService) -> Self {
+        Self {
+            peer_events: Arc::new(Mutex::new(HashMap::new())),
+            ranking_inputs_changed: Arc::new(AtomicBool::new(false)),
+            reputation_window: Duration::from_millis(reputation_window_ms),
+            time_service,
+        }
+    }
+
+    /// Removes all expired events (i.e., events outside the reputation window),
+    /// and the peers without any remaining events.
+    pub fn garbage_collect(&self) {
+        let time_now = self.time_service.now();
+        let mut peer_events = self.peer_events.lock();
+        for events in peer_events.values_mut() {
+            while let Some((event_time, _)) = events.front() {
+                if time_now.duration_since(*event_time) <= self.reputation_window {
+                    break;
+                }
+                events.pop_front();
+                self.ranking_inputs_changed.store(true, Ordering::Relaxed);
+            }
+        }
+        peer_events.retain(|_, events| !events.is_empty());
+    }
+
+    /// Returns the number of recent events (of each type) for the given peer
+    pub fn get_event_counts(
+        &self,
+        peer_network_id: &PeerNetworkId,
+    ) -> HashMap<PeerReputationEvent, u64> {
+        let mut event_counts = HashMap::new();
+        for event in self.get_recent_events(peer_network_id) {
+            *event_counts.entry(event).or_insert(0) += 1;
+        }
+        event_counts
+    }
+
+    /// Returns the reputation score for the given peer (i.e., the sum of the
+    /// penalties of all recent events). Lower scores are better, and peers
+    /// without any recent events have a score of zero.
+    pub fn get_reputation_score(&self, peer_network_id: &PeerNetworkId) -> u64 {
+        self.get_recent_events(peer_network_id)
+            .iter()
+            .map(|event| event.get_penalty())
+            .sum()
+    }
+
+    /// Returns the events recorded for the given peer within the reputation window
+    fn get_recent_events(&self, peer_network_id: &PeerNetworkId) -> Vec<PeerReputationEvent> {
+        let time_now = self.time_service.now();
+        self.peer_events
+            .lock()
+            .get(peer_network_id)
+            .map(|events| {
+                events
+                    .iter()
+                    .filter(|(event_time, _)| {
+                        time_now.duration_since(*event_time) <= self.reputation_window
+                    })
+                    .map(|(_, event)| *event)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Records the given reputation event for the peer
+    pub fn record_event(&self, peer_network_id: &PeerNetworkId, event: PeerReputationEvent) {
+        // Update the reputation event metrics
+        metrics::increment_peer_reputation_events(event.get_label(), &peer_network_id.network_id());
+
+        // Record the event for the peer
+        let time_now = self.time_service.now();
+        self.peer_events
+            .lock()
+            .entry(*peer_network_id)
+            .or_default()
+            .push_back((time_now, event));
+        self.ranking_inputs_changed.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true iff a peer's reputation score has changed since the last
+    /// call (i.e., an event was recorded or expired), and resets the flag.
+    pub fn take_ranking_inputs_changed(&self) -> bool {
+        self.ranking_inputs_changed.swap(false, Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reputation_scores() {
+        // Create a peer reputation tracker (with a 60 second window)
+        let time_service = TimeService::mock();
+        let peer_reputation_tracker = PeerReputationTracker::new(60_000, time_service.clone());
+
+        // Verify that peers without events have a score of zero
+        let peer_1 = PeerNetworkId::random();
+        let peer_2 = PeerNetworkId::random();
+        assert_eq!(peer_reputation_tracker.get_reputation_score(&peer_1), 0);
+        assert!(!peer_reputation_tracker.take_ranking_inputs_changed());
+
+        // Record several events for the first peer and verify the score and counts
+        peer_reputation_tracker.record_event(&peer_1, PeerReputationEvent::Timeout);
+        peer_reputation_tracker.record_event(&peer_1, PeerReputationEvent::Timeout);
+        peer_reputation_tracker.record_event(&peer_1, PeerReputationEvent::InvalidPayload);
+        assert_eq!(peer_reputation_tracker.get_reputation_score(&peer_1), 30);
+        assert_eq!(
+            peer_reputation_tracker.get_event_counts(&peer_1),
+            HashMap::from([
+                (PeerReputationEvent::Timeout, 2),
+                (PeerReputationEvent::InvalidPayload, 1)
+            ])
+        );
+        assert!(peer_reputation_tracker.take_ranking_inputs_changed());
+        assert!(!peer_reputation_tracker.take_ranking_inputs_changed());
+
+        // Elapse some time and record an event for the second peer
+        let time_service = time_service.into_mock();
+        time_service.advance(Duration::from_secs(40));
+        peer_reputation_tracker.record_event(&peer_2, PeerReputationEvent::Stall);
+        assert_eq!(peer_reputation_tracker.get_reputation_score(&peer_2), 10);
+
+        // Elapse the window for the first peer's events and verify they expire
+        time_service.advance(Duration::from_secs(21));
+        assert_eq!(peer_reputation_tracker.get_reputation_score(&peer_1), 0);
+        assert_eq!(peer_reputation_tracker.get_reputation_score(&peer_2), 10);
+
+        // Garbage collect the expired events and verify the ranking inputs changed
+        assert!(peer_reputation_tracker.take_ranking_inputs_changed());
+        peer_reputation_tracker.garbage_collect();
+        assert!(peer_reputation_tracker.take_ranking_inputs_changed());
+        assert!(peer_reputation_tracker.get_event_counts(&peer_1).is_empty());
+        assert_eq!(peer_reputation_tracker.peer_events.lock().len(), 1);
+
+        // Garbage collect again and verify nothing changed
+        peer_reputation_tracker.garbage_collect();
+        assert!(!peer_reputation_tracker.take_ranking_inputs_changed());
+    }
+}
@@ -0,0 +1,542 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    peer_activity::PeerActivityTracker, peer_reputation::PeerReputationTracker, subscription,
+};
+use aptos_config::network_id::PeerNetworkId;
+use aptos_network::application::metadata::PeerMetadata;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+/// A peer selector determines which peers the observer should subscribe to.
+/// Peers are returned in priority order (i.e., the first peer is the most
+/// optimal), and the same ordering is used to check subscription optimality.
+pub trait SubscriptionPeerSelector: Send + Sync {
+    /// Sorts the given peers by subscription priority (highest priority first).
+    /// Note: the peers and metadata are borrowed (e.g., from a snapshot that is
+    /// shared across checks), so implementations should avoid cloning them.
+    fn sort_peers_for_subscription(
+        &self,
+        peers_and_metadata: &HashMap<PeerNetworkId, PeerMetadata>,
+    ) -> Vec<PeerNetworkId>;
+}
+
+/// The default peer selector, which prioritizes peers by
+/// distance from the validator set, and then by latency.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DistanceAndLatencyPeerSelector;
+
+impl SubscriptionPeerSelector for DistanceAndLatencyPeerSelector {
+    fn sort_peers_for_subscription(
+        &self,
+        peers_and_metadata: &HashMap<PeerNetworkId, PeerMetadata>,
+    ) -> Vec<PeerNetworkId> {
+        subscription::sort_peers_by_distance_and_latency(peers_and_metadata)
+    }
+}
+
+/// A peer selector that ranks the peers without peer monitoring metadata
+/// (i.e., without a distance from the validators and a ping latency) using
+/// fallback inputs: recent message activity, request round-trip times and
+/// connection age. Peers with monitoring metadata are sorted by the wrapped
+/// selector, and are always preferred over peers without metadata. This
+/// ensures that peer selection still works (with a stable ordering) on
+/// small or private networks where peer monitoring is unavailable.
+pub struct FallbackRankingPeerSelector {
+    // The peer selector used to sort the peers with monitoring metadata
+    peer_selector: Arc<dyn SubscriptionPeerSelector>,
+
+    // The tracker for the locally observed peer activity (i.e., the fallback inputs)
+    peer_activity_tracker: PeerActivityTracker,
+}
+
+impl FallbackRankingPeerSelector {
+    pub fn new(
+        peer_selector: Arc<dyn SubscriptionPeerSelector>,
+        peer_activity_tracker: PeerActivityTracker,
+    ) -> Self {
+        Self {
+            peer_selector,
+            peer_activity_tracker,
+        }
+    }
+}
+
+impl SubscriptionPeerSelector for FallbackRankingPeerSelector {
+    fn sort_peers_for_subscription(
+        &self,
+        peers_and_metadata: &HashMap<PeerNetworkId, PeerMetadata>,
+    ) -> Vec<PeerNetworkId> {
+        // Sort all peers using the wrapped selector, and retain the peers with metadata
+        let mut sorted_peers: Vec<_> = self
+            .peer_selector
+            .sort_peers_for_subscription(peers_and_metadata)
+            .into_iter()
+            .filter(|peer_network_id| {
+                peers_and_metadata
+                    .get(peer_network_id)
+                    .map_or(false, has_peer_monitoring_metadata)
+            })
+            .collect();
+
+        // Sort the peers without metadata using the fallback sort keys
+        let mut fallback_sort_keys: Vec<_> = peers_and_metadata
+            .iter()
+            .filter(|(_, peer_metadata)| !has_peer_monitoring_metadata(peer_metadata))
+            .map(|(peer_network_id, peer_metadata)| {
+                self.peer_activity_tracker
+                    .get_fallback_sort_key(peer_network_id, peer_metadata)
+            })
+            .collect();
+        fallback_sort_keys.sort();
+
+        // Append the peers without metadata (after the peers with metadata)
+        sorted_peers.extend(
+            fallback_sort_keys
+                .into_iter()
+                .map(|(_, _, _, peer_network_id)| peer_network_id),
+        );
+        sorted_peers
+    }
+}
+
+/// A peer selector that groups peers into latency buckets (within each
+/// distance from the validator set), and prefers the nearest bucket. Within
+/// each bucket, peers are ordered by a salted hash of the peer ID (instead of
+/// strictly by latency). This balances the load across similarly close peers
+/// (e.g., peers in the same region), while keeping the ordering stable for
+/// each observer (to avoid churning subscriptions on small latency changes).
+#[derive(Clone, Copy, Debug)]
+pub struct LatencyBucketedPeerSelector {
+    // The width (in seconds) of each latency bucket
+    latency_bucket_width_secs: f64,
+
+    // The salt used to order the peers within each bucket (unique per observer)
+    load_balancing_salt: u64,
+}
+
+impl LatencyBucketedPeerSelector {
+    pub fn new(latency_bucket_width_ms: u64) -> Self {
+        Self {
+            latency_bucket_width_secs: latency_bucket_width_ms as f64 / 1000.0,
+            load_balancing_salt: rand::random(),
+        }
+    }
+
+    /// Returns the latency bucket for the given latency (in seconds)
+    fn get_latency_bucket(&self, latency_secs: f64) -> u64 {
+        (latency_secs / self.latency_bucket_width_secs) as u64
+    }
+
+    /// Returns the load balancing key for the given peer (used to order peers within a bucket)
+    fn get_load_balancing_key(&self, peer_network_id: &PeerNetworkId) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.load_balancing_salt.hash(&mut hasher);
+        peer_network_id.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl SubscriptionPeerSelector for LatencyBucketedPeerSelector {
+    fn sort_peers_for_subscription(
+        &self,
+        peers_and_metadata: &HashMap<PeerNetworkId, PeerMetadata>,
+    ) -> Vec<PeerNetworkId> {
+        // Calculate the sort key for each peer, i.e., (distance, latency bucket, load balancing key)
+        let mut peers_and_sort_keys: Vec<_> = peers_and_metadata
+            .iter()
+            .map(|(peer_network_id, peer_metadata)| {
+                let (distance, latency) =
+                    subscription::get_distance_and_latency_for_peer(peer_network_id, peer_metadata);
+                let sort_key = (
+                    distance,
+                    self.get_latency_bucket(latency),
+                    self.get_load_balancing_key(peer_network_id),
+                );
+                (*peer_network_id, sort_key)
+            })
+            .collect();
+
+        // Sort the peers by the sort keys
+        peers_and_sort_keys.sort_by_key(|(_, sort_key)| *sort_key);
+        peers_and_sort_keys
+            .into_iter()
+            .map(|(peer_network_id, _)| peer_network_id)
+            .collect()
+    }
+}
+
+/// A peer selector that demotes peers with bad reputations (i.e., peers that
+/// recently served invalid payloads, failed proofs, timeouts or stalls). Peers
+/// are ordered by reputation score (lower is better), and peers with the same
+/// score retain the ordering of the wrapped selector. This prevents the observer
+/// from repeatedly re-subscribing to peers that have served bad or slow data.
+pub struct ReputationRankingPeerSelector {
+    // The peer selector used to sort the peers (before applying the reputations)
+    peer_selector: Arc<dyn SubscriptionPeerSelector>,
+
+    // The tracker for the reputation of each peer
+    peer_reputation_tracker: PeerReputationTracker,
+}
+
+impl ReputationRankingPeerSelector {
+    pub fn new(
+        peer_selector: Arc<dyn SubscriptionPeerSelector>,
+        peer_reputation_tracker: PeerReputationTracker,
+    ) -> Self {
+        Self {
+            peer_selector,
+            peer_reputation_tracker,
+        }
+    }
+}
+
+impl SubscriptionPeerSelector for ReputationRankingPeerSelector {
+    fn sort_peers_for_subscription(
+        &self,
+        peers_and_metadata: &HashMap<PeerNetworkId, PeerMetadata>,
+    ) -> Vec<PeerNetworkId> {
+        // Sort the peers using the wrapped selector
+        let sorted_peers = self
+            .peer_selector
+            .sort_peers_for_subscription(peers_and_metadata);
+
+        // Stable sort the peers by reputation score (to preserve the wrapped ordering)
+        let mut peers_and_scores: Vec<_> = sorted_peers
+            .into_iter()
+            .map(|peer_network_id| {
+                let reputation_score = self
+                    .peer_reputation_tracker
+                    .get_reputation_score(&peer_network_id);
+                (peer_network_id, reputation_score)
+            })
+            .collect();
+        peers_and_scores.sort_by_key(|(_, reputation_score)| *reputation_score);
+        peers_and_scores
+            .into_iter()
+            .map(|(peer_network_id, _)| peer_network_id)
+            .collect()
+    }
+}
+
+/// A cache of the sorted candidate peers for subscriptions. The sorted peers
+/// are only recomputed when the peers and metadata change (as tracked by the
+/// given version), or when the cache is explicitly invalidated (e.g., because
+/// the peer filter has changed). This avoids sorting (and cloning) the peer
+/// metadata from scratch at every subscription attempt and optimality check.
+pub struct SortedPeersCache {
+    // The version of the peers and metadata used to sort the cached peers
+    cached_version: Option<u64>,
+
+    // The cached sorted peers (highest priority first)
+    sorted_peers: Arc<[PeerNetworkId]>,
+}
+
+impl SortedPeersCache {
+    pub fn new() -> Self {
+        Self {
+            cached_version: None,
+            sorted_peers: Arc::from(vec![]),
+        }
+    }
+
+    /// Returns the sorted peers for the given version of the peers and metadata.
+    /// If the cache is stale, the peers and metadata are fetched and re-sorted
+    /// using the given peer selector. If the fetch fails, None is returned.
+    pub fn get_sorted_peers(
+        &mut self,
+        version: u64,
+        fetch_peers_and_metadata: impl FnOnce() -> Option<HashMap<PeerNetworkId, PeerMetadata>>,
+        peer_selector: &dyn SubscriptionPeerSelector,
+    ) -> Option<Arc<[PeerNetworkId]>> {
+        // Recompute the sorted peers (if the cache is stale)
+        if self.cached_version != Some(version) {
+            let peers_and_metadata = fetch_peers_and_metadata()?;
+            self.sorted_peers = peer_selector
+                .sort_peers_for_subscription(&peers_and_metadata)
+                .into();
+            self.cached_version = Some(version);
+        }
+
+        Some(self.sorted_peers.clone())
+    }
+
+    /// Invalidates the cache (the sorted peers will be recomputed on the next read)
+    pub fn invalidate(&mut self) {
+        self.cached_version = None;
+    }
+}
+
+impl Default for SortedPeersCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns true iff the given peer has peer monitoring metadata (i.e.,
+/// a distance from the validators or a ping latency) that can be used
+/// for ranking.
+fn has_peer_monitoring_metadata(peer_metadata: &PeerMetadata) -> bool {
+    let peer_monitoring_metadata = peer_metadata.get_peer_monitoring_metadata();
+    peer_monitoring_metadata
+        .latest_network_info_response
+        .is_some()
+        || peer_monitoring_metadata.average_ping_latency_secs.is_some()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::peer_reputation::PeerReputationEvent;
+    use aptos_network::transport::ConnectionMetadata;
+    use aptos_peer_monitoring_service_types::{
+        response::NetworkInformationResponse, PeerMonitoringMetadata,
+    };
+    use aptos_time_service::TimeService;
+    use std::{
+        collections::{BTreeMap, HashSet},
+        sync::atomic::{AtomicU64, Ordering},
+        time::Duration,
+    };
+
+    /// A simple peer selector that counts the number of sorts
+    #[derive(Default)]
+    struct CountingPeerSelector {
+        num_sorts: AtomicU64,
+    }
+
+    impl SubscriptionPeerSelector for CountingPeerSelector {
+        fn sort_peers_for_subscription(
+            &self,
+            peers_and_metadata: &HashMap<PeerNetworkId, PeerMetadata>,
+        ) -> Vec<PeerNetworkId> {
+            self.num_sorts.fetch_add(1, Ordering::Relaxed);
+            let mut sorted_peers: Vec<_> = peers_and_metadata.keys().cloned().collect();
+            sorted_peers.sort();
+            sorted_peers
+        }
+    }
+
+    #[test]
+    fn test_sorted_peers_cache() {
+        // Create the cache, peer selector and peers
+        let mut sorted_peers_cache = SortedPeersCache::new();
+        let peer_selector = CountingPeerSelector::default();
+        let peers_and_metadata = create_peers_and_metadata(5);
+
+        // Verify that the peers are sorted on the first read
+        let sorted_peers = sorted_peers_cache
+            .get_sorted_peers(0, || Some(peers_and_metadata.clone()), &peer_selector)
+            .unwrap();
+        assert_eq!(sorted_peers.len(), 5);
+        assert_eq!(peer_selector.num_sorts.load(Ordering::Relaxed), 1);
+
+        // Verify that the cached peers are returned for the same version (without a fetch)
+        let cached_sorted_peers = sorted_peers_cache
+            .get_sorted_peers(
+                0,
+                || panic!("The peers should not be fetched!"),
+                &peer_selector,
+            )
+            .unwrap();
+        assert_eq!(cached_sorted_peers, sorted_peers);
+        assert_eq!(peer_selector.num_sorts.load(Ordering::Relaxed), 1);
+
+        // Verify that the peers are re-sorted for a new version
+        let new_peers_and_metadata = create_peers_and_metadata(3);
+        let sorted_peers = sorted_peers_cache
+            .get_sorted_peers(1, || Some(new_peers_and_metadata.clone()), &peer_selector)
+            .unwrap();
+        assert_eq!(sorted_peers.len(), 3);
+        assert_eq!(peer_selector.num_sorts.load(Ordering::Relaxed), 2);
+
+        // Invalidate the cache and verify that the peers are re-sorted
+        sorted_peers_cache.invalidate();
+        sorted_peers_cache
+            .get_sorted_peers(1, || Some(new_peers_and_metadata.clone()), &peer_selector)
+            .unwrap();
+        assert_eq!(peer_selector.num_sorts.load(Ordering::Relaxed), 3);
+
+        // Verify that a failed fetch returns None (and doesn't update the cache)
+        sorted_peers_cache.invalidate();
+        assert!(sorted_peers_cache
+            .get_sorted_peers(1, || None, &peer_selector)
+            .is_none());
+        assert_eq!(peer_selector.num_sorts.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_fallback_ranking_peer_selector() {
+        // Create several peers with monitoring metadata
+        let mut peers_and_metadata = HashMap::new();
+        let (nearest_peer, peer_metadata) = create_peer_and_metadata(Some(1), Some(0.1));
+        peers_and_metadata.insert(nearest_peer, peer_metadata);
+        let (further_peer, peer_metadata) = create_peer_and_metadata(Some(2), Some(0.1));
+        peers_and_metadata.insert(further_peer, peer_metadata);
+
+        // Create several peers without monitoring metadata
+        let mut unknown_peers = vec![];
+        for _ in 0..3 {
+            let (unknown_peer, peer_metadata) = create_peer_and_metadata(None, None);
+            peers_and_metadata.insert(unknown_peer, peer_metadata);
+            unknown_peers.push(unknown_peer);
+        }
+
+        // Create a fallback ranking peer selector
+        let peer_activity_tracker = PeerActivityTracker::new(60_000, TimeService::mock());
+        let peer_selector = FallbackRankingPeerSelector::new(
+            Arc::new(DistanceAndLatencyPeerSelector),
+            peer_activity_tracker.clone(),
+        );
+
+        // Record activity for the peers without metadata
+        peer_activity_tracker.record_request_rtt(&unknown_peers[0], Duration::from_millis(30));
+        peer_activity_tracker.record_request_rtt(&unknown_peers[1], Duration::from_millis(10));
+        peer_activity_tracker.record_message_received(&unknown_peers[2]);
+
+        // Verify that the peers with metadata are preferred, followed by the fallback ranking
+        let sorted_peers = peer_selector.sort_peers_for_subscription(&peers_and_metadata);
+        assert_eq!(sorted_peers, vec![
+            nearest_peer,
+            further_peer,
+            unknown_peers[2],
+            unknown_peers[1],
+            unknown_peers[0]
+        ]);
+
+        // Verify that peer selection still works when no peer has metadata
+        peers_and_metadata.remove(&nearest_peer);
+        peers_and_metadata.remove(&further_peer);
+        let sorted_peers = peer_selector.sort_peers_for_subscription(&peers_and_metadata);
+        assert_eq!(sorted_peers, vec![
+            unknown_peers[2],
+            unknown_peers[1],
+            unknown_peers[0]
+        ]);
+    }
+
+    #[test]
+    fn test_reputation_ranking_peer_selector() {
+        // Create several peers (at increasing distances from the validators)
+        let mut peers_and_metadata = HashMap::new();
+        let mut peers = vec![];
+        for distance in 1..=4 {
+            let (peer_network_id, peer_metadata) = create_peer_and_metadata(Some(distance), None);
+            peers_and_metadata.insert(peer_network_id, peer_metadata);
+            peers.push(peer_network_id);
+        }
+
+        // Create a reputation ranking peer selector
+        let time_service = TimeService::mock();
+        let peer_reputation_tracker = PeerReputationTracker::new(60_000, time_service.clone());
+        let peer_selector = ReputationRankingPeerSelector::new(
+            Arc::new(DistanceAndLatencyPeerSelector),
+            peer_reputation_tracker.clone(),
+        );
+
+        // Verify that the wrapped ordering is used when no peer has reputation events
+        let sorted_peers = peer_selector.sort_peers_for_subscription(&peers_and_metadata);
+        assert_eq!(sorted_peers, peers);
+
+        // Record reputation events for the nearest peers
+        peer_reputation_tracker.record_event(&peers[0], PeerReputationEvent::InvalidPayload);
+        peer_reputation_tracker.record_event(&peers[1], PeerReputationEvent::Timeout);
+
+        // Verify that the peers are demoted by reputation score (preserving the wrapped ordering)
+        let sorted_peers = peer_selector.sort_peers_for_subscription(&peers_and_metadata);
+        assert_eq!(sorted_peers, vec![peers[2], peers[3], peers[1], peers[0]]);
+
+        // Elapse the reputation window and verify the original ordering is restored
+        time_service.into_mock().advance(Duration::from_secs(61));
+        let sorted_peers = peer_selector.sort_peers_for_subscription(&peers_and_metadata);
+        assert_eq!(sorted_peers, peers);
+    }
+
+    #[test]
+    fn test_latency_bucketed_peer_selector() {
+        // Create peers in the nearest latency bucket (i.e., 0-50 ms)
+        let mut peers_and_metadata = HashMap::new();
+        let mut nearest_peers = vec![];
+        for latency in [0.001, 0.01, 0.02, 0.03, 0.049] {
+            let (peer_network_id, peer_metadata) = create_peer_and_metadata(Some(1), Some(latency));
+            peers_and_metadata.insert(peer_network_id, peer_metadata);
+            nearest_peers.push(peer_network_id);
+        }
+
+        // Create peers in a further bucket, with a greater distance, and without metadata
+        let (further_peer, peer_metadata) = create_peer_and_metadata(Some(1), Some(0.06));
+        peers_and_metadata.insert(further_peer, peer_metadata);
+        let (distant_peer, peer_metadata) = create_peer_and_metadata(Some(2), Some(0.001));
+        peers_and_metadata.insert(distant_peer, peer_metadata);
+        let (unknown_peer, peer_metadata) = create_peer_and_metadata(None, None);
+        peers_and_metadata.insert(unknown_peer, peer_metadata);
+
+        // Verify that the nearest bucket is preferred (followed by the other peers)
+        let peer_selector = LatencyBucketedPeerSelector::new(50);
+        let sorted_peers = peer_selector.sort_peers_for_subscription(&peers_and_metadata);
+        let mut sorted_nearest_peers = sorted_peers[0..5].to_vec();
+        sorted_nearest_peers.sort();
+        nearest_peers.sort();
+        assert_eq!(sorted_nearest_peers, nearest_peers);
+        assert_eq!(sorted_peers[5..].to_vec(), vec![
+            further_peer,
+            distant_peer,
+            unknown_peer
+        ]);
+
+        // Verify that the ordering is stable for the same selector
+        assert_eq!(
+            peer_selector.sort_peers_for_subscription(&peers_and_metadata),
+            sorted_peers
+        );
+
+        // Verify that selectors with different salts balance the load within the bucket
+        let mut preferred_peers = HashSet::new();
+        for load_balancing_salt in 0..100 {
+            let peer_selector = LatencyBucketedPeerSelector {
+                latency_bucket_width_secs: 0.05,
+                load_balancing_salt,
+            };
+            let sorted_peers = peer_selector.sort_peers_for_subscription(&peers_and_metadata);
+            assert!(nearest_peers.contains(&sorted_peers[0]));
+            preferred_peers.insert(sorted_peers[0]);
+        }
+        assert!(preferred_peers.len() > 1);
+    }
+
+    /// Creates a peer with the given distance and latency metadata
+    fn create_peer_and_metadata(
+        distance_from_validators: Option<u64>,
+        latency: Option<f64>,
+    ) -> (PeerNetworkId, PeerMetadata) {
+        let peer_network_id = PeerNetworkId::random();
+        let network_information_response =
+            distance_from_validators.map(|distance| NetworkInformationResponse {
+                connected_peers: BTreeMap::new(),
+                distance_from_validators: distance,
+            });
+        let peer_metadata = PeerMetadata::new_for_test(
+            ConnectionMetadata::mock(peer_network_id.peer_id()),
+            PeerMonitoringMetadata::new(latency, None, network_information_response, None, None),
+        );
+        (peer_network_id, peer_metadata)
+    }
+
+    /// Creates the given number of peers (with empty metadata)
+    fn create_peers_and_metadata(num_peers: usize) -> HashMap<PeerNetworkId, PeerMetadata> {
+        (0..num_peers)
+            .map(|_| {
+                let peer_network_id = PeerNetworkId::random();
+                let peer_metadata = PeerMetadata::new_for_test(
+                    ConnectionMetadata::mock(peer_network_id.peer_id()),
+                    PeerMonitoringMetadata::new(None, None, None, None, None),
+                );
+                (peer_network_id, peer_metadata)
+            })
+            .collect()
+    }
+}
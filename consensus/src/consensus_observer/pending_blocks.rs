@@ -1,18 +1,34 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::consensus_observer::{
-    logging::{LogEntry, LogSchema},
-    network_message::{CommitDecision, OrderedBlock},
+use crate::{
+    consensus_observer::{
+        error::Error,
+        logging::{LogEntry, LogSchema},
+        network_message::{CommitDecision, OrderedBlock},
+        state_snapshot::PendingBlockDigest,
+    },
+    execution_pipeline::SIG_VERIFY_POOL,
 };
-use aptos_config::config::ConsensusObserverConfig;
-use aptos_consensus_types::common::Round;
+use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
+use aptos_consensus_types::{common::Round, pipelined_block::PipelinedBlock};
+use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
 use aptos_logger::{debug, error, warn};
 use aptos_types::{
     block_info::BlockInfo, epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures,
 };
-use std::{collections::BTreeMap, sync::Arc};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use std::{collections::BTreeMap, mem, sync::Arc};
+
+/// A pending block entry: the ordered block, if the block was verified,
+/// the commit decision (if any) and the peer that sent the block.
+type PendingBlockEntry = (
+    Arc<OrderedBlock>,
+    bool,
+    Option<CommitDecision>,
+    PeerNetworkId,
+);
 
 /// A simple struct to store the block payloads of ordered and committed blocks
 #[derive(Clone)]
@@ -22,9 +38,13 @@ pub struct PendingOrderedBlocks {
 
     // Verified and unverified pending ordered blocks. The key is the epoch and
     // round of the last block in the ordered block. Each entry contains the
-    // block, if the block was verified, and the commit decision (if any).
-    pending_blocks:
-        Arc<Mutex<BTreeMap<(u64, Round), (OrderedBlock, bool, Option<CommitDecision>)>>>,
+    // block, if the block was verified, the commit decision (if any) and the
+    // peer that sent the block (to attribute any verification failures).
+    // The ordered blocks are shared (to avoid copying them on the hot path).
+    pending_blocks: Arc<Mutex<BTreeMap<(u64, Round), PendingBlockEntry>>>,
+
+    // The epoch and round of the highest commit (used to reject stale blocks)
+    highest_committed_block: Arc<Mutex<Option<(u64, Round)>>>,
 }
 
 impl PendingOrderedBlocks {
@@ -32,15 +52,72 @@ impl PendingOrderedBlocks {
         Self {
             consensus_observer_config,
             pending_blocks: Arc::new(Mutex::new(BTreeMap::new())),
+            highest_committed_block: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Removes all pending blocks (verified and unverified), and
+    /// returns the number of blocks that were removed.
+    pub fn clear_all_pending_blocks(&self) -> usize {
+        let mut pending_blocks = self.pending_blocks.lock();
+        let num_pending_blocks = pending_blocks.len();
+        pending_blocks.clear();
+        num_pending_blocks
+    }
+
+    /// Returns true iff the given block (i.e., epoch, round and block ID) is
+    /// contained in a pending ordered block (verified or unverified).
+    pub fn contains_block(&self, epoch: u64, round: Round, block_id: &HashValue) -> bool {
+        // The pending blocks are keyed by the last block, so the first
+        // pending ordered block at (or after) the block must contain it.
+        self.pending_blocks
+            .lock()
+            .range((epoch, round)..)
+            .next()
+            .is_some_and(|(_, (ordered_block, _, _, _))| {
+                ordered_block
+                    .blocks()
+                    .iter()
+                    .any(|block| block.id() == *block_id)
+            })
+    }
+
+    /// Returns the first verified pending block that conflicts with the given
+    /// (verified) ordered block, i.e., a different block for the same epoch and
+    /// round (if any). Unverified pending blocks are ignored: anyone can forge an
+    /// unverified block, so only conflicts between two verified blocks indicate
+    /// that the sender of the ordered block is equivocating.
+    pub fn get_conflicting_block(&self, ordered_block: &OrderedBlock) -> Option<BlockInfo> {
+        // The pending blocks are keyed by the last block, so only the pending
+        // ordered blocks at (or after) the first block can contain conflicts.
+        let first_block = ordered_block.first_block();
+        let pending_blocks = self.pending_blocks.lock();
+        for (_, (pending_ordered_block, verified_ordered_proof, _, _)) in
+            pending_blocks.range((first_block.epoch(), first_block.round())..)
+        {
+            if !*verified_ordered_proof {
+                continue; // Unverified blocks can't prove an equivocation
+            }
+            for pending_block in pending_ordered_block.blocks() {
+                let is_conflicting = ordered_block.blocks().iter().any(|block| {
+                    block.epoch() == pending_block.epoch()
+                        && block.round() == pending_block.round()
+                        && block.id() != pending_block.id()
+                });
+                if is_conflicting {
+                    return Some(pending_block.block_info());
+                }
+            }
         }
+        None
     }
 
     /// Returns a copy of the verified pending blocks
     pub fn get_all_verified_pending_blocks(
         &self,
-    ) -> BTreeMap<(u64, Round), (OrderedBlock, Option<CommitDecision>)> {
+    ) -> BTreeMap<(u64, Round), (Arc<OrderedBlock>, Option<CommitDecision>)> {
         let mut verified_pending_blocks = BTreeMap::new();
-        for (key, (ordered_block, verified_ordered_proof, commit_decision)) in
+        for (key, (ordered_block, verified_ordered_proof, commit_decision, _)) in
             self.pending_blocks.lock().iter()
         {
             if *verified_ordered_proof {
@@ -51,6 +128,22 @@ impl PendingOrderedBlocks {
         verified_pending_blocks
     }
 
+    /// Returns the number of pending ordered blocks (verified and unverified)
+    pub fn get_num_pending_blocks(&self) -> usize {
+        self.pending_blocks.lock().len()
+    }
+
+    /// Returns the digests of all pending blocks (verified and unverified),
+    /// ordered by epoch and round.
+    pub fn get_pending_block_digests(&self) -> Vec<PendingBlockDigest> {
+        self.pending_blocks
+            .lock()
+            .values()
+            .flat_map(|(ordered_block, _, _, _)| ordered_block.blocks().clone())
+            .map(|block| PendingBlockDigest::new(&block.block_info()))
+            .collect()
+    }
+
     /// Returns the last pending ordered block (if any). We take into
     /// account verified and unverified pending blocks (to ensure we're
     /// able to buffer blocks across epoch boundaries).
@@ -58,13 +151,17 @@ impl PendingOrderedBlocks {
         self.pending_blocks
             .lock()
             .last_key_value()
-            .map(|(_, (ordered_block, _, _))| ordered_block.last_block().block_info())
+            .map(|(_, (ordered_block, _, _, _))| ordered_block.last_block().block_info())
     }
 
     /// Returns the verified pending ordered block (if any)
-    pub fn get_verified_pending_block(&self, epoch: u64, round: Round) -> Option<OrderedBlock> {
+    pub fn get_verified_pending_block(
+        &self,
+        epoch: u64,
+        round: Round,
+    ) -> Option<Arc<OrderedBlock>> {
         self.pending_blocks.lock().get(&(epoch, round)).and_then(
-            |(ordered_block, verified_ordered_proof, _)| {
+            |(ordered_block, verified_ordered_proof, _, _)| {
                 if *verified_ordered_proof {
                     Some(ordered_block.clone())
                 } else {
@@ -74,12 +171,77 @@ impl PendingOrderedBlocks {
         )
     }
 
-    /// Inserts the given ordered block into the pending blocks. This function
-    /// assumes the block has already been checked to extend the current pending blocks.
-    pub fn insert_ordered_block(&self, ordered_block: OrderedBlock, verified_ordered_proof: bool) {
+    /// Returns the commit decision of the verified pending block with
+    /// the given epoch and round (if the block and decision exist).
+    pub fn get_commit_decision(&self, epoch: u64, round: Round) -> Option<CommitDecision> {
+        self.pending_blocks.lock().get(&(epoch, round)).and_then(
+            |(_, verified_ordered_proof, commit_decision, _)| {
+                if *verified_ordered_proof {
+                    commit_decision.clone()
+                } else {
+                    None
+                }
+            },
+        )
+    }
+
+    /// Returns the block with the given block info, if it is contained in a
+    /// verified pending ordered block. This is useful for verifying block
+    /// payloads against the (verified) blocks that reference them.
+    pub fn get_verified_pipelined_block(
+        &self,
+        block_info: &BlockInfo,
+    ) -> Option<Arc<PipelinedBlock>> {
+        // The pending blocks are keyed by the last block, so the first
+        // pending ordered block at (or after) the block must contain it.
+        let pending_blocks = self.pending_blocks.lock();
+        let (_, (ordered_block, verified_ordered_proof, _, _)) = pending_blocks
+            .range((block_info.epoch(), block_info.round())..)
+            .next()?;
+        if !*verified_ordered_proof {
+            return None;
+        }
+        ordered_block
+            .blocks()
+            .iter()
+            .find(|block| block.id() == block_info.id())
+            .cloned()
+    }
+
+    /// Inserts the given ordered block (sent by the given peer) into the pending blocks.
+    /// This function assumes the block has already been checked to extend the current
+    /// pending blocks. Stale blocks (i.e., at or below the highest commit), duplicate
+    /// blocks and blocks that exceed the maximum are rejected. Returns true iff the
+    /// block was inserted.
+    pub fn insert_ordered_block(
+        &self,
+        peer_network_id: PeerNetworkId,
+        ordered_block: Arc<OrderedBlock>,
+        verified_ordered_proof: bool,
+    ) -> bool {
+        // Get the epoch and round of the last ordered block
+        let last_block = ordered_block.last_block();
+        let last_block_epoch = last_block.epoch();
+        let last_block_round = last_block.round();
+
+        // Verify that the block is not stale (i.e., already committed)
+        if let Some(highest_committed_block) = *self.highest_committed_block.lock() {
+            if (last_block_epoch, last_block_round) <= highest_committed_block {
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Ignoring stale ordered block: {:?}. Highest commit (epoch, round): {:?}.",
+                        ordered_block.proof_block_info(),
+                        highest_committed_block
+                    ))
+                );
+                return false;
+            }
+        }
+
         // Verify that the number of pending blocks doesn't exceed the maximum
+        let mut pending_blocks = self.pending_blocks.lock();
         let max_num_pending_blocks = self.consensus_observer_config.max_num_pending_blocks as usize;
-        if self.pending_blocks.lock().len() >= max_num_pending_blocks {
+        if pending_blocks.len() >= max_num_pending_blocks {
             warn!(
                 LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
                     "Exceeded the maximum number of pending blocks: {:?}. Block verification: {:?}, block: {:?}.",
@@ -88,7 +250,18 @@ impl PendingOrderedBlocks {
                     ordered_block.proof_block_info()
                 ))
             );
-            return; // Drop the block if we've exceeded the maximum
+            return false; // Drop the block if we've exceeded the maximum
+        }
+
+        // Verify that the block is not a duplicate of an existing pending block
+        if pending_blocks.contains_key(&(last_block_epoch, last_block_round)) {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Ignoring duplicate ordered block: {:?}",
+                    ordered_block.proof_block_info()
+                ))
+            );
+            return false;
         }
 
         // Otherwise, we can add the block to the pending blocks
@@ -100,30 +273,48 @@ impl PendingOrderedBlocks {
             ))
         );
 
-        // Get the epoch and round of the last ordered block
-        let last_block = ordered_block.last_block();
-        let last_block_epoch = last_block.epoch();
-        let last_block_round = last_block.round();
-
         // Insert the pending block
-        self.pending_blocks.lock().insert(
+        pending_blocks.insert(
             (last_block_epoch, last_block_round),
-            (ordered_block, verified_ordered_proof, None),
+            (ordered_block, verified_ordered_proof, None, peer_network_id),
         );
+        true
     }
 
     /// Removes the pending blocks for the given commit ledger info. This will
     /// remove all blocks up to (and including) the epoch and round of the
     /// commit. Note: this function must remove both verified and unverified
-    /// blocks (to support state sync commits).
-    pub fn remove_blocks_for_commit(&self, commit_ledger_info: &LedgerInfoWithSignatures) {
+    /// blocks (to support state sync commits). Returns the removed blocks.
+    pub fn remove_blocks_for_commit(
+        &self,
+        commit_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Vec<Arc<OrderedBlock>> {
+        // Update the highest commit (to reject any stale blocks)
+        let commit_epoch = commit_ledger_info.ledger_info().epoch();
+        let commit_round = commit_ledger_info.commit_info().round();
+        {
+            let mut highest_committed_block = self.highest_committed_block.lock();
+            if highest_committed_block.map_or(true, |highest_commit| {
+                highest_commit < (commit_epoch, commit_round)
+            }) {
+                *highest_committed_block = Some((commit_epoch, commit_round));
+            }
+        }
+
         // Determine the epoch and round to split off
-        let split_off_epoch = commit_ledger_info.ledger_info().epoch();
-        let split_off_round = commit_ledger_info.commit_info().round().saturating_add(1);
+        let split_off_epoch = commit_epoch;
+        let split_off_round = commit_round.saturating_add(1);
 
         // Remove the blocks from the pending ordered blocks
         let mut pending_blocks = self.pending_blocks.lock();
-        *pending_blocks = pending_blocks.split_off(&(split_off_epoch, split_off_round));
+        let remaining_blocks = pending_blocks.split_off(&(split_off_epoch, split_off_round));
+        let removed_blocks = mem::replace(&mut *pending_blocks, remaining_blocks);
+
+        // Return the removed blocks
+        removed_blocks
+            .into_values()
+            .map(|(ordered_block, _, _, _)| ordered_block)
+            .collect()
     }
 
     /// Updates the commit decision of the pending ordered block (if found).
@@ -135,7 +326,7 @@ impl PendingOrderedBlocks {
 
         // Update the commit decision for the verified pending blocks
         let mut pending_blocks = self.pending_blocks.lock();
-        if let Some((_, verified_ordered_proof, existing_commit_decision)) =
+        if let Some((_, verified_ordered_proof, existing_commit_decision, _)) =
             pending_blocks.get_mut(&(commit_decision_epoch, commit_decision_round))
         {
             if *verified_ordered_proof {
@@ -154,57 +345,79 @@ impl PendingOrderedBlocks {
 
     /// Verifies the pending blocks against the given epoch state.
     /// If verification is successful, blocks are marked as verified.
-    pub fn verify_pending_blocks(&self, epoch_state: &EpochState) {
+    /// All unverified blocks of the epoch are verified in a single batch
+    /// (in parallel), which shortens the catch-up window after a sync. Each
+    /// proof is verified on its own: the multi-signatures are never summed
+    /// (unweighted sums would allow invalid signatures to cancel out).
+    /// If a proof fails verification, the peer that sent the block and the
+    /// error are returned (so that the peer can be held accountable).
+    pub fn verify_pending_blocks(
+        &self,
+        epoch_state: &EpochState,
+    ) -> Option<(PeerNetworkId, Error)> {
         // Get the current epoch
         let current_epoch = epoch_state.epoch;
 
-        // Go through all the pending blocks and verify them
-        let mut failed_verification_round = None;
-        for ((epoch, round), (ordered_block, verified_ordered_proof, _)) in
-            self.pending_blocks.lock().iter_mut()
-        {
-            // Check if we can return early (BtreeMaps are sorted by key)
-            if *epoch > current_epoch {
-                return;
-            }
+        // Gather the unverified pending blocks for the current epoch
+        let unverified_blocks: Vec<((u64, Round), Arc<OrderedBlock>, PeerNetworkId)> = self
+            .pending_blocks
+            .lock()
+            .range((current_epoch, 0)..=(current_epoch, Round::MAX))
+            .filter(|(_, (_, verified_ordered_proof, _, _))| !verified_ordered_proof)
+            .map(|(key, (ordered_block, _, _, peer_network_id))| {
+                (*key, ordered_block.clone(), *peer_network_id)
+            })
+            .collect();
+        if unverified_blocks.is_empty() {
+            return None; // There's nothing to verify
+        }
 
-            // If the block is not verified, attempt to verify it
-            if *epoch == current_epoch && !(*verified_ordered_proof) {
-                match ordered_block.verify_ordered_proof(epoch_state) {
-                    Ok(_) => {
-                        // Mark the block as verified
-                        *verified_ordered_proof = true;
-                    },
-                    Err(error) => {
-                        // Log the verification failure
-                        error!(
-                            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
-                                "Failed to verify ordered block: {}. Error: {:?}",
-                                ordered_block.last_block().block_info(),
-                                error
-                            ))
-                        );
+        // Verify the ordered proofs of all unverified blocks in one batch
+        let verification_results: Vec<_> = SIG_VERIFY_POOL.install(|| {
+            unverified_blocks
+                .par_iter()
+                .map(|(_, ordered_block, _)| ordered_block.verify_ordered_proof(epoch_state))
+                .collect()
+        });
 
-                        // Note the failure and break early
-                        failed_verification_round = Some(*round);
-                        break;
-                    },
-                }
+        // Mark the blocks as verified (up until the first verification failure)
+        let mut pending_blocks = self.pending_blocks.lock();
+        for ((key, ordered_block, peer_network_id), verification_result) in
+            unverified_blocks.iter().zip(verification_results)
+        {
+            match verification_result {
+                Ok(_) => {
+                    // Mark the block as verified (if it still exists)
+                    if let Some((_, verified_ordered_proof, _, _)) = pending_blocks.get_mut(key) {
+                        *verified_ordered_proof = true;
+                    }
+                },
+                Err(error) => {
+                    // Log the verification failure
+                    error!(
+                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                            "Failed to verify ordered block: {} (sent by peer: {}). Error: {:?}",
+                            ordered_block.last_block().block_info(),
+                            peer_network_id,
+                            error
+                        ))
+                    );
+
+                    // Remove all blocks after (and including) the failure
+                    pending_blocks.split_off(key);
+                    return Some((*peer_network_id, error));
+                },
             }
         }
 
-        // If verification failed, remove all blocks after (and including) the failure
-        if let Some(failed_round) = failed_verification_round {
-            self.pending_blocks
-                .lock()
-                .split_off(&(current_epoch, failed_round));
-        }
+        None
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::consensus_observer::payload_store::BlockPayloadStore;
     use aptos_consensus_types::{
         block::Block,
         block_data::{BlockData, BlockType},
@@ -212,13 +425,139 @@ mod test {
         quorum_cert::QuorumCert,
     };
     use aptos_crypto::HashValue;
+    use aptos_time_service::TimeService;
     use aptos_types::{
-        aggregate_signature::AggregateSignature,
+        aggregate_signature::{AggregateSignature, PartialSignatures},
         ledger_info::LedgerInfo,
         transaction::Version,
         validator_signer::ValidatorSigner,
         validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier},
     };
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+
+    // Useful constants for the property-based tests
+    const MAX_NUM_PROPTEST_OPERATIONS: usize = 200;
+    const MAX_NUM_PROPTEST_PAYLOADS: usize = 8;
+    const MAX_NUM_PROPTEST_PENDING_BLOCKS: usize = 10;
+    const NUM_PROPTEST_BLOCKS: usize = 20;
+
+    /// An operation applied to the pending blocks and payload store (by the observer)
+    #[derive(Clone, Debug)]
+    enum ObserverOperation {
+        InsertOrderedBlock(usize, bool), // The block index, and if the proof was verified
+        InsertBlockPayload(usize),       // The block index
+        ProcessCommitDecision(usize),    // The block index
+    }
+
+    /// Returns a strategy for interleaved (and possibly reordered and duplicated) operations
+    fn arb_observer_operations() -> impl Strategy<Value = Vec<ObserverOperation>> {
+        proptest::collection::vec(
+            prop_oneof![
+                (0..NUM_PROPTEST_BLOCKS, any::<bool>()).prop_map(|(index, verified)| {
+                    ObserverOperation::InsertOrderedBlock(index, verified)
+                }),
+                (0..NUM_PROPTEST_BLOCKS).prop_map(ObserverOperation::InsertBlockPayload),
+                (0..NUM_PROPTEST_BLOCKS).prop_map(ObserverOperation::ProcessCommitDecision),
+            ],
+            0..MAX_NUM_PROPTEST_OPERATIONS,
+        )
+    }
+
+    #[test]
+    pub fn test_clear_all_pending_blocks() {
+        // Create new pending ordered blocks
+        let pending_ordered_blocks = PendingOrderedBlocks::new(ConsensusObserverConfig::default());
+
+        // Insert several verified and unverified blocks
+        let current_epoch = 0;
+        create_and_add_pending_blocks(&pending_ordered_blocks, 10, current_epoch, true);
+        create_and_add_pending_blocks(&pending_ordered_blocks, 5, current_epoch + 1, false);
+
+        // Clear the pending blocks and verify all blocks were removed
+        assert_eq!(pending_ordered_blocks.clear_all_pending_blocks(), 15);
+        assert_eq!(get_num_pending_blocks(&pending_ordered_blocks), 0);
+        assert!(pending_ordered_blocks.get_last_pending_block().is_none());
+
+        // Verify that clearing the (empty) pending blocks is a no-op
+        assert_eq!(pending_ordered_blocks.clear_all_pending_blocks(), 0);
+    }
+
+    #[test]
+    pub fn test_contains_block() {
+        // Create new pending ordered blocks
+        let pending_ordered_blocks = PendingOrderedBlocks::new(ConsensusObserverConfig::default());
+
+        // Insert several verified and unverified blocks
+        let current_epoch = 0;
+        let verified_blocks =
+            create_and_add_pending_blocks(&pending_ordered_blocks, 10, current_epoch, true);
+        let unverified_blocks =
+            create_and_add_pending_blocks(&pending_ordered_blocks, 5, current_epoch + 1, false);
+
+        // Verify that all the pending blocks are contained
+        for ordered_block in verified_blocks.iter().chain(unverified_blocks.iter()) {
+            for block in ordered_block.blocks() {
+                assert!(pending_ordered_blocks.contains_block(
+                    block.epoch(),
+                    block.round(),
+                    &block.id()
+                ));
+            }
+        }
+
+        // Verify that a block with a different ID is not contained
+        let first_block = verified_blocks[0].first_block();
+        assert!(!pending_ordered_blocks.contains_block(
+            first_block.epoch(),
+            first_block.round(),
+            &HashValue::random()
+        ));
+    }
+
+    #[test]
+    pub fn test_get_conflicting_block() {
+        // Create new pending ordered blocks
+        let pending_ordered_blocks = PendingOrderedBlocks::new(ConsensusObserverConfig::default());
+
+        // Insert several verified and unverified blocks
+        let current_epoch = 0;
+        let verified_blocks =
+            create_and_add_pending_blocks(&pending_ordered_blocks, 10, current_epoch, true);
+        let unverified_blocks =
+            create_and_add_pending_blocks(&pending_ordered_blocks, 5, current_epoch + 1, false);
+
+        // Verify that the pending blocks don't conflict with themselves
+        for ordered_block in verified_blocks.iter().chain(unverified_blocks.iter()) {
+            assert!(pending_ordered_blocks
+                .get_conflicting_block(ordered_block)
+                .is_none());
+        }
+
+        // Verify that blocks for new rounds (or epochs) don't conflict
+        let new_round_block = create_ordered_block(current_epoch, 10);
+        assert!(pending_ordered_blocks
+            .get_conflicting_block(&new_round_block)
+            .is_none());
+        let new_epoch_block = create_ordered_block(current_epoch + 2, 0);
+        assert!(pending_ordered_blocks
+            .get_conflicting_block(&new_epoch_block)
+            .is_none());
+
+        // Verify that different blocks for verified rounds conflict
+        let conflicting_block = create_ordered_block(current_epoch, 3);
+        assert_eq!(
+            pending_ordered_blocks.get_conflicting_block(&conflicting_block),
+            Some(verified_blocks[3].first_block().block_info())
+        );
+
+        // Verify that different blocks for unverified rounds don't conflict
+        // (an unverified block may be forged, so it can't prove an equivocation).
+        let unverified_round_block = create_ordered_block(current_epoch + 1, 2);
+        assert!(pending_ordered_blocks
+            .get_conflicting_block(&unverified_round_block)
+            .is_none());
+    }
 
     #[test]
     pub fn test_get_last_pending_block() {
@@ -268,7 +607,7 @@ mod test {
         pending_ordered_blocks
             .pending_blocks
             .lock()
-            .retain(|_, (_, verified_ordered_proof, _)| *verified_ordered_proof);
+            .retain(|_, (_, verified_ordered_proof, _, _)| *verified_ordered_proof);
 
         // Verify the last pending block is the verified block with the highest round
         assert_eq!(
@@ -333,6 +672,55 @@ mod test {
         }
     }
 
+    #[test]
+    pub fn test_get_verified_pipelined_block() {
+        // Create new pending ordered blocks
+        let pending_ordered_blocks = PendingOrderedBlocks::new(ConsensusObserverConfig::default());
+
+        // Insert several verified blocks for the current epoch
+        let current_epoch = 0;
+        let verified_blocks =
+            create_and_add_pending_blocks(&pending_ordered_blocks, 10, current_epoch, true);
+
+        // Verify that all blocks in the verified pending blocks can be retrieved
+        for verified_block in &verified_blocks {
+            for block in verified_block.blocks() {
+                let pipelined_block = pending_ordered_blocks
+                    .get_verified_pipelined_block(&block.block_info())
+                    .unwrap();
+                assert_eq!(pipelined_block.id(), block.id());
+            }
+        }
+
+        // Verify that a block with an unknown ID cannot be retrieved
+        let block_info = verified_blocks[0].first_block().block_info();
+        let unknown_block_info = BlockInfo::new(
+            block_info.epoch(),
+            block_info.round(),
+            HashValue::random(),
+            block_info.executed_state_id(),
+            block_info.version(),
+            block_info.timestamp_usecs(),
+            None,
+        );
+        assert!(pending_ordered_blocks
+            .get_verified_pipelined_block(&unknown_block_info)
+            .is_none());
+
+        // Insert several unverified blocks for the next epoch
+        let unverified_blocks =
+            create_and_add_pending_blocks(&pending_ordered_blocks, 10, current_epoch + 1, false);
+
+        // Verify that the blocks in the unverified pending blocks cannot be retrieved
+        for unverified_block in &unverified_blocks {
+            for block in unverified_block.blocks() {
+                assert!(pending_ordered_blocks
+                    .get_verified_pipelined_block(&block.block_info())
+                    .is_none());
+            }
+        }
+    }
+
     #[test]
     pub fn test_insert_ordered_block_limit() {
         // Create a consensus observer config with a maximum of 10 pending blocks
@@ -382,6 +770,72 @@ mod test {
         assert_eq!(num_pending_blocks, max_num_pending_blocks);
     }
 
+    #[test]
+    pub fn test_insert_stale_and_duplicate_blocks() {
+        // Create new pending ordered blocks
+        let pending_ordered_blocks = PendingOrderedBlocks::new(ConsensusObserverConfig::default());
+
+        // Insert several verified blocks for the current epoch
+        let epoch = 10;
+        let num_pending_blocks = 10;
+        let pending_blocks =
+            create_and_add_pending_blocks(&pending_ordered_blocks, num_pending_blocks, epoch, true);
+
+        // Verify that duplicate blocks are rejected (and the existing blocks are retained)
+        for pending_block in &pending_blocks {
+            let duplicate_block =
+                create_ordered_block(epoch, pending_block.proof_block_info().round());
+            assert!(!pending_ordered_blocks.insert_ordered_block(
+                PeerNetworkId::random(),
+                duplicate_block,
+                false
+            ));
+        }
+        let all_verified_blocks = pending_ordered_blocks.get_all_verified_pending_blocks();
+        assert_eq!(all_verified_blocks.len(), num_pending_blocks);
+
+        // Remove the pending blocks for a commit (in the middle of the pending blocks)
+        let commit_block_info = pending_blocks[4].last_block().block_info();
+        let commit_ledger_info = LedgerInfoWithSignatures::new(
+            LedgerInfo::new(commit_block_info, HashValue::random()),
+            AggregateSignature::empty(),
+        );
+        pending_ordered_blocks.remove_blocks_for_commit(&commit_ledger_info);
+        assert_eq!(get_num_pending_blocks(&pending_ordered_blocks), 5);
+
+        // Verify that stale blocks (at or below the commit) are rejected
+        for round in 0..5 {
+            let stale_block = create_ordered_block(epoch, round);
+            assert!(!pending_ordered_blocks.insert_ordered_block(
+                PeerNetworkId::random(),
+                stale_block,
+                true
+            ));
+        }
+        let stale_block = create_ordered_block(epoch - 1, 100);
+        assert!(!pending_ordered_blocks.insert_ordered_block(
+            PeerNetworkId::random(),
+            stale_block,
+            true
+        ));
+        assert_eq!(get_num_pending_blocks(&pending_ordered_blocks), 5);
+
+        // Verify that new blocks are still inserted
+        let new_block = create_ordered_block(epoch, num_pending_blocks as Round);
+        assert!(pending_ordered_blocks.insert_ordered_block(
+            PeerNetworkId::random(),
+            new_block,
+            true
+        ));
+        let new_epoch_block = create_ordered_block(epoch + 1, 0);
+        assert!(pending_ordered_blocks.insert_ordered_block(
+            PeerNetworkId::random(),
+            new_epoch_block,
+            false
+        ));
+        assert_eq!(get_num_pending_blocks(&pending_ordered_blocks), 7);
+    }
+
     #[test]
     pub fn test_remove_blocks_for_commit() {
         // Create new pending ordered blocks
@@ -426,9 +880,15 @@ mod test {
         ));
 
         // Remove the pending blocks for the commit decision
-        pending_ordered_blocks.remove_blocks_for_commit(commit_decision.commit_proof());
+        let removed_blocks =
+            pending_ordered_blocks.remove_blocks_for_commit(commit_decision.commit_proof());
 
-        // Verify the first verified block was removed
+        // Verify the first verified block was removed (and returned)
+        assert_eq!(removed_blocks.len(), 1);
+        assert_eq!(
+            removed_blocks[0].proof_block_info(),
+            first_verified_block.proof_block_info()
+        );
         let all_verified_blocks = pending_ordered_blocks.get_all_verified_pending_blocks();
         assert_eq!(all_verified_blocks.len(), num_verified_blocks - 1);
         assert!(!all_verified_blocks.contains_key(&(
@@ -537,7 +997,14 @@ mod test {
         verify_commit_decision(
             &pending_ordered_blocks,
             &first_verified_block_info,
-            commit_decision,
+            commit_decision.clone(),
+        );
+        assert_eq!(
+            pending_ordered_blocks.get_commit_decision(
+                first_verified_block_info.epoch(),
+                first_verified_block_info.round()
+            ),
+            Some(commit_decision)
         );
 
         // Create a commit decision for the last pending block
@@ -578,7 +1045,7 @@ mod test {
 
         // Verify the commit decision was not updated
         let pending_blocks = pending_ordered_blocks.pending_blocks.lock();
-        let (_, _, commit_decision) = pending_blocks
+        let (_, _, commit_decision, _) = pending_blocks
             .get(&(next_epoch, last_unverified_block_info.round()))
             .unwrap();
         assert!(commit_decision.is_none());
@@ -697,8 +1164,10 @@ mod test {
         let validator_verified = ValidatorVerifier::new(vec![validator_consensus_info]);
         let epoch_state = EpochState::new(next_epoch, validator_verified);
 
-        // Verify the pending blocks for the next epoch
-        pending_ordered_blocks.verify_pending_blocks(&epoch_state);
+        // Verify the pending blocks for the next epoch (and ensure verification fails)
+        assert!(pending_ordered_blocks
+            .verify_pending_blocks(&epoch_state)
+            .is_some());
 
         // Ensure the unverified pending blocks were not inserted
         let all_verified_blocks = pending_ordered_blocks.get_all_verified_pending_blocks();
@@ -709,45 +1178,285 @@ mod test {
         assert_eq!(num_pending_blocks, num_verified_blocks);
     }
 
+    #[test]
+    fn test_verify_pending_blocks_batch() {
+        // Create new pending ordered blocks
+        let pending_ordered_blocks = PendingOrderedBlocks::new(ConsensusObserverConfig::default());
+
+        // Create an epoch state (with a single validator)
+        let epoch = 10;
+        let validator_signer = ValidatorSigner::random(None);
+        let validator_consensus_info = ValidatorConsensusInfo::new(
+            validator_signer.author(),
+            validator_signer.public_key(),
+            100,
+        );
+        let epoch_state = EpochState::new(
+            epoch,
+            ValidatorVerifier::new(vec![validator_consensus_info]),
+        );
+
+        // Insert several unverified blocks (with valid ordered proofs)
+        let num_valid_blocks = 10;
+        for round in 0..num_valid_blocks {
+            let ordered_block = create_signed_ordered_block(&validator_signer, &epoch_state, round);
+            pending_ordered_blocks.insert_ordered_block(
+                PeerNetworkId::random(),
+                ordered_block,
+                false,
+            );
+        }
+
+        // Verify the pending blocks and ensure they were all verified
+        pending_ordered_blocks.verify_pending_blocks(&epoch_state);
+        let all_verified_blocks = pending_ordered_blocks.get_all_verified_pending_blocks();
+        assert_eq!(all_verified_blocks.len(), num_valid_blocks as usize);
+
+        // Insert several more unverified blocks (with an invalid proof in the middle)
+        let invalid_round = num_valid_blocks + 5;
+        let invalid_peer_network_id = PeerNetworkId::random();
+        for round in num_valid_blocks..num_valid_blocks * 2 {
+            let (peer_network_id, ordered_block) = if round == invalid_round {
+                (invalid_peer_network_id, create_ordered_block(epoch, round))
+            } else {
+                (
+                    PeerNetworkId::random(),
+                    create_signed_ordered_block(&validator_signer, &epoch_state, round),
+                )
+            };
+            pending_ordered_blocks.insert_ordered_block(peer_network_id, ordered_block, false);
+        }
+
+        // Verify the pending blocks and ensure the sender of the invalid proof is returned
+        let (failed_peer_network_id, _) = pending_ordered_blocks
+            .verify_pending_blocks(&epoch_state)
+            .unwrap();
+        assert_eq!(failed_peer_network_id, invalid_peer_network_id);
+
+        // Ensure only the blocks before the invalid proof remain
+        let all_verified_blocks = pending_ordered_blocks.get_all_verified_pending_blocks();
+        assert_eq!(all_verified_blocks.len(), invalid_round as usize);
+        assert_eq!(
+            get_num_pending_blocks(&pending_ordered_blocks),
+            invalid_round as usize
+        );
+    }
+
+    #[test]
+    fn test_verify_pending_blocks_cancelling_signatures() {
+        // Create new pending ordered blocks
+        let pending_ordered_blocks = PendingOrderedBlocks::new(ConsensusObserverConfig::default());
+
+        // Create an epoch state (with a single validator)
+        let epoch = 10;
+        let validator_signer = ValidatorSigner::random(None);
+        let validator_consensus_info = ValidatorConsensusInfo::new(
+            validator_signer.author(),
+            validator_signer.public_key(),
+            100,
+        );
+        let epoch_state = EpochState::new(
+            epoch,
+            ValidatorVerifier::new(vec![validator_consensus_info]),
+        );
+
+        // Create two blocks with valid proofs, and swap their multi-signatures. Each
+        // proof is now invalid, but the sum of the signatures is unchanged (i.e., the
+        // invalid signatures cancel each other out in an unweighted aggregate).
+        let ordered_blocks: Vec<_> = (0..2)
+            .map(|round| create_signed_ordered_block(&validator_signer, &epoch_state, round))
+            .collect();
+        for (ordered_block, swapped_block) in ordered_blocks.iter().zip(ordered_blocks.iter().rev())
+        {
+            let ordered_proof = LedgerInfoWithSignatures::new(
+                ordered_block.ordered_proof().ledger_info().clone(),
+                swapped_block.ordered_proof().signatures().clone(),
+            );
+            let ordered_block = OrderedBlock::new(ordered_block.blocks().clone(), ordered_proof);
+            pending_ordered_blocks.insert_ordered_block(
+                PeerNetworkId::random(),
+                Arc::new(ordered_block),
+                false,
+            );
+        }
+
+        // Verify the pending blocks and ensure that none of the blocks were verified
+        pending_ordered_blocks.verify_pending_blocks(&epoch_state);
+        assert!(pending_ordered_blocks
+            .get_all_verified_pending_blocks()
+            .is_empty());
+        assert_eq!(get_num_pending_blocks(&pending_ordered_blocks), 0);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn test_pending_blocks_and_payload_store_invariants(
+            operations in arb_observer_operations()
+        ) {
+            // Create the pending blocks and payload store
+            let consensus_observer_config = ConsensusObserverConfig {
+                max_num_pending_blocks: MAX_NUM_PROPTEST_PENDING_BLOCKS as u64,
+                max_num_payload_store_entries: MAX_NUM_PROPTEST_PAYLOADS as u64,
+                ..ConsensusObserverConfig::default()
+            };
+            let pending_ordered_blocks =
+                PendingOrderedBlocks::new(consensus_observer_config.clone());
+            let mut block_payload_store =
+                BlockPayloadStore::new(consensus_observer_config, TimeService::mock());
+
+            // Create a chain of ordered blocks (the round of each block is its index)
+            let epoch = 10;
+            let ordered_blocks: Vec<_> = (0..NUM_PROPTEST_BLOCKS)
+                .map(|index| create_ordered_block(epoch, index as Round))
+                .collect();
+
+            // Track the verification status of the pending blocks and the committed rounds
+            let mut verified_pending_rounds: HashMap<Round, bool> = HashMap::new();
+            let mut highest_committed_round: Option<Round> = None;
+            let mut committed_blocks = vec![];
+
+            // Apply each operation (in the same way as the observer)
+            for operation in operations {
+                match operation {
+                    ObserverOperation::InsertOrderedBlock(index, verified_ordered_proof) => {
+                        // Determine if the block is stale, a duplicate, or if the pending blocks are full
+                        let round = index as Round;
+                        let stale_block = is_committed_round(highest_committed_round, round);
+                        let duplicate_block = verified_pending_rounds.contains_key(&round);
+                        let pending_blocks_full = get_num_pending_blocks(&pending_ordered_blocks)
+                            >= MAX_NUM_PROPTEST_PENDING_BLOCKS;
+
+                        // Insert the block and verify that it is only inserted if valid
+                        let inserted = pending_ordered_blocks.insert_ordered_block(PeerNetworkId::random(), ordered_blocks[index].clone(),
+                            verified_ordered_proof,
+                        );
+                        prop_assert_eq!(
+                            inserted,
+                            !stale_block && !duplicate_block && !pending_blocks_full
+                        );
+                        if inserted {
+                            verified_pending_rounds.insert(round, verified_ordered_proof);
+                        }
+                    },
+                    ObserverOperation::InsertBlockPayload(index) => {
+                        // Determine if the payload is stale or a duplicate
+                        let round = index as Round;
+                        let blocks = ordered_blocks[index].blocks();
+                        let stale_payload = is_committed_round(highest_committed_round, round);
+                        let duplicate_payload = block_payload_store.all_payloads_exist(blocks);
+                        let num_block_payloads = block_payload_store.get_occupancy().num_payloads;
+
+                        // Insert the block payload and verify that stale and duplicate
+                        // payloads are rejected by the payload store.
+                        let block_info = ordered_blocks[index].last_block().block_info();
+                        block_payload_store.insert_block_payload(block_info, vec![], None);
+                        if stale_payload || duplicate_payload {
+                            prop_assert_eq!(
+                                block_payload_store.get_occupancy().num_payloads,
+                                num_block_payloads
+                            );
+                        }
+                        if stale_payload {
+                            prop_assert!(!block_payload_store.all_payloads_exist(blocks));
+                        }
+                    },
+                    ObserverOperation::ProcessCommitDecision(index) => {
+                        // Only verified pending blocks (with all payloads) can be committed
+                        let round = index as Round;
+                        let pending_block =
+                            match pending_ordered_blocks.get_verified_pending_block(epoch, round) {
+                                Some(pending_block) => pending_block,
+                                None => continue,
+                            };
+                        if !block_payload_store.all_payloads_exist(pending_block.blocks()) {
+                            continue;
+                        }
+
+                        // Update the commit decision for the pending block
+                        let commit_decision = CommitDecision::new(create_ledger_info(epoch, round));
+                        pending_ordered_blocks.update_commit_decision(&commit_decision);
+                        verify_commit_decision(
+                            &pending_ordered_blocks,
+                            pending_block.proof_block_info(),
+                            commit_decision.clone(),
+                        );
+
+                        // Commit all verified blocks up to the decision (like the commit callbacks)
+                        for (_, (ordered_block, _)) in pending_ordered_blocks
+                            .get_all_verified_pending_blocks()
+                            .range(..=(epoch, round))
+                        {
+                            block_payload_store.remove_blocks(ordered_block.blocks());
+                            committed_blocks.extend(ordered_block.blocks().clone());
+                        }
+                        block_payload_store
+                            .remove_payloads_for_commit(commit_decision.commit_proof().commit_info());
+                        pending_ordered_blocks
+                            .remove_blocks_for_commit(commit_decision.commit_proof());
+
+                        // Update the committed rounds
+                        highest_committed_round = Some(round);
+                        verified_pending_rounds.retain(|pending_round, _| *pending_round > round);
+                    },
+                }
+
+                // Verify that all committed rounds have been removed
+                let pending_block_keys: Vec<_> =
+                    pending_ordered_blocks.pending_blocks.lock().keys().cloned().collect();
+                for (_, round) in pending_block_keys.iter() {
+                    prop_assert!(!is_committed_round(highest_committed_round, *round));
+                }
+                prop_assert!(pending_block_keys.len() <= MAX_NUM_PROPTEST_PENDING_BLOCKS);
+
+                // Verify that no unverified block can be finalized
+                let verified_pending_blocks =
+                    pending_ordered_blocks.get_all_verified_pending_blocks();
+                for (_, round) in verified_pending_blocks.keys() {
+                    prop_assert_eq!(verified_pending_rounds.get(round), Some(&true));
+                }
+                for (round, verified_ordered_proof) in verified_pending_rounds.iter() {
+                    let verified_pending_block =
+                        pending_ordered_blocks.get_verified_pending_block(epoch, *round);
+                    prop_assert_eq!(verified_pending_block.is_some(), *verified_ordered_proof);
+                }
+
+                // Verify that the payload store never exceeds its configured limit, and
+                // that the payloads of committed blocks are always removed from the store.
+                let num_block_payloads = block_payload_store
+                    .get_payload_store_backend()
+                    .get_num_payloads();
+                prop_assert!(num_block_payloads <= MAX_NUM_PROPTEST_PAYLOADS);
+                let occupancy = block_payload_store.get_occupancy();
+                prop_assert!(occupancy.num_payloads <= occupancy.max_num_payloads);
+                prop_assert_eq!(occupancy.max_num_payloads, MAX_NUM_PROPTEST_PAYLOADS as u64);
+                for committed_block in committed_blocks.iter() {
+                    let committed_block = [committed_block.clone()];
+                    prop_assert!(!block_payload_store.all_payloads_exist(&committed_block));
+                }
+            }
+        }
+    }
+
     /// Creates and adds the specified number of pending blocks to the pending ordered blocks
     fn create_and_add_pending_blocks(
         pending_ordered_blocks: &PendingOrderedBlocks,
         num_pending_blocks: usize,
         epoch: u64,
         verified_ordered_proof: bool,
-    ) -> Vec<OrderedBlock> {
+    ) -> Vec<Arc<OrderedBlock>> {
         let mut pending_blocks = vec![];
         for i in 0..num_pending_blocks {
-            // Create a new block info
-            let block_info = BlockInfo::new(
-                epoch,
-                i as aptos_types::block_info::Round,
-                HashValue::random(),
-                HashValue::random(),
-                i as Version,
-                i as u64,
-                None,
-            );
-
-            // Create a pipelined block
-            let block_data = BlockData::new_for_testing(
-                block_info.epoch(),
-                block_info.round(),
-                block_info.timestamp_usecs(),
-                QuorumCert::dummy(),
-                BlockType::Genesis,
-            );
-            let block = Block::new_for_testing(block_info.id(), block_data, None);
-            let pipelined_block = Arc::new(PipelinedBlock::new_ordered(block));
-
             // Create an ordered block
-            let blocks = vec![pipelined_block];
-            let ordered_proof = create_ledger_info(epoch, i as Round);
-            let ordered_block = OrderedBlock::new(blocks, ordered_proof);
+            let ordered_block = create_ordered_block(epoch, i as Round);
 
             // Insert the ordered block into the pending ordered blocks
-            pending_ordered_blocks
-                .insert_ordered_block(ordered_block.clone(), verified_ordered_proof);
+            pending_ordered_blocks.insert_ordered_block(
+                PeerNetworkId::random(),
+                ordered_block.clone(),
+                verified_ordered_proof,
+            );
 
             // Add the ordered block to the pending blocks
             pending_blocks.push(ordered_block);
@@ -756,6 +1465,35 @@ mod test {
         pending_blocks
     }
 
+    /// Creates and returns an ordered block (with a single block) for the specified epoch and round
+    fn create_ordered_block(epoch: u64, round: Round) -> Arc<OrderedBlock> {
+        // Create a new block info
+        let block_info = BlockInfo::new(
+            epoch,
+            round,
+            HashValue::random(),
+            HashValue::random(),
+            round as Version,
+            round,
+            None,
+        );
+
+        // Create a pipelined block
+        let block_data = BlockData::new_for_testing(
+            block_info.epoch(),
+            block_info.round(),
+            block_info.timestamp_usecs(),
+            QuorumCert::dummy(),
+            BlockType::Genesis,
+        );
+        let block = Block::new_for_testing(block_info.id(), block_data, None);
+        let pipelined_block = Arc::new(PipelinedBlock::new_ordered(block));
+
+        // Create the ordered block
+        let ordered_proof = create_ledger_info(epoch, round);
+        Arc::new(OrderedBlock::new(vec![pipelined_block], ordered_proof))
+    }
+
     /// Creates and returns a new ledger info with the specified epoch and round
     fn create_ledger_info(epoch: u64, round: Round) -> LedgerInfoWithSignatures {
         LedgerInfoWithSignatures::new(
@@ -767,11 +1505,45 @@ mod test {
         )
     }
 
+    /// Creates and returns an ordered block for the specified round, with an
+    /// ordered proof that is signed by the given signer (for the epoch state).
+    fn create_signed_ordered_block(
+        validator_signer: &ValidatorSigner,
+        epoch_state: &EpochState,
+        round: Round,
+    ) -> Arc<OrderedBlock> {
+        // Create an ordered block (with an unsigned proof)
+        let ordered_block = create_ordered_block(epoch_state.epoch, round);
+
+        // Sign the ordered proof
+        let ledger_info = ordered_block.ordered_proof().ledger_info().clone();
+        let mut partial_signatures = PartialSignatures::empty();
+        partial_signatures.add_signature(
+            validator_signer.author(),
+            validator_signer.sign(&ledger_info).unwrap(),
+        );
+        let multi_signature = epoch_state
+            .verifier
+            .aggregate_signatures(&partial_signatures)
+            .unwrap();
+
+        // Create the ordered block with the signed proof
+        Arc::new(OrderedBlock::new(
+            ordered_block.blocks().clone(),
+            LedgerInfoWithSignatures::new(ledger_info, multi_signature),
+        ))
+    }
+
     /// Returns the number of pending blocks (both verified and unverified)
     fn get_num_pending_blocks(pending_ordered_blocks: &PendingOrderedBlocks) -> usize {
         pending_ordered_blocks.pending_blocks.lock().len()
     }
 
+    /// Returns true iff the given round has been committed
+    fn is_committed_round(highest_committed_round: Option<Round>, round: Round) -> bool {
+        highest_committed_round.map_or(false, |committed_round| round <= committed_round)
+    }
+
     /// Verifies the commit decision for the specified block info
     fn verify_commit_decision(
         pending_ordered_blocks: &PendingOrderedBlocks,
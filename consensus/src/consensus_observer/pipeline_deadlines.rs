@@ -0,0 +1,214 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::metrics;
+use aptos_infallible::Mutex;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::block_info::{BlockInfo, Round};
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The types of handoffs from the observer to the execution pipeline
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PipelineHandoff {
+    OrderedBlock,   // An ordered block sent via `finalize_order`
+    CommitDecision, // A commit decision sent via the commit path
+}
+
+impl PipelineHandoff {
+    /// Returns all pipeline handoffs
+    pub fn all() -> [PipelineHandoff; 2] {
+        [
+            PipelineHandoff::OrderedBlock,
+            PipelineHandoff::CommitDecision,
+        ]
+    }
+
+    /// Returns a summary label for the handoff
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            PipelineHandoff::OrderedBlock => metrics::FINALIZE_ORDERED_BLOCK_LABEL,
+            PipelineHandoff::CommitDecision => metrics::FORWARD_COMMIT_DECISION_LABEL,
+        }
+    }
+}
+
+/// The processing deadline of a single outstanding handoff
+struct HandoffDeadline {
+    deadline: Instant,
+    deadline_missed: bool, // Whether the missed deadline has already been recorded
+}
+
+/// A tracker for the processing deadlines of blocks and commits handed off
+/// to the execution pipeline. Each handoff is given a deadline by which the
+/// pipeline should commit the block. This allows the observer (e.g., the
+/// progress checks) to distinguish a pipeline that is still working from a
+/// pipeline that has missed its deadlines, and allows metrics to track misses.
+#[derive(Clone)]
+pub struct PipelineDeadlineTracker {
+    // The duration the pipeline has to process each handoff
+    processing_deadline: Duration,
+
+    // The outstanding handoffs (indexed by epoch, round and handoff type)
+    outstanding_handoffs: Arc<Mutex<BTreeMap<(u64, Round, PipelineHandoff), HandoffDeadline>>>,
+
+    // The time service (used to calculate and check the deadlines)
+    time_service: TimeService,
+}
+
+impl PipelineDeadlineTracker {
+    pub fn new(processing_deadline_ms: u64, time_service: TimeService) -> Self {
+        Self {
+            processing_deadline: Duration::from_millis(processing_deadline_ms),
+            outstanding_handoffs: Arc::new(Mutex::new(BTreeMap::new())),
+            time_service,
+        }
+    }
+
+    /// Attaches a processing deadline to the handoff of the given block
+    /// (the deadline is relative to the time of the handoff).
+    pub fn add_handoff(&self, handoff: PipelineHandoff, block_info: &BlockInfo) {
+        let handoff_deadline = HandoffDeadline {
+            deadline: self.time_service.now() + self.processing_deadline,
+            deadline_missed: false,
+        };
+        self.outstanding_handoffs.lock().insert(
+            (block_info.epoch(), block_info.round(), handoff),
+            handoff_deadline,
+        );
+    }
+
+    /// Checks the deadlines of all outstanding handoffs. Any newly missed
+    /// deadlines are recorded, and the number of overdue handoffs is returned.
+    pub fn check_deadlines(&self) -> usize {
+        let time_now = self.time_service.now();
+
+        // Identify the overdue handoffs (and record the newly missed deadlines)
+        let mut num_overdue_handoffs = BTreeMap::new();
+        for ((_, _, handoff), handoff_deadline) in self.outstanding_handoffs.lock().iter_mut() {
+            if time_now > handoff_deadline.deadline {
+                if !handoff_deadline.deadline_missed {
+                    handoff_deadline.deadline_missed = true;
+                    metrics::increment_pipeline_deadline_miss(handoff.get_label());
+                }
+                *num_overdue_handoffs.entry(*handoff).or_insert(0) += 1;
+            }
+        }
+
+        // Update the overdue handoff metrics
+        for handoff in PipelineHandoff::all() {
+            let num_overdue = num_overdue_handoffs.get(&handoff).copied().unwrap_or(0);
+            metrics::set_gauge_with_label(
+                &metrics::OBSERVER_PIPELINE_OVERDUE_HANDOFFS,
+                handoff.get_label(),
+                num_overdue,
+            );
+        }
+
+        num_overdue_handoffs.values().sum::<i64>() as usize
+    }
+
+    /// Clears all outstanding handoffs (e.g., when the pipeline is reset)
+    pub fn clear(&self) {
+        self.outstanding_handoffs.lock().clear();
+    }
+
+    /// Completes all outstanding handoffs up to (and including) the given
+    /// committed block. Any deadlines missed (but not yet recorded) are recorded.
+    pub fn complete_handoffs(&self, commit_info: &BlockInfo) {
+        let time_now = self.time_service.now();
+
+        // Remove the completed handoffs
+        let mut outstanding_handoffs = self.outstanding_handoffs.lock();
+        let remaining_handoffs = outstanding_handoffs.split_off(&(
+            commit_info.epoch(),
+            commit_info.round() + 1,
+            PipelineHandoff::OrderedBlock,
+        ));
+        let completed_handoffs = std::mem::replace(&mut *outstanding_handoffs, remaining_handoffs);
+
+        // Record any missed deadlines
+        for ((_, _, handoff), handoff_deadline) in completed_handoffs {
+            if time_now > handoff_deadline.deadline && !handoff_deadline.deadline_missed {
+                metrics::increment_pipeline_deadline_miss(handoff.get_label());
+            }
+        }
+    }
+
+    /// Returns the number of outstanding handoffs
+    pub fn get_num_outstanding_handoffs(&self) -> usize {
+        self.outstanding_handoffs.lock().len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_complete_handoffs() {
+        // Create a deadline tracker
+        let deadline_tracker = PipelineDeadlineTracker::new(1000, TimeService::mock());
+
+        // Add several handoffs (for both handoff types)
+        let epoch = 10;
+        for round in 0..10 {
+            let block_info = BlockInfo::random_with_epoch(epoch, round);
+            deadline_tracker.add_handoff(PipelineHandoff::OrderedBlock, &block_info);
+            deadline_tracker.add_handoff(PipelineHandoff::CommitDecision, &block_info);
+        }
+        assert_eq!(deadline_tracker.get_num_outstanding_handoffs(), 20);
+
+        // Complete the handoffs up to round 4 and verify the remaining handoffs
+        deadline_tracker.complete_handoffs(&BlockInfo::random_with_epoch(epoch, 4));
+        assert_eq!(deadline_tracker.get_num_outstanding_handoffs(), 10);
+
+        // Complete the handoffs for a future epoch and verify all handoffs are removed
+        deadline_tracker.complete_handoffs(&BlockInfo::random_with_epoch(epoch + 1, 0));
+        assert_eq!(deadline_tracker.get_num_outstanding_handoffs(), 0);
+    }
+
+    #[test]
+    fn test_check_deadlines() {
+        // Create a deadline tracker
+        let processing_deadline_ms = 1000;
+        let time_service = TimeService::mock();
+        let deadline_tracker =
+            PipelineDeadlineTracker::new(processing_deadline_ms, time_service.clone());
+
+        // Add several handoffs and verify that none are overdue
+        let epoch = 5;
+        for round in 0..5 {
+            let block_info = BlockInfo::random_with_epoch(epoch, round);
+            deadline_tracker.add_handoff(PipelineHandoff::OrderedBlock, &block_info);
+        }
+        assert_eq!(deadline_tracker.check_deadlines(), 0);
+
+        // Elapse less than the deadline and add another handoff
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance(Duration::from_millis(processing_deadline_ms / 2));
+        let block_info = BlockInfo::random_with_epoch(epoch, 5);
+        deadline_tracker.add_handoff(PipelineHandoff::CommitDecision, &block_info);
+        assert_eq!(deadline_tracker.check_deadlines(), 0);
+
+        // Elapse the deadline of the first handoffs and verify they are overdue
+        mock_time_service.advance(Duration::from_millis(processing_deadline_ms / 2 + 1));
+        assert_eq!(deadline_tracker.check_deadlines(), 5);
+
+        // Complete some of the overdue handoffs and verify the remaining overdue handoffs
+        deadline_tracker.complete_handoffs(&BlockInfo::random_with_epoch(epoch, 2));
+        assert_eq!(deadline_tracker.check_deadlines(), 2);
+
+        // Elapse the deadline of the last handoff and verify it is overdue
+        mock_time_service.advance(Duration::from_millis(processing_deadline_ms));
+        assert_eq!(deadline_tracker.check_deadlines(), 3);
+
+        // Clear the handoffs and verify none are overdue
+        deadline_tracker.clear();
+        assert_eq!(deadline_tracker.check_deadlines(), 0);
+        assert_eq!(deadline_tracker.get_num_outstanding_handoffs(), 0);
+    }
+}
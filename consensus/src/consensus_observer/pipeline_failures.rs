@@ -0,0 +1,91 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::metrics;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// A simple tracker for the consecutive failures to send ordered blocks
+/// and commit decisions to the execution pipeline. Once the number of
+/// consecutive failures reaches the maximum, the failures should be
+/// escalated (e.g., by resetting the pipeline and state syncing).
+#[derive(Clone)]
+pub struct PipelineFailureTracker {
+    // The maximum number of consecutive failures before escalation is required
+    max_consecutive_failures: u64,
+
+    // The number of consecutive failures (since the last success or escalation)
+    num_consecutive_failures: Arc<AtomicU64>,
+}
+
+impl PipelineFailureTracker {
+    pub fn new(max_consecutive_failures: u64) -> Self {
+        Self {
+            max_consecutive_failures,
+            num_consecutive_failures: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Returns the number of consecutive failures
+    pub fn get_num_consecutive_failures(&self) -> u64 {
+        self.num_consecutive_failures.load(Ordering::Relaxed)
+    }
+
+    /// Records a failure to send the given item (e.g., an ordered
+    /// block or commit decision) to the execution pipeline.
+    pub fn record_failure(&self, failure_label: &str) {
+        self.num_consecutive_failures
+            .fetch_add(1, Ordering::Relaxed);
+        metrics::increment_pipeline_failure(failure_label);
+    }
+
+    /// Records a successful send to the execution pipeline (this
+    /// resets the number of consecutive failures).
+    pub fn record_success(&self) {
+        self.reset();
+    }
+
+    /// Resets the number of consecutive failures (e.g., after an escalation)
+    pub fn reset(&self) {
+        self.num_consecutive_failures.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns true iff the consecutive failures should be escalated
+    pub fn should_escalate(&self) -> bool {
+        self.get_num_consecutive_failures() >= self.max_consecutive_failures
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_failure_escalation() {
+        // Create a pipeline failure tracker
+        let max_consecutive_failures = 5;
+        let pipeline_failure_tracker = PipelineFailureTracker::new(max_consecutive_failures);
+
+        // Record several failures and verify that no escalation is required
+        for _ in 0..max_consecutive_failures - 1 {
+            pipeline_failure_tracker.record_failure(metrics::FINALIZE_ORDERED_BLOCK_LABEL);
+            assert!(!pipeline_failure_tracker.should_escalate());
+        }
+
+        // Record a success and verify that the failures are reset
+        pipeline_failure_tracker.record_success();
+        assert_eq!(pipeline_failure_tracker.get_num_consecutive_failures(), 0);
+
+        // Record the maximum number of consecutive failures and verify escalation is required
+        for _ in 0..max_consecutive_failures {
+            pipeline_failure_tracker.record_failure(metrics::FORWARD_COMMIT_DECISION_LABEL);
+        }
+        assert!(pipeline_failure_tracker.should_escalate());
+
+        // Reset the tracker and verify that no escalation is required
+        pipeline_failure_tracker.reset();
+        assert!(!pipeline_failure_tracker.should_escalate());
+    }
+}
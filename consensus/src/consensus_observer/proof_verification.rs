@@ -0,0 +1,563 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::{
+        logging::{LogEntry, LogSchema},
+        message_rate_limiter::InboundRateLimiter,
+        metrics,
+        network_events::NetworkMessage,
+        network_message::{ConsensusObserverDirectSend, ConsensusObserverMessage},
+        state_tracker::ObserverStateTracker,
+    },
+    execution_pipeline::SIG_VERIFY_POOL,
+};
+use aptos_config::network_id::PeerNetworkId;
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::warn;
+use aptos_types::{epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures};
+use futures::{
+    future::{self, BoxFuture},
+    stream::FuturesOrdered,
+    FutureExt, Stream, StreamExt,
+};
+use futures_channel::oneshot;
+use mini_moka::sync::Cache;
+use std::{
+    collections::{HashMap, HashSet},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+// Constants for the verified proof cache
+const VERIFIED_PROOF_CACHE_CAPACITY: u64 = 1_000;
+const VERIFIED_PROOF_CACHE_TTL_SECS: u64 = 60;
+
+/// A cache of the proofs (i.e., ledger infos and signatures) that were already
+/// verified off the observer loop. Each proof is identified by the digest of its
+/// serialized bytes (including the signatures), so a cached verification can
+/// never be reused for a proof with different (e.g., invalid) signatures.
+#[derive(Clone)]
+pub struct VerifiedProofCache {
+    // The digests of the verified proofs
+    verified_proof_digests: Cache<HashValue, ()>,
+}
+
+impl VerifiedProofCache {
+    pub fn new() -> Self {
+        Self {
+            verified_proof_digests: Cache::builder()
+                .max_capacity(VERIFIED_PROOF_CACHE_CAPACITY)
+                .time_to_live(Duration::from_secs(VERIFIED_PROOF_CACHE_TTL_SECS))
+                .build(),
+        }
+    }
+
+    /// Returns true iff the given proof was already verified
+    pub fn contains(&self, proof: &LedgerInfoWithSignatures) -> bool {
+        get_proof_digest(proof).map_or(false, |proof_digest| {
+            self.verified_proof_digests.contains_key(&proof_digest)
+        })
+    }
+
+    /// Records the given proof as verified
+    fn insert(&self, proof: &LedgerInfoWithSignatures) {
+        if let Some(proof_digest) = get_proof_digest(proof) {
+            self.verified_proof_digests.insert(proof_digest, ());
+        }
+    }
+}
+
+impl Default for VerifiedProofCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The checks applied to the sender of each message before its proof is verified
+/// off the observer loop, i.e., the sender must be a subscription peer, and must be
+/// within its rate limit. This ensures that unsolicited (or flooding) peers can't
+/// consume the signature verification pool. Note: the checks are read-only, and
+/// messages that fail them are still rejected by the observer (which owns the
+/// subscriptions and consumes the rate limit tokens).
+#[derive(Clone)]
+pub struct ProofVerificationFilter {
+    // The peers that the observer is currently subscribed to
+    subscription_peers: Arc<Mutex<HashSet<PeerNetworkId>>>,
+
+    // The inbound rate limiter (shared with the observer, if enabled)
+    inbound_rate_limiter: Option<Arc<Mutex<InboundRateLimiter>>>,
+}
+
+impl ProofVerificationFilter {
+    pub fn new(inbound_rate_limiter: Option<Arc<Mutex<InboundRateLimiter>>>) -> Self {
+        Self {
+            subscription_peers: Arc::new(Mutex::new(HashSet::new())),
+            inbound_rate_limiter,
+        }
+    }
+
+    /// Updates the peers that the observer is currently subscribed to
+    pub fn update_subscription_peers(&self, subscription_peers: &[PeerNetworkId]) {
+        *self.subscription_peers.lock() = subscription_peers.iter().copied().collect();
+    }
+
+    /// Returns true iff the proof of a message from the given peer should be
+    /// verified, given the number of the peer's messages already being verified.
+    fn should_verify_message(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        num_pending_messages: usize,
+    ) -> bool {
+        // Only verify the messages of subscription peers
+        if !self.subscription_peers.lock().contains(peer_network_id) {
+            return false;
+        }
+
+        // Only verify the message if the peer is within its rate limit (including
+        // the messages that are already being verified, but not yet processed).
+        self.inbound_rate_limiter
+            .as_ref()
+            .map_or(true, |inbound_rate_limiter| {
+                inbound_rate_limiter
+                    .lock()
+                    .has_available_tokens(peer_network_id, num_pending_messages + 1)
+            })
+    }
+}
+
+/// A stream adapter that verifies the proofs of the ordered blocks and commit
+/// decisions (for the current epoch) in parallel, on the signature verification
+/// pool, before the messages reach the observer loop. The verified proofs are
+/// recorded in the cache (so the observer doesn't have to verify them again),
+/// and the messages are always returned in arrival order (i.e., the stream acts
+/// as an ordering sequencer). The number of in-flight verifications is bounded,
+/// so the stream applies backpressure to the network once the limit is reached.
+/// Proofs are only verified for messages that pass the verification filter
+/// (i.e., the subscription and rate limit checks), so work is never spawned
+/// on behalf of unsolicited peers. Note: messages with invalid proofs (or that
+/// fail the filter) are still returned (unverified), so that the observer can
+/// reject them (and handle the failure).
+pub struct ProofVerificationStream<S> {
+    // The inner stream of network messages
+    network_messages: S,
+
+    // Whether the inner stream has terminated
+    network_messages_terminated: bool,
+
+    // The messages currently being verified (in arrival order). Each message
+    // is returned with its sender, iff the sender's pending count was incremented.
+    pending_messages:
+        FuturesOrdered<BoxFuture<'static, (Option<PeerNetworkId>, Option<NetworkMessage>)>>,
+
+    // The number of messages currently being verified for each peer
+    num_pending_messages_per_peer: HashMap<PeerNetworkId, usize>,
+
+    // The maximum number of messages being verified at any time
+    max_pending_messages: usize,
+
+    // The observer state tracker (used to read the current epoch state)
+    observer_state_tracker: ObserverStateTracker,
+
+    // The cache of the verified proofs (shared with the observer)
+    verified_proof_cache: VerifiedProofCache,
+
+    // The checks applied to the message senders before verification
+    verification_filter: ProofVerificationFilter,
+}
+
+impl<S: Stream<Item = NetworkMessage> + Unpin> ProofVerificationStream<S> {
+    pub fn new(
+        network_messages: S,
+        max_pending_messages: usize,
+        observer_state_tracker: ObserverStateTracker,
+        verified_proof_cache: VerifiedProofCache,
+        verification_filter: ProofVerificationFilter,
+    ) -> Self {
+        Self {
+            network_messages,
+            network_messages_terminated: false,
+            pending_messages: FuturesOrdered::new(),
+            num_pending_messages_per_peer: HashMap::new(),
+            max_pending_messages,
+            observer_state_tracker,
+            verified_proof_cache,
+            verification_filter,
+        }
+    }
+
+    /// Returns a future that resolves to the given message once its proof
+    /// (if any) has been verified on the signature verification pool.
+    fn verify_message(
+        &mut self,
+        network_message: NetworkMessage,
+    ) -> BoxFuture<'static, (Option<PeerNetworkId>, Option<NetworkMessage>)> {
+        // Only verify the proofs for the current epoch (if it has started)
+        let peer_network_id = network_message.peer_network_id;
+        let epoch_state = match self.observer_state_tracker.try_epoch_state() {
+            Some(epoch_state) => epoch_state,
+            None => return future::ready((None, Some(network_message))).boxed(),
+        };
+        if get_proof_for_epoch(&network_message, epoch_state.epoch).is_none() {
+            return future::ready((None, Some(network_message))).boxed();
+        }
+
+        // Only verify the proof if the sender passes the subscription and rate limit checks
+        let num_pending_messages = self
+            .num_pending_messages_per_peer
+            .get(&peer_network_id)
+            .copied()
+            .unwrap_or(0);
+        if !self
+            .verification_filter
+            .should_verify_message(&peer_network_id, num_pending_messages)
+        {
+            metrics::increment_parallel_proof_verifications(
+                metrics::PROOF_VERIFICATION_SKIPPED_LABEL,
+            );
+            return future::ready((None, Some(network_message))).boxed();
+        }
+        *self
+            .num_pending_messages_per_peer
+            .entry(peer_network_id)
+            .or_default() += 1;
+
+        // Verify the proof on the signature verification pool
+        let (verification_sender, verification_receiver) = oneshot::channel();
+        let verified_proof_cache = self.verified_proof_cache.clone();
+        SIG_VERIFY_POOL.spawn(move || {
+            verify_message_proof(&network_message, &epoch_state, &verified_proof_cache);
+            let _ = verification_sender.send(network_message);
+        });
+
+        // Return the message once the verification completes
+        async move {
+            match verification_receiver.await {
+                Ok(network_message) => (Some(peer_network_id), Some(network_message)),
+                Err(error) => {
+                    warn!(
+                        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                            "Failed to receive the message after proof verification! Error: {:?}",
+                            error
+                        ))
+                    );
+                    (Some(peer_network_id), None)
+                },
+            }
+        }
+        .boxed()
+    }
+
+    /// Decrements the number of messages being verified for the given peer
+    fn remove_pending_message(&mut self, peer_network_id: &PeerNetworkId) {
+        if let Some(num_pending_messages) =
+            self.num_pending_messages_per_peer.get_mut(peer_network_id)
+        {
+            *num_pending_messages = num_pending_messages.saturating_sub(1);
+            if *num_pending_messages == 0 {
+                self.num_pending_messages_per_peer.remove(peer_network_id);
+            }
+        }
+    }
+}
+
+impl<S: Stream<Item = NetworkMessage> + Unpin> Stream for ProofVerificationStream<S> {
+    type Item = NetworkMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Start verifying the new messages (until the in-flight limit is reached)
+        while !self.network_messages_terminated
+            && self.pending_messages.len() < self.max_pending_messages
+        {
+            match self.network_messages.poll_next_unpin(cx) {
+                Poll::Ready(Some(network_message)) => {
+                    let pending_message = self.verify_message(network_message);
+                    self.pending_messages.push_back(pending_message);
+                },
+                Poll::Ready(None) => self.network_messages_terminated = true,
+                Poll::Pending => break,
+            }
+        }
+
+        // Return the next verified message (in arrival order)
+        let next_message = self.pending_messages.poll_next_unpin(cx);
+        if let Poll::Ready(Some((Some(peer_network_id), _))) = &next_message {
+            let peer_network_id = *peer_network_id;
+            self.remove_pending_message(&peer_network_id);
+        }
+        match next_message {
+            Poll::Ready(Some((_, Some(network_message)))) => Poll::Ready(Some(network_message)),
+            Poll::Ready(Some((_, None))) => {
+                // The message was lost (skip it, and poll again to start verifying new messages)
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            },
+            Poll::Ready(None) if self.network_messages_terminated => Poll::Ready(None),
+            Poll::Ready(None) | Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Returns the proof of the given message iff the message is an ordered
+/// block or commit decision with a proof for the given epoch.
+fn get_proof_for_epoch(
+    network_message: &NetworkMessage,
+    epoch: u64,
+) -> Option<&LedgerInfoWithSignatures> {
+    let proof = match &network_message.consensus_observer_message {
+        ConsensusObserverMessage::DirectSend(ConsensusObserverDirectSend::OrderedBlock(
+            ordered_block,
+        )) => ordered_block.ordered_proof(),
+        ConsensusObserverMessage::DirectSend(ConsensusObserverDirectSend::CommitDecision(
+            commit_decision,
+        )) => commit_decision.commit_proof(),
+        _ => return None,
+    };
+    (proof.ledger_info().epoch() == epoch).then_some(proof)
+}
+
+/// Returns the digest of the given proof (i.e., the ledger info and signatures)
+fn get_proof_digest(proof: &LedgerInfoWithSignatures) -> Option<HashValue> {
+    bcs::to_bytes(proof)
+        .ok()
+        .map(|proof_bytes| HashValue::sha3_256_of(&proof_bytes))
+}
+
+/// Verifies the proof of the given message against the epoch state,
+/// and records the proof in the cache if the verification succeeds.
+fn verify_message_proof(
+    network_message: &NetworkMessage,
+    epoch_state: &EpochState,
+    verified_proof_cache: &VerifiedProofCache,
+) {
+    // Verify the proof of the message
+    let (proof, verification_result) = match &network_message.consensus_observer_message {
+        ConsensusObserverMessage::DirectSend(ConsensusObserverDirectSend::OrderedBlock(
+            ordered_block,
+        )) => (
+            ordered_block.ordered_proof(),
+            ordered_block.verify_ordered_proof(epoch_state),
+        ),
+        ConsensusObserverMessage::DirectSend(ConsensusObserverDirectSend::CommitDecision(
+            commit_decision,
+        )) => (
+            commit_decision.commit_proof(),
+            commit_decision.verify_commit_proof(epoch_state),
+        ),
+        _ => return, // The message has no proof
+    };
+
+    // Cache the proof if the verification succeeded
+    if verification_result.is_ok() {
+        verified_proof_cache.insert(proof);
+        metrics::increment_parallel_proof_verifications(metrics::PROOF_VERIFICATION_VALID_LABEL);
+    } else {
+        metrics::increment_parallel_proof_verifications(metrics::PROOF_VERIFICATION_INVALID_LABEL);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_config::config::ConsensusObserverConfig;
+    use aptos_time_service::TimeService;
+    use aptos_types::{
+        aggregate_signature::{AggregateSignature, PartialSignatures},
+        block_info::BlockInfo,
+        ledger_info::LedgerInfo,
+        validator_signer::ValidatorSigner,
+        validator_verifier::ValidatorVerifier,
+    };
+    use futures::stream;
+
+    #[tokio::test]
+    async fn test_proof_verification_stream() {
+        // Create an epoch state with a single validator
+        let epoch = 10;
+        let validator_signer = ValidatorSigner::random(None);
+        let epoch_state = Arc::new(EpochState::new(
+            epoch,
+            ValidatorVerifier::new_single(validator_signer.author(), validator_signer.public_key()),
+        ));
+
+        // Create an observer state tracker for the epoch
+        let observer_state_tracker = ObserverStateTracker::new(LedgerInfoWithSignatures::new(
+            LedgerInfo::new(BlockInfo::random_with_epoch(epoch, 0), HashValue::zero()),
+            AggregateSignature::empty(),
+        ));
+        observer_state_tracker.set_epoch_state(epoch_state.clone());
+
+        // Create several proofs (valid, invalid and for a future epoch)
+        let valid_proof = create_signed_proof(&validator_signer, &epoch_state, epoch, 1);
+        let invalid_proof = LedgerInfoWithSignatures::new(
+            LedgerInfo::new(BlockInfo::random_with_epoch(epoch, 2), HashValue::zero()),
+            AggregateSignature::empty(),
+        );
+        let future_proof = create_signed_proof(&validator_signer, &epoch_state, epoch + 1, 0);
+
+        // Create the network messages for the proofs
+        let direct_send_messages = vec![
+            ConsensusObserverMessage::new_ordered_block_message(vec![], valid_proof.clone()),
+            ConsensusObserverMessage::new_commit_decision_message(invalid_proof.clone()),
+            ConsensusObserverMessage::new_block_payload_message(
+                BlockInfo::random_with_epoch(epoch, 3),
+                vec![],
+                None,
+            ),
+            ConsensusObserverMessage::new_commit_decision_message(future_proof.clone()),
+            ConsensusObserverMessage::new_commit_decision_message(valid_proof.clone()),
+        ];
+        let peer_network_id = PeerNetworkId::random();
+        let network_messages: Vec<_> = direct_send_messages
+            .iter()
+            .cloned()
+            .map(|direct_send_message| create_network_message(peer_network_id, direct_send_message))
+            .collect();
+
+        // Create a verification filter that permits the sending peer
+        let verification_filter = ProofVerificationFilter::new(None);
+        verification_filter.update_subscription_peers(&[peer_network_id]);
+
+        // Verify the messages using the proof verification stream (with a small in-flight limit)
+        let verified_proof_cache = VerifiedProofCache::new();
+        let proof_verification_stream = ProofVerificationStream::new(
+            stream::iter(network_messages),
+            2,
+            observer_state_tracker,
+            verified_proof_cache.clone(),
+            verification_filter,
+        );
+        let verified_messages: Vec<_> = proof_verification_stream
+            .map(|network_message| network_message.consensus_observer_message)
+            .collect()
+            .await;
+
+        // Verify that all messages were returned (in arrival order)
+        let expected_messages: Vec<_> = direct_send_messages
+            .into_iter()
+            .map(ConsensusObserverMessage::DirectSend)
+            .collect();
+        assert_eq!(verified_messages, expected_messages);
+
+        // Verify that only the valid proof for the current epoch was cached
+        assert!(verified_proof_cache.contains(&valid_proof));
+        assert!(!verified_proof_cache.contains(&invalid_proof));
+        assert!(!verified_proof_cache.contains(&future_proof));
+    }
+
+    #[tokio::test]
+    async fn test_proof_verification_filter() {
+        // Create an epoch state with a single validator
+        let epoch = 10;
+        let validator_signer = ValidatorSigner::random(None);
+        let epoch_state = Arc::new(EpochState::new(
+            epoch,
+            ValidatorVerifier::new_single(validator_signer.author(), validator_signer.public_key()),
+        ));
+
+        // Create an observer state tracker for the epoch
+        let observer_state_tracker = ObserverStateTracker::new(LedgerInfoWithSignatures::new(
+            LedgerInfo::new(BlockInfo::random_with_epoch(epoch, 0), HashValue::zero()),
+            AggregateSignature::empty(),
+        ));
+        observer_state_tracker.set_epoch_state(epoch_state.clone());
+
+        // Create a verification filter with a rate limiter (with a burst of 2 messages)
+        let consensus_observer_config = ConsensusObserverConfig {
+            max_inbound_message_burst: 2,
+            ..ConsensusObserverConfig::default()
+        };
+        let inbound_rate_limiter = Arc::new(Mutex::new(InboundRateLimiter::new(
+            consensus_observer_config,
+            TimeService::mock(),
+        )));
+        let verification_filter = ProofVerificationFilter::new(Some(inbound_rate_limiter));
+        let subscription_peer = PeerNetworkId::random();
+        verification_filter.update_subscription_peers(&[subscription_peer]);
+
+        // Create several valid proofs (sent by the subscription peer and an unsolicited peer)
+        let unsolicited_peer = PeerNetworkId::random();
+        let mut network_messages = vec![];
+        let mut expected_verified_proofs = vec![];
+        let mut expected_unverified_proofs = vec![];
+        for round in 1..=5 {
+            let proof = create_signed_proof(&validator_signer, &epoch_state, epoch, round);
+            let peer_network_id = if round == 1 {
+                unsolicited_peer
+            } else {
+                subscription_peer
+            };
+            network_messages.push(create_network_message(
+                peer_network_id,
+                ConsensusObserverMessage::new_commit_decision_message(proof.clone()),
+            ));
+
+            // Only the proofs of the subscription peer within the burst are verified
+            if peer_network_id == subscription_peer && expected_verified_proofs.len() < 2 {
+                expected_verified_proofs.push(proof);
+            } else {
+                expected_unverified_proofs.push(proof);
+            }
+        }
+
+        // Verify the messages using the proof verification stream
+        let verified_proof_cache = VerifiedProofCache::new();
+        let proof_verification_stream = ProofVerificationStream::new(
+            stream::iter(network_messages),
+            10,
+            observer_state_tracker,
+            verified_proof_cache.clone(),
+            verification_filter,
+        );
+        let returned_messages: Vec<_> = proof_verification_stream.collect().await;
+        assert_eq!(returned_messages.len(), 5);
+
+        // Verify that only the proofs that passed the filter were verified
+        for proof in expected_verified_proofs {
+            assert!(verified_proof_cache.contains(&proof));
+        }
+        for proof in expected_unverified_proofs {
+            assert!(!verified_proof_cache.contains(&proof));
+        }
+    }
+
+    /// Creates a proof for the given epoch and round (signed by the given signer)
+    fn create_signed_proof(
+        validator_signer: &ValidatorSigner,
+        epoch_state: &EpochState,
+        epoch: u64,
+        round: u64,
+    ) -> LedgerInfoWithSignatures {
+        let ledger_info = LedgerInfo::new(
+            BlockInfo::random_with_epoch(epoch, round),
+            HashValue::zero(),
+        );
+        let mut partial_signatures = PartialSignatures::empty();
+        partial_signatures.add_signature(
+            validator_signer.author(),
+            validator_signer.sign(&ledger_info).unwrap(),
+        );
+        let aggregate_signature = epoch_state
+            .verifier
+            .aggregate_signatures(&partial_signatures)
+            .unwrap();
+        LedgerInfoWithSignatures::new(ledger_info, aggregate_signature)
+    }
+
+    /// Creates a network message (from the given peer) for the given direct send message
+    fn create_network_message(
+        peer_network_id: PeerNetworkId,
+        direct_send_message: ConsensusObserverDirectSend,
+    ) -> NetworkMessage {
+        NetworkMessage {
+            peer_network_id,
+            protocol_id: None,
+            consensus_observer_message: ConsensusObserverMessage::DirectSend(direct_send_message),
+            response_sender: None,
+            num_message_bytes: 0,
+        }
+    }
+}
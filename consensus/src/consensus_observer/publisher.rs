@@ -3,24 +3,43 @@
 
 use crate::consensus_observer::{
     logging::{LogEntry, LogEvent, LogSchema},
+    message_interceptor::{ConsensusObserverMessageInterceptor, MessageInterceptorChain},
+    message_rate_limiter::{InboundRateLimiter, RateLimitDecision},
     metrics,
     network_client::ConsensusObserverClient,
     network_events::ResponseSender,
     network_message::{
-        ConsensusObserverDirectSend, ConsensusObserverMessage, ConsensusObserverRequest,
-        ConsensusObserverResponse,
+        BlockPayload, CompressedBlockPayload, CompressionDictionary, ConsensusObserverDirectSend,
+        ConsensusObserverMessage, ConsensusObserverRequest, ConsensusObserverResponse,
+        OrderedBlock, PayloadAcknowledgement, SubscriptionOptions,
     },
+    payload_compression::PayloadDictionaryCompressor,
+    payload_delta::DeltaPayloadEncoder,
+    peer_misbehavior::{PeerMisbehavior, PeerMisbehaviorReporter},
+    replay_buffer::PublisherReplayBuffer,
 };
 use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
-use aptos_infallible::RwLock;
+use aptos_crypto::HashValue;
+use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::{info, warn};
 use aptos_network::application::interface::NetworkClient;
+use aptos_time_service::TimeService;
+use aptos_types::block_info::{BlockInfo, Round};
 use futures::{SinkExt, StreamExt};
 use futures_channel::mpsc;
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use lru::LruCache;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::time::interval;
 use tokio_stream::wrappers::IntervalStream;
 
+// The maximum number of recently prefetched payloads tracked by the publisher
+// (to avoid re-publishing the payloads once the blocks are executed).
+const MAX_NUM_PREFETCHED_PAYLOADS: usize = 1000;
+
 /// The consensus publisher sends consensus updates to downstream observers
 #[derive(Clone)]
 pub struct ConsensusPublisher {
@@ -34,8 +53,37 @@ pub struct ConsensusPublisher {
     // The set of active subscribers that have subscribed to consensus updates
     active_subscribers: Arc<RwLock<HashSet<PeerNetworkId>>>,
 
+    // The subscribers that accept dictionary compressed payloads (and the ID
+    // of the most recent dictionary shipped to each subscriber, if any).
+    compression_subscribers: Arc<RwLock<HashMap<PeerNetworkId, Option<u64>>>>,
+
+    // The payload compressor (if dictionary compression is enabled)
+    payload_compressor: Option<Arc<PayloadDictionaryCompressor>>,
+
+    // The payload delta encoder (if delta encoding is enabled)
+    payload_delta_encoder: Option<Arc<DeltaPayloadEncoder>>,
+
+    // The block IDs of the payloads recently published when prefetched
+    prefetched_payload_ids: Arc<Mutex<LruCache<HashValue, ()>>>,
+
+    // The block info of the latest published commit decision (used to answer probes)
+    latest_commit_info: Arc<RwLock<Option<BlockInfo>>>,
+
+    // The buffer of recently published messages (used to catch up new
+    // subscribers, and to serve observers that are missing blocks or payloads).
+    replay_buffer: Arc<Mutex<PublisherReplayBuffer>>,
+
     // The sender for outbound network messages
     outbound_message_sender: mpsc::Sender<(PeerNetworkId, ConsensusObserverDirectSend)>,
+
+    // The chain of interceptors invoked on every outbound message
+    message_interceptors: MessageInterceptorChain,
+
+    // The reporter for peer misbehavior (shared with the consensus observer)
+    peer_misbehavior_reporter: PeerMisbehaviorReporter,
+
+    // The rate limiter for the block and payload requests of each subscriber (if enabled)
+    inbound_rate_limiter: Option<Arc<Mutex<InboundRateLimiter>>>,
 }
 
 impl ConsensusPublisher {
@@ -51,18 +99,183 @@ impl ConsensusPublisher {
         let (outbound_message_sender, outbound_message_receiver) =
             mpsc::channel(max_network_channel_size);
 
+        // Create the consensus observer client and peer misbehavior reporter
+        let consensus_observer_client = Arc::new(ConsensusObserverClient::new(network_client));
+        let peer_misbehavior_reporter = PeerMisbehaviorReporter::new(
+            consensus_observer_config.clone(),
+            consensus_observer_client.clone(),
+        );
+
+        // Create the payload compressor (if dictionary compression is enabled)
+        let payload_compressor = consensus_observer_config
+            .enable_payload_dictionary_compression
+            .then(|| Arc::new(PayloadDictionaryCompressor::new(&consensus_observer_config)));
+
+        // Create the payload delta encoder (if delta encoding is enabled)
+        let payload_delta_encoder = consensus_observer_config
+            .enable_payload_delta_encoding
+            .then(|| Arc::new(DeltaPayloadEncoder::new(&consensus_observer_config)));
+
+        // Create the inbound rate limiter (if inbound rate limiting is enabled)
+        let inbound_rate_limiter =
+            consensus_observer_config
+                .enable_inbound_rate_limiting
+                .then(|| {
+                    Arc::new(Mutex::new(InboundRateLimiter::new(
+                        consensus_observer_config.clone(),
+                        TimeService::real(),
+                    )))
+                });
+
         // Create the consensus publisher
         let consensus_publisher = Self {
-            consensus_observer_client: Arc::new(ConsensusObserverClient::new(network_client)),
-            consensus_observer_config,
+            consensus_observer_client,
+            consensus_observer_config: consensus_observer_config.clone(),
             active_subscribers: Arc::new(RwLock::new(HashSet::new())),
+            compression_subscribers: Arc::new(RwLock::new(HashMap::new())),
+            payload_compressor,
+            payload_delta_encoder,
+            prefetched_payload_ids: Arc::new(Mutex::new(LruCache::new(
+                MAX_NUM_PREFETCHED_PAYLOADS,
+            ))),
+            latest_commit_info: Arc::new(RwLock::new(None)),
+            replay_buffer: Arc::new(Mutex::new(PublisherReplayBuffer::new(
+                consensus_observer_config.max_replay_buffer_messages as usize,
+            ))),
             outbound_message_sender,
+            message_interceptors: MessageInterceptorChain::new(),
+            peer_misbehavior_reporter,
+            inbound_rate_limiter,
         };
 
         // Return the publisher and the outbound message receiver
         (consensus_publisher, outbound_message_receiver)
     }
 
+    /// Adds the given interceptor to the end of the outbound message interceptor chain
+    pub fn add_message_interceptor(
+        &self,
+        message_interceptor: Arc<dyn ConsensusObserverMessageInterceptor>,
+    ) {
+        self.message_interceptors
+            .add_interceptor(message_interceptor);
+    }
+
+    /// Broadcasts a direct send message to all active subscribers
+    async fn broadcast_message(&self, message: ConsensusObserverDirectSend) {
+        // Get the set of active subscribers
+        let active_subscribers = self.active_subscribers.read().clone();
+
+        // Compress the message (if it is a block payload and compression is enabled)
+        let compressed_block_payload = self.compress_block_payload(&message);
+
+        // Send the message to all active subscribers
+        for peer_network_id in &active_subscribers {
+            let messages = self.create_messages_for_subscriber(
+                peer_network_id,
+                &message,
+                compressed_block_payload.as_ref(),
+            );
+            for message in messages {
+                self.send_message_to_subscriber(peer_network_id, message)
+                    .await;
+            }
+        }
+    }
+
+    /// Compresses the given message (if it is a block payload, dictionary
+    /// compression is enabled and a dictionary has been trained). The block
+    /// payload is also recorded as a sample for future dictionary training.
+    /// Returns the dictionary and the compressed payload (if any).
+    fn compress_block_payload(
+        &self,
+        message: &ConsensusObserverDirectSend,
+    ) -> Option<(Arc<CompressionDictionary>, CompressedBlockPayload)> {
+        // Only block payloads are compressed
+        let payload_compressor = self.payload_compressor.as_ref()?;
+        let block_payload = match message {
+            ConsensusObserverDirectSend::BlockPayload(block_payload) => block_payload,
+            _ => return None,
+        };
+
+        // Record the block payload as a training sample
+        payload_compressor.record_sample(block_payload);
+
+        // Only compress the payload if there are subscribers that accept it
+        if self.compression_subscribers.read().is_empty() {
+            return None;
+        }
+
+        // Compress the block payload using the current dictionary
+        let dictionary = payload_compressor.get_current_dictionary()?;
+        match payload_compressor.compress_payload(&dictionary, block_payload) {
+            Ok(compressed_block_payload) => Some((dictionary, compressed_block_payload)),
+            Err(error) => {
+                warn!(LogSchema::new(LogEntry::ConsensusPublisher)
+                    .event(LogEvent::UnexpectedError)
+                    .message(&format!(
+                        "Failed to compress the block payload! Error: {:?}",
+                        error
+                    )));
+                None
+            },
+        }
+    }
+
+    /// Returns the messages to send to the given subscriber for the published
+    /// message. Subscribers that accept delta encoded payloads receive digests
+    /// for the transactions they already hold. Subscribers that accept dictionary
+    /// compressed payloads receive the compressed payload (preceded by the
+    /// dictionary, if the subscriber has not yet received it). All other
+    /// subscribers receive the message.
+    fn create_messages_for_subscriber(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        message: &ConsensusObserverDirectSend,
+        compressed_block_payload: Option<&(Arc<CompressionDictionary>, CompressedBlockPayload)>,
+    ) -> Vec<ConsensusObserverDirectSend> {
+        // Check if the block payload can be delta encoded for the subscriber
+        if let (
+            Some(payload_delta_encoder),
+            ConsensusObserverDirectSend::BlockPayload(block_payload),
+        ) = (&self.payload_delta_encoder, message)
+        {
+            if let Some(delta_block_payload) =
+                payload_delta_encoder.encode_payload(peer_network_id, block_payload)
+            {
+                return vec![ConsensusObserverDirectSend::DeltaBlockPayload(
+                    delta_block_payload,
+                )];
+            }
+        }
+
+        // Check if the subscriber accepts the compressed payload
+        let (dictionary, compressed_block_payload) = match compressed_block_payload {
+            Some(compressed_block_payload) => compressed_block_payload,
+            None => return vec![message.clone()],
+        };
+        let mut compression_subscribers = self.compression_subscribers.write();
+        let shipped_dictionary_id = match compression_subscribers.get_mut(peer_network_id) {
+            Some(shipped_dictionary_id) => shipped_dictionary_id,
+            None => return vec![message.clone()],
+        };
+
+        // Ship the dictionary to the subscriber (if it hasn't been shipped yet)
+        let mut messages = vec![];
+        if *shipped_dictionary_id != Some(dictionary.dictionary_id) {
+            *shipped_dictionary_id = Some(dictionary.dictionary_id);
+            messages.push(ConsensusObserverDirectSend::CompressionDictionary(
+                dictionary.as_ref().clone(),
+            ));
+        }
+
+        // Send the compressed payload
+        messages.push(ConsensusObserverDirectSend::CompressedBlockPayload(
+            compressed_block_payload.clone(),
+        ));
+        messages
+    }
+
     /// Garbage collect inactive subscriptions by removing peers that are no longer connected
     fn garbage_collect_subscriptions(&self) {
         // Get the set of active subscribers
@@ -96,6 +309,10 @@ impl ConsensusPublisher {
         // Remove any subscriptions from peers that are no longer connected
         for peer_network_id in &disconnected_subscribers {
             self.active_subscribers.write().remove(peer_network_id);
+            self.compression_subscribers.write().remove(peer_network_id);
+            if let Some(payload_delta_encoder) = &self.payload_delta_encoder {
+                payload_delta_encoder.remove_subscriber(peer_network_id);
+            }
             info!(LogSchema::new(LogEntry::ConsensusPublisher)
                 .event(LogEvent::Subscription)
                 .message(&format!(
@@ -120,6 +337,20 @@ impl ConsensusPublisher {
                 num_active_subscribers,
             );
         }
+
+        // Garbage collect the misbehavior scores of disconnected peers
+        self.peer_misbehavior_reporter.garbage_collect_scores();
+
+        // Garbage collect the rate limits of peers that are no longer subscribed
+        if let Some(inbound_rate_limiter) = &self.inbound_rate_limiter {
+            let active_subscribers: Vec<_> = active_subscribers.into_iter().collect();
+            inbound_rate_limiter
+                .lock()
+                .garbage_collect(&active_subscribers);
+        }
+
+        // Release the metric labels of disconnected peers
+        metrics::garbage_collect_peer_labels(&connected_peers);
     }
 
     /// Returns a clone of the currently active subscribers
@@ -134,6 +365,29 @@ impl ConsensusPublisher {
         self.consensus_observer_client.clone()
     }
 
+    /// Returns the recently published payload for the given block (if any)
+    pub fn get_block_payload(&self, block_id: &HashValue) -> Option<BlockPayload> {
+        self.replay_buffer.lock().get_block_payload(block_id)
+    }
+
+    /// Returns the block info of the latest published commit decision (if any)
+    pub fn get_latest_commit_info(&self) -> Option<BlockInfo> {
+        self.latest_commit_info.read().clone()
+    }
+
+    /// Returns the recently published ordered blocks (of the latest epoch) that
+    /// contain any blocks in the given round range (sorted by round).
+    pub fn get_ordered_blocks(&self, start_round: Round, end_round: Round) -> Vec<OrderedBlock> {
+        self.replay_buffer
+            .lock()
+            .get_ordered_blocks(start_round, end_round)
+    }
+
+    /// Returns a copy of the peer misbehavior reporter
+    pub fn get_peer_misbehavior_reporter(&self) -> PeerMisbehaviorReporter {
+        self.peer_misbehavior_reporter.clone()
+    }
+
     /// Handles a subscription message from a peer
     pub fn handle_subscription_request(
         &self,
@@ -149,23 +403,58 @@ impl ConsensusPublisher {
         );
 
         // Handle the request
+        let request_label = request.get_label();
         match request {
+            ConsensusObserverRequest::AcknowledgePayloads(payload_acknowledgement) => {
+                self.handle_payload_acknowledgement(
+                    peer_network_id,
+                    payload_acknowledgement,
+                    response_sender,
+                );
+            },
+            ConsensusObserverRequest::GetLatestCommit => {
+                // Respond with the latest published commit (used by observers to probe liveness)
+                let latest_commit_info = self.get_latest_commit_info();
+                response_sender.send(ConsensusObserverResponse::LatestCommit(latest_commit_info));
+            },
+            ConsensusObserverRequest::GetOrderedBlocks {
+                start_round,
+                end_round,
+            } => {
+                // Respond with the recent ordered blocks (used by observers to backfill gaps)
+                let ordered_blocks = if self.should_serve_request(peer_network_id, request_label) {
+                    self.get_ordered_blocks(start_round, end_round)
+                } else {
+                    vec![]
+                };
+                response_sender.send(ConsensusObserverResponse::OrderedBlocks(ordered_blocks));
+            },
+            ConsensusObserverRequest::GetBlockPayload(block_id) => {
+                // Respond with the recent block payload (used by observers to fetch missing payloads)
+                let block_payload = if self.should_serve_request(peer_network_id, request_label) {
+                    self.get_block_payload(&block_id)
+                } else {
+                    None
+                };
+                response_sender.send(ConsensusObserverResponse::BlockPayload(block_payload));
+            },
             ConsensusObserverRequest::Subscribe => {
-                // Add the peer to the set of active subscribers
-                self.active_subscribers.write().insert(*peer_network_id);
-                info!(LogSchema::new(LogEntry::ConsensusPublisher)
-                    .event(LogEvent::Subscription)
-                    .message(&format!(
-                        "New peer subscribed to consensus updates! Peer: {:?}",
-                        peer_network_id
-                    )));
-
-                // Send a simple subscription ACK
-                response_sender.send(ConsensusObserverResponse::SubscribeAck);
+                self.handle_subscribe(
+                    peer_network_id,
+                    SubscriptionOptions::default(),
+                    response_sender,
+                );
+            },
+            ConsensusObserverRequest::SubscribeWithOptions(subscription_options) => {
+                self.handle_subscribe(peer_network_id, subscription_options, response_sender);
             },
             ConsensusObserverRequest::Unsubscribe => {
                 // Remove the peer from the set of active subscribers
                 self.active_subscribers.write().remove(peer_network_id);
+                self.compression_subscribers.write().remove(peer_network_id);
+                if let Some(payload_delta_encoder) = &self.payload_delta_encoder {
+                    payload_delta_encoder.remove_subscriber(peer_network_id);
+                }
                 info!(LogSchema::new(LogEntry::ConsensusPublisher)
                     .event(LogEvent::Subscription)
                     .message(&format!(
@@ -179,27 +468,278 @@ impl ConsensusPublisher {
         }
     }
 
-    /// Publishes a direct send message to all active subscribers
+    /// Handles a payload acknowledgement from a (delta encoding) subscriber
+    fn handle_payload_acknowledgement(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        payload_acknowledgement: PayloadAcknowledgement,
+        response_sender: ResponseSender,
+    ) {
+        // Verify that the peer is a delta encoding subscriber
+        match &self.payload_delta_encoder {
+            Some(payload_delta_encoder)
+                if payload_delta_encoder.is_delta_subscriber(peer_network_id) =>
+            {
+                payload_delta_encoder
+                    .acknowledge_payloads(peer_network_id, &payload_acknowledgement);
+            },
+            _ => {
+                // The peer didn't negotiate delta encoding, so the acknowledgement is unexpected
+                self.peer_misbehavior_reporter.report_misbehavior(
+                    peer_network_id,
+                    PeerMisbehavior::UnexpectedMessage,
+                    "Received a payload acknowledgement from a peer without delta encoding!",
+                );
+            },
+        }
+
+        // Send a simple acknowledgement ACK
+        response_sender.send(ConsensusObserverResponse::AcknowledgePayloadsAck);
+    }
+
+    /// Returns true iff the given block or payload request should be served.
+    /// Requests are only served to active subscribers (within their rate limit,
+    /// if enabled), as serving the replay buffer to any peer is expensive.
+    fn should_serve_request(&self, peer_network_id: &PeerNetworkId, request_label: &str) -> bool {
+        // Verify that the peer is an active subscriber
+        if !self.active_subscribers.read().contains(peer_network_id) {
+            self.peer_misbehavior_reporter.report_misbehavior(
+                peer_network_id,
+                PeerMisbehavior::UnsolicitedMessage,
+                &format!(
+                    "Received a {} request from a peer without an active subscription!",
+                    request_label
+                ),
+            );
+            return false;
+        }
+
+        // Verify that the request is within the peer's rate limit
+        if let Some(inbound_rate_limiter) = &self.inbound_rate_limiter {
+            let rate_limit_decision = inbound_rate_limiter.lock().check_message(peer_network_id);
+            if rate_limit_decision != RateLimitDecision::Allowed {
+                self.peer_misbehavior_reporter.report_misbehavior(
+                    peer_network_id,
+                    PeerMisbehavior::SpammyRequest,
+                    &format!(
+                        "Received a {} request that exceeds the peer's rate limit!",
+                        request_label
+                    ),
+                );
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Handles a subscription request (with the given options) from a peer
+    fn handle_subscribe(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        subscription_options: SubscriptionOptions,
+        response_sender: ResponseSender,
+    ) {
+        // Add the peer to the set of active subscribers
+        let new_subscriber = self.active_subscribers.write().insert(*peer_network_id);
+        if !new_subscriber {
+            // The peer is already subscribed, so the request is redundant
+            self.peer_misbehavior_reporter.report_misbehavior(
+                peer_network_id,
+                PeerMisbehavior::SpammyRequest,
+                "Received a subscription request from an existing subscriber!",
+            );
+        }
+        info!(LogSchema::new(LogEntry::ConsensusPublisher)
+            .event(LogEvent::Subscription)
+            .message(&format!(
+                "New peer subscribed to consensus updates! Peer: {:?}, options: {:?}",
+                peer_network_id, subscription_options
+            )));
+
+        // Update the compression subscribers. Note: the current dictionary
+        // (if any) is shipped to the peer alongside the next compressed payload.
+        let mut compression_subscribers = self.compression_subscribers.write();
+        if subscription_options.dictionary_compression && self.payload_compressor.is_some() {
+            compression_subscribers.insert(*peer_network_id, None);
+        } else {
+            compression_subscribers.remove(peer_network_id);
+        }
+
+        // Update the delta encoding subscribers
+        if let Some(payload_delta_encoder) = &self.payload_delta_encoder {
+            if subscription_options.delta_encoding {
+                payload_delta_encoder.add_subscriber(peer_network_id);
+            } else {
+                payload_delta_encoder.remove_subscriber(peer_network_id);
+            }
+        }
+
+        // Send a simple subscription ACK
+        response_sender.send(ConsensusObserverResponse::SubscribeAck);
+
+        // Replay the blocks after the starting block of the peer (if requested)
+        if let Some(starting_block) = subscription_options.starting_block {
+            self.replay_buffered_messages(peer_network_id, starting_block);
+        }
+    }
+
+    /// Returns true iff the publisher should prefetch and publish the payloads
+    /// of proposed blocks (i.e., before the blocks are ordered).
+    pub fn is_payload_prefetch_enabled(&self) -> bool {
+        self.consensus_observer_config.enable_payload_prefetch
+    }
+
+    /// Publishes a direct send message to all active subscribers. Block
+    /// payloads that were already published when prefetched are skipped.
     pub async fn publish_message(&self, message: ConsensusObserverDirectSend) {
-        // Get the set of active subscribers
-        let active_subscribers = self.active_subscribers.read().clone();
+        match &message {
+            ConsensusObserverDirectSend::BlockPayload(block_payload) => {
+                if self
+                    .prefetched_payload_ids
+                    .lock()
+                    .contains(&block_payload.block.id())
+                {
+                    metrics::increment_prefetched_payloads(metrics::PREFETCH_DEDUPLICATED_LABEL);
+                    return; // The payload was already published
+                }
+            },
+            ConsensusObserverDirectSend::CommitDecision(commit_decision) => {
+                // Track the latest published commit
+                *self.latest_commit_info.write() = Some(commit_decision.proof_block_info().clone());
+            },
+            _ => {},
+        }
 
-        // Send the message to all active subscribers
-        for peer_network_id in &active_subscribers {
-            // Send the message to the outbound receiver for publishing
-            let mut outbound_message_sender = self.outbound_message_sender.clone();
-            if let Err(error) = outbound_message_sender
-                .send((*peer_network_id, message.clone()))
-                .await
-            {
-                // The message send failed
+        // Buffer the message (to catch up new subscribers) and broadcast it
+        self.replay_buffer.lock().record_message(&message);
+        self.broadcast_message(message).await;
+    }
+
+    /// Publishes the given block payload message for a proposed block (i.e., the
+    /// payload was prefetched before the block was ordered). The payload is not
+    /// re-published when the block is later executed.
+    pub async fn publish_prefetched_payload(&self, message: ConsensusObserverDirectSend) {
+        if let ConsensusObserverDirectSend::BlockPayload(block_payload) = &message {
+            let newly_prefetched = self
+                .prefetched_payload_ids
+                .lock()
+                .put(block_payload.block.id(), ())
+                .is_none();
+            if newly_prefetched {
+                metrics::increment_prefetched_payloads(metrics::PREFETCH_PUBLISHED_LABEL);
+                self.replay_buffer.lock().record_message(&message);
+                self.broadcast_message(message).await;
+            }
+        }
+    }
+
+    /// Retrains the payload compression dictionary (if dictionary compression is
+    /// enabled). The new dictionary is installed and shipped to the compression
+    /// subscribers alongside the next compressed payloads.
+    async fn refresh_payload_dictionary(&self) {
+        // Get the payload compressor
+        let payload_compressor = match &self.payload_compressor {
+            Some(payload_compressor) => payload_compressor.clone(),
+            None => return, // Dictionary compression is disabled
+        };
+
+        // Train the new dictionary (training is CPU intensive, so we use a blocking task)
+        let training_compressor = payload_compressor.clone();
+        let training_result =
+            tokio::task::spawn_blocking(move || training_compressor.train_dictionary()).await;
+
+        // Install the new dictionary (if one was trained)
+        match training_result {
+            Ok(Ok(Some(dictionary))) => {
+                info!(
+                    LogSchema::new(LogEntry::ConsensusPublisher).message(&format!(
+                        "Installing a new payload compression dictionary! ID: {}, size: {}",
+                        dictionary.dictionary_id,
+                        dictionary.dictionary_bytes.len()
+                    ))
+                );
+                payload_compressor.install_dictionary(dictionary);
+            },
+            Ok(Ok(None)) => {}, // There are not enough samples to train a dictionary
+            Ok(Err(error)) => {
                 warn!(LogSchema::new(LogEntry::ConsensusPublisher)
-                    .event(LogEvent::SendDirectSendMessage)
+                    .event(LogEvent::UnexpectedError)
                     .message(&format!(
-                        "Failed to send outbound message to the receiver for peer {:?}! Error: {:?}",
-                        peer_network_id, error
+                        "Failed to train the payload compression dictionary! Error: {:?}",
+                        error
                     )));
-            }
+            },
+            Err(error) => {
+                warn!(LogSchema::new(LogEntry::ConsensusPublisher)
+                    .event(LogEvent::UnexpectedError)
+                    .message(&format!(
+                        "The payload dictionary training task failed! Error: {:?}",
+                        error
+                    )));
+            },
+        }
+    }
+
+    /// Replays the buffered messages (i.e., ordered blocks, payloads and commit
+    /// decisions) after the given starting block (i.e., epoch and round) to the
+    /// given subscriber. This allows new subscribers to catch up on the blocks
+    /// between their root and our latest block (instead of falling behind and
+    /// state syncing). Note: the messages are dropped if the channel is full.
+    fn replay_buffered_messages(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        starting_block: (u64, Round),
+    ) {
+        let replay_messages = self.replay_buffer.lock().get_messages_after(starting_block);
+        if replay_messages.is_empty() {
+            return; // There is nothing to replay
+        }
+
+        info!(LogSchema::new(LogEntry::ConsensusPublisher)
+            .event(LogEvent::Subscription)
+            .message(&format!(
+                "Replaying {} messages to the new subscriber: {:?}, starting block: {:?}",
+                replay_messages.len(),
+                peer_network_id,
+                starting_block
+            )));
+        for message in replay_messages {
+            metrics::increment_replayed_messages(message.get_label());
+            self.try_send_message_to_subscriber(peer_network_id, message);
+        }
+    }
+
+    /// Sends the given message to the subscriber (via the outbound message receiver)
+    async fn send_message_to_subscriber(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        message: ConsensusObserverDirectSend,
+    ) {
+        // Run the message through the interceptors (the message may be dropped)
+        let message = match self
+            .message_interceptors
+            .intercept_outbound_message(peer_network_id, message)
+        {
+            Some(message) => message,
+            None => return, // The message was dropped by an interceptor
+        };
+
+        // Send the message to the outbound receiver for publishing
+        let mut outbound_message_sender = self.outbound_message_sender.clone();
+        metrics::increment_channel_queue_depth(metrics::PUBLISHER_OUTBOUND_CHANNEL_LABEL);
+        if let Err(error) = outbound_message_sender
+            .send((*peer_network_id, message))
+            .await
+        {
+            // The message send failed
+            metrics::decrement_channel_queue_depth(metrics::PUBLISHER_OUTBOUND_CHANNEL_LABEL);
+            warn!(LogSchema::new(LogEntry::ConsensusPublisher)
+                .event(LogEvent::SendDirectSendMessage)
+                .message(&format!(
+                    "Failed to send outbound message to the receiver for peer {:?}! Error: {:?}",
+                    peer_network_id, error
+                )));
         }
     }
 
@@ -211,7 +751,7 @@ impl ConsensusPublisher {
         // Spawn the message serializer and sender
         spawn_message_serializer_and_sender(
             self.consensus_observer_client.clone(),
-            self.consensus_observer_config,
+            self.consensus_observer_config.clone(),
             outbound_message_receiver,
         );
 
@@ -222,6 +762,13 @@ impl ConsensusPublisher {
         )))
         .fuse();
 
+        // Create a payload dictionary refresh ticker
+        let mut dictionary_refresh_interval = IntervalStream::new(interval(Duration::from_millis(
+            self.consensus_observer_config
+                .payload_dictionary_refresh_interval_ms,
+        )))
+        .fuse();
+
         // Start the publisher garbage collection loop
         info!(LogSchema::new(LogEntry::ConsensusPublisher)
             .message("Starting the consensus publisher garbage collection loop!"));
@@ -231,9 +778,44 @@ impl ConsensusPublisher {
                     // Perform garbage collection
                     self.garbage_collect_subscriptions();
                 },
+                _ = dictionary_refresh_interval.select_next_some(), if self.payload_compressor.is_some() => {
+                    // Retrain the payload compression dictionary
+                    self.refresh_payload_dictionary().await;
+                },
             }
         }
     }
+
+    /// Sends the given message to the subscriber (via the outbound message receiver)
+    /// without waiting. The message is dropped if the outbound channel is full.
+    fn try_send_message_to_subscriber(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        message: ConsensusObserverDirectSend,
+    ) {
+        // Run the message through the interceptors (the message may be dropped)
+        let message = match self
+            .message_interceptors
+            .intercept_outbound_message(peer_network_id, message)
+        {
+            Some(message) => message,
+            None => return, // The message was dropped by an interceptor
+        };
+
+        // Send the message to the outbound receiver for publishing
+        let mut outbound_message_sender = self.outbound_message_sender.clone();
+        metrics::increment_channel_queue_depth(metrics::PUBLISHER_OUTBOUND_CHANNEL_LABEL);
+        if let Err(error) = outbound_message_sender.try_send((*peer_network_id, message)) {
+            // The message send failed
+            metrics::decrement_channel_queue_depth(metrics::PUBLISHER_OUTBOUND_CHANNEL_LABEL);
+            warn!(LogSchema::new(LogEntry::ConsensusPublisher)
+                .event(LogEvent::SendDirectSendMessage)
+                .message(&format!(
+                    "Failed to send outbound message to the receiver for peer {:?}! Error: {:?}",
+                    peer_network_id, error
+                )));
+        }
+    }
 }
 
 /// Spawns a message serialization task that serializes outbound publisher
@@ -250,6 +832,9 @@ fn spawn_message_serializer_and_sender(
         let consensus_observer_client_clone = consensus_observer_client.clone();
         let serialization_task =
             outbound_message_receiver.map(move |(peer_network_id, message)| {
+                // Update the outbound channel queue depth (the message was dequeued)
+                metrics::decrement_channel_queue_depth(metrics::PUBLISHER_OUTBOUND_CHANNEL_LABEL);
+
                 // Spawn a new blocking task to serialize the message
                 let consensus_observer_client_clone = consensus_observer_client_clone.clone();
                 tokio::task::spawn_blocking(move || {
@@ -314,16 +899,27 @@ fn spawn_message_serializer_and_sender(
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::consensus_observer::{
+        payload_compression::PayloadDictionaryDecompressor, test_utils::create_response_sender,
+    };
     use aptos_config::network_id::NetworkId;
-    use aptos_crypto::HashValue;
+    use aptos_consensus_types::{
+        block::Block,
+        block_data::{BlockData, BlockType},
+        pipelined_block::PipelinedBlock,
+        quorum_cert::QuorumCert,
+    };
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform};
     use aptos_network::{
         application::{metadata::ConnectionState, storage::PeersAndMetadata},
         transport::ConnectionMetadata,
     };
     use aptos_types::{
+        account_address::AccountAddress,
         aggregate_signature::AggregateSignature,
         block_info::BlockInfo,
         ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+        test_helpers::transaction_test_helpers::get_test_signed_txn,
         PeerId,
     };
     use futures::FutureExt;
@@ -398,6 +994,104 @@ mod test {
         ]);
     }
 
+    #[tokio::test]
+    async fn test_get_block_payload() {
+        // Create a network client
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let network_client = NetworkClient::new(vec![], vec![], hashmap![], peers_and_metadata);
+
+        // Create a consensus publisher
+        let (consensus_publisher, _) =
+            ConsensusPublisher::new(network_client, ConsensusObserverConfig::default());
+
+        // Publish more than the maximum number of buffered block payloads
+        let max_replay_buffer_messages =
+            ConsensusObserverConfig::default().max_replay_buffer_messages;
+        let mut block_payloads = vec![];
+        for round in 0..=max_replay_buffer_messages {
+            let block_payload_message = create_block_payload_message(round);
+            consensus_publisher
+                .publish_message(block_payload_message.clone())
+                .await;
+            if let ConsensusObserverDirectSend::BlockPayload(block_payload) = block_payload_message
+            {
+                block_payloads.push(block_payload);
+            }
+        }
+
+        // Verify that the oldest block payload was evicted
+        let oldest_block_id = block_payloads[0].block.id();
+        assert!(consensus_publisher
+            .get_block_payload(&oldest_block_id)
+            .is_none());
+
+        // Verify that the remaining block payloads are served
+        for block_payload in &block_payloads[1..] {
+            assert_eq!(
+                consensus_publisher.get_block_payload(&block_payload.block.id()),
+                Some(block_payload.clone())
+            );
+        }
+
+        // Verify that unknown block payloads are not served
+        assert!(consensus_publisher
+            .get_block_payload(&HashValue::random())
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_ordered_blocks() {
+        // Create a network client
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let network_client = NetworkClient::new(vec![], vec![], hashmap![], peers_and_metadata);
+
+        // Create a consensus publisher
+        let (consensus_publisher, _) =
+            ConsensusPublisher::new(network_client, ConsensusObserverConfig::default());
+
+        // Verify that no ordered blocks are served before any blocks are published
+        assert!(consensus_publisher.get_ordered_blocks(0, 100).is_empty());
+
+        // Publish several ordered blocks (for the first epoch)
+        for round in 1..=5 {
+            consensus_publisher
+                .publish_message(create_ordered_block_message(1, round))
+                .await;
+        }
+
+        // Verify that only the ordered blocks in the round range are served
+        let ordered_blocks = consensus_publisher.get_ordered_blocks(2, 4);
+        assert_eq!(get_epochs_and_rounds(&ordered_blocks), vec![
+            (1, 2),
+            (1, 3),
+            (1, 4)
+        ]);
+        assert!(consensus_publisher.get_ordered_blocks(6, 10).is_empty());
+
+        // Publish an ordered block for the next epoch and verify only it is served
+        consensus_publisher
+            .publish_message(create_ordered_block_message(2, 1))
+            .await;
+        let ordered_blocks = consensus_publisher.get_ordered_blocks(0, 10);
+        assert_eq!(get_epochs_and_rounds(&ordered_blocks), vec![(2, 1)]);
+
+        // Publish more than the maximum number of buffered blocks
+        let max_replay_buffer_messages =
+            ConsensusObserverConfig::default().max_replay_buffer_messages;
+        for round in 2..=(max_replay_buffer_messages + 1) {
+            consensus_publisher
+                .publish_message(create_ordered_block_message(2, round))
+                .await;
+        }
+
+        // Verify that the oldest ordered block was evicted
+        let ordered_blocks = consensus_publisher.get_ordered_blocks(0, Round::MAX);
+        assert_eq!(ordered_blocks.len() as u64, max_replay_buffer_messages);
+        assert_eq!(ordered_blocks[0].proof_block_info().round(), 2);
+    }
+
     #[test]
     fn test_handle_subscription_request() {
         // Create a network client
@@ -449,6 +1143,119 @@ mod test {
         ]);
     }
 
+    #[tokio::test]
+    async fn test_handle_subscription_request_responses() {
+        // Create a consensus publisher
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let network_client = NetworkClient::new(vec![], vec![], hashmap![], peers_and_metadata);
+        let (consensus_publisher, _) =
+            ConsensusPublisher::new(network_client, ConsensusObserverConfig::default());
+
+        // Verify that subscription and unsubscription requests are acknowledged
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        for (request, expected_response) in [
+            (
+                ConsensusObserverRequest::Subscribe,
+                ConsensusObserverResponse::SubscribeAck,
+            ),
+            (
+                ConsensusObserverRequest::Unsubscribe,
+                ConsensusObserverResponse::UnsubscribeAck,
+            ),
+        ] {
+            let (response_sender, mut response_receiver) = create_response_sender();
+            consensus_publisher.handle_subscription_request(
+                &peer_network_id,
+                request,
+                response_sender,
+            );
+            assert_eq!(
+                response_receiver.try_get_response(),
+                Some(expected_response)
+            );
+        }
+
+        // Verify that the latest commit is served (none has been published yet)
+        let (response_sender, mut response_receiver) = create_response_sender();
+        consensus_publisher.handle_subscription_request(
+            &peer_network_id,
+            ConsensusObserverRequest::GetLatestCommit,
+            response_sender,
+        );
+        assert_eq!(
+            response_receiver.try_get_response(),
+            Some(ConsensusObserverResponse::LatestCommit(None))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_block_and_payload_requests() {
+        // Create a consensus publisher (with a request burst of 2, and no refills)
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let network_client = NetworkClient::new(vec![], vec![], hashmap![], peers_and_metadata);
+        let consensus_observer_config = ConsensusObserverConfig {
+            enable_inbound_rate_limiting: true,
+            max_inbound_message_burst: 2,
+            max_inbound_messages_per_sec: 0,
+            ..ConsensusObserverConfig::default()
+        };
+        let (consensus_publisher, _) =
+            ConsensusPublisher::new(network_client, consensus_observer_config);
+
+        // Publish an ordered block and its block payload
+        let ordered_block = match create_ordered_block_message(1, 1) {
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block) => ordered_block,
+            message => panic!("Unexpected message: {:?}", message),
+        };
+        let block_payload = BlockPayload {
+            block: ordered_block.first_block().block_info(),
+            transactions: vec![],
+            limit: None,
+        };
+        consensus_publisher
+            .publish_message(ConsensusObserverDirectSend::OrderedBlock(
+                ordered_block.clone(),
+            ))
+            .await;
+        consensus_publisher
+            .publish_message(ConsensusObserverDirectSend::BlockPayload(
+                block_payload.clone(),
+            ))
+            .await;
+
+        // Verify that the requests of a peer without a subscription are not served
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        let block_id = ordered_block.first_block().id();
+        verify_block_and_payload_responses(
+            &consensus_publisher,
+            &peer_network_id,
+            block_id,
+            vec![],
+            None,
+        );
+
+        // Subscribe the peer and verify that the requests are served
+        process_subscription_for_peer(&consensus_publisher, &peer_network_id);
+        verify_block_and_payload_responses(
+            &consensus_publisher,
+            &peer_network_id,
+            block_id,
+            vec![ordered_block],
+            Some(block_payload),
+        );
+
+        // Verify that the requests are no longer served (the rate limit is exceeded)
+        verify_block_and_payload_responses(
+            &consensus_publisher,
+            &peer_network_id,
+            block_id,
+            vec![],
+            None,
+        );
+    }
+
     #[tokio::test]
     async fn test_publish_message() {
         // Create a network client
@@ -531,6 +1338,12 @@ mod test {
             assert_eq!(message, commit_decision_message);
         }
 
+        // Verify that the latest published commit is tracked
+        assert_eq!(
+            consensus_publisher.get_latest_commit_info(),
+            Some(BlockInfo::empty())
+        );
+
         // Unsubscribe the remaining peers from consensus updates
         for peer_network_id in additional_peer_network_ids {
             process_unsubscription_for_peer(&consensus_publisher, &peer_network_id);
@@ -547,7 +1360,309 @@ mod test {
         assert!(outbound_message_receiver.next().now_or_never().is_none());
     }
 
+    #[tokio::test]
+    async fn test_publish_compressed_payloads() {
+        // Create a network client
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let network_client =
+            NetworkClient::new(vec![], vec![], hashmap![], peers_and_metadata.clone());
+
+        // Create a consensus publisher (with dictionary compression enabled)
+        let consensus_observer_config = ConsensusObserverConfig {
+            enable_payload_dictionary_compression: true,
+            max_payload_dictionary_size_bytes: 4 * 1024, // 4 KB
+            ..ConsensusObserverConfig::default()
+        };
+        let (consensus_publisher, mut outbound_message_receiver) =
+            ConsensusPublisher::new(network_client, consensus_observer_config);
+
+        // Publish several block payloads (to record training samples) and train a dictionary
+        for round in 0..100 {
+            consensus_publisher
+                .publish_message(create_block_payload_message(round))
+                .await;
+        }
+        consensus_publisher.refresh_payload_dictionary().await;
+
+        // Subscribe a peer with dictionary compression, and a peer without it
+        let compression_peer = PeerNetworkId::new(network_id, PeerId::random());
+        consensus_publisher.handle_subscription_request(
+            &compression_peer,
+            ConsensusObserverRequest::SubscribeWithOptions(SubscriptionOptions {
+                dictionary_compression: true,
+                ..SubscriptionOptions::default()
+            }),
+            ResponseSender::new_for_test(),
+        );
+        let plain_peer = PeerNetworkId::new(network_id, PeerId::random());
+        process_subscription_for_peer(&consensus_publisher, &plain_peer);
+
+        // Publish a block payload and collect the sent messages for each peer
+        let block_payload_message = create_block_payload_message(100);
+        consensus_publisher
+            .publish_message(block_payload_message.clone())
+            .await;
+        let mut compression_peer_messages = vec![];
+        for _ in 0..3 {
+            let (peer_network_id, message) = outbound_message_receiver.next().await.unwrap();
+            if peer_network_id == compression_peer {
+                compression_peer_messages.push(message);
+            } else {
+                assert_eq!(peer_network_id, plain_peer);
+                assert_eq!(message, block_payload_message);
+            }
+        }
+
+        // Verify that the compression peer received the dictionary and compressed payload
+        let mut payload_decompressor = PayloadDictionaryDecompressor::new();
+        match &compression_peer_messages[..] {
+            [ConsensusObserverDirectSend::CompressionDictionary(dictionary), ConsensusObserverDirectSend::CompressedBlockPayload(compressed_block_payload)] =>
+            {
+                payload_decompressor.insert_dictionary(dictionary.clone());
+                let block_payload = payload_decompressor
+                    .decompress_payload(compressed_block_payload.clone())
+                    .unwrap();
+                assert_eq!(
+                    ConsensusObserverDirectSend::BlockPayload(block_payload),
+                    block_payload_message
+                );
+            },
+            messages => panic!(
+                "Unexpected messages for the compression peer: {:?}",
+                messages
+            ),
+        }
+
+        // Publish another block payload and verify the dictionary is not shipped again
+        consensus_publisher
+            .publish_message(create_block_payload_message(101))
+            .await;
+        for _ in 0..2 {
+            let (peer_network_id, message) = outbound_message_receiver.next().await.unwrap();
+            if peer_network_id == compression_peer {
+                assert!(matches!(
+                    message,
+                    ConsensusObserverDirectSend::CompressedBlockPayload(_)
+                ));
+            } else {
+                assert!(matches!(
+                    message,
+                    ConsensusObserverDirectSend::BlockPayload(_)
+                ));
+            }
+        }
+        assert!(outbound_message_receiver.next().now_or_never().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_publish_prefetched_payloads() {
+        // Create a network client
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let network_client =
+            NetworkClient::new(vec![], vec![], hashmap![], peers_and_metadata.clone());
+
+        // Create a consensus publisher (with payload prefetching enabled)
+        let consensus_observer_config = ConsensusObserverConfig {
+            enable_payload_prefetch: true,
+            ..ConsensusObserverConfig::default()
+        };
+        let (consensus_publisher, mut outbound_message_receiver) =
+            ConsensusPublisher::new(network_client, consensus_observer_config);
+        assert!(consensus_publisher.is_payload_prefetch_enabled());
+
+        // Subscribe a peer to the publisher
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        process_subscription_for_peer(&consensus_publisher, &peer_network_id);
+
+        // Publish a prefetched block payload and verify it is sent to the peer
+        let block_payload_message = create_block_payload_message(10);
+        consensus_publisher
+            .publish_prefetched_payload(block_payload_message.clone())
+            .await;
+        let (sent_peer_network_id, message) = outbound_message_receiver.next().await.unwrap();
+        assert_eq!(sent_peer_network_id, peer_network_id);
+        assert_eq!(message, block_payload_message);
+
+        // Publish the same payload again (e.g., on execution) and verify it is skipped
+        consensus_publisher
+            .publish_prefetched_payload(block_payload_message.clone())
+            .await;
+        consensus_publisher
+            .publish_message(block_payload_message)
+            .await;
+        assert!(outbound_message_receiver.next().now_or_never().is_none());
+
+        // Publish a payload that wasn't prefetched and verify it is sent to the peer
+        let block_payload_message = create_block_payload_message(11);
+        consensus_publisher
+            .publish_message(block_payload_message.clone())
+            .await;
+        let (_, message) = outbound_message_receiver.next().await.unwrap();
+        assert_eq!(message, block_payload_message);
+    }
+
+    #[tokio::test]
+    async fn test_subscription_replay() {
+        // Create a network client
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let network_client = NetworkClient::new(vec![], vec![], hashmap![], peers_and_metadata);
+
+        // Create a consensus publisher
+        let (consensus_publisher, mut outbound_message_receiver) =
+            ConsensusPublisher::new(network_client, ConsensusObserverConfig::default());
+
+        // Publish several block payloads, ordered blocks and commit decisions
+        // (before any peers subscribe).
+        let mut published_messages = vec![];
+        for round in 1..=5 {
+            let ordered_block_message = create_ordered_block_message(1, round);
+            let (block_payload_message, commit_decision_message) = match &ordered_block_message {
+                ConsensusObserverDirectSend::OrderedBlock(ordered_block) => (
+                    ConsensusObserverMessage::new_block_payload_message(
+                        ordered_block.first_block().block_info(),
+                        vec![],
+                        None,
+                    ),
+                    ConsensusObserverMessage::new_commit_decision_message(
+                        ordered_block.ordered_proof().clone(),
+                    ),
+                ),
+                message => panic!("Unexpected message: {:?}", message),
+            };
+            for message in [
+                block_payload_message,
+                ordered_block_message,
+                commit_decision_message,
+            ] {
+                consensus_publisher.publish_message(message.clone()).await;
+                published_messages.push(message);
+            }
+        }
+        assert!(outbound_message_receiver.next().now_or_never().is_none());
+
+        // Subscribe a peer (with a starting block) and verify the later blocks are replayed
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        consensus_publisher.handle_subscription_request(
+            &peer_network_id,
+            ConsensusObserverRequest::SubscribeWithOptions(SubscriptionOptions {
+                starting_block: Some((1, 3)),
+                ..SubscriptionOptions::default()
+            }),
+            ResponseSender::new_for_test(),
+        );
+        for expected_message in &published_messages[9..] {
+            let (sent_peer_network_id, message) = outbound_message_receiver.next().await.unwrap();
+            assert_eq!(sent_peer_network_id, peer_network_id);
+            assert_eq!(&message, expected_message);
+        }
+        assert!(outbound_message_receiver.next().now_or_never().is_none());
+
+        // Subscribe another peer (without a starting block) and verify nothing is replayed
+        let other_peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        process_subscription_for_peer(&consensus_publisher, &other_peer_network_id);
+        assert!(outbound_message_receiver.next().now_or_never().is_none());
+    }
+
+    /// Creates a block payload message (with several transactions) for the given round
+    fn create_block_payload_message(round: u64) -> ConsensusObserverDirectSend {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let transactions = (0..10)
+            .map(|sequence_number| {
+                get_test_signed_txn(
+                    AccountAddress::random(),
+                    sequence_number,
+                    &private_key,
+                    private_key.public_key(),
+                    None,
+                )
+            })
+            .collect();
+        ConsensusObserverMessage::new_block_payload_message(
+            BlockInfo::random_with_epoch(0, round),
+            transactions,
+            Some(round),
+        )
+    }
+
+    /// Creates an ordered block message (with a single block) for the given epoch and round
+    fn create_ordered_block_message(epoch: u64, round: Round) -> ConsensusObserverDirectSend {
+        let block_data = BlockData::new_for_testing(
+            epoch,
+            round,
+            round,
+            QuorumCert::dummy(),
+            BlockType::Genesis,
+        );
+        let block = Block::new_for_testing(HashValue::random(), block_data, None);
+        let ordered_proof = LedgerInfoWithSignatures::new(
+            LedgerInfo::new(
+                BlockInfo::random_with_epoch(epoch, round),
+                HashValue::zero(),
+            ),
+            AggregateSignature::empty(),
+        );
+        ConsensusObserverMessage::new_ordered_block_message(
+            vec![Arc::new(PipelinedBlock::new_ordered(block))],
+            ordered_proof,
+        )
+    }
+
+    /// Returns the epochs and rounds of the given ordered blocks
+    fn get_epochs_and_rounds(ordered_blocks: &[OrderedBlock]) -> Vec<(u64, Round)> {
+        ordered_blocks
+            .iter()
+            .map(|ordered_block| {
+                let proof_block_info = ordered_block.proof_block_info();
+                (proof_block_info.epoch(), proof_block_info.round())
+            })
+            .collect()
+    }
+
     /// Processes a subscription request for the given peer
+    /// Sends an ordered blocks request (for round 1) and a block payload request
+    /// (for the given block) from the peer, and verifies the responses.
+    fn verify_block_and_payload_responses(
+        consensus_publisher: &ConsensusPublisher,
+        peer_network_id: &PeerNetworkId,
+        block_id: HashValue,
+        expected_ordered_blocks: Vec<OrderedBlock>,
+        expected_block_payload: Option<BlockPayload>,
+    ) {
+        // Verify the ordered blocks response
+        let (response_sender, mut response_receiver) = create_response_sender();
+        consensus_publisher.handle_subscription_request(
+            peer_network_id,
+            ConsensusObserverRequest::GetOrderedBlocks {
+                start_round: 1,
+                end_round: 1,
+            },
+            response_sender,
+        );
+        assert_eq!(
+            response_receiver.try_get_response(),
+            Some(ConsensusObserverResponse::OrderedBlocks(
+                expected_ordered_blocks
+            ))
+        );
+
+        // Verify the block payload response
+        let (response_sender, mut response_receiver) = create_response_sender();
+        consensus_publisher.handle_subscription_request(
+            peer_network_id,
+            ConsensusObserverRequest::GetBlockPayload(block_id),
+            response_sender,
+        );
+        assert_eq!(
+            response_receiver.try_get_response(),
+            Some(ConsensusObserverResponse::BlockPayload(
+                expected_block_payload
+            ))
+        );
+    }
+
     fn process_subscription_for_peer(
         consensus_publisher: &ConsensusPublisher,
         peer_network_id: &PeerNetworkId,
@@ -0,0 +1,228 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    logging::{LogEntry, LogSchema},
+    message_interceptor::{ConsensusObserverMessageInterceptor, MessageInterceptorChain},
+    network_events::{ConsensusObserverNetworkEvents, NetworkMessage, ResponseSender},
+    network_message::{ConsensusObserverMessage, ConsensusObserverRequest},
+    observer,
+    peer_misbehavior::PeerMisbehavior,
+    publisher::ConsensusPublisher,
+};
+use aptos_config::network_id::PeerNetworkId;
+use aptos_logger::{error, info};
+use futures::StreamExt;
+use std::sync::Arc;
+
+/// A lightweight runtime for nodes that run the consensus publisher without
+/// the consensus observer. The runtime only forwards incoming subscription
+/// requests to the publisher, and so it doesn't require an execution client,
+/// storage or sync notifications (unlike the full consensus observer).
+pub struct PublisherOnlyRuntime {
+    // The consensus publisher to forward subscription requests to
+    consensus_publisher: Arc<ConsensusPublisher>,
+
+    // The chain of interceptors invoked on every inbound message
+    message_interceptors: MessageInterceptorChain,
+}
+
+impl PublisherOnlyRuntime {
+    pub fn new(consensus_publisher: Arc<ConsensusPublisher>) -> Self {
+        Self {
+            consensus_publisher,
+            message_interceptors: MessageInterceptorChain::new(),
+        }
+    }
+
+    /// Adds the given interceptor to the end of the inbound message interceptor chain
+    pub fn add_message_interceptor(
+        &self,
+        message_interceptor: Arc<dyn ConsensusObserverMessageInterceptor>,
+    ) {
+        self.message_interceptors
+            .add_interceptor(message_interceptor);
+    }
+
+    /// Processes the given network message (only requests are expected)
+    fn process_network_message(&self, network_message: NetworkMessage) {
+        // Unpack the network message
+        let NetworkMessage {
+            peer_network_id,
+            protocol_id: _,
+            consensus_observer_message,
+            response_sender,
+            num_message_bytes,
+        } = network_message;
+
+        // Update the inbound message metrics for the peer
+        observer::update_inbound_message_metrics(
+            &peer_network_id,
+            &consensus_observer_message,
+            num_message_bytes,
+        );
+
+        // Run the message through the interceptors (the message may be dropped)
+        let consensus_observer_message = match self
+            .message_interceptors
+            .intercept_inbound_message(&peer_network_id, consensus_observer_message)
+        {
+            Some(consensus_observer_message) => consensus_observer_message,
+            None => return, // The message was dropped by an interceptor
+        };
+
+        // Process the consensus observer message
+        match consensus_observer_message {
+            ConsensusObserverMessage::Request(request) => {
+                self.process_request_message(peer_network_id, request, response_sender);
+            },
+            _ => {
+                error!(
+                    LogSchema::new(LogEntry::ConsensusPublisher).message(&format!(
+                        "Received unexpected message from peer: {}",
+                        peer_network_id
+                    ))
+                );
+                self.consensus_publisher
+                    .get_peer_misbehavior_reporter()
+                    .report_misbehavior(
+                        &peer_network_id,
+                        PeerMisbehavior::UnexpectedMessage,
+                        "The publisher only expects request messages!",
+                    );
+            },
+        }
+    }
+
+    /// Forwards the given request message to the consensus publisher
+    fn process_request_message(
+        &self,
+        peer_network_id: PeerNetworkId,
+        request: ConsensusObserverRequest,
+        response_sender: Option<ResponseSender>,
+    ) {
+        // Ensure that the response sender is present
+        let response_sender = match response_sender {
+            Some(response_sender) => response_sender,
+            None => {
+                error!(
+                    LogSchema::new(LogEntry::ConsensusPublisher).message(&format!(
+                        "Missing response sender for RPC request: {:?}",
+                        request
+                    ))
+                );
+                return; // Something has gone wrong!
+            },
+        };
+
+        // Forward the request to the consensus publisher
+        self.consensus_publisher.handle_subscription_request(
+            &peer_network_id,
+            request,
+            response_sender,
+        );
+    }
+
+    /// Starts the publisher forwarding loop that forwards incoming
+    /// requests (from the network) to the consensus publisher.
+    pub async fn start(self, mut network_service_events: ConsensusObserverNetworkEvents) {
+        info!(LogSchema::new(LogEntry::ConsensusPublisher)
+            .message("Starting the consensus publisher forwarding loop!"));
+        while let Some(network_message) = network_service_events.next().await {
+            self.process_network_message(network_message);
+        }
+
+        // The network events stream has terminated
+        error!(LogSchema::new(LogEntry::ConsensusPublisher)
+            .message("The consensus publisher forwarding loop has stopped!"));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::network_message::ConsensusObserverResponse;
+    use aptos_config::{config::ConsensusObserverConfig, network_id::NetworkId};
+    use aptos_network::application::{interface::NetworkClient, storage::PeersAndMetadata};
+    use aptos_types::PeerId;
+    use bytes::Bytes;
+    use futures::channel::{mpsc, oneshot};
+    use maplit::hashmap;
+
+    #[tokio::test]
+    async fn test_forward_subscription_requests() {
+        // Create a consensus publisher
+        let network_id = NetworkId::Public;
+        let network_client = NetworkClient::new(
+            vec![],
+            vec![],
+            hashmap![],
+            PeersAndMetadata::new(&[network_id]),
+        );
+        let (consensus_publisher, _) =
+            ConsensusPublisher::new(network_client, ConsensusObserverConfig::default());
+        let consensus_publisher = Arc::new(consensus_publisher);
+
+        // Create and start the publisher only runtime
+        let (network_message_sender, network_message_receiver) = mpsc::unbounded();
+        let network_events = ConsensusObserverNetworkEvents::new_for_test(network_message_receiver);
+        let publisher_only_runtime = PublisherOnlyRuntime::new(consensus_publisher.clone());
+        tokio::spawn(publisher_only_runtime.start(network_events));
+
+        // Send a subscription request from a peer and verify the response
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        let response = send_request(
+            &network_message_sender,
+            peer_network_id,
+            ConsensusObserverRequest::Subscribe,
+        )
+        .await;
+        assert!(matches!(response, ConsensusObserverResponse::SubscribeAck));
+
+        // Verify that the peer is now an active subscriber
+        let active_subscribers = consensus_publisher.get_active_subscribers();
+        assert!(active_subscribers.contains(&peer_network_id));
+
+        // Send an unsubscribe request from the peer and verify the response
+        let response = send_request(
+            &network_message_sender,
+            peer_network_id,
+            ConsensusObserverRequest::Unsubscribe,
+        )
+        .await;
+        assert!(matches!(
+            response,
+            ConsensusObserverResponse::UnsubscribeAck
+        ));
+
+        // Verify that the peer is no longer an active subscriber
+        assert!(consensus_publisher.get_active_subscribers().is_empty());
+    }
+
+    /// Sends the given request (from the specified peer) to the runtime and returns the response
+    async fn send_request(
+        network_message_sender: &mpsc::UnboundedSender<NetworkMessage>,
+        peer_network_id: PeerNetworkId,
+        request: ConsensusObserverRequest,
+    ) -> ConsensusObserverResponse {
+        // Send the request to the runtime
+        let (response_tx, response_rx) = oneshot::channel();
+        let network_message = NetworkMessage {
+            peer_network_id,
+            protocol_id: None,
+            consensus_observer_message: ConsensusObserverMessage::Request(request),
+            response_sender: Some(ResponseSender::new(response_tx)),
+            num_message_bytes: 0,
+        };
+        network_message_sender
+            .unbounded_send(network_message)
+            .unwrap();
+
+        // Wait for the response and deserialize it
+        let response_bytes: Bytes = response_rx.await.unwrap().unwrap();
+        match bcs::from_bytes(&response_bytes).unwrap() {
+            ConsensusObserverMessage::Response(response) => response,
+            message => panic!("Unexpected message received: {:?}", message),
+        }
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::network_message::{
+    BlockPayload, ConsensusObserverDirectSend, OrderedBlock,
+};
+use aptos_crypto::HashValue;
+use aptos_types::block_info::Round;
+use std::collections::VecDeque;
+
+/// A bounded ring buffer of the messages recently broadcast by the publisher
+/// (i.e., ordered blocks, block payloads and commit decisions), in broadcast
+/// order. The buffer is used to catch up late-joining subscribers, and to serve
+/// observers that are missing blocks or payloads. Only the messages of the
+/// latest epoch are retained, as older messages can't be used by observers.
+pub struct PublisherReplayBuffer {
+    // The maximum number of messages to retain
+    max_num_messages: usize,
+
+    // The epoch of the most recently buffered messages
+    latest_epoch: u64,
+
+    // The buffered messages (in broadcast order)
+    buffered_messages: VecDeque<ConsensusObserverDirectSend>,
+}
+
+impl PublisherReplayBuffer {
+    pub fn new(max_num_messages: usize) -> Self {
+        Self {
+            max_num_messages,
+            latest_epoch: 0,
+            buffered_messages: VecDeque::new(),
+        }
+    }
+
+    /// Returns the buffered payload for the given block (if any)
+    pub fn get_block_payload(&self, block_id: &HashValue) -> Option<BlockPayload> {
+        self.buffered_messages
+            .iter()
+            .rev()
+            .find_map(|message| match message {
+                ConsensusObserverDirectSend::BlockPayload(block_payload)
+                    if block_payload.block.id() == *block_id =>
+                {
+                    Some(block_payload.clone())
+                },
+                _ => None,
+            })
+    }
+
+    /// Returns the buffered messages after the given block (i.e., epoch
+    /// and round), in broadcast order.
+    pub fn get_messages_after(
+        &self,
+        starting_block: (u64, Round),
+    ) -> Vec<ConsensusObserverDirectSend> {
+        self.buffered_messages
+            .iter()
+            .filter(|message| {
+                get_epoch_and_round(message)
+                    .is_some_and(|epoch_and_round| epoch_and_round > starting_block)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the buffered ordered blocks that contain any blocks
+    /// in the given round range (sorted by round).
+    pub fn get_ordered_blocks(&self, start_round: Round, end_round: Round) -> Vec<OrderedBlock> {
+        let mut ordered_blocks: Vec<_> = self
+            .buffered_messages
+            .iter()
+            .filter_map(|message| match message {
+                ConsensusObserverDirectSend::OrderedBlock(ordered_block)
+                    if ordered_block.proof_block_info().round() >= start_round
+                        && ordered_block.first_block().round() <= end_round =>
+                {
+                    Some(ordered_block.clone())
+                },
+                _ => None,
+            })
+            .collect();
+        ordered_blocks.sort_by_key(|ordered_block| ordered_block.proof_block_info().round());
+        ordered_blocks
+    }
+
+    /// Records the given broadcast message (messages that shouldn't be replayed,
+    /// e.g., compression dictionaries, are ignored). The oldest messages are
+    /// evicted if the buffer is full, and all messages of previous epochs are
+    /// evicted once a message for a new epoch is recorded.
+    pub fn record_message(&mut self, message: &ConsensusObserverDirectSend) {
+        // Ignore the messages that shouldn't be replayed (or are for a previous epoch)
+        let (epoch, _) = match get_epoch_and_round(message) {
+            Some(epoch_and_round) => epoch_and_round,
+            None => return,
+        };
+        if epoch < self.latest_epoch {
+            return;
+        }
+
+        // Evict the messages of previous epochs
+        if epoch > self.latest_epoch {
+            self.latest_epoch = epoch;
+            self.buffered_messages.clear();
+        }
+
+        // Buffer the message (and evict the oldest message, if required)
+        self.buffered_messages.push_back(message.clone());
+        while self.buffered_messages.len() > self.max_num_messages {
+            self.buffered_messages.pop_front();
+        }
+    }
+}
+
+/// Returns the epoch and round of the given message, or None
+/// if the message shouldn't be buffered for replay.
+fn get_epoch_and_round(message: &ConsensusObserverDirectSend) -> Option<(u64, Round)> {
+    let block_info = match message {
+        ConsensusObserverDirectSend::OrderedBlock(ordered_block) => {
+            ordered_block.proof_block_info()
+        },
+        ConsensusObserverDirectSend::CommitDecision(commit_decision) => {
+            commit_decision.proof_block_info()
+        },
+        ConsensusObserverDirectSend::BlockPayload(block_payload) => &block_payload.block,
+        _ => return None,
+    };
+    Some((block_info.epoch(), block_info.round()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::network_message::ConsensusObserverMessage;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        block_info::BlockInfo,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+
+    #[test]
+    fn test_record_and_replay_messages() {
+        // Create a replay buffer
+        let mut replay_buffer = PublisherReplayBuffer::new(10);
+
+        // Record a payload, ordered block and commit decision for several rounds
+        let mut recorded_messages = vec![];
+        for round in 1..=3 {
+            for message in create_block_messages(1, round) {
+                replay_buffer.record_message(&message);
+                recorded_messages.push(message);
+            }
+        }
+
+        // Verify that the messages after the starting block are replayed (in order)
+        assert_eq!(replay_buffer.get_messages_after((0, 10)), recorded_messages);
+        assert_eq!(
+            replay_buffer.get_messages_after((1, 1)),
+            recorded_messages[3..].to_vec()
+        );
+        assert!(replay_buffer.get_messages_after((1, 3)).is_empty());
+
+        // Record more messages and verify the oldest messages are evicted
+        for message in create_block_messages(1, 4) {
+            replay_buffer.record_message(&message);
+            recorded_messages.push(message);
+        }
+        assert_eq!(
+            replay_buffer.get_messages_after((0, 0)),
+            recorded_messages[2..].to_vec()
+        );
+
+        // Record a message for a new epoch and verify the previous epoch is evicted
+        let new_epoch_messages = create_block_messages(2, 1);
+        replay_buffer.record_message(&new_epoch_messages[0]);
+        assert_eq!(replay_buffer.get_messages_after((0, 0)), vec![
+            new_epoch_messages[0].clone()
+        ]);
+
+        // Verify that messages for previous epochs are ignored
+        replay_buffer.record_message(&recorded_messages[0]);
+        assert_eq!(replay_buffer.get_messages_after((0, 0)).len(), 1);
+    }
+
+    /// Creates a block payload, ordered block and commit decision message
+    /// (in that order) for the given epoch and round.
+    fn create_block_messages(epoch: u64, round: Round) -> Vec<ConsensusObserverDirectSend> {
+        let block_info = BlockInfo::random_with_epoch(epoch, round);
+        let ledger_info = LedgerInfoWithSignatures::new(
+            LedgerInfo::new(block_info.clone(), HashValue::zero()),
+            AggregateSignature::empty(),
+        );
+        vec![
+            ConsensusObserverMessage::new_block_payload_message(block_info, vec![], None),
+            ConsensusObserverMessage::new_ordered_block_message(vec![], ledger_info.clone()),
+            ConsensusObserverMessage::new_commit_decision_message(ledger_info),
+        ]
+    }
+}
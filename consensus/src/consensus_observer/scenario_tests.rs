@@ -0,0 +1,1854 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic end-to-end scenarios for the consensus observer. Each scenario
+//! is scripted using the simulation harness, and asserts on the calls made to
+//! the execution client, the event journal and the emitted metrics.
+
+use crate::consensus_observer::{
+    error::Error,
+    event_journal::ObserverEvent,
+    event_listener::ObserverEventListener,
+    metrics,
+    network_message::{
+        CommitDecision, ConsensusObserverDirectSend, ConsensusObserverMessage,
+        ConsensusObserverRequest, OrderedBlock,
+    },
+    test_harness::{create_genesis_block, ExecutionClientCall, ObserverTestHarness, GENESIS_EPOCH},
+};
+use anyhow::anyhow;
+use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
+use aptos_infallible::Mutex;
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::{BlockInfo, Round},
+    ledger_info::LedgerInfoWithSignatures,
+};
+use std::{sync::Arc, time::Duration};
+
+#[tokio::test]
+async fn test_publisher_disconnects_mid_epoch() {
+    // Create a test harness and add two publishers (the first is preferred)
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher_1 = harness.add_publisher_peer(0);
+    let publisher_2 = harness.add_publisher_peer(1);
+
+    // Verify that the observer subscribes to the first publisher
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher_1]);
+    verify_active_subscription_peer_gauge(&publisher_1, 1);
+
+    // Send and commit the first block from the first publisher
+    let ordered_block_1 = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    send_and_commit_block(&mut harness, publisher_1, &ordered_block_1).await;
+
+    // Disconnect the first publisher mid-epoch and check progress
+    let num_disconnected_subscriptions =
+        get_terminated_subscriptions("subscription_disconnected", &publisher_1);
+    harness.disconnect_peer(publisher_1);
+    harness.check_progress().await;
+
+    // Verify that the subscription failed over to the second publisher
+    assert_eq!(get_subscribe_requests(&harness), vec![
+        publisher_1,
+        publisher_2
+    ]);
+    verify_active_subscription_peer_gauge(&publisher_1, 0);
+    verify_active_subscription_peer_gauge(&publisher_2, 1);
+    assert!(
+        get_terminated_subscriptions("subscription_disconnected", &publisher_1)
+            > num_disconnected_subscriptions
+    );
+
+    // Send and commit the second block from the second publisher
+    let ordered_block_2 =
+        harness.create_ordered_block(ordered_block_1.proof_block_info(), GENESIS_EPOCH, 2);
+    send_and_commit_block(&mut harness, publisher_2, &ordered_block_2).await;
+
+    // Verify that blocks from the first publisher are now received, but ignored
+    let num_ordered_blocks = get_received_messages_for_peer(&publisher_1, "ordered_block");
+    let ordered_block_3 =
+        harness.create_ordered_block(ordered_block_2.proof_block_info(), GENESIS_EPOCH, 3);
+    harness
+        .send_direct_send_message(
+            publisher_1,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block_3),
+        )
+        .await;
+    assert!(get_received_messages_for_peer(&publisher_1, "ordered_block") > num_ordered_blocks);
+
+    // Verify the calls made to the execution client (the third block was dropped)
+    let block_info_1 = ordered_block_1.proof_block_info().clone();
+    let block_info_2 = ordered_block_2.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::FinalizeOrder(block_info_1.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info_1),
+        ExecutionClientCall::FinalizeOrder(block_info_2.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info_2),
+    ]);
+}
+
+#[tokio::test]
+async fn test_commit_decision_for_future_epoch() {
+    // Create a test harness (with a short future commit buffer) and subscribe to a publisher
+    let consensus_observer_config = ConsensusObserverConfig {
+        max_future_commit_buffer_ms: 1000,
+        ..ConsensusObserverConfig::default()
+    };
+    let mut harness = ObserverTestHarness::new(consensus_observer_config.clone());
+    harness.start_epoch(GENESIS_EPOCH).await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Send a commit decision for a block in the next epoch (the epoch proof is unknown)
+    let next_epoch = GENESIS_EPOCH + 1;
+    let num_buffered_decisions = get_future_commit_decisions(metrics::FUTURE_COMMIT_BUFFERED_LABEL);
+    let future_block = harness.create_ordered_block(&harness.genesis_block(), next_epoch, 5);
+    let commit_decision = harness.create_commit_decision(&future_block);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+
+    // Verify that the decision was buffered (and didn't trigger a state sync)
+    assert!(
+        get_future_commit_decisions(metrics::FUTURE_COMMIT_BUFFERED_LABEL) > num_buffered_decisions
+    );
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH)
+    ]);
+
+    // Send a (forged) commit decision far ahead of the current epoch
+    let num_rejected_decisions = get_future_commit_decisions(metrics::FUTURE_COMMIT_REJECTED_LABEL);
+    let forged_epoch = GENESIS_EPOCH + consensus_observer_config.max_future_commit_epochs + 1;
+    let forged_block = harness.create_ordered_block(&harness.genesis_block(), forged_epoch, 1);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(
+                harness.create_commit_decision(&forged_block),
+            ),
+        )
+        .await;
+
+    // Verify that the forged decision was dropped (instead of being buffered)
+    assert!(
+        get_future_commit_decisions(metrics::FUTURE_COMMIT_REJECTED_LABEL) > num_rejected_decisions
+    );
+
+    // Elapse the buffer duration and verify the decision expires
+    let num_expired_decisions = get_future_commit_decisions(metrics::FUTURE_COMMIT_EXPIRED_LABEL);
+    let num_terminated_subscriptions =
+        get_terminated_subscriptions("subscription_verification_failed", &publisher);
+    harness.advance_time(Duration::from_millis(
+        consensus_observer_config.max_future_commit_buffer_ms + 1,
+    ));
+    harness.check_progress().await;
+    assert!(
+        get_future_commit_decisions(metrics::FUTURE_COMMIT_EXPIRED_LABEL) > num_expired_decisions
+    );
+
+    // Verify that the subscription to the publisher was terminated
+    assert!(
+        get_terminated_subscriptions("subscription_verification_failed", &publisher)
+            > num_terminated_subscriptions
+    );
+    assert!(
+        get_journal_events(&harness).contains(&ObserverEvent::SubscriptionTerminated {
+            peer_network_id: publisher,
+            reason: format!(
+                "Subscription verification failed: The commit decision for epoch: {}, round: 5 \
+                could not be verified in time",
+                next_epoch
+            ),
+        })
+    );
+
+    // Verify that the unverified decision never triggered a state sync
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH)
+    ]);
+    assert_eq!(
+        harness.get_latest_ledger_info().ledger_info().epoch(),
+        GENESIS_EPOCH
+    );
+}
+
+#[tokio::test]
+async fn test_payload_arrives_after_block() {
+    // Create a test harness and subscribe to a publisher
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Send an ordered block (without the payload) and verify it is finalized
+    let ordered_block = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+        )
+        .await;
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 1);
+
+    // Send the commit decision and verify it is not forwarded (the payload is missing)
+    let commit_decision = harness.create_commit_decision(&ordered_block);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 1);
+    assert_eq!(
+        harness.get_latest_ledger_info().commit_info(),
+        &harness.genesis_block()
+    );
+
+    // Send the block payload, followed by the commit decision (again)
+    let block_payload_message = harness.create_block_payload_message(&ordered_block);
+    harness
+        .send_direct_send_message(publisher, block_payload_message)
+        .await;
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+
+    // Verify the block is now committed
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 0);
+    assert_eq!(
+        harness.get_latest_ledger_info(),
+        commit_decision.commit_proof().clone()
+    );
+
+    // Verify the calls made to the execution client
+    let block_info = ordered_block.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::FinalizeOrder(block_info.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info),
+    ]);
+}
+
+#[tokio::test]
+async fn test_incremental_block_finalization() {
+    // Create a test harness (with incremental finalization enabled) and subscribe to a publisher
+    let consensus_observer_config = ConsensusObserverConfig {
+        enable_incremental_block_finalization: true,
+        ..ConsensusObserverConfig::default()
+    };
+    let mut harness = ObserverTestHarness::new(consensus_observer_config);
+    harness.start_epoch(GENESIS_EPOCH).await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Send two ordered blocks (each with several blocks, but without the payloads)
+    let ordered_block_1 =
+        harness.create_ordered_block_with_blocks(&harness.genesis_block(), GENESIS_EPOCH, 1, 3);
+    let ordered_block_2 = harness.create_ordered_block_with_blocks(
+        ordered_block_1.proof_block_info(),
+        GENESIS_EPOCH,
+        4,
+        2,
+    );
+    for ordered_block in [&ordered_block_1, &ordered_block_2] {
+        harness
+            .send_direct_send_message(
+                publisher,
+                ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+            )
+            .await;
+    }
+
+    // Verify that no blocks were sent to the pipeline (the payloads are missing)
+    assert_eq!(
+        harness.execution_client().get_num_pending_commit_blocks(),
+        0
+    );
+
+    // Send the payload of the first block, and verify the block is sent to the pipeline
+    let blocks_1 = ordered_block_1.blocks();
+    let payload_message = harness.create_block_payload_message_for_block(&blocks_1[0]);
+    harness
+        .send_direct_send_message(publisher, payload_message)
+        .await;
+    assert_eq!(
+        harness.execution_client().get_num_pending_commit_blocks(),
+        1
+    );
+
+    // Send the payloads of the second ordered block, and verify that no
+    // blocks are sent (the blocks must be sent in order).
+    for block in ordered_block_2.blocks() {
+        let payload_message = harness.create_block_payload_message_for_block(block);
+        harness
+            .send_direct_send_message(publisher, payload_message)
+            .await;
+    }
+    assert_eq!(
+        harness.execution_client().get_num_pending_commit_blocks(),
+        1
+    );
+
+    // Send the payload of the second block, and verify the block is sent to the pipeline
+    let payload_message = harness.create_block_payload_message_for_block(&blocks_1[1]);
+    harness
+        .send_direct_send_message(publisher, payload_message)
+        .await;
+    assert_eq!(
+        harness.execution_client().get_num_pending_commit_blocks(),
+        2
+    );
+
+    // Send the commit decision for the second ordered block, and verify nothing
+    // is committed (the pipeline doesn't hold the committed block yet).
+    let commit_decision = harness.create_commit_decision(&ordered_block_2);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+    assert_eq!(
+        harness.get_latest_ledger_info().commit_info(),
+        &harness.genesis_block()
+    );
+
+    // Send the last missing payload, and verify all blocks are sent to the
+    // pipeline and committed (the commit decision is forwarded again).
+    let payload_message = harness.create_block_payload_message_for_block(&blocks_1[2]);
+    harness
+        .send_direct_send_message(publisher, payload_message)
+        .await;
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 0);
+    assert_eq!(
+        harness.get_latest_ledger_info(),
+        commit_decision.commit_proof().clone()
+    );
+
+    // Verify the calls made to the execution client
+    let block_info_1 = ordered_block_1.proof_block_info().clone();
+    let block_info_2 = ordered_block_2.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::FinalizeOrder(block_info_1.clone()),
+        ExecutionClientCall::FinalizeOrder(block_info_1.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info_2.clone()),
+        ExecutionClientCall::FinalizeOrder(block_info_1),
+        ExecutionClientCall::FinalizeOrder(block_info_2.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info_2),
+    ]);
+}
+
+#[tokio::test]
+async fn test_publisher_restarts() {
+    // Create a test harness and subscribe to a single publisher
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+    verify_active_subscription_peer_gauge(&publisher, 1);
+
+    // Send and commit the first block
+    let ordered_block_1 = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    send_and_commit_block(&mut harness, publisher, &ordered_block_1).await;
+
+    // Disconnect the publisher (e.g., the publisher restarts) and check progress
+    harness.disconnect_peer(publisher);
+    harness.check_progress().await;
+
+    // Verify the subscription was terminated (and no new subscription was created)
+    verify_active_subscription_peer_gauge(&publisher, 0);
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher]);
+    assert!(
+        get_journal_events(&harness).contains(&ObserverEvent::SubscriptionTerminated {
+            peer_network_id: publisher,
+            reason: "Subscription disconnected: The peer is no longer connected!".into(),
+        })
+    );
+
+    // Reconnect the publisher and verify that the observer resubscribes
+    harness.reconnect_peer(publisher);
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher, publisher]);
+    verify_active_subscription_peer_gauge(&publisher, 1);
+
+    // Send and commit the second block (after the restart)
+    let ordered_block_2 =
+        harness.create_ordered_block(ordered_block_1.proof_block_info(), GENESIS_EPOCH, 2);
+    send_and_commit_block(&mut harness, publisher, &ordered_block_2).await;
+    assert_eq!(
+        harness.get_latest_ledger_info().commit_info(),
+        ordered_block_2.proof_block_info()
+    );
+}
+
+#[tokio::test]
+async fn test_speculative_forwarding_with_invalid_proof() {
+    // Create a test harness (with speculative forwarding enabled) and subscribe to a publisher
+    let consensus_observer_config = ConsensusObserverConfig {
+        enable_speculative_forwarding: true,
+        ..ConsensusObserverConfig::default()
+    };
+    let mut harness = ObserverTestHarness::new(consensus_observer_config);
+    harness.start_epoch(GENESIS_EPOCH).await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Send and commit the first block (the block is forwarded speculatively)
+    let ordered_block_1 = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    send_and_commit_block(&mut harness, publisher, &ordered_block_1).await;
+
+    // Send the second block with an invalid (i.e., unsigned) ordered proof
+    let ordered_block_2 =
+        harness.create_ordered_block(ordered_block_1.proof_block_info(), GENESIS_EPOCH, 2);
+    let invalid_ordered_proof = LedgerInfoWithSignatures::new(
+        ordered_block_2.ordered_proof().ledger_info().clone(),
+        AggregateSignature::empty(),
+    );
+    let invalid_ordered_block =
+        OrderedBlock::new(ordered_block_2.blocks().clone(), invalid_ordered_proof);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::OrderedBlock(invalid_ordered_block),
+        )
+        .await;
+
+    // Verify that the pipeline is reset by syncing to the latest commit
+    let (epoch, round) = harness.wait_for_sync_notification().await;
+    assert_eq!((epoch, round), (GENESIS_EPOCH, 1));
+
+    // Verify the calls made to the execution client (the second block was forwarded
+    // speculatively, but never committed).
+    let block_info_1 = ordered_block_1.proof_block_info().clone();
+    let block_info_2 = ordered_block_2.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::FinalizeOrder(block_info_1.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info_1.clone()),
+        ExecutionClientCall::FinalizeOrder(block_info_2),
+        ExecutionClientCall::Reset(block_info_1.clone()),
+        ExecutionClientCall::SyncTo(block_info_1),
+    ]);
+}
+
+#[tokio::test]
+async fn test_speculative_forwarding_with_invalid_payload() {
+    // Create a test harness (with speculative forwarding enabled) and subscribe to a publisher
+    let consensus_observer_config = ConsensusObserverConfig {
+        enable_speculative_forwarding: true,
+        ..ConsensusObserverConfig::default()
+    };
+    let mut harness = ObserverTestHarness::new(consensus_observer_config);
+    harness.start_epoch(GENESIS_EPOCH).await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Send and commit the first block (the block is forwarded speculatively)
+    let ordered_block_1 = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    send_and_commit_block(&mut harness, publisher, &ordered_block_1).await;
+
+    // Send an invalid payload (i.e., with a mismatched timestamp) for the second
+    // block, before the ordered block arrives (so the payload is stored unverified).
+    let ordered_block_2 =
+        harness.create_ordered_block(ordered_block_1.proof_block_info(), GENESIS_EPOCH, 2);
+    let block_info_2 = ordered_block_2.proof_block_info().clone();
+    let invalid_payload_block = BlockInfo::new(
+        block_info_2.epoch(),
+        block_info_2.round(),
+        block_info_2.id(),
+        block_info_2.executed_state_id(),
+        block_info_2.version(),
+        block_info_2.timestamp_usecs() + 1,
+        None,
+    );
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverMessage::new_block_payload_message(
+                invalid_payload_block,
+                vec![],
+                None,
+            ),
+        )
+        .await;
+
+    // Send the second block (the block is forwarded speculatively, with the stored payload)
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block_2),
+        )
+        .await;
+
+    // Verify that the pipeline is reset by syncing to the latest commit
+    let (epoch, round) = harness.wait_for_sync_notification().await;
+    assert_eq!((epoch, round), (GENESIS_EPOCH, 1));
+
+    // Verify the calls made to the execution client (the second block was forwarded
+    // speculatively, but never committed).
+    let block_info_1 = ordered_block_1.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::FinalizeOrder(block_info_1.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info_1.clone()),
+        ExecutionClientCall::FinalizeOrder(block_info_2),
+        ExecutionClientCall::Reset(block_info_1.clone()),
+        ExecutionClientCall::SyncTo(block_info_1),
+    ]);
+}
+
+#[tokio::test]
+async fn test_forced_state_sync() {
+    // Create a test harness and subscribe to a publisher
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher]);
+
+    // Send and commit the first block
+    let ordered_block_1 = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    send_and_commit_block(&mut harness, publisher, &ordered_block_1).await;
+
+    // Verify that a forced sync to the root (or behind it) is rejected
+    let commit_decision_1 = harness.create_commit_decision(&ordered_block_1);
+    let result = harness.force_state_sync(Some(commit_decision_1.commit_proof().clone()));
+    assert!(matches!(result, Err(Error::InvalidMessageError(_))));
+
+    // Force a sync to a commit beyond the root
+    let ordered_block_2 =
+        harness.create_ordered_block(ordered_block_1.proof_block_info(), GENESIS_EPOCH, 2);
+    let commit_decision_2 = harness.create_commit_decision(&ordered_block_2);
+    let block_info_2 = ordered_block_2.proof_block_info().clone();
+    let sync_target = harness
+        .force_state_sync(Some(commit_decision_2.commit_proof().clone()))
+        .unwrap();
+    assert_eq!(sync_target, block_info_2);
+
+    // Verify that observation is paused (i.e., there is no resubscription)
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher]);
+
+    // Wait for the sync to complete and verify the new root
+    let (epoch, round) = harness.wait_for_sync_notification().await;
+    assert_eq!((epoch, round), (GENESIS_EPOCH, 2));
+    assert_eq!(
+        harness.get_latest_ledger_info(),
+        commit_decision_2.commit_proof().clone()
+    );
+
+    // Verify that observation resumes (i.e., the observer resubscribes)
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher, publisher]);
+
+    // Verify the calls made to the execution client
+    let block_info_1 = ordered_block_1.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::FinalizeOrder(block_info_1.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info_1),
+        ExecutionClientCall::Reset(block_info_2.clone()),
+        ExecutionClientCall::SyncTo(block_info_2),
+    ]);
+}
+
+#[tokio::test]
+async fn test_subscription_rotation() {
+    // Create a test harness (with subscription rotation enabled) and add two publishers
+    let consensus_observer_config = ConsensusObserverConfig {
+        enable_subscription_rotation: true,
+        subscription_rotation_interval_ms: 10_000,
+        subscription_rotation_candidates: 2,
+        ..ConsensusObserverConfig::default()
+    };
+    let mut harness = ObserverTestHarness::new(consensus_observer_config);
+    harness.start_epoch(GENESIS_EPOCH).await;
+    let publisher_1 = harness.add_publisher_peer(0);
+    let publisher_2 = harness.add_publisher_peer(1);
+
+    // Verify that the observer subscribes to the first publisher
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher_1]);
+
+    // Send and commit the first block from the first publisher
+    let ordered_block_1 = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    send_and_commit_block(&mut harness, publisher_1, &ordered_block_1).await;
+
+    // Check progress (before the rotation interval elapses) and verify there is no rotation
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher_1]);
+
+    // Check progress (after the rotation interval elapses) and verify the rotation
+    let num_rotated_subscriptions =
+        get_terminated_subscriptions("subscription_rotated", &publisher_1);
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![
+        publisher_1,
+        publisher_2
+    ]);
+    verify_active_subscription_peer_gauge(&publisher_1, 0);
+    verify_active_subscription_peer_gauge(&publisher_2, 1);
+    assert!(
+        get_terminated_subscriptions("subscription_rotated", &publisher_1)
+            > num_rotated_subscriptions
+    );
+    assert!(
+        get_journal_events(&harness).contains(&ObserverEvent::SubscriptionTerminated {
+            peer_network_id: publisher_1,
+            reason: format!(
+                "Subscription rotated: The subscription was rotated to peer: {}",
+                publisher_2
+            ),
+        })
+    );
+
+    // Send and commit the second block from the second publisher
+    let ordered_block_2 =
+        harness.create_ordered_block(ordered_block_1.proof_block_info(), GENESIS_EPOCH, 2);
+    send_and_commit_block(&mut harness, publisher_2, &ordered_block_2).await;
+
+    // Elapse the rotation interval and verify the subscription rotates back
+    harness.check_progress().await;
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![
+        publisher_1,
+        publisher_2,
+        publisher_1
+    ]);
+    verify_active_subscription_peer_gauge(&publisher_1, 1);
+    verify_active_subscription_peer_gauge(&publisher_2, 0);
+}
+
+#[tokio::test]
+async fn test_invalid_block_timestamps() {
+    // Create a test harness (with a non-zero genesis timestamp and timestamp
+    // rejection enabled) and subscribe to a publisher.
+    let genesis_block = create_genesis_block_with_timestamp(100);
+    let consensus_observer_config = ConsensusObserverConfig {
+        reject_invalid_block_timestamps: true,
+        ..ConsensusObserverConfig::default()
+    };
+    let mut harness = ObserverTestHarness::new_with_genesis_block(
+        consensus_observer_config,
+        genesis_block.clone(),
+        None,
+    );
+    harness.start_epoch(GENESIS_EPOCH).await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Send a block with a timestamp before the parent timestamp
+    let num_rejected_blocks = get_block_timestamp_violations(metrics::TIMESTAMP_REJECTED_LABEL);
+    let stale_parent_block = BlockInfo::new(
+        GENESIS_EPOCH,
+        0,
+        genesis_block.id(),
+        genesis_block.executed_state_id(),
+        0,
+        0,
+        None,
+    );
+    let stale_ordered_block = harness.create_ordered_block(&stale_parent_block, GENESIS_EPOCH, 1);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::OrderedBlock(stale_ordered_block),
+        )
+        .await;
+
+    // Send a block with a timestamp too far in the future
+    let future_parent_block = BlockInfo::new(
+        GENESIS_EPOCH,
+        0,
+        genesis_block.id(),
+        genesis_block.executed_state_id(),
+        0,
+        Duration::from_secs(3600).as_micros() as u64,
+        None,
+    );
+    let future_ordered_block = harness.create_ordered_block(&future_parent_block, GENESIS_EPOCH, 1);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::OrderedBlock(future_ordered_block),
+        )
+        .await;
+
+    // Verify that both blocks were rejected
+    assert!(
+        get_block_timestamp_violations(metrics::TIMESTAMP_REJECTED_LABEL)
+            >= num_rejected_blocks + 2
+    );
+    let num_timestamp_failures = get_journal_events(&harness)
+        .iter()
+        .filter(|event| {
+            matches!(event, ObserverEvent::VerificationFailed { message_type, .. }
+                if message_type == "block_timestamps")
+        })
+        .count();
+    assert_eq!(num_timestamp_failures, 2);
+
+    // Verify that a block with a valid timestamp is still committed
+    let ordered_block = harness.create_ordered_block(&genesis_block, GENESIS_EPOCH, 1);
+    send_and_commit_block(&mut harness, publisher, &ordered_block).await;
+
+    // Verify the calls made to the execution client (the invalid blocks were never finalized)
+    let block_info = ordered_block.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::FinalizeOrder(block_info.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info),
+    ]);
+}
+
+#[tokio::test]
+async fn test_subscription_timeout_probes() {
+    // Create a test harness and add two publishers
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher_1 = harness.add_publisher_peer(0);
+    let publisher_2 = harness.add_publisher_peer(1);
+
+    // Verify that the observer subscribes to the first publisher
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher_1]);
+
+    // Send and commit the first block
+    let ordered_block_1 = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    send_and_commit_block(&mut harness, publisher_1, &ordered_block_1).await;
+
+    // Elapse the subscription timeout (the publisher has no newer commits)
+    let subscription_timeout =
+        Duration::from_millis(ConsensusObserverConfig::default().max_subscription_timeout_ms);
+    harness.set_peer_latest_commit(publisher_1, ordered_block_1.proof_block_info().clone());
+    harness.advance_time(subscription_timeout);
+    harness.check_progress().await;
+
+    // Verify that the publisher was probed, and that the subscription was retained
+    assert_eq!(get_probe_requests(&harness), vec![publisher_1]);
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher_1]);
+    verify_active_subscription_peer_gauge(&publisher_1, 1);
+
+    // Elapse the subscription timeout (the publisher now has newer commits)
+    let ordered_block_2 =
+        harness.create_ordered_block(ordered_block_1.proof_block_info(), GENESIS_EPOCH, 2);
+    harness.set_peer_latest_commit(publisher_1, ordered_block_2.proof_block_info().clone());
+    let num_stale_subscriptions = get_terminated_subscriptions("subscription_stale", &publisher_1);
+    harness.advance_time(subscription_timeout);
+    harness.check_progress().await;
+
+    // Verify that the stale subscription was terminated (and the observer failed over)
+    assert_eq!(get_probe_requests(&harness), vec![publisher_1, publisher_1]);
+    assert_eq!(get_subscribe_requests(&harness), vec![
+        publisher_1,
+        publisher_2
+    ]);
+    assert!(
+        get_terminated_subscriptions("subscription_stale", &publisher_1) > num_stale_subscriptions
+    );
+
+    // Elapse the subscription timeout (the second publisher is unresponsive)
+    harness.set_peer_responsive(publisher_2, false);
+    let num_timed_out_subscriptions =
+        get_terminated_subscriptions("subscription_timeout", &publisher_2);
+    harness.advance_time(subscription_timeout);
+    harness.check_progress().await;
+
+    // Verify that the probe failed, and that the subscription timed out
+    assert_eq!(get_probe_requests(&harness), vec![
+        publisher_1,
+        publisher_1,
+        publisher_2
+    ]);
+    assert_eq!(get_subscribe_requests(&harness), vec![
+        publisher_1,
+        publisher_2,
+        publisher_1
+    ]);
+    assert!(
+        get_terminated_subscriptions("subscription_timeout", &publisher_2)
+            > num_timed_out_subscriptions
+    );
+}
+
+#[tokio::test]
+async fn test_pipeline_reset_on_subscription_failover() {
+    // Create a test harness and add two publishers (the first is preferred)
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher_1 = harness.add_publisher_peer(0);
+    let publisher_2 = harness.add_publisher_peer(1);
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher_1]);
+
+    // Send the first block from the first publisher (but not the commit decision)
+    let ordered_block_1 = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    let block_payload_message = harness.create_block_payload_message(&ordered_block_1);
+    harness
+        .send_direct_send_message(publisher_1, block_payload_message)
+        .await;
+    harness
+        .send_direct_send_message(
+            publisher_1,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block_1.clone()),
+        )
+        .await;
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 1);
+
+    // Disconnect the first publisher (with the block still in-flight) and check progress
+    harness.disconnect_peer(publisher_1);
+    harness.check_progress().await;
+
+    // Verify that the subscription failed over, and that the pipeline was reset to the root
+    assert_eq!(get_subscribe_requests(&harness), vec![
+        publisher_1,
+        publisher_2
+    ]);
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 0);
+    assert!(
+        get_journal_events(&harness).contains(&ObserverEvent::PipelineReset {
+            epoch: GENESIS_EPOCH,
+            round: harness.genesis_block().round(),
+            num_dropped_blocks: 1,
+        })
+    );
+
+    // Verify that the first block can be resent and committed by the second publisher
+    harness
+        .send_direct_send_message(
+            publisher_2,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block_1.clone()),
+        )
+        .await;
+    let commit_decision_1 = harness.create_commit_decision(&ordered_block_1);
+    harness
+        .send_direct_send_message(
+            publisher_2,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision_1.clone()),
+        )
+        .await;
+    assert_eq!(
+        harness.get_latest_ledger_info(),
+        commit_decision_1.commit_proof().clone()
+    );
+
+    // Verify the calls made to the execution client
+    let block_info_1 = ordered_block_1.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::FinalizeOrder(block_info_1.clone()),
+        ExecutionClientCall::Reset(harness.genesis_block()),
+        ExecutionClientCall::FinalizeOrder(block_info_1.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info_1),
+    ]);
+}
+
+#[tokio::test]
+async fn test_pipeline_teardown_and_failed_sync() {
+    // Create a test harness and subscribe to a publisher
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Script the first sync to fail (storage is not updated)
+    harness
+        .execution_client()
+        .push_sync_result(Err(anyhow!("Injected sync failure!").into()));
+
+    // Send a commit decision for a block the observer has never seen
+    let ordered_block = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 10);
+    let commit_decision = harness.create_commit_decision(&ordered_block);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+
+    // Wait for the failed sync and verify that the synced root check fails
+    let num_teardown_failures = get_pipeline_teardown_failures(metrics::TEARDOWN_SYNCED_ROOT_LABEL);
+    let (epoch, round) = harness.wait_for_sync_notification().await;
+    assert_eq!((epoch, round), (GENESIS_EPOCH, 10));
+    assert_eq!(
+        harness.get_latest_ledger_info().commit_info(),
+        &harness.genesis_block()
+    );
+    assert!(
+        get_pipeline_teardown_failures(metrics::TEARDOWN_SYNCED_ROOT_LABEL) > num_teardown_failures
+    );
+    assert!(
+        !get_journal_events(&harness).contains(&ObserverEvent::SyncCompleted {
+            epoch: GENESIS_EPOCH,
+            round: 10,
+        })
+    );
+
+    // Wait for the retried sync to complete and verify the new root
+    let (epoch, round) = harness.wait_for_sync_notification().await;
+    assert_eq!((epoch, round), (GENESIS_EPOCH, 10));
+    assert_eq!(
+        harness.get_latest_ledger_info(),
+        commit_decision.commit_proof().clone()
+    );
+    assert!(
+        get_journal_events(&harness).contains(&ObserverEvent::SyncCompleted {
+            epoch: GENESIS_EPOCH,
+            round: 10,
+        })
+    );
+
+    // Verify the calls made to the execution client (the pipeline is torn down before each sync)
+    let block_info = ordered_block.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::Reset(block_info.clone()),
+        ExecutionClientCall::SyncTo(block_info.clone()),
+        ExecutionClientCall::Reset(block_info.clone()),
+        ExecutionClientCall::SyncTo(block_info),
+    ]);
+}
+
+#[tokio::test]
+async fn test_stale_sync_notifications() {
+    // Create a test harness and subscribe to a publisher
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Send a commit decision for a block the observer has never seen
+    let ordered_block_1 = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 10);
+    let commit_decision = harness.create_commit_decision(&ordered_block_1);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision),
+        )
+        .await;
+
+    // Wait for the sync to complete
+    let (epoch, round) = harness.wait_for_sync_notification().await;
+    assert_eq!((epoch, round), (GENESIS_EPOCH, 10));
+
+    // Send and commit the next block (after the sync)
+    let ordered_block_2 =
+        harness.create_ordered_block(ordered_block_1.proof_block_info(), GENESIS_EPOCH, 11);
+    send_and_commit_block(&mut harness, publisher, &ordered_block_2).await;
+
+    // Process a duplicate of the sync notification and verify that it is dropped
+    let num_stale_notifications =
+        get_dropped_sync_notifications(metrics::SYNC_NOTIFICATION_STALE_LABEL);
+    harness.process_sync_notification(GENESIS_EPOCH, 10).await;
+    assert!(
+        get_dropped_sync_notifications(metrics::SYNC_NOTIFICATION_STALE_LABEL)
+            > num_stale_notifications
+    );
+
+    // Verify that the duplicate did not affect the root or the execution pipeline
+    let block_info_1 = ordered_block_1.proof_block_info().clone();
+    let block_info_2 = ordered_block_2.proof_block_info().clone();
+    assert_eq!(
+        harness.get_latest_ledger_info().commit_info(),
+        &block_info_2
+    );
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::Reset(block_info_1.clone()),
+        ExecutionClientCall::SyncTo(block_info_1),
+        ExecutionClientCall::FinalizeOrder(block_info_2.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info_2),
+    ]);
+}
+
+#[tokio::test]
+async fn test_invalid_block_payloads() {
+    // Create a test harness and subscribe to a publisher
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Send an ordered block (without the payload)
+    let ordered_block = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+        )
+        .await;
+
+    // Send a payload with a mismatched block timestamp (for the same block ID)
+    let num_rejected_payloads = get_rejected_block_payloads(metrics::PAYLOAD_BLOCK_MISMATCH_LABEL);
+    let block_info = ordered_block.proof_block_info().clone();
+    let mismatched_block_info = BlockInfo::new(
+        block_info.epoch(),
+        block_info.round(),
+        block_info.id(),
+        block_info.executed_state_id(),
+        block_info.version(),
+        block_info.timestamp_usecs() + 1,
+        None,
+    );
+    let block_payload_message =
+        ConsensusObserverMessage::new_block_payload_message(mismatched_block_info, vec![], None);
+    harness
+        .send_direct_send_message(publisher, block_payload_message)
+        .await;
+
+    // Verify that the payload was rejected (and the failure was recorded)
+    assert!(
+        get_rejected_block_payloads(metrics::PAYLOAD_BLOCK_MISMATCH_LABEL) > num_rejected_payloads
+    );
+    let num_payload_failures = get_journal_events(&harness)
+        .iter()
+        .filter(|event| {
+            matches!(event, ObserverEvent::VerificationFailed { message_type, .. }
+                if message_type == "block_payload")
+        })
+        .count();
+    assert_eq!(num_payload_failures, 1);
+
+    // Send the commit decision and verify it is not forwarded (the payload was not stored)
+    let commit_decision = harness.create_commit_decision(&ordered_block);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 1);
+
+    // Send the valid payload (and the commit decision) and verify the block is committed
+    let block_payload_message = harness.create_block_payload_message(&ordered_block);
+    harness
+        .send_direct_send_message(publisher, block_payload_message)
+        .await;
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 0);
+    assert_eq!(
+        harness.get_latest_ledger_info(),
+        commit_decision.commit_proof().clone()
+    );
+}
+
+#[tokio::test]
+async fn test_verified_commit_decision_for_future_epoch() {
+    // Create a test harness and subscribe to a publisher
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Store the epoch-ending ledger info of the current epoch (i.e., the epoch proof)
+    let next_epoch = GENESIS_EPOCH + 1;
+    harness.store_epoch_ending_ledger_info(GENESIS_EPOCH);
+    harness.notify_reconfiguration(next_epoch);
+
+    // Send an unsigned commit decision for a block in the next epoch
+    let num_rejected_decisions = get_future_commit_decisions(metrics::FUTURE_COMMIT_REJECTED_LABEL);
+    let future_block = harness.create_ordered_block(&harness.genesis_block(), next_epoch, 5);
+    let commit_decision = harness.create_commit_decision(&future_block);
+    let invalid_commit_decision = CommitDecision::new(LedgerInfoWithSignatures::new(
+        commit_decision.commit_proof().ledger_info().clone(),
+        AggregateSignature::empty(),
+    ));
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(invalid_commit_decision),
+        )
+        .await;
+
+    // Verify that the decision was rejected (and didn't trigger a state sync)
+    assert!(
+        get_future_commit_decisions(metrics::FUTURE_COMMIT_REJECTED_LABEL) > num_rejected_decisions
+    );
+    assert!(get_journal_events(&harness).iter().any(|event| {
+        matches!(event, ObserverEvent::VerificationFailed { message_type, .. }
+            if message_type == "commit_decision")
+    }));
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH)
+    ]);
+
+    // Send the valid commit decision and verify the observer syncs to it immediately
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+    let (epoch, round) = harness.wait_for_sync_notification().await;
+    assert_eq!((epoch, round), (next_epoch, 5));
+    assert_eq!(
+        harness.get_latest_ledger_info(),
+        commit_decision.commit_proof().clone()
+    );
+
+    // Verify the calls made to the execution client
+    let future_block_info = future_block.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::Reset(future_block_info.clone()),
+        ExecutionClientCall::SyncTo(future_block_info),
+        ExecutionClientCall::EndEpoch,
+        ExecutionClientCall::StartEpoch(next_epoch),
+    ]);
+}
+
+#[tokio::test]
+async fn test_pending_blocks_verified_after_epoch_change_sync() {
+    // Create a test harness and subscribe to a publisher
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Store the epoch-ending ledger info of the current epoch (i.e., the epoch proof)
+    let next_epoch = GENESIS_EPOCH + 1;
+    harness.store_epoch_ending_ledger_info(GENESIS_EPOCH);
+    harness.notify_reconfiguration(next_epoch);
+
+    // Send a commit decision for a block in the next epoch (this starts a sync to the next epoch)
+    let future_block = harness.create_ordered_block(&harness.genesis_block(), next_epoch, 5);
+    let commit_decision = harness.create_commit_decision(&future_block);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision),
+        )
+        .await;
+
+    // Send a block for the next epoch (and its payload) while the sync is in progress
+    let future_block_info = future_block.proof_block_info().clone();
+    let ordered_block = harness.create_ordered_block(&future_block_info, next_epoch, 6);
+    let block_payload_message = harness.create_block_payload_message(&ordered_block);
+    harness
+        .send_direct_send_message(publisher, block_payload_message)
+        .await;
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+        )
+        .await;
+
+    // Wait for the sync to complete (crossing into the next epoch)
+    let (epoch, round) = harness.wait_for_sync_notification().await;
+    assert_eq!((epoch, round), (next_epoch, 5));
+
+    // Verify that the buffered block was verified (using the new epoch state) and finalized
+    let block_info = ordered_block.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::Reset(future_block_info.clone()),
+        ExecutionClientCall::SyncTo(future_block_info),
+        ExecutionClientCall::EndEpoch,
+        ExecutionClientCall::StartEpoch(next_epoch),
+        ExecutionClientCall::FinalizeOrder(block_info.clone()),
+    ]);
+
+    // Send the commit decision for the block and verify it is committed
+    let commit_decision = harness.create_commit_decision(&ordered_block);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 0);
+    assert_eq!(
+        harness.get_latest_ledger_info(),
+        commit_decision.commit_proof().clone()
+    );
+}
+
+#[tokio::test]
+async fn test_backfill_missing_ordered_blocks() {
+    // Create a test harness (with ordered block backfill enabled) and subscribe to a publisher
+    let consensus_observer_config = ConsensusObserverConfig {
+        enable_ordered_block_backfill: true,
+        ..ConsensusObserverConfig::default()
+    };
+    let mut harness = ObserverTestHarness::new(consensus_observer_config);
+    harness.start_epoch(GENESIS_EPOCH).await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Create three consecutive blocks (the publisher will only serve the second block)
+    let ordered_block_1 = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    let ordered_block_2 =
+        harness.create_ordered_block(ordered_block_1.proof_block_info(), GENESIS_EPOCH, 2);
+    let ordered_block_3 =
+        harness.create_ordered_block(ordered_block_2.proof_block_info(), GENESIS_EPOCH, 3);
+    harness.set_peer_ordered_blocks(publisher, vec![ordered_block_2.clone()]);
+
+    // Send the first and third blocks (the second block is missing)
+    let num_successful_backfills = get_ordered_block_backfills(metrics::BACKFILL_SUCCEEDED_LABEL);
+    for ordered_block in [&ordered_block_1, &ordered_block_3] {
+        harness
+            .send_direct_send_message(
+                publisher,
+                ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+            )
+            .await;
+    }
+
+    // Verify that the missing block was requested from the publisher
+    assert!(harness.get_received_requests().contains(&(
+        publisher,
+        ConsensusObserverRequest::GetOrderedBlocks {
+            start_round: 2,
+            end_round: 2,
+        }
+    )));
+    assert!(
+        get_ordered_block_backfills(metrics::BACKFILL_SUCCEEDED_LABEL) > num_successful_backfills
+    );
+
+    // Verify that all blocks were finalized (in order)
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 3);
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::FinalizeOrder(ordered_block_1.proof_block_info().clone()),
+        ExecutionClientCall::FinalizeOrder(ordered_block_2.proof_block_info().clone()),
+        ExecutionClientCall::FinalizeOrder(ordered_block_3.proof_block_info().clone()),
+    ]);
+}
+
+#[tokio::test]
+async fn test_request_missing_block_payloads() {
+    // Create a test harness (with payload requests enabled) and subscribe to a publisher
+    let consensus_observer_config = ConsensusObserverConfig {
+        enable_block_payload_requests: true,
+        ..ConsensusObserverConfig::default()
+    };
+    let mut harness = ObserverTestHarness::new(consensus_observer_config);
+    harness.start_epoch(GENESIS_EPOCH).await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Send an ordered block and commit decision (without the payload)
+    let ordered_block = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    let commit_decision = harness.create_commit_decision(&ordered_block);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+        )
+        .await;
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+
+    // Verify the block is not committed (the payload is missing)
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 1);
+
+    // Check progress and verify the missing payload is requested from the publisher
+    let num_received_payloads = get_block_payload_requests(metrics::PAYLOAD_REQUEST_RECEIVED_LABEL);
+    harness.set_peer_ordered_blocks(publisher, vec![ordered_block.clone()]);
+    harness.check_progress().await;
+    let block_id = ordered_block.first_block().id();
+    assert!(harness.get_received_requests().contains(&(
+        publisher,
+        ConsensusObserverRequest::GetBlockPayload(block_id)
+    )));
+    assert!(
+        get_block_payload_requests(metrics::PAYLOAD_REQUEST_RECEIVED_LABEL) > num_received_payloads
+    );
+
+    // Send the commit decision (again) and verify the block is now committed
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 0);
+    assert_eq!(
+        harness.get_latest_ledger_info(),
+        commit_decision.commit_proof().clone()
+    );
+
+    // Verify the calls made to the execution client
+    let block_info = ordered_block.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+        ExecutionClientCall::FinalizeOrder(block_info.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info),
+    ]);
+}
+
+#[tokio::test]
+async fn test_pending_blocks_overflow_sync() {
+    // Create a test harness (with a small pending block limit) and subscribe to a publisher
+    let consensus_observer_config = ConsensusObserverConfig {
+        max_num_pending_blocks: 3,
+        ..ConsensusObserverConfig::default()
+    };
+    let mut harness = ObserverTestHarness::new(consensus_observer_config);
+    harness.start_epoch(GENESIS_EPOCH).await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+
+    // Send several ordered blocks (without the payloads, so they can't be committed)
+    let mut ordered_blocks = vec![];
+    let mut parent_block = harness.genesis_block();
+    for round in 1..=3 {
+        let ordered_block = harness.create_ordered_block(&parent_block, GENESIS_EPOCH, round);
+        harness
+            .send_direct_send_message(
+                publisher,
+                ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+            )
+            .await;
+        parent_block = ordered_block.proof_block_info().clone();
+        ordered_blocks.push(ordered_block);
+    }
+
+    // Send the commit decision for the second block (it can't be committed yet)
+    let commit_decision = harness.create_commit_decision(&ordered_blocks[1]);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 1);
+
+    // Send another ordered block and verify the observer falls back to state sync
+    let ordered_block = harness.create_ordered_block(&parent_block, GENESIS_EPOCH, 4);
+    harness
+        .send_direct_send_message(
+            publisher,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+        )
+        .await;
+    assert!(
+        get_journal_events(&harness).contains(&ObserverEvent::SyncStarted {
+            epoch: GENESIS_EPOCH,
+            round: 2
+        })
+    );
+
+    // Wait for the sync to complete and verify the new root
+    let (epoch, round) = harness.wait_for_sync_notification().await;
+    assert_eq!((epoch, round), (GENESIS_EPOCH, 2));
+    assert_eq!(
+        harness.get_latest_ledger_info(),
+        commit_decision.commit_proof().clone()
+    );
+
+    // Verify the execution pipeline was synced to the latest commit decision
+    let block_info = ordered_blocks[1].proof_block_info().clone();
+    assert!(harness
+        .execution_client()
+        .get_calls()
+        .contains(&ExecutionClientCall::SyncTo(block_info)));
+}
+
+#[tokio::test]
+async fn test_inbound_rate_limiting() {
+    // Create a test harness (with a small rate limit budget) and add two publishers
+    let consensus_observer_config = ConsensusObserverConfig {
+        enable_inbound_rate_limiting: true,
+        max_inbound_messages_per_sec: 1,
+        max_inbound_message_burst: 2,
+        max_rate_limited_messages: 2,
+        ..ConsensusObserverConfig::default()
+    };
+    let mut harness = ObserverTestHarness::new(consensus_observer_config);
+    harness.start_epoch(GENESIS_EPOCH).await;
+    let publisher_1 = harness.add_publisher_peer(0);
+    let publisher_2 = harness.add_publisher_peer(1);
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher_1]);
+
+    // Send the payload and ordered block for the first round (within the burst)
+    let ordered_block = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    let block_payload_message = harness.create_block_payload_message(&ordered_block);
+    harness
+        .send_direct_send_message(publisher_1, block_payload_message)
+        .await;
+    harness
+        .send_direct_send_message(
+            publisher_1,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+        )
+        .await;
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 1);
+
+    // Send the commit decision (exceeding the budget) and verify it is dropped
+    let commit_decision = harness.create_commit_decision(&ordered_block);
+    harness
+        .send_direct_send_message(
+            publisher_1,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+    assert_ne!(
+        harness.get_latest_ledger_info(),
+        commit_decision.commit_proof().clone()
+    );
+
+    // Resend the commit decision and verify the subscription is terminated
+    harness
+        .send_direct_send_message(
+            publisher_1,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+    assert!(get_journal_events(&harness).iter().any(|event| matches!(
+        event,
+        ObserverEvent::SubscriptionTerminated { peer_network_id, reason }
+            if *peer_network_id == publisher_1 && reason.contains("rate limited")
+    )));
+
+    // Check progress and verify that the observer subscribes to the second publisher
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![
+        publisher_1,
+        publisher_2
+    ]);
+}
+
+#[tokio::test]
+async fn test_ordered_block_equivocation() {
+    // Create a test harness and add two publishers (the first is preferred)
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher_1 = harness.add_publisher_peer(0);
+    let publisher_2 = harness.add_publisher_peer(1);
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher_1]);
+
+    // Send an ordered block for the first round
+    let ordered_block = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    harness
+        .send_direct_send_message(
+            publisher_1,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+        )
+        .await;
+
+    // Resend the same ordered block and verify it isn't treated as an equivocation
+    harness
+        .send_direct_send_message(
+            publisher_1,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block),
+        )
+        .await;
+    assert!(!get_journal_events(&harness)
+        .iter()
+        .any(|event| matches!(event, ObserverEvent::EquivocationDetected { .. })));
+
+    // Send a conflicting ordered block (for the same round) and verify the equivocation is detected
+    let conflicting_block =
+        harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    harness
+        .send_direct_send_message(
+            publisher_1,
+            ConsensusObserverDirectSend::OrderedBlock(conflicting_block),
+        )
+        .await;
+    let journal_events = get_journal_events(&harness);
+    assert!(
+        journal_events.contains(&ObserverEvent::EquivocationDetected {
+            peer_network_id: publisher_1,
+            epoch: GENESIS_EPOCH,
+            round: 1,
+        })
+    );
+    assert!(journal_events.iter().any(|event| matches!(
+        event,
+        ObserverEvent::SubscriptionTerminated { peer_network_id, reason }
+            if *peer_network_id == publisher_1 && reason.contains("equivocated")
+    )));
+
+    // Check progress and verify that the observer subscribes to the second publisher
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![
+        publisher_1,
+        publisher_2
+    ]);
+
+    // Disconnect the second publisher and verify the first publisher remains blocklisted
+    harness.disconnect_peer(publisher_2);
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![
+        publisher_1,
+        publisher_2
+    ]);
+}
+
+#[tokio::test]
+async fn test_forced_resubscription() {
+    // Create a test harness and add two publishers (the first is preferred)
+    let mut harness = create_harness_and_start_epoch().await;
+    let publisher_1 = harness.add_publisher_peer(0);
+    let publisher_2 = harness.add_publisher_peer(1);
+
+    // Verify that the observer subscribes to the first publisher
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher_1]);
+
+    // Force a resubscription to the second publisher and verify the new subscription
+    let subscription_peer = harness
+        .force_resubscription(Some(publisher_2))
+        .await
+        .unwrap();
+    assert_eq!(subscription_peer, publisher_2);
+    assert_eq!(get_subscribe_requests(&harness), vec![
+        publisher_1,
+        publisher_2
+    ]);
+
+    // Force a resubscription without a peer, and verify that the
+    // peer selection is rerun (excluding the previous peer).
+    let subscription_peer = harness.force_resubscription(None).await.unwrap();
+    assert_eq!(subscription_peer, publisher_1);
+    assert_eq!(get_subscribe_requests(&harness), vec![
+        publisher_1,
+        publisher_2,
+        publisher_1
+    ]);
+
+    // Verify that a forced resubscription to a disconnected peer is rejected
+    harness.disconnect_peer(publisher_2);
+    let result = harness.force_resubscription(Some(publisher_2)).await;
+    assert!(matches!(result, Err(Error::SubscriptionDisconnected(_))));
+
+    // Verify that the active subscription was retained
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![
+        publisher_1,
+        publisher_2,
+        publisher_1
+    ]);
+}
+
+#[tokio::test]
+async fn test_pause_and_resume_finalization() {
+    // Create a test harness (with a short synced version timeout) and subscribe to a publisher
+    let consensus_observer_config = ConsensusObserverConfig {
+        max_synced_version_timeout_ms: 10_000,
+        ..ConsensusObserverConfig::default()
+    };
+    let mut harness = ObserverTestHarness::new(consensus_observer_config);
+    harness.start_epoch(GENESIS_EPOCH).await;
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher]);
+
+    // Send and commit the first block
+    let ordered_block_1 = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    send_and_commit_block(&mut harness, publisher, &ordered_block_1).await;
+
+    // Pause finalization and send the second block (and its commit decision)
+    harness.pause_finalization();
+    harness.execution_client().clear_calls();
+    let ordered_block_2 =
+        harness.create_ordered_block(ordered_block_1.proof_block_info(), GENESIS_EPOCH, 2);
+    let commit_decision_2 = harness.create_commit_decision(&ordered_block_2);
+    for message in [
+        harness.create_block_payload_message(&ordered_block_2),
+        ConsensusObserverDirectSend::OrderedBlock(ordered_block_2.clone()),
+        ConsensusObserverDirectSend::CommitDecision(commit_decision_2.clone()),
+    ] {
+        harness.send_direct_send_message(publisher, message).await;
+    }
+
+    // Verify that nothing was sent to the execution pipeline
+    assert!(harness.execution_client().get_calls().is_empty());
+
+    // Verify that the subscription is maintained (even though the DB isn't syncing)
+    for _ in 0..3 {
+        harness.check_progress().await;
+    }
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher]);
+
+    // Resume finalization and verify that the buffered block is committed
+    harness.resume_finalization().await;
+    let block_info_2 = ordered_block_2.proof_block_info().clone();
+    assert_eq!(harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::FinalizeOrder(block_info_2.clone()),
+        ExecutionClientCall::SendCommitDecision(block_info_2),
+    ]);
+    assert_eq!(
+        harness.get_latest_ledger_info(),
+        commit_decision_2.commit_proof().clone()
+    );
+
+    // Verify that the subscription is still maintained after resuming
+    harness.check_progress().await;
+    assert_eq!(get_subscribe_requests(&harness), vec![publisher]);
+}
+
+#[tokio::test]
+async fn test_event_listener_notifications() {
+    // Create a test harness and register a recording event listener
+    let mut harness = create_harness_and_start_epoch().await;
+    let notifications = Arc::new(Mutex::new(vec![]));
+    harness.register_listener(Box::new(RecordingEventListener {
+        notifications: notifications.clone(),
+    }));
+
+    // Subscribe to a publisher, and send and commit the first block
+    let publisher = harness.add_publisher_peer(0);
+    harness.check_progress().await;
+    let ordered_block_1 = harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+    send_and_commit_block(&mut harness, publisher, &ordered_block_1).await;
+
+    // Force a sync to a commit beyond the root, and wait for the sync to complete
+    let ordered_block_2 =
+        harness.create_ordered_block(ordered_block_1.proof_block_info(), GENESIS_EPOCH, 2);
+    let commit_decision_2 = harness.create_commit_decision(&ordered_block_2);
+    harness
+        .force_state_sync(Some(commit_decision_2.commit_proof().clone()))
+        .unwrap();
+    harness.wait_for_sync_notification().await;
+
+    // Verify that the listener was notified of all the progress (in order)
+    let block_info_1 = ordered_block_1.proof_block_info().clone();
+    let block_info_2 = ordered_block_2.proof_block_info().clone();
+    assert_eq!(notifications.lock().clone(), vec![
+        ListenerNotification::OrderedBlock(block_info_1.clone()),
+        ListenerNotification::CommitDecision(block_info_1),
+        ListenerNotification::StateSyncStarted(block_info_2),
+        ListenerNotification::StateSyncCompleted(GENESIS_EPOCH, 2),
+    ]);
+}
+
+/// A notification received by the recording event listener
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ListenerNotification {
+    OrderedBlock(BlockInfo),
+    CommitDecision(BlockInfo),
+    StateSyncStarted(BlockInfo),
+    StateSyncCompleted(u64, Round),
+}
+
+/// An event listener that records all notifications it receives
+struct RecordingEventListener {
+    notifications: Arc<Mutex<Vec<ListenerNotification>>>,
+}
+
+impl ObserverEventListener for RecordingEventListener {
+    fn name(&self) -> &'static str {
+        "recording_event_listener"
+    }
+
+    fn on_ordered_block(&self, ordered_block: &OrderedBlock) {
+        self.notifications
+            .lock()
+            .push(ListenerNotification::OrderedBlock(
+                ordered_block.proof_block_info().clone(),
+            ));
+    }
+
+    fn on_commit_decision(&self, commit_decision: &CommitDecision) {
+        self.notifications
+            .lock()
+            .push(ListenerNotification::CommitDecision(
+                commit_decision.proof_block_info().clone(),
+            ));
+    }
+
+    fn on_state_sync_started(&self, sync_target: &BlockInfo) {
+        self.notifications
+            .lock()
+            .push(ListenerNotification::StateSyncStarted(sync_target.clone()));
+    }
+
+    fn on_state_sync_completed(&self, epoch: u64, round: Round) {
+        self.notifications
+            .lock()
+            .push(ListenerNotification::StateSyncCompleted(epoch, round));
+    }
+}
+
+/// Creates a new test harness (with the default config) and starts the genesis epoch
+async fn create_harness_and_start_epoch() -> ObserverTestHarness {
+    let mut harness = ObserverTestHarness::new(ConsensusObserverConfig::default());
+    harness.start_epoch(GENESIS_EPOCH).await;
+    harness
+}
+
+/// Creates a genesis block with the given timestamp
+fn create_genesis_block_with_timestamp(timestamp_usecs: u64) -> BlockInfo {
+    let genesis_block = create_genesis_block();
+    BlockInfo::new(
+        genesis_block.epoch(),
+        genesis_block.round(),
+        genesis_block.id(),
+        genesis_block.executed_state_id(),
+        genesis_block.version(),
+        timestamp_usecs,
+        None,
+    )
+}
+
+/// Returns the number of block payload requests for the given outcome.
+/// Note: the counter is shared by all tests, so callers should only assert on increases.
+fn get_block_payload_requests(outcome: &str) -> u64 {
+    metrics::OBSERVER_BLOCK_PAYLOAD_REQUESTS
+        .with_label_values(&[outcome])
+        .get()
+}
+
+/// Returns the number of block timestamp violations for the given action label.
+/// Note: the counter is shared by all tests, so callers should only assert on increases.
+fn get_block_timestamp_violations(action_label: &str) -> u64 {
+    metrics::OBSERVER_BLOCK_TIMESTAMP_VIOLATIONS
+        .with_label_values(&[action_label])
+        .get()
+}
+
+/// Returns the number of dropped sync notifications for the given reason.
+/// Note: the counter is shared by all tests, so callers should only assert on increases.
+fn get_dropped_sync_notifications(drop_reason: &str) -> u64 {
+    metrics::OBSERVER_DROPPED_SYNC_NOTIFICATIONS
+        .with_label_values(&[drop_reason])
+        .get()
+}
+
+/// Returns the number of future commit decisions for the given outcome.
+/// Note: the counter is shared by all tests, so callers should only assert on increases.
+fn get_future_commit_decisions(outcome: &str) -> u64 {
+    metrics::OBSERVER_FUTURE_COMMIT_DECISIONS
+        .with_label_values(&[outcome])
+        .get()
+}
+
+/// Returns all events recorded in the event journal of the harness
+fn get_journal_events(harness: &ObserverTestHarness) -> Vec<ObserverEvent> {
+    harness
+        .event_journal()
+        .get_journal_entries()
+        .into_iter()
+        .map(|journal_entry| journal_entry.event)
+        .collect()
+}
+
+/// Returns the number of ordered block backfills for the given outcome.
+/// Note: the counter is shared by all tests, so callers should only assert on increases.
+fn get_ordered_block_backfills(outcome: &str) -> u64 {
+    metrics::OBSERVER_ORDERED_BLOCK_BACKFILLS
+        .with_label_values(&[outcome])
+        .get()
+}
+
+/// Returns the number of pipeline teardown failures for the given step.
+/// Note: the counter is shared by all tests, so callers should only assert on increases.
+fn get_pipeline_teardown_failures(teardown_step: &str) -> u64 {
+    metrics::OBSERVER_PIPELINE_TEARDOWN_FAILURES
+        .with_label_values(&[teardown_step])
+        .get()
+}
+
+/// Returns the number of messages (of the given type) received from the given peer.
+/// Note: peers may share the "other" label, so callers should only assert on increases.
+fn get_received_messages_for_peer(peer_network_id: &PeerNetworkId, message_label: &str) -> u64 {
+    let peer_label = metrics::get_peer_label(peer_network_id);
+    let network_id = peer_network_id.network_id();
+    metrics::OBSERVER_RECEIVED_MESSAGES_PER_PEER
+        .with_label_values(&[message_label, peer_label.as_str(), network_id.as_str()])
+        .get()
+}
+
+/// Returns the peers that received probe requests (in the order they were sent)
+fn get_probe_requests(harness: &ObserverTestHarness) -> Vec<PeerNetworkId> {
+    harness
+        .get_received_requests()
+        .into_iter()
+        .filter(|(_, request)| *request == ConsensusObserverRequest::GetLatestCommit)
+        .map(|(peer_network_id, _)| peer_network_id)
+        .collect()
+}
+
+/// Returns the number of block payloads rejected for the given reason.
+/// Note: the counter is shared by all tests, so callers should only assert on increases.
+fn get_rejected_block_payloads(rejection_reason: &str) -> u64 {
+    metrics::OBSERVER_REJECTED_BLOCK_PAYLOADS
+        .with_label_values(&[rejection_reason])
+        .get()
+}
+
+/// Returns the peers that received subscription requests (in the order they were sent)
+fn get_subscribe_requests(harness: &ObserverTestHarness) -> Vec<PeerNetworkId> {
+    harness
+        .get_received_requests()
+        .into_iter()
+        .filter(|(_, request)| *request == ConsensusObserverRequest::Subscribe)
+        .map(|(peer_network_id, _)| peer_network_id)
+        .collect()
+}
+
+/// Returns the number of terminated subscriptions for the given label (and the
+/// network of the given peer). Note: the counter is shared by all tests, so
+/// callers should only assert on increases.
+fn get_terminated_subscriptions(termination_label: &str, peer_network_id: &PeerNetworkId) -> u64 {
+    let network_id = peer_network_id.network_id();
+    metrics::OBSERVER_TERMINATED_SUBSCRIPTIONS
+        .with_label_values(&[termination_label, network_id.as_str()])
+        .get()
+}
+
+/// Sends the payload, ordered block and commit decision for the given block
+/// (from the given peer), and verifies that the block is committed.
+async fn send_and_commit_block(
+    harness: &mut ObserverTestHarness,
+    peer_network_id: PeerNetworkId,
+    ordered_block: &OrderedBlock,
+) {
+    // Send the block payload and the ordered block
+    let block_payload_message = harness.create_block_payload_message(ordered_block);
+    harness
+        .send_direct_send_message(peer_network_id, block_payload_message)
+        .await;
+    harness
+        .send_direct_send_message(
+            peer_network_id,
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+        )
+        .await;
+
+    // Send the commit decision
+    let commit_decision = harness.create_commit_decision(ordered_block);
+    harness
+        .send_direct_send_message(
+            peer_network_id,
+            ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+        )
+        .await;
+
+    // Verify the block was committed
+    assert_eq!(harness.execution_client().get_num_pending_commits(), 0);
+    assert_eq!(
+        harness.get_latest_ledger_info(),
+        commit_decision.commit_proof().clone()
+    );
+}
+
+/// Verifies the active subscription peer gauge for the given peer. Note: if
+/// the peer shares the "other" label (i.e., the label cap has been reached
+/// by other tests), the gauge cannot be attributed to the peer, so it is skipped.
+fn verify_active_subscription_peer_gauge(peer_network_id: &PeerNetworkId, expected_value: i64) {
+    let peer_label = metrics::get_peer_label(peer_network_id);
+    if peer_label == metrics::OTHER_PEER_LABEL {
+        return;
+    }
+
+    let network_id = peer_network_id.network_id();
+    let gauge_value = metrics::OBSERVER_ACTIVE_SUBSCRIPTION_PEER
+        .with_label_values(&[peer_label.as_str(), network_id.as_str()])
+        .get();
+    assert_eq!(gauge_value, expected_value);
+}
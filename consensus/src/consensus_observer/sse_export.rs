@@ -0,0 +1,364 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    data_exporter::{ObservedData, ObserverDataExporter},
+    logging::{LogEntry, LogSchema},
+    websocket_export::{upgrade_to_websocket, WEBSOCKET_COMMITS_PATH},
+};
+use aptos_logger::{error, info, warn};
+use aptos_types::block_info::BlockInfo;
+use futures::{stream, Stream};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use std::{convert::Infallible, net::SocketAddr};
+use tokio::{
+    runtime::Handle,
+    sync::broadcast::{self, error::RecvError},
+};
+
+// The endpoint that streams observed commits (and optionally ordered blocks)
+pub const COMMITS_PATH: &str = "/commits";
+
+// The query parameter used to also stream ordered blocks
+const INCLUDE_ORDERED_BLOCKS_PARAM: &str = "include_ordered_blocks";
+
+// The names of the server-sent events
+const COMMIT_DECISION_EVENT: &str = "commit_decision";
+const ORDERED_BLOCK_EVENT: &str = "ordered_block";
+
+// Useful string constants
+const CONTENT_TYPE_EVENT_STREAM: &str = "text/event-stream";
+const INVALID_ENDPOINT_MESSAGE: &str = "The requested endpoint is invalid!";
+
+/// A JSON summary of a block (sent to external subscribers)
+#[derive(Debug, Serialize)]
+pub(crate) struct BlockSummary {
+    epoch: u64,
+    round: u64,
+    id: String,
+    version: u64,
+    timestamp_usecs: u64,
+}
+
+impl BlockSummary {
+    pub(crate) fn new(block_info: &BlockInfo) -> Self {
+        Self {
+            epoch: block_info.epoch(),
+            round: block_info.round(),
+            id: block_info.id().to_hex(),
+            version: block_info.version(),
+            timestamp_usecs: block_info.timestamp_usecs(),
+        }
+    }
+}
+
+/// The JSON data of a commit decision event
+#[derive(Debug, Serialize)]
+struct CommitDecisionEventData {
+    proof_block: BlockSummary,
+}
+
+/// The JSON data of an ordered block event (i.e., the block headers only)
+#[derive(Debug, Serialize)]
+struct OrderedBlockEventData {
+    blocks: Vec<BlockSummary>,
+    proof_block: BlockSummary,
+}
+
+/// Starts the server-sent events (SSE) export server (on the given runtime)
+/// at the specified address. This offers a lightweight streaming endpoint
+/// that pushes commit notifications to external consumers (e.g., dashboards).
+/// The server also offers the same notifications as a WebSocket feed.
+pub fn start_sse_export_server(
+    sse_export_address: SocketAddr,
+    data_exporter: ObserverDataExporter,
+    runtime: &Handle,
+) {
+    info!(
+        LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+            "Starting the consensus observer SSE export server at: {}",
+            sse_export_address
+        ))
+    );
+
+    // Spawn the SSE server
+    runtime.spawn(async move {
+        // Create the service function that handles the endpoint requests
+        let make_service = make_service_fn(move |_connection| {
+            let data_exporter = data_exporter.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    serve_request(request, data_exporter.clone())
+                }))
+            }
+        });
+
+        // Bind to the address and serve the requests
+        let result = match Server::try_bind(&sse_export_address) {
+            Ok(server_builder) => server_builder.serve(make_service).await,
+            Err(error) => Err(error),
+        };
+        if let Err(error) = result {
+            error!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "The consensus observer SSE export server failed! Error: {:?}",
+                    error
+                ))
+            );
+        }
+    });
+}
+
+/// Handles a single SSE export request
+async fn serve_request(
+    request: Request<Body>,
+    data_exporter: ObserverDataExporter,
+) -> Result<Response<Body>, Infallible> {
+    // Verify the request endpoint
+    let request_path = request.uri().path();
+    if request_path != COMMITS_PATH && request_path != WEBSOCKET_COMMITS_PATH {
+        return Ok(create_error_response(
+            StatusCode::NOT_FOUND,
+            Body::from(INVALID_ENDPOINT_MESSAGE),
+        ));
+    }
+
+    // Verify the request method
+    if *request.method() != Method::GET {
+        return Ok(create_error_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            Body::empty(),
+        ));
+    }
+
+    // If the WebSocket feed was requested, upgrade the connection
+    if request.uri().path() == WEBSOCKET_COMMITS_PATH {
+        return Ok(upgrade_to_websocket(request, data_exporter));
+    }
+
+    // Subscribe to the observed data and stream the events to the client
+    let include_ordered_blocks = include_ordered_blocks(request.uri().query());
+    let event_stream = create_event_stream(data_exporter.subscribe(), include_ordered_blocks);
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(hyper::header::CONTENT_TYPE, CONTENT_TYPE_EVENT_STREAM)
+        .header(hyper::header::CACHE_CONTROL, "no-cache")
+        .body(Body::wrap_stream(event_stream))
+        .unwrap_or_else(|error| {
+            create_error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Body::from(format!("Failed to create the response! Error: {:?}", error)),
+            )
+        });
+
+    Ok(response)
+}
+
+/// Creates an error response with the given status code and body
+fn create_error_response(status_code: StatusCode, body: Body) -> Response<Body> {
+    let mut response = Response::new(body);
+    *response.status_mut() = status_code;
+    response
+}
+
+/// Transforms the given receiver of observed data into a stream of SSE events
+fn create_event_stream(
+    observed_data_receiver: broadcast::Receiver<ObservedData>,
+    include_ordered_blocks: bool,
+) -> impl Stream<Item = Result<String, Infallible>> {
+    stream::unfold(
+        observed_data_receiver,
+        move |mut observed_data_receiver| async move {
+            loop {
+                match observed_data_receiver.recv().await {
+                    Ok(observed_data) => {
+                        if let Some(event) = create_sse_event(observed_data, include_ordered_blocks)
+                        {
+                            return Some((Ok(event), observed_data_receiver));
+                        }
+                    },
+                    Err(RecvError::Lagged(num_skipped_items)) => {
+                        warn!(
+                            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                "The SSE export stream is lagging! Skipped {} items!",
+                                num_skipped_items
+                            ))
+                        );
+                    },
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    )
+}
+
+/// Transforms the given observed data into an SSE event. Returns None
+/// if the data should not be sent to the client (e.g., block payloads).
+fn create_sse_event(observed_data: ObservedData, include_ordered_blocks: bool) -> Option<String> {
+    let (event_name, event_data) = match observed_data {
+        ObservedData::CommitDecision {
+            proof_block_info, ..
+        } => {
+            let event_data = CommitDecisionEventData {
+                proof_block: BlockSummary::new(&proof_block_info),
+            };
+            (COMMIT_DECISION_EVENT, serde_json::to_string(&event_data))
+        },
+        ObservedData::OrderedBlock {
+            blocks,
+            proof_block_info,
+            ..
+        } if include_ordered_blocks => {
+            let event_data = OrderedBlockEventData {
+                blocks: blocks.iter().map(BlockSummary::new).collect(),
+                proof_block: BlockSummary::new(&proof_block_info),
+            };
+            (ORDERED_BLOCK_EVENT, serde_json::to_string(&event_data))
+        },
+        _ => return None, // The remaining data is not sent to SSE clients
+    };
+
+    // Serialize the event data
+    match event_data {
+        Ok(event_data) => Some(format!("event: {}\ndata: {}\n\n", event_name, event_data)),
+        Err(error) => {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to serialize the SSE event data! Error: {:?}",
+                    error
+                ))
+            );
+            None
+        },
+    }
+}
+
+/// Returns true iff the given query requests ordered blocks
+pub(crate) fn include_ordered_blocks(query: Option<&str>) -> bool {
+    query
+        .map(|query| {
+            query.split('&').any(|parameter| {
+                parameter == INCLUDE_ORDERED_BLOCKS_PARAM
+                    || parameter == format!("{}=true", INCLUDE_ORDERED_BLOCKS_PARAM)
+            })
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::network_message::CommitDecision;
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+    use hyper::body::HttpBody;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_create_sse_event() {
+        // Create a commit decision event and verify the event contents
+        let block_info = BlockInfo::random_with_epoch(10, 20);
+        let sse_event = create_sse_event(
+            ObservedData::CommitDecision {
+                proof_block_info: block_info.clone(),
+                commit_proof: Arc::new(create_ledger_info(block_info.clone())),
+            },
+            false,
+        )
+        .unwrap();
+        assert!(sse_event.starts_with("event: commit_decision\ndata: "));
+        assert!(sse_event.contains(&block_info.id().to_hex()));
+        assert!(sse_event.ends_with("\n\n"));
+
+        // Verify that ordered blocks are only sent if requested
+        let ordered_block = ObservedData::OrderedBlock {
+            blocks: vec![block_info.clone()],
+            proof_block_info: block_info.clone(),
+            ordered_proof: Arc::new(create_ledger_info(block_info)),
+        };
+        assert!(create_sse_event(ordered_block.clone(), false).is_none());
+        let sse_event = create_sse_event(ordered_block, true).unwrap();
+        assert!(sse_event.starts_with("event: ordered_block\ndata: "));
+
+        // Verify that block payloads are never sent
+        let block_payload = ObservedData::BlockPayload {
+            block: BlockInfo::empty(),
+            num_transactions: 0,
+            transaction_limit: None,
+            transactions: Arc::new(vec![]),
+        };
+        assert!(create_sse_event(block_payload, true).is_none());
+    }
+
+    #[test]
+    fn test_include_ordered_blocks() {
+        assert!(!include_ordered_blocks(None));
+        assert!(!include_ordered_blocks(Some("")));
+        assert!(!include_ordered_blocks(Some(
+            "include_ordered_blocks=false"
+        )));
+        assert!(include_ordered_blocks(Some("include_ordered_blocks")));
+        assert!(include_ordered_blocks(Some(
+            "foo=bar&include_ordered_blocks=true"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_serve_request() {
+        // Create a data exporter
+        let data_exporter = ObserverDataExporter::new(10);
+
+        // Send a request to an invalid endpoint and verify the response
+        let request = Request::get("/invalid").body(Body::empty()).unwrap();
+        let response = serve_request(request, data_exporter.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Send a request with an invalid method and verify the response
+        let request = Request::post(COMMITS_PATH).body(Body::empty()).unwrap();
+        let response = serve_request(request, data_exporter.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        // Send a WebSocket request without the upgrade headers and verify the response
+        let request = Request::get(WEBSOCKET_COMMITS_PATH)
+            .body(Body::empty())
+            .unwrap();
+        let response = serve_request(request, data_exporter.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Send a valid request and verify the response
+        let request = Request::get(COMMITS_PATH).body(Body::empty()).unwrap();
+        let response = serve_request(request, data_exporter.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Export a commit decision
+        let block_info = BlockInfo::random_with_epoch(0, 10);
+        let ledger_info = LedgerInfo::new(block_info.clone(), HashValue::random());
+        let commit_decision = CommitDecision::new(LedgerInfoWithSignatures::new(
+            ledger_info,
+            AggregateSignature::empty(),
+        ));
+        data_exporter.export_commit_decision(&commit_decision);
+
+        // Verify that the commit decision event is streamed to the client
+        let mut response_body = response.into_body();
+        let event = response_body.data().await.unwrap().unwrap();
+        let event = String::from_utf8(event.to_vec()).unwrap();
+        assert!(event.starts_with("event: commit_decision\ndata: "));
+        assert!(event.contains(&block_info.id().to_hex()));
+    }
+
+    /// Creates a ledger info (with an empty signature) for the given block
+    fn create_ledger_info(block_info: BlockInfo) -> LedgerInfoWithSignatures {
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(block_info, HashValue::random()),
+            AggregateSignature::empty(),
+        )
+    }
+}
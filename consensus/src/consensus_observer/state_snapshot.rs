@@ -0,0 +1,247 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    error::Error,
+    logging::{LogEntry, LogSchema},
+};
+use aptos_config::network_id::PeerNetworkId;
+use aptos_crypto::HashValue;
+use aptos_logger::warn;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::block_info::{BlockInfo, Round};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+// The names of the snapshot file (and the temporary file used for atomic writes)
+const STATE_SNAPSHOT_FILE_NAME: &str = "observer_state_snapshot.bcs";
+const STATE_SNAPSHOT_TEMP_FILE_NAME: &str = "observer_state_snapshot.bcs.tmp";
+
+/// A compact digest of a single pending block
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PendingBlockDigest {
+    pub epoch: u64,
+    pub round: Round,
+    pub block_id: HashValue,
+}
+
+impl PendingBlockDigest {
+    pub fn new(block_info: &BlockInfo) -> Self {
+        Self {
+            epoch: block_info.epoch(),
+            round: block_info.round(),
+            block_id: block_info.id(),
+        }
+    }
+}
+
+/// A compact snapshot of the consensus observer state. The snapshot allows
+/// the observer to resume with warm state after a restart (e.g., by
+/// resubscribing to the same peer), instead of starting blind.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ObserverStateSnapshot {
+    // The root block (i.e., the last committed block)
+    pub root: BlockInfo,
+
+    // The active subscription peer (if any)
+    pub subscription_peer: Option<PeerNetworkId>,
+
+    // The digests of the pending blocks (ordered by epoch and round)
+    pub pending_block_digests: Vec<PendingBlockDigest>,
+
+    // The block IDs of the available payloads
+    pub payload_digests: Vec<HashValue>,
+}
+
+/// The state snapshotter periodically persists snapshots of the observer
+/// state to disk (overwriting the previous snapshot), and loads the last
+/// snapshot when the observer restarts.
+pub struct ObserverStateSnapshotter {
+    // The directory containing the snapshot file
+    snapshot_directory: PathBuf,
+
+    // The interval at which snapshots should be taken
+    snapshot_interval: Duration,
+
+    // The time of the last snapshot (if any)
+    last_snapshot_time: Option<Instant>,
+
+    // The time service (used to determine when to take snapshots)
+    time_service: TimeService,
+}
+
+impl ObserverStateSnapshotter {
+    pub fn new(
+        snapshot_directory: PathBuf,
+        snapshot_interval_ms: u64,
+        time_service: TimeService,
+    ) -> Self {
+        Self {
+            snapshot_directory,
+            snapshot_interval: Duration::from_millis(snapshot_interval_ms),
+            last_snapshot_time: None,
+            time_service,
+        }
+    }
+
+    /// Loads the last persisted snapshot (if one exists)
+    pub fn load_snapshot(&self) -> Result<Option<ObserverStateSnapshot>, Error> {
+        // Check if a snapshot exists
+        let snapshot_file_path = self.snapshot_directory.join(STATE_SNAPSHOT_FILE_NAME);
+        if !snapshot_file_path.exists() {
+            return Ok(None);
+        }
+
+        // Read and deserialize the snapshot
+        let snapshot_bytes = fs::read(&snapshot_file_path).map_err(|error| {
+            Error::StateSnapshotError(format!(
+                "Failed to read the state snapshot file: {:?}! Error: {:?}",
+                snapshot_file_path, error
+            ))
+        })?;
+        let state_snapshot = bcs::from_bytes(&snapshot_bytes).map_err(|error| {
+            Error::StateSnapshotError(format!(
+                "Failed to deserialize the state snapshot! Error: {:?}",
+                error
+            ))
+        })?;
+
+        Ok(Some(state_snapshot))
+    }
+
+    /// Returns true iff a new snapshot should be taken (i.e.,
+    /// the snapshot interval has elapsed since the last snapshot).
+    pub fn should_take_snapshot(&self) -> bool {
+        match self.last_snapshot_time {
+            Some(last_snapshot_time) => {
+                self.time_service.now().duration_since(last_snapshot_time) >= self.snapshot_interval
+            },
+            None => true, // No snapshot has been taken yet
+        }
+    }
+
+    /// Persists the given snapshot to disk. The snapshot is first written
+    /// to a temporary file, and then renamed (to avoid partial snapshots).
+    pub fn write_snapshot(&mut self, state_snapshot: &ObserverStateSnapshot) {
+        // Update the last snapshot time (even on failure, to avoid retrying constantly)
+        self.last_snapshot_time = Some(self.time_service.now());
+
+        // Write the snapshot
+        if let Err(error) = self.write_snapshot_to_disk(state_snapshot) {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to write the state snapshot! Error: {:?}",
+                    error
+                ))
+            );
+        }
+    }
+
+    /// Serializes and writes the given snapshot to disk
+    fn write_snapshot_to_disk(&self, state_snapshot: &ObserverStateSnapshot) -> Result<(), Error> {
+        // Serialize the snapshot
+        let snapshot_bytes = bcs::to_bytes(state_snapshot).map_err(|error| {
+            Error::StateSnapshotError(format!(
+                "Failed to serialize the state snapshot! Error: {:?}",
+                error
+            ))
+        })?;
+
+        // Write the snapshot to a temporary file and move it into place
+        let temp_file_path = self.snapshot_directory.join(STATE_SNAPSHOT_TEMP_FILE_NAME);
+        let snapshot_file_path = self.snapshot_directory.join(STATE_SNAPSHOT_FILE_NAME);
+        fs::create_dir_all(&self.snapshot_directory)
+            .and_then(|_| fs::write(&temp_file_path, snapshot_bytes))
+            .and_then(|_| fs::rename(&temp_file_path, &snapshot_file_path))
+            .map_err(|error| {
+                Error::StateSnapshotError(format!(
+                    "Failed to write the state snapshot file: {:?}! Error: {:?}",
+                    snapshot_file_path, error
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_config::network_id::NetworkId;
+    use aptos_temppath::TempPath;
+    use aptos_types::PeerId;
+
+    #[test]
+    fn test_write_and_load_snapshot() {
+        // Create a state snapshotter
+        let snapshot_directory = TempPath::new();
+        let mut state_snapshotter = ObserverStateSnapshotter::new(
+            snapshot_directory.path().to_path_buf(),
+            1000,
+            TimeService::mock(),
+        );
+
+        // Verify that no snapshot exists (yet)
+        assert!(state_snapshotter.load_snapshot().unwrap().is_none());
+
+        // Write a snapshot and verify that it can be loaded
+        let state_snapshot = create_state_snapshot(10);
+        state_snapshotter.write_snapshot(&state_snapshot);
+        assert_eq!(
+            state_snapshotter.load_snapshot().unwrap(),
+            Some(state_snapshot)
+        );
+
+        // Overwrite the snapshot and verify that the new snapshot is loaded
+        let new_state_snapshot = create_state_snapshot(20);
+        state_snapshotter.write_snapshot(&new_state_snapshot);
+        assert_eq!(
+            state_snapshotter.load_snapshot().unwrap(),
+            Some(new_state_snapshot)
+        );
+    }
+
+    #[test]
+    fn test_should_take_snapshot() {
+        // Create a state snapshotter
+        let snapshot_interval_ms = 1000;
+        let time_service = TimeService::mock();
+        let snapshot_directory = TempPath::new();
+        let mut state_snapshotter = ObserverStateSnapshotter::new(
+            snapshot_directory.path().to_path_buf(),
+            snapshot_interval_ms,
+            time_service.clone(),
+        );
+
+        // Verify that a snapshot should be taken (no snapshot has been taken yet)
+        assert!(state_snapshotter.should_take_snapshot());
+
+        // Take a snapshot and verify that another snapshot isn't required
+        state_snapshotter.write_snapshot(&create_state_snapshot(10));
+        assert!(!state_snapshotter.should_take_snapshot());
+
+        // Elapse less than the snapshot interval and verify no snapshot is required
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance(Duration::from_millis(snapshot_interval_ms - 1));
+        assert!(!state_snapshotter.should_take_snapshot());
+
+        // Elapse the remaining interval and verify that a snapshot is required
+        mock_time_service.advance(Duration::from_millis(1));
+        assert!(state_snapshotter.should_take_snapshot());
+    }
+
+    /// Creates a state snapshot with the given root round
+    fn create_state_snapshot(root_round: Round) -> ObserverStateSnapshot {
+        let pending_block_digests = (root_round + 1..root_round + 5)
+            .map(|round| PendingBlockDigest::new(&BlockInfo::random_with_epoch(0, round)))
+            .collect();
+        ObserverStateSnapshot {
+            root: BlockInfo::random_with_epoch(0, root_round),
+            subscription_peer: Some(PeerNetworkId::new(NetworkId::Public, PeerId::random())),
+            pending_block_digests,
+            payload_digests: vec![HashValue::random(), HashValue::random()],
+        }
+    }
+}
@@ -0,0 +1,279 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{error::Error, pending_blocks::PendingOrderedBlocks};
+use aptos_infallible::Mutex;
+use aptos_types::{
+    block_info::{BlockInfo, Round},
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+};
+use std::sync::Arc;
+
+/// A simple tracker for the root ledger info and epoch state of the
+/// consensus observer. The tracker is cheaply cloneable (e.g., so that
+/// the commit callbacks can advance the root), and all clones share state.
+#[derive(Clone)]
+pub struct ObserverStateTracker {
+    // The current epoch state (set when each epoch starts)
+    epoch_state: Arc<Mutex<Option<Arc<EpochState>>>>,
+
+    // The latest ledger info (updated via commit callbacks and state sync)
+    root: Arc<Mutex<Arc<LedgerInfoWithSignatures>>>,
+}
+
+impl ObserverStateTracker {
+    pub fn new(root: LedgerInfoWithSignatures) -> Self {
+        Self {
+            epoch_state: Arc::new(Mutex::new(None)),
+            root: Arc::new(Mutex::new(Arc::new(root))),
+        }
+    }
+
+    /// Advances the root to the given (committed) ledger info. If the ledger
+    /// info is for a different epoch, an error is returned. If the ledger info
+    /// is not for a higher round than the current root, it is ignored. This
+    /// avoids racing with the root updates made by the state sync process.
+    pub fn advance_root(&self, ledger_info: Arc<LedgerInfoWithSignatures>) -> Result<(), Error> {
+        let mut root = self.root.lock();
+
+        // Verify the ledger info is for the same epoch
+        if ledger_info.commit_info().epoch() != root.commit_info().epoch() {
+            return Err(Error::UnexpectedError(format!(
+                "Received commit for a different epoch! Ledger info: {:?}, Root: {:?}",
+                ledger_info.commit_info(),
+                root.commit_info()
+            )));
+        }
+
+        // Update the root ledger info (if the round is higher)
+        if ledger_info.commit_info().round() > root.commit_info().round() {
+            *root = ledger_info;
+        }
+
+        Ok(())
+    }
+
+    /// Returns true iff the given block is more than the specified number of
+    /// rounds ahead of the root. Note: blocks for future epochs are never
+    /// considered beyond the horizon (their rounds aren't comparable to the
+    /// root round), but these are bounded by the maximum number of pending blocks.
+    pub fn is_beyond_rounds_ahead_horizon(
+        &self,
+        block_info: &BlockInfo,
+        max_rounds_ahead_of_root: u64,
+    ) -> bool {
+        let root = self.root.lock();
+        let root_block = root.commit_info();
+        block_info.epoch() == root_block.epoch()
+            && block_info.round() > root_block.round().saturating_add(max_rounds_ahead_of_root)
+    }
+
+    /// Returns the current epoch state, and panics if it is not set
+    pub fn epoch_state(&self) -> Arc<EpochState> {
+        self.epoch_state
+            .lock()
+            .clone()
+            .expect("The epoch state is not set! This should never happen!")
+    }
+
+    /// Returns the current epoch state (if it is set)
+    pub fn try_epoch_state(&self) -> Option<Arc<EpochState>> {
+        self.epoch_state.lock().clone()
+    }
+
+    /// Returns the last known block (i.e., the last pending
+    /// block, or the root if there are no pending blocks).
+    pub fn last_block(&self, pending_ordered_blocks: &PendingOrderedBlocks) -> BlockInfo {
+        if let Some(last_pending_block) = pending_ordered_blocks.get_last_pending_block() {
+            last_pending_block
+        } else {
+            // Return the root ledger info
+            self.root_block()
+        }
+    }
+
+    /// Returns a shared reference to the root ledger info
+    pub fn root(&self) -> Arc<LedgerInfoWithSignatures> {
+        self.root.lock().clone()
+    }
+
+    /// Returns the block info of the root ledger info
+    pub fn root_block(&self) -> BlockInfo {
+        self.root.lock().commit_info().clone()
+    }
+
+    /// Returns true iff the root matches the given epoch and round
+    pub fn root_matches_epoch_and_round(&self, epoch: u64, round: Round) -> bool {
+        let root = self.root.lock();
+        root.commit_info().epoch() == epoch && root.commit_info().round() == round
+    }
+
+    /// Sets the epoch state (e.g., when a new epoch starts)
+    pub fn set_epoch_state(&self, epoch_state: Arc<EpochState>) {
+        *self.epoch_state.lock() = Some(epoch_state);
+    }
+
+    /// Sets the root to the given ledger info unconditionally
+    /// (e.g., when syncing to a commit decision).
+    pub fn set_root(&self, ledger_info: Arc<LedgerInfoWithSignatures>) {
+        *self.root.lock() = ledger_info;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_config::config::ConsensusObserverConfig;
+    use aptos_crypto::HashValue;
+    use aptos_types::{aggregate_signature::AggregateSignature, ledger_info::LedgerInfo};
+
+    #[test]
+    fn test_advance_root() {
+        // Create a new state tracker with a root at epoch 10, round 100
+        let epoch = 10;
+        let state_tracker = ObserverStateTracker::new(create_ledger_info(epoch, 100));
+
+        // Advance the root to a higher round and verify the root is updated
+        state_tracker
+            .advance_root(Arc::new(create_ledger_info(epoch, 101)))
+            .unwrap();
+        verify_root(&state_tracker, epoch, 101);
+
+        // Advance the root to a lower round and verify the root is not updated
+        state_tracker
+            .advance_root(Arc::new(create_ledger_info(epoch, 50)))
+            .unwrap();
+        verify_root(&state_tracker, epoch, 101);
+
+        // Advance the root to the same round and verify the root is not updated
+        state_tracker
+            .advance_root(Arc::new(create_ledger_info(epoch, 101)))
+            .unwrap();
+        verify_root(&state_tracker, epoch, 101);
+
+        // Advance the root to a different epoch and verify an error is returned
+        for different_epoch in [epoch - 1, epoch + 1] {
+            assert!(state_tracker
+                .advance_root(Arc::new(create_ledger_info(different_epoch, 200)))
+                .is_err());
+            verify_root(&state_tracker, epoch, 101);
+        }
+    }
+
+    #[test]
+    fn test_advance_root_after_sync() {
+        // Create a new state tracker with a root at epoch 10, round 100
+        let epoch = 10;
+        let state_tracker = ObserverStateTracker::new(create_ledger_info(epoch, 100));
+
+        // Set the root (e.g., when syncing to a commit decision)
+        state_tracker.set_root(Arc::new(create_ledger_info(epoch, 200)));
+        verify_root(&state_tracker, epoch, 200);
+
+        // Advance the root with a stale commit (e.g., a commit callback
+        // racing with state sync) and verify the root is not moved backwards.
+        state_tracker
+            .advance_root(Arc::new(create_ledger_info(epoch, 150)))
+            .unwrap();
+        verify_root(&state_tracker, epoch, 200);
+
+        // Set the root to a new epoch and verify stale commits are rejected
+        let new_epoch = epoch + 1;
+        state_tracker.set_root(Arc::new(create_ledger_info(new_epoch, 0)));
+        assert!(state_tracker
+            .advance_root(Arc::new(create_ledger_info(epoch, 300)))
+            .is_err());
+        verify_root(&state_tracker, new_epoch, 0);
+
+        // Advance the root in the new epoch and verify the root is updated
+        state_tracker
+            .advance_root(Arc::new(create_ledger_info(new_epoch, 1)))
+            .unwrap();
+        verify_root(&state_tracker, new_epoch, 1);
+    }
+
+    #[test]
+    fn test_epoch_state() {
+        // Create a new state tracker
+        let state_tracker = ObserverStateTracker::new(create_ledger_info(0, 0));
+
+        // Set the epoch state and verify it is shared across clones
+        let cloned_state_tracker = state_tracker.clone();
+        let epoch_state = Arc::new(EpochState::empty());
+        state_tracker.set_epoch_state(epoch_state.clone());
+        assert_eq!(cloned_state_tracker.epoch_state(), epoch_state);
+    }
+
+    #[test]
+    fn test_is_beyond_rounds_ahead_horizon() {
+        // Create a new state tracker with a root at epoch 10, round 100
+        let epoch = 10;
+        let state_tracker = ObserverStateTracker::new(create_ledger_info(epoch, 100));
+
+        // Verify that blocks within the horizon are not beyond it
+        let max_rounds_ahead_of_root = 50;
+        for round in [0, 100, 101, 150] {
+            let block_info = BlockInfo::random_with_epoch(epoch, round);
+            assert!(!state_tracker
+                .is_beyond_rounds_ahead_horizon(&block_info, max_rounds_ahead_of_root));
+        }
+
+        // Verify that blocks past the horizon are beyond it
+        for round in [151, 1000] {
+            let block_info = BlockInfo::random_with_epoch(epoch, round);
+            assert!(
+                state_tracker.is_beyond_rounds_ahead_horizon(&block_info, max_rounds_ahead_of_root)
+            );
+        }
+
+        // Verify that blocks for other epochs are never beyond the horizon
+        for different_epoch in [epoch - 1, epoch + 1] {
+            let block_info = BlockInfo::random_with_epoch(different_epoch, 1000);
+            assert!(!state_tracker
+                .is_beyond_rounds_ahead_horizon(&block_info, max_rounds_ahead_of_root));
+        }
+
+        // Advance the root and verify the horizon moves with it
+        state_tracker
+            .advance_root(Arc::new(create_ledger_info(epoch, 200)))
+            .unwrap();
+        let block_info = BlockInfo::random_with_epoch(epoch, 250);
+        assert!(
+            !state_tracker.is_beyond_rounds_ahead_horizon(&block_info, max_rounds_ahead_of_root)
+        );
+    }
+
+    #[test]
+    fn test_last_block() {
+        // Create a new state tracker with a root at epoch 10, round 100
+        let epoch = 10;
+        let state_tracker = ObserverStateTracker::new(create_ledger_info(epoch, 100));
+
+        // Verify that the last block is the root (there are no pending blocks)
+        let pending_ordered_blocks = PendingOrderedBlocks::new(ConsensusObserverConfig::default());
+        let last_block = state_tracker.last_block(&pending_ordered_blocks);
+        assert_eq!(last_block, state_tracker.root_block());
+        assert_eq!(last_block.epoch(), epoch);
+        assert_eq!(last_block.round(), 100);
+    }
+
+    /// Creates and returns a ledger info with the specified epoch and round
+    fn create_ledger_info(epoch: u64, round: Round) -> LedgerInfoWithSignatures {
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(
+                BlockInfo::random_with_epoch(epoch, round),
+                HashValue::random(),
+            ),
+            AggregateSignature::empty(),
+        )
+    }
+
+    /// Verifies the root of the state tracker matches the given epoch and round
+    fn verify_root(state_tracker: &ObserverStateTracker, epoch: u64, round: Round) {
+        assert!(state_tracker.root_matches_epoch_and_round(epoch, round));
+        let root = state_tracker.root();
+        assert_eq!(root.commit_info().epoch(), epoch);
+        assert_eq!(root.commit_info().round(), round);
+    }
+}
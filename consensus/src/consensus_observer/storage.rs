@@ -0,0 +1,199 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::error::Error;
+use aptos_infallible::Mutex;
+use aptos_storage_interface::DbReader;
+use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::Version};
+use std::{collections::HashMap, sync::Arc};
+
+/// A narrow interface for the storage reads required by the consensus
+/// observer. This allows the observer to be tested without a real database.
+pub trait ObserverStorageInterface: Send + Sync {
+    /// Returns the ledger info that ends the given epoch
+    fn get_epoch_ending_ledger_info(&self, epoch: u64) -> Result<LedgerInfoWithSignatures, Error>;
+
+    /// Returns the latest ledger info in storage
+    fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures, Error>;
+
+    /// Returns the latest synced version in storage
+    fn get_latest_synced_version(&self) -> Result<Version, Error>;
+}
+
+/// The production storage implementation (backed by the DB reader)
+pub struct DbBackedObserverStorage {
+    db_reader: Arc<dyn DbReader>,
+}
+
+impl DbBackedObserverStorage {
+    pub fn new(db_reader: Arc<dyn DbReader>) -> Self {
+        Self { db_reader }
+    }
+}
+
+impl ObserverStorageInterface for DbBackedObserverStorage {
+    fn get_epoch_ending_ledger_info(&self, epoch: u64) -> Result<LedgerInfoWithSignatures, Error> {
+        let epoch_change_proof = self
+            .db_reader
+            .get_epoch_ending_ledger_infos(epoch, epoch + 1)
+            .map_err(|error| {
+                Error::UnexpectedError(format!(
+                    "Failed to read the epoch ending ledger info for epoch {}: {:?}",
+                    epoch, error
+                ))
+            })?;
+        epoch_change_proof
+            .ledger_info_with_sigs
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                Error::UnexpectedError(format!(
+                    "No epoch ending ledger info was found for epoch {}!",
+                    epoch
+                ))
+            })
+    }
+
+    fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures, Error> {
+        self.db_reader.get_latest_ledger_info().map_err(|error| {
+            Error::UnexpectedError(format!("Failed to read latest ledger info: {:?}", error))
+        })
+    }
+
+    fn get_latest_synced_version(&self) -> Result<Version, Error> {
+        self.db_reader
+            .get_latest_ledger_info_version()
+            .map_err(|error| {
+                Error::UnexpectedError(format!(
+                    "Failed to read highest synced version: {:?}",
+                    error
+                ))
+            })
+    }
+}
+
+/// A simple in-memory storage implementation (useful for tests and simulations)
+pub struct InMemoryObserverStorage {
+    epoch_ending_ledger_infos: Mutex<HashMap<u64, LedgerInfoWithSignatures>>,
+    latest_ledger_info: Mutex<LedgerInfoWithSignatures>,
+    latest_synced_version: Mutex<Version>,
+}
+
+impl InMemoryObserverStorage {
+    pub fn new(latest_ledger_info: LedgerInfoWithSignatures) -> Self {
+        let latest_synced_version = latest_ledger_info.ledger_info().version();
+        Self {
+            epoch_ending_ledger_infos: Mutex::new(HashMap::new()),
+            latest_ledger_info: Mutex::new(latest_ledger_info),
+            latest_synced_version: Mutex::new(latest_synced_version),
+        }
+    }
+
+    /// Inserts the given epoch ending ledger info
+    pub fn insert_epoch_ending_ledger_info(&self, ledger_info: LedgerInfoWithSignatures) {
+        self.epoch_ending_ledger_infos
+            .lock()
+            .insert(ledger_info.ledger_info().epoch(), ledger_info);
+    }
+
+    /// Updates the latest ledger info (and the latest synced version)
+    pub fn set_latest_ledger_info(&self, latest_ledger_info: LedgerInfoWithSignatures) {
+        *self.latest_synced_version.lock() = latest_ledger_info.ledger_info().version();
+        *self.latest_ledger_info.lock() = latest_ledger_info;
+    }
+
+    /// Updates the latest synced version
+    pub fn set_latest_synced_version(&self, latest_synced_version: Version) {
+        *self.latest_synced_version.lock() = latest_synced_version;
+    }
+}
+
+impl ObserverStorageInterface for InMemoryObserverStorage {
+    fn get_epoch_ending_ledger_info(&self, epoch: u64) -> Result<LedgerInfoWithSignatures, Error> {
+        self.epoch_ending_ledger_infos
+            .lock()
+            .get(&epoch)
+            .cloned()
+            .ok_or_else(|| {
+                Error::UnexpectedError(format!(
+                    "No epoch ending ledger info was found for epoch {}!",
+                    epoch
+                ))
+            })
+    }
+
+    fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures, Error> {
+        Ok(self.latest_ledger_info.lock().clone())
+    }
+
+    fn get_latest_synced_version(&self) -> Result<Version, Error> {
+        Ok(*self.latest_synced_version.lock())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature, block_info::BlockInfo, ledger_info::LedgerInfo,
+    };
+
+    #[test]
+    fn test_in_memory_storage() {
+        // Create an in-memory storage with an initial ledger info
+        let ledger_info = create_ledger_info(100);
+        let observer_storage = InMemoryObserverStorage::new(ledger_info.clone());
+
+        // Verify the latest ledger info and synced version
+        assert_eq!(
+            observer_storage.get_latest_ledger_info().unwrap(),
+            ledger_info
+        );
+        assert_eq!(observer_storage.get_latest_synced_version().unwrap(), 100);
+
+        // Update the synced version and verify the new version is returned
+        observer_storage.set_latest_synced_version(150);
+        assert_eq!(observer_storage.get_latest_synced_version().unwrap(), 150);
+        assert_eq!(
+            observer_storage.get_latest_ledger_info().unwrap(),
+            ledger_info
+        );
+
+        // Update the ledger info and verify the new ledger info and version are returned
+        let new_ledger_info = create_ledger_info(200);
+        observer_storage.set_latest_ledger_info(new_ledger_info.clone());
+        assert_eq!(
+            observer_storage.get_latest_ledger_info().unwrap(),
+            new_ledger_info
+        );
+        assert_eq!(observer_storage.get_latest_synced_version().unwrap(), 200);
+
+        // Verify that no epoch ending ledger info exists
+        assert!(observer_storage.get_epoch_ending_ledger_info(0).is_err());
+
+        // Insert an epoch ending ledger info and verify it is returned
+        observer_storage.insert_epoch_ending_ledger_info(ledger_info.clone());
+        assert_eq!(
+            observer_storage.get_epoch_ending_ledger_info(0).unwrap(),
+            ledger_info
+        );
+    }
+
+    /// Creates and returns a ledger info with the specified version
+    fn create_ledger_info(version: Version) -> LedgerInfoWithSignatures {
+        let block_info = BlockInfo::new(
+            0,
+            0,
+            HashValue::random(),
+            HashValue::random(),
+            version,
+            0,
+            None,
+        );
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(block_info, HashValue::random()),
+            AggregateSignature::empty(),
+        )
+    }
+}
@@ -4,13 +4,14 @@
 use crate::consensus_observer::{
     error::Error,
     logging::{LogEntry, LogSchema},
+    storage::ObserverStorageInterface,
 };
 use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
 use aptos_logger::warn;
 use aptos_network::application::metadata::PeerMetadata;
-use aptos_storage_interface::DbReader;
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use ordered_float::OrderedFloat;
+use serde::Serialize;
 use std::{
     collections::{BTreeMap, HashMap},
     sync::Arc,
@@ -20,13 +21,23 @@ use std::{
 // A useful constant for representing the maximum ping latency
 const MAX_PING_LATENCY_SECS: f64 = 10_000.0;
 
+/// A summary of the health of a single subscription
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct SubscriptionHealth {
+    pub peer_network_id: PeerNetworkId,
+    pub subscription_age_ms: u64,
+    pub time_since_last_message_ms: u64,
+    pub highest_synced_version: u64,
+    pub time_since_last_synced_version_increase_ms: u64,
+}
+
 /// A single consensus observer subscription
 pub struct ConsensusObserverSubscription {
     // The configuration of the consensus observer
     consensus_observer_config: ConsensusObserverConfig,
 
     // A handle to storage (used to read the latest state and check progress)
-    db_reader: Arc<dyn DbReader>,
+    observer_storage: Arc<dyn ObserverStorageInterface>,
 
     // The peer network id of the active subscription
     peer_network_id: PeerNetworkId,
@@ -37,9 +48,19 @@ pub struct ConsensusObserverSubscription {
     // The timestamp of the last peer optimality check
     last_peer_optimality_check: Instant,
 
+    // The timestamp of the last subscription rotation check
+    last_subscription_rotation_check: Instant,
+
+    // The timestamp at which the subscription was created
+    subscription_start_time: Instant,
+
     // The highest synced version we've seen from storage, along with the time at which it was seen
     highest_synced_version_and_time: (u64, Instant),
 
+    // The last synced version increase, along with the time at which it was seen
+    // (used to detect stale subscriptions, i.e., publishers that stop sending data)
+    last_synced_version_increase: (u64, Instant),
+
     // The time service (used to check the last message receive time)
     time_service: TimeService,
 }
@@ -47,7 +68,7 @@ pub struct ConsensusObserverSubscription {
 impl ConsensusObserverSubscription {
     pub fn new(
         consensus_observer_config: ConsensusObserverConfig,
-        db_reader: Arc<dyn DbReader>,
+        observer_storage: Arc<dyn ObserverStorageInterface>,
         peer_network_id: PeerNetworkId,
         time_service: TimeService,
     ) -> Self {
@@ -55,22 +76,31 @@ impl ConsensusObserverSubscription {
 
         Self {
             consensus_observer_config,
-            db_reader,
+            observer_storage,
             peer_network_id,
             last_message_receive_time: time_now,
             last_peer_optimality_check: time_now,
+            last_subscription_rotation_check: time_now,
+            subscription_start_time: time_now,
             highest_synced_version_and_time: (0, time_now),
+            last_synced_version_increase: (0, time_now),
             time_service,
         }
     }
 
     /// Verifies that the peer selected for the subscription is optimal
-    /// based on the set of currently available peers. This is done
-    /// periodically to avoid excessive subscription terminations.
+    /// based on the set of currently available peers (as sorted by the
+    /// peer selector). This is done periodically to avoid excessive
+    /// subscription terminations.
     pub fn check_subscription_peer_optimality(
         &mut self,
-        peers_and_metadata: HashMap<PeerNetworkId, PeerMetadata>,
+        sorted_peers: &[PeerNetworkId],
     ) -> Result<(), Error> {
+        // If the subscription is still within the hold time, it is considered optimal
+        if self.is_within_hold_time() {
+            return Ok(());
+        }
+
         // Check if we need to perform the peer optimality check
         let time_now = self.time_service.now();
         let duration_since_last_check = time_now.duration_since(self.last_peer_optimality_check);
@@ -86,9 +116,20 @@ impl ConsensusObserverSubscription {
         // Update the last peer optimality check time
         self.last_peer_optimality_check = time_now;
 
-        // Verify that we're subscribed to the most optimal peer
-        if let Some(optimal_peer) = sort_peers_by_distance_and_latency(peers_and_metadata).first() {
-            if *optimal_peer != self.peer_network_id {
+        // Verify that we're subscribed to the most optimal peer. If subscription
+        // rotation is enabled, any peer in the rotation candidate set is optimal.
+        let num_optimal_peers = if self.consensus_observer_config.enable_subscription_rotation {
+            self.consensus_observer_config
+                .subscription_rotation_candidates as usize
+        } else {
+            1
+        };
+        if let Some(optimal_peer) = sorted_peers.first() {
+            if !sorted_peers
+                .iter()
+                .take(num_optimal_peers)
+                .any(|peer_network_id| *peer_network_id == self.peer_network_id)
+            {
                 return Err(Error::SubscriptionSuboptimal(format!(
                     "Subscription to peer: {} is no longer optimal! New optimal peer: {}",
                     self.peer_network_id, optimal_peer
@@ -99,6 +140,86 @@ impl ConsensusObserverSubscription {
         Ok(())
     }
 
+    /// Returns true iff the subscription is due for rotation (i.e., the rotation
+    /// interval has elapsed since the last rotation check). If so, the time of
+    /// the last rotation check is also updated.
+    pub fn check_subscription_rotation(&mut self) -> bool {
+        // If the subscription is still within the hold time, it should not be rotated
+        if self.is_within_hold_time() {
+            return false;
+        }
+
+        // Check if the rotation interval has elapsed
+        let time_now = self.time_service.now();
+        let duration_since_last_check =
+            time_now.duration_since(self.last_subscription_rotation_check);
+        if duration_since_last_check
+            < Duration::from_millis(
+                self.consensus_observer_config
+                    .subscription_rotation_interval_ms,
+            )
+        {
+            return false; // We don't need to rotate the subscription yet
+        }
+
+        // Update the last rotation check time
+        self.last_subscription_rotation_check = time_now;
+
+        true
+    }
+
+    /// Verifies that the subscription is not stale, i.e., that the synced version
+    /// has not stalled while other connected peers (as reported by the peer
+    /// monitoring service) are sufficiently ahead. This detects publishers that
+    /// remain connected and responsive, but silently stop sending new data.
+    pub fn check_subscription_staleness(
+        &mut self,
+        connected_peers_and_metadata: &HashMap<PeerNetworkId, PeerMetadata>,
+    ) -> Result<(), Error> {
+        // If the synced version has increased, the subscription is not stale
+        let current_synced_version = self.observer_storage.get_latest_synced_version()?;
+        let time_now = self.time_service.now();
+        let (last_synced_version, last_increase_time) = self.last_synced_version_increase;
+        if current_synced_version > last_synced_version {
+            self.last_synced_version_increase = (current_synced_version, time_now);
+            return Ok(());
+        }
+
+        // Check if the synced version has stalled for too long
+        let duration_since_increase = time_now.duration_since(last_increase_time);
+        if duration_since_increase
+            <= Duration::from_millis(self.consensus_observer_config.max_subscription_staleness_ms)
+        {
+            return Ok(());
+        }
+
+        // Verify that no other connected peer is sufficiently ahead of us
+        let highest_peer_synced_version = connected_peers_and_metadata
+            .iter()
+            .filter(|(peer_network_id, _)| **peer_network_id != self.peer_network_id)
+            .filter_map(|(_, peer_metadata)| get_synced_version_for_peer(peer_metadata))
+            .max();
+        if let Some(highest_peer_synced_version) = highest_peer_synced_version {
+            let version_lag = highest_peer_synced_version.saturating_sub(current_synced_version);
+            if version_lag
+                >= self
+                    .consensus_observer_config
+                    .min_subscription_staleness_version_lag
+            {
+                return Err(Error::SubscriptionStale(format!(
+                    "Subscription to peer: {} is stale! The synced version: {} has not \
+                    increased for: {:?}, but other peers report synced version: {}",
+                    self.peer_network_id,
+                    current_synced_version,
+                    duration_since_increase,
+                    highest_peer_synced_version
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Verifies that the subscription has not timed out based
     /// on the last received message time.
     pub fn check_subscription_timeout(&self) -> Result<(), Error> {
@@ -122,15 +243,7 @@ impl ConsensusObserverSubscription {
     /// Verifies that the DB is continuing to sync and commit new data
     pub fn check_syncing_progress(&mut self) -> Result<(), Error> {
         // Get the current synced version from storage
-        let current_synced_version =
-            self.db_reader
-                .get_latest_ledger_info_version()
-                .map_err(|error| {
-                    Error::UnexpectedError(format!(
-                        "Failed to read highest synced version: {:?}",
-                        error
-                    ))
-                })?;
+        let current_synced_version = self.observer_storage.get_latest_synced_version()?;
 
         // Verify that the synced version is increasing appropriately
         let (highest_synced_version, highest_version_timestamp) =
@@ -158,11 +271,55 @@ impl ConsensusObserverSubscription {
         Ok(())
     }
 
+    /// Resets the syncing progress timers of the subscription (e.g., after
+    /// execution was paused), to avoid treating the pause as a stalled DB.
+    pub fn reset_syncing_progress(&mut self) {
+        let time_now = self.time_service.now();
+        self.highest_synced_version_and_time.1 = time_now;
+        self.last_synced_version_increase.1 = time_now;
+    }
+
     /// Returns the peer network id of the subscription
     pub fn get_peer_network_id(&self) -> PeerNetworkId {
         self.peer_network_id
     }
 
+    /// Returns a summary of the current health of the subscription
+    pub fn get_subscription_health(&self) -> SubscriptionHealth {
+        let time_now = self.time_service.now();
+        let (highest_synced_version, _) = self.highest_synced_version_and_time;
+        let (_, last_synced_version_increase_time) = self.last_synced_version_increase;
+        SubscriptionHealth {
+            peer_network_id: self.peer_network_id,
+            subscription_age_ms: time_now
+                .duration_since(self.subscription_start_time)
+                .as_millis() as u64,
+            time_since_last_message_ms: time_now
+                .duration_since(self.last_message_receive_time)
+                .as_millis() as u64,
+            highest_synced_version,
+            time_since_last_synced_version_increase_ms: time_now
+                .duration_since(last_synced_version_increase_time)
+                .as_millis() as u64,
+        }
+    }
+
+    /// Returns true iff the subscription is still within the minimum hold
+    /// time (i.e., the observer should not switch peers voluntarily).
+    fn is_within_hold_time(&self) -> bool {
+        let duration_since_start = self
+            .time_service
+            .now()
+            .duration_since(self.subscription_start_time);
+        duration_since_start
+            < Duration::from_millis(self.consensus_observer_config.min_subscription_hold_time_ms)
+    }
+
+    /// Resets the subscription timeout (e.g., after a successful liveness probe)
+    pub fn reset_subscription_timeout(&mut self) {
+        self.last_message_receive_time = self.time_service.now();
+    }
+
     /// Verifies the given message is from the expected peer
     pub fn verify_message_sender(&mut self, peer_network_id: &PeerNetworkId) -> Result<(), Error> {
         // Verify the message is from the expected peer
@@ -227,32 +384,54 @@ fn get_latency_for_peer(
     latency
 }
 
+/// Gets the highest synced version for the specified peer from the peer metadata
+/// (as reported by the latest node info response of the peer monitoring service).
+fn get_synced_version_for_peer(peer_metadata: &PeerMetadata) -> Option<u64> {
+    peer_metadata
+        .get_peer_monitoring_metadata()
+        .latest_node_info_response
+        .as_ref()
+        .map(|node_info_response| node_info_response.highest_synced_version)
+}
+
+/// Gets the distance from the validators and the latency for the specified
+/// peer. If the distance or latency is missing, the maximum value is used.
+pub fn get_distance_and_latency_for_peer(
+    peer_network_id: &PeerNetworkId,
+    peer_metadata: &PeerMetadata,
+) -> (u64, f64) {
+    // Get the distance and latency for the peer
+    let distance = get_distance_for_peer(peer_network_id, peer_metadata);
+    let latency = get_latency_for_peer(peer_network_id, peer_metadata);
+
+    // If the distance is not found, use the maximum distance
+    let distance =
+        distance.unwrap_or(aptos_peer_monitoring_service_types::MAX_DISTANCE_FROM_VALIDATORS);
+
+    // If the latency is not found, use a large latency
+    let latency = latency.unwrap_or(MAX_PING_LATENCY_SECS);
+
+    (distance, latency)
+}
+
 /// Sorts the peers by distance from the validator set and latency.
 /// We prioritize distance over latency as we want to avoid close
 /// but not up-to-date peers. If peers don't have sufficient metadata
 /// for sorting, they are given a lower priority.
 pub fn sort_peers_by_distance_and_latency(
-    peers_and_metadata: HashMap<PeerNetworkId, PeerMetadata>,
+    peers_and_metadata: &HashMap<PeerNetworkId, PeerMetadata>,
 ) -> Vec<PeerNetworkId> {
     // Group peers and latencies by validator distance, i.e., distance -> [(peer, latency)]
     let mut peers_and_latencies_by_distance = BTreeMap::new();
     for (peer_network_id, peer_metadata) in peers_and_metadata {
         // Get the distance and latency for the peer
-        let distance = get_distance_for_peer(&peer_network_id, &peer_metadata);
-        let latency = get_latency_for_peer(&peer_network_id, &peer_metadata);
-
-        // If the distance is not found, use the maximum distance
-        let distance =
-            distance.unwrap_or(aptos_peer_monitoring_service_types::MAX_DISTANCE_FROM_VALIDATORS);
-
-        // If the latency is not found, use a large latency
-        let latency = latency.unwrap_or(MAX_PING_LATENCY_SECS);
+        let (distance, latency) = get_distance_and_latency_for_peer(peer_network_id, peer_metadata);
 
         // Add the peer and latency to the distance group
         peers_and_latencies_by_distance
             .entry(distance)
             .or_insert_with(Vec::new)
-            .push((peer_network_id, OrderedFloat(latency)));
+            .push((*peer_network_id, OrderedFloat(latency)));
     }
 
     // Sort the peers by distance and latency. Note: BTreeMaps are
@@ -276,12 +455,15 @@ pub fn sort_peers_by_distance_and_latency(
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::consensus_observer::storage::DbBackedObserverStorage;
     use aptos_network::transport::ConnectionMetadata;
     use aptos_peer_monitoring_service_types::{
-        response::NetworkInformationResponse, PeerMonitoringMetadata,
+        response::{NetworkInformationResponse, NodeInformationResponse},
+        PeerMonitoringMetadata,
     };
-    use aptos_storage_interface::Result;
-    use aptos_types::transaction::Version;
+    use aptos_storage_interface::{DbReader, Result};
+    use aptos_types::{transaction::Version, PeerId};
+    use maplit::hashmap;
     use mockall::mock;
 
     // This is a simple mock of the DbReader (it generates a MockDatabaseReader)
@@ -299,8 +481,8 @@ mod test {
         let peer_network_id = PeerNetworkId::random();
         let time_service = TimeService::mock();
         let mut subscription = ConsensusObserverSubscription::new(
-            consensus_observer_config,
-            Arc::new(MockDatabaseReader::new()),
+            consensus_observer_config.clone(),
+            create_observer_storage(MockDatabaseReader::new()),
             peer_network_id,
             time_service.clone(),
         );
@@ -310,9 +492,7 @@ mod test {
         assert_eq!(subscription.last_peer_optimality_check, current_time);
 
         // Verify that the peer is optimal (not enough time has elapsed to check)
-        assert!(subscription
-            .check_subscription_peer_optimality(HashMap::new())
-            .is_ok());
+        assert!(subscription.check_subscription_peer_optimality(&[]).is_ok());
 
         // Elapse some amount of time (but not enough to check optimality)
         let mock_time_service = time_service.into_mock();
@@ -331,7 +511,9 @@ mod test {
             ),
         );
         assert!(subscription
-            .check_subscription_peer_optimality(peers_and_metadata.clone())
+            .check_subscription_peer_optimality(&sort_peers_by_distance_and_latency(
+                &peers_and_metadata
+            ))
             .is_ok());
 
         // Elapse enough time to check optimality
@@ -341,7 +523,9 @@ mod test {
 
         // Verify that the original peer is no longer optimal
         assert!(subscription
-            .check_subscription_peer_optimality(peers_and_metadata.clone())
+            .check_subscription_peer_optimality(&sort_peers_by_distance_and_latency(
+                &peers_and_metadata
+            ))
             .is_err());
 
         // Add the original peer to the list of peers (with optimal metadata)
@@ -355,7 +539,9 @@ mod test {
 
         // Verify that the peer is still optimal
         assert!(subscription
-            .check_subscription_peer_optimality(peers_and_metadata)
+            .check_subscription_peer_optimality(&sort_peers_by_distance_and_latency(
+                &peers_and_metadata
+            ))
             .is_ok());
 
         // Verify the time of the last peer optimality check
@@ -363,6 +549,130 @@ mod test {
         assert_eq!(subscription.last_peer_optimality_check, current_time);
     }
 
+    #[test]
+    fn check_subscription_peer_optimality_with_hold_time() {
+        // Create a new observer subscription (with a hold time of several optimality checks)
+        let consensus_observer_config = ConsensusObserverConfig {
+            min_subscription_hold_time_ms: 300_000,
+            peer_optimality_check_interval_ms: 60_000,
+            enable_subscription_rotation: true,
+            subscription_rotation_interval_ms: 60_000,
+            ..ConsensusObserverConfig::default()
+        };
+        let peer_network_id = PeerNetworkId::random();
+        let time_service = TimeService::mock();
+        let mut subscription = ConsensusObserverSubscription::new(
+            consensus_observer_config.clone(),
+            create_observer_storage(MockDatabaseReader::new()),
+            peer_network_id,
+            time_service.clone(),
+        );
+
+        // Elapse enough time to check optimality (but not enough for the hold time to expire)
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.peer_optimality_check_interval_ms * 2,
+        ));
+
+        // Verify that the peer is still optimal and not due for rotation (it is held)
+        let optimal_peers = vec![PeerNetworkId::random()];
+        assert!(subscription
+            .check_subscription_peer_optimality(&optimal_peers)
+            .is_ok());
+        assert!(!subscription.check_subscription_rotation());
+
+        // Elapse enough time for the hold time to expire
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.min_subscription_hold_time_ms,
+        ));
+
+        // Verify that the peer is no longer optimal and is due for rotation
+        assert!(subscription
+            .check_subscription_peer_optimality(&optimal_peers)
+            .is_err());
+        assert!(subscription.check_subscription_rotation());
+    }
+
+    #[test]
+    fn check_subscription_peer_optimality_with_rotation() {
+        // Create a new observer subscription (with subscription rotation enabled)
+        let consensus_observer_config = ConsensusObserverConfig {
+            enable_subscription_rotation: true,
+            subscription_rotation_candidates: 2,
+            ..ConsensusObserverConfig::default()
+        };
+        let peer_network_id = PeerNetworkId::random();
+        let time_service = TimeService::mock();
+        let mut subscription = ConsensusObserverSubscription::new(
+            consensus_observer_config.clone(),
+            create_observer_storage(MockDatabaseReader::new()),
+            peer_network_id,
+            time_service.clone(),
+        );
+
+        // Elapse enough time to check optimality
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.peer_optimality_check_interval_ms + 1,
+        ));
+
+        // Verify that the peer is optimal (it is in the rotation candidate set)
+        let optimal_peer = PeerNetworkId::random();
+        assert!(subscription
+            .check_subscription_peer_optimality(&[optimal_peer, peer_network_id])
+            .is_ok());
+
+        // Elapse enough time to check optimality again
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.peer_optimality_check_interval_ms + 1,
+        ));
+
+        // Verify that the peer is no longer optimal (it is not in the rotation candidate set)
+        assert!(subscription
+            .check_subscription_peer_optimality(&[
+                optimal_peer,
+                PeerNetworkId::random(),
+                peer_network_id
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn test_check_subscription_rotation() {
+        // Create a new observer subscription
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let time_service = TimeService::mock();
+        let mut subscription = ConsensusObserverSubscription::new(
+            consensus_observer_config.clone(),
+            create_observer_storage(MockDatabaseReader::new()),
+            PeerNetworkId::random(),
+            time_service.clone(),
+        );
+
+        // Verify that the subscription is not due for rotation
+        assert!(!subscription.check_subscription_rotation());
+
+        // Elapse some amount of time (but not enough to rotate)
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.subscription_rotation_interval_ms / 2,
+        ));
+        assert!(!subscription.check_subscription_rotation());
+
+        // Elapse enough time to rotate and verify the subscription is due for rotation
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.subscription_rotation_interval_ms,
+        ));
+        assert!(subscription.check_subscription_rotation());
+
+        // Verify that the rotation check time was updated (so the subscription is no longer due)
+        assert_eq!(
+            subscription.last_subscription_rotation_check,
+            mock_time_service.now()
+        );
+        assert!(!subscription.check_subscription_rotation());
+    }
+
     #[test]
     fn test_check_subscription_timeout() {
         // Create a new observer subscription
@@ -370,8 +680,8 @@ mod test {
         let peer_network_id = PeerNetworkId::random();
         let time_service = TimeService::mock();
         let mut subscription = ConsensusObserverSubscription::new(
-            consensus_observer_config,
-            Arc::new(MockDatabaseReader::new()),
+            consensus_observer_config.clone(),
+            create_observer_storage(MockDatabaseReader::new()),
             peer_network_id,
             time_service.clone(),
         );
@@ -428,8 +738,8 @@ mod test {
         let peer_network_id = PeerNetworkId::random();
         let time_service = TimeService::mock();
         let mut subscription = ConsensusObserverSubscription::new(
-            consensus_observer_config,
-            Arc::new(mock_db_reader),
+            consensus_observer_config.clone(),
+            create_observer_storage(mock_db_reader),
             peer_network_id,
             time_service.clone(),
         );
@@ -478,6 +788,81 @@ mod test {
         assert!(subscription.check_syncing_progress().is_err());
     }
 
+    #[test]
+    fn test_check_subscription_staleness() {
+        // Create a mock DB reader (the synced version never increases)
+        let synced_version = 10;
+        let mut mock_db_reader = MockDatabaseReader::new();
+        mock_db_reader
+            .expect_get_latest_ledger_info_version()
+            .returning(move || Ok(synced_version));
+
+        // Create a new observer subscription
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let peer_network_id = PeerNetworkId::random();
+        let time_service = TimeService::mock();
+        let mut subscription = ConsensusObserverSubscription::new(
+            consensus_observer_config.clone(),
+            create_observer_storage(mock_db_reader),
+            peer_network_id,
+            time_service.clone(),
+        );
+
+        // Create the connected peers (the subscription peer and another peer far ahead of us)
+        let version_lag = consensus_observer_config.min_subscription_staleness_version_lag;
+        let other_peer_network_id = PeerNetworkId::random();
+        let mut connected_peers_and_metadata = hashmap! {
+            peer_network_id => create_peer_metadata_with_synced_version(synced_version),
+            other_peer_network_id => create_peer_metadata_with_synced_version(
+                synced_version + version_lag,
+            ),
+        };
+
+        // Verify that the subscription is not stale (the synced version has just increased)
+        assert!(subscription
+            .check_subscription_staleness(&connected_peers_and_metadata)
+            .is_ok());
+
+        // Elapse some amount of time (not enough for the subscription to be stale)
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.max_subscription_staleness_ms / 2,
+        ));
+        assert!(subscription
+            .check_subscription_staleness(&connected_peers_and_metadata)
+            .is_ok());
+
+        // Elapse enough time for the subscription to be stale
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.max_subscription_staleness_ms,
+        ));
+
+        // Verify that the subscription is stale (the other peer is ahead of us)
+        assert!(matches!(
+            subscription.check_subscription_staleness(&connected_peers_and_metadata),
+            Err(Error::SubscriptionStale(_))
+        ));
+
+        // Verify the subscription is not stale if the other peer is not far enough ahead
+        connected_peers_and_metadata.insert(
+            other_peer_network_id,
+            create_peer_metadata_with_synced_version(synced_version + version_lag - 1),
+        );
+        assert!(subscription
+            .check_subscription_staleness(&connected_peers_and_metadata)
+            .is_ok());
+
+        // Verify the subscription peer's own synced version is ignored
+        connected_peers_and_metadata.remove(&other_peer_network_id);
+        connected_peers_and_metadata.insert(
+            peer_network_id,
+            create_peer_metadata_with_synced_version(synced_version + version_lag),
+        );
+        assert!(subscription
+            .check_subscription_staleness(&connected_peers_and_metadata)
+            .is_ok());
+    }
+
     #[test]
     fn test_verify_message_sender() {
         // Create a new observer subscription
@@ -486,7 +871,7 @@ mod test {
         let time_service = TimeService::mock();
         let mut subscription = ConsensusObserverSubscription::new(
             consensus_observer_config,
-            Arc::new(MockDatabaseReader::new()),
+            create_observer_storage(MockDatabaseReader::new()),
             peer_network_id,
             time_service.clone(),
         );
@@ -520,20 +905,20 @@ mod test {
     fn test_sort_peers_by_distance_and_latency() {
         // Sort an empty list of peers
         let peers_and_metadata = HashMap::new();
-        assert!(sort_peers_by_distance_and_latency(peers_and_metadata).is_empty());
+        assert!(sort_peers_by_distance_and_latency(&peers_and_metadata).is_empty());
 
         // Create a list of peers with empty metadata
         let peers_and_metadata = create_peers_and_metadata(true, true, 10);
 
         // Sort the peers and verify the results
-        let sorted_peers = sort_peers_by_distance_and_latency(peers_and_metadata);
+        let sorted_peers = sort_peers_by_distance_and_latency(&peers_and_metadata);
         assert_eq!(sorted_peers.len(), 10);
 
         // Create a list of peers with valid metadata
         let peers_and_metadata = create_peers_and_metadata(false, false, 10);
 
         // Sort the peers
-        let sorted_peers = sort_peers_by_distance_and_latency(peers_and_metadata.clone());
+        let sorted_peers = sort_peers_by_distance_and_latency(&peers_and_metadata);
 
         // Verify the order of the peers
         verify_increasing_distance_latencies(&peers_and_metadata, &sorted_peers);
@@ -546,7 +931,7 @@ mod test {
         peers_and_metadata.extend(create_peers_and_metadata(true, true, 10));
 
         // Sort the peers
-        let sorted_peers = sort_peers_by_distance_and_latency(peers_and_metadata.clone());
+        let sorted_peers = sort_peers_by_distance_and_latency(&peers_and_metadata);
         assert_eq!(sorted_peers.len(), 40);
 
         // Verify the order of the first 20 peers
@@ -594,6 +979,22 @@ mod test {
         (peer_network_id, peer_metadata)
     }
 
+    /// Creates a new peer metadata (with the given synced version) for testing
+    fn create_peer_metadata_with_synced_version(highest_synced_version: u64) -> PeerMetadata {
+        let connection_metadata = ConnectionMetadata::mock(PeerId::random());
+        let node_information_response = NodeInformationResponse {
+            build_information: BTreeMap::new(),
+            highest_synced_epoch: 0,
+            highest_synced_version,
+            ledger_timestamp_usecs: 0,
+            lowest_available_version: 0,
+            uptime: Duration::from_secs(0),
+        };
+        let peer_monitoring_metadata =
+            PeerMonitoringMetadata::new(None, None, None, Some(node_information_response), None);
+        PeerMetadata::new_for_test(connection_metadata, peer_monitoring_metadata)
+    }
+
     /// Creates a list of peers and metadata for testing
     fn create_peers_and_metadata(
         empty_latency: bool,
@@ -645,4 +1046,11 @@ mod test {
             previous_distance = distance;
         }
     }
+
+    /// Creates an observer storage backed by the given mock DB reader
+    fn create_observer_storage(
+        mock_db_reader: MockDatabaseReader,
+    ) -> Arc<dyn ObserverStorageInterface> {
+        Arc::new(DbBackedObserverStorage::new(Arc::new(mock_db_reader)))
+    }
 }
@@ -0,0 +1,2287 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    epoch_summary::EpochSummaryTracker,
+    error::Error,
+    event_journal::{ObserverEvent, ObserverEventJournal},
+    logging::{LogEntry, LogSchema},
+    metrics,
+    network_client::ConsensusObserverClient,
+    network_message::{
+        BlockPayload, ConsensusObserverMessage, ConsensusObserverRequest,
+        ConsensusObserverResponse, OrderedBlock, PayloadAcknowledgement, SubscriptionOptions,
+    },
+    peer_activity::PeerActivityTracker,
+    peer_overrides::SubscriptionPeerOverrides,
+    peer_reputation::{PeerReputationEvent, PeerReputationTracker},
+    peer_selector::{
+        FallbackRankingPeerSelector, ReputationRankingPeerSelector, SortedPeersCache,
+        SubscriptionPeerSelector,
+    },
+    publisher::ConsensusPublisher,
+    storage::ObserverStorageInterface,
+    subscription::{ConsensusObserverSubscription, SubscriptionHealth},
+    subscription_state::{SubscriptionStateMachine, SubscriptionTransition},
+    task_registry::TaskRegistry,
+};
+use aptos_config::{
+    config::{ConsensusObserverConfig, VerificationFailurePolicy},
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_crypto::HashValue;
+use aptos_logger::{error, info, warn};
+use aptos_network::application::{
+    interface::NetworkClient, metadata::PeerMetadata, storage::PeersAndMetadata,
+};
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::block_info::Round;
+use fail::fail_point;
+use rand::seq::SliceRandom;
+use std::{
+    collections::HashMap,
+    mem,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The subscription manager owns the lifecycle of the observer subscription
+/// (i.e., peer selection, creation, health checks, termination and metrics).
+pub struct SubscriptionManager {
+    // The configuration of the consensus observer
+    consensus_observer_config: ConsensusObserverConfig,
+    // The consensus observer client to send network messages
+    consensus_observer_client:
+        Arc<ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>>,
+
+    // The consensus publisher (used to avoid subscribing to our own subscribers)
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    // The peer selector (used to prioritize peers for subscriptions)
+    peer_selector: Arc<dyn SubscriptionPeerSelector>,
+    // The cache of sorted connected peers (only recomputed when the peers change)
+    sorted_peers_cache: SortedPeersCache,
+    // The peers and metadata version at which the peer metric labels were last released
+    peer_labels_version: Option<u64>,
+    // The tracker for the locally observed peer activity (used for fallback ranking)
+    peer_activity_tracker: PeerActivityTracker,
+    // The tracker for the reputation of each peer (used to demote bad or slow peers)
+    peer_reputation_tracker: PeerReputationTracker,
+    // The operator overrides for subscription peer selection (e.g., pinned peers)
+    peer_overrides: SubscriptionPeerOverrides,
+    // The currently active consensus observer subscription
+    active_observer_subscription: Option<ConsensusObserverSubscription>,
+    // The backup subscriptions (i.e., concurrent subscriptions to other peers
+    // that take over if the active subscription fails).
+    backup_observer_subscriptions: Vec<ConsensusObserverSubscription>,
+    // The state machine for the observer subscription lifecycle
+    subscription_state_machine: SubscriptionStateMachine,
+    // The number of verification failures for the active subscription
+    num_verification_failures: u64,
+    // The peers blocklisted from subscriptions (and their blocklist expiration times)
+    blocklisted_peers: HashMap<PeerNetworkId, Instant>,
+    // The peers blocklisted from subscriptions for the remainder of an epoch (and the epoch)
+    epoch_blocklisted_peers: HashMap<PeerNetworkId, u64>,
+    // The peer to prioritize for the next subscription (e.g., after a restart)
+    resume_subscription_peer: Option<PeerNetworkId>,
+    // Whether a subscription was replaced (i.e., terminated non-gracefully)
+    // since the last check (used to reset the execution pipeline).
+    subscription_replaced: bool,
+    // Whether the syncing progress checks are paused (e.g., while execution is on hold)
+    syncing_progress_checks_paused: bool,
+
+    // A handle to storage (used to read the latest state and check progress)
+    observer_storage: Arc<dyn ObserverStorageInterface>,
+    // The time service (used to check progress)
+    time_service: TimeService,
+
+    // The journal of recent significant observer events
+    event_journal: ObserverEventJournal,
+    // The tracker for the summary statistics of the current epoch
+    epoch_summary_tracker: EpochSummaryTracker,
+
+    // The registry of background tasks (e.g., unsubscribe requests)
+    task_registry: TaskRegistry,
+}
+
+impl SubscriptionManager {
+    pub fn new(
+        consensus_observer_config: ConsensusObserverConfig,
+        consensus_observer_client: Arc<
+            ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>,
+        >,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
+        peer_selector: Arc<dyn SubscriptionPeerSelector>,
+        observer_storage: Arc<dyn ObserverStorageInterface>,
+        time_service: TimeService,
+        event_journal: ObserverEventJournal,
+        epoch_summary_tracker: EpochSummaryTracker,
+        task_registry: TaskRegistry,
+    ) -> Self {
+        // Rank the peers without monitoring metadata using the local activity (if enabled)
+        let peer_activity_tracker = PeerActivityTracker::new(
+            consensus_observer_config.peer_activity_window_ms,
+            time_service.clone(),
+        );
+        let peer_selector: Arc<dyn SubscriptionPeerSelector> =
+            if consensus_observer_config.enable_fallback_peer_ranking {
+                Arc::new(FallbackRankingPeerSelector::new(
+                    peer_selector,
+                    peer_activity_tracker.clone(),
+                ))
+            } else {
+                peer_selector
+            };
+
+        // Demote the peers with bad reputations (if enabled)
+        let peer_reputation_tracker = PeerReputationTracker::new(
+            consensus_observer_config.peer_reputation_window_ms,
+            time_service.clone(),
+        );
+        let peer_selector: Arc<dyn SubscriptionPeerSelector> =
+            if consensus_observer_config.enable_peer_reputation_ranking {
+                Arc::new(ReputationRankingPeerSelector::new(
+                    peer_selector,
+                    peer_reputation_tracker.clone(),
+                ))
+            } else {
+                peer_selector
+            };
+
+        Self {
+            consensus_observer_config,
+            consensus_observer_client,
+            consensus_publisher,
+            peer_selector,
+            sorted_peers_cache: SortedPeersCache::new(),
+            peer_labels_version: None,
+            peer_activity_tracker,
+            peer_reputation_tracker,
+            peer_overrides: SubscriptionPeerOverrides::new(),
+            active_observer_subscription: None,
+            backup_observer_subscriptions: vec![],
+            subscription_state_machine: SubscriptionStateMachine::new(),
+            num_verification_failures: 0,
+            blocklisted_peers: HashMap::new(),
+            epoch_blocklisted_peers: HashMap::new(),
+            resume_subscription_peer: None,
+            subscription_replaced: false,
+            syncing_progress_checks_paused: false,
+            observer_storage,
+            time_service,
+            event_journal,
+            epoch_summary_tracker,
+            task_registry,
+        }
+    }
+
+    /// Checks the health of the active subscription (terminating it if
+    /// required) and creates a new subscription if there is none.
+    pub async fn check_and_manage_subscriptions(&mut self) {
+        // Get the peer ID of the currently active subscription (if any)
+        let active_subscription_peer = self.get_active_subscription_peer();
+
+        // Get the sorted connected peers (the same snapshot is
+        // reused for all checks within this tick).
+        let sorted_connected_peers = self.get_sorted_connected_peers();
+
+        // If the active subscription is about to time out, probe the peer first
+        // (to avoid unnecessary failovers during brief quiet periods on chain).
+        if self
+            .consensus_observer_config
+            .enable_subscription_timeout_probes
+        {
+            self.probe_subscription_before_timeout().await;
+        }
+
+        // Verify that the backup subscriptions are still healthy (before checking
+        // the active subscription, so that only healthy backups can take over).
+        self.check_backup_subscriptions(sorted_connected_peers.as_deref());
+
+        // If we have an active subscription, verify that the subscription
+        // is still healthy. If not, the subscription should be terminated.
+        if let Some(active_subscription_peer) = active_subscription_peer {
+            if let Err(error) = self.check_active_subscription(sorted_connected_peers.as_deref()) {
+                self.terminate_subscription(active_subscription_peer, error);
+            }
+        }
+
+        // If subscription rotation is enabled, periodically rotate the (healthy)
+        // subscription to another candidate peer (to spread the upstream load).
+        if self.consensus_observer_config.enable_subscription_rotation {
+            self.rotate_subscription_if_required(sorted_connected_peers.as_deref())
+                .await;
+        }
+
+        // If we don't have a subscription, we should select a new peer to
+        // subscribe to. If we had a previous subscription, it should be
+        // excluded from the selection process.
+        if self.active_observer_subscription.is_none() {
+            self.create_subscription(active_subscription_peer, sorted_connected_peers.as_deref())
+                .await;
+        }
+
+        // Create backup subscriptions to other peers (if enabled and required)
+        self.create_backup_subscriptions(sorted_connected_peers.as_deref())
+            .await;
+
+        // Release the metric labels of any disconnected peers (this is done last,
+        // so that the metrics of any terminated subscriptions are removed first).
+        self.garbage_collect_peer_labels();
+    }
+
+    /// Releases the metric labels of all peers that are no longer
+    /// connected (only if the connected peers have changed).
+    fn garbage_collect_peer_labels(&mut self) {
+        let peers_and_metadata = self.consensus_observer_client.get_peers_and_metadata();
+        let version = peers_and_metadata.get_peers_and_metadata_version();
+        if self.peer_labels_version == Some(version) {
+            return; // The connected peers have not changed
+        }
+
+        // Release the labels of all disconnected peers
+        if let Ok(connected_peers_and_metadata) =
+            peers_and_metadata.get_connected_peers_and_metadata()
+        {
+            let connected_peers = connected_peers_and_metadata.keys().cloned().collect();
+            metrics::garbage_collect_peer_labels(&connected_peers);
+            self.peer_labels_version = Some(version);
+        }
+    }
+
+    /// Terminates the active subscription (if any) for the given reason, and
+    /// attempts to create a new subscription (excluding the previous peer).
+    pub async fn terminate_and_resubscribe(&mut self, error: Error) {
+        let active_subscription_peer = self.get_active_subscription_peer();
+        self.terminate_active_subscription(error);
+        let sorted_connected_peers = self.get_sorted_connected_peers();
+        self.create_subscription(active_subscription_peer, sorted_connected_peers.as_deref())
+            .await;
+    }
+
+    /// Forces a resubscription (e.g., by an operator that knows the upstream is
+    /// bad before the health checks do). The active subscription (if any) is
+    /// terminated, and a new subscription is immediately created to the given
+    /// peer (which must be connected). If no peer is given, the peer selection
+    /// is rerun (excluding the previous peer). Returns the new subscription peer.
+    pub async fn force_resubscription(
+        &mut self,
+        peer_network_id: Option<PeerNetworkId>,
+    ) -> Result<PeerNetworkId, Error> {
+        let termination_error =
+            Error::SubscriptionOverridden("The operator forced a resubscription!".into());
+        match peer_network_id {
+            Some(peer_network_id) => {
+                // Verify the peer is permitted by the operator overrides (and connected)
+                self.peer_overrides
+                    .check_subscription_peer(&peer_network_id)?;
+                let peer_connected = self
+                    .get_sorted_connected_peers()
+                    .map_or(false, |sorted_peers| {
+                        sorted_peers.contains(&peer_network_id)
+                    });
+                if !peer_connected {
+                    return Err(Error::SubscriptionDisconnected(format!(
+                        "The peer is not connected (or is blocklisted): {}",
+                        peer_network_id
+                    )));
+                }
+
+                // Terminate the active subscription and subscribe to the peer
+                self.terminate_active_subscription(termination_error);
+                self.transition_subscription_state(SubscriptionTransition::SubscriptionRequested);
+                if self.send_subscription_request(&peer_network_id).await {
+                    self.set_active_subscription(peer_network_id);
+                    self.record_subscription_creation(peer_network_id);
+                } else {
+                    self.transition_subscription_state(SubscriptionTransition::SubscriptionFailed);
+                }
+            },
+            None => self.terminate_and_resubscribe(termination_error).await,
+        }
+
+        // Return the new subscription peer
+        self.get_active_subscription_peer().ok_or_else(|| {
+            Error::UnexpectedError(
+                "Failed to create a new subscription! The subscription will be retried.".into(),
+            )
+        })
+    }
+
+    /// Terminates the active subscription (if any) for the given reason.
+    /// Note: a new subscription is only created at the next subscription check.
+    pub fn terminate_active_subscription(&mut self, error: Error) {
+        if let Some(active_subscription_peer) = self.get_active_subscription_peer() {
+            self.active_observer_subscription = None;
+            self.terminate_subscription(active_subscription_peer, error);
+        }
+    }
+
+    /// Terminates the subscription (active or backup) to the given peer for the
+    /// given reason. Note: a new active subscription is only created at the next
+    /// subscription check.
+    pub fn terminate_subscription_to_peer(&mut self, peer_network_id: PeerNetworkId, error: Error) {
+        // Terminate the backup subscription to the peer (if any)
+        if let Some(index) = self
+            .backup_observer_subscriptions
+            .iter()
+            .position(|subscription| subscription.get_peer_network_id() == peer_network_id)
+        {
+            self.backup_observer_subscriptions.remove(index);
+            self.terminate_backup_subscription(peer_network_id, error);
+            return;
+        }
+
+        // Otherwise, terminate the active subscription (if it is to the peer)
+        if self.get_active_subscription_peer() == Some(peer_network_id) {
+            self.terminate_active_subscription(error);
+        }
+    }
+
+    /// Blocklists the given peer from subscriptions (for the configured duration)
+    fn blocklist_peer(&mut self, peer_network_id: PeerNetworkId) {
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Blocklisting peer: {} for {} ms!",
+                peer_network_id, self.consensus_observer_config.peer_blocklist_duration_ms
+            ))
+        );
+
+        // Add the peer to the blocklist
+        let blocklist_duration =
+            Duration::from_millis(self.consensus_observer_config.peer_blocklist_duration_ms);
+        let expiration_time = self.time_service.now() + blocklist_duration;
+        self.blocklisted_peers
+            .insert(peer_network_id, expiration_time);
+
+        // Invalidate the sorted peers (they exclude all blocklisted peers)
+        self.sorted_peers_cache.invalidate();
+    }
+
+    /// Blocklists the given peer from subscriptions for the remainder of the given
+    /// epoch (e.g., because the peer equivocated). The blocklist is lifted once the
+    /// next epoch starts (see `remove_expired_epoch_blocklists`).
+    pub fn blocklist_peer_for_epoch(&mut self, peer_network_id: PeerNetworkId, epoch: u64) {
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Blocklisting peer: {} for the remainder of epoch: {}!",
+                peer_network_id, epoch
+            ))
+        );
+
+        // Add the peer to the epoch blocklist
+        self.epoch_blocklisted_peers.insert(peer_network_id, epoch);
+
+        // Invalidate the sorted peers (they exclude all blocklisted peers)
+        self.sorted_peers_cache.invalidate();
+    }
+
+    /// Checks if the given backup subscription is still healthy (using the given
+    /// sorted connected peers). If not, an error is returned. Note: the syncing
+    /// progress, staleness and optimality are only checked for the active
+    /// subscription (backups only need to be ready to take over).
+    fn check_backup_subscription(
+        &self,
+        backup_subscription: &ConsensusObserverSubscription,
+        sorted_connected_peers: Option<&[PeerNetworkId]>,
+    ) -> Result<(), Error> {
+        // Verify the peer is still permitted by the operator overrides
+        let peer_network_id = backup_subscription.get_peer_network_id();
+        self.peer_overrides
+            .check_subscription_peer(&peer_network_id)?;
+
+        // Verify the peer is still connected
+        let peer_still_connected = sorted_connected_peers.map_or(false, |sorted_peers| {
+            sorted_peers.contains(&peer_network_id)
+        });
+        if !peer_still_connected {
+            return Err(Error::SubscriptionDisconnected(
+                "The peer is no longer connected!".to_string(),
+            ));
+        }
+
+        // Verify the subscription has not timed out
+        backup_subscription.check_subscription_timeout()
+    }
+
+    /// Checks the health of all backup subscriptions (using the given sorted
+    /// connected peers), and terminates any unhealthy backup subscriptions.
+    fn check_backup_subscriptions(&mut self, sorted_connected_peers: Option<&[PeerNetworkId]>) {
+        for backup_subscription in mem::take(&mut self.backup_observer_subscriptions) {
+            match self.check_backup_subscription(&backup_subscription, sorted_connected_peers) {
+                Ok(()) => self.backup_observer_subscriptions.push(backup_subscription),
+                Err(error) => {
+                    self.terminate_backup_subscription(
+                        backup_subscription.get_peer_network_id(),
+                        error,
+                    );
+                },
+            }
+        }
+    }
+
+    /// Checks if the active subscription is still healthy (using the given
+    /// sorted connected peers). If not, an error is returned.
+    fn check_active_subscription(
+        &mut self,
+        sorted_connected_peers: Option<&[PeerNetworkId]>,
+    ) -> Result<(), Error> {
+        let active_observer_subscription = self.active_observer_subscription.take();
+        if let Some(mut active_subscription) = active_observer_subscription {
+            // Verify the peer is still permitted by the operator overrides
+            let peer_network_id = active_subscription.get_peer_network_id();
+            self.peer_overrides
+                .check_subscription_peer(&peer_network_id)?;
+
+            // Check if the peer for the subscription is still connected
+            let peer_still_connected = sorted_connected_peers.map_or(false, |sorted_peers| {
+                sorted_peers.contains(&peer_network_id)
+            });
+
+            // Verify the peer is still connected
+            if !peer_still_connected {
+                return Err(Error::SubscriptionDisconnected(
+                    "The peer is no longer connected!".to_string(),
+                ));
+            }
+
+            // Verify the subscription has not timed out
+            active_subscription.check_subscription_timeout()?;
+
+            // Verify that the DB is continuing to sync and commit new data, and
+            // that the subscription is not stale (i.e., that the peer hasn't silently
+            // stopped sending data while other peers continue to make progress).
+            // Note: these are skipped while the syncing progress checks are paused.
+            if !self.syncing_progress_checks_paused {
+                active_subscription.check_syncing_progress()?;
+
+                let peers_and_metadata = self.consensus_observer_client.get_peers_and_metadata();
+                if let Some(connected_peers_and_metadata) = get_connected_peers_and_metadata(
+                    &peers_and_metadata,
+                    &self.blocklisted_peers,
+                    &self.epoch_blocklisted_peers,
+                ) {
+                    active_subscription
+                        .check_subscription_staleness(&connected_peers_and_metadata)?;
+                }
+            }
+
+            // Verify that the subscription peer is optimal
+            if let Some(sorted_peers) = sorted_connected_peers {
+                active_subscription.check_subscription_peer_optimality(sorted_peers)?;
+            }
+
+            // The subscription seems healthy, we can keep it
+            self.active_observer_subscription = Some(active_subscription);
+        }
+
+        Ok(())
+    }
+
+    /// Creates backup subscriptions to the highest ranked peers that we're not
+    /// already subscribed to, until the maximum number of concurrent subscriptions
+    /// is reached. Backups are only created once there is an active subscription.
+    async fn create_backup_subscriptions(
+        &mut self,
+        sorted_connected_peers: Option<&[PeerNetworkId]>,
+    ) {
+        // Check if any backup subscriptions are required
+        let max_num_backup_subscriptions =
+            (self.consensus_observer_config.max_concurrent_subscriptions as usize)
+                .saturating_sub(1);
+        let active_subscription_peer = match self.get_active_subscription_peer() {
+            Some(active_subscription_peer)
+                if self.backup_observer_subscriptions.len() < max_num_backup_subscriptions =>
+            {
+                active_subscription_peer
+            },
+            _ => return, // There is no active subscription, or enough backups
+        };
+
+        // Get the candidate peers (excluding the peers we're already subscribed to)
+        let sorted_connected_peers = match sorted_connected_peers {
+            Some(sorted_connected_peers) => sorted_connected_peers,
+            None => return, // We failed to get the connected peers
+        };
+        let subscription_peers = self.get_subscription_peers();
+        let mut sorted_peers = self
+            .sort_peers_for_subscription(Some(active_subscription_peer), sorted_connected_peers);
+        sorted_peers.retain(|peer_network_id| !subscription_peers.contains(peer_network_id));
+
+        // Subscribe to the candidate peers (in order) until we have enough backups
+        for selected_peer in sorted_peers {
+            if self.backup_observer_subscriptions.len() >= max_num_backup_subscriptions {
+                return; // We have enough backup subscriptions
+            }
+            if self.send_subscription_request(&selected_peer).await {
+                self.backup_observer_subscriptions
+                    .push(ConsensusObserverSubscription::new(
+                        self.consensus_observer_config.clone(),
+                        self.observer_storage.clone(),
+                        selected_peer,
+                        self.time_service.clone(),
+                    ));
+                self.update_backup_subscription_creation_metrics(selected_peer);
+                self.event_journal
+                    .record_event(ObserverEvent::BackupSubscriptionCreated {
+                        peer_network_id: selected_peer,
+                    });
+            }
+        }
+    }
+
+    /// Creates a new observer subscription (excluding the previous subscription
+    /// peer, if provided), and updates the metrics and event journal on success.
+    /// If there is a backup subscription, it takes over instead (as it is already
+    /// receiving the same data), and no new peer is subscribed to.
+    async fn create_subscription(
+        &mut self,
+        previous_subscription_peer: Option<PeerNetworkId>,
+        sorted_connected_peers: Option<&[PeerNetworkId]>,
+    ) {
+        // Promote a backup subscription (or create a new observer subscription)
+        self.transition_subscription_state(SubscriptionTransition::SubscriptionRequested);
+        if !self.promote_backup_subscription() {
+            self.create_new_observer_subscription(
+                previous_subscription_peer,
+                sorted_connected_peers,
+            )
+            .await;
+        }
+
+        // If we successfully created a new subscription, update the
+        // subscription creation metrics and record the event.
+        if let Some(peer_network_id) = self.get_active_subscription_peer() {
+            self.record_subscription_creation(peer_network_id);
+        } else {
+            self.transition_subscription_state(SubscriptionTransition::SubscriptionFailed);
+        }
+    }
+
+    /// Creates a new observer subscription by sending subscription requests to
+    /// appropriate peers and waiting for a successful response. If `previous_subscription_peer`
+    /// is provided, it will be excluded from the selection process.
+    async fn create_new_observer_subscription(
+        &mut self,
+        previous_subscription_peer: Option<PeerNetworkId>,
+        sorted_connected_peers: Option<&[PeerNetworkId]>,
+    ) {
+        fail_point!(
+            "consensus_observer::create_new_observer_subscription",
+            |_| {}
+        );
+
+        // Get a set of sorted peers to service our subscription request
+        let sorted_peers = match sorted_connected_peers {
+            Some(sorted_connected_peers) => {
+                self.sort_peers_for_subscription(previous_subscription_peer, sorted_connected_peers)
+            },
+            None => {
+                error!(LogSchema::new(LogEntry::ConsensusObserver)
+                    .message("Failed to sort peers for subscription requests!"));
+                return;
+            },
+        };
+
+        // Verify that we have potential peers
+        if sorted_peers.is_empty() {
+            warn!(LogSchema::new(LogEntry::ConsensusObserver)
+                .message("There are no peers to subscribe to!"));
+            return;
+        }
+
+        // Go through the sorted peers and attempt to subscribe to a single peer.
+        // The first peer that responds successfully will be the selected peer.
+        for selected_peer in &sorted_peers {
+            if self.send_subscription_request(selected_peer).await {
+                self.set_active_subscription(*selected_peer);
+                return; // Return after successfully subscribing
+            }
+        }
+
+        // We failed to connect to any peers
+        warn!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Failed to subscribe to any peers! Num peers attempted: {:?}",
+                sorted_peers.len()
+            ))
+        );
+    }
+
+    /// Creates the subscription request to send to peers. If dictionary
+    /// compression or delta encoding is enabled, the request asks the peer
+    /// for those payload encodings. If subscription replay is enabled, the
+    /// request also carries our latest block. Otherwise, a simple subscription
+    /// request is sent.
+    fn create_subscription_request(&self) -> ConsensusObserverRequest {
+        let subscription_options = SubscriptionOptions {
+            dictionary_compression: self
+                .consensus_observer_config
+                .enable_payload_dictionary_compression,
+            delta_encoding: self.consensus_observer_config.enable_payload_delta_encoding,
+            starting_block: self.get_subscription_starting_block(),
+        };
+        if subscription_options == SubscriptionOptions::default() {
+            ConsensusObserverRequest::Subscribe
+        } else {
+            ConsensusObserverRequest::SubscribeWithOptions(subscription_options)
+        }
+    }
+
+    /// Returns the peer network id of the active subscription (if any)
+    pub fn get_active_subscription_peer(&self) -> Option<PeerNetworkId> {
+        self.active_observer_subscription
+            .as_ref()
+            .map(|subscription| subscription.get_peer_network_id())
+    }
+
+    /// Pauses (or resumes) the syncing progress and staleness checks of the
+    /// subscriptions, e.g., while an operator holds execution (and the DB is
+    /// not expected to make progress). When the checks are resumed, the progress
+    /// timers of all subscriptions are reset (to avoid terminating them).
+    pub fn set_syncing_progress_checks_paused(&mut self, paused: bool) {
+        self.syncing_progress_checks_paused = paused;
+        if !paused {
+            for subscription in self
+                .active_observer_subscription
+                .iter_mut()
+                .chain(self.backup_observer_subscriptions.iter_mut())
+            {
+                subscription.reset_syncing_progress();
+            }
+        }
+    }
+
+    /// Returns the health of the active subscription (if any)
+    pub fn get_active_subscription_health(&self) -> Option<SubscriptionHealth> {
+        self.active_observer_subscription
+            .as_ref()
+            .map(|subscription| subscription.get_subscription_health())
+    }
+
+    /// Returns the peers of the active and backup subscriptions (in that order)
+    pub fn get_subscription_peers(&self) -> Vec<PeerNetworkId> {
+        self.get_active_subscription_peer()
+            .into_iter()
+            .chain(
+                self.backup_observer_subscriptions
+                    .iter()
+                    .map(|subscription| subscription.get_peer_network_id()),
+            )
+            .collect()
+    }
+
+    /// Returns the operator overrides for subscription peer selection
+    pub fn get_peer_overrides(&self) -> SubscriptionPeerOverrides {
+        self.peer_overrides.clone()
+    }
+
+    /// Returns the latest block (i.e., epoch and round) committed by the observer,
+    /// if subscription replay is enabled. This allows the publisher to replay the
+    /// blocks after the block (instead of the observer waiting for state sync).
+    fn get_subscription_starting_block(&self) -> Option<(u64, Round)> {
+        if !self.consensus_observer_config.enable_subscription_replay {
+            return None;
+        }
+
+        match self.observer_storage.get_latest_ledger_info() {
+            Ok(latest_ledger_info) => {
+                let commit_info = latest_ledger_info.commit_info();
+                Some((commit_info.epoch(), commit_info.round()))
+            },
+            Err(error) => {
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to get the latest ledger info for the subscription replay! \
+                        Error: {:?}",
+                        error
+                    ))
+                );
+                None
+            },
+        }
+    }
+
+    /// Handles a verification failure for a message sent by the given peer. Depending
+    /// on the verification failure policy, this may terminate the active subscription
+    /// (and blocklist the peer) once there have been too many verification failures.
+    pub fn handle_verification_failure(&mut self, peer_network_id: PeerNetworkId, error: &Error) {
+        // If the policy is to only drop invalid messages, there's nothing to do
+        let verification_failure_policy =
+            self.consensus_observer_config.verification_failure_policy;
+        if verification_failure_policy == VerificationFailurePolicy::DropOnly {
+            return;
+        }
+
+        // Backup subscriptions are redundant, so they are terminated on the first failure
+        if let Some(index) = self
+            .backup_observer_subscriptions
+            .iter()
+            .position(|subscription| subscription.get_peer_network_id() == peer_network_id)
+        {
+            self.backup_observer_subscriptions.remove(index);
+            self.terminate_backup_subscription(
+                peer_network_id,
+                Error::SubscriptionVerificationFailed(format!(
+                    "Verification failure for a backup subscription! Error: {:?}",
+                    error
+                )),
+            );
+            if verification_failure_policy == VerificationFailurePolicy::TerminateAndBlocklist {
+                self.blocklist_peer(peer_network_id);
+            }
+            return;
+        }
+
+        // Only track the verification failures of the active subscription peer
+        if self.get_active_subscription_peer() != Some(peer_network_id) {
+            return;
+        }
+
+        // Check if there have been too many verification failures
+        self.num_verification_failures += 1;
+        if self.num_verification_failures < self.consensus_observer_config.max_verification_failures
+        {
+            return;
+        }
+
+        // Terminate the subscription
+        let num_verification_failures = self.num_verification_failures;
+        self.active_observer_subscription = None;
+        self.terminate_subscription(
+            peer_network_id,
+            Error::SubscriptionVerificationFailed(format!(
+                "Too many verification failures: {}! Last error: {:?}",
+                num_verification_failures, error
+            )),
+        );
+
+        // Blocklist the peer (if required by the policy)
+        if verification_failure_policy == VerificationFailurePolicy::TerminateAndBlocklist {
+            self.blocklist_peer(peer_network_id);
+        }
+    }
+
+    /// Returns true iff the given peer is currently blocklisted from subscriptions
+    pub fn is_peer_blocklisted(&self, peer_network_id: &PeerNetworkId) -> bool {
+        self.epoch_blocklisted_peers.contains_key(peer_network_id)
+            || self
+                .blocklisted_peers
+                .get(peer_network_id)
+                .map_or(false, |expiration_time| {
+                    *expiration_time > self.time_service.now()
+                })
+    }
+
+    /// Returns the connected peers (excluding all blocklisted peers), sorted by
+    /// the peer selector. The sorted peers are cached, and are only recomputed
+    /// when the peers and metadata (or the blocklist) change. The operator
+    /// overrides are applied to the cached peers (as they can change at any
+    /// time). If an error occurred, it is logged and None is returned.
+    fn get_sorted_connected_peers(&mut self) -> Option<Arc<[PeerNetworkId]>> {
+        // Garbage collect the expired blocklist entries (and invalidate the cache if required)
+        let time_now = self.time_service.now();
+        let num_blocklisted_peers = self.blocklisted_peers.len();
+        self.blocklisted_peers
+            .retain(|_, expiration_time| *expiration_time > time_now);
+        if self.blocklisted_peers.len() != num_blocklisted_peers {
+            self.sorted_peers_cache.invalidate();
+        }
+
+        // If a peer has new fallback ranking inputs, the cached peers must be re-sorted
+        if self.peer_activity_tracker.take_ranking_inputs_changed() {
+            self.sorted_peers_cache.invalidate();
+        }
+
+        // If a peer's reputation score has changed (i.e., an event was recorded
+        // or expired), the cached peers must also be re-sorted.
+        self.peer_reputation_tracker.garbage_collect();
+        if self.peer_reputation_tracker.take_ranking_inputs_changed() {
+            self.sorted_peers_cache.invalidate();
+        }
+
+        // Get the sorted peers (the version must be read before the peers and metadata).
+        // The activity of disconnected peers is garbage collected whenever the peers change.
+        let peers_and_metadata = self.consensus_observer_client.get_peers_and_metadata();
+        let version = peers_and_metadata.get_peers_and_metadata_version();
+        let blocklisted_peers = &self.blocklisted_peers;
+        let epoch_blocklisted_peers = &self.epoch_blocklisted_peers;
+        let peer_activity_tracker = &self.peer_activity_tracker;
+        let sorted_peers = self.sorted_peers_cache.get_sorted_peers(
+            version,
+            || {
+                let connected_peers_and_metadata = get_connected_peers_and_metadata(
+                    &peers_and_metadata,
+                    blocklisted_peers,
+                    epoch_blocklisted_peers,
+                )?;
+                let connected_peers: Vec<_> =
+                    connected_peers_and_metadata.keys().cloned().collect();
+                peer_activity_tracker.garbage_collect(&connected_peers);
+                Some(connected_peers_and_metadata)
+            },
+            self.peer_selector.as_ref(),
+        )?;
+
+        // Apply the operator overrides to the sorted peers
+        let sorted_peers = self.peer_overrides.apply_to_sorted_peers(sorted_peers);
+
+        // Move the preferred subscription peers to the front (so that the
+        // peer optimality checks also consider the preferred peers optimal).
+        if self
+            .consensus_observer_config
+            .preferred_subscription_peers
+            .is_empty()
+        {
+            return Some(sorted_peers);
+        }
+        let mut sorted_peers = sorted_peers.to_vec();
+        self.move_preferred_peers_to_front(&mut sorted_peers);
+        Some(sorted_peers.into())
+    }
+
+    /// Moves the preferred subscription peers (as specified by the config) to
+    /// the front of the given sorted peers, in the configured order. Preferred
+    /// peers that are not in the sorted peers (e.g., disconnected) are ignored.
+    fn move_preferred_peers_to_front(&self, sorted_peers: &mut Vec<PeerNetworkId>) {
+        for preferred_peer in self
+            .consensus_observer_config
+            .preferred_subscription_peers
+            .iter()
+            .rev()
+        {
+            if let Some(index) = sorted_peers
+                .iter()
+                .position(|peer_network_id| peer_network_id == preferred_peer)
+            {
+                let preferred_peer = sorted_peers.remove(index);
+                sorted_peers.insert(0, preferred_peer);
+            }
+        }
+    }
+
+    /// Produces a list of sorted peers to service our subscription request (from
+    /// the given sorted connected peers, which exclude all blocklisted peers).
+    /// Note: if `previous_subscription_peer` is provided, it will be excluded
+    /// from the selection process. Likewise, all peers currently subscribed
+    /// to us will be excluded from the selection process. The preferred
+    /// subscription peers (if they are still candidates) are always tried first.
+    fn sort_peers_for_subscription(
+        &mut self,
+        previous_subscription_peer: Option<PeerNetworkId>,
+        sorted_connected_peers: &[PeerNetworkId],
+    ) -> Vec<PeerNetworkId> {
+        let mut sorted_peers = sorted_connected_peers.to_vec();
+
+        // Identify the peers that are currently subscribed to us
+        let active_subscribers = self
+            .consensus_publisher
+            .as_ref()
+            .map(|consensus_publisher| consensus_publisher.get_active_subscribers())
+            .unwrap_or_default();
+
+        // Remove the previous subscription peer and any peers that are currently subscribed to us
+        sorted_peers.retain(|peer_network_id| {
+            Some(*peer_network_id) != previous_subscription_peer
+                && !active_subscribers.contains(peer_network_id)
+        });
+
+        // Move the resume subscription peer to the front (if it is still a candidate)
+        if let Some(resume_subscription_peer) = self.resume_subscription_peer.take() {
+            if let Some(index) = sorted_peers
+                .iter()
+                .position(|peer_network_id| *peer_network_id == resume_subscription_peer)
+            {
+                let resume_subscription_peer = sorted_peers.remove(index);
+                sorted_peers.insert(0, resume_subscription_peer);
+            }
+        }
+
+        // Move the preferred subscription peers to the front (these are always tried first)
+        self.move_preferred_peers_to_front(&mut sorted_peers);
+
+        sorted_peers
+    }
+
+    /// Probes the active subscription peer for its latest commit if the subscription
+    /// is about to time out. If the peer responds, and has no commits beyond our
+    /// latest commit (i.e., the chain is quiet), the subscription timeout is reset.
+    /// If the peer has newer commits that it isn't sending, the subscription is
+    /// terminated as stale. Otherwise, the subscription is left to time out.
+    async fn probe_subscription_before_timeout(&mut self) {
+        // Only probe the active subscription if it is about to time out
+        let peer_network_id = match &self.active_observer_subscription {
+            Some(active_subscription)
+                if active_subscription.check_subscription_timeout().is_err() =>
+            {
+                active_subscription.get_peer_network_id()
+            },
+            _ => return,
+        };
+
+        // Send the probe to the peer and wait for the response
+        let request_start_time = self.time_service.now();
+        let response = self
+            .consensus_observer_client
+            .send_rpc_request_to_peer(
+                &peer_network_id,
+                ConsensusObserverRequest::GetLatestCommit,
+                self.consensus_observer_config.network_request_timeout_ms,
+            )
+            .await;
+        let peer_commit_info = match response {
+            Ok(ConsensusObserverResponse::LatestCommit(peer_commit_info)) => {
+                self.record_request_rtt(&peer_network_id, request_start_time);
+                peer_commit_info
+            },
+            Ok(response) => {
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Got unexpected probe response type: {:?}",
+                        response.get_label()
+                    ))
+                );
+                metrics::increment_subscription_probes(metrics::PROBE_FAILED_LABEL);
+                return;
+            },
+            Err(error) => {
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to probe the subscription peer: {}! Error: {:?}",
+                        peer_network_id, error
+                    ))
+                );
+                metrics::increment_subscription_probes(metrics::PROBE_FAILED_LABEL);
+                return;
+            },
+        };
+
+        // Get our latest commit (to compare against the commit of the peer)
+        let latest_commit_info = match self.observer_storage.get_latest_ledger_info() {
+            Ok(latest_ledger_info) => latest_ledger_info.commit_info().clone(),
+            Err(error) => {
+                error!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to get the latest ledger info for the probe! Error: {:?}",
+                        error
+                    ))
+                );
+                return;
+            },
+        };
+
+        // If the peer has newer commits that it isn't sending, the subscription is stale
+        if let Some(peer_commit_info) = peer_commit_info {
+            if (peer_commit_info.epoch(), peer_commit_info.round())
+                > (latest_commit_info.epoch(), latest_commit_info.round())
+            {
+                metrics::increment_subscription_probes(metrics::PROBE_STALE_LABEL);
+                self.terminate_active_subscription(Error::SubscriptionStale(format!(
+                    "The probe of peer: {} returned a newer commit: {}, but no messages \
+                    were received! Latest commit: {}",
+                    peer_network_id, peer_commit_info, latest_commit_info
+                )));
+                return;
+            }
+        }
+
+        // Otherwise, the peer is alive and the chain is quiet (reset the subscription timeout)
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "The probe of peer: {} succeeded (the chain is quiet). Retaining the subscription!",
+                peer_network_id
+            ))
+        );
+        metrics::increment_subscription_probes(metrics::PROBE_HEALTHY_LABEL);
+        if let Some(active_subscription) = self.active_observer_subscription.as_mut() {
+            active_subscription.reset_subscription_timeout();
+        }
+    }
+
+    /// Promotes the oldest backup subscription (if any) to the active subscription
+    /// (e.g., after the active subscription failed). Returns true iff a backup
+    /// subscription was promoted.
+    fn promote_backup_subscription(&mut self) -> bool {
+        if self.backup_observer_subscriptions.is_empty() {
+            return false; // There are no backup subscriptions
+        }
+
+        // Promote the backup subscription (and reset the verification failures)
+        let backup_subscription = self.backup_observer_subscriptions.remove(0);
+        let peer_network_id = backup_subscription.get_peer_network_id();
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Failing over to the backup subscription to peer: {}!",
+                peer_network_id
+            ))
+        );
+        self.active_observer_subscription = Some(backup_subscription);
+        self.num_verification_failures = 0;
+        metrics::increment_subscription_failovers(&peer_network_id.network_id());
+
+        true
+    }
+
+    /// Records the round-trip time of a successful request to the given peer
+    /// (i.e., the time elapsed since the given request start time).
+    fn record_request_rtt(&self, peer_network_id: &PeerNetworkId, request_start_time: Instant) {
+        let request_rtt = self
+            .time_service
+            .now()
+            .saturating_duration_since(request_start_time);
+        self.peer_activity_tracker
+            .record_request_rtt(peer_network_id, request_rtt);
+    }
+
+    /// Removes the epoch blocklists of all epochs before the given (current) epoch
+    pub fn remove_expired_epoch_blocklists(&mut self, current_epoch: u64) {
+        let num_epoch_blocklisted_peers = self.epoch_blocklisted_peers.len();
+        self.epoch_blocklisted_peers
+            .retain(|_, blocklist_epoch| *blocklist_epoch >= current_epoch);
+        if self.epoch_blocklisted_peers.len() != num_epoch_blocklisted_peers {
+            self.sorted_peers_cache.invalidate();
+        }
+    }
+
+    /// Records the given reputation event for the peer (e.g., an invalid payload).
+    /// The reputation is used to demote the peer when selecting subscription peers.
+    pub fn record_peer_reputation_event(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        reputation_event: PeerReputationEvent,
+    ) {
+        self.peer_reputation_tracker
+            .record_event(peer_network_id, reputation_event);
+    }
+
+    /// Records a reputation event for the given peer if the subscription was
+    /// terminated because the peer served slow or excessive data (i.e., timeouts,
+    /// stalls and rate limit violations).
+    fn record_termination_reputation_event(&self, peer_network_id: &PeerNetworkId, error: &Error) {
+        let reputation_event = match error {
+            Error::SubscriptionRateLimited(_) => PeerReputationEvent::RateLimited,
+            Error::SubscriptionTimeout(_) => PeerReputationEvent::Timeout,
+            Error::SubscriptionProgressStopped(_) | Error::SubscriptionStale(_) => {
+                PeerReputationEvent::Stall
+            },
+            _ => return, // The termination doesn't affect the peer's reputation
+        };
+        self.record_peer_reputation_event(peer_network_id, reputation_event);
+    }
+
+    /// Records the creation of a new subscription to the given peer (i.e.,
+    /// updates the subscription state, the creation metrics and the journal).
+    fn record_subscription_creation(&mut self, peer_network_id: PeerNetworkId) {
+        self.transition_subscription_state(SubscriptionTransition::SubscriptionCreated(
+            peer_network_id,
+        ));
+        self.update_subscription_creation_metrics(peer_network_id);
+        self.event_journal
+            .record_event(ObserverEvent::SubscriptionCreated { peer_network_id });
+        self.epoch_summary_tracker.record_subscription_switch();
+    }
+
+    /// Requests the payload of the given block from the given peer and waits for
+    /// the response. Returns None if the peer doesn't hold the payload. Note: the
+    /// payload is unverified.
+    pub async fn request_block_payload(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        block_id: HashValue,
+    ) -> Result<Option<BlockPayload>, Error> {
+        let request_start_time = self.time_service.now();
+        let response = self
+            .consensus_observer_client
+            .send_rpc_request_to_peer(
+                peer_network_id,
+                ConsensusObserverRequest::GetBlockPayload(block_id),
+                self.consensus_observer_config.network_request_timeout_ms,
+            )
+            .await?;
+
+        // Process the response
+        match response {
+            ConsensusObserverResponse::BlockPayload(block_payload) => {
+                self.record_request_rtt(peer_network_id, request_start_time);
+                Ok(block_payload)
+            },
+            response => Err(Error::UnexpectedError(format!(
+                "Got unexpected response type: {:?}",
+                response.get_label()
+            ))),
+        }
+    }
+
+    /// Requests the ordered blocks in the given round range (inclusive) from the
+    /// given peer and waits for the response. Note: the blocks are unverified.
+    pub async fn request_ordered_blocks(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        start_round: Round,
+        end_round: Round,
+    ) -> Result<Vec<OrderedBlock>, Error> {
+        let request_start_time = self.time_service.now();
+        let response = self
+            .consensus_observer_client
+            .send_rpc_request_to_peer(
+                peer_network_id,
+                ConsensusObserverRequest::GetOrderedBlocks {
+                    start_round,
+                    end_round,
+                },
+                self.consensus_observer_config.network_request_timeout_ms,
+            )
+            .await?;
+
+        // Process the response
+        match response {
+            ConsensusObserverResponse::OrderedBlocks(ordered_blocks) => {
+                self.record_request_rtt(peer_network_id, request_start_time);
+                Ok(ordered_blocks)
+            },
+            response => Err(Error::UnexpectedError(format!(
+                "Got unexpected response type: {:?}",
+                response.get_label()
+            ))),
+        }
+    }
+
+    /// Rotates the active subscription to another peer in the rotation candidate
+    /// set (i.e., the top ranked peers), if the rotation interval has elapsed. The
+    /// handoff is graceful: the new peer is subscribed to before the old peer is
+    /// unsubscribed from. If the handoff fails, the old subscription is retained.
+    async fn rotate_subscription_if_required(
+        &mut self,
+        sorted_connected_peers: Option<&[PeerNetworkId]>,
+    ) {
+        // Check if the active subscription is due for rotation
+        let active_subscription_peer = match self.active_observer_subscription.as_mut() {
+            Some(active_subscription) if active_subscription.check_subscription_rotation() => {
+                active_subscription.get_peer_network_id()
+            },
+            _ => return, // There is no subscription to rotate (yet)
+        };
+
+        // Identify the rotation candidates (excluding the active subscription peer)
+        let sorted_connected_peers = match sorted_connected_peers {
+            Some(sorted_connected_peers) => sorted_connected_peers,
+            None => return, // We failed to get the connected peers
+        };
+        let num_rotation_candidates = (self
+            .consensus_observer_config
+            .subscription_rotation_candidates as usize)
+            .min(sorted_connected_peers.len());
+        let rotation_candidates = self.sort_peers_for_subscription(
+            Some(active_subscription_peer),
+            &sorted_connected_peers[..num_rotation_candidates],
+        );
+
+        // Select a random candidate (to spread the load evenly across the candidates)
+        let new_subscription_peer = match rotation_candidates.choose(&mut rand::thread_rng()) {
+            Some(new_subscription_peer) => *new_subscription_peer,
+            None => {
+                info!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "There are no candidates to rotate the subscription to! Retaining \
+                        the subscription to peer: {}",
+                        active_subscription_peer
+                    ))
+                );
+                return;
+            },
+        };
+
+        // Subscribe to the new peer (before unsubscribing from the old peer)
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Rotating the subscription from peer: {} to peer: {}",
+                active_subscription_peer, new_subscription_peer
+            ))
+        );
+        if !self.send_subscription_request(&new_subscription_peer).await {
+            return; // The handoff failed, so we retain the old subscription
+        }
+
+        // Terminate the old subscription and activate the new one
+        self.terminate_active_subscription(Error::SubscriptionRotated(format!(
+            "The subscription was rotated to peer: {}",
+            new_subscription_peer
+        )));
+        self.transition_subscription_state(SubscriptionTransition::SubscriptionRequested);
+        self.set_active_subscription(new_subscription_peer);
+        self.record_subscription_creation(new_subscription_peer);
+    }
+
+    /// Sends the given payload acknowledgement to the peer of the active
+    /// subscription (if any). Note: we execute this asynchronously, as we
+    /// don't need to wait for the response (lost acknowledgements only mean
+    /// that the publisher sends the full transactions).
+    pub fn send_payload_acknowledgement(&self, payload_acknowledgement: PayloadAcknowledgement) {
+        // Get the peer of the active subscription
+        let peer_network_id = match self.get_active_subscription_peer() {
+            Some(peer_network_id) => peer_network_id,
+            None => return, // There is no active subscription
+        };
+
+        // Send the acknowledgement to the peer
+        let consensus_observer_client = self.consensus_observer_client.clone();
+        let network_request_timeout_ms = self.consensus_observer_config.network_request_timeout_ms;
+        self.task_registry
+            .spawn_task("send_payload_acknowledgement", async move {
+                let acknowledgement_request =
+                    ConsensusObserverRequest::AcknowledgePayloads(payload_acknowledgement);
+                let response = consensus_observer_client
+                    .send_rpc_request_to_peer(
+                        &peer_network_id,
+                        acknowledgement_request,
+                        network_request_timeout_ms,
+                    )
+                    .await;
+                match response {
+                    Ok(ConsensusObserverResponse::AcknowledgePayloadsAck) => {},
+                    Ok(response) => {
+                        warn!(
+                            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                "Got unexpected response type for payload acknowledgement: {:?}",
+                                response.get_label()
+                            ))
+                        );
+                    },
+                    Err(error) => {
+                        warn!(
+                            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                "Failed to send payload acknowledgement to peer: {}! Error: {:?}",
+                                peer_network_id, error
+                            ))
+                        );
+                    },
+                }
+            });
+    }
+
+    /// Sends a subscription request to the given peer and waits for the response.
+    /// Returns true iff the peer acknowledged the subscription. Note: it is fine
+    /// to block here because we assume only a few concurrent subscriptions.
+    async fn send_subscription_request(&self, selected_peer: &PeerNetworkId) -> bool {
+        fail_point!("consensus_observer::send_subscription_request", |_| false);
+
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Attempting to subscribe to peer: {}!",
+                selected_peer
+            ))
+        );
+
+        // Send a subscription request to the peer and wait for the response
+        let subscription_request = self.create_subscription_request();
+        let request_start_time = self.time_service.now();
+        let response = self
+            .consensus_observer_client
+            .send_rpc_request_to_peer(
+                selected_peer,
+                subscription_request,
+                self.consensus_observer_config.network_request_timeout_ms,
+            )
+            .await;
+
+        // Process the response
+        match response {
+            Ok(ConsensusObserverResponse::SubscribeAck) => {
+                self.record_request_rtt(selected_peer, request_start_time);
+                info!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Successfully subscribed to peer: {}!",
+                        selected_peer
+                    ))
+                );
+                true
+            },
+            Ok(response) => {
+                // We received an invalid response
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Got unexpected response type: {:?}",
+                        response.get_label()
+                    ))
+                );
+                false
+            },
+            Err(error) => {
+                // We encountered an error while sending the request
+                error!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to send subscription request to peer: {}! Error: {:?}",
+                        selected_peer, error
+                    ))
+                );
+                false
+            },
+        }
+    }
+
+    /// Sets the active subscription to the given peer (and resets the verification failures)
+    fn set_active_subscription(&mut self, peer_network_id: PeerNetworkId) {
+        let subscription = ConsensusObserverSubscription::new(
+            self.consensus_observer_config.clone(),
+            self.observer_storage.clone(),
+            peer_network_id,
+            self.time_service.clone(),
+        );
+        self.active_observer_subscription = Some(subscription);
+        self.num_verification_failures = 0;
+
+        // Remove the peer from the backup subscriptions (e.g., after a rotation)
+        self.backup_observer_subscriptions
+            .retain(|subscription| subscription.get_peer_network_id() != peer_network_id);
+    }
+
+    /// Sets the peer to prioritize for the next subscription attempt (e.g., the
+    /// subscription peer before a restart). The peer is only prioritized once.
+    pub fn set_resume_subscription_peer(&mut self, peer_network_id: PeerNetworkId) {
+        self.resume_subscription_peer = Some(peer_network_id);
+    }
+
+    /// Returns true iff a subscription was replaced (i.e., terminated
+    /// non-gracefully) since the last call, and resets the flag. Note:
+    /// subscription rotations are graceful (the new peer is subscribed
+    /// before the old peer is unsubscribed), so they are excluded.
+    pub fn take_subscription_replaced(&mut self) -> bool {
+        mem::take(&mut self.subscription_replaced)
+    }
+
+    /// Terminates the subscription to the given peer (for the specified reason)
+    fn terminate_subscription(&mut self, subscription_peer: PeerNetworkId, error: Error) {
+        // Log the subscription termination
+        warn!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Terminating subscription to peer: {:?}! Error: {:?}",
+                subscription_peer, error
+            ))
+        );
+
+        // Unsubscribe from the peer (and reset the verification failures)
+        self.transition_subscription_state(SubscriptionTransition::TerminationStarted);
+        self.num_verification_failures = 0;
+        self.unsubscribe_from_peer(subscription_peer);
+
+        // Update the peer's reputation (if the peer served slow or excessive data)
+        self.record_termination_reputation_event(&subscription_peer, &error);
+
+        // Mark the subscription as replaced (unless it was gracefully rotated, or a
+        // backup subscription will take over, as the backup receives the same data).
+        if !matches!(error, Error::SubscriptionRotated(_))
+            && self.backup_observer_subscriptions.is_empty()
+        {
+            self.subscription_replaced = true;
+        }
+
+        // Record the subscription termination in the event journal
+        self.event_journal
+            .record_event(ObserverEvent::SubscriptionTerminated {
+                peer_network_id: subscription_peer,
+                reason: error.to_string(),
+            });
+
+        // Update the subscription termination metrics
+        self.update_subscription_termination_metrics(subscription_peer, error);
+        self.transition_subscription_state(SubscriptionTransition::TerminationCompleted);
+    }
+
+    /// Terminates the backup subscription to the given peer (for the specified
+    /// reason). Note: the subscription must already be removed from the backups.
+    fn terminate_backup_subscription(&mut self, subscription_peer: PeerNetworkId, error: Error) {
+        // Log the subscription termination
+        warn!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Terminating backup subscription to peer: {:?}! Error: {:?}",
+                subscription_peer, error
+            ))
+        );
+
+        // Unsubscribe from the peer (and update the peer's reputation)
+        self.unsubscribe_from_peer(subscription_peer);
+        self.record_termination_reputation_event(&subscription_peer, &error);
+
+        // Record the subscription termination in the event journal
+        self.event_journal
+            .record_event(ObserverEvent::SubscriptionTerminated {
+                peer_network_id: subscription_peer,
+                reason: error.to_string(),
+            });
+
+        // Update the subscription termination metrics
+        self.update_num_active_subscriptions(&subscription_peer.network_id());
+        metrics::increment_request_counter(
+            &metrics::OBSERVER_TERMINATED_SUBSCRIPTIONS,
+            error.get_label(),
+            &subscription_peer,
+        );
+    }
+
+    /// Transitions the subscription state machine using the given event.
+    /// If the transition is invalid, an error is logged.
+    fn transition_subscription_state(&mut self, transition: SubscriptionTransition) {
+        if let Err(error) = self
+            .subscription_state_machine
+            .handle_transition(transition)
+        {
+            error!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to transition the subscription state! Error: {:?}",
+                    error
+                ))
+            );
+        }
+    }
+
+    /// Unsubscribes from the given peer by sending an unsubscribe request
+    pub fn unsubscribe_from_peer(&self, peer_network_id: PeerNetworkId) {
+        // Send an unsubscribe request to the peer and process the response.
+        // Note: we execute this asynchronously, as we don't need to wait for the response.
+        let consensus_observer_client = self.consensus_observer_client.clone();
+        let network_request_timeout_ms = self.consensus_observer_config.network_request_timeout_ms;
+        self.task_registry
+            .spawn_task("unsubscribe_from_peer", async move {
+                // Send the unsubscribe request to the peer
+                let unsubscribe_request = ConsensusObserverRequest::Unsubscribe;
+                let response = consensus_observer_client
+                    .send_rpc_request_to_peer(
+                        &peer_network_id,
+                        unsubscribe_request,
+                        network_request_timeout_ms,
+                    )
+                    .await;
+
+                // Process the response and update the unsubscribe outcome metrics
+                let network_id = peer_network_id.network_id();
+                match response {
+                    Ok(ConsensusObserverResponse::UnsubscribeAck) => {
+                        metrics::increment_unsubscribe_outcome(
+                            metrics::UNSUBSCRIBE_SUCCESS_LABEL,
+                            &network_id,
+                        );
+                        info!(
+                            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                "Successfully unsubscribed from peer: {}!",
+                                peer_network_id
+                            ))
+                        );
+                    },
+                    Ok(response) => {
+                        // We received an invalid response
+                        metrics::increment_unsubscribe_outcome(
+                            metrics::UNSUBSCRIBE_FAILED_LABEL,
+                            &network_id,
+                        );
+                        warn!(
+                            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                "Got unexpected response type: {:?}",
+                                response.get_label()
+                            ))
+                        );
+                    },
+                    Err(error) => {
+                        // We encountered an error while sending the request
+                        let outcome_label = if error.is_timeout() {
+                            metrics::UNSUBSCRIBE_TIMEOUT_LABEL
+                        } else {
+                            metrics::UNSUBSCRIBE_FAILED_LABEL
+                        };
+                        metrics::increment_unsubscribe_outcome(outcome_label, &network_id);
+                        error!(
+                            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                "Failed to send unsubscribe request to peer: {}! Error: {:?}",
+                                peer_network_id, error
+                            ))
+                        );
+                    },
+                }
+            });
+    }
+
+    /// Updates the backup subscription creation metrics for the given peer
+    fn update_backup_subscription_creation_metrics(&self, peer_network_id: PeerNetworkId) {
+        self.update_num_active_subscriptions(&peer_network_id.network_id());
+        metrics::increment_request_counter(
+            &metrics::OBSERVER_CREATED_SUBSCRIPTIONS,
+            metrics::CREATED_BACKUP_SUBSCRIPTION_LABEL,
+            &peer_network_id,
+        );
+    }
+
+    /// Updates the number of active subscriptions (i.e., the active
+    /// subscription and any backup subscriptions) for the given network.
+    fn update_num_active_subscriptions(&self, network_id: &NetworkId) {
+        let num_active_subscriptions = self
+            .get_subscription_peers()
+            .iter()
+            .filter(|peer_network_id| peer_network_id.network_id() == *network_id)
+            .count();
+        metrics::set_gauge(
+            &metrics::OBSERVER_NUM_ACTIVE_SUBSCRIPTIONS,
+            network_id,
+            num_active_subscriptions as i64,
+        );
+    }
+
+    /// Updates the subscription creation metrics for the given peer
+    fn update_subscription_creation_metrics(&self, peer_network_id: PeerNetworkId) {
+        // Set the number of active subscriptions
+        self.update_num_active_subscriptions(&peer_network_id.network_id());
+
+        // Expose the identity of the subscribed peer
+        metrics::set_active_subscription_peer(&peer_network_id);
+
+        // Update the number of created subscriptions
+        metrics::increment_request_counter(
+            &metrics::OBSERVER_CREATED_SUBSCRIPTIONS,
+            metrics::CREATED_SUBSCRIPTION_LABEL,
+            &peer_network_id,
+        );
+    }
+
+    /// Updates the subscription termination metrics for the given peer
+    fn update_subscription_termination_metrics(
+        &self,
+        peer_network_id: PeerNetworkId,
+        error: Error,
+    ) {
+        // Update the number of active subscriptions
+        self.update_num_active_subscriptions(&peer_network_id.network_id());
+
+        // Clear the identity of the previously subscribed peer
+        metrics::remove_active_subscription_peer(&peer_network_id);
+
+        // Update the number of terminated subscriptions
+        metrics::increment_request_counter(
+            &metrics::OBSERVER_TERMINATED_SUBSCRIPTIONS,
+            error.get_label(),
+            &peer_network_id,
+        );
+    }
+
+    /// Verifies the given message is from the peer of the active subscription (or
+    /// a backup subscription). Note: the message is recorded as activity for the
+    /// peer (regardless).
+    pub fn verify_message_sender(&mut self, peer_network_id: &PeerNetworkId) -> Result<(), Error> {
+        self.peer_activity_tracker
+            .record_message_received(peer_network_id);
+        if let Some(backup_subscription) = self
+            .backup_observer_subscriptions
+            .iter_mut()
+            .find(|subscription| subscription.get_peer_network_id() == *peer_network_id)
+        {
+            return backup_subscription.verify_message_sender(peer_network_id);
+        }
+        match &mut self.active_observer_subscription {
+            Some(active_subscription) => active_subscription.verify_message_sender(peer_network_id),
+            None => Err(Error::UnexpectedError(format!(
+                "Received message from unexpected peer: {}! No active subscription found!",
+                peer_network_id
+            ))),
+        }
+    }
+}
+
+/// Gets the connected peers and metadata (excluding all blocklisted
+/// peers). If an error occurred, it is logged and None is returned.
+fn get_connected_peers_and_metadata(
+    peers_and_metadata: &PeersAndMetadata,
+    blocklisted_peers: &HashMap<PeerNetworkId, Instant>,
+    epoch_blocklisted_peers: &HashMap<PeerNetworkId, u64>,
+) -> Option<HashMap<PeerNetworkId, PeerMetadata>> {
+    // Only clone the metadata of the peers that aren't blocklisted
+    match peers_and_metadata.get_connected_peers_and_metadata_filtered(|peer_network_id, _| {
+        !blocklisted_peers.contains_key(peer_network_id)
+            && !epoch_blocklisted_peers.contains_key(peer_network_id)
+    }) {
+        Ok(connected_peers_and_metadata) => Some(connected_peers_and_metadata),
+        Err(error) => {
+            error!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to get connected peers and metadata! Error: {:?}",
+                    error
+                ))
+            );
+            None
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::{
+        peer_selector::DistanceAndLatencyPeerSelector,
+        storage::InMemoryObserverStorage,
+        subscription_state::SubscriptionState,
+        test_utils::{connect_peer, AcknowledgingRpcHandler, MockNetwork, MockRpcHandler},
+    };
+    use aptos_config::network_id::NetworkId;
+    use aptos_crypto::HashValue;
+    use aptos_network::{protocols::network::RpcError, transport::ConnectionMetadata};
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        block_info::BlockInfo,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+        PeerId,
+    };
+    use maplit::hashmap;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_check_and_manage_subscriptions_no_peers() {
+        // Create a subscription manager (with no connected peers)
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let mut subscription_manager = create_subscription_manager(peers_and_metadata);
+
+        // Check and manage the subscriptions
+        subscription_manager.check_and_manage_subscriptions().await;
+
+        // Verify that no subscription was created and the state is unsubscribed
+        assert!(subscription_manager
+            .get_active_subscription_peer()
+            .is_none());
+        assert_eq!(
+            subscription_manager
+                .subscription_state_machine
+                .get_current_state(),
+            SubscriptionState::Unsubscribed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_and_manage_subscriptions_disconnected() {
+        // Create a subscription manager
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let mut subscription_manager = create_subscription_manager(peers_and_metadata.clone());
+
+        // Create an active subscription to a peer that is not connected
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        create_active_subscription(&mut subscription_manager, peer_network_id);
+        assert_eq!(
+            subscription_manager.get_active_subscription_peer(),
+            Some(peer_network_id)
+        );
+
+        // Check and manage the subscriptions
+        subscription_manager.check_and_manage_subscriptions().await;
+
+        // Verify that the subscription was terminated (and no new subscription was created)
+        assert!(subscription_manager
+            .get_active_subscription_peer()
+            .is_none());
+        assert_eq!(
+            subscription_manager
+                .subscription_state_machine
+                .get_current_state(),
+            SubscriptionState::Unsubscribed
+        );
+
+        // Verify the termination was recorded in the event journal
+        let journal_entries = subscription_manager.event_journal.get_journal_entries();
+        assert!(journal_entries.iter().any(|journal_entry| matches!(
+            &journal_entry.event,
+            ObserverEvent::SubscriptionTerminated { peer_network_id: peer, .. } if *peer == peer_network_id
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_check_and_manage_subscriptions_progress_stopped() {
+        // Create a subscription manager
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let observer_storage = create_observer_storage();
+        let mut subscription_manager = create_subscription_manager_with_storage(
+            peers_and_metadata.clone(),
+            observer_storage.clone(),
+        );
+
+        // Create an active subscription to a connected peer
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        let connection_metadata = ConnectionMetadata::mock(peer_network_id.peer_id());
+        peers_and_metadata
+            .insert_connection_metadata(peer_network_id, connection_metadata)
+            .unwrap();
+        create_active_subscription(&mut subscription_manager, peer_network_id);
+
+        // Verify the subscription is healthy (the synced version is increasing)
+        observer_storage.set_latest_synced_version(10);
+        subscription_manager.check_and_manage_subscriptions().await;
+        assert_eq!(
+            subscription_manager.get_active_subscription_peer(),
+            Some(peer_network_id)
+        );
+
+        // Elapse enough time for the synced version to timeout (but keep receiving messages)
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let mock_time_service = subscription_manager.time_service.clone().into_mock();
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.max_synced_version_timeout_ms + 1,
+        ));
+        subscription_manager
+            .verify_message_sender(&peer_network_id)
+            .unwrap();
+
+        // Verify the subscription is terminated (the synced version has not increased)
+        subscription_manager.check_and_manage_subscriptions().await;
+        assert!(subscription_manager
+            .get_active_subscription_peer()
+            .is_none());
+
+        // Verify the termination reason was recorded in the event journal
+        let journal_entries = subscription_manager.event_journal.get_journal_entries();
+        assert!(journal_entries.iter().any(|journal_entry| matches!(
+            &journal_entry.event,
+            ObserverEvent::SubscriptionTerminated { reason, .. } if reason.contains("progress stopped")
+        )));
+
+        // Verify the stall was recorded in the peer's reputation
+        assert_eq!(
+            subscription_manager
+                .peer_reputation_tracker
+                .get_reputation_score(&peer_network_id),
+            PeerReputationEvent::Stall.get_penalty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_and_manage_subscriptions_overridden() {
+        // Create a subscription manager
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let mut subscription_manager = create_subscription_manager(peers_and_metadata.clone());
+
+        // Create an active subscription to a connected peer
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        let connection_metadata = ConnectionMetadata::mock(peer_network_id.peer_id());
+        peers_and_metadata
+            .insert_connection_metadata(peer_network_id, connection_metadata)
+            .unwrap();
+        create_active_subscription(&mut subscription_manager, peer_network_id);
+
+        // Connect another peer and pin it
+        let pinned_peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        let connection_metadata = ConnectionMetadata::mock(pinned_peer_network_id.peer_id());
+        peers_and_metadata
+            .insert_connection_metadata(pinned_peer_network_id, connection_metadata)
+            .unwrap();
+        let peer_overrides = subscription_manager.get_peer_overrides();
+        peer_overrides.pin_peer(pinned_peer_network_id.peer_id());
+
+        // Verify that only the pinned peer is a candidate for subscriptions
+        let sorted_peers = sort_peers_for_subscription(&mut subscription_manager, None);
+        assert_eq!(sorted_peers, vec![pinned_peer_network_id]);
+
+        // Check and manage the subscriptions
+        subscription_manager.check_and_manage_subscriptions().await;
+
+        // Verify that the subscription was terminated immediately (without waiting
+        // for the optimality check), and that the override was recorded as the reason.
+        assert!(subscription_manager
+            .get_active_subscription_peer()
+            .is_none());
+        let journal_entries = subscription_manager.event_journal.get_journal_entries();
+        assert!(journal_entries.iter().any(|journal_entry| matches!(
+            &journal_entry.event,
+            ObserverEvent::SubscriptionTerminated { reason, .. } if reason.contains("overridden")
+        )));
+
+        // Remove the pinned peer and forbid the original peer
+        peer_overrides.unpin_peer();
+        peer_overrides.forbid_peer(peer_network_id.peer_id());
+
+        // Verify that the forbidden peer is not a candidate for subscriptions
+        let sorted_peers = sort_peers_for_subscription(&mut subscription_manager, None);
+        assert_eq!(sorted_peers, vec![pinned_peer_network_id]);
+    }
+
+    #[tokio::test]
+    async fn test_terminate_and_resubscribe() {
+        // Create a subscription manager
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let mut subscription_manager = create_subscription_manager(peers_and_metadata.clone());
+
+        // Create an active subscription to a connected peer
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        let connection_metadata = ConnectionMetadata::mock(peer_network_id.peer_id());
+        peers_and_metadata
+            .insert_connection_metadata(peer_network_id, connection_metadata)
+            .unwrap();
+        create_active_subscription(&mut subscription_manager, peer_network_id);
+
+        // Terminate the subscription and resubscribe
+        subscription_manager
+            .terminate_and_resubscribe(Error::PipelineFailure("Test failure!".into()))
+            .await;
+
+        // Verify that the subscription was terminated (and the previous peer was not reselected)
+        assert!(subscription_manager
+            .get_active_subscription_peer()
+            .is_none());
+
+        // Verify the subscription was marked as replaced (and the flag is reset once taken)
+        assert!(subscription_manager.take_subscription_replaced());
+        assert!(!subscription_manager.take_subscription_replaced());
+
+        // Verify the termination reason was recorded in the event journal
+        let journal_entries = subscription_manager.event_journal.get_journal_entries();
+        assert!(journal_entries.iter().any(|journal_entry| matches!(
+            &journal_entry.event,
+            ObserverEvent::SubscriptionTerminated { reason, .. } if reason.contains("pipeline failure")
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_handle_verification_failure() {
+        // Create a subscription manager that blocklists peers after verification failures
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let mut subscription_manager = create_subscription_manager(peers_and_metadata.clone());
+        let consensus_observer_config = ConsensusObserverConfig {
+            verification_failure_policy: VerificationFailurePolicy::TerminateAndBlocklist,
+            max_verification_failures: 2,
+            ..Default::default()
+        };
+        subscription_manager.consensus_observer_config = consensus_observer_config.clone();
+
+        // Create an active subscription to a connected peer
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        let connection_metadata = ConnectionMetadata::mock(peer_network_id.peer_id());
+        peers_and_metadata
+            .insert_connection_metadata(peer_network_id, connection_metadata)
+            .unwrap();
+        create_active_subscription(&mut subscription_manager, peer_network_id);
+
+        // Handle a verification failure and verify the subscription is still active
+        let error = Error::InvalidMessageError("Invalid proof!".into());
+        subscription_manager.handle_verification_failure(peer_network_id, &error);
+        assert_eq!(
+            subscription_manager.get_active_subscription_peer(),
+            Some(peer_network_id)
+        );
+
+        // Handle another verification failure and verify the subscription is terminated
+        subscription_manager.handle_verification_failure(peer_network_id, &error);
+        assert!(subscription_manager
+            .get_active_subscription_peer()
+            .is_none());
+
+        // Verify that the peer is blocklisted (and excluded from the subscription peers)
+        assert!(subscription_manager.is_peer_blocklisted(&peer_network_id));
+        let sorted_peers = sort_peers_for_subscription(&mut subscription_manager, None);
+        assert!(sorted_peers.is_empty());
+
+        // Elapse enough time for the blocklist to expire
+        let mock_time_service = subscription_manager.time_service.clone().into_mock();
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.peer_blocklist_duration_ms + 1,
+        ));
+
+        // Verify that the peer is no longer blocklisted
+        assert!(!subscription_manager.is_peer_blocklisted(&peer_network_id));
+        let sorted_peers = sort_peers_for_subscription(&mut subscription_manager, None);
+        assert_eq!(sorted_peers, vec![peer_network_id]);
+    }
+
+    #[tokio::test]
+    async fn test_backup_subscription_failover() {
+        // Create a subscription manager (with a single backup subscription)
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let mut subscription_manager = create_subscription_manager(peers_and_metadata.clone());
+        subscription_manager.consensus_observer_config = ConsensusObserverConfig {
+            max_concurrent_subscriptions: 2,
+            verification_failure_policy: VerificationFailurePolicy::TerminateAndBlocklist,
+            ..Default::default()
+        };
+
+        // Create an active subscription to a peer that is not connected
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        create_active_subscription(&mut subscription_manager, peer_network_id);
+
+        // Create a backup subscription to a connected peer
+        let backup_peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        let connection_metadata = ConnectionMetadata::mock(backup_peer_network_id.peer_id());
+        peers_and_metadata
+            .insert_connection_metadata(backup_peer_network_id, connection_metadata)
+            .unwrap();
+        create_backup_subscription(&mut subscription_manager, backup_peer_network_id);
+        assert_eq!(subscription_manager.get_subscription_peers(), vec![
+            peer_network_id,
+            backup_peer_network_id
+        ]);
+
+        // Verify that messages from both subscription peers are accepted
+        for peer_network_id in [peer_network_id, backup_peer_network_id] {
+            assert!(subscription_manager
+                .verify_message_sender(&peer_network_id)
+                .is_ok());
+        }
+        let other_peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        assert!(subscription_manager
+            .verify_message_sender(&other_peer_network_id)
+            .is_err());
+
+        // Check and manage the subscriptions
+        subscription_manager.check_and_manage_subscriptions().await;
+
+        // Verify that the backup subscription took over (without replacing the subscription)
+        assert_eq!(
+            subscription_manager.get_active_subscription_peer(),
+            Some(backup_peer_network_id)
+        );
+        assert_eq!(subscription_manager.get_subscription_peers(), vec![
+            backup_peer_network_id
+        ]);
+        assert_eq!(
+            subscription_manager
+                .subscription_state_machine
+                .get_current_state(),
+            SubscriptionState::Active(backup_peer_network_id)
+        );
+        assert!(!subscription_manager.take_subscription_replaced());
+
+        // Create another backup subscription and handle a verification failure for it
+        create_backup_subscription(&mut subscription_manager, other_peer_network_id);
+        let error = Error::InvalidMessageError("Invalid proof!".into());
+        subscription_manager.handle_verification_failure(other_peer_network_id, &error);
+
+        // Verify that the backup subscription was terminated immediately (and the peer
+        // blocklisted), but that the active subscription is unaffected.
+        assert_eq!(subscription_manager.get_subscription_peers(), vec![
+            backup_peer_network_id
+        ]);
+        assert!(subscription_manager.is_peer_blocklisted(&other_peer_network_id));
+        assert!(subscription_manager
+            .verify_message_sender(&other_peer_network_id)
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_new_observer_subscription() {
+        // Create a mock network where the closest peer doesn't respond to requests
+        let network_id = NetworkId::Public;
+        let unresponsive_peer = PeerNetworkId::new(network_id, PeerId::random());
+        let mock_network =
+            MockNetwork::new(Arc::new(UnresponsivePeerRpcHandler { unresponsive_peer }));
+        connect_peer(&mock_network.peers_and_metadata(), unresponsive_peer, 0);
+        let responsive_peer = mock_network.add_peer(1);
+
+        // Create a subscription manager (using the mock network)
+        let mut subscription_manager = create_subscription_manager_with_client(
+            mock_network.consensus_observer_client(),
+            create_observer_storage(),
+        );
+
+        // Create a new subscription
+        let sorted_connected_peers = subscription_manager.get_sorted_connected_peers();
+        subscription_manager
+            .create_new_observer_subscription(None, sorted_connected_peers.as_deref())
+            .await;
+
+        // Verify that the closest peer was tried first, and that the subscription
+        // was created with the responsive peer.
+        assert_eq!(mock_network.get_sent_requests(), vec![
+            (unresponsive_peer, ConsensusObserverRequest::Subscribe),
+            (responsive_peer, ConsensusObserverRequest::Subscribe),
+        ]);
+        assert_eq!(
+            subscription_manager.get_active_subscription_peer(),
+            Some(responsive_peer)
+        );
+
+        // Disconnect the responsive peer and verify that no subscription can be created
+        mock_network.disconnect_peer(responsive_peer);
+        subscription_manager.terminate_active_subscription(Error::SubscriptionDisconnected(
+            "Test disconnect!".into(),
+        ));
+        let sorted_connected_peers = subscription_manager.get_sorted_connected_peers();
+        subscription_manager
+            .create_new_observer_subscription(None, sorted_connected_peers.as_deref())
+            .await;
+        assert!(subscription_manager
+            .get_active_subscription_peer()
+            .is_none());
+    }
+
+    #[test]
+    fn test_create_subscription_request() {
+        // Create a subscription manager (with a latest committed block in storage)
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let observer_storage =
+            Arc::new(InMemoryObserverStorage::new(LedgerInfoWithSignatures::new(
+                LedgerInfo::new(BlockInfo::random_with_epoch(3, 10), HashValue::zero()),
+                AggregateSignature::empty(),
+            )));
+        let mut subscription_manager =
+            create_subscription_manager_with_storage(peers_and_metadata, observer_storage);
+
+        // Verify that a simple subscription request is created by default
+        assert_eq!(
+            subscription_manager.create_subscription_request(),
+            ConsensusObserverRequest::Subscribe
+        );
+
+        // Enable subscription replay and verify the request carries the latest block
+        subscription_manager
+            .consensus_observer_config
+            .enable_subscription_replay = true;
+        assert_eq!(
+            subscription_manager.create_subscription_request(),
+            ConsensusObserverRequest::SubscribeWithOptions(SubscriptionOptions {
+                starting_block: Some((3, 10)),
+                ..SubscriptionOptions::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_sort_peers_for_subscription() {
+        // Create a subscription manager
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let mut subscription_manager = create_subscription_manager(peers_and_metadata.clone());
+
+        // Verify that there are no peers to sort
+        let sorted_peers = sort_peers_for_subscription(&mut subscription_manager, None);
+        assert!(sorted_peers.is_empty());
+
+        // Add several connected peers
+        let mut connected_peers = vec![];
+        for _ in 0..5 {
+            let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+            let connection_metadata = ConnectionMetadata::mock(peer_network_id.peer_id());
+            peers_and_metadata
+                .insert_connection_metadata(peer_network_id, connection_metadata)
+                .unwrap();
+            connected_peers.push(peer_network_id);
+        }
+
+        // Verify that all connected peers are returned
+        let sorted_peers = sort_peers_for_subscription(&mut subscription_manager, None);
+        assert_eq!(sorted_peers.len(), connected_peers.len());
+
+        // Verify that the previous subscription peer is excluded
+        let previous_subscription_peer = connected_peers[0];
+        let sorted_peers = sort_peers_for_subscription(
+            &mut subscription_manager,
+            Some(previous_subscription_peer),
+        );
+        assert_eq!(sorted_peers.len(), connected_peers.len() - 1);
+        assert!(!sorted_peers.contains(&previous_subscription_peer));
+
+        // Set a resume subscription peer and verify that it is sorted first
+        let resume_subscription_peer = connected_peers[4];
+        subscription_manager.set_resume_subscription_peer(resume_subscription_peer);
+        let sorted_peers = sort_peers_for_subscription(&mut subscription_manager, None);
+        assert_eq!(sorted_peers.len(), connected_peers.len());
+        assert_eq!(sorted_peers[0], resume_subscription_peer);
+
+        // Verify that the resume subscription peer is only prioritized once
+        assert!(subscription_manager.resume_subscription_peer.is_none());
+    }
+
+    #[test]
+    fn test_sort_peers_for_subscription_preferred_peers() {
+        // Create a subscription manager
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let mut subscription_manager = create_subscription_manager(peers_and_metadata.clone());
+
+        // Add several connected peers
+        let mut connected_peers = vec![];
+        for _ in 0..5 {
+            let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+            let connection_metadata = ConnectionMetadata::mock(peer_network_id.peer_id());
+            peers_and_metadata
+                .insert_connection_metadata(peer_network_id, connection_metadata)
+                .unwrap();
+            connected_peers.push(peer_network_id);
+        }
+
+        // Configure several preferred peers (including a disconnected peer)
+        let disconnected_peer = PeerNetworkId::new(network_id, PeerId::random());
+        let preferred_subscription_peers =
+            vec![connected_peers[3], disconnected_peer, connected_peers[1]];
+        subscription_manager.consensus_observer_config = ConsensusObserverConfig {
+            preferred_subscription_peers,
+            ..ConsensusObserverConfig::default()
+        };
+
+        // Verify that the connected preferred peers are sorted first (in the configured order)
+        let sorted_peers = sort_peers_for_subscription(&mut subscription_manager, None);
+        assert_eq!(sorted_peers.len(), connected_peers.len());
+        assert_eq!(sorted_peers[0..2], [connected_peers[3], connected_peers[1]]);
+        assert!(!sorted_peers.contains(&disconnected_peer));
+
+        // Verify that the preferred peers are also sorted first for the optimality checks
+        let sorted_connected_peers = subscription_manager.get_sorted_connected_peers().unwrap();
+        assert_eq!(sorted_connected_peers[0..2], [
+            connected_peers[3],
+            connected_peers[1]
+        ]);
+
+        // Verify that the preferred peers are tried before the resume subscription peer
+        let resume_subscription_peer = connected_peers[4];
+        subscription_manager.set_resume_subscription_peer(resume_subscription_peer);
+        let sorted_peers = sort_peers_for_subscription(&mut subscription_manager, None);
+        assert_eq!(sorted_peers[0..3], [
+            connected_peers[3],
+            connected_peers[1],
+            resume_subscription_peer
+        ]);
+
+        // Verify that an excluded preferred peer falls back to the next preferred peer
+        let sorted_peers =
+            sort_peers_for_subscription(&mut subscription_manager, Some(connected_peers[3]));
+        assert_eq!(sorted_peers.len(), connected_peers.len() - 1);
+        assert_eq!(sorted_peers[0], connected_peers[1]);
+    }
+
+    #[test]
+    fn test_verify_message_sender() {
+        // Create a subscription manager
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let mut subscription_manager = create_subscription_manager(peers_and_metadata);
+
+        // Verify that messages are rejected when there is no active subscription
+        let peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        assert!(subscription_manager
+            .verify_message_sender(&peer_network_id)
+            .is_err());
+
+        // Create an active subscription to the peer
+        create_active_subscription(&mut subscription_manager, peer_network_id);
+
+        // Verify that messages from the subscribed peer are accepted
+        assert!(subscription_manager
+            .verify_message_sender(&peer_network_id)
+            .is_ok());
+
+        // Verify that messages from other peers are rejected
+        let other_peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        assert!(subscription_manager
+            .verify_message_sender(&other_peer_network_id)
+            .is_err());
+    }
+
+    /// An RPC handler that times out all requests sent to the
+    /// unresponsive peer (and acknowledges all other requests).
+    struct UnresponsivePeerRpcHandler {
+        unresponsive_peer: PeerNetworkId,
+    }
+
+    impl MockRpcHandler for UnresponsivePeerRpcHandler {
+        fn handle_request(
+            &self,
+            peer_network_id: &PeerNetworkId,
+            request: &ConsensusObserverRequest,
+        ) -> Result<ConsensusObserverResponse, RpcError> {
+            if *peer_network_id == self.unresponsive_peer {
+                Err(RpcError::TimedOut)
+            } else {
+                AcknowledgingRpcHandler.handle_request(peer_network_id, request)
+            }
+        }
+    }
+
+    /// Creates an in-memory observer storage (with an empty ledger info)
+    fn create_observer_storage() -> Arc<InMemoryObserverStorage> {
+        Arc::new(InMemoryObserverStorage::new(LedgerInfoWithSignatures::new(
+            LedgerInfo::new(BlockInfo::empty(), HashValue::zero()),
+            AggregateSignature::empty(),
+        )))
+    }
+
+    /// Creates an active subscription to the given peer (and updates the state machine)
+    fn create_active_subscription(
+        subscription_manager: &mut SubscriptionManager,
+        peer_network_id: PeerNetworkId,
+    ) {
+        // Update the active subscription
+        subscription_manager.active_observer_subscription =
+            Some(ConsensusObserverSubscription::new(
+                subscription_manager.consensus_observer_config.clone(),
+                subscription_manager.observer_storage.clone(),
+                peer_network_id,
+                subscription_manager.time_service.clone(),
+            ));
+
+        // Update the subscription state machine
+        subscription_manager
+            .transition_subscription_state(SubscriptionTransition::SubscriptionRequested);
+        subscription_manager.transition_subscription_state(
+            SubscriptionTransition::SubscriptionCreated(peer_network_id),
+        );
+    }
+
+    /// Creates a backup subscription to the given peer
+    fn create_backup_subscription(
+        subscription_manager: &mut SubscriptionManager,
+        peer_network_id: PeerNetworkId,
+    ) {
+        subscription_manager.backup_observer_subscriptions.push(
+            ConsensusObserverSubscription::new(
+                subscription_manager.consensus_observer_config.clone(),
+                subscription_manager.observer_storage.clone(),
+                peer_network_id,
+                subscription_manager.time_service.clone(),
+            ),
+        );
+    }
+
+    /// Sorts the peers for a subscription (using the sorted connected peers)
+    fn sort_peers_for_subscription(
+        subscription_manager: &mut SubscriptionManager,
+        previous_subscription_peer: Option<PeerNetworkId>,
+    ) -> Vec<PeerNetworkId> {
+        let sorted_connected_peers = subscription_manager.get_sorted_connected_peers().unwrap();
+        subscription_manager
+            .sort_peers_for_subscription(previous_subscription_peer, &sorted_connected_peers)
+    }
+
+    /// Creates a subscription manager using the given peers and metadata
+    fn create_subscription_manager(
+        peers_and_metadata: Arc<PeersAndMetadata>,
+    ) -> SubscriptionManager {
+        create_subscription_manager_with_storage(peers_and_metadata, create_observer_storage())
+    }
+
+    /// Creates a subscription manager using the given peers and metadata and storage
+    fn create_subscription_manager_with_storage(
+        peers_and_metadata: Arc<PeersAndMetadata>,
+        observer_storage: Arc<InMemoryObserverStorage>,
+    ) -> SubscriptionManager {
+        // Create the consensus observer client
+        let network_client = NetworkClient::new(vec![], vec![], hashmap![], peers_and_metadata);
+        let consensus_observer_client = Arc::new(ConsensusObserverClient::new(network_client));
+        create_subscription_manager_with_client(consensus_observer_client, observer_storage)
+    }
+
+    /// Creates a subscription manager using the given consensus observer client and storage
+    fn create_subscription_manager_with_client(
+        consensus_observer_client: Arc<
+            ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>,
+        >,
+        observer_storage: Arc<InMemoryObserverStorage>,
+    ) -> SubscriptionManager {
+        // Create the subscription manager
+        let time_service = TimeService::mock();
+        SubscriptionManager::new(
+            ConsensusObserverConfig::default(),
+            consensus_observer_client,
+            None,
+            Arc::new(DistanceAndLatencyPeerSelector),
+            observer_storage,
+            time_service.clone(),
+            ObserverEventJournal::new(100, time_service.clone()),
+            EpochSummaryTracker::new(time_service),
+            TaskRegistry::new(),
+        )
+    }
+}
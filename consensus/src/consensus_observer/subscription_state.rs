@@ -0,0 +1,262 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    error::Error,
+    logging::{LogEntry, LogSchema},
+    metrics,
+};
+use aptos_config::network_id::PeerNetworkId;
+use aptos_logger::info;
+use std::fmt::{Display, Formatter};
+
+/// The states of the observer subscription lifecycle
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SubscriptionState {
+    Unsubscribed,               // There is no subscription
+    Subscribing,                // The observer is attempting to create a subscription
+    Active(PeerNetworkId),      // The subscription to the peer is active
+    Terminating(PeerNetworkId), // The subscription to the peer is being terminated
+}
+
+impl SubscriptionState {
+    /// Returns a summary label for the state
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            SubscriptionState::Unsubscribed => "unsubscribed",
+            SubscriptionState::Subscribing => "subscribing",
+            SubscriptionState::Active(_) => "active",
+            SubscriptionState::Terminating(_) => "terminating",
+        }
+    }
+}
+
+impl Display for SubscriptionState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubscriptionState::Active(peer_network_id)
+            | SubscriptionState::Terminating(peer_network_id) => {
+                write!(f, "{} (peer: {})", self.get_label(), peer_network_id)
+            },
+            _ => write!(f, "{}", self.get_label()),
+        }
+    }
+}
+
+/// The typed events that drive transitions between subscription states
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SubscriptionTransition {
+    SubscriptionRequested,              // Unsubscribed -> Subscribing
+    SubscriptionCreated(PeerNetworkId), // Subscribing -> Active
+    SubscriptionFailed,                 // Subscribing -> Unsubscribed
+    TerminationStarted,                 // Active -> Terminating
+    TerminationCompleted,               // Terminating -> Unsubscribed
+}
+
+impl SubscriptionTransition {
+    /// Returns a summary label for the transition
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            SubscriptionTransition::SubscriptionRequested => "subscription_requested",
+            SubscriptionTransition::SubscriptionCreated(_) => "subscription_created",
+            SubscriptionTransition::SubscriptionFailed => "subscription_failed",
+            SubscriptionTransition::TerminationStarted => "termination_started",
+            SubscriptionTransition::TerminationCompleted => "termination_completed",
+        }
+    }
+}
+
+/// An explicit state machine for the observer subscription lifecycle:
+/// Unsubscribed -> Subscribing -> Active -> Terminating -> Unsubscribed.
+/// All transitions are validated, logged and exported as metrics.
+pub struct SubscriptionStateMachine {
+    current_state: SubscriptionState,
+}
+
+impl SubscriptionStateMachine {
+    pub fn new() -> Self {
+        Self {
+            current_state: SubscriptionState::Unsubscribed,
+        }
+    }
+
+    /// Returns the current subscription state
+    pub fn get_current_state(&self) -> SubscriptionState {
+        self.current_state
+    }
+
+    /// Handles the given transition event and returns the new state. If the
+    /// transition is invalid for the current state, an error is returned
+    /// (and the current state is left unchanged).
+    pub fn handle_transition(
+        &mut self,
+        transition: SubscriptionTransition,
+    ) -> Result<SubscriptionState, Error> {
+        // Identify the new state based on the current state and transition
+        let new_state = match (self.current_state, transition) {
+            (SubscriptionState::Unsubscribed, SubscriptionTransition::SubscriptionRequested) => {
+                SubscriptionState::Subscribing
+            },
+            (
+                SubscriptionState::Subscribing,
+                SubscriptionTransition::SubscriptionCreated(peer_network_id),
+            ) => SubscriptionState::Active(peer_network_id),
+            (SubscriptionState::Subscribing, SubscriptionTransition::SubscriptionFailed) => {
+                SubscriptionState::Unsubscribed
+            },
+            (
+                SubscriptionState::Active(peer_network_id),
+                SubscriptionTransition::TerminationStarted,
+            ) => SubscriptionState::Terminating(peer_network_id),
+            (SubscriptionState::Terminating(_), SubscriptionTransition::TerminationCompleted) => {
+                SubscriptionState::Unsubscribed
+            },
+            (current_state, transition) => {
+                return Err(Error::UnexpectedError(format!(
+                    "Invalid subscription transition: {:?} from state: {}",
+                    transition, current_state
+                )));
+            },
+        };
+
+        // Log the transition
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Subscription state transition: {} -> {} (event: {})",
+                self.current_state,
+                new_state,
+                transition.get_label()
+            ))
+        );
+
+        // Update the transition metrics
+        metrics::increment_subscription_state_transition(
+            self.current_state.get_label(),
+            new_state.get_label(),
+        );
+
+        // Update the current state
+        self.current_state = new_state;
+
+        Ok(new_state)
+    }
+}
+
+impl Default for SubscriptionStateMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_subscription_lifecycle() {
+        // Create a new subscription state machine
+        let mut state_machine = SubscriptionStateMachine::new();
+        assert_eq!(
+            state_machine.get_current_state(),
+            SubscriptionState::Unsubscribed
+        );
+
+        // Request a subscription and verify the state
+        let new_state = state_machine
+            .handle_transition(SubscriptionTransition::SubscriptionRequested)
+            .unwrap();
+        assert_eq!(new_state, SubscriptionState::Subscribing);
+
+        // Fail the subscription and verify we're unsubscribed
+        let new_state = state_machine
+            .handle_transition(SubscriptionTransition::SubscriptionFailed)
+            .unwrap();
+        assert_eq!(new_state, SubscriptionState::Unsubscribed);
+
+        // Request another subscription and create it successfully
+        let peer_network_id = PeerNetworkId::random();
+        state_machine
+            .handle_transition(SubscriptionTransition::SubscriptionRequested)
+            .unwrap();
+        let new_state = state_machine
+            .handle_transition(SubscriptionTransition::SubscriptionCreated(peer_network_id))
+            .unwrap();
+        assert_eq!(new_state, SubscriptionState::Active(peer_network_id));
+
+        // Terminate the subscription and verify the states
+        let new_state = state_machine
+            .handle_transition(SubscriptionTransition::TerminationStarted)
+            .unwrap();
+        assert_eq!(new_state, SubscriptionState::Terminating(peer_network_id));
+        let new_state = state_machine
+            .handle_transition(SubscriptionTransition::TerminationCompleted)
+            .unwrap();
+        assert_eq!(new_state, SubscriptionState::Unsubscribed);
+    }
+
+    #[test]
+    fn test_invalid_transitions() {
+        // Create a new subscription state machine
+        let mut state_machine = SubscriptionStateMachine::new();
+
+        // Verify that invalid transitions are rejected when unsubscribed
+        let peer_network_id = PeerNetworkId::random();
+        for transition in [
+            SubscriptionTransition::SubscriptionCreated(peer_network_id),
+            SubscriptionTransition::SubscriptionFailed,
+            SubscriptionTransition::TerminationStarted,
+            SubscriptionTransition::TerminationCompleted,
+        ] {
+            verify_invalid_transition(&mut state_machine, transition);
+        }
+
+        // Move to the subscribing state and verify invalid transitions are rejected
+        state_machine
+            .handle_transition(SubscriptionTransition::SubscriptionRequested)
+            .unwrap();
+        for transition in [
+            SubscriptionTransition::SubscriptionRequested,
+            SubscriptionTransition::TerminationStarted,
+            SubscriptionTransition::TerminationCompleted,
+        ] {
+            verify_invalid_transition(&mut state_machine, transition);
+        }
+
+        // Move to the active state and verify invalid transitions are rejected
+        state_machine
+            .handle_transition(SubscriptionTransition::SubscriptionCreated(peer_network_id))
+            .unwrap();
+        for transition in [
+            SubscriptionTransition::SubscriptionRequested,
+            SubscriptionTransition::SubscriptionCreated(peer_network_id),
+            SubscriptionTransition::SubscriptionFailed,
+            SubscriptionTransition::TerminationCompleted,
+        ] {
+            verify_invalid_transition(&mut state_machine, transition);
+        }
+
+        // Move to the terminating state and verify invalid transitions are rejected
+        state_machine
+            .handle_transition(SubscriptionTransition::TerminationStarted)
+            .unwrap();
+        for transition in [
+            SubscriptionTransition::SubscriptionRequested,
+            SubscriptionTransition::SubscriptionCreated(peer_network_id),
+            SubscriptionTransition::SubscriptionFailed,
+            SubscriptionTransition::TerminationStarted,
+        ] {
+            verify_invalid_transition(&mut state_machine, transition);
+        }
+    }
+
+    /// Verifies that the given transition is rejected and the state is unchanged
+    fn verify_invalid_transition(
+        state_machine: &mut SubscriptionStateMachine,
+        transition: SubscriptionTransition,
+    ) {
+        let current_state = state_machine.get_current_state();
+        let result = state_machine.handle_transition(transition);
+        assert!(matches!(result, Err(Error::UnexpectedError(_))));
+        assert_eq!(state_machine.get_current_state(), current_state);
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    error::Error,
+    logging::{LogEntry, LogSchema},
+    metrics,
+    network_events::ConsensusObserverNetworkEvents,
+    observer::ConsensusObserver,
+    observer_control::ObserverControlHandle,
+    observer_status::ObserverStatusHandle,
+    sync_notifications::SyncNotificationListener,
+};
+use aptos_infallible::Mutex;
+use aptos_logger::info;
+use std::collections::BTreeMap;
+use tokio::{runtime::Handle, task::JoinHandle};
+
+/// The name of the default (i.e., primary) observer instance
+pub const PRIMARY_OBSERVER_INSTANCE: &str = "primary";
+
+/// A single observer instance managed by the supervisor
+struct ObserverInstance {
+    // The handle used to send control commands to the instance
+    control_handle: ObserverControlHandle,
+
+    // The handle used to query the status of the instance
+    status_handle: ObserverStatusHandle,
+
+    // The handle of the task running the instance loop
+    join_handle: JoinHandle<()>,
+}
+
+/// A supervisor that manages several independent observer instances on a
+/// shared runtime (e.g., one instance per upstream network, or one instance
+/// per experiment configuration). Each instance is built with its own config
+/// and execution client, and is driven by its own network events and sync
+/// notifications. Instances are identified by name, and can be started,
+/// inspected and stopped independently.
+pub struct ObserverSupervisor {
+    // The runtime on which all instances are spawned
+    runtime_handle: Handle,
+
+    // The currently managed instances (indexed by instance name)
+    observer_instances: Mutex<BTreeMap<String, ObserverInstance>>,
+}
+
+impl ObserverSupervisor {
+    pub fn new(runtime_handle: Handle) -> Self {
+        Self {
+            runtime_handle,
+            observer_instances: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns the control handle of the given instance (if it exists)
+    pub fn get_control_handle(&self, instance_name: &str) -> Option<ObserverControlHandle> {
+        self.observer_instances
+            .lock()
+            .get(instance_name)
+            .map(|observer_instance| observer_instance.control_handle.clone())
+    }
+
+    /// Returns the names of all managed instances (sorted by name)
+    pub fn get_instance_names(&self) -> Vec<String> {
+        self.observer_instances.lock().keys().cloned().collect()
+    }
+
+    /// Returns the status handle of the given instance (if it exists)
+    pub fn get_status_handle(&self, instance_name: &str) -> Option<ObserverStatusHandle> {
+        self.observer_instances
+            .lock()
+            .get(instance_name)
+            .map(|observer_instance| observer_instance.status_handle.clone())
+    }
+
+    /// Returns true iff the given instance exists and its loop is still running
+    pub fn is_instance_running(&self, instance_name: &str) -> bool {
+        self.observer_instances
+            .lock()
+            .get(instance_name)
+            .map_or(false, |observer_instance| {
+                !observer_instance.join_handle.is_finished()
+            })
+    }
+
+    /// Starts the given observer as a new instance (with the given name). The
+    /// instance is driven by the given network events and sync notifications.
+    pub fn start_instance(
+        &self,
+        instance_name: &str,
+        consensus_observer: ConsensusObserver,
+        network_events: ConsensusObserverNetworkEvents,
+        sync_notification_listener: SyncNotificationListener,
+    ) -> Result<(), Error> {
+        // Verify that the instance name is unique
+        let mut observer_instances = self.observer_instances.lock();
+        if observer_instances.contains_key(instance_name) {
+            return Err(Error::ObserverBuildError(format!(
+                "An observer instance already exists with the name: {}",
+                instance_name
+            )));
+        }
+
+        // Spawn the instance loop
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Starting consensus observer instance: {}",
+                instance_name
+            ))
+        );
+        let control_handle = consensus_observer.get_control_handle();
+        let status_handle = consensus_observer.get_status_handle();
+        let join_handle = self
+            .runtime_handle
+            .spawn(consensus_observer.start(network_events, sync_notification_listener));
+
+        // Add the instance to the supervisor
+        observer_instances.insert(instance_name.to_string(), ObserverInstance {
+            control_handle,
+            status_handle,
+            join_handle,
+        });
+        metrics::set_gauge_with_label(&metrics::OBSERVER_INSTANCES, instance_name, 1);
+
+        Ok(())
+    }
+
+    /// Stops the given instance (if it exists) and removes it from the
+    /// supervisor. Returns true iff the instance was found and stopped.
+    pub fn stop_instance(&self, instance_name: &str) -> bool {
+        let observer_instance = self.observer_instances.lock().remove(instance_name);
+        match observer_instance {
+            Some(observer_instance) => {
+                info!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Stopping consensus observer instance: {}",
+                        instance_name
+                    ))
+                );
+                observer_instance.join_handle.abort();
+                metrics::set_gauge_with_label(&metrics::OBSERVER_INSTANCES, instance_name, 0);
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::test_harness::ObserverTestHarness;
+    use aptos_config::config::ConsensusObserverConfig;
+
+    #[tokio::test]
+    async fn test_start_and_stop_instances() {
+        // Create a supervisor
+        let observer_supervisor = ObserverSupervisor::new(Handle::current());
+
+        // Start two instances (each with a different config)
+        let instance_configs = [
+            (
+                PRIMARY_OBSERVER_INSTANCE,
+                ConsensusObserverConfig::default(),
+            ),
+            ("experiment", ConsensusObserverConfig {
+                enable_subscription_rotation: true,
+                ..ConsensusObserverConfig::default()
+            }),
+        ];
+        for (instance_name, consensus_observer_config) in instance_configs {
+            let (consensus_observer, network_events, sync_notification_listener) =
+                ObserverTestHarness::new(consensus_observer_config).into_observer_parts();
+            observer_supervisor
+                .start_instance(
+                    instance_name,
+                    consensus_observer,
+                    network_events,
+                    sync_notification_listener,
+                )
+                .unwrap();
+        }
+
+        // Verify that both instances are running
+        assert_eq!(observer_supervisor.get_instance_names(), vec![
+            "experiment".to_string(),
+            PRIMARY_OBSERVER_INSTANCE.to_string()
+        ]);
+        for instance_name in ["experiment", PRIMARY_OBSERVER_INSTANCE] {
+            assert!(observer_supervisor.is_instance_running(instance_name));
+            assert!(observer_supervisor
+                .get_control_handle(instance_name)
+                .is_some());
+            assert!(observer_supervisor
+                .get_status_handle(instance_name)
+                .is_some());
+        }
+
+        // Verify that an instance with a duplicate name cannot be started
+        let (consensus_observer, network_events, sync_notification_listener) =
+            ObserverTestHarness::new(ConsensusObserverConfig::default()).into_observer_parts();
+        let result = observer_supervisor.start_instance(
+            PRIMARY_OBSERVER_INSTANCE,
+            consensus_observer,
+            network_events,
+            sync_notification_listener,
+        );
+        assert!(matches!(result, Err(Error::ObserverBuildError(_))));
+
+        // Stop the experiment instance and verify the primary instance is unaffected
+        assert!(observer_supervisor.stop_instance("experiment"));
+        assert!(!observer_supervisor.stop_instance("experiment"));
+        assert!(!observer_supervisor.is_instance_running("experiment"));
+        assert!(observer_supervisor.is_instance_running(PRIMARY_OBSERVER_INSTANCE));
+        assert_eq!(observer_supervisor.get_instance_names(), vec![
+            PRIMARY_OBSERVER_INSTANCE.to_string()
+        ]);
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::metrics;
+use aptos_consensus_types::common::Round;
+use aptos_infallible::Mutex;
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+/// The shared state of the sync notification channel
+#[derive(Default)]
+struct SyncNotificationSlot {
+    // The latest sync notification (i.e., epoch and round) that is yet to be received
+    pending_notification: Mutex<Option<(u64, Round)>>,
+
+    // The notifier used to wake the listener when a notification is sent
+    notifier: Notify,
+}
+
+/// Creates a new sync notification channel. The channel is bounded to a
+/// single pending notification: if a newer sync completes before the
+/// listener receives the pending notification, the pending notification
+/// is replaced (i.e., only the latest completed sync target is delivered).
+/// This prevents repeated or racing sync completions from queueing up.
+pub fn new_sync_notification_channel() -> (SyncNotificationSender, SyncNotificationListener) {
+    let notification_slot = Arc::new(SyncNotificationSlot::default());
+    (
+        SyncNotificationSender {
+            notification_slot: notification_slot.clone(),
+        },
+        SyncNotificationListener { notification_slot },
+    )
+}
+
+/// The sender half of the sync notification channel (cheaply cloneable)
+#[derive(Clone)]
+pub struct SyncNotificationSender {
+    notification_slot: Arc<SyncNotificationSlot>,
+}
+
+impl SyncNotificationSender {
+    /// Notifies the listener that a sync to the given epoch and round has
+    /// completed. Any pending (i.e., unreceived) notification is replaced.
+    pub fn notify_sync_completed(&self, epoch: u64, round: Round) {
+        let previous_notification = self
+            .notification_slot
+            .pending_notification
+            .lock()
+            .replace((epoch, round));
+        if previous_notification.is_some() {
+            metrics::increment_dropped_sync_notifications(
+                metrics::SYNC_NOTIFICATION_REPLACED_LABEL,
+            );
+        }
+        metrics::set_gauge_with_label(
+            &metrics::OBSERVER_CHANNEL_QUEUE_DEPTHS,
+            metrics::SYNC_NOTIFICATIONS_CHANNEL_LABEL,
+            1,
+        );
+
+        self.notification_slot.notifier.notify_one();
+    }
+}
+
+/// The listener half of the sync notification channel
+pub struct SyncNotificationListener {
+    notification_slot: Arc<SyncNotificationSlot>,
+}
+
+impl SyncNotificationListener {
+    /// Waits for and returns the latest sync notification (i.e., epoch and round)
+    pub async fn recv(&mut self) -> Option<(u64, Round)> {
+        loop {
+            if let Some(notification) = self.try_recv() {
+                return Some(notification);
+            }
+            self.notification_slot.notifier.notified().await;
+        }
+    }
+
+    /// Returns the pending sync notification (if any), without waiting
+    pub fn try_recv(&mut self) -> Option<(u64, Round)> {
+        let notification = self.notification_slot.pending_notification.lock().take();
+        if notification.is_some() {
+            metrics::set_gauge_with_label(
+                &metrics::OBSERVER_CHANNEL_QUEUE_DEPTHS,
+                metrics::SYNC_NOTIFICATIONS_CHANNEL_LABEL,
+                0,
+            );
+        }
+        notification
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[tokio::test]
+    async fn test_sync_notification_channel() {
+        // Create a sync notification channel
+        let (sync_notification_sender, mut sync_notification_listener) =
+            new_sync_notification_channel();
+
+        // Verify that there is no pending notification
+        assert_eq!(sync_notification_listener.try_recv(), None);
+
+        // Send a notification and verify it is received
+        sync_notification_sender.notify_sync_completed(1, 10);
+        assert_eq!(sync_notification_listener.recv().await, Some((1, 10)));
+        assert_eq!(sync_notification_listener.try_recv(), None);
+
+        // Send several notifications (using a clone) and verify only the latest is received
+        let sync_notification_sender_clone = sync_notification_sender.clone();
+        sync_notification_sender.notify_sync_completed(1, 20);
+        sync_notification_sender_clone.notify_sync_completed(1, 30);
+        sync_notification_sender.notify_sync_completed(2, 5);
+        assert_eq!(sync_notification_listener.recv().await, Some((2, 5)));
+
+        // Verify that the listener does not receive the replaced notifications
+        let result = timeout(
+            Duration::from_millis(100),
+            sync_notification_listener.recv(),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sync_notification_wakes_listener() {
+        // Create a sync notification channel
+        let (sync_notification_sender, mut sync_notification_listener) =
+            new_sync_notification_channel();
+
+        // Send a notification (in the background) after the listener starts waiting
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            sync_notification_sender.notify_sync_completed(3, 7);
+        });
+
+        // Verify that the waiting listener is woken and receives the notification
+        let notification = timeout(Duration::from_secs(10), sync_notification_listener.recv())
+            .await
+            .unwrap();
+        assert_eq!(notification, Some((3, 7)));
+    }
+}
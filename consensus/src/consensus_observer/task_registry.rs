@@ -0,0 +1,221 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    logging::{LogEntry, LogSchema},
+    metrics,
+};
+use aptos_infallible::Mutex;
+use aptos_logger::{info, warn};
+use futures::future::{AbortHandle, Abortable};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tokio::task::JoinHandle;
+
+/// A single background task registered with the task registry
+struct RegisteredTask {
+    // The name of the task (used for logging and metrics)
+    task_name: &'static str,
+
+    // The handle used to cancel the task
+    abort_handle: AbortHandle,
+
+    // The handle used to join the task (e.g., on shutdown)
+    join_handle: JoinHandle<()>,
+}
+
+/// A registry for the background tasks spawned by the consensus observer.
+/// Every task is named, can be cancelled (via the returned abort handle)
+/// and is automatically removed from the registry once it completes. All
+/// remaining tasks are cancelled and joined when the registry is shut down.
+/// The registry is cheaply cloneable, and all clones share the same tasks.
+#[derive(Clone, Default)]
+pub struct TaskRegistry {
+    // The currently active tasks (indexed by task ID)
+    active_tasks: Arc<Mutex<HashMap<u64, RegisteredTask>>>,
+
+    // The ID to assign to the next spawned task
+    next_task_id: Arc<AtomicU64>,
+}
+
+impl TaskRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the names of all currently active tasks (sorted by name)
+    pub fn get_active_task_names(&self) -> Vec<&'static str> {
+        let mut active_task_names: Vec<_> = self
+            .active_tasks
+            .lock()
+            .values()
+            .map(|registered_task| registered_task.task_name)
+            .collect();
+        active_task_names.sort();
+        active_task_names
+    }
+
+    /// Returns the number of currently active tasks
+    pub fn get_num_active_tasks(&self) -> usize {
+        self.active_tasks.lock().len()
+    }
+
+    /// Cancels all active tasks and waits for them to terminate
+    pub async fn shutdown(&self) {
+        // Remove all active tasks from the registry
+        let registered_tasks: Vec<_> = self
+            .active_tasks
+            .lock()
+            .drain()
+            .map(|(_, registered_task)| registered_task)
+            .collect();
+        info!(
+            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                "Shutting down the task registry! Num active tasks: {}",
+                registered_tasks.len()
+            ))
+        );
+
+        // Cancel all the tasks
+        for registered_task in &registered_tasks {
+            registered_task.abort_handle.abort();
+        }
+
+        // Wait for all the tasks to terminate
+        for registered_task in registered_tasks {
+            metrics::decrement_active_tasks(registered_task.task_name);
+            if let Err(error) = registered_task.join_handle.await {
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to join task: {}! Error: {:?}",
+                        registered_task.task_name, error
+                    ))
+                );
+            }
+        }
+    }
+
+    /// Spawns the given task (with the specified name) and registers it with
+    /// the registry. Returns an abort handle that can be used to cancel the task.
+    /// Note: this must be called from within a tokio runtime.
+    pub fn spawn_task<F>(&self, task_name: &'static str, task: F) -> AbortHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        // Create the abort handle and task ID
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let task_id = self.next_task_id.fetch_add(1, Ordering::Relaxed);
+
+        // Lock the active tasks (to ensure the task is
+        // registered before it can remove itself on completion).
+        let mut active_tasks = self.active_tasks.lock();
+
+        // Spawn the task (the task removes itself from the registry once done)
+        let active_tasks_clone = self.active_tasks.clone();
+        let join_handle = tokio::spawn(async move {
+            let _ = Abortable::new(task, abort_registration).await;
+            if let Some(registered_task) = active_tasks_clone.lock().remove(&task_id) {
+                metrics::decrement_active_tasks(registered_task.task_name);
+            }
+        });
+
+        // Register the task
+        active_tasks.insert(task_id, RegisteredTask {
+            task_name,
+            abort_handle: abort_handle.clone(),
+            join_handle,
+        });
+        metrics::increment_active_tasks(task_name);
+
+        abort_handle
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::future;
+    use std::time::Duration;
+    use tokio::sync::oneshot;
+
+    // Useful test constants
+    const MAX_WAIT_TIME_SECS: u64 = 5;
+
+    #[tokio::test]
+    async fn test_completed_tasks_are_removed() {
+        // Create a task registry
+        let task_registry = TaskRegistry::new();
+
+        // Spawn a task that waits for a notification
+        let (notification_sender, notification_receiver) = oneshot::channel::<()>();
+        task_registry.spawn_task("test_task", async move {
+            let _ = notification_receiver.await;
+        });
+
+        // Verify that the task is active
+        assert_eq!(task_registry.get_active_task_names(), vec!["test_task"]);
+
+        // Notify the task and verify that it is removed from the registry
+        notification_sender.send(()).unwrap();
+        wait_for_num_active_tasks(&task_registry, 0).await;
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_tasks_are_removed() {
+        // Create a task registry
+        let task_registry = TaskRegistry::new();
+
+        // Spawn several tasks that never complete
+        let abort_handle_1 = task_registry.spawn_task("task_1", future::pending::<()>());
+        let _abort_handle_2 = task_registry.spawn_task("task_2", future::pending::<()>());
+
+        // Verify that both tasks are active
+        assert_eq!(task_registry.get_active_task_names(), vec![
+            "task_1", "task_2"
+        ]);
+
+        // Cancel the first task and verify that it is removed from the registry
+        abort_handle_1.abort();
+        wait_for_num_active_tasks(&task_registry, 1).await;
+        assert_eq!(task_registry.get_active_task_names(), vec!["task_2"]);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown() {
+        // Create a task registry
+        let task_registry = TaskRegistry::new();
+
+        // Spawn several tasks that never complete
+        let num_tasks = 10;
+        for _ in 0..num_tasks {
+            task_registry.spawn_task("pending_task", future::pending::<()>());
+        }
+        assert_eq!(task_registry.get_num_active_tasks(), num_tasks);
+
+        // Shutdown the registry and verify that all tasks were removed
+        tokio::time::timeout(
+            Duration::from_secs(MAX_WAIT_TIME_SECS),
+            task_registry.shutdown(),
+        )
+        .await
+        .expect("Timed out waiting for the task registry to shutdown!");
+        assert_eq!(task_registry.get_num_active_tasks(), 0);
+    }
+
+    /// Waits until the registry contains the expected number of active tasks
+    async fn wait_for_num_active_tasks(task_registry: &TaskRegistry, num_active_tasks: usize) {
+        tokio::time::timeout(Duration::from_secs(MAX_WAIT_TIME_SECS), async {
+            while task_registry.get_num_active_tasks() != num_active_tasks {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for the expected number of active tasks!");
+    }
+}
@@ -0,0 +1,1139 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::{
+        builder::ObserverBuilder,
+        error::Error,
+        event_journal::ObserverEventJournal,
+        event_listener::ObserverEventListener,
+        network_client::ConsensusObserverClient,
+        network_events::{ConsensusObserverNetworkEvents, NetworkMessage, ResponseSender},
+        network_message::{
+            BlockPayload, CommitDecision, ConsensusObserverDirectSend, ConsensusObserverMessage,
+            ConsensusObserverRequest, ConsensusObserverResponse, OrderedBlock,
+        },
+        observer::ConsensusObserver,
+        publisher::ConsensusPublisher,
+        storage::{InMemoryObserverStorage, ObserverStorageInterface},
+        sync_notifications::{new_sync_notification_channel, SyncNotificationListener},
+        test_utils,
+    },
+    error::StateSyncError,
+    network::{IncomingCommitRequest, IncomingRandGenRequest},
+    network_interface::CommitMessage,
+    payload_manager::PayloadManager,
+    pipeline::{
+        buffer_manager::OrderedBlocks, execution_client::TExecutionClient,
+        signing_phase::CommitSignerProvider,
+    },
+    rand::rand_gen::types::RandConfig,
+    state_replication::StateComputerCommitCallBackType,
+};
+use anyhow::anyhow;
+use aptos_channels::{aptos_channel, message_queues::QueueStyle};
+use aptos_config::{
+    config::ConsensusObserverConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_consensus_types::{
+    block::Block,
+    block_data::{BlockData, BlockType},
+    pipelined_block::PipelinedBlock,
+    quorum_cert::QuorumCert,
+    vote_data::VoteData,
+};
+use aptos_crypto::HashValue;
+use aptos_event_notifications::{
+    DbBackedOnChainConfig, ReconfigNotification, ReconfigNotificationListener,
+};
+use aptos_executor_types::ExecutorResult;
+use aptos_infallible::Mutex;
+use aptos_network::{
+    application::{interface::NetworkClient, metadata::ConnectionState, storage::PeersAndMetadata},
+    peer_manager::{ConnectionRequestSender, PeerManagerRequest, PeerManagerRequestSender},
+    protocols::{
+        network::{NetworkSender, NewNetworkSender},
+        rpc::error::RpcError,
+        wire::handshake::v1::ProtocolId,
+    },
+};
+use aptos_storage_interface::DbReader;
+use aptos_time_service::TimeService;
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::{BlockInfo, Round},
+    epoch_state::EpochState,
+    ledger_info::{generate_ledger_info_with_sig, LedgerInfo, LedgerInfoWithSignatures},
+    on_chain_config::{
+        OnChainConfigPayload, OnChainConsensusConfig, OnChainExecutionConfig,
+        OnChainRandomnessConfig, ValidatorSet,
+    },
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+    validator_config::ValidatorConfig,
+    validator_info::ValidatorInfo,
+    validator_signer::ValidatorSigner,
+    validator_verifier::{ValidatorConsensusInfo, ValidatorVerifier},
+    PeerId,
+};
+use bytes::Bytes;
+use futures::{channel::mpsc, StreamExt};
+use maplit::hashmap;
+use move_core_types::account_address::AccountAddress;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::time::timeout;
+
+// The epoch at which the harness starts (i.e., the epoch of the initial root)
+pub const GENESIS_EPOCH: u64 = 1;
+
+// The maximum time to wait for asynchronous observer events (e.g., sync notifications)
+const MAX_WAIT_TIME_SECS: u64 = 10;
+
+/// The calls made by the observer to the execution client
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExecutionClientCall {
+    StartEpoch(u64),               // The epoch that was started
+    FinalizeOrder(BlockInfo),      // The block info of the ordered proof
+    SendCommitDecision(BlockInfo), // The block info of the commit proof
+    SyncTo(BlockInfo),             // The block info of the sync target
+    Reset(BlockInfo),              // The block info of the reset target
+    EndEpoch,                      // The current epoch was ended
+}
+
+/// A set of finalized blocks that are waiting for a commit decision
+struct PendingCommit {
+    blocks: Vec<Arc<PipelinedBlock>>,
+    callback: StateComputerCommitCallBackType,
+}
+
+/// A scripted execution client that records all calls made by the observer.
+/// Finalized blocks are committed (i.e., the commit callbacks are invoked and
+/// storage is updated) as soon as a matching commit decision is received.
+/// Sync requests succeed by default, but failures can be scripted.
+pub struct ScriptedExecutionClient {
+    // All calls made to the execution client (in the order they were made)
+    calls: Mutex<Vec<ExecutionClientCall>>,
+    // The finalized blocks that are waiting for a commit decision
+    pending_commits: Mutex<Vec<PendingCommit>>,
+    // The scripted results of the next sync requests (syncs succeed if empty)
+    sync_results: Mutex<VecDeque<Result<(), StateSyncError>>>,
+
+    // The observer storage (updated on every commit and sync, like the node's DB)
+    observer_storage: Arc<InMemoryObserverStorage>,
+}
+
+impl ScriptedExecutionClient {
+    pub fn new(observer_storage: Arc<InMemoryObserverStorage>) -> Self {
+        Self {
+            calls: Mutex::new(vec![]),
+            pending_commits: Mutex::new(vec![]),
+            sync_results: Mutex::new(VecDeque::new()),
+            observer_storage,
+        }
+    }
+
+    /// Clears all recorded calls
+    pub fn clear_calls(&self) {
+        self.calls.lock().clear();
+    }
+
+    /// Returns a copy of all recorded calls (in the order they were made)
+    pub fn get_calls(&self) -> Vec<ExecutionClientCall> {
+        self.calls.lock().clone()
+    }
+
+    /// Returns the number of finalized blocks waiting for a commit decision
+    pub fn get_num_pending_commits(&self) -> usize {
+        self.pending_commits.lock().len()
+    }
+
+    /// Returns the number of blocks (across all finalized blocks) waiting for a commit decision
+    pub fn get_num_pending_commit_blocks(&self) -> usize {
+        self.pending_commits
+            .lock()
+            .iter()
+            .map(|pending_commit| pending_commit.blocks.len())
+            .sum()
+    }
+
+    /// Scripts the result of the next (unscripted) sync request
+    pub fn push_sync_result(&self, sync_result: Result<(), StateSyncError>) {
+        self.sync_results.lock().push_back(sync_result);
+    }
+
+    /// Commits all pending blocks up to (and including) the given commit proof.
+    /// Like the buffer manager, the commit is dropped if the committed block
+    /// hasn't been finalized (i.e., it isn't the last of any pending blocks).
+    fn commit_pending_blocks(&self, commit_proof: &LedgerInfoWithSignatures) {
+        // Identify the pending blocks covered by the commit proof
+        let commit_info = commit_proof.commit_info();
+        let mut pending_commits = self.pending_commits.lock();
+        let Some(committed_index) = pending_commits.iter().position(|pending_commit| {
+            pending_commit
+                .blocks
+                .last()
+                .is_some_and(|block| block.id() == commit_info.id())
+        }) else {
+            return; // The committed block isn't in the pipeline
+        };
+        let committed_blocks: Vec<_> = pending_commits.drain(..=committed_index).collect();
+        drop(pending_commits);
+
+        // Commit the blocks and invoke the commit callbacks
+        if !committed_blocks.is_empty() {
+            self.observer_storage
+                .set_latest_ledger_info(commit_proof.clone());
+            for pending_commit in committed_blocks {
+                (pending_commit.callback)(&pending_commit.blocks, commit_proof.clone());
+            }
+        }
+    }
+
+    /// Records the given call
+    fn record_call(&self, call: ExecutionClientCall) {
+        self.calls.lock().push(call);
+    }
+}
+
+#[async_trait::async_trait]
+impl TExecutionClient for ScriptedExecutionClient {
+    async fn start_epoch(
+        &self,
+        epoch_state: Arc<EpochState>,
+        _commit_signer_provider: Arc<dyn CommitSignerProvider>,
+        _payload_manager: Arc<PayloadManager>,
+        _onchain_consensus_config: &OnChainConsensusConfig,
+        _onchain_execution_config: &OnChainExecutionConfig,
+        _onchain_randomness_config: &OnChainRandomnessConfig,
+        _rand_config: Option<RandConfig>,
+        _fast_rand_config: Option<RandConfig>,
+        _rand_msg_rx: aptos_channel::Receiver<AccountAddress, IncomingRandGenRequest>,
+        _highest_ordered_round: Round,
+    ) {
+        self.record_call(ExecutionClientCall::StartEpoch(epoch_state.epoch));
+    }
+
+    fn get_execution_channel(&self) -> Option<mpsc::UnboundedSender<OrderedBlocks>> {
+        None // The observer does not use the execution channel
+    }
+
+    async fn finalize_order(
+        &self,
+        blocks: &[Arc<PipelinedBlock>],
+        ordered_proof: LedgerInfoWithSignatures,
+        callback: StateComputerCommitCallBackType,
+    ) -> ExecutorResult<()> {
+        self.record_call(ExecutionClientCall::FinalizeOrder(
+            ordered_proof.commit_info().clone(),
+        ));
+        self.pending_commits.lock().push(PendingCommit {
+            blocks: blocks.to_vec(),
+            callback,
+        });
+        Ok(())
+    }
+
+    fn send_commit_msg(
+        &self,
+        _peer_id: AccountAddress,
+        commit_msg: IncomingCommitRequest,
+    ) -> anyhow::Result<()> {
+        match commit_msg.req {
+            CommitMessage::Decision(commit_decision) => {
+                let commit_proof = commit_decision.ledger_info();
+                self.record_call(ExecutionClientCall::SendCommitDecision(
+                    commit_proof.commit_info().clone(),
+                ));
+                self.commit_pending_blocks(commit_proof);
+                Ok(())
+            },
+            commit_message => Err(anyhow!("Unexpected commit message: {:?}", commit_message)),
+        }
+    }
+
+    async fn sync_to(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError> {
+        self.record_call(ExecutionClientCall::SyncTo(target.commit_info().clone()));
+
+        // Syncing resets the pipeline, so all pending blocks are dropped
+        self.pending_commits.lock().clear();
+
+        // Return the scripted result (and update storage if the sync succeeded)
+        let sync_result = self.sync_results.lock().pop_front().unwrap_or(Ok(()));
+        if sync_result.is_ok() {
+            self.observer_storage.set_latest_ledger_info(target);
+        }
+        sync_result
+    }
+
+    async fn reset(&self, target: &LedgerInfoWithSignatures) -> anyhow::Result<()> {
+        self.record_call(ExecutionClientCall::Reset(target.commit_info().clone()));
+
+        // Resetting drops all pending blocks (without updating storage)
+        self.pending_commits.lock().clear();
+        Ok(())
+    }
+
+    async fn end_epoch(&self) {
+        self.record_call(ExecutionClientCall::EndEpoch);
+        self.pending_commits.lock().clear();
+    }
+}
+
+/// A simple DB reader that serves the on-chain validator set (all
+/// other on-chain configs are missing, so the observer uses defaults).
+struct MockOnChainConfigReader {
+    validator_set: ValidatorSet,
+}
+
+impl DbReader for MockOnChainConfigReader {
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        _version: Version,
+    ) -> aptos_storage_interface::Result<Option<StateValue>> {
+        if *state_key == StateKey::on_chain_config::<ValidatorSet>()? {
+            let validator_set_bytes = bcs::to_bytes(&self.validator_set)?;
+            return Ok(Some(StateValue::new_legacy(validator_set_bytes.into())));
+        }
+        Ok(None)
+    }
+}
+
+/// A link from the mock publisher network to a real consensus publisher.
+/// If set, all observer requests are forwarded to the publisher (on behalf
+/// of the observer), instead of being handled by the mock publishers.
+#[derive(Clone)]
+pub struct PublisherLink {
+    pub consensus_publisher: ConsensusPublisher,
+    pub observer_peer_network_id: PeerNetworkId,
+}
+
+/// An in-process simulation harness for the consensus observer. The harness
+/// wires a real observer to a scripted execution client, a fake network
+/// (including mock publishers that respond to subscription requests), scripted
+/// reconfiguration events and a mock time service. The harness drives the
+/// observer step-by-step, so that end-to-end behaviours (e.g., subscription
+/// failover, sync fallback and epoch changes) can be tested deterministically.
+pub struct ObserverTestHarness {
+    // The observer under test
+    consensus_observer: ConsensusObserver,
+    // The configuration of the observer under test
+    consensus_observer_config: ConsensusObserverConfig,
+
+    // The scripted execution client used by the observer
+    execution_client: Arc<ScriptedExecutionClient>,
+    // The in-memory storage used by the observer
+    observer_storage: Arc<InMemoryObserverStorage>,
+    // The event journal of the observer
+    event_journal: ObserverEventJournal,
+
+    // The network ID of all publisher peers
+    network_id: NetworkId,
+    // The peers and metadata (used to connect and disconnect publishers)
+    peers_and_metadata: Arc<PeersAndMetadata>,
+    // The fake network events stream (and the sender to inject messages)
+    network_events: ConsensusObserverNetworkEvents,
+    network_message_sender: mpsc::UnboundedSender<NetworkMessage>,
+    // The requests received by the mock publishers (in the order they were received)
+    received_requests: Arc<Mutex<Vec<(PeerNetworkId, ConsensusObserverRequest)>>>,
+    // The publishers that will not respond to requests
+    unresponsive_peers: Arc<Mutex<HashSet<PeerNetworkId>>>,
+    // The latest commits reported by the publishers (when probed)
+    peer_latest_commits: Arc<Mutex<HashMap<PeerNetworkId, BlockInfo>>>,
+    // The ordered blocks (and empty payloads) served by the publishers (when requested)
+    peer_ordered_blocks: Arc<Mutex<HashMap<PeerNetworkId, Vec<OrderedBlock>>>>,
+
+    // The listener for sync notifications sent by the observer
+    sync_notification_listener: SyncNotificationListener,
+    // The sender for reconfiguration notifications
+    reconfig_sender: aptos_channel::Sender<(), ReconfigNotification<DbBackedOnChainConfig>>,
+    // The DB reader that serves the on-chain configs
+    on_chain_config_reader: Arc<MockOnChainConfigReader>,
+
+    // The root block at the time the harness was created
+    genesis_block: BlockInfo,
+    // The validator signers (used to sign ordered and commit proofs)
+    validator_signers: Vec<ValidatorSigner>,
+    // The mock time service used by the observer
+    time_service: TimeService,
+}
+
+impl ObserverTestHarness {
+    /// Creates a new test harness using the given observer config. Note:
+    /// this must be called from within a tokio runtime (as the mock
+    /// publishers are spawned as tasks).
+    pub fn new(consensus_observer_config: ConsensusObserverConfig) -> Self {
+        Self::new_with_genesis_block(consensus_observer_config, create_genesis_block(), None)
+    }
+
+    /// Creates a new test harness using the given observer config, genesis
+    /// block and (optional) publisher link. This is useful for running several
+    /// observers against the same chain (e.g., in a multi-node simulation).
+    pub fn new_with_genesis_block(
+        consensus_observer_config: ConsensusObserverConfig,
+        genesis_block: BlockInfo,
+        publisher_link: Option<PublisherLink>,
+    ) -> Self {
+        Self::new_with_time_service(
+            consensus_observer_config,
+            genesis_block,
+            publisher_link,
+            TimeService::mock(),
+        )
+    }
+
+    /// Creates a new test harness using the given observer config, genesis
+    /// block, (optional) publisher link and mock time service. This is useful
+    /// for sharing a single clock between several observers (e.g., so that
+    /// a multi-node simulation can advance time for all nodes at once).
+    pub fn new_with_time_service(
+        consensus_observer_config: ConsensusObserverConfig,
+        genesis_block: BlockInfo,
+        publisher_link: Option<PublisherLink>,
+        time_service: TimeService,
+    ) -> Self {
+        // Create the validator set and the on-chain config reader
+        let validator_signers = vec![ValidatorSigner::from_int(0)];
+        let validator_infos = validator_signers
+            .iter()
+            .enumerate()
+            .map(|(index, signer)| {
+                let validator_config =
+                    ValidatorConfig::new(signer.public_key(), vec![], vec![], index as u64);
+                ValidatorInfo::new(signer.author(), 1, validator_config)
+            })
+            .collect();
+        let on_chain_config_reader = Arc::new(MockOnChainConfigReader {
+            validator_set: ValidatorSet::new(validator_infos),
+        });
+
+        // Create the in-memory storage and the scripted execution client
+        let observer_storage =
+            Arc::new(InMemoryObserverStorage::new(LedgerInfoWithSignatures::new(
+                LedgerInfo::new(genesis_block.clone(), HashValue::zero()),
+                AggregateSignature::empty(),
+            )));
+        let execution_client = Arc::new(ScriptedExecutionClient::new(observer_storage.clone()));
+
+        // Create the mock publisher network and the consensus observer client
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let (peer_manager_request_sender, peer_manager_request_receiver) =
+            aptos_channel::new(QueueStyle::FIFO, 100, None);
+        let (connection_request_sender, _connection_request_receiver) =
+            aptos_channel::new(QueueStyle::FIFO, 100, None);
+        let network_sender = NetworkSender::new(
+            PeerManagerRequestSender::new(peer_manager_request_sender),
+            ConnectionRequestSender::new(connection_request_sender),
+        );
+        let network_client = NetworkClient::new(
+            vec![ProtocolId::ConsensusObserver],
+            vec![ProtocolId::ConsensusObserverRpc],
+            hashmap! {network_id => network_sender},
+            peers_and_metadata.clone(),
+        );
+        let consensus_observer_client = Arc::new(ConsensusObserverClient::new(network_client));
+
+        // Spawn the mock publishers (to respond to observer requests)
+        let received_requests = Arc::new(Mutex::new(vec![]));
+        let unresponsive_peers = Arc::new(Mutex::new(HashSet::new()));
+        let peer_latest_commits = Arc::new(Mutex::new(HashMap::new()));
+        let peer_ordered_blocks = Arc::new(Mutex::new(HashMap::new()));
+        tokio::spawn(handle_publisher_requests(
+            network_id,
+            peer_manager_request_receiver,
+            received_requests.clone(),
+            unresponsive_peers.clone(),
+            peer_latest_commits.clone(),
+            peer_ordered_blocks.clone(),
+            publisher_link,
+        ));
+
+        // Create the fake network events stream
+        let (network_message_sender, network_message_receiver) = mpsc::unbounded();
+        let network_events = ConsensusObserverNetworkEvents::new_for_test(network_message_receiver);
+
+        // Create the reconfiguration and sync notification channels
+        let (reconfig_sender, reconfig_receiver) = aptos_channel::new(QueueStyle::KLAST, 1, None);
+        let reconfig_events = ReconfigNotificationListener {
+            notification_receiver: reconfig_receiver,
+        };
+        let (sync_notification_sender, sync_notification_listener) =
+            new_sync_notification_channel();
+
+        // Create the consensus observer
+        let event_journal = ObserverEventJournal::new(
+            consensus_observer_config.max_num_journal_events,
+            time_service.clone(),
+        );
+        let consensus_observer = ObserverBuilder::new(consensus_observer_config.clone())
+            .with_consensus_observer_client(consensus_observer_client)
+            .with_observer_storage(observer_storage.clone())
+            .with_execution_client(execution_client.clone())
+            .with_sync_notification_sender(sync_notification_sender)
+            .with_reconfig_events(Some(reconfig_events))
+            .with_time_service(time_service.clone())
+            .with_event_journal(event_journal.clone())
+            .build()
+            .unwrap();
+
+        Self {
+            consensus_observer,
+            consensus_observer_config,
+            execution_client,
+            observer_storage,
+            event_journal,
+            network_id,
+            peers_and_metadata,
+            network_events,
+            network_message_sender,
+            received_requests,
+            unresponsive_peers,
+            peer_latest_commits,
+            peer_ordered_blocks,
+            sync_notification_listener,
+            reconfig_sender,
+            on_chain_config_reader,
+            genesis_block,
+            validator_signers,
+            time_service,
+        }
+    }
+
+    /// Connects a new publisher peer with the given distance from the validators
+    /// (peers with smaller distances are preferred for subscriptions).
+    pub fn add_publisher_peer(&self, distance_from_validators: u64) -> PeerNetworkId {
+        let peer_network_id = PeerNetworkId::new(self.network_id, PeerId::random());
+        self.connect_publisher_peer(peer_network_id, distance_from_validators);
+        peer_network_id
+    }
+
+    /// Connects the given publisher peer with the given distance from the validators
+    pub fn connect_publisher_peer(
+        &self,
+        peer_network_id: PeerNetworkId,
+        distance_from_validators: u64,
+    ) {
+        test_utils::connect_peer(
+            &self.peers_and_metadata,
+            peer_network_id,
+            distance_from_validators,
+        );
+    }
+
+    /// Advances the mock time by the progress check interval and checks
+    /// the progress of the observer (e.g., to manage subscriptions).
+    pub async fn check_progress(&mut self) {
+        self.advance_time(Duration::from_millis(
+            self.consensus_observer_config.progress_check_interval_ms,
+        ));
+        self.consensus_observer.check_progress().await;
+    }
+
+    /// Advances the mock time by the given duration
+    pub fn advance_time(&self, duration: Duration) {
+        self.time_service.clone().into_mock().advance(duration);
+    }
+
+    /// Creates a block payload message for the given ordered block
+    pub fn create_block_payload_message(
+        &self,
+        ordered_block: &OrderedBlock,
+    ) -> ConsensusObserverDirectSend {
+        ConsensusObserverMessage::new_block_payload_message(
+            ordered_block.proof_block_info().clone(),
+            vec![],
+            None,
+        )
+    }
+
+    /// Creates a block payload message (with no transactions) for the given block
+    /// (e.g., to send the payloads of an ordered block with multiple blocks).
+    pub fn create_block_payload_message_for_block(
+        &self,
+        block: &PipelinedBlock,
+    ) -> ConsensusObserverDirectSend {
+        ConsensusObserverMessage::new_block_payload_message(block.block_info(), vec![], None)
+    }
+
+    /// Creates a (correctly signed) commit decision for the given ordered block
+    pub fn create_commit_decision(&self, ordered_block: &OrderedBlock) -> CommitDecision {
+        let commit_proof = self.create_signed_ledger_info(ordered_block.proof_block_info().clone());
+        CommitDecision::new(commit_proof)
+    }
+
+    /// Creates a (correctly signed) ordered block containing a single
+    /// block with the given epoch and round that extends the given parent.
+    pub fn create_ordered_block(
+        &self,
+        parent_block: &BlockInfo,
+        epoch: u64,
+        round: Round,
+    ) -> OrderedBlock {
+        self.create_ordered_block_with_blocks(parent_block, epoch, round, 1)
+    }
+
+    /// Creates a (correctly signed) ordered block containing a chain of the
+    /// given number of blocks (starting at the given epoch and round) that
+    /// extends the given parent.
+    pub fn create_ordered_block_with_blocks(
+        &self,
+        parent_block: &BlockInfo,
+        epoch: u64,
+        first_round: Round,
+        num_blocks: u64,
+    ) -> OrderedBlock {
+        // Create the chain of pipelined blocks
+        let mut blocks = vec![];
+        let mut parent_block = parent_block.clone();
+        for round in first_round..first_round + num_blocks {
+            let (block_info, pipelined_block) = create_pipelined_block(&parent_block, epoch, round);
+            blocks.push(pipelined_block);
+            parent_block = block_info;
+        }
+
+        // Create the ordered block (the proof certifies the last block)
+        let ordered_proof = self.create_signed_ledger_info(parent_block);
+        OrderedBlock::new(blocks, ordered_proof)
+    }
+
+    /// Disconnects the given publisher peer
+    pub fn disconnect_peer(&self, peer_network_id: PeerNetworkId) {
+        test_utils::disconnect_peer(&self.peers_and_metadata, peer_network_id);
+    }
+
+    /// Returns the event journal of the observer
+    pub fn event_journal(&self) -> &ObserverEventJournal {
+        &self.event_journal
+    }
+
+    /// Returns the scripted execution client used by the observer
+    pub fn execution_client(&self) -> &Arc<ScriptedExecutionClient> {
+        &self.execution_client
+    }
+
+    /// Forces the observer to state sync to the given commit (or the latest
+    /// known commit, if none is given). Returns the sync target.
+    pub fn force_state_sync(
+        &mut self,
+        ledger_info: Option<LedgerInfoWithSignatures>,
+    ) -> Result<BlockInfo, Error> {
+        self.consensus_observer.force_state_sync(ledger_info)
+    }
+
+    /// Forces the observer to resubscribe to the given peer (or to rerun the
+    /// peer selection, if no peer is given). Returns the new subscription peer.
+    pub async fn force_resubscription(
+        &mut self,
+        peer_network_id: Option<PeerNetworkId>,
+    ) -> Result<PeerNetworkId, Error> {
+        self.consensus_observer
+            .force_resubscription(peer_network_id)
+            .await
+    }
+
+    /// Pauses the finalization of ordered blocks and commit decisions
+    pub fn pause_finalization(&mut self) {
+        self.consensus_observer.pause_finalization();
+    }
+
+    /// Resumes the finalization of ordered blocks and commit decisions
+    pub async fn resume_finalization(&mut self) {
+        self.consensus_observer.resume_finalization().await;
+    }
+
+    /// Registers the given event listener with the observer
+    pub fn register_listener(&mut self, listener: Box<dyn ObserverEventListener>) {
+        self.consensus_observer.register_listener(listener);
+    }
+
+    /// Returns the root block at the time the harness was created
+    pub fn genesis_block(&self) -> BlockInfo {
+        self.genesis_block.clone()
+    }
+
+    /// Returns the latest ledger info in the observer storage
+    pub fn get_latest_ledger_info(&self) -> LedgerInfoWithSignatures {
+        self.observer_storage.get_latest_ledger_info().unwrap()
+    }
+
+    /// Returns a copy of all requests received by the mock publishers
+    pub fn get_received_requests(&self) -> Vec<(PeerNetworkId, ConsensusObserverRequest)> {
+        self.received_requests.lock().clone()
+    }
+
+    /// Consumes the harness and returns the observer under test (along with
+    /// the network events and sync notifications that drive the observer).
+    /// This is useful for running the observer loop directly (e.g., via the
+    /// supervisor). Note: the observer will wait for the first epoch to start.
+    pub fn into_observer_parts(
+        self,
+    ) -> (
+        ConsensusObserver,
+        ConsensusObserverNetworkEvents,
+        SyncNotificationListener,
+    ) {
+        (
+            self.consensus_observer,
+            self.network_events,
+            self.sync_notification_listener,
+        )
+    }
+
+    /// Returns a sender that can be used to inject messages into the fake network.
+    /// Note: injected messages are only processed when the harness is driven
+    /// (e.g., via `process_next_network_message()`).
+    pub fn network_message_sender(&self) -> mpsc::UnboundedSender<NetworkMessage> {
+        self.network_message_sender.clone()
+    }
+
+    /// Notifies the observer of a reconfiguration to the given epoch. Note:
+    /// the observer will only process the notification when it waits for
+    /// the next epoch to start (e.g., after syncing to a new epoch).
+    pub fn notify_reconfiguration(&self, epoch: u64) {
+        let on_chain_configs = OnChainConfigPayload::new(
+            epoch,
+            DbBackedOnChainConfig::new(self.on_chain_config_reader.clone(), epoch),
+        );
+        self.reconfig_sender
+            .push((), ReconfigNotification {
+                version: epoch,
+                on_chain_configs,
+            })
+            .unwrap();
+    }
+
+    /// Waits for the next message on the fake network and processes it
+    pub async fn process_next_network_message(&mut self) {
+        let network_message = timeout(
+            Duration::from_secs(MAX_WAIT_TIME_SECS),
+            self.network_events.next(),
+        )
+        .await
+        .expect("Timed out waiting for the next network message!")
+        .expect("The network message stream was closed!");
+        self.consensus_observer
+            .process_network_message(network_message)
+            .await;
+    }
+
+    /// Reconnects the given (previously disconnected) publisher peer
+    pub fn reconnect_peer(&self, peer_network_id: PeerNetworkId) {
+        self.peers_and_metadata
+            .update_connection_state(peer_network_id, ConnectionState::Connected)
+            .unwrap();
+    }
+
+    /// Injects the given direct send message (from the given peer) into
+    /// the fake network and waits for the observer to process it.
+    pub async fn send_direct_send_message(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        message: ConsensusObserverDirectSend,
+    ) {
+        self.send_network_message(NetworkMessage {
+            peer_network_id,
+            protocol_id: None,
+            consensus_observer_message: ConsensusObserverMessage::DirectSend(message),
+            response_sender: None,
+            num_message_bytes: 0,
+        })
+        .await;
+    }
+
+    /// Injects the given network message into the fake network and waits
+    /// for the observer to process it.
+    pub async fn send_network_message(&mut self, network_message: NetworkMessage) {
+        self.network_message_sender
+            .unbounded_send(network_message)
+            .unwrap();
+        self.process_next_network_message().await;
+    }
+
+    /// Sets the latest commit reported by the given publisher peer (when probed)
+    pub fn set_peer_latest_commit(&self, peer_network_id: PeerNetworkId, commit_info: BlockInfo) {
+        self.peer_latest_commits
+            .lock()
+            .insert(peer_network_id, commit_info);
+    }
+
+    /// Sets the ordered blocks served by the given publisher peer (when requested).
+    /// The publisher also serves empty payloads for the blocks (when requested).
+    pub fn set_peer_ordered_blocks(
+        &self,
+        peer_network_id: PeerNetworkId,
+        ordered_blocks: Vec<OrderedBlock>,
+    ) {
+        self.peer_ordered_blocks
+            .lock()
+            .insert(peer_network_id, ordered_blocks);
+    }
+
+    /// Sets whether the given publisher peer responds to requests
+    pub fn set_peer_responsive(&self, peer_network_id: PeerNetworkId, responsive: bool) {
+        let mut unresponsive_peers = self.unresponsive_peers.lock();
+        if responsive {
+            unresponsive_peers.remove(&peer_network_id);
+        } else {
+            unresponsive_peers.insert(peer_network_id);
+        }
+    }
+
+    /// Notifies the observer of a reconfiguration to the given
+    /// epoch and waits for the observer to start the epoch.
+    pub async fn start_epoch(&mut self, epoch: u64) {
+        self.notify_reconfiguration(epoch);
+        self.consensus_observer.wait_for_epoch_start().await;
+    }
+
+    /// Stores a (correctly signed) epoch-ending ledger info for the given epoch.
+    /// This makes the epoch state of the next epoch known to the observer (e.g.,
+    /// to verify commit decisions for the next epoch).
+    pub fn store_epoch_ending_ledger_info(&self, epoch: u64) {
+        let validator_infos = self
+            .validator_signers
+            .iter()
+            .map(|signer| ValidatorConsensusInfo::new(signer.author(), signer.public_key(), 1))
+            .collect();
+        let next_epoch_state = EpochState::new(epoch + 1, ValidatorVerifier::new(validator_infos));
+        let block_info = BlockInfo::new(
+            epoch,
+            0,
+            HashValue::random(),
+            HashValue::random(),
+            0,
+            0,
+            Some(next_epoch_state),
+        );
+        self.observer_storage
+            .insert_epoch_ending_ledger_info(self.create_signed_ledger_info(block_info));
+    }
+
+    /// Waits for the observer to complete the next sync (i.e., for the sync
+    /// notification to be sent) and processes the notification. Returns the
+    /// epoch and round of the sync notification.
+    pub async fn wait_for_sync_notification(&mut self) -> (u64, Round) {
+        let (epoch, round) = timeout(
+            Duration::from_secs(MAX_WAIT_TIME_SECS),
+            self.sync_notification_listener.recv(),
+        )
+        .await
+        .expect("Timed out waiting for the sync notification!")
+        .expect("The sync notification channel was closed!");
+        self.consensus_observer
+            .process_sync_notification(epoch, round)
+            .await;
+        (epoch, round)
+    }
+
+    /// Processes the given sync notification directly (i.e., without waiting for
+    /// a sync to complete). This is useful for simulating duplicate or racing
+    /// sync notifications.
+    pub async fn process_sync_notification(&mut self, epoch: u64, round: Round) {
+        self.consensus_observer
+            .process_sync_notification(epoch, round)
+            .await;
+    }
+
+    /// Creates a ledger info for the given block info (signed by all validators)
+    fn create_signed_ledger_info(&self, block_info: BlockInfo) -> LedgerInfoWithSignatures {
+        generate_ledger_info_with_sig(
+            &self.validator_signers,
+            LedgerInfo::new(block_info, HashValue::zero()),
+        )
+    }
+}
+
+/// Creates and returns a new (random) genesis block
+pub fn create_genesis_block() -> BlockInfo {
+    BlockInfo::new(
+        GENESIS_EPOCH,
+        0,
+        HashValue::random(),
+        HashValue::random(),
+        0,
+        0,
+        None,
+    )
+}
+
+/// Creates a pipelined block with the given epoch and round that
+/// extends the given parent (and returns the block info of the block).
+fn create_pipelined_block(
+    parent_block: &BlockInfo,
+    epoch: u64,
+    round: Round,
+) -> (BlockInfo, Arc<PipelinedBlock>) {
+    // Create the block info (the version and timestamp extend the parent)
+    let block_info = BlockInfo::new(
+        epoch,
+        round,
+        HashValue::random(),
+        HashValue::random(),
+        parent_block.version() + 1,
+        parent_block.timestamp_usecs() + 1,
+        None,
+    );
+
+    // Create the pipelined block (certifying the parent block)
+    let quorum_cert = QuorumCert::new(
+        VoteData::new(parent_block.clone(), parent_block.clone()),
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(parent_block.clone(), HashValue::zero()),
+            AggregateSignature::empty(),
+        ),
+    );
+    let block_data = BlockData::new_for_testing(
+        epoch,
+        round,
+        block_info.timestamp_usecs(),
+        quorum_cert,
+        BlockType::Genesis,
+    );
+    let block = Block::new_for_testing(block_info.id(), block_data, None);
+    let pipelined_block = Arc::new(PipelinedBlock::new_ordered(block));
+
+    (block_info, pipelined_block)
+}
+
+/// Handles the requests sent to the mock publishers. Subscription and unsubscription
+/// requests are acknowledged, probes are answered with the latest commit set for the
+/// publisher (if any), and block (and payload) requests are answered with the ordered
+/// blocks set for the publisher (within the requested rounds) or their empty payloads,
+/// unless the publisher has been marked as unresponsive.
+/// If a publisher link is provided, the requests are forwarded to the real publisher.
+async fn handle_publisher_requests(
+    network_id: NetworkId,
+    mut peer_manager_request_receiver: aptos_channel::Receiver<
+        (PeerId, ProtocolId),
+        PeerManagerRequest,
+    >,
+    received_requests: Arc<Mutex<Vec<(PeerNetworkId, ConsensusObserverRequest)>>>,
+    unresponsive_peers: Arc<Mutex<HashSet<PeerNetworkId>>>,
+    peer_latest_commits: Arc<Mutex<HashMap<PeerNetworkId, BlockInfo>>>,
+    peer_ordered_blocks: Arc<Mutex<HashMap<PeerNetworkId, Vec<OrderedBlock>>>>,
+    publisher_link: Option<PublisherLink>,
+) {
+    while let Some(peer_manager_request) = peer_manager_request_receiver.next().await {
+        // The observer only sends RPC requests to publishers
+        let (peer_id, outbound_rpc_request) = match peer_manager_request {
+            PeerManagerRequest::SendRpc(peer_id, outbound_rpc_request) => {
+                (peer_id, outbound_rpc_request)
+            },
+            PeerManagerRequest::SendDirectSend(peer_id, _) => {
+                panic!("Unexpected direct send message sent to peer: {}", peer_id)
+            },
+        };
+
+        // Deserialize and record the request
+        let peer_network_id = PeerNetworkId::new(network_id, peer_id);
+        let protocol_id = outbound_rpc_request.protocol_id;
+        let request = match protocol_id
+            .from_bytes::<ConsensusObserverMessage>(outbound_rpc_request.data.as_ref())
+        {
+            Ok(ConsensusObserverMessage::Request(request)) => request,
+            message => panic!("Unexpected RPC message sent to publisher: {:?}", message),
+        };
+        received_requests
+            .lock()
+            .push((peer_network_id, request.clone()));
+
+        // If the publisher is unresponsive, time out the request
+        if unresponsive_peers.lock().contains(&peer_network_id) {
+            let _ = outbound_rpc_request.res_tx.send(Err(RpcError::TimedOut));
+            continue;
+        }
+
+        // If the publisher is linked, forward the request to the real publisher
+        if let Some(publisher_link) = &publisher_link {
+            publisher_link
+                .consensus_publisher
+                .handle_subscription_request(
+                    &publisher_link.observer_peer_network_id,
+                    request,
+                    ResponseSender::new(outbound_rpc_request.res_tx),
+                );
+            continue;
+        }
+
+        // Otherwise, acknowledge the request
+        let response = match request {
+            ConsensusObserverRequest::Subscribe
+            | ConsensusObserverRequest::SubscribeWithOptions(_) => {
+                ConsensusObserverResponse::SubscribeAck
+            },
+            ConsensusObserverRequest::Unsubscribe => ConsensusObserverResponse::UnsubscribeAck,
+            ConsensusObserverRequest::AcknowledgePayloads(_) => {
+                ConsensusObserverResponse::AcknowledgePayloadsAck
+            },
+            ConsensusObserverRequest::GetLatestCommit => ConsensusObserverResponse::LatestCommit(
+                peer_latest_commits.lock().get(&peer_network_id).cloned(),
+            ),
+            ConsensusObserverRequest::GetOrderedBlocks {
+                start_round,
+                end_round,
+            } => {
+                let ordered_blocks = peer_ordered_blocks
+                    .lock()
+                    .get(&peer_network_id)
+                    .into_iter()
+                    .flatten()
+                    .filter(|ordered_block| {
+                        let round = ordered_block.proof_block_info().round();
+                        (start_round..=end_round).contains(&round)
+                    })
+                    .cloned()
+                    .collect();
+                ConsensusObserverResponse::OrderedBlocks(ordered_blocks)
+            },
+            ConsensusObserverRequest::GetBlockPayload(block_id) => {
+                let block_payload = peer_ordered_blocks
+                    .lock()
+                    .get(&peer_network_id)
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|ordered_block| ordered_block.blocks())
+                    .find(|block| block.id() == block_id)
+                    .map(|block| BlockPayload {
+                        block: block.block_info(),
+                        transactions: vec![],
+                        limit: None,
+                    });
+                ConsensusObserverResponse::BlockPayload(block_payload)
+            },
+        };
+        let response_bytes = protocol_id
+            .to_bytes(&ConsensusObserverMessage::Response(response))
+            .unwrap();
+        let _ = outbound_rpc_request
+            .res_tx
+            .send(Ok(Bytes::from(response_bytes)));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::event_journal::ObserverEvent;
+
+    #[tokio::test]
+    async fn test_harness_commit_flow() {
+        // Create a test harness and start the genesis epoch
+        let mut harness = ObserverTestHarness::new(ConsensusObserverConfig::default());
+        harness.start_epoch(GENESIS_EPOCH).await;
+
+        // Add a publisher and verify that the observer subscribes to it
+        let publisher = harness.add_publisher_peer(0);
+        harness.check_progress().await;
+        assert_eq!(harness.get_received_requests(), vec![(
+            publisher,
+            ConsensusObserverRequest::Subscribe
+        )]);
+        verify_subscription_created(&harness, publisher);
+
+        // Send a block payload and an ordered block from the publisher
+        let ordered_block =
+            harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 1);
+        let block_payload_message = harness.create_block_payload_message(&ordered_block);
+        harness
+            .send_direct_send_message(publisher, block_payload_message)
+            .await;
+        harness
+            .send_direct_send_message(
+                publisher,
+                ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+            )
+            .await;
+        assert_eq!(harness.execution_client().get_num_pending_commits(), 1);
+
+        // Send the commit decision and verify the block is committed
+        let commit_decision = harness.create_commit_decision(&ordered_block);
+        harness
+            .send_direct_send_message(
+                publisher,
+                ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+            )
+            .await;
+        assert_eq!(harness.execution_client().get_num_pending_commits(), 0);
+        assert_eq!(
+            harness.get_latest_ledger_info(),
+            commit_decision.commit_proof().clone()
+        );
+
+        // Verify the calls made to the execution client
+        let block_info = ordered_block.proof_block_info().clone();
+        assert_eq!(harness.execution_client().get_calls(), vec![
+            ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+            ExecutionClientCall::FinalizeOrder(block_info.clone()),
+            ExecutionClientCall::SendCommitDecision(block_info),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_harness_sync_fallback() {
+        // Create a test harness, start the genesis epoch and subscribe to a publisher
+        let mut harness = ObserverTestHarness::new(ConsensusObserverConfig::default());
+        harness.start_epoch(GENESIS_EPOCH).await;
+        let publisher = harness.add_publisher_peer(0);
+        harness.check_progress().await;
+
+        // Send a commit decision for a block the observer has never seen
+        let ordered_block =
+            harness.create_ordered_block(&harness.genesis_block(), GENESIS_EPOCH, 10);
+        let commit_decision = harness.create_commit_decision(&ordered_block);
+        harness
+            .send_direct_send_message(
+                publisher,
+                ConsensusObserverDirectSend::CommitDecision(commit_decision.clone()),
+            )
+            .await;
+
+        // Wait for the sync to complete and verify the sync notification
+        let (epoch, round) = harness.wait_for_sync_notification().await;
+        assert_eq!((epoch, round), (GENESIS_EPOCH, 10));
+        assert_eq!(
+            harness.get_latest_ledger_info(),
+            commit_decision.commit_proof().clone()
+        );
+
+        // Verify the calls made to the execution client
+        assert_eq!(harness.execution_client().get_calls(), vec![
+            ExecutionClientCall::StartEpoch(GENESIS_EPOCH),
+            ExecutionClientCall::Reset(ordered_block.proof_block_info().clone()),
+            ExecutionClientCall::SyncTo(ordered_block.proof_block_info().clone()),
+        ]);
+
+        // Verify the sync was recorded in the event journal
+        let journal_events: Vec<_> = harness
+            .event_journal()
+            .get_journal_entries()
+            .into_iter()
+            .map(|journal_entry| journal_entry.event)
+            .collect();
+        assert!(journal_events.contains(&ObserverEvent::SyncStarted {
+            epoch: GENESIS_EPOCH,
+            round: 10
+        }));
+        assert!(journal_events.contains(&ObserverEvent::SyncCompleted {
+            epoch: GENESIS_EPOCH,
+            round: 10
+        }));
+    }
+
+    /// Verifies that a subscription to the given peer was recorded in the event journal
+    fn verify_subscription_created(harness: &ObserverTestHarness, peer_network_id: PeerNetworkId) {
+        let subscription_created = harness
+            .event_journal()
+            .get_journal_entries()
+            .into_iter()
+            .any(|journal_entry| {
+                journal_entry.event == ObserverEvent::SubscriptionCreated { peer_network_id }
+            });
+        assert!(subscription_created);
+    }
+}
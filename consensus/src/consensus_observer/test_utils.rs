@@ -0,0 +1,381 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test utilities for unit testing the consensus observer and publisher in
+//! isolation (e.g., subscription management and message handlers). This
+//! includes a mock network (with mockable RPC handlers for each peer), helpers
+//! to connect and disconnect peers, and mock response senders for RPC requests.
+
+use crate::consensus_observer::{
+    network_client::ConsensusObserverClient,
+    network_events::{NetworkMessage, ResponseSender},
+    network_message::{
+        ConsensusObserverMessage, ConsensusObserverRequest, ConsensusObserverResponse,
+    },
+};
+use aptos_channels::{aptos_channel, message_queues::QueueStyle};
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_infallible::Mutex;
+use aptos_network::{
+    application::{interface::NetworkClient, metadata::ConnectionState, storage::PeersAndMetadata},
+    peer_manager::{ConnectionRequestSender, PeerManagerRequest, PeerManagerRequestSender},
+    protocols::{
+        network::{NetworkSender, NewNetworkSender},
+        rpc::error::RpcError,
+        wire::handshake::v1::ProtocolId,
+    },
+    transport::ConnectionMetadata,
+};
+use aptos_peer_monitoring_service_types::{
+    response::NetworkInformationResponse, PeerMonitoringMetadata,
+};
+use aptos_types::PeerId;
+use bytes::Bytes;
+use futures::StreamExt;
+use futures_channel::oneshot;
+use maplit::hashmap;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// A mockable handler for the RPC requests sent to peers over the mock network
+pub trait MockRpcHandler: Send + Sync {
+    /// Returns the response of the given peer to the request (or
+    /// an RPC error, e.g., to simulate a request timeout).
+    fn handle_request(
+        &self,
+        peer_network_id: &PeerNetworkId,
+        request: &ConsensusObserverRequest,
+    ) -> Result<ConsensusObserverResponse, RpcError>;
+}
+
+/// An RPC handler that acknowledges all requests (and serves no data)
+pub struct AcknowledgingRpcHandler;
+
+impl MockRpcHandler for AcknowledgingRpcHandler {
+    fn handle_request(
+        &self,
+        _peer_network_id: &PeerNetworkId,
+        request: &ConsensusObserverRequest,
+    ) -> Result<ConsensusObserverResponse, RpcError> {
+        let response = match request {
+            ConsensusObserverRequest::Subscribe
+            | ConsensusObserverRequest::SubscribeWithOptions(_) => {
+                ConsensusObserverResponse::SubscribeAck
+            },
+            ConsensusObserverRequest::Unsubscribe => ConsensusObserverResponse::UnsubscribeAck,
+            ConsensusObserverRequest::AcknowledgePayloads(_) => {
+                ConsensusObserverResponse::AcknowledgePayloadsAck
+            },
+            ConsensusObserverRequest::GetLatestCommit => {
+                ConsensusObserverResponse::LatestCommit(None)
+            },
+            ConsensusObserverRequest::GetOrderedBlocks { .. } => {
+                ConsensusObserverResponse::OrderedBlocks(vec![])
+            },
+            ConsensusObserverRequest::GetBlockPayload(_) => {
+                ConsensusObserverResponse::BlockPayload(None)
+            },
+        };
+        Ok(response)
+    }
+}
+
+/// A mock network for the consensus observer client. All messages sent by the
+/// client are recorded, and RPC requests are answered by the given RPC handler.
+/// This allows components that require a client (e.g., the subscription manager)
+/// to be tested without a real network.
+pub struct MockNetwork {
+    // The network ID of all peers
+    network_id: NetworkId,
+    // The consensus observer client (connected to the mock network)
+    consensus_observer_client:
+        Arc<ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>>,
+    // The peers and metadata of the mock network
+    peers_and_metadata: Arc<PeersAndMetadata>,
+    // The messages sent by the client (in the order they were sent)
+    sent_messages: Arc<Mutex<Vec<(PeerNetworkId, ConsensusObserverMessage)>>>,
+}
+
+impl MockNetwork {
+    /// Creates a new mock network that answers RPC requests using the given
+    /// handler. Note: this must be called from within a tokio runtime.
+    pub fn new(rpc_handler: Arc<dyn MockRpcHandler>) -> Self {
+        // Create the network client
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let (peer_manager_request_sender, peer_manager_request_receiver) =
+            aptos_channel::new(QueueStyle::FIFO, 100, None);
+        let (connection_request_sender, _connection_request_receiver) =
+            aptos_channel::new(QueueStyle::FIFO, 100, None);
+        let network_sender = NetworkSender::new(
+            PeerManagerRequestSender::new(peer_manager_request_sender),
+            ConnectionRequestSender::new(connection_request_sender),
+        );
+        let network_client = NetworkClient::new(
+            vec![ProtocolId::ConsensusObserver],
+            vec![ProtocolId::ConsensusObserverRpc],
+            hashmap! {network_id => network_sender},
+            peers_and_metadata.clone(),
+        );
+        let consensus_observer_client = Arc::new(ConsensusObserverClient::new(network_client));
+
+        // Spawn the mock peers (to record messages and respond to requests)
+        let sent_messages = Arc::new(Mutex::new(vec![]));
+        tokio::spawn(handle_peer_manager_requests(
+            network_id,
+            peer_manager_request_receiver,
+            sent_messages.clone(),
+            rpc_handler,
+        ));
+
+        Self {
+            network_id,
+            consensus_observer_client,
+            peers_and_metadata,
+            sent_messages,
+        }
+    }
+
+    /// Connects a new (random) peer with the given distance from the validators
+    pub fn add_peer(&self, distance_from_validators: u64) -> PeerNetworkId {
+        let peer_network_id = PeerNetworkId::new(self.network_id, PeerId::random());
+        connect_peer(
+            &self.peers_and_metadata,
+            peer_network_id,
+            distance_from_validators,
+        );
+        peer_network_id
+    }
+
+    /// Returns the consensus observer client (connected to the mock network)
+    pub fn consensus_observer_client(
+        &self,
+    ) -> Arc<ConsensusObserverClient<NetworkClient<ConsensusObserverMessage>>> {
+        self.consensus_observer_client.clone()
+    }
+
+    /// Disconnects the given peer
+    pub fn disconnect_peer(&self, peer_network_id: PeerNetworkId) {
+        disconnect_peer(&self.peers_and_metadata, peer_network_id);
+    }
+
+    /// Returns a copy of all messages sent by the client
+    pub fn get_sent_messages(&self) -> Vec<(PeerNetworkId, ConsensusObserverMessage)> {
+        self.sent_messages.lock().clone()
+    }
+
+    /// Returns a copy of all RPC requests sent by the client
+    pub fn get_sent_requests(&self) -> Vec<(PeerNetworkId, ConsensusObserverRequest)> {
+        self.sent_messages
+            .lock()
+            .iter()
+            .filter_map(|(peer_network_id, message)| match message {
+                ConsensusObserverMessage::Request(request) => {
+                    Some((*peer_network_id, request.clone()))
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the peers and metadata of the mock network
+    pub fn peers_and_metadata(&self) -> Arc<PeersAndMetadata> {
+        self.peers_and_metadata.clone()
+    }
+}
+
+/// A mock receiver for the response to an RPC request (sent via a response sender)
+pub struct MockResponseReceiver {
+    response_rx: oneshot::Receiver<Result<Bytes, RpcError>>,
+}
+
+impl MockResponseReceiver {
+    /// Returns the response sent to the receiver (if any). Panics
+    /// if the response could not be deserialized.
+    pub fn try_get_response(&mut self) -> Option<ConsensusObserverResponse> {
+        let response_bytes = match self.response_rx.try_recv() {
+            Ok(Some(response)) => response.expect("The RPC response is an error!"),
+            _ => return None, // No response was sent (yet)
+        };
+        match bcs::from_bytes(&response_bytes) {
+            Ok(ConsensusObserverMessage::Response(response)) => Some(response),
+            message => panic!("Unexpected RPC response message: {:?}", message),
+        }
+    }
+}
+
+/// Connects the given peer (with support for the consensus observer
+/// protocols) and sets its distance from the validators.
+pub fn connect_peer(
+    peers_and_metadata: &PeersAndMetadata,
+    peer_network_id: PeerNetworkId,
+    distance_from_validators: u64,
+) {
+    // Add the peer (with support for the consensus observer protocols)
+    let mut connection_metadata = ConnectionMetadata::mock(peer_network_id.peer_id());
+    connection_metadata
+        .application_protocols
+        .insert(ProtocolId::ConsensusObserver);
+    connection_metadata
+        .application_protocols
+        .insert(ProtocolId::ConsensusObserverRpc);
+    peers_and_metadata
+        .insert_connection_metadata(peer_network_id, connection_metadata)
+        .unwrap();
+
+    // Set the distance of the peer from the validators
+    let network_information_response = NetworkInformationResponse {
+        connected_peers: BTreeMap::new(),
+        distance_from_validators,
+    };
+    let peer_monitoring_metadata =
+        PeerMonitoringMetadata::new(None, None, Some(network_information_response), None, None);
+    peers_and_metadata
+        .update_peer_monitoring_metadata(peer_network_id, peer_monitoring_metadata)
+        .unwrap();
+}
+
+/// Creates a network message for the given RPC request (from the given peer),
+/// along with a receiver for the response sent by the message handler.
+pub fn create_rpc_request_message(
+    peer_network_id: PeerNetworkId,
+    request: ConsensusObserverRequest,
+) -> (NetworkMessage, MockResponseReceiver) {
+    let (response_sender, response_receiver) = create_response_sender();
+    let network_message = NetworkMessage {
+        peer_network_id,
+        protocol_id: Some(ProtocolId::ConsensusObserverRpc),
+        consensus_observer_message: ConsensusObserverMessage::Request(request),
+        response_sender: Some(response_sender),
+        num_message_bytes: 0,
+    };
+    (network_message, response_receiver)
+}
+
+/// Creates a response sender (and a receiver for the sent response)
+pub fn create_response_sender() -> (ResponseSender, MockResponseReceiver) {
+    let (response_tx, response_rx) = oneshot::channel();
+    (ResponseSender::new(response_tx), MockResponseReceiver {
+        response_rx,
+    })
+}
+
+/// Disconnects the given (previously connected) peer
+pub fn disconnect_peer(peers_and_metadata: &PeersAndMetadata, peer_network_id: PeerNetworkId) {
+    peers_and_metadata
+        .update_connection_state(peer_network_id, ConnectionState::Disconnected)
+        .unwrap();
+}
+
+/// Handles the requests sent over the mock network. All messages are recorded,
+/// and RPC requests are answered using the given RPC handler.
+async fn handle_peer_manager_requests(
+    network_id: NetworkId,
+    mut peer_manager_request_receiver: aptos_channel::Receiver<
+        (PeerId, ProtocolId),
+        PeerManagerRequest,
+    >,
+    sent_messages: Arc<Mutex<Vec<(PeerNetworkId, ConsensusObserverMessage)>>>,
+    rpc_handler: Arc<dyn MockRpcHandler>,
+) {
+    while let Some(peer_manager_request) = peer_manager_request_receiver.next().await {
+        match peer_manager_request {
+            PeerManagerRequest::SendDirectSend(peer_id, message) => {
+                // Deserialize and record the message
+                let consensus_observer_message = message
+                    .protocol_id
+                    .from_bytes::<ConsensusObserverMessage>(message.mdata.as_ref())
+                    .unwrap();
+                let peer_network_id = PeerNetworkId::new(network_id, peer_id);
+                sent_messages
+                    .lock()
+                    .push((peer_network_id, consensus_observer_message));
+            },
+            PeerManagerRequest::SendRpc(peer_id, outbound_rpc_request) => {
+                // Deserialize and record the request
+                let protocol_id = outbound_rpc_request.protocol_id;
+                let request = match protocol_id
+                    .from_bytes::<ConsensusObserverMessage>(outbound_rpc_request.data.as_ref())
+                {
+                    Ok(ConsensusObserverMessage::Request(request)) => request,
+                    message => panic!("Unexpected RPC message sent to peer: {:?}", message),
+                };
+                let peer_network_id = PeerNetworkId::new(network_id, peer_id);
+                sent_messages.lock().push((
+                    peer_network_id,
+                    ConsensusObserverMessage::Request(request.clone()),
+                ));
+
+                // Respond to the request (using the RPC handler)
+                let result =
+                    rpc_handler
+                        .handle_request(&peer_network_id, &request)
+                        .map(|response| {
+                            let response_bytes = protocol_id
+                                .to_bytes(&ConsensusObserverMessage::Response(response))
+                                .unwrap();
+                            Bytes::from(response_bytes)
+                        });
+                let _ = outbound_rpc_request.res_tx.send(result);
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_network() {
+        // Create a mock network and connect a peer
+        let mock_network = MockNetwork::new(Arc::new(AcknowledgingRpcHandler));
+        let peer_network_id = mock_network.add_peer(0);
+        assert!(mock_network
+            .peers_and_metadata()
+            .get_connected_peers_and_metadata()
+            .unwrap()
+            .contains_key(&peer_network_id));
+
+        // Send a subscription request and verify that it is acknowledged (and recorded)
+        let response = mock_network
+            .consensus_observer_client()
+            .send_rpc_request_to_peer(&peer_network_id, ConsensusObserverRequest::Subscribe, 1000)
+            .await
+            .unwrap();
+        assert_eq!(response, ConsensusObserverResponse::SubscribeAck);
+        assert_eq!(mock_network.get_sent_requests(), vec![(
+            peer_network_id,
+            ConsensusObserverRequest::Subscribe
+        )]);
+
+        // Disconnect the peer and verify that it is no longer connected
+        mock_network.disconnect_peer(peer_network_id);
+        assert!(!mock_network
+            .peers_and_metadata()
+            .get_connected_peers_and_metadata()
+            .unwrap()
+            .contains_key(&peer_network_id));
+    }
+
+    #[test]
+    fn test_create_rpc_request_message() {
+        // Create an RPC request message
+        let peer_network_id = PeerNetworkId::random();
+        let (network_message, mut response_receiver) =
+            create_rpc_request_message(peer_network_id, ConsensusObserverRequest::Unsubscribe);
+        assert_eq!(network_message.peer_network_id, peer_network_id);
+
+        // Verify that no response is received before the response is sent
+        assert!(response_receiver.try_get_response().is_none());
+
+        // Send the response and verify that it is received
+        network_message
+            .response_sender
+            .unwrap()
+            .send(ConsensusObserverResponse::UnsubscribeAck);
+        assert_eq!(
+            response_receiver.try_get_response(),
+            Some(ConsensusObserverResponse::UnsubscribeAck)
+        );
+    }
+}
@@ -0,0 +1,466 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    network_events::NetworkMessage,
+    network_message::{ConsensusObserverDirectSend, ConsensusObserverMessage, OrderedBlock},
+    publisher::ConsensusPublisher,
+    test_harness::{create_genesis_block, ObserverTestHarness, PublisherLink, GENESIS_EPOCH},
+};
+use aptos_channels::{aptos_channel, message_queues::QueueStyle};
+use aptos_config::{
+    config::ConsensusObserverConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_infallible::Mutex;
+use aptos_network::{
+    application::{interface::NetworkClient, storage::PeersAndMetadata},
+    peer_manager::{ConnectionRequestSender, PeerManagerRequest, PeerManagerRequestSender},
+    protocols::{
+        network::{NetworkSender, NewNetworkSender},
+        wire::handshake::v1::ProtocolId,
+    },
+    transport::ConnectionMetadata,
+};
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::{
+    block_info::{BlockInfo, Round},
+    PeerId,
+};
+use futures::{channel::mpsc, StreamExt};
+use maplit::hashmap;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::time::timeout;
+
+/// The number of messages published for each block (the payload,
+/// the ordered block and the commit decision).
+const NUM_MESSAGES_PER_BLOCK: usize = 3;
+
+/// The maximum time to wait for the publisher messages to be routed
+const MAX_ROUTING_WAIT_TIME_SECS: u64 = 10;
+
+/// The seed of the RNG used to drop messages (this keeps the drops deterministic)
+const SIMULATION_RNG_SEED: u64 = 0;
+
+/// The conditions of the simulated link between the publisher and an observer.
+/// By default, messages are delivered immediately, and in the order they were sent.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LinkConditions {
+    /// The probability (between 0 and 1) that each message is dropped
+    pub drop_probability: f64,
+    /// If set, all messages are dropped (i.e., the observer is partitioned)
+    pub partitioned: bool,
+    /// The (simulated) time before each message is delivered
+    pub delay: Duration,
+    /// If set, messages that become deliverable at the same time are
+    /// delivered in the reverse order to which they were sent.
+    pub reorder: bool,
+}
+
+/// A message sent by the publisher that hasn't been delivered to the observer yet
+struct InFlightMessage {
+    delivery_time: Instant,
+    network_message: NetworkMessage,
+}
+
+/// The simulated link between the publisher and an observer
+#[derive(Default)]
+struct SimulatedLink {
+    link_conditions: LinkConditions,
+    in_flight_messages: Vec<InFlightMessage>,
+    num_dropped_messages: u64,
+}
+
+/// The simulated links between the publisher and all observers
+struct SimulatedLinks {
+    // The links (indexed by the observer peer ID)
+    links: HashMap<PeerId, SimulatedLink>,
+    // The total number of messages routed by the network (including dropped messages)
+    num_routed_messages: usize,
+    // The RNG used to drop messages
+    rng: StdRng,
+}
+
+impl SimulatedLinks {
+    fn new() -> Self {
+        Self {
+            links: HashMap::new(),
+            num_routed_messages: 0,
+            rng: StdRng::seed_from_u64(SIMULATION_RNG_SEED),
+        }
+    }
+
+    /// Routes the given message to the link of the given observer. The message
+    /// is dropped (or delayed) according to the conditions of the link.
+    fn route_message(&mut self, peer_id: PeerId, network_message: NetworkMessage, now: Instant) {
+        self.num_routed_messages += 1;
+        let link = match self.links.get_mut(&peer_id) {
+            Some(link) => link,
+            None => return, // The message is not for an observer
+        };
+
+        // Drop the message if the link is partitioned (or if the message is unlucky)
+        let link_conditions = link.link_conditions;
+        if link_conditions.partitioned
+            || (link_conditions.drop_probability > 0.0
+                && self.rng.gen_bool(link_conditions.drop_probability))
+        {
+            link.num_dropped_messages += 1;
+            return;
+        }
+
+        // Otherwise, hold the message until it is delivered
+        link.in_flight_messages.push(InFlightMessage {
+            delivery_time: now + link_conditions.delay,
+            network_message,
+        });
+    }
+
+    /// Removes and returns the messages that can be delivered to the given
+    /// observer at the given time (in the order they should be delivered).
+    fn take_deliverable_messages(&mut self, peer_id: &PeerId, now: Instant) -> Vec<NetworkMessage> {
+        let link = match self.links.get_mut(peer_id) {
+            Some(link) => link,
+            None => return vec![],
+        };
+
+        // Split the in-flight messages into deliverable and pending messages
+        let (deliverable_messages, pending_messages): (Vec<_>, Vec<_>) = link
+            .in_flight_messages
+            .drain(..)
+            .partition(|message| message.delivery_time <= now);
+        link.in_flight_messages = pending_messages;
+
+        // Order the deliverable messages (according to the link conditions)
+        let mut network_messages: Vec<_> = deliverable_messages
+            .into_iter()
+            .map(|message| message.network_message)
+            .collect();
+        if link.link_conditions.reorder {
+            network_messages.reverse();
+        }
+        network_messages
+    }
+}
+
+/// An in-process network containing a single (real) consensus publisher and
+/// several observers (each driven by a test harness). The publisher's outbound
+/// messages are routed to the observers over an in-memory network, and the
+/// observer subscription requests are forwarded to the publisher.
+///
+/// All observers share a single mock time service, and messages are only
+/// delivered when the simulation is driven (e.g., when a block is published, or
+/// time is advanced). Each link can drop, delay and reorder messages, so that
+/// multi-node scenarios (e.g., partitions and epoch changes) are deterministic.
+pub struct SimulatedObserverNetwork {
+    // The consensus publisher
+    consensus_publisher: ConsensusPublisher,
+    // The peer ID of the publisher (as seen by the observers)
+    publisher_peer_network_id: PeerNetworkId,
+
+    // The observers (and their peer IDs, as seen by the publisher)
+    observers: Vec<(PeerNetworkId, ObserverTestHarness)>,
+    // The simulated links between the publisher and the observers
+    simulated_links: Arc<Mutex<SimulatedLinks>>,
+
+    // The root block shared by all observers
+    genesis_block: BlockInfo,
+    // The current epoch of the published blocks
+    current_epoch: u64,
+    // The mock time service shared by all observers
+    time_service: TimeService,
+}
+
+impl SimulatedObserverNetwork {
+    /// Creates a new simulated network with the given number of observers.
+    /// Note: this must be called from within a tokio runtime.
+    pub fn new(num_observers: usize, consensus_observer_config: ConsensusObserverConfig) -> Self {
+        // Create the publisher network client
+        let network_id = NetworkId::Public;
+        let peers_and_metadata = PeersAndMetadata::new(&[network_id]);
+        let (peer_manager_request_sender, peer_manager_request_receiver) =
+            aptos_channel::new(QueueStyle::FIFO, 1000, None);
+        let (connection_request_sender, _connection_request_receiver) =
+            aptos_channel::new(QueueStyle::FIFO, 100, None);
+        let network_sender = NetworkSender::new(
+            PeerManagerRequestSender::new(peer_manager_request_sender),
+            ConnectionRequestSender::new(connection_request_sender),
+        );
+        let network_client = NetworkClient::new(
+            vec![ProtocolId::ConsensusObserver],
+            vec![ProtocolId::ConsensusObserverRpc],
+            hashmap! {network_id => network_sender},
+            peers_and_metadata.clone(),
+        );
+
+        // Create and start the consensus publisher
+        let (consensus_publisher, outbound_message_receiver) =
+            ConsensusPublisher::new(network_client, consensus_observer_config.clone());
+        tokio::spawn(consensus_publisher.clone().start(outbound_message_receiver));
+
+        // Create the observers (all observers share the same genesis block and clock)
+        let genesis_block = create_genesis_block();
+        let time_service = TimeService::mock();
+        let publisher_peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+        let mut simulated_links = SimulatedLinks::new();
+        let mut observers = vec![];
+        for _ in 0..num_observers {
+            // Connect the observer to the publisher
+            let observer_peer_network_id = PeerNetworkId::new(network_id, PeerId::random());
+            let mut connection_metadata =
+                ConnectionMetadata::mock(observer_peer_network_id.peer_id());
+            connection_metadata
+                .application_protocols
+                .insert(ProtocolId::ConsensusObserver);
+            peers_and_metadata
+                .insert_connection_metadata(observer_peer_network_id, connection_metadata)
+                .unwrap();
+
+            // Create the observer (and link it to the publisher)
+            let publisher_link = PublisherLink {
+                consensus_publisher: consensus_publisher.clone(),
+                observer_peer_network_id,
+            };
+            let harness = ObserverTestHarness::new_with_time_service(
+                consensus_observer_config.clone(),
+                genesis_block.clone(),
+                Some(publisher_link),
+                time_service.clone(),
+            );
+            harness.connect_publisher_peer(publisher_peer_network_id, 0);
+
+            // Save the observer (and create its link)
+            simulated_links
+                .links
+                .insert(observer_peer_network_id.peer_id(), SimulatedLink::default());
+            observers.push((observer_peer_network_id, harness));
+        }
+
+        // Spawn the in-memory network (to route publisher messages to the observers)
+        let simulated_links = Arc::new(Mutex::new(simulated_links));
+        tokio::spawn(route_publisher_messages(
+            publisher_peer_network_id,
+            peer_manager_request_receiver,
+            simulated_links.clone(),
+            time_service.clone(),
+        ));
+
+        Self {
+            consensus_publisher,
+            publisher_peer_network_id,
+            observers,
+            simulated_links,
+            genesis_block,
+            current_epoch: GENESIS_EPOCH,
+            time_service,
+        }
+    }
+
+    /// Advances the shared mock time by the given duration, and delivers
+    /// all messages that become deliverable. Returns the number of
+    /// delivered messages.
+    pub async fn advance_time(&mut self, duration: Duration) -> usize {
+        self.time_service.clone().into_mock().advance(duration);
+        self.deliver_messages().await
+    }
+
+    /// Checks the progress of all observers (e.g., to subscribe to the publisher)
+    pub async fn check_progress(&mut self) {
+        for (_, harness) in self.observers.iter_mut() {
+            harness.check_progress().await;
+        }
+    }
+
+    /// Returns the consensus publisher
+    pub fn consensus_publisher(&self) -> &ConsensusPublisher {
+        &self.consensus_publisher
+    }
+
+    /// Returns the current epoch of the published blocks
+    pub fn current_epoch(&self) -> u64 {
+        self.current_epoch
+    }
+
+    /// Delivers all in-flight messages that are deliverable at the current
+    /// (mock) time, and waits for the observers to process them. Observers
+    /// are driven in order. Returns the number of delivered messages.
+    pub async fn deliver_messages(&mut self) -> usize {
+        let now = self.time_service.now();
+        let mut num_delivered_messages = 0;
+        for (observer_peer_network_id, harness) in self.observers.iter_mut() {
+            // Take the deliverable messages for the observer
+            let network_messages = self
+                .simulated_links
+                .lock()
+                .take_deliverable_messages(&observer_peer_network_id.peer_id(), now);
+
+            // Deliver the messages and wait for the observer to process them
+            for network_message in network_messages {
+                harness.send_network_message(network_message).await;
+                num_delivered_messages += 1;
+            }
+        }
+        num_delivered_messages
+    }
+
+    /// Returns the root block shared by all observers
+    pub fn genesis_block(&self) -> BlockInfo {
+        self.genesis_block.clone()
+    }
+
+    /// Returns the number of messages dropped by the link to the given observer
+    pub fn get_num_dropped_messages(&self, observer_peer_network_id: &PeerNetworkId) -> u64 {
+        self.simulated_links
+            .lock()
+            .links
+            .get(&observer_peer_network_id.peer_id())
+            .map(|link| link.num_dropped_messages)
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of messages in flight to the given observer
+    pub fn get_num_in_flight_messages(&self, observer_peer_network_id: &PeerNetworkId) -> usize {
+        self.simulated_links
+            .lock()
+            .links
+            .get(&observer_peer_network_id.peer_id())
+            .map(|link| link.in_flight_messages.len())
+            .unwrap_or(0)
+    }
+
+    /// Returns the observers (and their peer IDs, as seen by the publisher)
+    pub fn observers(&self) -> &[(PeerNetworkId, ObserverTestHarness)] {
+        &self.observers
+    }
+
+    /// Returns the observers (and their peer IDs) as mutable (e.g., to drive them directly)
+    pub fn observers_mut(&mut self) -> &mut [(PeerNetworkId, ObserverTestHarness)] {
+        &mut self.observers
+    }
+
+    /// Publishes the payload, ordered block and commit decision for a new block
+    /// (extending the given parent) in the current epoch, and delivers all
+    /// deliverable messages to the observers (according to the link conditions).
+    /// Returns the published ordered block.
+    pub async fn publish_block(&mut self, parent_block: &BlockInfo, round: Round) -> OrderedBlock {
+        // Create the block messages (all observers share the same validators)
+        let (_, harness) = self.observers.first().expect("No observers were created!");
+        let ordered_block = harness.create_ordered_block(parent_block, self.current_epoch, round);
+        let messages = vec![
+            harness.create_block_payload_message(&ordered_block),
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block.clone()),
+            ConsensusObserverDirectSend::CommitDecision(
+                harness.create_commit_decision(&ordered_block),
+            ),
+        ];
+
+        // Publish the messages to all subscribers
+        let num_subscribers = self.consensus_publisher.get_active_subscribers().len();
+        let num_routed_messages = self.simulated_links.lock().num_routed_messages;
+        for message in messages {
+            self.consensus_publisher.publish_message(message).await;
+        }
+
+        // Wait for the network to route all messages (and deliver them)
+        self.wait_for_routed_messages(
+            num_routed_messages + num_subscribers * NUM_MESSAGES_PER_BLOCK,
+        )
+        .await;
+        self.deliver_messages().await;
+
+        ordered_block
+    }
+
+    /// Returns the peer ID of the publisher (as seen by the observers)
+    pub fn publisher_peer_network_id(&self) -> PeerNetworkId {
+        self.publisher_peer_network_id
+    }
+
+    /// Sets the conditions of the link between the publisher and the given observer
+    pub fn set_link_conditions(
+        &self,
+        observer_peer_network_id: &PeerNetworkId,
+        link_conditions: LinkConditions,
+    ) {
+        self.simulated_links
+            .lock()
+            .links
+            .get_mut(&observer_peer_network_id.peer_id())
+            .expect("The observer link was not found!")
+            .link_conditions = link_conditions;
+    }
+
+    /// Starts the genesis epoch for all observers
+    pub async fn start_epoch(&mut self) {
+        for (_, harness) in self.observers.iter_mut() {
+            harness.start_epoch(GENESIS_EPOCH).await;
+        }
+    }
+
+    /// Ends the current epoch and moves the published blocks to the next epoch.
+    /// The epoch proof is made known to all observers (along with the
+    /// reconfiguration), so that the observers can verify the first commit
+    /// decision of the next epoch (and sync to it). Returns the next epoch.
+    pub fn start_next_epoch(&mut self) -> u64 {
+        let next_epoch = self.current_epoch + 1;
+        for (_, harness) in self.observers.iter() {
+            harness.store_epoch_ending_ledger_info(self.current_epoch);
+            harness.notify_reconfiguration(next_epoch);
+        }
+        self.current_epoch = next_epoch;
+        next_epoch
+    }
+
+    /// Waits for the network to route the given total number of messages
+    async fn wait_for_routed_messages(&self, expected_num_routed_messages: usize) {
+        timeout(Duration::from_secs(MAX_ROUTING_WAIT_TIME_SECS), async {
+            while self.simulated_links.lock().num_routed_messages < expected_num_routed_messages {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for the publisher messages to be routed!");
+    }
+}
+
+/// Routes the messages sent by the publisher to the observer links (over the in-memory network)
+async fn route_publisher_messages(
+    publisher_peer_network_id: PeerNetworkId,
+    mut peer_manager_request_receiver: aptos_channel::Receiver<
+        (PeerId, ProtocolId),
+        PeerManagerRequest,
+    >,
+    simulated_links: Arc<Mutex<SimulatedLinks>>,
+    time_service: TimeService,
+) {
+    while let Some(peer_manager_request) = peer_manager_request_receiver.next().await {
+        // The publisher only sends direct send messages to observers
+        let (peer_id, message) = match peer_manager_request {
+            PeerManagerRequest::SendDirectSend(peer_id, message) => (peer_id, message),
+            PeerManagerRequest::SendRpc(peer_id, _) => {
+                panic!("Unexpected RPC request sent to observer: {}", peer_id)
+            },
+        };
+
+        // Deserialize the message and route it to the observer link
+        let protocol_id = message.protocol_id;
+        let consensus_observer_message = protocol_id
+            .from_bytes::<ConsensusObserverMessage>(message.mdata.as_ref())
+            .unwrap();
+        let network_message = NetworkMessage {
+            peer_network_id: publisher_peer_network_id,
+            protocol_id: Some(protocol_id),
+            consensus_observer_message,
+            response_sender: None,
+            num_message_bytes: message.mdata.len(),
+        };
+        simulated_links
+            .lock()
+            .route_message(peer_id, network_message, time_service.now());
+    }
+}
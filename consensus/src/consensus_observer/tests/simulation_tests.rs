@@ -0,0 +1,349 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic multi-node scenarios for the consensus observer. Each scenario
+//! drives a real publisher and several observers over the simulated network,
+//! and injects link faults (e.g., drops, delays and reorderings) along the way.
+
+use crate::consensus_observer::{
+    event_journal::ObserverEvent,
+    test_harness::{ExecutionClientCall, ObserverTestHarness, GENESIS_EPOCH},
+    tests::simulated_network::{LinkConditions, SimulatedObserverNetwork},
+};
+use aptos_config::config::ConsensusObserverConfig;
+use aptos_types::block_info::BlockInfo;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_multi_node_smoke() {
+    // Create a simulated network with several observers and start the genesis epoch
+    let num_observers = 4;
+    let mut simulated_network =
+        SimulatedObserverNetwork::new(num_observers, ConsensusObserverConfig::default());
+    simulated_network.start_epoch().await;
+
+    // Verify that all observers subscribe to the publisher
+    simulated_network.check_progress().await;
+    let active_subscribers = simulated_network
+        .consensus_publisher()
+        .get_active_subscribers();
+    assert_eq!(active_subscribers.len(), num_observers);
+    for (observer_peer_network_id, _) in simulated_network.observers() {
+        assert!(active_subscribers.contains(observer_peer_network_id));
+    }
+
+    // Drive a synthetic block stream through the publisher
+    let num_blocks = 20;
+    let mut parent_block = simulated_network.genesis_block();
+    let mut published_blocks = vec![];
+    for round in 1..=num_blocks {
+        let ordered_block = simulated_network.publish_block(&parent_block, round).await;
+        parent_block = ordered_block.proof_block_info().clone();
+        published_blocks.push(parent_block.clone());
+    }
+
+    // Verify that all observers converged to the last published block
+    for (_, harness) in simulated_network.observers() {
+        assert_eq!(
+            harness.get_latest_ledger_info().commit_info(),
+            &parent_block
+        );
+        assert_eq!(harness.execution_client().get_num_pending_commits(), 0);
+
+        // Verify that each block was finalized and committed (in order)
+        let mut expected_calls = vec![ExecutionClientCall::StartEpoch(GENESIS_EPOCH)];
+        for block_info in published_blocks.iter() {
+            expected_calls.push(ExecutionClientCall::FinalizeOrder(block_info.clone()));
+            expected_calls.push(ExecutionClientCall::SendCommitDecision(block_info.clone()));
+        }
+        assert_eq!(harness.execution_client().get_calls(), expected_calls);
+    }
+}
+
+#[tokio::test]
+async fn test_partitioned_observer_syncs_after_heal() {
+    // Create a simulated network and subscribe all observers to the publisher
+    let mut simulated_network = create_simulated_network_and_subscribe(3).await;
+
+    // Partition the first observer and publish several blocks
+    let partitioned_observer = simulated_network.observers()[0].0;
+    simulated_network.set_link_conditions(&partitioned_observer, LinkConditions {
+        partitioned: true,
+        ..LinkConditions::default()
+    });
+    let num_partitioned_blocks = 3;
+    let genesis_block = simulated_network.genesis_block();
+    let parent_block = publish_blocks(
+        &mut simulated_network,
+        &genesis_block,
+        1,
+        num_partitioned_blocks,
+    )
+    .await;
+
+    // Verify that all messages to the partitioned observer were dropped
+    assert_eq!(
+        simulated_network.get_num_dropped_messages(&partitioned_observer),
+        num_partitioned_blocks * 3
+    );
+    let (_, partitioned_harness) = &simulated_network.observers()[0];
+    assert_eq!(partitioned_harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH)
+    ]);
+
+    // Heal the partition and publish the next block
+    simulated_network.set_link_conditions(&partitioned_observer, LinkConditions::default());
+    let next_round = num_partitioned_blocks + 1;
+    let ordered_block = simulated_network
+        .publish_block(&parent_block, next_round)
+        .await;
+    let block_info = ordered_block.proof_block_info().clone();
+
+    // Verify that the partitioned observer falls back to state sync (as the parent is missing)
+    let (_, partitioned_harness) = &mut simulated_network.observers_mut()[0];
+    let (epoch, round) = partitioned_harness.wait_for_sync_notification().await;
+    assert_eq!((epoch, round), (GENESIS_EPOCH, next_round));
+    assert!(partitioned_harness
+        .execution_client()
+        .get_calls()
+        .contains(&ExecutionClientCall::SyncTo(block_info.clone())));
+
+    // Publish another block and verify that all observers converge
+    partitioned_harness.execution_client().clear_calls();
+    let last_block = publish_blocks(&mut simulated_network, &block_info, next_round + 1, 1).await;
+    verify_observers_converged(&simulated_network, &last_block);
+    let (_, partitioned_harness) = &simulated_network.observers()[0];
+    assert_eq!(partitioned_harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::FinalizeOrder(last_block.clone()),
+        ExecutionClientCall::SendCommitDecision(last_block),
+    ]);
+}
+
+#[tokio::test]
+async fn test_delayed_observer_catches_up() {
+    // Create a simulated network and subscribe all observers to the publisher
+    let mut simulated_network = create_simulated_network_and_subscribe(3).await;
+
+    // Delay all messages to the first observer and publish several blocks
+    let delayed_observer = simulated_network.observers()[0].0;
+    let link_delay = Duration::from_millis(100);
+    simulated_network.set_link_conditions(&delayed_observer, LinkConditions {
+        delay: link_delay,
+        ..LinkConditions::default()
+    });
+    let num_blocks = 3;
+    let genesis_block = simulated_network.genesis_block();
+    let last_block = publish_blocks(&mut simulated_network, &genesis_block, 1, num_blocks).await;
+
+    // Verify that the messages to the delayed observer are still in flight
+    let num_messages = num_blocks as usize * 3;
+    assert_eq!(
+        simulated_network.get_num_in_flight_messages(&delayed_observer),
+        num_messages
+    );
+    let (_, delayed_harness) = &simulated_network.observers()[0];
+    assert_eq!(delayed_harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::StartEpoch(GENESIS_EPOCH)
+    ]);
+
+    // Elapse part of the delay and verify that no messages are delivered
+    assert_eq!(simulated_network.advance_time(link_delay / 2).await, 0);
+
+    // Elapse the rest of the delay and verify that all messages are delivered (in order)
+    assert_eq!(
+        simulated_network.advance_time(link_delay / 2).await,
+        num_messages
+    );
+    assert_eq!(
+        simulated_network.get_num_in_flight_messages(&delayed_observer),
+        0
+    );
+    verify_observers_converged(&simulated_network, &last_block);
+
+    // Verify that the delayed observer didn't need to fall back to state sync
+    let (_, delayed_harness) = &simulated_network.observers()[0];
+    assert!(!get_journal_events(delayed_harness)
+        .iter()
+        .any(|event| matches!(event, ObserverEvent::SyncStarted { .. })));
+}
+
+#[tokio::test]
+async fn test_reordered_messages_converge() {
+    // Create a simulated network and subscribe all observers to the publisher
+    let mut simulated_network = create_simulated_network_and_subscribe(2).await;
+
+    // Delay (and reorder) all messages to the first observer, and publish a block
+    let reordered_observer = simulated_network.observers()[0].0;
+    let link_delay = Duration::from_millis(100);
+    simulated_network.set_link_conditions(&reordered_observer, LinkConditions {
+        delay: link_delay,
+        reorder: true,
+        ..LinkConditions::default()
+    });
+    let genesis_block = simulated_network.genesis_block();
+    let first_block = publish_blocks(&mut simulated_network, &genesis_block, 1, 1).await;
+
+    // Deliver the messages (the commit decision arrives before the ordered block)
+    assert_eq!(simulated_network.advance_time(link_delay).await, 3);
+
+    // Verify that the reordered observer syncs to the commit decision
+    let (_, reordered_harness) = &mut simulated_network.observers_mut()[0];
+    let (epoch, round) = reordered_harness.wait_for_sync_notification().await;
+    assert_eq!((epoch, round), (GENESIS_EPOCH, 1));
+    assert!(reordered_harness
+        .execution_client()
+        .get_calls()
+        .contains(&ExecutionClientCall::SyncTo(first_block.clone())));
+
+    // Restore the link and verify that all observers converge on the next block
+    reordered_harness.execution_client().clear_calls();
+    simulated_network.set_link_conditions(&reordered_observer, LinkConditions::default());
+    let last_block = publish_blocks(&mut simulated_network, &first_block, 2, 1).await;
+    verify_observers_converged(&simulated_network, &last_block);
+    let (_, reordered_harness) = &simulated_network.observers()[0];
+    assert_eq!(reordered_harness.execution_client().get_calls(), vec![
+        ExecutionClientCall::FinalizeOrder(last_block.clone()),
+        ExecutionClientCall::SendCommitDecision(last_block),
+    ]);
+}
+
+#[tokio::test]
+async fn test_lossy_links_are_deterministic() {
+    // Run the same lossy scenario twice, and record the number of dropped messages
+    let mut num_dropped_messages = vec![];
+    for _ in 0..2 {
+        // Create a simulated network and make the link to the first observer lossy
+        let mut simulated_network = create_simulated_network_and_subscribe(2).await;
+        let lossy_observer = simulated_network.observers()[0].0;
+        simulated_network.set_link_conditions(&lossy_observer, LinkConditions {
+            drop_probability: 0.5,
+            ..LinkConditions::default()
+        });
+
+        // Publish several blocks and verify the lossless observer is unaffected
+        let genesis_block = simulated_network.genesis_block();
+        let last_block = publish_blocks(&mut simulated_network, &genesis_block, 1, 10).await;
+        let (_, lossless_harness) = &simulated_network.observers()[1];
+        assert_eq!(
+            lossless_harness.get_latest_ledger_info().commit_info(),
+            &last_block
+        );
+        assert_eq!(
+            simulated_network.get_num_dropped_messages(&simulated_network.observers()[1].0),
+            0
+        );
+
+        // Record the number of dropped messages
+        num_dropped_messages.push(simulated_network.get_num_dropped_messages(&lossy_observer));
+    }
+
+    // Verify that some messages were dropped, and that the drops are identical across runs
+    assert!(num_dropped_messages[0] > 0);
+    assert_eq!(num_dropped_messages[0], num_dropped_messages[1]);
+}
+
+#[tokio::test]
+async fn test_epoch_change() {
+    // Create a simulated network and subscribe all observers to the publisher
+    let mut simulated_network = create_simulated_network_and_subscribe(3).await;
+
+    // Publish several blocks in the genesis epoch
+    let genesis_block = simulated_network.genesis_block();
+    let parent_block = publish_blocks(&mut simulated_network, &genesis_block, 1, 2).await;
+    verify_observers_converged(&simulated_network, &parent_block);
+
+    // End the genesis epoch and publish the first block of the next epoch
+    let next_epoch = simulated_network.start_next_epoch();
+    assert_eq!(simulated_network.current_epoch(), next_epoch);
+    let ordered_block = simulated_network.publish_block(&parent_block, 1).await;
+    let epoch_block = ordered_block.proof_block_info().clone();
+
+    // Verify that all observers sync to the new epoch (and start it)
+    for (_, harness) in simulated_network.observers_mut() {
+        let (epoch, round) = harness.wait_for_sync_notification().await;
+        assert_eq!((epoch, round), (next_epoch, 1));
+        assert!(get_journal_events(harness)
+            .contains(&ObserverEvent::EpochStarted { epoch: next_epoch }));
+        assert!(harness.execution_client().get_calls().ends_with(&[
+            ExecutionClientCall::SyncTo(epoch_block.clone()),
+            ExecutionClientCall::EndEpoch,
+            ExecutionClientCall::StartEpoch(next_epoch),
+        ]));
+        harness.execution_client().clear_calls();
+    }
+
+    // Publish a block in the new epoch and verify that all observers commit it
+    let last_block = publish_blocks(&mut simulated_network, &epoch_block, 2, 1).await;
+    verify_observers_converged(&simulated_network, &last_block);
+    for (_, harness) in simulated_network.observers() {
+        assert_eq!(harness.execution_client().get_calls(), vec![
+            ExecutionClientCall::FinalizeOrder(last_block.clone()),
+            ExecutionClientCall::SendCommitDecision(last_block.clone()),
+        ]);
+    }
+}
+
+/// Creates a simulated network with the given number of observers, starts the
+/// genesis epoch and subscribes all observers to the publisher.
+async fn create_simulated_network_and_subscribe(num_observers: usize) -> SimulatedObserverNetwork {
+    let mut simulated_network =
+        SimulatedObserverNetwork::new(num_observers, ConsensusObserverConfig::default());
+    simulated_network.start_epoch().await;
+    simulated_network.check_progress().await;
+    assert_eq!(
+        simulated_network
+            .consensus_publisher()
+            .get_active_subscribers()
+            .len(),
+        num_observers
+    );
+
+    // Verify that all observers created a subscription to the publisher
+    let publisher_peer_network_id = simulated_network.publisher_peer_network_id();
+    for (_, harness) in simulated_network.observers() {
+        assert!(
+            get_journal_events(harness).contains(&ObserverEvent::SubscriptionCreated {
+                peer_network_id: publisher_peer_network_id,
+            })
+        );
+    }
+
+    simulated_network
+}
+
+/// Returns the events recorded in the event journal of the given observer
+fn get_journal_events(harness: &ObserverTestHarness) -> Vec<ObserverEvent> {
+    harness
+        .event_journal()
+        .get_journal_entries()
+        .into_iter()
+        .map(|journal_entry| journal_entry.event)
+        .collect()
+}
+
+/// Publishes the given number of blocks (starting at the given round and
+/// extending the given parent) and returns the last published block.
+async fn publish_blocks(
+    simulated_network: &mut SimulatedObserverNetwork,
+    parent_block: &BlockInfo,
+    first_round: u64,
+    num_blocks: u64,
+) -> BlockInfo {
+    let mut parent_block = parent_block.clone();
+    for round in first_round..first_round + num_blocks {
+        let ordered_block = simulated_network.publish_block(&parent_block, round).await;
+        parent_block = ordered_block.proof_block_info().clone();
+    }
+    parent_block
+}
+
+/// Verifies that all observers have committed the given block (and have no pending commits)
+fn verify_observers_converged(
+    simulated_network: &SimulatedObserverNetwork,
+    block_info: &BlockInfo,
+) {
+    for (_, harness) in simulated_network.observers() {
+        assert_eq!(harness.get_latest_ledger_info().commit_info(), block_info);
+        assert_eq!(harness.execution_client().get_num_pending_commits(), 0);
+    }
+}
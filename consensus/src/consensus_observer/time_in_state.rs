@@ -0,0 +1,140 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::metrics;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// The high-level states of the consensus observer
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ObserverState {
+    EpochTransition, // The observer is waiting for a new epoch to start
+    Searching,       // The observer has no active subscription
+    Subscribed,      // The observer has an active subscription (and is not syncing)
+    Syncing,         // The observer is waiting for state sync to complete
+}
+
+impl ObserverState {
+    /// Returns a summary label for the state
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ObserverState::EpochTransition => "epoch_transition",
+            ObserverState::Searching => "searching",
+            ObserverState::Subscribed => "subscribed",
+            ObserverState::Syncing => "syncing",
+        }
+    }
+}
+
+/// A simple tracker that records the cumulative time the
+/// observer has spent in each state (and exports it as metrics).
+pub struct TimeInStateTracker {
+    // The current state of the observer
+    current_state: ObserverState,
+    // The time at which the current state was last accounted for
+    last_update_time: Instant,
+
+    // The cumulative time spent in each state
+    cumulative_time_in_state: HashMap<ObserverState, Duration>,
+
+    // The time service (used to measure the time spent in each state)
+    time_service: TimeService,
+}
+
+impl TimeInStateTracker {
+    pub fn new(initial_state: ObserverState, time_service: TimeService) -> Self {
+        let last_update_time = time_service.now();
+        Self {
+            current_state: initial_state,
+            last_update_time,
+            cumulative_time_in_state: HashMap::new(),
+            time_service,
+        }
+    }
+
+    /// Returns the current state of the observer
+    pub fn get_current_state(&self) -> ObserverState {
+        self.current_state
+    }
+
+    /// Returns the cumulative time spent in the given state (as of the last update)
+    pub fn get_time_in_state(&self, state: &ObserverState) -> Duration {
+        self.cumulative_time_in_state
+            .get(state)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Accounts for the time spent in the current state and transitions
+    /// to the given state. Note: this can also be called with the current
+    /// state to periodically update the metrics for long-lived states.
+    pub fn update_state(&mut self, new_state: ObserverState) {
+        // Calculate the time spent in the current state (since the last update)
+        let time_now = self.time_service.now();
+        let time_in_state = time_now.duration_since(self.last_update_time);
+
+        // Update the cumulative time and the metrics for the current state
+        *self
+            .cumulative_time_in_state
+            .entry(self.current_state)
+            .or_default() += time_in_state;
+        metrics::increment_time_in_state(self.current_state.get_label(), time_in_state);
+
+        // Transition to the new state
+        self.current_state = new_state;
+        self.last_update_time = time_now;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_time_in_state() {
+        // Create a new time in state tracker
+        let time_service = TimeService::mock();
+        let mut time_in_state_tracker =
+            TimeInStateTracker::new(ObserverState::EpochTransition, time_service.clone());
+
+        // Spend some time in the epoch transition state and then transition to searching
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance(Duration::from_secs(5));
+        time_in_state_tracker.update_state(ObserverState::Searching);
+        assert_eq!(
+            time_in_state_tracker.get_current_state(),
+            ObserverState::Searching
+        );
+
+        // Spend some time searching and then transition to subscribed
+        mock_time_service.advance(Duration::from_secs(2));
+        time_in_state_tracker.update_state(ObserverState::Subscribed);
+
+        // Spend some time subscribed (with periodic updates)
+        for _ in 0..10 {
+            mock_time_service.advance(Duration::from_secs(1));
+            time_in_state_tracker.update_state(ObserverState::Subscribed);
+        }
+
+        // Transition to syncing and then back to subscribed
+        time_in_state_tracker.update_state(ObserverState::Syncing);
+        mock_time_service.advance(Duration::from_secs(3));
+        time_in_state_tracker.update_state(ObserverState::Subscribed);
+
+        // Verify the cumulative time spent in each state
+        for (state, expected_time_in_state) in [
+            (ObserverState::EpochTransition, 5),
+            (ObserverState::Searching, 2),
+            (ObserverState::Subscribed, 10),
+            (ObserverState::Syncing, 3),
+        ] {
+            assert_eq!(
+                time_in_state_tracker.get_time_in_state(&state),
+                Duration::from_secs(expected_time_in_state)
+            );
+        }
+    }
+}
@@ -0,0 +1,409 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::consensus_observer::{
+    data_exporter::{ObservedData, ObserverDataExporter},
+    logging::{LogEntry, LogSchema},
+    sse_export::{include_ordered_blocks, BlockSummary},
+};
+use aptos_logger::warn;
+use futures::{SinkExt, StreamExt};
+use hyper::{header, Body, Request, Response, StatusCode};
+use serde::Serialize;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::broadcast::{self, error::RecvError},
+};
+use tokio_tungstenite::{
+    tungstenite::{handshake::derive_accept_key, protocol::Role, Message},
+    WebSocketStream,
+};
+
+// The endpoint that streams observed commits over a WebSocket (served by the SSE export server)
+pub const WEBSOCKET_COMMITS_PATH: &str = "/commits/ws";
+
+// Useful string constants
+const CONNECTION_UPGRADE: &str = "upgrade";
+const INVALID_UPGRADE_MESSAGE: &str = "The request is not a valid WebSocket upgrade!";
+const UNSUPPORTED_VERSION_MESSAGE: &str = "The WebSocket version is not supported!";
+const WEBSOCKET_PROTOCOL: &str = "websocket";
+const WEBSOCKET_VERSION: &str = "13"; // The only version defined by RFC 6455
+
+/// A JSON event sent to the WebSocket clients. Each event is tagged
+/// with its type (e.g., `{"type": "commit_decision", ...}`).
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WebSocketEvent {
+    CommitDecision {
+        proof_block: BlockSummary,
+        num_signers: usize,
+    },
+    OrderedBlock {
+        blocks: Vec<BlockSummary>,
+        proof_block: BlockSummary,
+        num_signers: usize,
+    },
+}
+
+/// Upgrades the given request to a WebSocket connection that pushes verified
+/// commits (and their block metadata) to external monitoring tools (e.g.,
+/// finality dashboards). The WebSocket feed is served by the SSE export
+/// server, so that only a single HTTP server is required for both feeds.
+pub(crate) fn upgrade_to_websocket(
+    request: Request<Body>,
+    data_exporter: ObserverDataExporter,
+) -> Response<Body> {
+    // Verify the upgrade request and derive the accept key
+    let accept_key = match get_websocket_accept_key(&request) {
+        Ok(accept_key) => accept_key,
+        Err(response) => return response,
+    };
+
+    // Subscribe to the observed data (before the upgrade, to avoid missing any data)
+    let include_ordered_blocks = include_ordered_blocks(request.uri().query());
+    let observed_data_receiver = data_exporter.subscribe();
+
+    // Serve the WebSocket once the connection has been upgraded
+    tokio::spawn(async move {
+        match hyper::upgrade::on(request).await {
+            Ok(upgraded_connection) => {
+                let websocket =
+                    WebSocketStream::from_raw_socket(upgraded_connection, Role::Server, None).await;
+                serve_websocket(websocket, observed_data_receiver, include_ordered_blocks).await;
+            },
+            Err(error) => {
+                warn!(
+                    LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                        "Failed to upgrade the WebSocket export connection! Error: {:?}",
+                        error
+                    ))
+                );
+            },
+        }
+    });
+
+    // Accept the upgrade
+    Response::builder()
+        .status(StatusCode::SWITCHING_PROTOCOLS)
+        .header(header::CONNECTION, CONNECTION_UPGRADE)
+        .header(header::UPGRADE, WEBSOCKET_PROTOCOL)
+        .header(header::SEC_WEBSOCKET_ACCEPT, accept_key)
+        .body(Body::empty())
+        .unwrap_or_else(|error| {
+            let mut response = Response::new(Body::from(format!(
+                "Failed to create the response! Error: {:?}",
+                error
+            )));
+            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            response
+        })
+}
+
+/// Returns the accept key for the given WebSocket upgrade request. If the
+/// request is not a valid upgrade request (as defined by RFC 6455), the
+/// rejection response is returned instead: 400 (Bad Request) for malformed
+/// upgrades, and 426 (Upgrade Required) for unsupported WebSocket versions.
+fn get_websocket_accept_key(request: &Request<Body>) -> Result<String, Response<Body>> {
+    let headers = request.headers();
+
+    // Verify the connection is being upgraded (the header is a list of tokens)
+    let is_connection_upgrade = headers
+        .get(header::CONNECTION)
+        .and_then(|connection| connection.to_str().ok())
+        .map_or(false, |connection| {
+            connection
+                .split(',')
+                .any(|token| token.trim().eq_ignore_ascii_case(CONNECTION_UPGRADE))
+        });
+
+    // Verify the connection is being upgraded to a WebSocket
+    let is_websocket_upgrade = headers
+        .get(header::UPGRADE)
+        .and_then(|upgrade| upgrade.to_str().ok())
+        .map_or(false, |upgrade| {
+            upgrade.eq_ignore_ascii_case(WEBSOCKET_PROTOCOL)
+        });
+
+    // Get the WebSocket key
+    let websocket_key = match headers.get(header::SEC_WEBSOCKET_KEY) {
+        Some(websocket_key) if is_connection_upgrade && is_websocket_upgrade => websocket_key,
+        _ => {
+            let mut response = Response::new(Body::from(INVALID_UPGRADE_MESSAGE));
+            *response.status_mut() = StatusCode::BAD_REQUEST;
+            return Err(response);
+        },
+    };
+
+    // Verify the WebSocket version (the client must retry with a supported version)
+    let is_supported_version = headers
+        .get(header::SEC_WEBSOCKET_VERSION)
+        .map_or(false, |version| version == WEBSOCKET_VERSION);
+    if !is_supported_version {
+        let mut response = Response::new(Body::from(UNSUPPORTED_VERSION_MESSAGE));
+        *response.status_mut() = StatusCode::UPGRADE_REQUIRED;
+        response.headers_mut().insert(
+            header::SEC_WEBSOCKET_VERSION,
+            header::HeaderValue::from_static(WEBSOCKET_VERSION),
+        );
+        return Err(response);
+    }
+
+    Ok(derive_accept_key(websocket_key.as_bytes()))
+}
+
+/// Streams the observed data to the given WebSocket client (until the
+/// client disconnects, or the data exporter is dropped).
+async fn serve_websocket<S: AsyncRead + AsyncWrite + Unpin>(
+    websocket: WebSocketStream<S>,
+    mut observed_data_receiver: broadcast::Receiver<ObservedData>,
+    include_ordered_blocks: bool,
+) {
+    let (mut websocket_sender, mut websocket_receiver) = websocket.split();
+    loop {
+        tokio::select! {
+            result = observed_data_receiver.recv() => {
+                match result {
+                    Ok(observed_data) => {
+                        let event = create_websocket_event(observed_data, include_ordered_blocks);
+                        if let Some(event) = event {
+                            if websocket_sender.send(Message::Text(event)).await.is_err() {
+                                return; // The client has disconnected
+                            }
+                        }
+                    },
+                    Err(RecvError::Lagged(num_skipped_items)) => {
+                        warn!(
+                            LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                                "The WebSocket export stream is lagging! Skipped {} items!",
+                                num_skipped_items
+                            ))
+                        );
+                    },
+                    Err(RecvError::Closed) => break,
+                }
+            },
+            message = websocket_receiver.next() => {
+                match message {
+                    Some(Ok(message)) if !message.is_close() => {
+                        // Ignore all client messages (pings are answered automatically)
+                    },
+                    _ => return, // The client has closed the connection
+                }
+            },
+        }
+    }
+
+    // Close the connection gracefully
+    let _ = websocket_sender.close().await;
+}
+
+/// Transforms the given observed data into a JSON WebSocket event. Returns
+/// None if the data should not be sent to the client (e.g., block payloads).
+fn create_websocket_event(
+    observed_data: ObservedData,
+    include_ordered_blocks: bool,
+) -> Option<String> {
+    let websocket_event = match observed_data {
+        ObservedData::CommitDecision {
+            proof_block_info,
+            commit_proof,
+        } => WebSocketEvent::CommitDecision {
+            proof_block: BlockSummary::new(&proof_block_info),
+            num_signers: commit_proof.signatures().get_num_voters(),
+        },
+        ObservedData::OrderedBlock {
+            blocks,
+            proof_block_info,
+            ordered_proof,
+        } if include_ordered_blocks => WebSocketEvent::OrderedBlock {
+            blocks: blocks.iter().map(BlockSummary::new).collect(),
+            proof_block: BlockSummary::new(&proof_block_info),
+            num_signers: ordered_proof.signatures().get_num_voters(),
+        },
+        _ => return None, // The remaining data is not sent to WebSocket clients
+    };
+
+    // Serialize the event
+    match serde_json::to_string(&websocket_event) {
+        Ok(event) => Some(event),
+        Err(error) => {
+            warn!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Failed to serialize the WebSocket event! Error: {:?}",
+                    error
+                ))
+            );
+            None
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::consensus_observer::network_message::CommitDecision;
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        block_info::BlockInfo,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn test_create_websocket_event() {
+        // Create a commit decision event and verify the event contents
+        let block_info = BlockInfo::random_with_epoch(10, 20);
+        let websocket_event = create_websocket_event(
+            ObservedData::CommitDecision {
+                proof_block_info: block_info.clone(),
+                commit_proof: Arc::new(create_ledger_info(block_info.clone())),
+            },
+            false,
+        )
+        .unwrap();
+        let event_json: serde_json::Value = serde_json::from_str(&websocket_event).unwrap();
+        assert_eq!(event_json["type"], "commit_decision");
+        assert_eq!(event_json["proof_block"]["epoch"], 10);
+        assert_eq!(event_json["proof_block"]["round"], 20);
+        assert_eq!(event_json["proof_block"]["id"], block_info.id().to_hex());
+        assert_eq!(event_json["num_signers"], 0);
+
+        // Verify that ordered blocks are only sent if requested
+        let ordered_block = ObservedData::OrderedBlock {
+            blocks: vec![block_info.clone()],
+            proof_block_info: block_info.clone(),
+            ordered_proof: Arc::new(create_ledger_info(block_info)),
+        };
+        assert!(create_websocket_event(ordered_block.clone(), false).is_none());
+        let websocket_event = create_websocket_event(ordered_block, true).unwrap();
+        let event_json: serde_json::Value = serde_json::from_str(&websocket_event).unwrap();
+        assert_eq!(event_json["type"], "ordered_block");
+        assert_eq!(event_json["blocks"].as_array().unwrap().len(), 1);
+
+        // Verify that block payloads are never sent
+        let block_payload = ObservedData::BlockPayload {
+            block: BlockInfo::empty(),
+            num_transactions: 0,
+            transaction_limit: None,
+            transactions: Arc::new(vec![]),
+        };
+        assert!(create_websocket_event(block_payload, true).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_to_websocket() {
+        // Create a data exporter
+        let data_exporter = ObserverDataExporter::new(10);
+
+        // Send a request without the upgrade headers and verify the response
+        let request = Request::get(WEBSOCKET_COMMITS_PATH)
+            .body(Body::empty())
+            .unwrap();
+        let response = upgrade_to_websocket(request, data_exporter.clone());
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Send a valid upgrade request (with the sample key from RFC 6455)
+        let request = create_upgrade_request("keep-alive, Upgrade", Some(WEBSOCKET_VERSION));
+        let response = upgrade_to_websocket(request, data_exporter);
+
+        // Verify that the upgrade is accepted (with the expected accept key)
+        assert_eq!(response.status(), StatusCode::SWITCHING_PROTOCOLS);
+        assert_eq!(
+            response
+                .headers()
+                .get(header::SEC_WEBSOCKET_ACCEPT)
+                .unwrap(),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_to_websocket_missing_connection_upgrade() {
+        // Create a data exporter
+        let data_exporter = ObserverDataExporter::new(10);
+
+        // Send upgrade requests without the connection upgrade token
+        for connection in ["keep-alive", "close", "upgrades"] {
+            let request = create_upgrade_request(connection, Some(WEBSOCKET_VERSION));
+            let response = upgrade_to_websocket(request, data_exporter.clone());
+
+            // Verify that the requests are rejected as bad requests
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_to_websocket_unsupported_version() {
+        // Create a data exporter
+        let data_exporter = ObserverDataExporter::new(10);
+
+        // Send upgrade requests with missing and unsupported WebSocket versions
+        for websocket_version in [None, Some("8"), Some("14")] {
+            let request = create_upgrade_request(CONNECTION_UPGRADE, websocket_version);
+            let response = upgrade_to_websocket(request, data_exporter.clone());
+
+            // Verify that the requests are rejected (with the supported version)
+            assert_eq!(response.status(), StatusCode::UPGRADE_REQUIRED);
+            assert_eq!(
+                response
+                    .headers()
+                    .get(header::SEC_WEBSOCKET_VERSION)
+                    .unwrap(),
+                WEBSOCKET_VERSION
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serve_websocket() {
+        // Create a WebSocket connection (over an in-memory stream)
+        let (client_stream, server_stream) = tokio::io::duplex(64 * 1024);
+        let mut websocket_client =
+            WebSocketStream::from_raw_socket(client_stream, Role::Client, None).await;
+        let websocket_server =
+            WebSocketStream::from_raw_socket(server_stream, Role::Server, None).await;
+
+        // Serve the observed data to the WebSocket client
+        let data_exporter = ObserverDataExporter::new(10);
+        tokio::spawn(serve_websocket(
+            websocket_server,
+            data_exporter.subscribe(),
+            false,
+        ));
+
+        // Export a commit decision
+        let block_info = BlockInfo::random_with_epoch(0, 10);
+        let commit_decision = CommitDecision::new(create_ledger_info(block_info.clone()));
+        data_exporter.export_commit_decision(&commit_decision);
+
+        // Verify that the commit decision event is pushed to the client
+        let message = websocket_client.next().await.unwrap().unwrap();
+        let event_json: serde_json::Value =
+            serde_json::from_str(message.to_text().unwrap()).unwrap();
+        assert_eq!(event_json["type"], "commit_decision");
+        assert_eq!(event_json["proof_block"]["id"], block_info.id().to_hex());
+    }
+
+    /// Creates a ledger info (with an empty signature) for the given block
+    /// Creates a WebSocket upgrade request (with the sample key from RFC 6455)
+    /// using the given connection header and WebSocket version (if any).
+    fn create_upgrade_request(connection: &str, websocket_version: Option<&str>) -> Request<Body> {
+        let mut request_builder = Request::get(WEBSOCKET_COMMITS_PATH)
+            .header(header::CONNECTION, connection)
+            .header(header::UPGRADE, WEBSOCKET_PROTOCOL)
+            .header(header::SEC_WEBSOCKET_KEY, "dGhlIHNhbXBsZSBub25jZQ==");
+        if let Some(websocket_version) = websocket_version {
+            request_builder =
+                request_builder.header(header::SEC_WEBSOCKET_VERSION, websocket_version);
+        }
+        request_builder.body(Body::empty()).unwrap()
+    }
+
+    fn create_ledger_info(block_info: BlockInfo) -> LedgerInfoWithSignatures {
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(block_info, HashValue::random()),
+            AggregateSignature::empty(),
+        )
+    }
+}
@@ -4,16 +4,34 @@
 
 use crate::{
     consensus_observer::{
-        network_client::ConsensusObserverClient, network_events::ConsensusObserverNetworkEvents,
-        network_message::ConsensusObserverMessage, observer::ConsensusObserver,
+        archive_replay::ArchiveReplayBlockSource,
+        block_archive::BlockArchiveWriter,
+        builder::ObserverBuilder,
+        commit_journal::CommitJournalWriter,
+        data_exporter::ObserverDataExporter,
+        event_journal::ObserverEventJournal,
+        feature_flags::ObserverFeatureFlags,
+        network_client::ConsensusObserverClient,
+        network_events::ConsensusObserverNetworkEvents,
+        network_message::ConsensusObserverMessage,
+        observer_control::ObserverControlHandle,
+        observer_status::ObserverStatusHandle,
+        payload_store_db::ObserverPayloadDb,
+        peer_overrides::SubscriptionPeerOverrides,
         publisher::ConsensusPublisher,
+        publisher_runtime::PublisherOnlyRuntime,
+        sse_export::start_sse_export_server,
+        state_snapshot::ObserverStateSnapshotter,
+        storage::DbBackedObserverStorage,
+        supervisor::{ObserverSupervisor, PRIMARY_OBSERVER_INSTANCE},
+        sync_notifications::new_sync_notification_channel,
     },
     counters,
     epoch_manager::EpochManager,
     network::NetworkTask,
     network_interface::{ConsensusMsg, ConsensusNetworkClient},
     persistent_liveness_storage::StorageWriteProxy,
-    pipeline::execution_client::{DummyExecutionClient, ExecutionProxyClient, TExecutionClient},
+    pipeline::execution_client::ExecutionProxyClient,
     quorum_store::quorum_store_db::QuorumStoreDB,
     rand::rand_gen::storage::db::RandDb,
     state_computer::ExecutionProxy,
@@ -24,7 +42,10 @@ use crate::{
 use aptos_bounded_executor::BoundedExecutor;
 use aptos_config::config::NodeConfig;
 use aptos_consensus_notifications::ConsensusNotificationSender;
-use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
+use aptos_event_notifications::{
+    DbBackedOnChainConfig, ObservedCommitNotifier, ReconfigNotificationListener,
+    SyncedCommitNotificationListener,
+};
 use aptos_executor::block_executor::BlockExecutor;
 use aptos_logger::prelude::*;
 use aptos_mempool::QuorumStoreRequest;
@@ -40,6 +61,15 @@ use move_core_types::account_address::AccountAddress;
 use std::{collections::HashMap, sync::Arc};
 use tokio::runtime::Runtime;
 
+// The directory (within the storage directory) for the consensus observer block archive
+const CONSENSUS_OBSERVER_BLOCK_ARCHIVE_DIR: &str = "consensus_observer_block_archive";
+
+// The directory (within the storage directory) for the consensus observer commit journal
+const CONSENSUS_OBSERVER_COMMIT_JOURNAL_DIR: &str = "consensus_observer_commit_journal";
+
+// The directory (within the storage directory) for the consensus observer state snapshots
+const CONSENSUS_OBSERVER_STATE_SNAPSHOT_DIR: &str = "consensus_observer_state_snapshot";
+
 /// Helper function to start consensus based on configuration and return the runtime
 pub fn start_consensus(
     node_config: &NodeConfig,
@@ -87,7 +117,7 @@ pub fn start_consensus(
         consensus_network_client.clone(),
         bounded_executor.clone(),
         rand_storage.clone(),
-        node_config.consensus_observer,
+        node_config.consensus_observer.clone(),
         consensus_publisher.clone(),
     ));
 
@@ -118,7 +148,20 @@ pub fn start_consensus(
     (runtime, storage, quorum_store_db)
 }
 
-/// A helper function to start the consensus observer
+/// The handles used by other components (e.g., the admin service)
+/// to inspect and control the running consensus observer.
+pub struct ConsensusObserverHandles {
+    pub control_handle: ObserverControlHandle,
+    pub event_journal: ObserverEventJournal,
+    pub feature_flags: ObserverFeatureFlags,
+    pub peer_overrides: SubscriptionPeerOverrides,
+    pub status_handle: ObserverStatusHandle,
+    pub supervisor: Arc<ObserverSupervisor>,
+}
+
+/// A helper function to start the consensus observer. Returns the runtime
+/// and the handles of the consensus observer.
+/// Note: this should only be called if the observer is enabled.
 pub fn start_consensus_observer(
     node_config: &NodeConfig,
     observer_network_client: NetworkClient<ConsensusObserverMessage>,
@@ -128,9 +171,14 @@ pub fn start_consensus_observer(
     consensus_to_mempool_sender: mpsc::Sender<QuorumStoreRequest>,
     aptos_db: DbReaderWriter,
     reconfig_events: Option<ReconfigNotificationListener<DbBackedOnChainConfig>>,
-) -> Runtime {
+    synced_commit_listener: Option<SyncedCommitNotificationListener>,
+    observed_commit_notifier: ObservedCommitNotifier,
+) -> (Runtime, ConsensusObserverHandles) {
     // Create a consensus observer runtime
-    let runtime = aptos_runtimes::spawn_named_runtime("observer".into(), None);
+    let runtime = aptos_runtimes::spawn_named_runtime(
+        "observer".into(),
+        node_config.consensus_observer.observer_runtime_threads,
+    );
 
     // Create the consensus observer client
     let consensus_observer_client = if let Some(consensus_publisher) = &consensus_publisher {
@@ -157,56 +205,301 @@ pub fn start_consensus_observer(
         observer_network_client.get_peers_and_metadata(),
     ));
 
-    // If the consensus observer is enabled, create the execution client.
-    // If not, stub it out with a dummy client.
-    let execution_client = if node_config.consensus_observer.observer_enabled {
-        // Create the execution proxy
-        let txn_notifier = Arc::new(MempoolNotifier::new(
-            consensus_to_mempool_sender.clone(),
-            node_config.consensus.mempool_executed_txn_timeout_ms,
-        ));
-        let execution_proxy = ExecutionProxy::new(
-            Arc::new(BlockExecutor::<AptosVM>::new(aptos_db.clone())),
-            txn_notifier,
-            state_sync_notifier,
-            runtime.handle(),
-            TransactionFilter::new(node_config.execution.transaction_filter.clone()),
-        );
+    // Create the execution proxy
+    let txn_notifier = Arc::new(MempoolNotifier::new(
+        consensus_to_mempool_sender.clone(),
+        node_config.consensus.mempool_executed_txn_timeout_ms,
+    ));
+    let execution_proxy = ExecutionProxy::new(
+        Arc::new(BlockExecutor::<AptosVM>::new(aptos_db.clone())),
+        txn_notifier,
+        state_sync_notifier,
+        runtime.handle(),
+        TransactionFilter::new(node_config.execution.transaction_filter.clone()),
+    );
 
-        // Create the execution proxy client
-        let bounded_executor = BoundedExecutor::new(32, runtime.handle().clone());
-        let rand_storage = Arc::new(RandDb::new(node_config.storage.dir()));
-        let execution_proxy_client = Arc::new(ExecutionProxyClient::new(
-            node_config.consensus.clone(),
-            Arc::new(execution_proxy),
-            AccountAddress::ONE,
-            self_sender.clone(),
-            consensus_network_client,
-            bounded_executor,
-            rand_storage.clone(),
-            node_config.consensus_observer,
-            consensus_publisher.clone(),
-        ));
-        execution_proxy_client as Arc<dyn TExecutionClient>
-    } else {
-        Arc::new(DummyExecutionClient) as Arc<dyn TExecutionClient>
-    };
+    // Create the execution proxy client
+    let bounded_executor = BoundedExecutor::new(32, runtime.handle().clone());
+    let rand_storage = Arc::new(RandDb::new(node_config.storage.dir()));
+    let execution_client = Arc::new(ExecutionProxyClient::new(
+        node_config.consensus.clone(),
+        Arc::new(execution_proxy),
+        AccountAddress::ONE,
+        self_sender.clone(),
+        consensus_network_client,
+        bounded_executor,
+        rand_storage.clone(),
+        node_config.consensus_observer.clone(),
+        consensus_publisher.clone(),
+    ));
+
+    // Create the consensus observer event journal
+    let event_journal = ObserverEventJournal::new(
+        node_config.consensus_observer.max_num_journal_events,
+        TimeService::real(),
+    );
+
+    // Create the consensus observer runtime feature flags
+    let feature_flags = ObserverFeatureFlags::new(&node_config.consensus_observer);
+
+    // Create the consensus observer data exporter (if the export is enabled)
+    let data_exporter = create_observer_data_exporter(node_config, &runtime);
+
+    // Create the consensus observer commit journal (if the journal is enabled)
+    let commit_journal = create_observer_commit_journal(node_config);
+
+    // Create the consensus observer block archive (if the archive is enabled)
+    let block_archive = create_observer_block_archive(node_config);
+
+    // Create the consensus observer archive replay (if the replay is enabled)
+    let archive_replay = create_observer_archive_replay(node_config);
+
+    // Create the consensus observer state snapshotter (if snapshots are enabled)
+    let state_snapshotter = create_observer_state_snapshotter(node_config);
+
+    // Create the consensus observer payload db (if the persistent payload store is enabled)
+    let payload_store_db = create_observer_payload_store_db(node_config);
 
     // Create the consensus observer
-    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-    let consensus_observer = ConsensusObserver::new(
-        node_config.consensus_observer,
-        consensus_observer_client,
-        aptos_db.reader.clone(),
-        execution_client,
-        tx,
-        reconfig_events,
-        consensus_publisher,
+    let (tx, rx) = new_sync_notification_channel();
+    let mut observer_builder = ObserverBuilder::new(node_config.consensus_observer.clone())
+        .with_consensus_observer_client(consensus_observer_client)
+        .with_observer_storage(Arc::new(DbBackedObserverStorage::new(
+            aptos_db.reader.clone(),
+        )))
+        .with_execution_client(execution_client)
+        .with_sync_notification_sender(tx)
+        .with_reconfig_events(reconfig_events)
+        .with_consensus_publisher(consensus_publisher)
+        .with_time_service(TimeService::real())
+        .with_event_journal(event_journal.clone())
+        .with_feature_flags(feature_flags.clone())
+        .with_data_exporter(data_exporter)
+        .with_commit_journal(commit_journal)
+        .with_block_archive(block_archive)
+        .with_observed_commit_notifier(observed_commit_notifier)
+        .with_synced_commit_listener(synced_commit_listener)
+        .with_state_snapshotter(state_snapshotter)
+        .with_payload_store_db(payload_store_db);
+    if let Some(archive_replay) = archive_replay {
+        observer_builder = observer_builder.with_block_source(Box::new(archive_replay));
+    }
+    let consensus_observer = observer_builder
+        .build()
+        .expect("Failed to build the consensus observer!");
+
+    // Start the consensus observer (as the primary instance of the supervisor)
+    let observer_control_handle = consensus_observer.get_control_handle();
+    let observer_status_handle = consensus_observer.get_status_handle();
+    let peer_overrides = consensus_observer.get_peer_overrides();
+    let observer_supervisor = Arc::new(ObserverSupervisor::new(runtime.handle().clone()));
+    observer_supervisor
+        .start_instance(
+            PRIMARY_OBSERVER_INSTANCE,
+            consensus_observer,
+            observer_network_events,
+            rx,
+        )
+        .expect("Failed to start the consensus observer!");
+
+    let consensus_observer_handles = ConsensusObserverHandles {
+        control_handle: observer_control_handle,
+        event_journal,
+        feature_flags,
+        peer_overrides,
+        status_handle: observer_status_handle,
+        supervisor: observer_supervisor,
+    };
+    (runtime, consensus_observer_handles)
+}
+
+/// Creates the consensus observer archive replay (if the replay is enabled).
+/// The replayed archive is read from the node's storage directory.
+fn create_observer_archive_replay(node_config: &NodeConfig) -> Option<ArchiveReplayBlockSource> {
+    if !node_config.consensus_observer.enable_block_archive_replay {
+        return None;
+    }
+
+    let archive_directory = node_config
+        .storage
+        .dir()
+        .join(CONSENSUS_OBSERVER_BLOCK_ARCHIVE_DIR);
+    match ArchiveReplayBlockSource::new(&archive_directory) {
+        Ok(archive_replay) => Some(archive_replay),
+        Err(error) => {
+            error!(
+                "Failed to open the consensus observer block archive for replay at: {:?}! Error: {:?}",
+                archive_directory, error
+            );
+            None
+        },
+    }
+}
+
+/// Creates the consensus observer block archive (if the archive is enabled).
+/// The archive segments are stored in the node's storage directory.
+fn create_observer_block_archive(node_config: &NodeConfig) -> Option<BlockArchiveWriter> {
+    let consensus_observer_config = &node_config.consensus_observer;
+    if !consensus_observer_config.enable_block_archive {
+        return None;
+    }
+
+    // Avoid archiving the messages that are being replayed (from the same archive)
+    if consensus_observer_config.enable_block_archive_replay {
+        warn!(
+            "The consensus observer block archive is disabled while the archive is being replayed!"
+        );
+        return None;
+    }
+
+    let archive_directory = node_config
+        .storage
+        .dir()
+        .join(CONSENSUS_OBSERVER_BLOCK_ARCHIVE_DIR);
+    match BlockArchiveWriter::new(
+        archive_directory.clone(),
+        consensus_observer_config.max_block_archive_segment_size_bytes,
+        consensus_observer_config.max_num_block_archive_segments,
+    ) {
+        Ok(block_archive) => Some(block_archive),
+        Err(error) => {
+            error!(
+                "Failed to create the consensus observer block archive at: {:?}! Error: {:?}",
+                archive_directory, error
+            );
+            None
+        },
+    }
+}
+
+/// Creates the consensus observer commit journal (if the journal is enabled).
+/// The journal files are stored in the node's storage directory.
+fn create_observer_commit_journal(node_config: &NodeConfig) -> Option<CommitJournalWriter> {
+    let consensus_observer_config = &node_config.consensus_observer;
+    if !consensus_observer_config.enable_commit_journal {
+        return None;
+    }
+
+    let journal_directory = node_config
+        .storage
+        .dir()
+        .join(CONSENSUS_OBSERVER_COMMIT_JOURNAL_DIR);
+    match CommitJournalWriter::new(
+        journal_directory.clone(),
+        consensus_observer_config.max_commit_journal_file_size_bytes,
+        consensus_observer_config.max_num_commit_journal_files,
+    ) {
+        Ok(commit_journal) => Some(commit_journal),
+        Err(error) => {
+            error!(
+                "Failed to create the consensus observer commit journal at: {:?}! Error: {:?}",
+                journal_directory, error
+            );
+            None
+        },
+    }
+}
+
+/// Creates the consensus observer payload db (if the persistent payload store
+/// is enabled). The db is stored in the node's storage directory.
+fn create_observer_payload_store_db(node_config: &NodeConfig) -> Option<Arc<ObserverPayloadDb>> {
+    if !node_config
+        .consensus_observer
+        .enable_persistent_payload_store
+    {
+        return None;
+    }
+
+    Some(Arc::new(ObserverPayloadDb::new(node_config.storage.dir())))
+}
+
+/// Creates the consensus observer state snapshotter (if snapshots are enabled).
+/// The snapshots are stored in the node's storage directory.
+fn create_observer_state_snapshotter(node_config: &NodeConfig) -> Option<ObserverStateSnapshotter> {
+    let consensus_observer_config = &node_config.consensus_observer;
+    if !consensus_observer_config.enable_state_snapshots {
+        return None;
+    }
+
+    let snapshot_directory = node_config
+        .storage
+        .dir()
+        .join(CONSENSUS_OBSERVER_STATE_SNAPSHOT_DIR);
+    Some(ObserverStateSnapshotter::new(
+        snapshot_directory,
+        consensus_observer_config.state_snapshot_interval_ms,
         TimeService::real(),
+    ))
+}
+
+/// Creates the consensus observer data exporter and starts the gRPC and
+/// SSE export servers on the given runtime (if the addresses are configured).
+/// Note: the SSE export server also serves the WebSocket feed.
+fn create_observer_data_exporter(
+    node_config: &NodeConfig,
+    runtime: &Runtime,
+) -> Option<ObserverDataExporter> {
+    let consensus_observer_config = &node_config.consensus_observer;
+    let grpc_export_address = consensus_observer_config.grpc_export_address;
+    let sse_export_address = consensus_observer_config.sse_export_address;
+    if grpc_export_address.is_none() && sse_export_address.is_none() {
+        return None; // The export is disabled
+    }
+
+    // Create the data exporter
+    let data_exporter =
+        ObserverDataExporter::new(consensus_observer_config.max_export_buffer_size as usize);
+
+    // Start the gRPC export server (if configured)
+    if let Some(grpc_export_address) = grpc_export_address {
+        #[cfg(feature = "consensus-observer-grpc")]
+        {
+            crate::consensus_observer::grpc_export::start_grpc_export_server(
+                grpc_export_address,
+                data_exporter.clone(),
+                runtime.handle(),
+            );
+        }
+
+        #[cfg(not(feature = "consensus-observer-grpc"))]
+        {
+            warn!(
+                "The consensus observer gRPC export address is set ({}), but the \
+                consensus-observer-grpc feature is disabled! The export will not be started.",
+                grpc_export_address
+            );
+        }
+    }
+
+    // Start the SSE export server (if configured)
+    if let Some(sse_export_address) = sse_export_address {
+        start_sse_export_server(sse_export_address, data_exporter.clone(), runtime.handle());
+    }
+
+    Some(data_exporter)
+}
+
+/// A helper function to start the publisher only runtime (i.e., for nodes
+/// that run the consensus publisher, but not the consensus observer). The
+/// runtime only forwards incoming subscription requests to the publisher.
+pub fn start_publisher_only_runtime(
+    node_config: &NodeConfig,
+    observer_network_service_events: NetworkServiceEvents<ConsensusObserverMessage>,
+    consensus_publisher: Arc<ConsensusPublisher>,
+) -> Runtime {
+    // Create the publisher only runtime
+    let runtime = aptos_runtimes::spawn_named_runtime(
+        "pub-forward".into(),
+        node_config.consensus_observer.observer_runtime_threads,
     );
 
-    // Start the consensus observer
-    runtime.spawn(consensus_observer.start(observer_network_events, rx));
+    // Create the consensus observer network events
+    let observer_network_events =
+        ConsensusObserverNetworkEvents::new(observer_network_service_events);
+
+    // Start the publisher only runtime
+    let publisher_only_runtime = PublisherOnlyRuntime::new(consensus_publisher);
+    runtime.spawn(publisher_only_runtime.start(observer_network_events));
 
     runtime
 }
@@ -3,8 +3,8 @@
 
 use crate::{
     consensus_observer::{
-        network_message::ConsensusObserverMessage, payload_store::BlockPayloadStatus,
-        publisher::ConsensusPublisher,
+        metrics as observer_metrics, network_message::ConsensusObserverMessage,
+        payload_store::PayloadStoreBackend, publisher::ConsensusPublisher,
     },
     counters,
     quorum_store::{batch_store::BatchReader, quorum_store_coordinator::CoordinatorCommand},
@@ -16,16 +16,11 @@ use aptos_consensus_types::{
 };
 use aptos_crypto::HashValue;
 use aptos_executor_types::{ExecutorError::DataNotFound, *};
-use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_types::transaction::SignedTransaction;
 use futures::channel::mpsc::Sender;
 use itertools::Either;
-use std::{
-    collections::{hash_map::Entry, HashMap},
-    sync::Arc,
-    time::Duration,
-};
+use std::{sync::Arc, time::Duration};
 use tokio::{sync::oneshot, time::timeout};
 
 pub trait TPayloadManager: Send + Sync {
@@ -42,7 +37,7 @@ pub enum PayloadManager {
         Option<Arc<ConsensusPublisher>>,
     ),
     ConsensusObserver(
-        Arc<Mutex<HashMap<HashValue, BlockPayloadStatus>>>,
+        Arc<dyn PayloadStoreBackend>,
         Option<Arc<ConsensusPublisher>>,
     ),
 }
@@ -173,6 +168,93 @@ impl PayloadManager {
         }
     }
 
+    /// Prefetches the transactions of the given (proposed) block and publishes the
+    /// block payload to consensus observers before the block is ordered. This allows
+    /// observers to start executing the block as soon as the ordered block arrives.
+    /// Payloads are only published early if all the batches are available locally
+    /// (missing batches are fetched by `prefetch_payload_data`, and the payload is
+    /// then published when the block is executed).
+    pub fn prefetch_and_publish_payload(&self, block: &Block) {
+        // Ensure that payload prefetching is enabled
+        let (batch_reader, consensus_publisher) = match self {
+            PayloadManager::InQuorumStore(batch_reader, _, Some(consensus_publisher))
+                if consensus_publisher.is_payload_prefetch_enabled() =>
+            {
+                (batch_reader.clone(), consensus_publisher.clone())
+            },
+            _ => return, // Payload prefetching is disabled
+        };
+
+        // Identify the proofs, inline transactions and limit of the block payload
+        let (proof_with_data, inline_transactions, limit) = match block.payload() {
+            Some(Payload::InQuorumStore(proof_with_data)) => (proof_with_data, vec![], None),
+            Some(Payload::InQuorumStoreWithLimit(proof_with_data)) => (
+                &proof_with_data.proof_with_data,
+                vec![],
+                proof_with_data.max_txns_to_execute,
+            ),
+            Some(Payload::QuorumStoreInlineHybrid(
+                inline_batches,
+                proof_with_data,
+                max_txns_to_execute,
+            )) => (
+                proof_with_data,
+                inline_batches
+                    .iter()
+                    .flat_map(|(_batch_info, txns)| txns.clone())
+                    .collect(),
+                *max_txns_to_execute,
+            ),
+            _ => return, // There are no batches to prefetch
+        };
+
+        // Identify the unexpired proofs (the transactions of expired proofs are skipped)
+        let block_timestamp = block.timestamp_usecs();
+        let proofs: Vec<_> = proof_with_data
+            .proofs
+            .iter()
+            .filter(|proof| block_timestamp <= proof.expiration())
+            .cloned()
+            .collect();
+
+        // Ensure that all the batches are available locally
+        if proofs
+            .iter()
+            .any(|proof| batch_reader.exists(proof.digest()).is_none())
+        {
+            observer_metrics::increment_prefetched_payloads(
+                observer_metrics::PREFETCH_MISSING_BATCHES_LABEL,
+            );
+            return;
+        }
+
+        // Fetch the batches and publish the block payload
+        let block_info = block.gen_block_info(HashValue::zero(), 0, None);
+        let batch_receivers: Vec<_> = proofs
+            .into_iter()
+            .map(|proof| batch_reader.get_batch(proof))
+            .collect();
+        tokio::spawn(async move {
+            let mut transactions = vec![];
+            for batch_receiver in batch_receivers {
+                match batch_receiver.await {
+                    Ok(Ok(batch_transactions)) => transactions.extend(batch_transactions),
+                    _ => return, // The payload will be published when the block is executed
+                }
+            }
+            transactions.extend(inline_transactions);
+
+            let message = ConsensusObserverMessage::new_block_payload_message(
+                block_info,
+                transactions,
+                limit,
+            );
+            consensus_publisher
+                .publish_prefetched_payload(message)
+                .await;
+        });
+    }
+
     /// Extract transaction from a given block
     /// Assumes it is never called for the same block concurrently. Otherwise status can be None.
     pub async fn get_transactions(
@@ -184,24 +266,11 @@ impl PayloadManager {
             None => return Ok((Vec::new(), None)),
         };
 
-        if let PayloadManager::ConsensusObserver(txns_pool, consensus_publisher) = self {
-            // If the data is already available, return it, otherwise put the tx in the pool and wait for it.
+        if let PayloadManager::ConsensusObserver(payload_store_backend, consensus_publisher) = self
+        {
+            // If the data is already available, return it, otherwise request it and wait for it.
             // It's important to make sure this doesn't race with the payload insertion part.
-            let result = match txns_pool.lock().entry(block.id()) {
-                Entry::Occupied(mut value) => match value.get_mut() {
-                    BlockPayloadStatus::Available(data) => Either::Left(data.clone()),
-                    BlockPayloadStatus::Requested(tx) => {
-                        let (new_tx, rx) = oneshot::channel();
-                        *tx = new_tx;
-                        Either::Right(rx)
-                    },
-                },
-                Entry::Vacant(entry) => {
-                    let (tx, rx) = oneshot::channel();
-                    entry.insert(BlockPayloadStatus::Requested(tx));
-                    Either::Right(rx)
-                },
-            };
+            let result = payload_store_backend.get_or_request_payload(block.id());
             let block_transaction_payload = match result {
                 Either::Left(data) => data,
                 Either::Right(rx) => timeout(Duration::from_millis(300), rx)
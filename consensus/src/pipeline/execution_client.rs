@@ -87,6 +87,10 @@ pub trait TExecutionClient: Send + Sync {
         commit_msg: IncomingCommitRequest,
     ) -> Result<()>;
 
+    /// Reset the rand and buffer managers to the target round (i.e., drop
+    /// all in-flight blocks) without synchronizing storage.
+    async fn reset(&self, target: &LedgerInfoWithSignatures) -> Result<()>;
+
     /// Synchronize to a commit that not present locally.
     async fn sync_to(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError>;
 
@@ -308,7 +312,7 @@ impl TExecutionClient for ExecutionProxyClient {
             onchain_consensus_config,
             rand_msg_rx,
             highest_ordered_round,
-            self.consensus_observer_config,
+            self.consensus_observer_config.clone(),
             self.consensus_publisher.clone(),
         );
 
@@ -393,6 +397,16 @@ impl TExecutionClient for ExecutionProxyClient {
             Err(anyhow::anyhow!("Injected error in sync_to").into())
         });
 
+        // Reset the rand and buffer managers to the target round
+        self.reset(&target).await?;
+
+        // TODO: handle the sync error, should re-push the ordered blocks to buffer manager
+        // when it's reset but sync fails.
+        self.execution_proxy.sync_to(target).await?;
+        Ok(())
+    }
+
+    async fn reset(&self, target: &LedgerInfoWithSignatures) -> Result<()> {
         let (reset_tx_to_rand_manager, reset_tx_to_buffer_manager) = {
             let handle = self.handle.read();
             (
@@ -426,9 +440,6 @@ impl TExecutionClient for ExecutionProxyClient {
             rx.await.map_err(|_| Error::ResetDropped)?;
         }
 
-        // TODO: handle the sync error, should re-push the ordered blocks to buffer manager
-        // when it's reset but sync fails.
-        self.execution_proxy.sync_to(target).await?;
         Ok(())
     }
 
@@ -507,5 +518,9 @@ impl TExecutionClient for DummyExecutionClient {
         Ok(())
     }
 
+    async fn reset(&self, _: &LedgerInfoWithSignatures) -> Result<()> {
+        Ok(())
+    }
+
     async fn end_epoch(&self) {}
 }
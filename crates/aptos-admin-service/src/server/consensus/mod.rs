@@ -2,17 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{bail, Error};
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
 use aptos_consensus::{
+    consensus_observer::{
+        event_journal::ObserverEventJournal,
+        feature_flags::{ObserverFeature, ObserverFeatureFlags},
+        observer_control::ObserverControlHandle,
+        observer_status::ObserverStatusHandle,
+        peer_overrides::SubscriptionPeerOverrides,
+        supervisor::ObserverSupervisor,
+    },
     persistent_liveness_storage::PersistentLivenessStorage,
-    quorum_store::quorum_store_db::QuorumStoreStorage, util::db_tool::extract_txns_from_block,
+    quorum_store::quorum_store_db::QuorumStoreStorage,
+    util::db_tool::extract_txns_from_block,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::info;
 use aptos_system_utils::utils::{reply_with, reply_with_status, spawn_blocking};
-use aptos_types::transaction::Transaction;
+use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::Transaction, PeerId};
 use http::header::{HeaderValue, CONTENT_LENGTH};
 use hyper::{Body, Request, Response, StatusCode};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 pub async fn handle_dump_consensus_db_request(
     _req: Request<Body>,
@@ -127,6 +137,292 @@ pub async fn handle_dump_block_request(
     }
 }
 
+pub async fn handle_dump_consensus_observer_events_request(
+    _req: Request<Body>,
+    event_journal: ObserverEventJournal,
+) -> hyper::Result<Response<Body>> {
+    info!("Dumping consensus observer events.");
+
+    let mut body = String::new();
+    for journal_entry in event_journal.get_journal_entries() {
+        body.push_str(&format!("{journal_entry}\n"));
+    }
+    if body.is_empty() {
+        body.push_str("Done, no consensus observer events are found.");
+    }
+
+    let headers: Vec<(_, HeaderValue)> = vec![(CONTENT_LENGTH, HeaderValue::from(body.len()))];
+    Ok(reply_with(headers, body))
+}
+
+pub async fn handle_consensus_observer_features_request(
+    req: Request<Body>,
+    feature_flags: ObserverFeatureFlags,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    // Update the requested feature flag (if any)
+    if let Some(label) = query_pairs.get("feature") {
+        let feature = match ObserverFeature::from_label(label) {
+            Some(feature) => feature,
+            None => {
+                return Ok(reply_with_status(
+                    StatusCode::BAD_REQUEST,
+                    format!("Unknown consensus observer feature: {label}"),
+                ))
+            },
+        };
+        let enabled: bool = match query_pairs.get("enabled") {
+            Some(val) => match val.parse() {
+                Ok(val) => val,
+                Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+            },
+            None => {
+                return Ok(reply_with_status(
+                    StatusCode::BAD_REQUEST,
+                    "The enabled parameter is required when updating a feature.",
+                ))
+            },
+        };
+
+        info!("Updating consensus observer feature ({feature}), enabled: {enabled}.");
+        feature_flags.set_enabled(feature, enabled);
+    }
+
+    let mut body = String::new();
+    for (feature, enabled) in feature_flags.get_all_features() {
+        body.push_str(&format!("{feature}: {enabled}\n"));
+    }
+
+    let headers: Vec<(_, HeaderValue)> = vec![(CONTENT_LENGTH, HeaderValue::from(body.len()))];
+    Ok(reply_with(headers, body))
+}
+
+pub async fn handle_consensus_observer_finalization_request(
+    req: Request<Body>,
+    control_handle: ObserverControlHandle,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    // Pause or resume finalization (as requested)
+    let action = match query_pairs.get("action") {
+        Some(action) => action.to_string(),
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "The action parameter (pause or resume) is required.",
+            ))
+        },
+    };
+    info!("Updating consensus observer finalization (action: {action}).");
+    let result = match action.as_str() {
+        "pause" => control_handle.pause_finalization().await,
+        "resume" => control_handle.resume_finalization().await,
+        _ => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                format!("Unknown consensus observer finalization action: {action}"),
+            ))
+        },
+    };
+
+    match result {
+        Ok(()) => {
+            let body = format!("Consensus observer finalization action completed: {action}\n");
+            let headers: Vec<(_, HeaderValue)> =
+                vec![(CONTENT_LENGTH, HeaderValue::from(body.len()))];
+            Ok(reply_with(headers, body))
+        },
+        Err(err) => Ok(reply_with_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to {action} consensus observer finalization: {err:?}"),
+        )),
+    }
+}
+
+pub async fn handle_dump_consensus_observer_instances_request(
+    _req: Request<Body>,
+    supervisor: Arc<ObserverSupervisor>,
+) -> hyper::Result<Response<Body>> {
+    info!("Dumping consensus observer instances.");
+
+    let mut body = String::new();
+    for instance_name in supervisor.get_instance_names() {
+        let running = supervisor.is_instance_running(&instance_name);
+        body.push_str(&format!("Instance: {instance_name} (running: {running})\n"));
+        if let Some(status_handle) = supervisor.get_status_handle(&instance_name) {
+            body.push_str(&format!("{}\n\n", status_handle.get_status()));
+        }
+    }
+
+    let headers: Vec<(_, HeaderValue)> = vec![(CONTENT_LENGTH, HeaderValue::from(body.len()))];
+    Ok(reply_with(headers, body))
+}
+
+pub async fn handle_consensus_observer_peers_request(
+    req: Request<Body>,
+    peer_overrides: SubscriptionPeerOverrides,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    // Parse the requested peer (if any)
+    let peer_id = match query_pairs.get("peer_id") {
+        Some(val) => match PeerId::from_str(val) {
+            Ok(peer_id) => Some(peer_id),
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => None,
+    };
+
+    // Update the requested override (if any)
+    if let Some(action) = query_pairs.get("action").map(|action| action.to_string()) {
+        match (action.as_str(), peer_id) {
+            ("pin", Some(peer_id)) => peer_overrides.pin_peer(peer_id),
+            ("unpin", _) => peer_overrides.unpin_peer(),
+            ("forbid", Some(peer_id)) => peer_overrides.forbid_peer(peer_id),
+            ("allow", Some(peer_id)) => peer_overrides.allow_peer(peer_id),
+            ("pin" | "forbid" | "allow", None) => {
+                return Ok(reply_with_status(
+                    StatusCode::BAD_REQUEST,
+                    format!("The peer_id parameter is required for the {action} action."),
+                ))
+            },
+            _ => {
+                return Ok(reply_with_status(
+                    StatusCode::BAD_REQUEST,
+                    format!("Unknown consensus observer peer action: {action}"),
+                ))
+            },
+        }
+        info!("Updated consensus observer peer overrides (action: {action}, peer: {peer_id:?}).");
+    }
+
+    let body = format!("{peer_overrides}\n");
+    let headers: Vec<(_, HeaderValue)> = vec![(CONTENT_LENGTH, HeaderValue::from(body.len()))];
+    Ok(reply_with(headers, body))
+}
+
+pub async fn handle_consensus_observer_resubscribe_request(
+    req: Request<Body>,
+    control_handle: ObserverControlHandle,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    // Parse the requested peer (if any). If no peer is given, the
+    // observer reruns the peer selection (excluding the current peer).
+    let peer_network_id = match (query_pairs.get("peer_id"), query_pairs.get("network_id")) {
+        (Some(peer_id), Some(network_id)) => {
+            let peer_id = match PeerId::from_str(peer_id) {
+                Ok(peer_id) => peer_id,
+                Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+            };
+            let network_id = match NetworkId::from_str(network_id) {
+                Ok(network_id) => network_id,
+                Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+            };
+            Some(PeerNetworkId::new(network_id, peer_id))
+        },
+        (None, None) => None,
+        _ => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "The peer_id and network_id parameters must be provided together.",
+            ))
+        },
+    };
+
+    info!("Forcing a consensus observer resubscription (peer: {peer_network_id:?}).");
+
+    match control_handle.force_resubscribe(peer_network_id).await {
+        Ok(subscription_peer) => {
+            let body = format!("Subscribed to peer: {subscription_peer}\n");
+            let headers: Vec<(_, HeaderValue)> =
+                vec![(CONTENT_LENGTH, HeaderValue::from(body.len()))];
+            Ok(reply_with(headers, body))
+        },
+        Err(err) => Ok(reply_with_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to force a consensus observer resubscription: {err:?}"),
+        )),
+    }
+}
+
+pub async fn handle_consensus_observer_sync_request(
+    req: Request<Body>,
+    control_handle: ObserverControlHandle,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    // Parse the requested sync target (if any). If no target is given,
+    // the observer syncs to the latest known commit.
+    let ledger_info = match query_pairs.get("ledger_info") {
+        Some(val) => match parse_ledger_info(val) {
+            Ok(ledger_info) => Some(ledger_info),
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => None,
+    };
+
+    info!("Forcing a consensus observer state sync (ledger info: {ledger_info:?}).");
+
+    match control_handle.force_sync(ledger_info).await {
+        Ok(sync_target) => {
+            let body = format!("Started syncing to: {sync_target}\n");
+            let headers: Vec<(_, HeaderValue)> =
+                vec![(CONTENT_LENGTH, HeaderValue::from(body.len()))];
+            Ok(reply_with(headers, body))
+        },
+        Err(err) => Ok(reply_with_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to force a consensus observer state sync: {err:?}"),
+        )),
+    }
+}
+
+pub async fn handle_dump_consensus_observer_state_request(
+    _req: Request<Body>,
+    status_handle: ObserverStatusHandle,
+) -> hyper::Result<Response<Body>> {
+    info!("Dumping consensus observer state.");
+
+    match serde_json::to_string_pretty(&status_handle.get_state_dump()) {
+        Ok(body) => {
+            let headers: Vec<(_, HeaderValue)> =
+                vec![(CONTENT_LENGTH, HeaderValue::from(body.len()))];
+            Ok(reply_with(headers, body))
+        },
+        Err(e) => {
+            info!("Failed to dump consensus observer state: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn handle_dump_consensus_observer_status_request(
+    _req: Request<Body>,
+    status_handle: ObserverStatusHandle,
+) -> hyper::Result<Response<Body>> {
+    info!("Dumping consensus observer status.");
+
+    let body = format!("{}\n", status_handle.get_status());
+    let headers: Vec<(_, HeaderValue)> = vec![(CONTENT_LENGTH, HeaderValue::from(body.len()))];
+    Ok(reply_with(headers, body))
+}
+
+/// Parses a hex-encoded, BCS-serialized ledger info with signatures
+fn parse_ledger_info(ledger_info: &str) -> anyhow::Result<LedgerInfoWithSignatures> {
+    let ledger_info_bytes = hex::decode(ledger_info.trim_start_matches("0x"))?;
+    Ok(bcs::from_bytes(&ledger_info_bytes)?)
+}
+
 fn dump_consensus_db(consensus_db: &dyn PersistentLivenessStorage) -> anyhow::Result<String> {
     let mut body = String::new();
 
@@ -3,7 +3,13 @@
 
 use aptos_config::config::{AuthenticationConfig, NodeConfig};
 use aptos_consensus::{
-    persistent_liveness_storage::StorageWriteProxy, quorum_store::quorum_store_db::QuorumStoreDB,
+    consensus_observer::{
+        event_journal::ObserverEventJournal, feature_flags::ObserverFeatureFlags,
+        observer_control::ObserverControlHandle, observer_status::ObserverStatusHandle,
+        peer_overrides::SubscriptionPeerOverrides, supervisor::ObserverSupervisor,
+    },
+    persistent_liveness_storage::StorageWriteProxy,
+    quorum_store::quorum_store_db::QuorumStoreDB,
 };
 use aptos_infallible::RwLock;
 use aptos_logger::info;
@@ -34,6 +40,12 @@ pub struct Context {
     aptos_db: RwLock<Option<Arc<DbReaderWriter>>>,
     consensus_db: RwLock<Option<Arc<StorageWriteProxy>>>,
     quorum_store_db: RwLock<Option<Arc<QuorumStoreDB>>>,
+    consensus_observer_control_handle: RwLock<Option<ObserverControlHandle>>,
+    consensus_observer_event_journal: RwLock<Option<ObserverEventJournal>>,
+    consensus_observer_feature_flags: RwLock<Option<ObserverFeatureFlags>>,
+    consensus_observer_peer_overrides: RwLock<Option<SubscriptionPeerOverrides>>,
+    consensus_observer_status_handle: RwLock<Option<ObserverStatusHandle>>,
+    consensus_observer_supervisor: RwLock<Option<Arc<ObserverSupervisor>>>,
 }
 
 impl Context {
@@ -49,6 +61,30 @@ impl Context {
         *self.consensus_db.write() = Some(consensus_db);
         *self.quorum_store_db.write() = Some(quorum_store_db);
     }
+
+    fn set_consensus_observer_control_handle(&self, control_handle: ObserverControlHandle) {
+        *self.consensus_observer_control_handle.write() = Some(control_handle);
+    }
+
+    fn set_consensus_observer_event_journal(&self, event_journal: ObserverEventJournal) {
+        *self.consensus_observer_event_journal.write() = Some(event_journal);
+    }
+
+    fn set_consensus_observer_feature_flags(&self, feature_flags: ObserverFeatureFlags) {
+        *self.consensus_observer_feature_flags.write() = Some(feature_flags);
+    }
+
+    fn set_consensus_observer_peer_overrides(&self, peer_overrides: SubscriptionPeerOverrides) {
+        *self.consensus_observer_peer_overrides.write() = Some(peer_overrides);
+    }
+
+    fn set_consensus_observer_status_handle(&self, status_handle: ObserverStatusHandle) {
+        *self.consensus_observer_status_handle.write() = Some(status_handle);
+    }
+
+    fn set_consensus_observer_supervisor(&self, supervisor: Arc<ObserverSupervisor>) {
+        *self.consensus_observer_supervisor.write() = Some(supervisor);
+    }
 }
 
 pub struct AdminService {
@@ -107,6 +143,35 @@ impl AdminService {
             .set_consensus_dbs(consensus_db, quorum_store_db)
     }
 
+    pub fn set_consensus_observer_control_handle(&self, control_handle: ObserverControlHandle) {
+        self.context
+            .set_consensus_observer_control_handle(control_handle)
+    }
+
+    pub fn set_consensus_observer_event_journal(&self, event_journal: ObserverEventJournal) {
+        self.context
+            .set_consensus_observer_event_journal(event_journal)
+    }
+
+    pub fn set_consensus_observer_feature_flags(&self, feature_flags: ObserverFeatureFlags) {
+        self.context
+            .set_consensus_observer_feature_flags(feature_flags)
+    }
+
+    pub fn set_consensus_observer_peer_overrides(&self, peer_overrides: SubscriptionPeerOverrides) {
+        self.context
+            .set_consensus_observer_peer_overrides(peer_overrides)
+    }
+
+    pub fn set_consensus_observer_status_handle(&self, status_handle: ObserverStatusHandle) {
+        self.context
+            .set_consensus_observer_status_handle(status_handle)
+    }
+
+    pub fn set_consensus_observer_supervisor(&self, supervisor: Arc<ObserverSupervisor>) {
+        self.context.set_consensus_observer_supervisor(supervisor)
+    }
+
     fn start(&self, address: SocketAddr, enabled: bool) {
         let context = self.context.clone();
         self.runtime.spawn(async move {
@@ -210,6 +275,111 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::GET, "/debug/consensus/observer/events") => {
+                let event_journal = context.consensus_observer_event_journal.read().clone();
+                if let Some(event_journal) = event_journal {
+                    consensus::handle_dump_consensus_observer_events_request(req, event_journal)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus observer event journal is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/observer/features") => {
+                let feature_flags = context.consensus_observer_feature_flags.read().clone();
+                if let Some(feature_flags) = feature_flags {
+                    consensus::handle_consensus_observer_features_request(req, feature_flags).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus observer feature flags are not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/observer/finalization") => {
+                let control_handle = context.consensus_observer_control_handle.read().clone();
+                if let Some(control_handle) = control_handle {
+                    consensus::handle_consensus_observer_finalization_request(req, control_handle)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus observer control handle is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/observer/instances") => {
+                let supervisor = context.consensus_observer_supervisor.read().clone();
+                if let Some(supervisor) = supervisor {
+                    consensus::handle_dump_consensus_observer_instances_request(req, supervisor)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus observer supervisor is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/observer/peers") => {
+                let peer_overrides = context.consensus_observer_peer_overrides.read().clone();
+                if let Some(peer_overrides) = peer_overrides {
+                    consensus::handle_consensus_observer_peers_request(req, peer_overrides).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus observer peer overrides are not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/observer/sync") => {
+                let control_handle = context.consensus_observer_control_handle.read().clone();
+                if let Some(control_handle) = control_handle {
+                    consensus::handle_consensus_observer_sync_request(req, control_handle).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus observer control handle is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/observer/resubscribe") => {
+                let control_handle = context.consensus_observer_control_handle.read().clone();
+                if let Some(control_handle) = control_handle {
+                    consensus::handle_consensus_observer_resubscribe_request(req, control_handle)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus observer control handle is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/observer/state") => {
+                let status_handle = context.consensus_observer_status_handle.read().clone();
+                if let Some(status_handle) = status_handle {
+                    consensus::handle_dump_consensus_observer_state_request(req, status_handle)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus observer status is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/observer/status") => {
+                let status_handle = context.consensus_observer_status_handle.read().clone();
+                if let Some(status_handle) = status_handle {
+                    consensus::handle_dump_consensus_observer_status_request(req, status_handle)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus observer status is not available.",
+                    ))
+                }
+            },
             _ => Ok(reply_with_status(StatusCode::NOT_FOUND, "Not found.")),
         }
     }
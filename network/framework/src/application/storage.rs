@@ -24,7 +24,10 @@ use arc_swap::ArcSwap;
 use std::{
     collections::{hash_map::Entry, HashMap},
     ops::Deref,
-    sync::{Arc, RwLockWriteGuard},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLockWriteGuard,
+    },
     time::Duration,
 };
 use tokio::sync::mpsc::error::TrySendError;
@@ -51,6 +54,11 @@ pub struct PeersAndMetadata {
     // TODO: should we remove this when generational versioning is supported?
     cached_peers_and_metadata: Arc<ArcSwap<HashMap<NetworkId, HashMap<PeerId, PeerMetadata>>>>,
 
+    // The version of the cached peers and metadata. The version is incremented
+    // on every cache update, allowing applications to cheaply detect changes
+    // (e.g., to avoid recomputing state derived from the peers and metadata).
+    cached_peers_and_metadata_version: AtomicU64,
+
     subscribers: Mutex<Vec<tokio::sync::mpsc::Sender<ConnectionNotification>>>,
 }
 
@@ -61,6 +69,7 @@ impl PeersAndMetadata {
             peers_and_metadata: RwLock::new(HashMap::new()),
             trusted_peers: HashMap::new(),
             cached_peers_and_metadata: Arc::new(ArcSwap::from(Arc::new(HashMap::new()))),
+            cached_peers_and_metadata_version: AtomicU64::new(0),
             subscribers: Mutex::new(vec![]),
         };
 
@@ -108,23 +117,44 @@ impl PeersAndMetadata {
     /// Returns metadata for all peers currently connected to the node
     pub fn get_connected_peers_and_metadata(
         &self,
+    ) -> Result<HashMap<PeerNetworkId, PeerMetadata>, Error> {
+        self.get_connected_peers_and_metadata_filtered(|_, _| true)
+    }
+
+    /// Returns metadata for all peers currently connected to the node that
+    /// satisfy the given filter. Only the metadata of the matching peers is
+    /// cloned, so this should be preferred by applications that only require
+    /// a subset of the connected peers (e.g., on nodes with many peers).
+    pub fn get_connected_peers_and_metadata_filtered(
+        &self,
+        filter: impl Fn(&PeerNetworkId, &PeerMetadata) -> bool,
     ) -> Result<HashMap<PeerNetworkId, PeerMetadata>, Error> {
         // Get the cached peers and metadata
         let cached_peers_and_metadata = self.cached_peers_and_metadata.load();
 
-        // Collect all connected peers
+        // Collect all connected peers that satisfy the filter
         let mut connected_peers_and_metadata = HashMap::new();
         for (network_id, peers_and_metadata) in cached_peers_and_metadata.iter() {
             for (peer_id, peer_metadata) in peers_and_metadata.iter() {
                 if peer_metadata.is_connected() {
                     let peer_network_id = PeerNetworkId::new(*network_id, *peer_id);
-                    connected_peers_and_metadata.insert(peer_network_id, peer_metadata.clone());
+                    if filter(&peer_network_id, peer_metadata) {
+                        connected_peers_and_metadata.insert(peer_network_id, peer_metadata.clone());
+                    }
                 }
             }
         }
         Ok(connected_peers_and_metadata)
     }
 
+    /// Returns the current version of the peers and metadata. The version
+    /// changes whenever any peer or peer metadata is updated. Note: the version
+    /// should be read before the peers and metadata (to avoid missing updates).
+    pub fn get_peers_and_metadata_version(&self) -> u64 {
+        self.cached_peers_and_metadata_version
+            .load(Ordering::Acquire)
+    }
+
     /// Returns all connected peers that support at least one of
     /// the given protocols.
     pub fn get_connected_supported_peers(
@@ -324,6 +354,8 @@ impl PeersAndMetadata {
     ) {
         self.cached_peers_and_metadata
             .store(Arc::new(cached_peers_and_metadata));
+        self.cached_peers_and_metadata_version
+            .fetch_add(1, Ordering::AcqRel);
     }
 
     /// Returns a clone of the trusted peer set for the given network ID
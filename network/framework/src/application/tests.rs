@@ -14,6 +14,7 @@ use crate::{
         PeerManagerRequest, PeerManagerRequestSender,
     },
     protocols::{
+        direct_send::Message,
         network::{Event, NetworkEvents, NetworkSender, NewNetworkEvents, NewNetworkSender},
         rpc::InboundRpcRequest,
         wire::handshake::v1::{ProtocolId, ProtocolIdSet},
@@ -178,6 +179,91 @@ fn test_peers_and_metadata_simple_interface() {
     check_connected_supported_peers(&peers_and_metadata, &[ProtocolId::ConsensusRpcBcs], vec![]);
 }
 
+#[test]
+fn test_peers_and_metadata_filtered() {
+    // Create the peers and metadata container
+    let network_ids = vec![NetworkId::Validator, NetworkId::Vfn];
+    let peers_and_metadata = PeersAndMetadata::new(&network_ids);
+
+    // Create two peers (on different networks)
+    let (peer_network_id_1, _) = create_peer_and_connection(
+        NetworkId::Validator,
+        vec![ProtocolId::MempoolDirectSend],
+        peers_and_metadata.clone(),
+    );
+    let (peer_network_id_2, _) = create_peer_and_connection(
+        NetworkId::Vfn,
+        vec![ProtocolId::MempoolDirectSend],
+        peers_and_metadata.clone(),
+    );
+
+    // Verify that all connected peers are returned when the filter accepts everything
+    let connected_peers_and_metadata = peers_and_metadata
+        .get_connected_peers_and_metadata_filtered(|_, _| true)
+        .unwrap();
+    assert_eq!(connected_peers_and_metadata.len(), 2);
+
+    // Verify that only the peers satisfying the filter are returned
+    let connected_peers_and_metadata = peers_and_metadata
+        .get_connected_peers_and_metadata_filtered(|peer_network_id, _| {
+            peer_network_id.network_id() == NetworkId::Vfn
+        })
+        .unwrap();
+    assert_eq!(
+        connected_peers_and_metadata.keys().collect::<Vec<_>>(),
+        vec![&peer_network_id_2]
+    );
+
+    // Mark peer 2 as disconnected and verify it is no longer returned
+    mark_peer_disconnecting(&peers_and_metadata, peer_network_id_2);
+    let connected_peers_and_metadata = peers_and_metadata
+        .get_connected_peers_and_metadata_filtered(|_, _| true)
+        .unwrap();
+    assert_eq!(
+        connected_peers_and_metadata.keys().collect::<Vec<_>>(),
+        vec![&peer_network_id_1]
+    );
+}
+
+#[test]
+fn test_peers_and_metadata_version() {
+    // Create the peers and metadata container
+    let peers_and_metadata = PeersAndMetadata::new(&[NetworkId::Public]);
+    let initial_version = peers_and_metadata.get_peers_and_metadata_version();
+
+    // Create a peer and verify the version has increased
+    let (peer_network_id, _) = create_peer_and_connection(
+        NetworkId::Public,
+        vec![ProtocolId::MempoolDirectSend],
+        peers_and_metadata.clone(),
+    );
+    let connected_version = peers_and_metadata.get_peers_and_metadata_version();
+    assert!(connected_version > initial_version);
+
+    // Verify that reading the peers and metadata doesn't change the version
+    peers_and_metadata
+        .get_connected_peers_and_metadata()
+        .unwrap();
+    assert_eq!(
+        peers_and_metadata.get_peers_and_metadata_version(),
+        connected_version
+    );
+
+    // Update the peer monitoring metadata and verify the version has increased
+    peers_and_metadata
+        .update_peer_monitoring_metadata(
+            peer_network_id,
+            PeerMonitoringMetadata::new(Some(0.1), None, None, None, None),
+        )
+        .unwrap();
+    let updated_version = peers_and_metadata.get_peers_and_metadata_version();
+    assert!(updated_version > connected_version);
+
+    // Disconnect the peer and verify the version has increased
+    mark_peer_disconnecting(&peers_and_metadata, peer_network_id);
+    assert!(peers_and_metadata.get_peers_and_metadata_version() > updated_version);
+}
+
 #[test]
 fn test_peers_and_metadata_simple_errors() {
     // Create the peers and metadata container
@@ -953,6 +1039,40 @@ fn check_registered_networks(
     compare_vectors_ignore_order(registered_networks, expected_networks);
 }
 
+#[tokio::test]
+async fn test_network_events_sized_event_stream() {
+    // Create the network events and fetch the sized event stream
+    let (inbound_request_sender, inbound_request_receiver) = create_aptos_channel();
+    let network_events: NetworkEvents<DummyMessage> =
+        NetworkEvents::new(inbound_request_receiver, None, true);
+    let mut sized_event_stream = network_events.into_sized_event_stream();
+
+    // Send a direct send message to the network events
+    let peer_id = PeerId::random();
+    let protocol_id = ProtocolId::MempoolDirectSend;
+    let dummy_message = DummyMessage::new(10101);
+    let message_data = protocol_id.to_bytes(&dummy_message).unwrap();
+    let message = Message {
+        protocol_id,
+        mdata: message_data.clone().into(),
+    };
+    inbound_request_sender
+        .push(
+            (peer_id, protocol_id),
+            PeerManagerNotification::RecvMessage(peer_id, message),
+        )
+        .unwrap();
+
+    // Verify the event is received along with the size of the serialized message
+    let channel_wait_time = Duration::from_secs(MAX_CHANNEL_TIMEOUT_SECS);
+    let (event, num_message_bytes) = timeout(channel_wait_time, sized_event_stream.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event, Event::Message(peer_id, dummy_message));
+    assert_eq!(num_message_bytes, message_data.len());
+}
+
 /// Verifies that all returned peers are correct
 fn check_all_peers(peers_and_metadata: &Arc<PeersAndMetadata>, expected_peers: Vec<PeerNetworkId>) {
     let all_peers = peers_and_metadata.get_all_peers();
@@ -143,11 +143,16 @@ impl NetworkApplicationConfig {
 #[pin_project]
 pub struct NetworkEvents<TMessage> {
     #[pin]
-    event_stream: Pin<Box<dyn Stream<Item = Event<TMessage>> + Send + Sync + 'static>>,
+    event_stream: SizedEventStream<TMessage>,
     done: bool,
     _marker: PhantomData<TMessage>,
 }
 
+/// A stream of network events, each paired with the size (in bytes)
+/// of the serialized message received over the wire.
+pub type SizedEventStream<TMessage> =
+    Pin<Box<dyn Stream<Item = (Event<TMessage>, usize)> + Send + Sync + 'static>>;
+
 /// Trait specifying the signature for `new()` `NetworkEvents`
 pub trait NewNetworkEvents {
     fn new(
@@ -170,9 +175,7 @@ impl<TMessage: Message + Send + Sync + 'static> NewNetworkEvents for NetworkEven
             tokio::task::spawn_blocking(move || peer_mgr_notif_to_event(notification))
         });
 
-        let data_event_stream: Pin<
-            Box<dyn Stream<Item = Event<TMessage>> + Send + Sync + 'static>,
-        > = if allow_out_of_order_delivery {
+        let data_event_stream: SizedEventStream<TMessage> = if allow_out_of_order_delivery {
             Box::pin(
                 data_event_stream
                     .buffer_unordered(max_parallel_deserialization_tasks)
@@ -194,6 +197,16 @@ impl<TMessage: Message + Send + Sync + 'static> NewNetworkEvents for NetworkEven
     }
 }
 
+impl<TMessage> NetworkEvents<TMessage> {
+    /// Consumes the network events and returns the underlying event stream,
+    /// where each event is paired with the size (in bytes) of the serialized
+    /// message received over the wire. This allows applications to track
+    /// inbound bytes without re-serializing the deserialized messages.
+    pub fn into_sized_event_stream(self) -> SizedEventStream<TMessage> {
+        self.event_stream
+    }
+}
+
 impl<TMessage> Stream for NetworkEvents<TMessage> {
     type Item = Event<TMessage>;
 
@@ -206,7 +219,7 @@ impl<TMessage> Stream for NetworkEvents<TMessage> {
         if item.is_none() {
             *this.done = true;
         }
-        Poll::Ready(item)
+        Poll::Ready(item.map(|(event, _)| event))
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -215,17 +228,23 @@ impl<TMessage> Stream for NetworkEvents<TMessage> {
 }
 
 /// Deserialize inbound direct send and rpc messages into the application `TMessage`
-/// type, logging and dropping messages that fail to deserialize.
+/// type, logging and dropping messages that fail to deserialize. Each event is
+/// returned alongside the size of the serialized message.
 fn peer_mgr_notif_to_event<TMessage: Message>(
     notification: PeerManagerNotification,
-) -> Option<Event<TMessage>> {
+) -> Option<(Event<TMessage>, usize)> {
     match notification {
         PeerManagerNotification::RecvRpc(peer_id, rpc_req) => {
-            request_to_network_event(peer_id, &rpc_req)
-                .map(|msg| Event::RpcRequest(peer_id, msg, rpc_req.protocol_id, rpc_req.res_tx))
+            let num_message_bytes = rpc_req.data().len();
+            request_to_network_event(peer_id, &rpc_req).map(|msg| {
+                let event = Event::RpcRequest(peer_id, msg, rpc_req.protocol_id, rpc_req.res_tx);
+                (event, num_message_bytes)
+            })
         },
         PeerManagerNotification::RecvMessage(peer_id, request) => {
-            request_to_network_event(peer_id, &request).map(|msg| Event::Message(peer_id, msg))
+            let num_message_bytes = request.data().len();
+            request_to_network_event(peer_id, &request)
+                .map(|msg| (Event::Message(peer_id, msg), num_message_bytes))
         },
     }
 }
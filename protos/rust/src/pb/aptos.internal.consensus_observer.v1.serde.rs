@@ -0,0 +1,801 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// @generated
+impl serde::Serialize for BlockSummary {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.epoch != 0 {
+            len += 1;
+        }
+        if self.round != 0 {
+            len += 1;
+        }
+        if !self.id.is_empty() {
+            len += 1;
+        }
+        if self.version != 0 {
+            len += 1;
+        }
+        if self.timestamp_usecs != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.internal.consensus_observer.v1.BlockSummary", len)?;
+        if self.epoch != 0 {
+            struct_ser.serialize_field("epoch", ToString::to_string(&self.epoch).as_str())?;
+        }
+        if self.round != 0 {
+            struct_ser.serialize_field("round", ToString::to_string(&self.round).as_str())?;
+        }
+        if !self.id.is_empty() {
+            struct_ser.serialize_field("id", pbjson::private::base64::encode(&self.id).as_str())?;
+        }
+        if self.version != 0 {
+            struct_ser.serialize_field("version", ToString::to_string(&self.version).as_str())?;
+        }
+        if self.timestamp_usecs != 0 {
+            struct_ser.serialize_field("timestampUsecs", ToString::to_string(&self.timestamp_usecs).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BlockSummary {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "epoch",
+            "round",
+            "id",
+            "version",
+            "timestamp_usecs",
+            "timestampUsecs",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Epoch,
+            Round,
+            Id,
+            Version,
+            TimestampUsecs,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "epoch" => Ok(GeneratedField::Epoch),
+                            "round" => Ok(GeneratedField::Round),
+                            "id" => Ok(GeneratedField::Id),
+                            "version" => Ok(GeneratedField::Version),
+                            "timestampUsecs" | "timestamp_usecs" => Ok(GeneratedField::TimestampUsecs),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BlockSummary;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.internal.consensus_observer.v1.BlockSummary")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<BlockSummary, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut epoch__ = None;
+                let mut round__ = None;
+                let mut id__ = None;
+                let mut version__ = None;
+                let mut timestamp_usecs__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::Epoch => {
+                            if epoch__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("epoch"));
+                            }
+                            epoch__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Round => {
+                            if round__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("round"));
+                            }
+                            round__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ =
+                                Some(map.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Version => {
+                            if version__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("version"));
+                            }
+                            version__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::TimestampUsecs => {
+                            if timestamp_usecs__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("timestampUsecs"));
+                            }
+                            timestamp_usecs__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(BlockSummary {
+                    epoch: epoch__.unwrap_or_default(),
+                    round: round__.unwrap_or_default(),
+                    id: id__.unwrap_or_default(),
+                    version: version__.unwrap_or_default(),
+                    timestamp_usecs: timestamp_usecs__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.internal.consensus_observer.v1.BlockSummary", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for ObservedBlockPayload {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.block.is_some() {
+            len += 1;
+        }
+        if self.num_transactions != 0 {
+            len += 1;
+        }
+        if self.transaction_limit.is_some() {
+            len += 1;
+        }
+        if !self.transactions.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.internal.consensus_observer.v1.ObservedBlockPayload", len)?;
+        if let Some(v) = self.block.as_ref() {
+            struct_ser.serialize_field("block", v)?;
+        }
+        if self.num_transactions != 0 {
+            struct_ser.serialize_field("numTransactions", ToString::to_string(&self.num_transactions).as_str())?;
+        }
+        if let Some(v) = self.transaction_limit.as_ref() {
+            struct_ser.serialize_field("transactionLimit", ToString::to_string(&v).as_str())?;
+        }
+        if !self.transactions.is_empty() {
+            struct_ser.serialize_field("transactions", &self.transactions.iter().map(pbjson::private::base64::encode).collect::<Vec<_>>())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for ObservedBlockPayload {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "block",
+            "num_transactions",
+            "numTransactions",
+            "transaction_limit",
+            "transactionLimit",
+            "transactions",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Block,
+            NumTransactions,
+            TransactionLimit,
+            Transactions,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "block" => Ok(GeneratedField::Block),
+                            "numTransactions" | "num_transactions" => Ok(GeneratedField::NumTransactions),
+                            "transactionLimit" | "transaction_limit" => Ok(GeneratedField::TransactionLimit),
+                            "transactions" => Ok(GeneratedField::Transactions),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = ObservedBlockPayload;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.internal.consensus_observer.v1.ObservedBlockPayload")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<ObservedBlockPayload, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut block__ = None;
+                let mut num_transactions__ = None;
+                let mut transaction_limit__ = None;
+                let mut transactions__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::Block => {
+                            if block__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("block"));
+                            }
+                            block__ = map.next_value()?;
+                        }
+                        GeneratedField::NumTransactions => {
+                            if num_transactions__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("numTransactions"));
+                            }
+                            num_transactions__ =
+                                Some(map.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::TransactionLimit => {
+                            if transaction_limit__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("transactionLimit"));
+                            }
+                            transaction_limit__ =
+                                map.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
+                        }
+                        GeneratedField::Transactions => {
+                            if transactions__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("transactions"));
+                            }
+                            transactions__ =
+                                Some(map.next_value::<Vec<::pbjson::private::BytesDeserialize<_>>>()?
+                                    .into_iter().map(|x| x.0).collect())
+                            ;
+                        }
+                    }
+                }
+                Ok(ObservedBlockPayload {
+                    block: block__,
+                    num_transactions: num_transactions__.unwrap_or_default(),
+                    transaction_limit: transaction_limit__,
+                    transactions: transactions__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.internal.consensus_observer.v1.ObservedBlockPayload", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for ObservedCommitDecision {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.proof_block.is_some() {
+            len += 1;
+        }
+        if !self.commit_proof.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.internal.consensus_observer.v1.ObservedCommitDecision", len)?;
+        if let Some(v) = self.proof_block.as_ref() {
+            struct_ser.serialize_field("proofBlock", v)?;
+        }
+        if !self.commit_proof.is_empty() {
+            struct_ser.serialize_field("commitProof", pbjson::private::base64::encode(&self.commit_proof).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for ObservedCommitDecision {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "proof_block",
+            "proofBlock",
+            "commit_proof",
+            "commitProof",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            ProofBlock,
+            CommitProof,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "proofBlock" | "proof_block" => Ok(GeneratedField::ProofBlock),
+                            "commitProof" | "commit_proof" => Ok(GeneratedField::CommitProof),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = ObservedCommitDecision;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.internal.consensus_observer.v1.ObservedCommitDecision")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<ObservedCommitDecision, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut proof_block__ = None;
+                let mut commit_proof__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::ProofBlock => {
+                            if proof_block__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("proofBlock"));
+                            }
+                            proof_block__ = map.next_value()?;
+                        }
+                        GeneratedField::CommitProof => {
+                            if commit_proof__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("commitProof"));
+                            }
+                            commit_proof__ =
+                                Some(map.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(ObservedCommitDecision {
+                    proof_block: proof_block__,
+                    commit_proof: commit_proof__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.internal.consensus_observer.v1.ObservedCommitDecision", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for ObservedOrderedBlock {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.blocks.is_empty() {
+            len += 1;
+        }
+        if self.proof_block.is_some() {
+            len += 1;
+        }
+        if !self.ordered_proof.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.internal.consensus_observer.v1.ObservedOrderedBlock", len)?;
+        if !self.blocks.is_empty() {
+            struct_ser.serialize_field("blocks", &self.blocks)?;
+        }
+        if let Some(v) = self.proof_block.as_ref() {
+            struct_ser.serialize_field("proofBlock", v)?;
+        }
+        if !self.ordered_proof.is_empty() {
+            struct_ser.serialize_field("orderedProof", pbjson::private::base64::encode(&self.ordered_proof).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for ObservedOrderedBlock {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "blocks",
+            "proof_block",
+            "proofBlock",
+            "ordered_proof",
+            "orderedProof",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Blocks,
+            ProofBlock,
+            OrderedProof,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "blocks" => Ok(GeneratedField::Blocks),
+                            "proofBlock" | "proof_block" => Ok(GeneratedField::ProofBlock),
+                            "orderedProof" | "ordered_proof" => Ok(GeneratedField::OrderedProof),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = ObservedOrderedBlock;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.internal.consensus_observer.v1.ObservedOrderedBlock")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<ObservedOrderedBlock, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut blocks__ = None;
+                let mut proof_block__ = None;
+                let mut ordered_proof__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::Blocks => {
+                            if blocks__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("blocks"));
+                            }
+                            blocks__ = Some(map.next_value()?);
+                        }
+                        GeneratedField::ProofBlock => {
+                            if proof_block__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("proofBlock"));
+                            }
+                            proof_block__ = map.next_value()?;
+                        }
+                        GeneratedField::OrderedProof => {
+                            if ordered_proof__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderedProof"));
+                            }
+                            ordered_proof__ =
+                                Some(map.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(ObservedOrderedBlock {
+                    blocks: blocks__.unwrap_or_default(),
+                    proof_block: proof_block__,
+                    ordered_proof: ordered_proof__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.internal.consensus_observer.v1.ObservedOrderedBlock", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for StreamObservedDataRequest {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.include_proofs {
+            len += 1;
+        }
+        if self.include_transactions {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.internal.consensus_observer.v1.StreamObservedDataRequest", len)?;
+        if self.include_proofs {
+            struct_ser.serialize_field("includeProofs", &self.include_proofs)?;
+        }
+        if self.include_transactions {
+            struct_ser.serialize_field("includeTransactions", &self.include_transactions)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for StreamObservedDataRequest {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "include_proofs",
+            "includeProofs",
+            "include_transactions",
+            "includeTransactions",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            IncludeProofs,
+            IncludeTransactions,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "includeProofs" | "include_proofs" => Ok(GeneratedField::IncludeProofs),
+                            "includeTransactions" | "include_transactions" => Ok(GeneratedField::IncludeTransactions),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = StreamObservedDataRequest;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.internal.consensus_observer.v1.StreamObservedDataRequest")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<StreamObservedDataRequest, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut include_proofs__ = None;
+                let mut include_transactions__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::IncludeProofs => {
+                            if include_proofs__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("includeProofs"));
+                            }
+                            include_proofs__ = Some(map.next_value()?);
+                        }
+                        GeneratedField::IncludeTransactions => {
+                            if include_transactions__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("includeTransactions"));
+                            }
+                            include_transactions__ = Some(map.next_value()?);
+                        }
+                    }
+                }
+                Ok(StreamObservedDataRequest {
+                    include_proofs: include_proofs__.unwrap_or_default(),
+                    include_transactions: include_transactions__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.internal.consensus_observer.v1.StreamObservedDataRequest", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for StreamObservedDataResponse {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.data.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("aptos.internal.consensus_observer.v1.StreamObservedDataResponse", len)?;
+        if let Some(v) = self.data.as_ref() {
+            match v {
+                stream_observed_data_response::Data::OrderedBlock(v) => {
+                    struct_ser.serialize_field("orderedBlock", v)?;
+                }
+                stream_observed_data_response::Data::BlockPayload(v) => {
+                    struct_ser.serialize_field("blockPayload", v)?;
+                }
+                stream_observed_data_response::Data::CommitDecision(v) => {
+                    struct_ser.serialize_field("commitDecision", v)?;
+                }
+            }
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for StreamObservedDataResponse {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "ordered_block",
+            "orderedBlock",
+            "block_payload",
+            "blockPayload",
+            "commit_decision",
+            "commitDecision",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            OrderedBlock,
+            BlockPayload,
+            CommitDecision,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "orderedBlock" | "ordered_block" => Ok(GeneratedField::OrderedBlock),
+                            "blockPayload" | "block_payload" => Ok(GeneratedField::BlockPayload),
+                            "commitDecision" | "commit_decision" => Ok(GeneratedField::CommitDecision),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = StreamObservedDataResponse;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct aptos.internal.consensus_observer.v1.StreamObservedDataResponse")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> std::result::Result<StreamObservedDataResponse, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut data__ = None;
+                while let Some(k) = map.next_key()? {
+                    match k {
+                        GeneratedField::OrderedBlock => {
+                            if data__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderedBlock"));
+                            }
+                            data__ = map.next_value::<::std::option::Option<_>>()?.map(stream_observed_data_response::Data::OrderedBlock)
+;
+                        }
+                        GeneratedField::BlockPayload => {
+                            if data__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("blockPayload"));
+                            }
+                            data__ = map.next_value::<::std::option::Option<_>>()?.map(stream_observed_data_response::Data::BlockPayload)
+;
+                        }
+                        GeneratedField::CommitDecision => {
+                            if data__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("commitDecision"));
+                            }
+                            data__ = map.next_value::<::std::option::Option<_>>()?.map(stream_observed_data_response::Data::CommitDecision)
+;
+                        }
+                    }
+                }
+                Ok(StreamObservedDataResponse {
+                    data: data__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("aptos.internal.consensus_observer.v1.StreamObservedDataResponse", FIELDS, GeneratedVisitor)
+    }
+}
@@ -11,6 +11,13 @@ pub mod aptos {
         }
     }
     pub mod internal {
+        pub mod consensus_observer {
+            // @@protoc_insertion_point(attribute:aptos.internal.consensus_observer.v1)
+            pub mod v1 {
+                include!("aptos.internal.consensus_observer.v1.rs");
+                // @@protoc_insertion_point(aptos.internal.consensus_observer.v1)
+            }
+        }
         pub mod fullnode {
             // @@protoc_insertion_point(attribute:aptos.internal.fullnode.v1)
             pub mod v1 {
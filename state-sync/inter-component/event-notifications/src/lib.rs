@@ -6,11 +6,12 @@
 use anyhow::{anyhow, Result};
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_id_generator::{IdGenerator, U64IdGenerator};
-use aptos_infallible::RwLock;
+use aptos_infallible::{Mutex, RwLock};
 use aptos_storage_interface::{state_view::DbStateViewAtVersion, DbReader, DbReaderWriter};
 use aptos_types::{
     contract_event::ContractEvent,
     event::EventKey,
+    ledger_info::LedgerInfoWithSignatures,
     on_chain_config::{
         ConfigurationResource, OnChainConfig, OnChainConfigPayload, OnChainConfigProvider,
     },
@@ -36,7 +37,10 @@ mod tests;
 // consumed, they will be dropped (oldest messages first). The remaining messages
 // will be retrieved using FIFO ordering.
 const EVENT_NOTIFICATION_CHANNEL_SIZE: usize = 100;
+const OBSERVED_COMMIT_NOTIFICATION_CHANNEL_SIZE: usize = 100;
 const RECONFIG_NOTIFICATION_CHANNEL_SIZE: usize = 1;
+const SYNCED_COMMIT_NOTIFICATION_CHANNEL_SIZE: usize = 1;
+const VERIFIED_COMMIT_NOTIFICATION_CHANNEL_SIZE: usize = 1;
 
 #[derive(Clone, Debug, Deserialize, Error, PartialEq, Eq, Serialize)]
 pub enum Error {
@@ -67,6 +71,14 @@ pub trait EventNotificationSender: Send {
     /// This is useful for forcing reconfiguration notifications even if no
     /// reconfiguration event was processed (e.g., on startup).
     fn notify_initial_configs(&mut self, version: Version) -> Result<(), Error>;
+
+    /// Notify the subscription service that storage has been synced to the
+    /// specified version (and ledger info).
+    fn notify_synced_commit(
+        &mut self,
+        version: Version,
+        ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<(), Error>;
 }
 
 /// The subscription service offered by state sync, responsible for notifying
@@ -80,6 +92,15 @@ pub struct EventSubscriptionService {
     // Reconfig subscription registry
     reconfig_subscriptions: HashMap<SubscriptionId, ReconfigSubscription>,
 
+    // Synced commit subscription registry
+    synced_commit_subscriptions: HashMap<SubscriptionId, SyncedCommitSubscription>,
+
+    // Observed commit subscription registry (shared with the observed commit notifiers)
+    observed_commit_subscriptions: Arc<Mutex<HashMap<SubscriptionId, ObservedCommitSubscription>>>,
+
+    // Verified commit subscription registry (shared with the observed commit notifiers)
+    verified_commit_subscriptions: Arc<Mutex<HashMap<SubscriptionId, ObservedCommitSubscription>>>,
+
     // Database to fetch on-chain configuration data
     storage: Arc<RwLock<DbReaderWriter>>,
 
@@ -94,6 +115,9 @@ impl EventSubscriptionService {
             event_v2_tag_subscriptions: HashMap::new(),
             subscription_id_to_event_subscription: HashMap::new(),
             reconfig_subscriptions: HashMap::new(),
+            synced_commit_subscriptions: HashMap::new(),
+            observed_commit_subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            verified_commit_subscriptions: Arc::new(Mutex::new(HashMap::new())),
             storage,
             subscription_id_generator: U64IdGenerator::new(),
         }
@@ -196,6 +220,136 @@ impl EventSubscriptionService {
         })
     }
 
+    /// Returns a SyncedCommitNotificationListener that can be monitored for
+    /// commits synced to storage (e.g., by state sync). Subscribers will be
+    /// sent a notification containing the latest synced version and ledger
+    /// info whenever new data is committed. Note: only the latest notification
+    /// is buffered (older notifications are dropped), as subscribers are only
+    /// expected to care about the latest synced state.
+    pub fn subscribe_to_synced_commits(
+        &mut self,
+    ) -> Result<SyncedCommitNotificationListener, Error> {
+        let (notification_sender, notification_receiver) = aptos_channel::new(
+            QueueStyle::KLAST,
+            SYNCED_COMMIT_NOTIFICATION_CHANNEL_SIZE,
+            None,
+        );
+
+        // Create a new synced commit subscription
+        let subscription_id = self.get_new_subscription_id();
+        let synced_commit_subscription = SyncedCommitSubscription {
+            notification_sender,
+        };
+
+        // Store the new subscription
+        if self
+            .synced_commit_subscriptions
+            .insert(subscription_id, synced_commit_subscription)
+            .is_some()
+        {
+            return Err(Error::UnexpectedErrorEncountered(format!(
+                "Duplicate synced commit subscription found! This should not occur! ID: {}",
+                subscription_id,
+            )));
+        }
+
+        Ok(SyncedCommitNotificationListener {
+            notification_receiver,
+        })
+    }
+
+    /// Returns an ObservedCommitNotificationListener that can be monitored
+    /// for commits applied by the consensus observer. Subscribers will be
+    /// sent a notification (containing the commit ledger info) every time
+    /// the observer applies a new commit. Note: if the notification buffer
+    /// fills up too quickly, older notifications will be dropped. As such,
+    /// it is the responsibility of the subscriber to ensure notifications
+    /// are processed in a timely manner.
+    pub fn subscribe_to_observed_commits(
+        &mut self,
+    ) -> Result<ObservedCommitNotificationListener, Error> {
+        let (notification_sender, notification_receiver) = aptos_channel::new(
+            QueueStyle::KLAST,
+            OBSERVED_COMMIT_NOTIFICATION_CHANNEL_SIZE,
+            None,
+        );
+
+        // Create a new observed commit subscription
+        let subscription_id = self.get_new_subscription_id();
+        let observed_commit_subscription = ObservedCommitSubscription {
+            notification_sender,
+        };
+
+        // Store the new subscription
+        if self
+            .observed_commit_subscriptions
+            .lock()
+            .insert(subscription_id, observed_commit_subscription)
+            .is_some()
+        {
+            return Err(Error::UnexpectedErrorEncountered(format!(
+                "Duplicate observed commit subscription found! This should not occur! ID: {}",
+                subscription_id,
+            )));
+        }
+
+        Ok(ObservedCommitNotificationListener {
+            notification_receiver,
+        })
+    }
+
+    /// Returns a VerifiedCommitNotificationListener that can be monitored
+    /// for commits verified by the consensus observer. Unlike observed commits,
+    /// verified commits are sent as soon as the commit proof is verified (i.e.,
+    /// before the commit is applied locally). This allows subscribers (e.g.,
+    /// state sync) to learn about the latest commits as quickly as possible.
+    /// Note: only the latest notification is buffered (older notifications are
+    /// dropped), as each verified commit supersedes the previous ones.
+    pub fn subscribe_to_verified_commits(
+        &mut self,
+    ) -> Result<VerifiedCommitNotificationListener, Error> {
+        let (notification_sender, notification_receiver) = aptos_channel::new(
+            QueueStyle::KLAST,
+            VERIFIED_COMMIT_NOTIFICATION_CHANNEL_SIZE,
+            None,
+        );
+
+        // Create a new verified commit subscription
+        let subscription_id = self.get_new_subscription_id();
+        let verified_commit_subscription = ObservedCommitSubscription {
+            notification_sender,
+        };
+
+        // Store the new subscription
+        if self
+            .verified_commit_subscriptions
+            .lock()
+            .insert(subscription_id, verified_commit_subscription)
+            .is_some()
+        {
+            return Err(Error::UnexpectedErrorEncountered(format!(
+                "Duplicate verified commit subscription found! This should not occur! ID: {}",
+                subscription_id,
+            )));
+        }
+
+        Ok(VerifiedCommitNotificationListener {
+            notification_receiver,
+        })
+    }
+
+    /// Returns a notifier that can be used (e.g., by the consensus observer)
+    /// to notify all observed commit subscribers of newly applied commits
+    /// (and all verified commit subscribers of newly verified commits).
+    /// Note: subscribers added after the notifier is created will also be
+    /// notified (i.e., the subscription registries are shared).
+    pub fn get_observed_commit_notifier(&self) -> ObservedCommitNotifier {
+        ObservedCommitNotifier {
+            observed_commit_subscriptions: self.observed_commit_subscriptions.clone(),
+            verified_commit_subscriptions: self.verified_commit_subscriptions.clone(),
+        }
+    }
+
     fn get_new_subscription_id(&mut self) -> u64 {
         self.subscription_id_generator.next()
     }
@@ -327,6 +481,23 @@ impl EventNotificationSender for EventSubscriptionService {
     fn notify_initial_configs(&mut self, version: Version) -> Result<(), Error> {
         self.notify_reconfiguration_subscribers(version)
     }
+
+    fn notify_synced_commit(
+        &mut self,
+        version: Version,
+        ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        let synced_commit_notification = SyncedCommitNotification {
+            version,
+            ledger_info,
+        };
+        for (_, synced_commit_subscription) in self.synced_commit_subscriptions.iter_mut() {
+            synced_commit_subscription
+                .notify_subscriber_of_commit(synced_commit_notification.clone())?;
+        }
+
+        Ok(())
+    }
 }
 
 /// A unique ID used to identify each subscription.
@@ -381,6 +552,90 @@ impl ReconfigSubscription {
     }
 }
 
+/// A single synced commit subscription, holding the channel to send
+/// the corresponding notifications.
+struct SyncedCommitSubscription {
+    pub notification_sender: aptos_channels::aptos_channel::Sender<(), SyncedCommitNotification>,
+}
+
+impl SyncedCommitSubscription {
+    fn notify_subscriber_of_commit(
+        &mut self,
+        synced_commit_notification: SyncedCommitNotification,
+    ) -> Result<(), Error> {
+        self.notification_sender
+            .push((), synced_commit_notification)
+            .map_err(|error| Error::UnexpectedErrorEncountered(format!("{:?}", error)))
+    }
+}
+
+/// A single observed (or verified) commit subscription, holding the
+/// channel to send the corresponding notifications.
+struct ObservedCommitSubscription {
+    pub notification_sender: aptos_channels::aptos_channel::Sender<(), ObservedCommitNotification>,
+}
+
+impl ObservedCommitSubscription {
+    fn notify_subscriber_of_commit(
+        &mut self,
+        observed_commit_notification: ObservedCommitNotification,
+    ) -> Result<(), Error> {
+        self.notification_sender
+            .push((), observed_commit_notification)
+            .map_err(|error| Error::UnexpectedErrorEncountered(format!("{:?}", error)))
+    }
+}
+
+/// The notifier used to publish the commits applied (and verified) by the
+/// consensus observer to all observed (and verified) commit subscribers. The
+/// notifier is cheaply cloneable, and all clones notify the same subscribers.
+#[derive(Clone)]
+pub struct ObservedCommitNotifier {
+    observed_commit_subscriptions: Arc<Mutex<HashMap<SubscriptionId, ObservedCommitSubscription>>>,
+    verified_commit_subscriptions: Arc<Mutex<HashMap<SubscriptionId, ObservedCommitSubscription>>>,
+}
+
+impl ObservedCommitNotifier {
+    /// Notifies all observed commit subscribers of the given commit. The
+    /// ledger info is shared by all notifications (to avoid copying it).
+    pub fn notify_observed_commit(
+        &self,
+        ledger_info: Arc<LedgerInfoWithSignatures>,
+    ) -> Result<(), Error> {
+        notify_commit_subscribers(&self.observed_commit_subscriptions, ledger_info)
+    }
+
+    /// Notifies all verified commit subscribers of the given commit. The
+    /// ledger info is shared by all notifications (to avoid copying it).
+    pub fn notify_verified_commit(
+        &self,
+        ledger_info: Arc<LedgerInfoWithSignatures>,
+    ) -> Result<(), Error> {
+        notify_commit_subscribers(&self.verified_commit_subscriptions, ledger_info)
+    }
+}
+
+/// Notifies all subscribers in the given registry of the specified commit
+fn notify_commit_subscribers(
+    commit_subscriptions: &Mutex<HashMap<SubscriptionId, ObservedCommitSubscription>>,
+    ledger_info: Arc<LedgerInfoWithSignatures>,
+) -> Result<(), Error> {
+    let mut commit_subscriptions = commit_subscriptions.lock();
+    if commit_subscriptions.is_empty() {
+        return Ok(()); // No commit subscribers!
+    }
+
+    let commit_notification = ObservedCommitNotification {
+        version: ledger_info.ledger_info().version(),
+        ledger_info,
+    };
+    for (_, commit_subscription) in commit_subscriptions.iter_mut() {
+        commit_subscription.notify_subscriber_of_commit(commit_notification.clone())?;
+    }
+
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct DbBackedOnChainConfig {
     pub reader: Arc<dyn DbReader>,
@@ -431,12 +686,36 @@ pub struct ReconfigNotification<P: OnChainConfigProvider> {
     pub on_chain_configs: OnChainConfigPayload<P>,
 }
 
+/// A notification for commits synced to storage.
+#[derive(Clone, Debug)]
+pub struct SyncedCommitNotification {
+    pub version: Version,
+    pub ledger_info: LedgerInfoWithSignatures,
+}
+
+/// A notification for commits applied by the consensus observer.
+#[derive(Clone, Debug)]
+pub struct ObservedCommitNotification {
+    pub version: Version,
+    pub ledger_info: Arc<LedgerInfoWithSignatures>,
+}
+
 /// A subscription listener for on-chain events.
 pub type EventNotificationListener = NotificationListener<EventNotification>;
 
 /// A subscription listener for reconfigurations.
 pub type ReconfigNotificationListener<P> = NotificationListener<ReconfigNotification<P>>;
 
+/// A subscription listener for commits synced to storage.
+pub type SyncedCommitNotificationListener = NotificationListener<SyncedCommitNotification>;
+
+/// A subscription listener for commits applied by the consensus observer.
+pub type ObservedCommitNotificationListener = NotificationListener<ObservedCommitNotification>;
+
+/// A subscription listener for commits verified (but not necessarily
+/// applied) by the consensus observer.
+pub type VerifiedCommitNotificationListener = NotificationListener<ObservedCommitNotification>;
+
 /// The component responsible for listening to subscription notifications.
 #[derive(Debug)]
 pub struct NotificationListener<T> {
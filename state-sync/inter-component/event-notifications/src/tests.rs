@@ -6,16 +6,20 @@
 
 use crate::{
     DbBackedOnChainConfig, Error, EventNotificationListener, EventNotificationSender,
-    EventSubscriptionService, ReconfigNotificationListener,
+    EventSubscriptionService, ObservedCommitNotificationListener, ReconfigNotificationListener,
 };
+use aptos_crypto::HashValue;
 use aptos_db::AptosDB;
 use aptos_executor_test_helpers::bootstrap_genesis;
 use aptos_infallible::RwLock;
 use aptos_storage_interface::DbReaderWriter;
 use aptos_types::{
     account_address::AccountAddress,
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
     contract_event::ContractEvent,
     event::EventKey,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     on_chain_config,
     on_chain_config::OnChainConfig,
     transaction::{Transaction, Version, WriteSetPayload},
@@ -376,6 +380,104 @@ fn test_no_events_no_subscribers() {
     notify_events(&mut event_service, 1, vec![]);
 }
 
+#[test]
+fn test_observed_commit_subscribers() {
+    // Create subscription service and mock database
+    let mut event_service = create_event_subscription_service();
+
+    // Create an observed commit notifier and verify that notifying
+    // without any subscribers doesn't cause notification errors.
+    let observed_commit_notifier = event_service.get_observed_commit_notifier();
+    assert_ok!(observed_commit_notifier.notify_observed_commit(Arc::new(create_ledger_info(0, 0))));
+
+    // Create several observed commit subscribers (after the notifier was created)
+    let mut listener_1 = event_service.subscribe_to_observed_commits().unwrap();
+    let mut listener_2 = event_service.subscribe_to_observed_commits().unwrap();
+
+    // Notify the subscribers of several commits and verify the notifications
+    let num_commits = 10;
+    for version in 1..=num_commits {
+        let ledger_info = Arc::new(create_ledger_info(version, version));
+        assert_ok!(observed_commit_notifier.notify_observed_commit(ledger_info.clone()));
+        for listener in [&mut listener_1, &mut listener_2] {
+            verify_observed_commit_notification_received(listener, version, &ledger_info);
+        }
+    }
+
+    // Verify no more notifications were received
+    for listener in [&mut listener_1, &mut listener_2] {
+        assert!(listener.select_next_some().now_or_never().is_none());
+    }
+}
+
+#[test]
+fn test_verified_commit_subscribers() {
+    // Create subscription service and mock database
+    let mut event_service = create_event_subscription_service();
+
+    // Create an observed commit notifier and verified commit subscribers
+    let observed_commit_notifier = event_service.get_observed_commit_notifier();
+    let mut observed_listener = event_service.subscribe_to_observed_commits().unwrap();
+    let mut verified_listener = event_service.subscribe_to_verified_commits().unwrap();
+
+    // Notify the verified commit subscribers and verify the notification
+    let ledger_info = Arc::new(create_ledger_info(1, 1));
+    assert_ok!(observed_commit_notifier.notify_verified_commit(ledger_info.clone()));
+    verify_observed_commit_notification_received(&mut verified_listener, 1, &ledger_info);
+
+    // Verify the observed commit subscribers were not notified
+    assert!(observed_listener
+        .select_next_some()
+        .now_or_never()
+        .is_none());
+
+    // Notify the subscribers of several verified commits
+    let mut latest_ledger_info = ledger_info;
+    for version in 2..=10 {
+        latest_ledger_info = Arc::new(create_ledger_info(version, version));
+        assert_ok!(observed_commit_notifier.notify_verified_commit(latest_ledger_info.clone()));
+    }
+
+    // Verify that only the latest verified commit was buffered
+    verify_observed_commit_notification_received(&mut verified_listener, 10, &latest_ledger_info);
+    assert!(verified_listener
+        .select_next_some()
+        .now_or_never()
+        .is_none());
+}
+
+#[test]
+fn test_synced_commit_subscribers() {
+    // Create subscription service and mock database
+    let mut event_service = create_event_subscription_service();
+
+    // Verify that notifying without any subscribers doesn't cause notification errors
+    assert_ok!(event_service.notify_synced_commit(0, create_ledger_info(0, 0)));
+
+    // Create several synced commit subscribers
+    let mut listener_1 = event_service.subscribe_to_synced_commits().unwrap();
+    let mut listener_2 = event_service.subscribe_to_synced_commits().unwrap();
+
+    // Notify the subscribers of several commits
+    let num_commits = 10;
+    for version in 1..=num_commits {
+        assert_ok!(
+            event_service.notify_synced_commit(version, create_ledger_info(version, version))
+        );
+    }
+
+    // Verify that only the latest notification was buffered for each subscriber
+    for listener in [&mut listener_1, &mut listener_2] {
+        let synced_commit_notification = listener.select_next_some().now_or_never().unwrap();
+        assert_eq!(synced_commit_notification.version, num_commits);
+        assert_eq!(
+            synced_commit_notification.ledger_info.commit_info().round(),
+            num_commits
+        );
+        assert!(listener.select_next_some().now_or_never().is_none());
+    }
+}
+
 #[test]
 fn test_event_v2_subscription_by_tag() {
     // Create subscription service and mock database
@@ -517,6 +619,23 @@ fn verify_reconfig_notifications_received(
     }
 }
 
+// Ensures that the specified listener has received the expected observed commit notification
+fn verify_observed_commit_notification_received(
+    listener: &mut ObservedCommitNotificationListener,
+    expected_version: Version,
+    expected_ledger_info: &LedgerInfoWithSignatures,
+) {
+    if let Some(observed_commit_notification) = listener.select_next_some().now_or_never() {
+        assert_eq!(observed_commit_notification.version, expected_version);
+        assert_eq!(
+            observed_commit_notification.ledger_info.as_ref(),
+            expected_ledger_info
+        );
+    } else {
+        panic!("Expected an observed commit notification but got None!");
+    }
+}
+
 fn notify_initial_configs(event_service: &mut EventSubscriptionService, version: Version) {
     assert_ok!(event_service.notify_initial_configs(version));
 }
@@ -533,6 +652,22 @@ fn create_test_event(event_key: EventKey) -> ContractEvent {
     ContractEvent::new_v1(event_key, 0, TypeTag::Bool, bcs::to_bytes(&0).unwrap())
 }
 
+fn create_ledger_info(round: u64, version: Version) -> LedgerInfoWithSignatures {
+    let block_info = BlockInfo::new(
+        0,
+        round,
+        HashValue::random(),
+        HashValue::random(),
+        version,
+        0,
+        None,
+    );
+    LedgerInfoWithSignatures::new(
+        LedgerInfo::new(block_info, HashValue::zero()),
+        AggregateSignature::empty(),
+    )
+}
+
 fn create_random_event_key() -> EventKey {
     EventKey::new(0, AccountAddress::random())
 }
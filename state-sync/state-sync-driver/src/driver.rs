@@ -28,7 +28,9 @@ use aptos_data_client::interface::AptosDataClientInterface;
 use aptos_data_streaming_service::streaming_client::{
     DataStreamingClient, NotificationAndFeedback, NotificationFeedback,
 };
-use aptos_event_notifications::EventSubscriptionService;
+use aptos_event_notifications::{
+    EventSubscriptionService, ObservedCommitNotification, VerifiedCommitNotificationListener,
+};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_mempool_notifications::MempoolNotificationSender;
@@ -36,7 +38,9 @@ use aptos_schemadb::DB;
 use aptos_storage_interface::DbReader;
 use aptos_storage_service_notifications::StorageServiceNotificationSender;
 use aptos_time_service::{TimeService, TimeServiceTrait};
-use aptos_types::{contract_event::ContractEvent, waypoint::Waypoint};
+use aptos_types::{
+    contract_event::ContractEvent, ledger_info::LedgerInfoWithSignatures, waypoint::Waypoint,
+};
 use futures::StreamExt;
 use std::{sync::Arc, time::Instant};
 use tokio::{
@@ -47,6 +51,7 @@ use tokio_stream::wrappers::IntervalStream;
 
 // Useful constants for the driver
 const DRIVER_ERROR_LOG_FREQ_SECS: u64 = 3;
+const DRIVER_INFO_LOG_FREQ_SECS: u64 = 10;
 
 /// The configuration of the state sync driver
 #[derive(Clone)]
@@ -116,6 +121,9 @@ pub struct StateSyncDriver<
     // The event subscription service to notify listeners of on-chain events
     event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
 
+    // The highest commit verified by the consensus observer (if any)
+    highest_verified_commit: Option<Arc<LedgerInfoWithSignatures>>,
+
     // The handler for notifications to mempool
     mempool_notification_handler: MempoolNotificationHandler<MempoolNotifier>,
 
@@ -133,6 +141,9 @@ pub struct StateSyncDriver<
 
     // The time service
     time_service: TimeService,
+
+    // The listener for commits verified by the consensus observer
+    verified_commit_listener: VerifiedCommitNotificationListener,
 }
 
 impl<
@@ -171,6 +182,7 @@ impl<
         storage: Arc<dyn DbReader>,
         time_service: TimeService,
         internal_indexer_db: Option<Arc<DB>>,
+        verified_commit_listener: VerifiedCommitNotificationListener,
     ) -> Self {
         let output_fallback_handler =
             OutputFallbackHandler::new(driver_configuration.clone(), time_service.clone());
@@ -201,12 +213,14 @@ impl<
             driver_configuration,
             error_notification_listener,
             event_subscription_service,
+            highest_verified_commit: None,
             mempool_notification_handler,
             start_time: None,
             storage,
             storage_service_notification_handler,
             storage_synchronizer,
             time_service,
+            verified_commit_listener,
         }
     }
 
@@ -236,6 +250,9 @@ impl<
                 notification = self.error_notification_listener.select_next_some() => {
                     self.handle_error_notification(notification).await;
                 }
+                notification = self.verified_commit_listener.select_next_some() => {
+                    self.handle_verified_commit_notification(notification);
+                }
                 _ = progress_check_interval.select_next_some() => {
                     self.drive_progress().await;
                 }
@@ -401,6 +418,75 @@ impl<
             .await
     }
 
+    /// Handles a verified commit notification sent by the consensus observer.
+    /// Verified commits are used as hints for the latest commits (i.e., before
+    /// the commits have been executed and synced to storage locally).
+    fn handle_verified_commit_notification(&mut self, notification: ObservedCommitNotification) {
+        // Ignore the notification if it's not newer than the highest verified commit
+        if let Some(highest_verified_commit) = &self.highest_verified_commit {
+            if notification.version <= highest_verified_commit.ledger_info().version() {
+                return;
+            }
+        }
+
+        // Update the highest verified commit
+        trace!(LogSchema::new(LogEntry::Driver).message(&format!(
+            "Received a newer verified commit from the consensus observer! Version: {:?}",
+            notification.version
+        )));
+        metrics::increment_counter(
+            &metrics::DRIVER_COUNTERS,
+            metrics::DRIVER_VERIFIED_COMMIT_NOTIFICATION,
+        );
+        metrics::set_gauge(
+            &metrics::VERIFIED_COMMITS,
+            metrics::VERIFIED_COMMIT_VERSION,
+            notification.version,
+        );
+        self.highest_verified_commit = Some(notification.ledger_info);
+    }
+
+    /// Updates the lag between the highest verified commit and the latest
+    /// synced version. If there's an active sync request and the verified
+    /// commit is beyond the sync target, a newer target is already known.
+    fn update_verified_commit_lag(&self) -> Result<(), Error> {
+        // Get the highest verified commit version
+        let highest_verified_version = match &self.highest_verified_commit {
+            Some(highest_verified_commit) => highest_verified_commit.ledger_info().version(),
+            None => return Ok(()), // No commits have been verified yet
+        };
+
+        // Update the lag between the verified commit and the latest synced version
+        let latest_synced_version = utils::fetch_latest_synced_version(self.storage.clone())?;
+        metrics::set_gauge(
+            &metrics::VERIFIED_COMMITS,
+            metrics::VERIFIED_COMMIT_SYNC_LAG,
+            highest_verified_version.saturating_sub(latest_synced_version),
+        );
+
+        // Log if the verified commit is beyond the active sync target
+        let sync_target_version = self
+            .consensus_notification_handler
+            .get_sync_request()
+            .lock()
+            .as_ref()
+            .map(|sync_request| sync_request.get_sync_target_version());
+        if let Some(sync_target_version) = sync_target_version {
+            if highest_verified_version > sync_target_version {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(DRIVER_INFO_LOG_FREQ_SECS)),
+                    info!(LogSchema::new(LogEntry::Driver).message(&format!(
+                        "A verified commit beyond the sync target is available! Sync target: {:?}, \
+                        verified commit: {:?}, latest synced version: {:?}",
+                        sync_target_version, highest_verified_version, latest_synced_version
+                    )));
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     /// Handles a client notification sent by the driver client
     async fn handle_client_notification(&mut self, notification: DriverNotification) {
         debug!(LogSchema::new(LogEntry::ClientNotification)
@@ -614,6 +700,13 @@ impl<
                 .message("Error found when checking the sync request progress!"));
         }
 
+        // Update the lag behind the highest verified commit
+        if let Err(error) = self.update_verified_commit_lag() {
+            warn!(LogSchema::new(LogEntry::Driver)
+                .error(&error)
+                .message("Error found when updating the verified commit lag!"));
+        }
+
         // If consensus is executing, there's nothing to do
         if self.check_if_consensus_executing() {
             trace!(LogSchema::new(LogEntry::Driver)
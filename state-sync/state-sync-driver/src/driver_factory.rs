@@ -122,6 +122,11 @@ impl DriverFactory {
             Err(error) => panic!("Failed to fetch the initial synced version: {:?}", error),
         }
 
+        // Subscribe to the commits verified by the consensus observer
+        let verified_commit_listener = event_subscription_service
+            .subscribe_to_verified_commits()
+            .expect("Failed to subscribe to the verified commits!");
+
         // Create the notification handlers
         let (client_notification_sender, client_notification_receiver) = mpsc::unbounded();
         let client_notification_listener =
@@ -162,7 +167,7 @@ impl DriverFactory {
         // Create the driver configuration
         let driver_configuration = DriverConfiguration::new(
             node_config.state_sync.state_sync_driver,
-            node_config.consensus_observer,
+            node_config.consensus_observer.clone(),
             node_config.base.role,
             waypoint,
         );
@@ -184,6 +189,7 @@ impl DriverFactory {
             storage.reader,
             time_service,
             internal_indexer_db,
+            verified_commit_listener,
         );
 
         // Spawn the driver
@@ -12,6 +12,7 @@ use std::time::Instant;
 pub const DRIVER_CLIENT_NOTIFICATION: &str = "driver_client_notification";
 pub const DRIVER_CONSENSUS_COMMIT_NOTIFICATION: &str = "driver_consensus_commit_notification";
 pub const DRIVER_CONSENSUS_SYNC_NOTIFICATION: &str = "driver_consensus_sync_notification";
+pub const DRIVER_VERIFIED_COMMIT_NOTIFICATION: &str = "driver_verified_commit_notification";
 
 /// Data notification metric labels
 pub const NOTIFICATION_CREATE_TO_APPLY: &str = "notification_create_to_apply";
@@ -31,6 +32,10 @@ pub const STORAGE_SYNCHRONIZER_COMMIT_CHUNK: &str = "commit_chunk";
 pub const STORAGE_SYNCHRONIZER_COMMIT_POST_PROCESS: &str = "commit_post_process";
 pub const STORAGE_SYNCHRONIZER_STATE_VALUE_CHUNK: &str = "state_value_chunk";
 
+/// Verified commit metric labels
+pub const VERIFIED_COMMIT_SYNC_LAG: &str = "verified_commit_sync_lag";
+pub const VERIFIED_COMMIT_VERSION: &str = "verified_commit_version";
+
 /// An enum representing the component currently executing
 pub enum ExecutingComponent {
     Bootstrapper,
@@ -190,6 +195,16 @@ pub static STORAGE_SYNCHRONIZER_OPERATIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Gauges for the commits verified by the consensus observer
+pub static VERIFIED_COMMITS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_state_sync_verified_commits",
+        "Gauges related to the commits verified by the consensus observer",
+        &["label"]
+    )
+    .unwrap()
+});
+
 /// Increments the given counter with the provided label values.
 pub fn increment_counter(counter: &Lazy<IntCounterVec>, label: &str) {
     counter.with_label_values(&[label]).inc();
@@ -107,6 +107,11 @@ impl CommitNotification {
             .lock()
             .notify_events(latest_synced_version, events)?;
 
+        // Notify the event subscription service of the synced commit
+        event_subscription_service
+            .lock()
+            .notify_synced_commit(latest_synced_version, latest_synced_ledger_info)?;
+
         Ok(())
     }
 }
@@ -58,6 +58,61 @@ async fn test_consensus_observer_fullnode_sync() {
     wait_for_all_nodes(&mut swarm).await;
 }
 
+#[tokio::test]
+async fn test_consensus_observer_fullnode_pipeline_failures() {
+    // Create a validator swarm of 1 validator with consensus observer enabled
+    let mut swarm = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .with_init_config(Arc::new(|_, config, _| {
+            enable_consensus_observer(true, config);
+        }))
+        .build()
+        .await;
+
+    // Create a fullnode config that uses consensus observer (with failpoints enabled)
+    let mut vfn_config = NodeConfig::get_default_vfn_config();
+    enable_consensus_observer(true, &mut vfn_config);
+    vfn_config.api.failpoints_enabled = true;
+
+    // Create the fullnode
+    let vfn_peer_id = state_sync_utils::create_fullnode(vfn_config, &mut swarm).await;
+
+    // Inject errors when the fullnode sends ordered blocks to the execution pipeline
+    let vfn_client = swarm.full_node(vfn_peer_id).unwrap().rest_client();
+    vfn_client
+        .set_failpoint(
+            "consensus_observer::finalize_order".to_string(),
+            "50%return".to_string(),
+        )
+        .await
+        .unwrap();
+
+    // Execute a number of transactions on the validator
+    let validator_peer_id = swarm.validators().next().unwrap().peer_id();
+    let validator_client = swarm.validator(validator_peer_id).unwrap().rest_client();
+    let (mut account_0, account_1) = create_test_accounts(&mut swarm).await;
+    execute_transactions(
+        &mut swarm,
+        &validator_client,
+        &mut account_0,
+        &account_1,
+        false,
+    )
+    .await;
+
+    // Remove the failpoint
+    vfn_client
+        .set_failpoint(
+            "consensus_observer::finalize_order".to_string(),
+            "off".to_string(),
+        )
+        .await
+        .unwrap();
+
+    // Verify the fullnode recovers and is up-to-date
+    wait_for_all_nodes(&mut swarm).await;
+}
+
 #[tokio::test]
 async fn test_consensus_observer_fullnode_restart() {
     // Create a validator swarm of 1 validator with consensus observer enabled